@@ -91,6 +91,28 @@ where
 	AccountPublic::from(get_from_seed::<TPublic>(seed)).into_account()
 }
 
+/// An asset pair registered in automation-price at genesis: its chain, exchange, assets, the
+/// decimals of its prices and its oracle providers.
+pub type PriceAsset = (Vec<u8>, Vec<u8>, Vec<u8>, Vec<u8>, u8, Vec<AccountId>);
+
+/// The asset pairs of the development and local test chains, priced by `oracle_provider`.
+#[allow(dead_code)]
+pub fn dev_price_assets(oracle_provider: AccountId) -> Vec<PriceAsset> {
+	[("shibuya", "arthswap", "WRSTR", "USDT"), ("moonbase", "stellaswap", "WDEV", "USDC")]
+		.into_iter()
+		.map(|(chain, exchange, asset1, asset2)| {
+			(
+				chain.as_bytes().to_vec(),
+				exchange.as_bytes().to_vec(),
+				asset1.as_bytes().to_vec(),
+				asset2.as_bytes().to_vec(),
+				8,
+				vec![oracle_provider.clone()],
+			)
+		})
+		.collect()
+}
+
 pub fn inflation_config(
 	blocks_per_round: u32,
 	annual_inflation_percentage: u32,
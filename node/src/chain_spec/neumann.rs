@@ -9,13 +9,13 @@ use sp_runtime::{Perbill, Percent};
 
 use super::TELEMETRY_URL;
 use crate::chain_spec::{
-	get_account_id_from_seed, get_collator_keys_from_seed, inflation_config, DummyChainSpec,
-	Extensions,
+	dev_price_assets, get_account_id_from_seed, get_collator_keys_from_seed, inflation_config,
+	DummyChainSpec, Extensions, PriceAsset,
 };
 use common_runtime::constants::currency::{DOLLAR, TOKEN_DECIMALS};
 use neumann_runtime::{
-	CouncilConfig, PolkadotXcmConfig, SudoConfig, TechnicalMembershipConfig, ValveConfig,
-	VestingConfig,
+	AutomationPriceConfig, CouncilConfig, PolkadotXcmConfig, SudoConfig, TechnicalMembershipConfig,
+	ValveConfig, VestingConfig,
 };
 use primitives::{AccountId, AuraId, Balance};
 
@@ -23,6 +23,7 @@ static TOKEN_SYMBOL: &str = "NEU";
 const SS_58_FORMAT: u32 = 51;
 static RELAY_CHAIN: &str = "rococo-local";
 static NEUMANN_RELAY_CHAIN: &str = "rococo-testnet";
+static PASEO_RELAY_CHAIN: &str = "paseo";
 const DEFAULT_PARA_ID: u32 = 2000;
 
 /// The default XCM version to set in genesis config.
@@ -92,6 +93,7 @@ pub fn development_config() -> ChainSpec {
 					get_account_id_from_seed::<sr25519::Public>("Eve"),
 					get_account_id_from_seed::<sr25519::Public>("Ferdie"),
 				],
+				dev_price_assets(get_account_id_from_seed::<sr25519::Public>("Alice")),
 			)
 		},
 		Vec::new(),
@@ -183,6 +185,7 @@ pub fn local_testnet_config() -> ChainSpec {
 					get_account_id_from_seed::<sr25519::Public>("Eve"),
 					get_account_id_from_seed::<sr25519::Public>("Ferdie"),
 				],
+				dev_price_assets(get_account_id_from_seed::<sr25519::Public>("Alice")),
 			)
 		},
 		// Bootnodes
@@ -215,64 +218,7 @@ pub fn neumann_staging_testnet_config() -> ChainSpec {
 		// ID
 		"neumann",
 		ChainType::Live,
-		move || {
-			let allocation_json =
-				&include_bytes!("../../../distribution/neumann_vest_test_alloc.json")[..];
-			let initial_allocation: Vec<(AccountId, Balance)> =
-				serde_json::from_slice(allocation_json).unwrap();
-
-			let vesting_json = &include_bytes!("../../../distribution/neumann_vesting.json")[..];
-			let initial_vesting: Vec<(u64, Vec<(AccountId, Balance)>)> =
-				serde_json::from_slice(vesting_json).unwrap();
-
-			testnet_genesis(
-				// initial collators.
-				vec![
-					(
-						// 5ECasnYivb8cQ4wBrQsdjwRTW4dzJ1ZcFqJNCLJwcc2N6WGL
-						hex!["5e7aee4ee53ef08d5032ba5db9f7a6fdd9eef52423ac8c1aa960236377b46610"]
-							.into(),
-						hex!["5e7aee4ee53ef08d5032ba5db9f7a6fdd9eef52423ac8c1aa960236377b46610"]
-							.unchecked_into(),
-					),
-					(
-						// 5D2VxzUBZBkYtLxnpZ9uAV7Vht2Jz5MwqSco2GaqyLwGDZ4J
-						hex!["2a8db6ca2e0cb5679e0eff0609de708c9957f465af49abbe7ff0a3594d52933e"]
-							.into(),
-						hex!["2a8db6ca2e0cb5679e0eff0609de708c9957f465af49abbe7ff0a3594d52933e"]
-							.unchecked_into(),
-					),
-				],
-				// 5GcD1vPdWzBd3VPTPgVFWL9K7b27A2tPYcVTJoGwKcLjdG5w
-				hex!["c8f7b3791290f2d0f66a08b6ae1ebafe8d1efff56e31b0bb14e8d98157379028"].into(),
-				initial_allocation,
-				DEFAULT_PARA_ID.into(),
-				vec![],
-				initial_vesting,
-				vec![
-					// 67nmVh57G9yo7sqiGLjgNNqtUd7H2CSESTyQgp5272aMibwS
-					hex!["488ced7d199b4386081a52505962128da5a3f54f4665db3d78b6e9f9e89eea4d"].into(),
-					// 67kgfmY6zpw1PRYpj3D5RtkzVZnVvn49XHGyR4v9MEsRRyet
-					hex!["46f630b3f79c588100dc0f69845633a830e01ea09eed4f1d01314a9bf33b9c16"].into(),
-					// 67D6ecyNhnAzZqgRbxr3MdGnxB9Bw8VadMhjpLAYB3wf5Pq6
-					hex!["2edf0fd8948ea642f135b314b1358c77ec6d0a4af83220b6ea18136e5ce36277"].into(),
-					// 6AMsXyV1CYc3LMTk155JTDGEzbgVPvsX9aXp7VXz9heC3iuP
-					hex!["ba44d2c00d9528c2d1fc51cef8ce8b9c3939928ecda8f404cdc46e3a2c090627"].into(),
-				],
-				vec![
-					// 67nmVh57G9yo7sqiGLjgNNqtUd7H2CSESTyQgp5272aMibwS
-					hex!["488ced7d199b4386081a52505962128da5a3f54f4665db3d78b6e9f9e89eea4d"].into(),
-					// 67kgfmY6zpw1PRYpj3D5RtkzVZnVvn49XHGyR4v9MEsRRyet
-					hex!["46f630b3f79c588100dc0f69845633a830e01ea09eed4f1d01314a9bf33b9c16"].into(),
-					// 6A6VuGbeUwm3J2HqLduH7VFZTvrYQs8GuqzdhopLGN2JKMAe
-					hex!["ae8b51cd0aa290645e593a4f54673ae62bab95791a137b943723bb6070533830"].into(),
-					// 699YyPF2uA83zsFnQU4GCAvZXzucvyS5rx8LS9UrL9kEv8PP
-					hex!["84a328f5f568d82ecd91861df7eae1065c1a2f1bcfec0950d4124e9363205b4a"].into(),
-					// 669ocRxey7vxUJs1TTRWe31zwrpGr8B13zRfAHB6yhhfcMud
-					hex!["001fbcefa8c96f3d2e236688da5485a0af67988b78d61ea952f461255d1f4267"].into(),
-				],
-			)
-		},
+		public_testnet_genesis,
 		// Bootnodes
 		Vec::new(),
 		// Telemetry
@@ -290,10 +236,100 @@ pub fn neumann_staging_testnet_config() -> ChainSpec {
 	)
 }
 
+/// Neumann on the Paseo test relay, for partners integrating against a public network.
+pub fn neumann_paseo_testnet_config() -> ChainSpec {
+	// Give your base currency a unit name and decimal places
+	let mut properties = sc_chain_spec::Properties::new();
+	properties.insert("tokenSymbol".into(), TOKEN_SYMBOL.into());
+	properties.insert("tokenDecimals".into(), TOKEN_DECIMALS.into());
+	properties.insert("ss58Format".into(), SS_58_FORMAT.into());
+
+	ChainSpec::from_genesis(
+		// Name
+		"Neumann Paseo",
+		// ID
+		"neumann-paseo",
+		ChainType::Live,
+		public_testnet_genesis,
+		// Bootnodes
+		Vec::new(),
+		// Telemetry
+		TelemetryEndpoints::new(vec![(TELEMETRY_URL.into(), 0)]).ok(),
+		// Protocol ID
+		Some("neumann-paseo"),
+		None,
+		// Properties
+		Some(properties),
+		// Extensions
+		Extensions {
+			relay_chain: PASEO_RELAY_CHAIN.into(), // You MUST set this to the correct network!
+			para_id: DEFAULT_PARA_ID,
+		},
+	)
+}
+
 pub fn neumann_latest() -> Result<DummyChainSpec, String> {
 	DummyChainSpec::from_json_bytes(&include_bytes!("../../res/neumann.json")[..])
 }
 
+/// Genesis shared by the public Neumann test networks.
+fn public_testnet_genesis() -> neumann_runtime::GenesisConfig {
+	let allocation_json = &include_bytes!("../../../distribution/neumann_vest_test_alloc.json")[..];
+	let initial_allocation: Vec<(AccountId, Balance)> =
+		serde_json::from_slice(allocation_json).unwrap();
+
+	let vesting_json = &include_bytes!("../../../distribution/neumann_vesting.json")[..];
+	let initial_vesting: Vec<(u64, Vec<(AccountId, Balance)>)> =
+		serde_json::from_slice(vesting_json).unwrap();
+
+	testnet_genesis(
+		// initial collators.
+		vec![
+			(
+				// 5ECasnYivb8cQ4wBrQsdjwRTW4dzJ1ZcFqJNCLJwcc2N6WGL
+				hex!["5e7aee4ee53ef08d5032ba5db9f7a6fdd9eef52423ac8c1aa960236377b46610"].into(),
+				hex!["5e7aee4ee53ef08d5032ba5db9f7a6fdd9eef52423ac8c1aa960236377b46610"]
+					.unchecked_into(),
+			),
+			(
+				// 5D2VxzUBZBkYtLxnpZ9uAV7Vht2Jz5MwqSco2GaqyLwGDZ4J
+				hex!["2a8db6ca2e0cb5679e0eff0609de708c9957f465af49abbe7ff0a3594d52933e"].into(),
+				hex!["2a8db6ca2e0cb5679e0eff0609de708c9957f465af49abbe7ff0a3594d52933e"]
+					.unchecked_into(),
+			),
+		],
+		// 5GcD1vPdWzBd3VPTPgVFWL9K7b27A2tPYcVTJoGwKcLjdG5w
+		hex!["c8f7b3791290f2d0f66a08b6ae1ebafe8d1efff56e31b0bb14e8d98157379028"].into(),
+		initial_allocation,
+		DEFAULT_PARA_ID.into(),
+		vec![],
+		initial_vesting,
+		vec![
+			// 67nmVh57G9yo7sqiGLjgNNqtUd7H2CSESTyQgp5272aMibwS
+			hex!["488ced7d199b4386081a52505962128da5a3f54f4665db3d78b6e9f9e89eea4d"].into(),
+			// 67kgfmY6zpw1PRYpj3D5RtkzVZnVvn49XHGyR4v9MEsRRyet
+			hex!["46f630b3f79c588100dc0f69845633a830e01ea09eed4f1d01314a9bf33b9c16"].into(),
+			// 67D6ecyNhnAzZqgRbxr3MdGnxB9Bw8VadMhjpLAYB3wf5Pq6
+			hex!["2edf0fd8948ea642f135b314b1358c77ec6d0a4af83220b6ea18136e5ce36277"].into(),
+			// 6AMsXyV1CYc3LMTk155JTDGEzbgVPvsX9aXp7VXz9heC3iuP
+			hex!["ba44d2c00d9528c2d1fc51cef8ce8b9c3939928ecda8f404cdc46e3a2c090627"].into(),
+		],
+		vec![
+			// 67nmVh57G9yo7sqiGLjgNNqtUd7H2CSESTyQgp5272aMibwS
+			hex!["488ced7d199b4386081a52505962128da5a3f54f4665db3d78b6e9f9e89eea4d"].into(),
+			// 67kgfmY6zpw1PRYpj3D5RtkzVZnVvn49XHGyR4v9MEsRRyet
+			hex!["46f630b3f79c588100dc0f69845633a830e01ea09eed4f1d01314a9bf33b9c16"].into(),
+			// 6A6VuGbeUwm3J2HqLduH7VFZTvrYQs8GuqzdhopLGN2JKMAe
+			hex!["ae8b51cd0aa290645e593a4f54673ae62bab95791a137b943723bb6070533830"].into(),
+			// 699YyPF2uA83zsFnQU4GCAvZXzucvyS5rx8LS9UrL9kEv8PP
+			hex!["84a328f5f568d82ecd91861df7eae1065c1a2f1bcfec0950d4124e9363205b4a"].into(),
+			// 669ocRxey7vxUJs1TTRWe31zwrpGr8B13zRfAHB6yhhfcMud
+			hex!["001fbcefa8c96f3d2e236688da5485a0af67988b78d61ea952f461255d1f4267"].into(),
+		],
+		vec![],
+	)
+}
+
 const NUM_SELECTED_CANDIDATES: u32 = 6;
 fn testnet_genesis(
 	invulnerables: Vec<(AccountId, AuraId)>,
@@ -304,6 +340,7 @@ fn testnet_genesis(
 	vesting_schedule: Vec<(u64, Vec<(AccountId, Balance)>)>,
 	general_councils: Vec<AccountId>,
 	technical_memberships: Vec<AccountId>,
+	price_assets: Vec<PriceAsset>,
 ) -> neumann_runtime::GenesisConfig {
 	let candidate_stake = neumann_runtime::MinCandidateStk::get();
 
@@ -360,6 +397,8 @@ fn testnet_genesis(
 		valve: ValveConfig { start_with_valve_closed: false, closed_gates: pallet_gates_closed },
 		vesting: VestingConfig { vesting_schedule },
 		asset_registry: Default::default(),
+		automation_price: AutomationPriceConfig { assets: price_assets },
+		oracle_membership: Default::default(),
 	}
 }
 
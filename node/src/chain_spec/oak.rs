@@ -397,5 +397,7 @@ fn testnet_genesis(
 		valve: ValveConfig { start_with_valve_closed: false, closed_gates: pallet_gates_closed },
 		vesting: VestingConfig { vesting_schedule },
 		asset_registry: Default::default(),
+		automation_price: Default::default(),
+		oracle_membership: Default::default(),
 	}
 }
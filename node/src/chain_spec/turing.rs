@@ -4,15 +4,15 @@ use sp_core::sr25519;
 use sp_runtime::{Perbill, Percent};
 
 use crate::chain_spec::{
-	get_account_id_from_seed, get_collator_keys_from_seed, inflation_config, DummyChainSpec,
-	Extensions,
+	dev_price_assets, get_account_id_from_seed, get_collator_keys_from_seed, inflation_config,
+	DummyChainSpec, Extensions, PriceAsset,
 };
 use codec::Encode;
 use common_runtime::constants::currency::{DOLLAR, TOKEN_DECIMALS};
 use primitives::{assets::CustomMetadata, AccountId, AuraId, Balance, TokenId};
 use turing_runtime::{
-	AssetRegistryConfig, CouncilConfig, PolkadotXcmConfig, TechnicalMembershipConfig, ValveConfig,
-	VestingConfig,
+	AssetRegistryConfig, AutomationPriceConfig, CouncilConfig, PolkadotXcmConfig,
+	TechnicalMembershipConfig, ValveConfig, VestingConfig,
 };
 use xcm::{prelude::*, VersionedMultiLocation::V3};
 
@@ -176,6 +176,7 @@ pub fn turing_development_config() -> ChainSpec {
 						),
 					),
 				],
+				dev_price_assets(get_account_id_from_seed::<sr25519::Public>("Alice")),
 			)
 		},
 		Vec::new(),
@@ -208,6 +209,7 @@ fn testnet_genesis(
 	general_councils: Vec<AccountId>,
 	technical_memberships: Vec<AccountId>,
 	additional_assets: Vec<(TokenId, Vec<u8>)>,
+	price_assets: Vec<PriceAsset>,
 ) -> turing_runtime::GenesisConfig {
 	let candidate_stake = turing_runtime::MinCandidateStk::get();
 
@@ -286,6 +288,8 @@ fn testnet_genesis(
 		valve: ValveConfig { start_with_valve_closed: false, closed_gates: pallet_gates_closed },
 		vesting: VestingConfig { vesting_schedule },
 		asset_registry: AssetRegistryConfig { assets, last_asset_id },
+		automation_price: AutomationPriceConfig { assets: price_assets },
+		oracle_membership: Default::default(),
 	}
 }
 
@@ -26,6 +26,8 @@ fn load_spec(id: &str) -> std::result::Result<Box<dyn ChainSpec>, String> {
 		#[cfg(feature = "neumann-node")]
 		"neumann-staging" => Box::new(chain_spec::neumann::neumann_staging_testnet_config()),
 		#[cfg(feature = "neumann-node")]
+		"neumann-paseo" => Box::new(chain_spec::neumann::neumann_paseo_testnet_config()),
+		#[cfg(feature = "neumann-node")]
 		"neumann" => Box::new(chain_spec::neumann::neumann_latest()?),
 		#[cfg(feature = "turing-node")]
 		"turing-dev" => Box::new(chain_spec::turing::turing_development_config()),
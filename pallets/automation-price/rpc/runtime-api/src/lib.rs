@@ -28,16 +28,117 @@ use serde::{Deserialize, Serialize};
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
 pub struct FeeDetails<Balance> {
+	/// The fee for scheduling the task, in the schedule fee asset.
 	pub schedule_fee: Balance,
+	/// The part of the XCM fee charged up front when the task is scheduled.
 	pub execution_fee: Balance,
+	/// The fee for executing the XCM message on the destination, in the execution fee asset.
+	pub xcm_fee: Balance,
+}
+
+/// The fee details reported by version 1 of the runtime api, before the XCM fee was added.
+#[derive(Debug, PartialEq, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct FeeDetailsV1<Balance> {
+	pub schedule_fee: Balance,
+	pub execution_fee: Balance,
+}
+
+#[derive(Debug, PartialEq, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct AssetMetadata {
+	pub symbol: Vec<u8>,
+	pub decimal: u8,
+}
+
+/// A price of an asset pair, as written by its oracle providers.
+#[derive(Debug, PartialEq, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct PriceData {
+	pub round: u128,
+	pub updated_at: u128,
+	pub value: u128,
+}
+
+/// The kind of action a task carries out when it is triggered.
+#[derive(Debug, PartialEq, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum TaskActionKind {
+	Xcmp,
+	DynamicDispatch,
+}
+
+/// Where a task stands in its life.
+#[derive(Debug, PartialEq, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum TaskStatus {
+	/// Waiting for its price condition.
+	Scheduled,
+	/// Triggered and waiting to run.
+	Queued,
+	/// A recurring task waiting out its cooldown, or a task waiting for its time window to open.
+	CoolingDown,
+	/// An XCMP task waiting to send its message again.
+	RetryingXcmSend,
+	/// Paused by its owner.
+	Paused,
+	/// Past its expiry and about to be removed.
+	Expired,
+}
+
+#[derive(Debug, PartialEq, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct TaskDetails<AccountId, Hash> {
+	pub owner_id: AccountId,
+	pub task_id: Hash,
+	pub chain: Vec<u8>,
+	pub exchange: Vec<u8>,
+	pub asset_pair: (Vec<u8>, Vec<u8>),
+	pub trigger_function: Vec<u8>,
+	pub trigger_params: Vec<u128>,
+	pub action: TaskActionKind,
+	pub expired_at: u128,
+	pub status: TaskStatus,
 }
 
 sp_api::decl_runtime_apis! {
+	#[api_version(2)]
 	pub trait AutomationPriceApi<AccountId, Hash, Balance> where
 		AccountId: Codec,
 		Hash: Codec,
 		Balance: Codec,
 	{
+		#[changed_in(2)]
+		fn query_fee_details(uxt: Block::Extrinsic) -> Result<FeeDetailsV1<Balance>, Vec<u8>>;
 		fn query_fee_details(uxt: Block::Extrinsic) -> Result<FeeDetails<Balance>, Vec<u8>>;
+		#[api_version(2)]
+		fn get_asset_metadata(
+			chain: Vec<u8>,
+			exchange: Vec<u8>,
+			asset1: Vec<u8>,
+			asset2: Vec<u8>,
+		) -> Option<AssetMetadata>;
+		#[api_version(2)]
+		fn get_price(
+			chain: Vec<u8>,
+			exchange: Vec<u8>,
+			asset1: Vec<u8>,
+			asset2: Vec<u8>,
+		) -> Option<PriceData>;
+		#[api_version(2)]
+		fn get_price_history(
+			chain: Vec<u8>,
+			exchange: Vec<u8>,
+			asset1: Vec<u8>,
+			asset2: Vec<u8>,
+		) -> Vec<PriceData>;
+		#[api_version(2)]
+		fn get_task(task_id: Hash) -> Option<TaskDetails<AccountId, Hash>>;
+		#[api_version(2)]
+		fn get_account_tasks(account_id: AccountId) -> Vec<TaskDetails<AccountId, Hash>>;
 	}
 }
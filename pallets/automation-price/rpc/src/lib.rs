@@ -22,8 +22,8 @@ use jsonrpsee::{
 	types::error::{CallError, ErrorObject},
 };
 pub use pallet_automation_price_rpc_runtime_api::AutomationPriceApi as AutomationPriceRuntimeApi;
-use pallet_automation_price_rpc_runtime_api::FeeDetails;
-use sp_api::ProvideRuntimeApi;
+use pallet_automation_price_rpc_runtime_api::{FeeDetails, PriceData, TaskDetails};
+use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
 use sp_core::Bytes;
 use sp_rpc::number::NumberOrHex;
@@ -39,6 +39,44 @@ pub trait AutomationPriceApi<BlockHash, AccountId, Hash, Balance> {
 		encoded_xt: Bytes,
 		at: Option<BlockHash>,
 	) -> RpcResult<FeeDetails<NumberOrHex>>;
+
+	/// Returns the current price of an asset pair.
+	#[method(name = "automationPrice_getPrice")]
+	fn get_price(
+		&self,
+		chain: String,
+		exchange: String,
+		asset1: String,
+		asset2: String,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<PriceData>>;
+
+	/// Returns the last prices of an asset pair, oldest first.
+	#[method(name = "automationPrice_getPriceHistory")]
+	fn get_price_history(
+		&self,
+		chain: String,
+		exchange: String,
+		asset1: String,
+		asset2: String,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<PriceData>>;
+
+	/// Returns the details of a task, found by its id alone.
+	#[method(name = "automationPrice_getTask")]
+	fn get_task(
+		&self,
+		task_id: Hash,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<TaskDetails<AccountId, Hash>>>;
+
+	/// Returns the details of every task of an account.
+	#[method(name = "automationPrice_getAccountTasks")]
+	fn get_account_tasks(
+		&self,
+		account: AccountId,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<TaskDetails<AccountId, Hash>>>;
 }
 
 /// An implementation of Automation-specific RPC methods on full client.
@@ -96,8 +134,37 @@ where
 				Some(format!("{:?}", e)),
 			))
 		})?;
-		let fee_details = api
-			.query_fee_details(at_hash, uxt)
+		let api_version = api
+			.api_version::<dyn AutomationPriceRuntimeApi<Block, AccountId, Hash, Balance>>(at_hash)
+			.map_err(|e| {
+				CallError::Custom(ErrorObject::owned(
+					Error::RuntimeError.into(),
+					"Unable to get the runtime api version.".to_string(),
+					Some(format!("{:?}", e)),
+				))
+			})?
+			.ok_or_else(|| {
+				CallError::Custom(ErrorObject::owned(
+					Error::RuntimeError.into(),
+					"The automation price runtime api was not found.".to_string(),
+					None::<()>,
+				))
+			})?;
+		// Runtimes before version 2 of the api do not report the XCM fee
+		let fee_details = if api_version < 2 {
+			#[allow(deprecated)]
+			api.query_fee_details_before_version_2(at_hash, uxt).map(|result| {
+				result
+					.map(|fee_details| (fee_details.schedule_fee, fee_details.execution_fee, None))
+			})
+		} else {
+			api.query_fee_details(at_hash, uxt).map(|result| {
+				result.map(|fee_details| {
+					(fee_details.schedule_fee, fee_details.execution_fee, Some(fee_details.xcm_fee))
+				})
+			})
+		};
+		let (schedule_fee, execution_fee, xcm_fee) = fee_details
 			.map_err(|e| {
 				CallError::Custom(ErrorObject::owned(
 					Error::RuntimeError.into(),
@@ -124,8 +191,96 @@ where
 		};
 
 		Ok(FeeDetails {
-			schedule_fee: try_into_rpc_balance(fee_details.schedule_fee)?,
-			execution_fee: try_into_rpc_balance(fee_details.execution_fee)?,
+			schedule_fee: try_into_rpc_balance(schedule_fee)?,
+			execution_fee: try_into_rpc_balance(execution_fee)?,
+			xcm_fee: match xcm_fee {
+				Some(xcm_fee) => try_into_rpc_balance(xcm_fee)?,
+				None => NumberOrHex::Number(0),
+			},
+		})
+	}
+
+	fn get_price(
+		&self,
+		chain: String,
+		exchange: String,
+		asset1: String,
+		asset2: String,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Option<PriceData>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+		api.get_price(
+			at_hash,
+			chain.into_bytes(),
+			exchange.into_bytes(),
+			asset1.into_bytes(),
+			asset2.into_bytes(),
+		)
+		.map_err(|e| {
+			JsonRpseeError::Call(CallError::Custom(ErrorObject::owned(
+				Error::RuntimeError.into(),
+				"Unable to get price",
+				Some(format!("{:?}", e)),
+			)))
+		})
+	}
+
+	fn get_price_history(
+		&self,
+		chain: String,
+		exchange: String,
+		asset1: String,
+		asset2: String,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Vec<PriceData>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+		api.get_price_history(
+			at_hash,
+			chain.into_bytes(),
+			exchange.into_bytes(),
+			asset1.into_bytes(),
+			asset2.into_bytes(),
+		)
+		.map_err(|e| {
+			JsonRpseeError::Call(CallError::Custom(ErrorObject::owned(
+				Error::RuntimeError.into(),
+				"Unable to get price history",
+				Some(format!("{:?}", e)),
+			)))
+		})
+	}
+
+	fn get_task(
+		&self,
+		task_id: Hash,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Option<TaskDetails<AccountId, Hash>>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+		api.get_task(at_hash, task_id).map_err(|e| {
+			JsonRpseeError::Call(CallError::Custom(ErrorObject::owned(
+				Error::RuntimeError.into(),
+				"Unable to get task",
+				Some(format!("{:?}", e)),
+			)))
+		})
+	}
+
+	fn get_account_tasks(
+		&self,
+		account: AccountId,
+		at: Option<Block::Hash>,
+	) -> RpcResult<Vec<TaskDetails<AccountId, Hash>>> {
+		let api = self.client.runtime_api();
+		let at_hash = at.unwrap_or_else(|| self.client.info().best_hash);
+		api.get_account_tasks(at_hash, account).map_err(|e| {
+			JsonRpseeError::Call(CallError::Custom(ErrorObject::owned(
+				Error::RuntimeError.into(),
+				"Unable to get account tasks",
+				Some(format!("{:?}", e)),
+			)))
 		})
 	}
 }
@@ -0,0 +1,46 @@
+// This file is part of OAK Blockchain.
+
+// Copyright (C) 2022 OAK Network
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use sp_std::vec::Vec;
+
+/// Combines the prices that the oracle providers submitted for a round of an asset pair into the
+/// price of the pair.
+pub trait AggregatePrices {
+	/// The price of the pair, or `None` when no price was submitted.
+	fn aggregate(prices: Vec<u128>) -> Option<u128>;
+}
+
+/// Takes the median of the submitted prices, which is the mean of the two middle prices for an
+/// even number of them. A single provider cannot move the median far on its own.
+pub struct MedianPrice;
+
+impl AggregatePrices for MedianPrice {
+	fn aggregate(mut prices: Vec<u128>) -> Option<u128> {
+		if prices.is_empty() {
+			return None
+		}
+
+		prices.sort_unstable();
+		let middle = prices.len() / 2;
+		if prices.len() % 2 == 0 {
+			let (low, high) = (prices[middle - 1], prices[middle]);
+			Some(low + (high - low) / 2)
+		} else {
+			Some(prices[middle])
+		}
+	}
+}
@@ -19,7 +19,9 @@
 
 use super::*;
 use frame_benchmarking::{account, benchmarks};
+use frame_support::traits::EnsureOrigin;
 use frame_system::RawOrigin;
+use pallet_timestamp::Pallet as Timestamp;
 
 use polkadot_parachain::primitives::Sibling;
 use sp_runtime::traits::{AccountIdConversion, Saturating};
@@ -36,6 +38,8 @@ const SEED: u32 = 0;
 const ED_MULTIPLIER: u32 = 1_000;
 // ensure enough funds to execute tasks
 const DEPOSIT_MULTIPLIER: u32 = 100_000_000;
+// the longest call scheduled when benchmarking how the weight grows with the task input
+const MAX_CALL_LENGTH: u32 = 10_240;
 
 const chain: &[u8] = "chain".as_bytes();
 const exchange: &[u8] = "exchange".as_bytes();
@@ -57,6 +61,19 @@ fn setup_asset<T: Config>(authorized_wallets: Vec<T::AccountId>) {
 	);
 }
 
+// a price update of the benchmarked asset pair
+fn price_update(price: u128) -> PriceUpdate {
+	PriceUpdate {
+		chain: chain.to_vec(),
+		exchange: exchange.to_vec(),
+		asset1: asset_tur.to_vec(),
+		asset2: asset_usd.to_vec(),
+		price,
+		submitted_at: 1,
+		round: 1,
+	}
+}
+
 // a helper method to schedule task with a set of default params to support benchmark easier
 fn schedule_xcmp_task<T: Config>(
 	para_id: u32,
@@ -82,9 +99,15 @@ fn schedule_xcmp_task<T: Config>(
 		call,
 		Weight::from_parts(100_000, 0),
 		Weight::from_parts(200_000, 0),
+		None,
 	);
 }
 
+// a task id for the `i`th task set up by a benchmark
+fn task_id_of(i: u128) -> TaskId {
+	sp_io::hashing::blake2_256(&i.to_le_bytes())
+}
+
 // direct_task_schedule push the task directly to the task registry and relevant setup,
 // by pass the normal extrinsic execution.
 // This funciton should be used to prepare data for benchmark
@@ -135,8 +158,14 @@ fn direct_task_schedule<T: Config>(
 
 benchmarks! {
 	initialize_asset_extrinsic {
+		// A signed account registers the asset pair, holding a deposit for it
 		let v in 1..5;
 		let asset_pair = (asset_tur.to_vec(), asset_usd.to_vec());
+		let owner: T::AccountId = account("owner", 0, SEED);
+		T::Currency::deposit_creating(
+			&owner,
+			T::Currency::minimum_balance().saturating_mul(DEPOSIT_MULTIPLIER.into()).saturating_add(T::AssetDeposit::get()),
+		);
 
 		let mut authorized_wallets: Vec<T::AccountId> = vec![];
 		for i in 1..=v {
@@ -144,53 +173,82 @@ benchmarks! {
 		}
 	} : {
 		AutomationPrice::<T>::initialize_asset(
-			RawOrigin::Root.into(),
+			RawOrigin::Signed(owner.clone()).into(),
 			chain.to_vec(), exchange.to_vec(),
 			asset_tur.to_vec(), asset_usd.to_vec(), decimal, authorized_wallets);
 	}
+	verify {
+		let key = (chain.to_vec(), exchange.to_vec(), asset_pair);
+		assert_eq!(AutomationPrice::<T>::get_asset_ownership(&key).map(|ownership| ownership.owner), Some(owner));
+	}
 
 	asset_price_update_extrinsic {
-		// Depend on the size of the input, the weight change, ideally scale linearly
-		// Therefore we can simulate v from 1..100 and substrate will agg those value
+		// Every update is accepted and moves the price of its own asset pair, which has a circuit
+		// breaker to check and a price history to record
 		let v in 1..100;
 		let sender : T::AccountId = account("caller", 0, SEED);
+		Timestamp::<T>::set_timestamp(10_000_000u32.into());
 
-		setup_asset::<T>(vec![sender.clone()]);
-
-		let mut chains: Vec<Vec<u8>> = vec![];
-		let mut exchanges: Vec<Vec<u8>> = vec![];
-		let mut assets1: Vec<Vec<u8>> = vec![];
-		let mut assets2: Vec<Vec<u8>> = vec![];
-		let mut prices: Vec<u128> = vec![];
-		let mut submitted_ats: Vec<u128> = vec![];
-		let mut rounds: Vec<u128> = vec![];
-
+		let mut updates: Vec<PriceUpdate> = vec![];
 		for i in 1..=v {
-			chains.push(format!("chain:{:?}", i).as_bytes().to_vec());
-			exchanges.push(format!("exchange:{:?}", i).as_bytes().to_vec());
-			assets1.push(format!("ASSET1{:?}", i).as_bytes().to_vec());
-			assets2.push(format!("ASSET2{:?}", i).as_bytes().to_vec());
-			prices.push(i as u128);
-			submitted_ats.push(i as u128);
-			rounds.push(i as u128);
+			let update = PriceUpdate {
+				chain: format!("chain:{:?}", i).as_bytes().to_vec(),
+				exchange: format!("exchange:{:?}", i).as_bytes().to_vec(),
+				asset1: format!("ASSET1{:?}", i).as_bytes().to_vec(),
+				asset2: format!("ASSET2{:?}", i).as_bytes().to_vec(),
+				price: i as u128,
+				submitted_at: i as u128,
+				round: 1,
+			};
+			AutomationPrice::<T>::initialize_asset(RawOrigin::Root.into(), update.chain.clone(), update.exchange.clone(), update.asset1.clone(), update.asset2.clone(), decimal, vec![sender.clone()])
+				.map_err(|_| "cannot initialize asset")?;
+			let key = (update.chain.clone(), update.exchange.clone(), (update.asset1.clone(), update.asset2.clone()));
+			PriceRegistry::<T>::insert(&key, PriceData { round: 0, updated_at: 1, value: i.into() });
+			CircuitBreakers::<T>::insert(&key, CircuitBreaker { max_deviation: Perbill::one(), cooldown: None });
+			updates.push(update);
 		}
+		let last_key = (format!("chain:{:?}", v).as_bytes().to_vec(), format!("exchange:{:?}", v).as_bytes().to_vec(), (format!("ASSET1{:?}", v).as_bytes().to_vec(), format!("ASSET2{:?}", v).as_bytes().to_vec()));
 	} : {
 		AutomationPrice::<T>::update_asset_prices(
 			RawOrigin::Signed(sender.clone()).into(),
-			chains,
-			exchanges,
-			assets1,
-			assets2,
-			prices,
-			submitted_ats,
-			rounds
+			updates
 		);
 	}
+	verify {
+		assert_eq!(AutomationPrice::<T>::get_asset_price_data(&last_key).map(|price| price.round), Some(1));
+	}
+
+	delete_asset_extrinsic {
+		// Every task of the asset is cancelled and its execution fee refunded, and the deposit of
+		// the account that registered the asset is slashed
+		let v in 0 .. T::MaxTasksPerPurge::get();
+
+		Timestamp::<T>::set_timestamp(10_000_000u32.into());
+		let owner: T::AccountId = account("owner", 0, SEED);
+		T::Currency::deposit_creating(
+			&owner,
+			T::Currency::minimum_balance().saturating_mul(DEPOSIT_MULTIPLIER.into()).saturating_add(T::AssetDeposit::get()),
+		);
+		AutomationPrice::<T>::initialize_asset(RawOrigin::Signed(owner).into(), chain.to_vec(), exchange.to_vec(), asset_tur.to_vec(), asset_usd.to_vec(), decimal, vec![])
+			.map_err(|_| "cannot initialize asset")?;
+		for i in 0..v {
+			// Every task has its own owner to stay under the per account limit
+			let creator: T::AccountId = account("caller", i, SEED);
+			direct_task_schedule::<T>(creator, task_id_of(i.into()), 100_000, "gt".as_bytes().to_vec(), (i + 1).into(), vec![100, 200, (i % 256) as u8])
+				.map_err(|_| "cannot schedule task")?;
+		}
+		let key = (chain.to_vec(), exchange.to_vec(), (asset_tur.to_vec(), asset_usd.to_vec()));
+	}: delete_asset(RawOrigin::Root, chain.to_vec(), exchange.to_vec(), asset_tur.to_vec(), asset_usd.to_vec())
+	verify {
+		assert!(AutomationPrice::<T>::get_asset_registry_info(&key).is_none());
+	}
 
 	schedule_xcmp_task_extrinsic {
+		// The length of the call, which makes up most of the input of the task
+		let v in 1 .. MAX_CALL_LENGTH;
 		let sender : T::AccountId = account("caller", 0, SEED);
 		let para_id: u32 = 1000;
-		let call: Vec<u8> = vec![2, 4, 5];
+		let call: Vec<u8> = vec![2; v as usize];
 		setup_asset::<T>(vec![sender.clone()]);
 		let transfer_amount = T::Currency::minimum_balance().saturating_mul(ED_MULTIPLIER.into());
 		T::Currency::deposit_creating(
@@ -221,13 +279,13 @@ benchmarks! {
 		  // Fund the account so we can schedule task
 		  let account_min = T::Currency::minimum_balance().saturating_mul(ED_MULTIPLIER.into());
 		  T::Currency::deposit_creating(&creator, account_min.saturating_mul(DEPOSIT_MULTIPLIER.into()));
-		  direct_task_schedule::<T>(creator.clone(), format!("{:?}", i).as_bytes().to_vec(), i, "gt".as_bytes().to_vec(), i, vec![100, 200, (i % 256) as u8]);
-		  task_ids.push(format!("{:?}", i).as_bytes().to_vec());
+		  direct_task_schedule::<T>(creator.clone(), task_id_of(i), i, "gt".as_bytes().to_vec(), i, vec![100, 200, (i % 256) as u8]);
+		  task_ids.push(task_id_of(i));
 		}
 
-		let task_id_to_cancel = "1".as_bytes().to_vec();
+		let task_id_to_cancel = task_id_of(1);
 	} : {
-		AutomationPrice::<T>::cancel_task(RawOrigin::Signed(creator).into(), task_id_to_cancel.clone());
+		AutomationPrice::<T>::cancel_task(RawOrigin::Signed(creator).into(), task_id_to_cancel);
 	}
 	verify {
 	}
@@ -250,6 +308,23 @@ benchmarks! {
 		AutomationPrice::<T>::run_xcmp_task(destination, creator, fee, call, Weight::from_parts(100_000, 0), Weight::from_parts(200_000, 0), InstructionSequence::PayThroughSovereignAccount)
 	}
 
+	run_dynamic_dispatch_action {
+		let caller: T::AccountId = account("caller", 0, SEED);
+		let call: <T as Config>::RuntimeCall = frame_system::Call::remark { remark: vec![] }.into();
+		let encoded_call = call.encode();
+	}: {
+		let (_, error) = AutomationPrice::<T>::run_dynamic_dispatch_action(caller.clone(), encoded_call);
+		assert_eq!(error, None);
+	}
+
+	run_dynamic_dispatch_action_fail_decode {
+		let caller: T::AccountId = account("caller", 0, SEED);
+		let bad_encoded_call: Vec<u8> = vec![1];
+	}: {
+		let (_, error) = AutomationPrice::<T>::run_dynamic_dispatch_action(caller.clone(), bad_encoded_call);
+		assert_eq!(error, Some(DispatchError::from(Error::<T>::CallCannotBeDecoded)));
+	}
+
 	remove_task {
 		let creator : T::AccountId = account("caller", 0, SEED);
 		let para_id: u32 = 1000;
@@ -278,13 +353,13 @@ benchmarks! {
 		let mut task_ids: Vec<TaskId> = vec![];
 		let mut tasks: Vec<Task<T>> = vec![];
 		for i in 1..100 {
-		  let task_id = format!("{:?}", i).as_bytes().to_vec();
+		  let task_id = task_id_of(i);
 		  let expired_at = i;
 		  let trigger_function = "gt".as_bytes().to_vec();
 		  let price_target: u128 = i;
 		  let encoded_call = vec![100, 200, (i % 256) as u8];
 
-		  task_ids.push(format!("{:?}", i).as_bytes().to_vec());
+		  task_ids.push(task_id_of(i));
 			let action = Action::XCMP {
 				destination,
 				schedule_fee,
@@ -298,7 +373,7 @@ benchmarks! {
 
 			let task: Task<T> = Task::<T> {
 				owner_id: creator.clone(),
-				task_id: task_id.clone(),
+				task_id,
 				chain: chain.to_vec(),
 				exchange: exchange.to_vec(),
 				asset_pair: (asset_tur.to_vec(), asset_usd.to_vec()),
@@ -316,7 +391,7 @@ benchmarks! {
 		// remove a task at the end to simulate the worst case
 		AutomationPrice::<T>::remove_task(&task, Some(crate::Event::<T>::TaskSweep {
 			owner_id: task.owner_id.clone(),
-			task_id: task.task_id.clone(),
+			task_id: task.task_id,
 			condition: crate::TaskCondition::AlreadyExpired {
 				expired_at: task.expired_at,
 				now: 100,
@@ -325,15 +400,343 @@ benchmarks! {
 	}
 
 
+	purge_tasks_extrinsic {
+		let v in 1 .. T::MaxTasksPerPurge::get();
+		let creator : T::AccountId = account("caller", 0, SEED);
+		setup_asset::<T>(vec![creator.clone()]);
+
+		for i in 0..v {
+			direct_task_schedule::<T>(creator.clone(), task_id_of(i.into()), (i + 1).into(), "gt".as_bytes().to_vec(), i.into(), vec![100, 200, (i % 256) as u8]);
+		}
+
+		let filter = PurgeFilter::Destination(MultiLocation::new(1, X1(Parachain(2000))));
+		let origin = T::PurgeOrigin::try_successful_origin()
+			.map_err(|_| "PurgeOrigin has no successful origin")?;
+	}: _<T::RuntimeOrigin>(origin, filter.clone(), v)
+	verify {
+		assert!(Tasks::<T>::iter().next().is_none());
+	}
+
+	reclaim_deposit {
+		let caller: T::AccountId = account("caller", 0, SEED);
+		let owner: T::AccountId = account("owner", 0, SEED);
+		let task_id = task_id_of(4);
+		let deposit = T::Currency::minimum_balance().saturating_mul(ED_MULTIPLIER.into());
+		T::Currency::deposit_creating(&owner, deposit.saturating_mul(2u32.into()));
+		T::Currency::reserve(&owner, deposit)?;
+		TaskDeposits::<T>::insert(&owner, &task_id, deposit);
+	}: _(RawOrigin::Signed(caller), owner.clone(), task_id)
+	verify {
+		assert!(AutomationPrice::<T>::get_task_deposit(&owner, &task_id).is_none());
+	}
+
+	set_shutdown_flags {
+		let flags = ShutdownFlags { scheduling: true, execution: false, price_ingestion: false };
+		let origin = T::ShutdownOrigin::try_successful_origin()
+			.map_err(|_| "ShutdownOrigin has no successful origin")?;
+	}: _<T::RuntimeOrigin>(origin, flags)
+	verify {
+		assert_eq!(AutomationPrice::<T>::get_shutdown_flags(), flags);
+	}
+
+	update_task_trigger {
+		let creator: T::AccountId = account("caller", 0, SEED);
+		let task_id = task_id_of(1);
+		Timestamp::<T>::set_timestamp(10_000_000u32.into());
+		let deposit = T::Currency::minimum_balance().saturating_mul(ED_MULTIPLIER.into());
+		T::Currency::deposit_creating(&creator, deposit.saturating_mul(DEPOSIT_MULTIPLIER.into()));
+		setup_asset::<T>(vec![creator.clone()]);
+		direct_task_schedule::<T>(creator.clone(), task_id, 100_000, "gt".as_bytes().to_vec(), 100, vec![100, 200, 1])
+			.map_err(|_| "cannot schedule task")?;
+	}: _(RawOrigin::Signed(creator.clone()), task_id, vec![200])
+	verify {
+		let task = AutomationPrice::<T>::get_task(&creator, &task_id).ok_or("task is missing")?;
+		assert_eq!(task.trigger_params, vec![200]);
+	}
+
+	transfer_asset_ownership {
+		let new_owner: T::AccountId = account("owner", 0, SEED);
+		setup_asset::<T>(vec![]);
+	}: _(RawOrigin::Root, chain.to_vec(), exchange.to_vec(), asset_tur.to_vec(), asset_usd.to_vec(), AssetOwner::Account(new_owner.clone()))
+	verify {
+		let key = (chain.to_vec(), exchange.to_vec(), (asset_tur.to_vec(), asset_usd.to_vec()));
+		assert_eq!(AutomationPrice::<T>::get_pending_asset_owner(&key), Some(AssetOwner::Account(new_owner)));
+	}
+
+	accept_asset_ownership {
+		// The asset pair moves between accounts, so the deposit is moved as well
+		let owner: T::AccountId = account("owner", 0, SEED);
+		let new_owner: T::AccountId = account("owner", 1, SEED);
+		let deposit = T::Currency::minimum_balance().saturating_mul(ED_MULTIPLIER.into());
+		T::Currency::deposit_creating(&owner, deposit.saturating_mul(2u32.into()));
+		T::Currency::deposit_creating(&new_owner, deposit.saturating_mul(2u32.into()));
+		T::Currency::reserve(&owner, deposit)?;
+		setup_asset::<T>(vec![]);
+		let key = (chain.to_vec(), exchange.to_vec(), (asset_tur.to_vec(), asset_usd.to_vec()));
+		AssetOwners::<T>::insert(&key, AssetOwnership { owner, deposit });
+		PendingAssetOwners::<T>::insert(&key, AssetOwner::Account(new_owner.clone()));
+	}: _(RawOrigin::Signed(new_owner.clone()), chain.to_vec(), exchange.to_vec(), asset_tur.to_vec(), asset_usd.to_vec())
+	verify {
+		assert_eq!(AutomationPrice::<T>::get_asset_ownership(&key), Some(AssetOwnership { owner: new_owner, deposit }));
+	}
+
+	add_oracle_provider {
+		let provider: T::AccountId = account("provider", 0, SEED);
+		setup_asset::<T>(vec![]);
+		let origin = T::OracleProviderOrigin::try_successful_origin()
+			.map_err(|_| "OracleProviderOrigin has no successful origin")?;
+	}: _<T::RuntimeOrigin>(origin, chain.to_vec(), exchange.to_vec(), asset_tur.to_vec(), asset_usd.to_vec(), provider.clone())
+	verify {
+		assert!(AutomationPrice::<T>::is_oracle_provider_for(&provider, &[price_update(1)]));
+	}
+
+	remove_oracle_provider {
+		let provider: T::AccountId = account("provider", 0, SEED);
+		setup_asset::<T>(vec![provider.clone()]);
+		let origin = T::OracleProviderOrigin::try_successful_origin()
+			.map_err(|_| "OracleProviderOrigin has no successful origin")?;
+	}: _<T::RuntimeOrigin>(origin, chain.to_vec(), exchange.to_vec(), asset_tur.to_vec(), asset_usd.to_vec(), provider.clone())
+	verify {
+		assert!(!AutomationPrice::<T>::is_oracle_provider_for(&provider, &[price_update(1)]));
+	}
+
+	set_asset_symbol {
+		let symbol = "TUR/USD".as_bytes().to_vec();
+		setup_asset::<T>(vec![]);
+	}: _(RawOrigin::Root, chain.to_vec(), exchange.to_vec(), asset_tur.to_vec(), asset_usd.to_vec(), symbol.clone())
+	verify {
+		let metadata = AutomationPrice::<T>::get_asset_metadata(chain.to_vec(), exchange.to_vec(), asset_tur.to_vec(), asset_usd.to_vec());
+		assert_eq!(metadata, Some(AssetMetadata { symbol, decimal }));
+	}
+
+	set_circuit_breaker {
+		let circuit_breaker =
+			CircuitBreaker { max_deviation: Perbill::from_percent(20), cooldown: Some(3600) };
+		setup_asset::<T>(vec![]);
+	}: _(RawOrigin::Root, chain.to_vec(), exchange.to_vec(), asset_tur.to_vec(), asset_usd.to_vec(), Some(circuit_breaker))
+	verify {
+		let key = (chain.to_vec(), exchange.to_vec(), (asset_tur.to_vec(), asset_usd.to_vec()));
+		assert_eq!(AutomationPrice::<T>::get_circuit_breaker(&key), Some(circuit_breaker));
+	}
+
+	resume_asset {
+		let key = (chain.to_vec(), exchange.to_vec(), (asset_tur.to_vec(), asset_usd.to_vec()));
+		setup_asset::<T>(vec![]);
+		HaltedAssets::<T>::insert(&key, 0);
+		let origin = T::ShutdownOrigin::try_successful_origin()
+			.map_err(|_| "ShutdownOrigin has no successful origin")?;
+	}: _<T::RuntimeOrigin>(origin, chain.to_vec(), exchange.to_vec(), asset_tur.to_vec(), asset_usd.to_vec())
+	verify {
+		assert_eq!(AutomationPrice::<T>::get_halted_asset(&key), None);
+	}
+
+	set_task_schedule {
+		let creator: T::AccountId = account("caller", 0, SEED);
+		let task_id = task_id_of(1);
+		let schedule = Schedule::Recurring { cooldown_seconds: 3600, max_executions: Some(10) };
+		Timestamp::<T>::set_timestamp(10_000_000u32.into());
+		let deposit = T::Currency::minimum_balance().saturating_mul(ED_MULTIPLIER.into());
+		T::Currency::deposit_creating(&creator, deposit.saturating_mul(DEPOSIT_MULTIPLIER.into()));
+		setup_asset::<T>(vec![creator.clone()]);
+		direct_task_schedule::<T>(creator.clone(), task_id, 100_000, "gt".as_bytes().to_vec(), 100, vec![100, 200, 1])
+			.map_err(|_| "cannot schedule task")?;
+	}: _(RawOrigin::Signed(creator.clone()), task_id, schedule)
+	verify {
+		assert_eq!(
+			AutomationPrice::<T>::get_task_recurrence(&creator, &task_id),
+			Some(Recurrence { cooldown_seconds: 3600, remaining_executions: Some(10) })
+		);
+	}
+
+	set_missed_task_policy {
+		let creator: T::AccountId = account("caller", 0, SEED);
+		let task_id = task_id_of(1);
+		Timestamp::<T>::set_timestamp(10_000_000u32.into());
+		let deposit = T::Currency::minimum_balance().saturating_mul(ED_MULTIPLIER.into());
+		T::Currency::deposit_creating(&creator, deposit.saturating_mul(DEPOSIT_MULTIPLIER.into()));
+		setup_asset::<T>(vec![creator.clone()]);
+		direct_task_schedule::<T>(creator.clone(), task_id, 100_000, "gt".as_bytes().to_vec(), 100, vec![100, 200, 1])
+			.map_err(|_| "cannot schedule task")?;
+	}: _(RawOrigin::Signed(creator.clone()), task_id, MissedTaskPolicy::ExecuteLate)
+	verify {
+		assert_eq!(AutomationPrice::<T>::get_missed_task_policy(&creator, &task_id), MissedTaskPolicy::ExecuteLate);
+	}
+
+	pause_task {
+		let creator: T::AccountId = account("caller", 0, SEED);
+		let task_id = task_id_of(1);
+		Timestamp::<T>::set_timestamp(10_000_000u32.into());
+		let deposit = T::Currency::minimum_balance().saturating_mul(ED_MULTIPLIER.into());
+		T::Currency::deposit_creating(&creator, deposit.saturating_mul(DEPOSIT_MULTIPLIER.into()));
+		setup_asset::<T>(vec![creator.clone()]);
+		direct_task_schedule::<T>(creator.clone(), task_id, 100_000, "gt".as_bytes().to_vec(), 100, vec![100, 200, 1])
+			.map_err(|_| "cannot schedule task")?;
+	}: _(RawOrigin::Signed(creator.clone()), task_id)
+	verify {
+		assert!(AutomationPrice::<T>::is_task_paused(&creator, &task_id));
+	}
+
+	resume_task {
+		let creator: T::AccountId = account("caller", 0, SEED);
+		let task_id = task_id_of(1);
+		Timestamp::<T>::set_timestamp(10_000_000u32.into());
+		let deposit = T::Currency::minimum_balance().saturating_mul(ED_MULTIPLIER.into());
+		T::Currency::deposit_creating(&creator, deposit.saturating_mul(DEPOSIT_MULTIPLIER.into()));
+		setup_asset::<T>(vec![creator.clone()]);
+		direct_task_schedule::<T>(creator.clone(), task_id, 100_000, "gt".as_bytes().to_vec(), 100, vec![100, 200, 1])
+			.map_err(|_| "cannot schedule task")?;
+		AutomationPrice::<T>::pause_task(RawOrigin::Signed(creator.clone()).into(), task_id)?;
+	}: _(RawOrigin::Signed(creator.clone()), task_id)
+	verify {
+		assert!(!AutomationPrice::<T>::is_task_paused(&creator, &task_id));
+	}
+
+	set_task_time_window {
+		let creator: T::AccountId = account("caller", 0, SEED);
+		let task_id = task_id_of(1);
+		let window = TimeWindow { start: 8 * 3600, end: 20 * 3600 };
+		Timestamp::<T>::set_timestamp(10_000_000u32.into());
+		let deposit = T::Currency::minimum_balance().saturating_mul(ED_MULTIPLIER.into());
+		T::Currency::deposit_creating(&creator, deposit.saturating_mul(DEPOSIT_MULTIPLIER.into()));
+		setup_asset::<T>(vec![creator.clone()]);
+		direct_task_schedule::<T>(creator.clone(), task_id, 100_000, "gt".as_bytes().to_vec(), 100, vec![100, 200, 1])
+			.map_err(|_| "cannot schedule task")?;
+	}: _(RawOrigin::Signed(creator.clone()), task_id, Some(window))
+	verify {
+		assert_eq!(AutomationPrice::<T>::get_task_time_window(&creator, &task_id), Some(window));
+	}
+
+	set_task_conditions {
+		// Every condition is on an asset pair of its own
+		let creator: T::AccountId = account("caller", 0, SEED);
+		let task_id = task_id_of(1);
+		Timestamp::<T>::set_timestamp(10_000_000u32.into());
+		let deposit = T::Currency::minimum_balance().saturating_mul(ED_MULTIPLIER.into());
+		T::Currency::deposit_creating(&creator, deposit.saturating_mul(DEPOSIT_MULTIPLIER.into()));
+		setup_asset::<T>(vec![creator.clone()]);
+		let mut conditions = vec![];
+		for i in 0..T::MaxExtraConditions::get() {
+			let asset = format!("ASSET{}", i).into_bytes();
+			AutomationPrice::<T>::initialize_asset(RawOrigin::Root.into(), chain.to_vec(), exchange.to_vec(), asset.clone(), asset_usd.to_vec(), decimal, vec![])?;
+			conditions.push(PriceCondition {
+				chain: chain.to_vec(),
+				exchange: exchange.to_vec(),
+				asset_pair: (asset, asset_usd.to_vec()),
+				trigger_function: "lt".as_bytes().to_vec(),
+				trigger_params: vec![100],
+			});
+		}
+		let compound = CompoundCondition { combinator: Combinator::And, conditions };
+		direct_task_schedule::<T>(creator.clone(), task_id, 100_000, "gt".as_bytes().to_vec(), 100, vec![100, 200, 1])
+			.map_err(|_| "cannot schedule task")?;
+	}: _(RawOrigin::Signed(creator.clone()), task_id, Some(compound.clone()))
+	verify {
+		assert_eq!(AutomationPrice::<T>::get_task_conditions(&creator, &task_id), Some(compound));
+	}
+
+	set_asset_task_cap {
+		setup_asset::<T>(vec![]);
+		let origin = T::AssetAdminOrigin::try_successful_origin()
+			.map_err(|_| "AssetAdminOrigin has no successful origin")?;
+	}: _<T::RuntimeOrigin>(origin, chain.to_vec(), exchange.to_vec(), asset_tur.to_vec(), asset_usd.to_vec(), Some(10_000))
+	verify {
+		let key = (chain.to_vec(), exchange.to_vec(), (asset_tur.to_vec(), asset_usd.to_vec()));
+		assert_eq!(AutomationPrice::<T>::get_asset_task_cap(&key), Some(10_000));
+	}
+
+	set_task_tip {
+		let creator: T::AccountId = account("caller", 0, SEED);
+		let task_id = task_id_of(1);
+		Timestamp::<T>::set_timestamp(10_000_000u32.into());
+		let deposit = T::Currency::minimum_balance().saturating_mul(ED_MULTIPLIER.into());
+		T::Currency::deposit_creating(&creator, deposit.saturating_mul(DEPOSIT_MULTIPLIER.into()));
+		setup_asset::<T>(vec![creator.clone()]);
+		direct_task_schedule::<T>(creator.clone(), task_id, 100_000, "gt".as_bytes().to_vec(), 100, vec![100, 200, 1])
+			.map_err(|_| "cannot schedule task")?;
+	}: _(RawOrigin::Signed(creator.clone()), task_id, deposit)
+	verify {
+		assert_eq!(AutomationPrice::<T>::get_task_tip(&creator, &task_id), Some(deposit));
+	}
+
+	execute_ready_tasks {
+		// The keeper runs v queued tasks, and is paid the reward held for each of them
+		let v in 1 .. T::MaxTasksPerKeeperCall::get();
+
+		let keeper: T::AccountId = account("keeper", 0, SEED);
+		let reward = T::Currency::minimum_balance().saturating_mul(ED_MULTIPLIER.into());
+		T::Currency::deposit_creating(&AutomationPrice::<T>::keeper_pot_account(), reward.saturating_mul(DEPOSIT_MULTIPLIER.into()));
+		Timestamp::<T>::set_timestamp(10_000_000u32.into());
+		setup_asset::<T>(vec![]);
+		let mut task_queue: TaskIdList<T> = vec![];
+		for i in 0..v {
+			// Every task has its own owner to stay under the per account limit
+			let creator: T::AccountId = account("caller", i, SEED);
+			direct_task_schedule::<T>(creator.clone(), task_id_of(i.into()), 100_000, "gt".as_bytes().to_vec(), 1, vec![100, 200, (i % 256) as u8])
+				.map_err(|_| "cannot schedule task")?;
+			KeeperRewards::<T>::insert(&creator, task_id_of(i.into()), reward.saturated_into::<u128>().saturated_into::<MultiBalanceOf<T>>());
+			task_queue.push((creator, task_id_of(i.into())));
+		}
+		PriceRegistry::<T>::insert(
+			(chain.to_vec(), exchange.to_vec(), (asset_tur.to_vec(), asset_usd.to_vec())),
+			PriceData { round: 1, updated_at: 1, value: 2 },
+		);
+		TaskQueue::<T>::put(task_queue);
+	}: _(RawOrigin::Signed(keeper), v)
+	verify {
+		assert!(AutomationPrice::<T>::get_task_queue().is_empty());
+	}
+
+	shift_tasks {
+		// The price crossed the targets of all v tasks in the index of the asset pair
+		let v in 1 .. T::MaxShiftedTasksPerAssetPair::get();
+
+		Timestamp::<T>::set_timestamp(10_000_000u32.into());
+		setup_asset::<T>(vec![]);
+		for i in 0..v {
+			// Every task has its own owner to stay under the per account limit
+			let creator: T::AccountId = account("caller", i, SEED);
+			direct_task_schedule::<T>(creator, task_id_of(i.into()), 100_000, "gt".as_bytes().to_vec(), (i + 1).into(), vec![100, 200, (i % 256) as u8])
+				.map_err(|_| "cannot schedule task")?;
+		}
+		PriceRegistry::<T>::insert(
+			(chain.to_vec(), exchange.to_vec(), (asset_tur.to_vec(), asset_usd.to_vec())),
+			PriceData { round: 1, updated_at: 1, value: (v + 1).into() },
+		);
+	}: { AutomationPrice::<T>::shift_tasks(Weight::MAX, &mut PriceCache::default()) }
+	verify {
+		assert_eq!(AutomationPrice::<T>::get_task_queue().len(), v as usize);
+	}
+
+	update_task_queue {
+		// The task queue can hold every task when prices cross all of their targets at once
+		let v in 0 .. T::MaxTasksOverall::get();
+
+		let task_queue: TaskIdList<T> = (0..v)
+			.map(|i| (account("caller", i, SEED), task_id_of(i.into())))
+			.collect();
+		TaskQueue::<T>::put(task_queue);
+	}: {
+		let task_queue = AutomationPrice::<T>::get_task_queue();
+		TaskQueue::<T>::put(task_queue);
+	}
+
 	emit_event {
 		let owner_id: T::AccountId = account("call", 1, SEED);
 		let schedule_as: T::AccountId = account("schedule_as", 1, SEED);
-		let task_id: TaskId = vec![1,2,3];
+		let task_id = task_id_of(1);
 	} : {
 		AutomationPrice::<T>::deposit_event(crate::Event::<T>::TaskScheduled {
 				owner_id,
 				task_id,
 				schedule_as: Some(schedule_as),
+				chain: chain.to_vec(),
+				exchange: exchange.to_vec(),
+				asset_pair: (asset_tur.to_vec(), asset_usd.to_vec()),
+				trigger_function: "gt".as_bytes().to_vec(),
+				trigger_params: vec![6000],
+				action_kind: ActionKind::XCMP,
+				destination: MultiLocation::new(1, X1(Parachain(2000))),
+				expired_at: 12345,
 			});
 	}
 
@@ -36,12 +36,28 @@ pub trait HandleFees<T: Config> {
 		action: &ActionOf<T>,
 		prereq: F,
 	) -> Result<R, DispatchError>;
+
+	/// Return the execution fee paid when the action was scheduled, for an action that will no
+	/// longer run. Returns the amount refunded.
+	fn refund_execution_fee_for(
+		owner: &AccountOf<T>,
+		action: &ActionOf<T>,
+	) -> Result<MultiBalanceOf<T>, DispatchError>;
+
+	/// Pay the reward held in the keeper pot for an action to the keeper that ran it, or to the
+	/// fee revenue when no keeper did.
+	fn release_keeper_reward(
+		action: &ActionOf<T>,
+		amount: MultiBalanceOf<T>,
+		keeper: Option<&AccountOf<T>>,
+	) -> DispatchResult;
 }
 pub struct FeeHandler<T: Config, TR> {
 	owner: T::AccountId,
 	pub schedule_fee_location: MultiLocation,
 	pub schedule_fee_amount: MultiBalanceOf<T>,
 	pub execution_fee_amount: MultiBalanceOf<T>,
+	pub keeper_reward_amount: MultiBalanceOf<T>,
 	_phantom_data: PhantomData<TR>,
 }
 
@@ -61,6 +77,49 @@ where
 		fee_handler.pay_fees()?;
 		Ok(outcome)
 	}
+
+	fn refund_execution_fee_for(
+		owner: &AccountOf<T>,
+		action: &ActionOf<T>,
+	) -> Result<MultiBalanceOf<T>, DispatchError> {
+		let execution_fee_amount = Self::execution_fee_amount(action);
+		if execution_fee_amount.is_zero() {
+			return Ok(execution_fee_amount)
+		}
+
+		let currency_id = T::CurrencyIdConvert::convert(action.schedule_fee_location::<T>())
+			.ok_or("IncoveribleMultilocation")?;
+		T::XcmpTransactor::refund_xcm_fee(
+			currency_id,
+			owner.clone(),
+			execution_fee_amount.saturated_into(),
+		)?;
+		Ok(execution_fee_amount)
+	}
+
+	fn release_keeper_reward(
+		action: &ActionOf<T>,
+		amount: MultiBalanceOf<T>,
+		keeper: Option<&AccountOf<T>>,
+	) -> DispatchResult {
+		let schedule_fee_location = action.schedule_fee_location::<T>();
+		let currency_id = T::CurrencyIdConvert::convert(schedule_fee_location)
+			.ok_or("IncoveribleMultilocation")?
+			.into();
+		let pot = Pallet::<T>::keeper_pot_account();
+
+		match keeper {
+			Some(keeper) => T::MultiCurrency::transfer(currency_id, &pot, keeper, amount),
+			None => {
+				T::MultiCurrency::withdraw(currency_id, &pot, amount)?;
+				TR::take_revenue(MultiAsset {
+					id: AssetId::Concrete(schedule_fee_location),
+					fun: Fungibility::Fungible(amount.saturated_into()),
+				});
+				Ok(())
+			},
+		}
+	}
 }
 
 impl<T, TR> FeeHandler<T, TR>
@@ -104,10 +163,19 @@ where
 
 		match T::MultiCurrency::withdraw(currency_id.into(), &self.owner, fee) {
 			Ok(_) => {
+				// The part held for keepers waits in the keeper pot until the task runs
+				let revenue = self.schedule_fee_amount.saturating_sub(self.keeper_reward_amount);
 				TR::take_revenue(MultiAsset {
 					id: AssetId::Concrete(self.schedule_fee_location),
-					fun: Fungibility::Fungible(self.schedule_fee_amount.saturated_into()),
+					fun: Fungibility::Fungible(revenue.saturated_into()),
 				});
+				if !self.keeper_reward_amount.is_zero() {
+					T::MultiCurrency::deposit(
+						currency_id.into(),
+						&Pallet::<T>::keeper_pot_account(),
+						self.keeper_reward_amount,
+					)?;
+				}
 
 				if self.execution_fee_amount > MultiBalanceOf::<T>::zero() {
 					T::XcmpTransactor::pay_xcm_fee(
@@ -130,22 +198,27 @@ where
 		let schedule_fee_amount: u128 =
 			Pallet::<T>::calculate_schedule_fee_amount(action)?.saturated_into();
 
-		let execution_fee_amount = match action.clone() {
-			Action::XCMP { execution_fee, instruction_sequence, .. }
-				if instruction_sequence == InstructionSequence::PayThroughSovereignAccount =>
-				execution_fee.amount.saturated_into(),
-			_ => 0u32.saturated_into(),
-		};
-
 		Ok(Self {
 			owner: owner.clone(),
 			schedule_fee_location,
 			schedule_fee_amount: schedule_fee_amount.saturated_into(),
-			execution_fee_amount,
+			execution_fee_amount: Self::execution_fee_amount(action),
+			keeper_reward_amount: Pallet::<T>::calculate_keeper_reward_amount(action)?,
 			_phantom_data: Default::default(),
 		})
 	}
 
+	/// The execution fee paid up front, which only actions paying through the sovereign account
+	/// do.
+	fn execution_fee_amount(action: &ActionOf<T>) -> MultiBalanceOf<T> {
+		match action.clone() {
+			Action::XCMP { execution_fee, instruction_sequence, .. }
+				if instruction_sequence == InstructionSequence::PayThroughSovereignAccount =>
+				execution_fee.amount.saturated_into(),
+			_ => 0u32.saturated_into(),
+		}
+	}
+
 	/// Executes the fee handler
 	fn pay_fees(self) -> DispatchResult {
 		// This should never error if can_pay_fee passed.
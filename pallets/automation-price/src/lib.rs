@@ -37,13 +37,29 @@ pub use types::*;
 pub mod trigger;
 pub use trigger::*;
 
+pub mod aggregation;
+pub use aggregation::*;
+
+pub mod extension;
+pub use extension::PrioritizeOracleUpdates;
+
 mod fees;
+pub mod migrations;
+
+mod price_cache;
+pub use price_cache::PriceCache;
 
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
 mod tests;
 
+#[cfg(feature = "std")]
+pub mod simulation;
+
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+
 mod benchmarking;
 
 pub use fees::*;
@@ -54,21 +70,42 @@ use cumulus_primitives_core::InteriorMultiLocation;
 
 use cumulus_primitives_core::ParaId;
 use frame_support::{
-	pallet_prelude::*, traits::Currency, transactional,
+	dispatch::{GetDispatchInfo, PostDispatchInfo},
+	pallet_prelude::*,
+	storage::{with_transaction, TransactionOutcome},
+	traits::{
+		Contains, Currency, Imbalance, OnUnbalanced, OriginTrait, Randomness, ReservableCurrency,
+	},
+	transactional,
 	weights::constants::WEIGHT_REF_TIME_PER_SECOND,
+	PalletId,
 };
 use frame_system::pallet_prelude::*;
 use orml_traits::{FixedConversionRateProvider, MultiCurrency};
 use pallet_timestamp::{self as timestamp};
-use scale_info::{prelude::format, TypeInfo};
+use scale_info::TypeInfo;
+use sp_io::hashing::blake2_256;
 use sp_runtime::{
-	traits::{CheckedConversion, Convert, SaturatedConversion, Saturating},
+	traits::{
+		AccountIdConversion, CheckedConversion, Convert, Dispatchable, Hash, IdentifyAccount, One,
+		SaturatedConversion, Saturating, Verify, Zero,
+	},
 	ArithmeticError, Perbill,
 };
-use sp_std::{boxed::Box, collections::btree_map::BTreeMap, ops::Bound::Included, vec, vec::Vec};
+use sp_std::{
+	boxed::Box,
+	cmp::Reverse,
+	collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+	ops::Bound::Included,
+	vec,
+	vec::Vec,
+};
 
 pub use pallet_xcmp_handler::InstructionSequence;
-use primitives::EnsureProxy;
+use primitives::{
+	deposit::{hold_deposit, release_deposit},
+	EnsureProxy, OnTaskExecuted, StorageDeposit,
+};
 pub use weights::WeightInfo;
 
 use pallet_xcmp_handler::XcmpTransactor;
@@ -81,6 +118,9 @@ pub mod pallet {
 	pub type AccountOf<T> = <T as frame_system::Config>::AccountId;
 	pub type BalanceOf<T> =
 		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+	pub type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
+		<T as frame_system::Config>::AccountId,
+	>>::NegativeImbalance;
 	pub type MultiBalanceOf<T> = <<T as Config>::MultiCurrency as MultiCurrency<
 		<T as frame_system::Config>::AccountId,
 	>>::Balance;
@@ -91,7 +131,7 @@ pub mod pallet {
 	>>::CurrencyId;
 
 	type UnixTime = u64;
-	pub type TaskId = Vec<u8>;
+	pub type TaskId = [u8; 32];
 	pub type TaskAddress<T> = (AccountOf<T>, TaskId);
 	pub type TaskIdList<T> = Vec<TaskAddress<T>>;
 
@@ -101,7 +141,6 @@ pub mod pallet {
 	type AssetName = Vec<u8>;
 	type AssetPair = (AssetName, AssetName);
 	type AssetPrice = u128;
-	type TriggerFunction = Vec<u8>;
 
 	/// The struct that stores all information needed for a task.
 	#[derive(Debug, Eq, Encode, Decode, TypeInfo, Clone)]
@@ -156,9 +195,14 @@ pub mod pallet {
 		#[pallet::constant]
 		type MaxTasksOverall: Get<u32>;
 
-		/// The maximum weight per block.
+		/// The maximum number of tasks scheduled on an asset pair, so a single pair cannot take
+		/// the whole execution budget. `set_asset_task_cap` can give a pair a cap of its own.
 		#[pallet::constant]
-		type MaxBlockWeight: Get<u64>;
+		type MaxTasksPerAssetPair: Get<u32>;
+
+		/// The maximum weight per block, in compute time and proof size.
+		#[pallet::constant]
+		type MaxBlockWeight: Get<Weight>;
 
 		/// The maximum percentage of weight per block used for scheduled tasks.
 		#[pallet::constant]
@@ -168,7 +212,7 @@ pub mod pallet {
 		type ExecutionWeightFee: Get<BalanceOf<Self>>;
 
 		/// The Currency type for interacting with balances
-		type Currency: Currency<Self::AccountId>;
+		type Currency: ReservableCurrency<Self::AccountId>;
 
 		/// The MultiCurrency type for interacting with balances
 		type MultiCurrency: MultiCurrency<Self::AccountId>;
@@ -209,9 +253,153 @@ pub mod pallet {
 
 		/// Ensure proxy
 		type EnsureProxy: primitives::EnsureProxy<Self::AccountId>;
+
+		/// Accounts trusted to submit prices for every asset pair, on top of each
+		/// pair's own oracle providers.
+		type OracleCommittee: Contains<Self::AccountId>;
+
+		/// Notified of the outcome of every task execution.
+		type OnTaskExecuted: OnTaskExecuted;
+
+		/// The origin that can purge tasks in an emergency.
+		type PurgeOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// The origin that can halt and resume scheduling, execution and price ingestion.
+		type ShutdownOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// The origin that can add and remove the oracle providers of an asset pair.
+		type OracleProviderOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// The origin that can register asset pairs without holding a deposit, and delete any
+		/// asset pair. Other signed accounts can register asset pairs by holding `AssetDeposit`.
+		type AssetAdminOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// The deposit held from a signed account for each asset pair it registers.
+		#[pallet::constant]
+		type AssetDeposit: Get<BalanceOf<Self>>;
+
+		/// Receives the deposit of an asset pair deleted by the asset admin origin.
+		type AssetDepositSlash: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+		/// Receives the tips collected from tasks when they run.
+		type TipDestination: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+		/// The pallet's id, from which the account holding the rewards of keepers is derived.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// The part of the fee paid when a task is scheduled that is held for the keeper that
+		/// runs it.
+		#[pallet::constant]
+		type KeeperRewardShare: Get<Perbill>;
+
+		/// The maximum number of queued tasks a keeper can run in one call.
+		#[pallet::constant]
+		type MaxTasksPerKeeperCall: Get<u32>;
+
+		/// The maximum number of asset pairs in the registry.
+		#[pallet::constant]
+		type MaxAssets: Get<u32>;
+
+		/// The maximum number of tasks examined by a single `purge_tasks` call.
+		#[pallet::constant]
+		type MaxTasksPerPurge: Get<u32>;
+
+		/// The maximum number of tasks of one asset pair moved into the task queue in a block.
+		#[pallet::constant]
+		type MaxShiftedTasksPerAssetPair: Get<u32>;
+
+		/// The width of the price buckets the sorted task indexes of an asset pair are split
+		/// into, so that adding or triggering a task only decodes the tasks of nearby prices.
+		/// Changing it requires the sorted task indexes to be rebuilt.
+		#[pallet::constant]
+		type PriceBucketWidth: Get<AssetPrice>;
+
+		/// The number of times the message of an XCMP task is sent before the task is given up,
+		/// when the message cannot be sent to its destination.
+		#[pallet::constant]
+		type MaxXcmpSendAttempts: Get<u32>;
+
+		/// The number of blocks before the message of an XCMP task that could not be sent is sent
+		/// again. The delay doubles with every further attempt.
+		#[pallet::constant]
+		type XcmpRetryDelay: Get<Self::BlockNumber>;
+
+		/// The maximum number of XCMP tasks waiting to send their message again. A task whose
+		/// message cannot be sent while the queue is full is given up.
+		#[pallet::constant]
+		type MaxXcmpRetries: Get<u32>;
+
+		/// The deposit reserved from the owner of a task for as long as it stays in storage.
+		type StorageDeposit: StorageDeposit<BalanceOf<Self>>;
+
+		/// The priority `PrioritizeOracleUpdates` gives to price updates from their providers.
+		#[pallet::constant]
+		type OracleUpdatePriority: Get<TransactionPriority>;
+
+		/// Whether successful price updates are free for their providers.
+		#[pallet::constant]
+		type WaiveOracleFees: Get<bool>;
+
+		/// The signature of the payloads oracle providers submit unsigned.
+		type OracleSignature: Parameter + Verify<Signer = Self::OracleSigner>;
+
+		/// Identifies the oracle provider who signed a payload.
+		type OracleSigner: IdentifyAccount<AccountId = Self::AccountId>;
+
+		/// The number of blocks an oracle provider waits between two unsigned price submissions.
+		#[pallet::constant]
+		type UnsignedSubmissionInterval: Get<Self::BlockNumber>;
+
+		/// The number of blocks an oracle submission stays in `OracleSubmissions`.
+		#[pallet::constant]
+		type OracleSubmissionRetention: Get<Self::BlockNumber>;
+
+		/// The number of decimals of the fixed-point representation of `AssetPrice`. Prices are
+		/// normalized to it from the decimals their asset pair was registered with, so trigger
+		/// params are expressed in it as well.
+		#[pallet::constant]
+		type PriceDecimals: Get<u8>;
+
+		/// The number of past prices kept in `PriceHistory` for each asset pair.
+		#[pallet::constant]
+		type MaxPriceHistory: Get<u32>;
+
+		/// Decides which trigger functions tasks can use and when their conditions hold.
+		type ConditionEvaluator: ConditionEvaluator;
+
+		/// The maximum number of price conditions a task can combine with its own.
+		#[pallet::constant]
+		type MaxExtraConditions: Get<u32>;
+
+		/// The number of oracle providers that must submit a price for a round before the price
+		/// of the asset pair is updated.
+		#[pallet::constant]
+		type OracleQuorum: Get<u32>;
+
+		/// Combines the prices submitted for a round into the price of the asset pair.
+		type PriceAggregator: AggregatePrices;
+
+		/// The age in seconds after which the price of an asset pair is stale. Tasks are not
+		/// triggered by a stale price, they wait for a fresh one.
+		#[pallet::constant]
+		type MaxPriceStaleness: Get<u64>;
+
+		/// The source of the seed that shuffles the tasks sharing a trigger price.
+		type Randomness: Randomness<Self::Hash, Self::BlockNumber>;
+
+		/// The overarching call type.
+		type RuntimeCall: Parameter
+			+ Dispatchable<RuntimeOrigin = Self::RuntimeOrigin, PostInfo = PostDispatchInfo>
+			+ GetDispatchInfo
+			+ From<frame_system::Call<Self>>
+			+ IsType<<Self as frame_system::Config>::RuntimeCall>;
+
+		/// The calls that tasks can dispatch on this chain.
+		type ScheduleAllowList: Contains<<Self as frame_system::Config>::RuntimeCall>;
 	}
 
-	const STORAGE_VERSION: StorageVersion = StorageVersion::new(0);
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(9);
 
 	#[pallet::pallet]
 	#[pallet::without_storage_info]
@@ -228,8 +416,7 @@ pub mod pallet {
 		oracle_providers: Vec<AccountOf<T>>,
 	}
 
-	// TODO: Use a ring buffer to also store last n history data effectively
-	#[derive(Debug, Encode, Decode, TypeInfo)]
+	#[derive(Clone, Debug, Eq, PartialEq, Encode, Decode, TypeInfo, MaxEncodedLen)]
 	#[scale_info(skip_type_params(T))]
 	pub struct PriceData {
 		pub round: u128,
@@ -237,6 +424,46 @@ pub mod pallet {
 		pub value: u128,
 	}
 
+	/// The price of an asset pair submitted by an oracle provider in `update_asset_prices`.
+	#[derive(Clone, Debug, Eq, PartialEq, Encode, Decode, TypeInfo)]
+	pub struct PriceUpdate {
+		pub chain: ChainName,
+		pub exchange: Exchange,
+		pub asset1: AssetName,
+		pub asset2: AssetName,
+		/// The price of asset1, represented in asset2.
+		pub price: AssetPrice,
+		/// The time the oracle provider recognized the price at.
+		pub submitted_at: u128,
+		/// The round of the asset price being updated.
+		pub round: u128,
+	}
+
+	/// The metadata of an asset pair.
+	#[derive(Clone, Debug, Eq, PartialEq, Encode, Decode, TypeInfo)]
+	pub struct AssetMetadata {
+		/// The symbol the asset pair is displayed with.
+		pub symbol: Vec<u8>,
+		/// The number of decimals of the prices submitted for the asset pair.
+		pub decimal: u8,
+	}
+
+	/// The price updates an oracle provider signs to submit them in
+	/// `submit_asset_prices_unsigned`.
+	#[derive(Clone, Debug, Eq, PartialEq, Encode, Decode, TypeInfo)]
+	pub struct PricePayload<AccountId> {
+		/// The oracle provider who signed the payload.
+		pub provider: AccountId,
+		pub updates: Vec<PriceUpdate>,
+	}
+
+	/// The prices the oracle providers submitted for the latest round of an asset pair.
+	#[derive(Clone, Debug, Default, Eq, PartialEq, Encode, Decode, TypeInfo)]
+	pub struct SubmittedRound<AccountId> {
+		pub round: u128,
+		pub prices: Vec<(AccountId, AssetPrice)>,
+	}
+
 	// AssetRegistry holds information and metadata about the asset we support
 	#[pallet::storage]
 	#[pallet::getter(fn get_asset_registry_info)]
@@ -250,6 +477,105 @@ pub mod pallet {
 		RegistryInfo<T>,
 	>;
 
+	// The number of tasks scheduled on each asset pair that has any.
+	#[pallet::storage]
+	#[pallet::getter(fn get_asset_task_count)]
+	pub type AssetTaskCounts<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Twox64Concat, ChainName>,
+			NMapKey<Twox64Concat, Exchange>,
+			NMapKey<Twox64Concat, AssetPair>,
+		),
+		u32,
+		ValueQuery,
+	>;
+
+	// The task cap of each asset pair that does not use `MaxTasksPerAssetPair`.
+	#[pallet::storage]
+	#[pallet::getter(fn get_asset_task_cap)]
+	pub type AssetTaskCaps<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Twox64Concat, ChainName>,
+			NMapKey<Twox64Concat, Exchange>,
+			NMapKey<Twox64Concat, AssetPair>,
+		),
+		u32,
+	>;
+
+	// The circuit breaker of each asset pair that has one.
+	#[pallet::storage]
+	#[pallet::getter(fn get_circuit_breaker)]
+	pub type CircuitBreakers<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Twox64Concat, ChainName>,
+			NMapKey<Twox64Concat, Exchange>,
+			NMapKey<Twox64Concat, AssetPair>,
+		),
+		CircuitBreaker,
+	>;
+
+	// The asset pairs halted by their circuit breaker, with the time in seconds they were halted
+	// at. Their tasks are not triggered until they resume.
+	#[pallet::storage]
+	#[pallet::getter(fn get_halted_asset)]
+	pub type HaltedAssets<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Twox64Concat, ChainName>,
+			NMapKey<Twox64Concat, Exchange>,
+			NMapKey<Twox64Concat, AssetPair>,
+		),
+		u128,
+	>;
+
+	// The symbol each asset pair is displayed with, set by its owner.
+	#[pallet::storage]
+	#[pallet::getter(fn get_asset_symbol)]
+	pub type AssetSymbols<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Twox64Concat, ChainName>,
+			NMapKey<Twox64Concat, Exchange>,
+			NMapKey<Twox64Concat, AssetPair>,
+		),
+		Vec<u8>,
+	>;
+
+	// The account maintaining each asset pair of the registry. Asset pairs without an entry are
+	// maintained by governance.
+	#[pallet::storage]
+	#[pallet::getter(fn get_asset_ownership)]
+	pub type AssetOwners<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Twox64Concat, ChainName>,
+			NMapKey<Twox64Concat, Exchange>,
+			NMapKey<Twox64Concat, AssetPair>,
+		),
+		AssetOwnership<AccountOf<T>, BalanceOf<T>>,
+	>;
+
+	// The number of asset pairs in AssetRegistry.
+	#[pallet::storage]
+	#[pallet::getter(fn get_asset_count)]
+	pub type AssetCount<T> = StorageValue<_, u32, ValueQuery>;
+
+	// The owner an asset pair is being handed to, until they accept it.
+	#[pallet::storage]
+	#[pallet::getter(fn get_pending_asset_owner)]
+	pub type PendingAssetOwners<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Twox64Concat, ChainName>,
+			NMapKey<Twox64Concat, Exchange>,
+			NMapKey<Twox64Concat, AssetPair>,
+		),
+		AssetOwner<AccountOf<T>>,
+	>;
+
 	// PriceRegistry holds price only information for the asset we support
 	#[pallet::storage]
 	#[pallet::getter(fn get_asset_price_data)]
@@ -263,26 +589,146 @@ pub mod pallet {
 		PriceData,
 	>;
 
-	// SortedTasksIndex is our sorted by price task shard
-	// Each task for a given asset is organized into a BTreeMap
+	// The last `MaxPriceHistory` prices of each asset, oldest first. Once full, every new price
+	// pushes the oldest one out.
+	#[pallet::storage]
+	#[pallet::getter(fn get_price_history)]
+	pub type PriceHistory<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Twox64Concat, ChainName>,
+			NMapKey<Twox64Concat, Exchange>,
+			NMapKey<Twox64Concat, AssetPair>,
+		),
+		BoundedVec<PriceData, T::MaxPriceHistory>,
+		ValueQuery,
+	>;
+
+	// The prices submitted for the latest round of each asset. Once `OracleQuorum` providers
+	// submitted a price for the round, the aggregated price is written to PriceRegistry.
+	#[pallet::storage]
+	#[pallet::getter(fn get_round_prices)]
+	pub type RoundPrices<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Twox64Concat, ChainName>,
+			NMapKey<Twox64Concat, Exchange>,
+			NMapKey<Twox64Concat, AssetPair>,
+		),
+		SubmittedRound<AccountOf<T>>,
+	>;
+
+	// The block of the last unsigned price submission of each oracle provider.
+	#[pallet::storage]
+	#[pallet::getter(fn get_last_unsigned_submission)]
+	pub type LastUnsignedSubmissions<T: Config> =
+		StorageMap<_, Twox64Concat, AccountOf<T>, T::BlockNumber>;
+
+	// The last round each oracle provider submitted a price for, per asset. Rounds of a provider
+	// only go up, so a submission cannot be replayed.
+	#[pallet::storage]
+	#[pallet::getter(fn get_oracle_round)]
+	pub type OracleRounds<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Twox64Concat, ChainName>,
+			NMapKey<Twox64Concat, Exchange>,
+			NMapKey<Twox64Concat, AssetPair>,
+			NMapKey<Twox64Concat, AccountOf<T>>,
+		),
+		u128,
+	>;
+
+	// SortedTasksAbove and SortedTasksBelow are our sorted by price task shards, one for each
+	// direction a price can move in. The tasks of an asset are split by trigger price into buckets
+	// `PriceBucketWidth` wide, and each bucket is organized into a BTreeMap
 	// https://doc.rust-lang.org/std/collections/struct.BTreeMap.html#method.insert
 	// - key: Trigger Price
 	// - value: vector of task id
-	// TODO: move these to a trigger model
-	// TODO: handle task expiration
+	//
+	// SortedTasksAbove holds the `gt` tasks and is drained from the lowest trigger price up when
+	// the price rises. SortedTasksBelow holds the `lt` tasks and is drained from the highest
+	// trigger price down when the price falls.
+	#[pallet::storage]
+	pub type SortedTasksAbove<T> = StorageNMap<
+		_,
+		(
+			NMapKey<Twox64Concat, ChainName>,
+			NMapKey<Twox64Concat, Exchange>,
+			NMapKey<Twox64Concat, AssetPair>,
+			NMapKey<Twox64Concat, u128>,
+		),
+		BTreeMap<AssetPrice, TaskIdList<T>>,
+	>;
+
 	#[pallet::storage]
-	#[pallet::getter(fn get_sorted_tasks_index)]
-	pub type SortedTasksIndex<T> = StorageNMap<
+	pub type SortedTasksBelow<T> = StorageNMap<
 		_,
 		(
 			NMapKey<Twox64Concat, ChainName>,
 			NMapKey<Twox64Concat, Exchange>,
 			NMapKey<Twox64Concat, AssetPair>,
-			NMapKey<Twox64Concat, TriggerFunction>,
+			NMapKey<Twox64Concat, u128>,
 		),
 		BTreeMap<AssetPrice, TaskIdList<T>>,
 	>;
 
+	// The buckets of SortedTasksAbove and SortedTasksBelow that hold tasks of each asset, so that
+	// a price move only reads the buckets it crossed.
+	#[pallet::storage]
+	#[pallet::getter(fn get_sorted_buckets_above)]
+	pub type SortedBucketsAbove<T> = StorageNMap<
+		_,
+		(
+			NMapKey<Twox64Concat, ChainName>,
+			NMapKey<Twox64Concat, Exchange>,
+			NMapKey<Twox64Concat, AssetPair>,
+		),
+		BTreeSet<u128>,
+	>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_sorted_buckets_below)]
+	pub type SortedBucketsBelow<T> = StorageNMap<
+		_,
+		(
+			NMapKey<Twox64Concat, ChainName>,
+			NMapKey<Twox64Concat, Exchange>,
+			NMapKey<Twox64Concat, AssetPair>,
+		),
+		BTreeSet<u128>,
+	>;
+
+	// The tasks of each asset whose trigger function is neither `gt` nor `lt`. They have no single
+	// target price to be sorted by, so they are checked one by one whenever the price moves.
+	#[pallet::storage]
+	#[pallet::getter(fn get_unsorted_tasks)]
+	pub type UnsortedTasks<T> = StorageNMap<
+		_,
+		(
+			NMapKey<Twox64Concat, ChainName>,
+			NMapKey<Twox64Concat, Exchange>,
+			NMapKey<Twox64Concat, AssetPair>,
+		),
+		TaskIdList<T>,
+	>;
+
+	// The price of each asset when its sorted tasks were last shifted into the TaskQueue. A price
+	// that has not moved since leaves both sides alone, a rise only touches SortedTasksAbove and a
+	// fall only touches SortedTasksBelow. Scheduling a task clears the entry so that the next
+	// shift checks both sides.
+	#[pallet::storage]
+	#[pallet::getter(fn get_last_shifted_price)]
+	pub type LastShiftedPrice<T> = StorageNMap<
+		_,
+		(
+			NMapKey<Twox64Concat, ChainName>,
+			NMapKey<Twox64Concat, Exchange>,
+			NMapKey<Twox64Concat, AssetPair>,
+		),
+		AssetPrice,
+	>;
+
 	// SortedTasksByExpiration is our expiration sorted tasks
 	#[pallet::type_value]
 	pub fn DefaultSortedTasksByExpiration<T: Config>(
@@ -334,46 +780,180 @@ pub mod pallet {
 	#[pallet::getter(fn get_task_queue)]
 	pub type TaskQueue<T: Config> = StorageValue<_, TaskIdList<T>, ValueQuery>;
 
+	// The tasks that missed their price condition and run late, once TaskQueue is empty.
 	#[pallet::storage]
-	#[pallet::getter(fn is_shutdown)]
-	pub type Shutdown<T: Config> = StorageValue<_, bool, ValueQuery>;
+	#[pallet::getter(fn get_missed_tasks_queue)]
+	pub type MissedTasksQueue<T: Config> = StorageValue<_, TaskIdList<T>, ValueQuery>;
 
-	#[pallet::error]
-	pub enum Error<T> {
-		InvalidTaskId,
-		/// Duplicate task
-		DuplicateTask,
+	// The parts of the pallet that are currently halted.
+	#[pallet::storage]
+	#[pallet::getter(fn get_shutdown_flags)]
+	pub type Shutdown<T: Config> = StorageValue<_, ShutdownFlags, ValueQuery>;
 
-		/// Non existent asset
-		AssetNotSupported,
-		AssetNotInitialized,
-		/// Asset already supported
-		AssetAlreadySupported,
-		AssetAlreadyInitialized,
-		/// Asset cannot be updated by this account
-		InvalidAssetSudo,
-		OracleNotAuthorized,
-		/// Asset must be in triggerable range.
-		AssetNotInTriggerableRange,
-		AssetUpdatePayloadMalform,
-		/// Block Time not set
-		BlockTimeNotSet,
-		/// Invalid Expiration Window for new asset
-		InvalidAssetExpirationWindow,
-		/// Maximum tasks reached for the slot
-		MaxTasksReached,
-		/// Maximum tasks reached for a given account
-		MaxTasksPerAccountReached,
-		/// Failed to insert task
-		TaskInsertionFailure,
-		/// Failed to remove task
-		TaskRemoveFailure,
-		/// Task Not Found When canceling
-		TaskNotFound,
-		/// Error when setting task expired less than the current block time
-		InvalidTaskExpiredAt,
-		/// Error when failed to update task expiration storage
-		TaskExpiredStorageFailedToUpdate,
+	// IndexMigration is set while the sorted task indexes are being rebuilt from Tasks across
+	// several blocks. Tasks are not triggered until the rebuild completes.
+	#[pallet::storage]
+	#[pallet::getter(fn get_index_migration)]
+	pub type IndexMigration<T: Config> = StorageValue<_, IndexMigrationStage>;
+
+	// Where the shift of matched tasks stopped when its block ran out of weight, as the index and
+	// the asset pair it checked last. The next block resumes after it, so every asset pair is
+	// checked in turn however many of them have tasks.
+	#[pallet::storage]
+	#[pallet::getter(fn get_shift_cursor)]
+	pub type ShiftCursor<T: Config> =
+		StorageValue<_, (ShiftIndex, (ChainName, Exchange, AssetPair))>;
+
+	// Where an unfinished purge stopped, as the raw key of the last task it examined, so the next
+	// `purge_tasks` call with the same filter picks up from there.
+	#[pallet::storage]
+	#[pallet::getter(fn get_purge_cursor)]
+	pub type PurgeCursors<T: Config> = StorageMap<_, Blake2_128Concat, PurgeFilter, Vec<u8>>;
+
+	// Deposits held for tasks, keyed by the task's owner and id. They outlive the task and are
+	// released with `reclaim_deposit`.
+	#[pallet::storage]
+	#[pallet::getter(fn get_task_deposit)]
+	pub type TaskDeposits<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, AccountOf<T>, Twox64Concat, TaskId, BalanceOf<T>>;
+
+	// The schedule of the recurring tasks, keyed by the task's owner and id. Tasks without an
+	// entry run once.
+	#[pallet::storage]
+	#[pallet::getter(fn get_task_recurrence)]
+	pub type TaskRecurrences<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, AccountOf<T>, Twox64Concat, TaskId, Recurrence>;
+
+	// What happens to each task when it misses its price condition, keyed by the task's owner and
+	// id. Tasks without an entry are skipped.
+	#[pallet::storage]
+	#[pallet::getter(fn get_missed_task_policy)]
+	pub type MissedTaskPolicies<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		AccountOf<T>,
+		Twox64Concat,
+		TaskId,
+		MissedTaskPolicy,
+		ValueQuery,
+	>;
+
+	// The tasks paused by their owner, keyed by the task's owner and id. A paused task stays
+	// scheduled, but is kept out of the sorted task index until it is resumed.
+	#[pallet::storage]
+	#[pallet::getter(fn is_task_paused)]
+	pub type PausedTasks<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, AccountOf<T>, Twox64Concat, TaskId, bool, ValueQuery>;
+
+	// The time of day each task can run in, keyed by the task's owner and id. Tasks without an
+	// entry run whenever they are triggered.
+	#[pallet::storage]
+	#[pallet::getter(fn get_task_time_window)]
+	pub type TaskTimeWindows<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, AccountOf<T>, Twox64Concat, TaskId, TimeWindow>;
+
+	// The price conditions each task combines with its own, keyed by the task's owner and id. A
+	// task with extra conditions waits in the unsorted tasks of every asset pair it refers to, so
+	// it is checked again whenever one of their prices moves.
+	#[pallet::storage]
+	#[pallet::getter(fn get_task_conditions)]
+	pub type TaskConditions<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, AccountOf<T>, Twox64Concat, TaskId, CompoundCondition>;
+
+	// The tip held from the owner of each task until it runs, keyed by the task's owner and id.
+	// Of the tasks triggered together, those with a higher tip run first.
+	#[pallet::storage]
+	#[pallet::getter(fn get_task_tip)]
+	pub type TaskTips<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, AccountOf<T>, Twox64Concat, TaskId, BalanceOf<T>>;
+
+	// The part of the fees of each task held for the keeper that runs it, in the asset the task
+	// pays its fees in. The funds are held in the keeper pot account.
+	#[pallet::storage]
+	#[pallet::getter(fn get_keeper_reward)]
+	pub type KeeperRewards<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, AccountOf<T>, Twox64Concat, TaskId, MultiBalanceOf<T>>;
+
+	// The tasks kept out of the sorted task index until a given time, by the time they are armed
+	// again: recurring tasks that ran, until their cooldown has passed, and tasks triggered outside
+	// of their time window, until it opens.
+	#[pallet::storage]
+	#[pallet::getter(fn get_cooling_down_tasks)]
+	pub type CoolingDownTasks<T: Config> =
+		StorageValue<_, BTreeMap<u128, TaskIdList<T>>, ValueQuery>;
+
+	// The other leg of a bracket task, keyed by the task's owner and id. Once either leg runs,
+	// the other one is cancelled.
+	#[pallet::storage]
+	#[pallet::getter(fn get_linked_task)]
+	pub type LinkedTasks<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, AccountOf<T>, Twox64Concat, TaskId, TaskId>;
+
+	// Every oracle price submission, accepted or rejected, by the block it was made in. Entries
+	// older than `OracleSubmissionRetention` blocks are pruned in `on_initialize`.
+	#[pallet::storage]
+	#[pallet::getter(fn get_oracle_submissions)]
+	pub type OracleSubmissions<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		Vec<OracleSubmission<AccountOf<T>>>,
+		ValueQuery,
+	>;
+
+	// The XCMP tasks whose message could not be sent to its destination, by the block the message
+	// is sent again in.
+	#[pallet::storage]
+	#[pallet::getter(fn get_xcmp_retries)]
+	pub type XcmpRetryQueue<T: Config> =
+		StorageMap<_, Twox64Concat, T::BlockNumber, Vec<XcmpRetry<AccountOf<T>>>, ValueQuery>;
+
+	// The number of tasks waiting in XcmpRetryQueue, bounded by `MaxXcmpRetries`.
+	#[pallet::storage]
+	#[pallet::getter(fn get_xcmp_retry_count)]
+	pub type XcmpRetryCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	#[pallet::error]
+	pub enum Error<T> {
+		InvalidTaskId,
+		/// Duplicate task
+		DuplicateTask,
+
+		/// Non existent asset
+		AssetNotSupported,
+		AssetNotInitialized,
+		/// Asset already supported
+		AssetAlreadySupported,
+		AssetAlreadyInitialized,
+		/// Asset cannot be updated by this account
+		InvalidAssetSudo,
+		OracleNotAuthorized,
+		/// Asset must be in triggerable range.
+		AssetNotInTriggerableRange,
+		/// The batch of price updates is empty
+		AssetUpdatePayloadMalform,
+		/// A price update carries a zero price, or one that is zero once normalized
+		InvalidPrice,
+		/// Block Time not set
+		BlockTimeNotSet,
+		/// Invalid Expiration Window for new asset
+		InvalidAssetExpirationWindow,
+		/// Maximum tasks reached for the slot
+		MaxTasksReached,
+		/// Maximum tasks reached for a given account
+		MaxTasksPerAccountReached,
+		/// Maximum tasks reached for the asset pair
+		MaxTasksPerAssetPairReached,
+		/// Failed to insert task
+		TaskInsertionFailure,
+		/// Failed to remove task
+		TaskRemoveFailure,
+		/// Task Not Found When canceling
+		TaskNotFound,
+		/// Error when setting task expired less than the current block time
+		InvalidTaskExpiredAt,
+		/// Error when failed to update task expiration storage
+		TaskExpiredStorageFailedToUpdate,
 		/// Insufficient Balance
 		InsufficientBalance,
 		/// Restrictions on Liquidity in Account
@@ -387,6 +967,55 @@ pub mod pallet {
 		/// The version of the `VersionedMultiLocation` value used is not able
 		/// to be interpreted.
 		BadVersion,
+		/// A purge cannot examine more than `MaxTasksPerPurge` tasks.
+		PurgeBatchTooLarge,
+		/// The round of a price is older than the round of the current price of the asset.
+		StaleRound,
+		/// The oracle provider already submitted a price for this or a later round of the asset.
+		RoundAlreadySubmitted,
+		/// An asset with more than `MaxTasksPerPurge` tasks cannot be deleted before its tasks
+		/// are purged.
+		TooManyTasksToDelete,
+		/// No deposit is held for the task.
+		DepositNotFound,
+		/// The deposit cannot be reclaimed while the task is still scheduled.
+		TaskStillScheduled,
+		/// Scheduling is halted.
+		SchedulingHalted,
+		/// Price ingestion is halted.
+		PriceIngestionHalted,
+		/// The trigger function is not supported, or the trigger params do not fit it.
+		InvalidTriggerParams,
+		/// The origin is not the owner of the asset pair.
+		NotAssetOwner,
+		/// No ownership transfer of the asset pair is waiting to be accepted.
+		NoPendingAssetTransfer,
+		/// A recurring schedule needs a cooldown and at least one execution.
+		InvalidSchedule,
+		/// The call of the task cannot be decoded.
+		CallCannotBeDecoded,
+		/// The stop loss price of a bracket must be below its take profit price.
+		InvalidBracket,
+		/// The account already provides prices for the asset pair.
+		OracleProviderAlreadyAdded,
+		/// The account does not provide prices for the asset pair.
+		OracleProviderNotFound,
+		/// The asset pair is not halted by its circuit breaker.
+		AssetNotHalted,
+		/// The task is already paused.
+		TaskAlreadyPaused,
+		/// The task is not paused.
+		TaskNotPaused,
+		/// The time window is empty or does not fit in a day.
+		InvalidTimeWindow,
+		/// A task can combine its price condition with one to `MaxExtraConditions` conditions.
+		InvalidConditionCount,
+		/// Task execution is halted.
+		ExecutionHalted,
+		/// A keeper cannot run more than `MaxTasksPerKeeperCall` tasks in one call.
+		KeeperBatchTooLarge,
+		/// No task is waiting in the task queue.
+		NoTasksReady,
 	}
 
 	/// This is a event helper struct to help us making sense of the chain state and surrounded
@@ -431,19 +1060,32 @@ pub mod pallet {
 			owner_id: AccountOf<T>,
 			task_id: TaskId,
 			schedule_as: Option<AccountOf<T>>,
+			chain: ChainName,
+			exchange: Exchange,
+			asset_pair: AssetPair,
+			trigger_function: Vec<u8>,
+			trigger_params: Vec<u128>,
+			action_kind: ActionKind,
+			destination: MultiLocation,
+			expired_at: u128,
 		},
-		// an event when we're about to run the task
+		/// The condition of a task matched and its action is about to run. The task was
+		/// triggered at `trigger_price`, the price of its asset pair as of `oracle_round`.
 		TaskTriggered {
 			owner_id: AccountOf<T>,
 			task_id: TaskId,
+			asset_pair: AssetPair,
+			trigger_price: AssetPrice,
+			oracle_round: u128,
 			condition: TaskCondition,
 		},
-		// An event when the task ran succesfully
+		/// The action of a task ran successfully.
 		TaskExecuted {
 			owner_id: AccountOf<T>,
 			task_id: TaskId,
+			action_outcome: ActionOutcome,
 		},
-		// An event when the task is trigger, ran but result in an error
+		/// The action of a task ran but resulted in `error`.
 		TaskExecutionFailed {
 			owner_id: AccountOf<T>,
 			task_id: TaskId,
@@ -454,16 +1096,36 @@ pub mod pallet {
 			owner_id: AccountOf<T>,
 			task_id: TaskId,
 		},
+		/// The tasks of an asset pair run in this block were settled together. All of them were
+		/// checked against `price`.
+		TasksSettled {
+			chain: ChainName,
+			exchange: Exchange,
+			asset_pair: AssetPair,
+			price: AssetPrice,
+			tasks: TaskIdList<T>,
+		},
 		// An event when the task is cancelled, either by owner or by root
 		TaskCancelled {
 			owner_id: AccountOf<T>,
 			task_id: TaskId,
 		},
+		/// The trigger params of a task were changed by its owner.
+		TaskTriggerUpdated {
+			owner_id: AccountOf<T>,
+			task_id: TaskId,
+			trigger_params: Vec<u128>,
+		},
 		// An event whenever we expect a task but cannot find it
 		TaskNotFound {
 			owner_id: AccountOf<T>,
 			task_id: TaskId,
 		},
+		/// A stored task could not be decoded and was removed.
+		TaskUndecodable {
+			owner_id: AccountOf<T>,
+			task_id: TaskId,
+		},
 		// An event when we are about to run task, but the task has expired right before
 		// it's actually run
 		TaskExpired {
@@ -507,24 +1169,312 @@ pub mod pallet {
 			asset1: AssetName,
 			asset2: AssetName,
 		},
+		/// The deposit of an asset pair deleted by the asset admin origin was slashed.
+		AssetDepositSlashed {
+			chain: ChainName,
+			exchange: Exchange,
+			asset1: AssetName,
+			asset2: AssetName,
+			owner: AccountOf<T>,
+			amount: BalanceOf<T>,
+		},
+		/// The asset pair holds as many tasks as its cap allows. No more tasks can be scheduled
+		/// on it until some of them are gone, or its cap is raised.
+		AssetTaskCapReached {
+			chain: ChainName,
+			exchange: Exchange,
+			asset1: AssetName,
+			asset2: AssetName,
+			cap: u32,
+		},
+		/// The task cap of an asset pair was changed, or set back to `MaxTasksPerAssetPair` if
+		/// `cap` is `None`.
+		AssetTaskCapUpdated {
+			chain: ChainName,
+			exchange: Exchange,
+			asset1: AssetName,
+			asset2: AssetName,
+			cap: Option<u32>,
+		},
+		/// The owner of an asset pair offered it to `new_owner`.
+		AssetOwnershipTransferProposed {
+			chain: ChainName,
+			exchange: Exchange,
+			asset1: AssetName,
+			asset2: AssetName,
+			new_owner: AssetOwner<AccountOf<T>>,
+		},
+		/// `owner` accepted an asset pair and now maintains it.
+		AssetOwnershipTransferred {
+			chain: ChainName,
+			exchange: Exchange,
+			asset1: AssetName,
+			asset2: AssetName,
+			owner: AssetOwner<AccountOf<T>>,
+		},
+		/// A block's worth of the sorted task index migration was processed.
+		IndexMigrationProgressed {
+			migrated: u32,
+		},
+		/// The sorted task index migration finished and task triggering resumed.
+		IndexMigrationCompleted {
+			migrated: u32,
+		},
+		/// A batch of tasks matching `filter` was cancelled. When `complete` is false, more tasks
+		/// remain to be examined.
+		TasksPurged {
+			filter: PurgeFilter,
+			purged: u32,
+			complete: bool,
+		},
+		/// The execution fee paid for a cancelled task was returned to its owner.
+		ExecutionFeeRefunded {
+			owner_id: AccountOf<T>,
+			task_id: TaskId,
+			amount: MultiBalanceOf<T>,
+		},
+		/// The deposit of a removed task was returned to its owner.
+		DepositReclaimed {
+			owner_id: AccountOf<T>,
+			task_id: TaskId,
+			amount: BalanceOf<T>,
+		},
+		/// The parts of the pallet that are halted changed.
+		ShutdownFlagsUpdated {
+			flags: ShutdownFlags,
+		},
+		/// The owner of a task changed how often it runs.
+		TaskScheduleUpdated {
+			owner_id: AccountOf<T>,
+			task_id: TaskId,
+			schedule: Schedule,
+		},
+		/// A recurring task ran and was charged for its next run. It is armed again at
+		/// `armed_at`.
+		TaskRearmed {
+			owner_id: AccountOf<T>,
+			task_id: TaskId,
+			armed_at: u128,
+		},
+		/// A stop loss and a take profit task were scheduled as a bracket. Once either of them
+		/// runs, the other one is cancelled.
+		BracketScheduled {
+			owner_id: AccountOf<T>,
+			stop_loss_task_id: TaskId,
+			take_profit_task_id: TaskId,
+		},
+		/// The price of an asset pair was last updated at `updated_at`, too long ago to trigger
+		/// its tasks.
+		PriceStale {
+			chain: ChainName,
+			exchange: Exchange,
+			asset_pair: AssetPair,
+			updated_at: u128,
+		},
+		/// A price update of a batch was rejected, the other updates of the batch are still
+		/// processed.
+		PriceUpdateRejected {
+			owner_id: AccountOf<T>,
+			chain: ChainName,
+			exchange: Exchange,
+			asset1: AssetName,
+			asset2: AssetName,
+			round: u128,
+			error: DispatchError,
+		},
+		/// `provider` can now push the price of an asset pair.
+		OracleProviderAdded {
+			chain: ChainName,
+			exchange: Exchange,
+			asset1: AssetName,
+			asset2: AssetName,
+			provider: AccountOf<T>,
+		},
+		/// `provider` can no longer push the price of an asset pair.
+		OracleProviderRemoved {
+			chain: ChainName,
+			exchange: Exchange,
+			asset1: AssetName,
+			asset2: AssetName,
+			provider: AccountOf<T>,
+		},
+		/// The owner of an asset pair changed its symbol.
+		AssetSymbolUpdated {
+			chain: ChainName,
+			exchange: Exchange,
+			asset1: AssetName,
+			asset2: AssetName,
+			symbol: Vec<u8>,
+		},
+		/// The owner of an asset pair set or removed its circuit breaker.
+		CircuitBreakerUpdated {
+			chain: ChainName,
+			exchange: Exchange,
+			asset1: AssetName,
+			asset2: AssetName,
+			circuit_breaker: Option<CircuitBreaker>,
+		},
+		/// The price of an asset pair moved from `previous_price` to `price`, further than its
+		/// circuit breaker allows. Its tasks are not triggered until it resumes.
+		PriceCircuitBroken {
+			chain: ChainName,
+			exchange: Exchange,
+			asset_pair: AssetPair,
+			previous_price: AssetPrice,
+			price: AssetPrice,
+		},
+		/// The tasks of an asset pair halted by its circuit breaker can be triggered again.
+		AssetResumed {
+			chain: ChainName,
+			exchange: Exchange,
+			asset_pair: AssetPair,
+		},
+		/// The owner of a task changed what happens when it misses its price condition.
+		MissedTaskPolicyUpdated {
+			owner_id: AccountOf<T>,
+			task_id: TaskId,
+			policy: MissedTaskPolicy,
+		},
+		/// A triggered task could not run before its price condition stopped matching, and was
+		/// handled according to `policy`.
+		TaskMissed {
+			owner_id: AccountOf<T>,
+			task_id: TaskId,
+			condition: TaskCondition,
+			policy: MissedTaskPolicy,
+		},
+		/// The message of an XCMP task could not be sent to its destination after `attempts`
+		/// attempts, and is sent again in block `retry_at`.
+		TaskXcmpSendDeferred {
+			owner_id: AccountOf<T>,
+			task_id: TaskId,
+			attempts: u32,
+			retry_at: T::BlockNumber,
+			error: DispatchError,
+		},
+		/// The message of an XCMP task could not be sent to its destination after `attempts`
+		/// attempts, and the task was given up. The execution fee of the run is refunded.
+		TaskXcmpSendFailed {
+			owner_id: AccountOf<T>,
+			task_id: TaskId,
+			attempts: u32,
+			error: DispatchError,
+		},
+		/// The owner of a task paused it.
+		TaskPaused {
+			owner_id: AccountOf<T>,
+			task_id: TaskId,
+		},
+		/// The owner of a task resumed it.
+		TaskResumed {
+			owner_id: AccountOf<T>,
+			task_id: TaskId,
+		},
+		/// The owner of a task changed the time of day it can run in.
+		TaskTimeWindowUpdated {
+			owner_id: AccountOf<T>,
+			task_id: TaskId,
+			window: Option<TimeWindow>,
+		},
+		/// A task was triggered outside of its time window. It is put back into the sorted task
+		/// index at `resume_at`, when the window opens.
+		TaskDeferred {
+			owner_id: AccountOf<T>,
+			task_id: TaskId,
+			resume_at: u128,
+		},
+		/// The owner of a task changed the price conditions it combines with its own.
+		TaskConditionsUpdated {
+			owner_id: AccountOf<T>,
+			task_id: TaskId,
+			conditions: Option<CompoundCondition>,
+		},
+		/// The owner of a task changed the tip held for it.
+		TaskTipUpdated {
+			owner_id: AccountOf<T>,
+			task_id: TaskId,
+			tip: BalanceOf<T>,
+		},
+		/// The tip of a task was collected when it ran.
+		TaskTipCollected {
+			owner_id: AccountOf<T>,
+			task_id: TaskId,
+			amount: BalanceOf<T>,
+		},
+		/// A keeper ran a task from the task queue and was paid the reward held for it.
+		KeeperRewarded {
+			keeper: AccountOf<T>,
+			owner_id: AccountOf<T>,
+			task_id: TaskId,
+			amount: MultiBalanceOf<T>,
+		},
 	}
 
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
-		fn on_initialize(_: T::BlockNumber) -> Weight {
-			if Self::is_shutdown() {
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let prune_weight = Self::prune_oracle_submissions(now);
+
+			if Self::get_shutdown_flags().execution {
+				return prune_weight.saturating_add(T::DbWeight::get().reads(1u64))
+			}
+
+			let max_weight: Weight = T::MaxWeightPercentage::get() * T::MaxBlockWeight::get();
+
+			if let Some(stage) = Self::get_index_migration() {
+				return prune_weight
+					.saturating_add(Self::migrate_sorted_tasks_index(stage, max_weight))
+			}
+
+			// Expired tasks are swept with whatever the triggered tasks left, so they are removed
+			// and refunded even on blocks that have no idle weight
+			let trigger_weight = Self::trigger_tasks(max_weight);
+			let sweep_limit = max_weight.saturating_sub(trigger_weight);
+			let sweep_weight = sweep_limit.saturating_sub(Self::sweep_expired_task(sweep_limit));
+
+			prune_weight.saturating_add(trigger_weight).saturating_add(sweep_weight)
+		}
+
+		fn on_idle(_: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			remaining_weight.saturating_sub(Self::sweep_expired_task(remaining_weight))
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn try_state(_: T::BlockNumber) -> Result<(), &'static str> {
+			Self::do_try_state()
+		}
+
+		// Runs after the runtime's migrations. Storage left behind the code may not decode, so
+		// task execution is halted until it is migrated.
+		fn on_runtime_upgrade() -> Weight {
+			let on_chain_version = Self::on_chain_storage_version();
+			if on_chain_version >= STORAGE_VERSION {
 				return T::DbWeight::get().reads(1u64)
 			}
 
-			let max_weight: Weight = Weight::from_parts(
-				T::MaxWeightPercentage::get().mul_floor(T::MaxBlockWeight::get()),
-				0,
+			log::error!(
+				target: "runtime::automation-price",
+				"Storage is at {:?} while the code expects {:?}, halting task execution",
+				on_chain_version,
+				STORAGE_VERSION
 			);
-			Self::trigger_tasks(max_weight)
+			let flags = ShutdownFlags { execution: true, ..Self::get_shutdown_flags() };
+			Shutdown::<T>::put(flags);
+			Self::deposit_event(Event::ShutdownFlagsUpdated { flags });
+			T::DbWeight::get().reads_writes(2u64, 1u64)
 		}
+	}
 
-		fn on_idle(_: T::BlockNumber, remaining_weight: Weight) -> Weight {
-			Self::sweep_expired_task(remaining_weight)
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			if let Call::submit_asset_prices_unsigned { payload, signature } = call {
+				Self::validate_price_payload(payload, signature)
+			} else {
+				InvalidTransaction::Call.into()
+			}
 		}
 	}
 
@@ -532,17 +1482,19 @@ pub mod pallet {
 	impl<T: Config> Pallet<T> {
 		/// Initialize an asset
 		///
-		/// Add a new asset
+		/// Add a new asset pair to the registry. The asset admin origin registers it for
+		/// governance. Any other signed account holds `AssetDeposit` for it and becomes its owner,
+		/// until the asset pair is deleted.
 		///
 		/// # Parameters
-		/// * `asset`: asset type
-		/// * `target_price`: baseline price of the asset
-		/// * `upper_bound`: TBD - highest executable percentage increase for asset
-		/// * `lower_bound`: TBD - highest executable percentage decrease for asset
-		/// * `asset_owner`: owner of the asset
-		/// * `expiration_period`: how frequently the tasks for an asset should expire
+		/// * `chain`, `exchange`, `asset1`, `asset2`: The asset pair.
+		/// * `decimal`: The number of decimals of the prices submitted for the asset pair.
+		/// * `asset_owners`: The oracle providers of the asset pair.
 		///
 		/// # Errors
+		/// * `AssetAlreadyInitialized`: The asset pair is already in the registry.
+		/// * `AssetLimitReached`: The registry already holds `MaxAssets` asset pairs.
+		/// * `InsufficientBalance`: The account cannot hold `AssetDeposit`.
 		#[pallet::call_index(1)]
 		#[pallet::weight(<T as Config>::WeightInfo::initialize_asset_extrinsic(asset_owners.len() as u32))]
 		#[transactional]
@@ -555,11 +1507,15 @@ pub mod pallet {
 			decimal: u8,
 			asset_owners: Vec<AccountOf<T>>,
 		) -> DispatchResult {
-			// TODO: use sudo and remove this feature flag
-			// TODO: needs fees if opened up to non-sudo
-			// When enable dev-queue, we skip this check
-			#[cfg(not(feature = "dev-queue"))]
-			ensure_root(origin)?;
+			if let Some(owner) = Self::ensure_asset_admin_or_signed(origin)? {
+				let deposit = T::AssetDeposit::get();
+				T::Currency::reserve(&owner, deposit)
+					.map_err(|_| Error::<T>::InsufficientBalance)?;
+				AssetOwners::<T>::insert(
+					(&chain, &exchange, (&asset1, &asset2)),
+					AssetOwnership { owner, deposit },
+				);
+			}
 
 			Self::create_new_asset(chain, exchange, asset1, asset2, decimal, asset_owners)?;
 
@@ -571,111 +1527,55 @@ pub mod pallet {
 		/// Only authorized origin can update the price. The authorized origin is set when
 		/// initializing an asset.
 		///
-		/// An asset is identified by this tuple: (chain, exchange, (asset1, asset2)).
-		///
-		/// To support updating multiple pairs, each element of the tuple become a separate
-		/// argument to this function, where as each of these argument is a vector.
-		///
-		/// Every element of each vector arguments, in the same position in the vector form the
-		/// above tuple.
+		/// Every submission is recorded in `OracleSubmissions`. One for an asset that is not
+		/// initialized, or that the origin is not authorized for, is recorded as rejected and
+		/// leaves the price untouched, without failing the rest of the batch. A rejected
+		/// submission is also reported with a `PriceUpdateRejected` event.
 		///
 		/// # Parameters
-		/// * `chains`: a vector of chain names
-		/// * `exchange`: a vector of  exchange name
-		/// * `asset1`: a vector of asset1 name
-		/// * `asset2`: a vector of asset2 name
-		/// * `prices`: a vector of price of asset1, re-present in asset2
-		/// * `submitted_at`: a vector of epoch. This epoch is the time when the price is recognized from the oracle provider
-		/// * `rounds`: a number to re-present which round of the asset price we're updating.  Unused internally
+		/// * `updates`: the price updates. An asset pair is identified by the chain, exchange,
+		///   asset1 and asset2 of an update. A price of zero, a price older than the round of the
+		///   current price, or of a round the origin already submitted for the asset, is rejected.
+		///
+		/// # Errors
+		/// * `AssetUpdatePayloadMalform`: The batch is empty.
 		#[pallet::call_index(2)]
-		#[pallet::weight(<T as Config>::WeightInfo::asset_price_update_extrinsic(assets1.len() as u32))]
+		#[pallet::weight(<T as Config>::WeightInfo::asset_price_update_extrinsic(updates.len() as u32))]
 		#[transactional]
 		pub fn update_asset_prices(
 			origin: OriginFor<T>,
-			chains: Vec<ChainName>,
-			exchanges: Vec<Exchange>,
-			assets1: Vec<AssetName>,
-			assets2: Vec<AssetName>,
-			prices: Vec<AssetPrice>,
-			submitted_at: Vec<u128>,
-			rounds: Vec<u128>,
-		) -> DispatchResult {
+			updates: Vec<PriceUpdate>,
+		) -> DispatchResultWithPostInfo {
 			let owner_id = ensure_signed(origin)?;
+			let all_accepted = Self::ingest_prices(&owner_id, updates)?;
 
-			let current_block_time = Self::get_current_block_time();
-			if current_block_time.is_err() {
-				Err(Error::<T>::BlockTimeNotSet)?
-			}
-
-			let now = current_block_time.unwrap() as u128;
-
-			if !(chains.len() == exchanges.len() &&
-				exchanges.len() == assets1.len() &&
-				assets1.len() == assets2.len() &&
-				assets2.len() == prices.len() &&
-				prices.len() == submitted_at.len() &&
-				submitted_at.len() == rounds.len())
-			{
-				Err(Error::<T>::AssetUpdatePayloadMalform)?
+			// Rejected submissions are only logged, and their submitter pays for that.
+			if all_accepted && T::WaiveOracleFees::get() {
+				Ok(Pays::No.into())
+			} else {
+				Ok(().into())
 			}
+		}
 
-			for (index, price) in prices.clone().iter().enumerate() {
-				let index: usize = index.try_into().unwrap();
-
-				let chain = chains[index].clone();
-				let exchange = exchanges[index].clone();
-				let asset1 = assets1[index].clone();
-				let asset2 = assets2[index].clone();
-				let round = rounds[index];
-
-				let key = (&chain, &exchange, (&asset1, &asset2));
-
-				if !AssetRegistry::<T>::contains_key(&key) {
-					Err(Error::<T>::AssetNotInitialized)?
-				}
-
-				if let Some(asset_registry) = Self::get_asset_registry_info(key) {
-					let allow_wallets: Vec<AccountOf<T>> = asset_registry.oracle_providers;
-					if !allow_wallets.contains(&owner_id) {
-						Err(Error::<T>::OracleNotAuthorized)?
-					}
-
-					// TODO: Eventually we will need to handle submitted_at and round properly when
-					// we had more than one oracle
-					// Currently not doing that check for the simplicity shake of interface
-					let this_round = match Self::get_asset_price_data(key) {
-						Some(previous_price) => previous_price.round + 1,
-						None => round,
-					};
-
-					PriceRegistry::<T>::insert(
-						&key,
-						PriceData { round: this_round, updated_at: now, value: *price },
-					);
-
-					Self::deposit_event(Event::AssetUpdated {
-						owner_id: owner_id.clone(),
-						chain,
-						exchange,
-						asset1,
-						asset2,
-						price: *price,
-					});
-				}
-			}
-			Ok(())
-		}
-
-		/// Delete an asset. Delete may not happen immediately if there was  task scheduled for
-		/// this asset. Upon
+		/// Delete an asset along with its prices and the tasks it triggers.
+		///
+		/// Every task of the asset is cancelled and its execution fee refunded, wherever it is
+		/// waiting: in the sorted task indexes, in the task queue or in its cooldown. At most
+		/// `MaxTasksPerPurge` tasks can be cancelled this way, the tasks of an asset with more
+		/// must be purged with `purge_tasks` first.
+		///
+		/// The owner of an asset pair can delete it to get its deposit back. The asset admin
+		/// origin can delete any asset pair, and the deposit held for it is slashed.
 		///
 		/// # Parameters
-		/// * `asset`: asset type
-		/// * `directions`: number of directions of data input. (up, down, ?)
+		/// * `chain`, `exchange`, `asset1`, `asset2`: The asset pair to delete.
 		///
 		/// # Errors
+		/// * `AssetNotSupported`: The asset pair is not initialized.
+		/// * `NotAssetOwner`: The origin is a signed account that does not own the asset pair.
+		/// * `TooManyTasksToDelete`: The asset has more than `MaxTasksPerPurge` tasks.
 		#[pallet::call_index(3)]
-		#[pallet::weight(<T as Config>::WeightInfo::initialize_asset_extrinsic(1))]
+		#[pallet::weight(<T as Config>::WeightInfo::delete_asset_extrinsic(T::MaxTasksPerPurge::get()))]
 		#[transactional]
 		pub fn delete_asset(
 			origin: OriginFor<T>,
@@ -683,26 +1583,112 @@ pub mod pallet {
 			exchange: Exchange,
 			asset1: AssetName,
 			asset2: AssetName,
-		) -> DispatchResult {
-			// TODO: use sudo and remove this feature flag
-			// When enable dev queue, we want to skip this root check so local development can
-			// happen easier
-			#[cfg(not(feature = "dev-queue"))]
-			ensure_root(origin)?;
+		) -> DispatchResultWithPostInfo {
+			let who = Self::ensure_asset_admin_or_signed(origin)?;
 
 			let key = (&chain, &exchange, (&asset1, &asset2));
-			if let Some(_asset_info) = Self::get_asset_registry_info(key) {
-				AssetRegistry::<T>::remove(&key);
-				PriceRegistry::<T>::remove(&key);
-				Self::deposit_event(Event::AssetDeleted { chain, exchange, asset1, asset2 });
-			} else {
+			if Self::get_asset_registry_info(key).is_none() {
 				Err(Error::<T>::AssetNotSupported)?
 			}
-			Ok(())
+			if let Some(who) = &who {
+				ensure!(
+					Self::get_asset_ownership(key)
+						.map_or(false, |ownership| ownership.owner == *who),
+					Error::<T>::NotAssetOwner
+				);
+			}
+
+			let tasks = Self::get_asset_tasks(&chain, &exchange, &(asset1.clone(), asset2.clone()));
+			ensure!(
+				tasks.len() <= T::MaxTasksPerPurge::get() as usize,
+				Error::<T>::TooManyTasksToDelete
+			);
+			for task in tasks.iter() {
+				Self::remove_task(
+					task,
+					Some(Event::TaskCancelled {
+						owner_id: task.owner_id.clone(),
+						task_id: task.task_id,
+					}),
+				);
+				Self::remove_from_task_queue(&task.owner_id, &task.task_id);
+				Self::refund_execution_fee(task);
+			}
+
+			AssetRegistry::<T>::remove(&key);
+			PriceRegistry::<T>::remove(&key);
+			PriceHistory::<T>::remove(&key);
+			RoundPrices::<T>::remove(&key);
+			let _ = OracleRounds::<T>::clear_prefix(key, u32::MAX, None);
+			let _ = SortedTasksAbove::<T>::clear_prefix(key, u32::MAX, None);
+			let _ = SortedTasksBelow::<T>::clear_prefix(key, u32::MAX, None);
+			SortedBucketsAbove::<T>::remove(&key);
+			SortedBucketsBelow::<T>::remove(&key);
+			UnsortedTasks::<T>::remove(&key);
+			LastShiftedPrice::<T>::remove(&key);
+			AssetSymbols::<T>::remove(&key);
+			CircuitBreakers::<T>::remove(&key);
+			HaltedAssets::<T>::remove(&key);
+			AssetTaskCounts::<T>::remove(&key);
+			AssetTaskCaps::<T>::remove(&key);
+			PendingAssetOwners::<T>::remove(&key);
+			AssetCount::<T>::mutate(|count| *count = count.saturating_sub(1));
+			match AssetOwners::<T>::take(&key) {
+				Some(ownership) if who.is_none() => {
+					let (slashed, _) =
+						T::Currency::slash_reserved(&ownership.owner, ownership.deposit);
+					let amount = slashed.peek();
+					T::AssetDepositSlash::on_unbalanced(slashed);
+					Self::deposit_event(Event::AssetDepositSlashed {
+						chain: chain.clone(),
+						exchange: exchange.clone(),
+						asset1: asset1.clone(),
+						asset2: asset2.clone(),
+						owner: ownership.owner,
+						amount,
+					});
+				},
+				Some(ownership) => {
+					release_deposit::<_, T::Currency>(&ownership.owner, ownership.deposit);
+				},
+				None => {},
+			}
+			Self::deposit_event(Event::AssetDeleted { chain, exchange, asset1, asset2 });
+
+			let weight = <T as Config>::WeightInfo::delete_asset_extrinsic(tasks.len() as u32);
+			Ok(Some(weight).into())
 		}
 
+		/// Schedule a task to fire an XCMP message with a provided call once the price of an asset
+		/// pair matches the trigger of the task.
+		///
+		/// The schedule fee and the execution fee are withdrawn in the `schedule_fee` asset when
+		/// the task is scheduled. The execution fee is refunded if the task is cancelled or
+		/// expires.
+		///
+		/// An optional `provided_id` makes scheduling idempotent: the task id is derived from the
+		/// owner and `provided_id`, so retrying the same scheduling fails instead of creating a
+		/// second task.
+		///
+		/// # Errors
+		/// * `InsufficientBalance`: The owner cannot pay the fees.
+		/// * `LiquidityRestrictions`: The fees cannot be withdrawn from the owner.
+		/// * `DuplicateTask`: The owner already has a task with `provided_id`.
 		#[pallet::call_index(4)]
-		#[pallet::weight(<T as Config>::WeightInfo::schedule_xcmp_task_extrinsic())]
+		#[pallet::weight(<T as Config>::WeightInfo::schedule_xcmp_task_extrinsic(
+			Pallet::<T>::task_input_len(
+				&[
+					&chain,
+					&exchange,
+					&asset1,
+					&asset2,
+					&trigger_function,
+					&encoded_call,
+					provided_id.as_deref().unwrap_or_default(),
+				],
+				&trigger_param,
+			)
+		))]
 		#[transactional]
 		pub fn schedule_xcmp_task(
 			origin: OriginFor<T>,
@@ -719,6 +1705,7 @@ pub mod pallet {
 			encoded_call: Vec<u8>,
 			encoded_call_weight: Weight,
 			overall_weight: Weight,
+			provided_id: Option<Vec<u8>>,
 		) -> DispatchResult {
 			// Step 1:
 			//   Build Task and put it into the task registry
@@ -726,15 +1713,20 @@ pub mod pallet {
 			//   Put task id on the index
 			// TODO: the value to be inserted into the BTree should come from a function that
 			// extract value from param
-			//
-			// TODO: HANDLE FEE to see user can pay fee
 			let owner_id = ensure_signed(origin)?;
-			let task_id = Self::generate_task_id();
+			let task_id = Self::task_id_for(&owner_id, provided_id);
 
 			let destination =
 				MultiLocation::try_from(*destination).map_err(|()| Error::<T>::BadVersion)?;
 			let schedule_fee =
 				MultiLocation::try_from(*schedule_fee).map_err(|()| Error::<T>::BadVersion)?;
+			T::XcmpTransactor::check_transact_weights(
+				destination,
+				encoded_call.len(),
+				encoded_call_weight,
+				overall_weight,
+				InstructionSequence::PayThroughSovereignAccount,
+			)?;
 
 			let action = Action::XCMP {
 				destination,
@@ -775,15 +1767,33 @@ pub mod pallet {
 		/// * `asset1`: The payment asset location required for scheduling automation task.
 		/// * `asset2`: The fee will be paid for XCMP execution.
 		/// * `expired_at`: the epoch when after that time we will remove the task if it has not been executed yet
-		/// * `trigger_function`: currently only support `gt` or `lt`. Essentially mean greater than or less than.
+		/// * `trigger_function`: `gt` or `lt`, greater than or less than, or another trigger function supported by the runtime's `ConditionEvaluator`.
 		/// * `trigger_params`: a list of parameter to feed into `trigger_function`. with `gt` and `lt` we only need to pass the target price as a single element vector
 		/// * `schedule_fee`: The payment asset location required for scheduling automation task.
 		/// * `execution_fee`: The fee will be paid for XCMP execution.
 		/// * `encoded_call`: Call that will be sent via XCMP to the parachain id provided.
 		/// * `encoded_call_weight`: Required weight at most the provided call will take.
 		/// * `overall_weight`: The overall weight in which fees will be paid for XCM instructions.
+		/// * `schedule_as`: The account the task is scheduled for, of which the owner is a proxy.
+		/// * `provided_id`: An optional id chosen by the owner, unique among the owner's tasks.
+		///
+		/// # Errors
+		/// * `DuplicateTask`: The owner already has a task with `provided_id`.
 		#[pallet::call_index(5)]
-		#[pallet::weight(<T as Config>::WeightInfo::schedule_xcmp_task_extrinsic().saturating_add(T::DbWeight::get().reads(1)))]
+		#[pallet::weight(<T as Config>::WeightInfo::schedule_xcmp_task_extrinsic(
+			Pallet::<T>::task_input_len(
+				&[
+					&chain,
+					&exchange,
+					&asset1,
+					&asset2,
+					&trigger_function,
+					&encoded_call,
+					provided_id.as_deref().unwrap_or_default(),
+				],
+				&trigger_params,
+			)
+		).saturating_add(T::DbWeight::get().reads(1)))]
 		#[transactional]
 		pub fn schedule_xcmp_task_through_proxy(
 			origin: OriginFor<T>,
@@ -802,6 +1812,7 @@ pub mod pallet {
 			encoded_call_weight: Weight,
 			overall_weight: Weight,
 			schedule_as: T::AccountId,
+			provided_id: Option<Vec<u8>>,
 		) -> DispatchResult {
 			let owner_id = ensure_signed(origin)?;
 
@@ -812,6 +1823,13 @@ pub mod pallet {
 				MultiLocation::try_from(*destination).map_err(|()| Error::<T>::BadVersion)?;
 			let schedule_fee =
 				MultiLocation::try_from(*schedule_fee).map_err(|()| Error::<T>::BadVersion)?;
+			T::XcmpTransactor::check_transact_weights(
+				destination,
+				encoded_call.len(),
+				encoded_call_weight,
+				overall_weight,
+				InstructionSequence::PayThroughRemoteDerivativeAccount,
+			)?;
 
 			let action = Action::XCMP {
 				destination,
@@ -824,7 +1842,7 @@ pub mod pallet {
 				instruction_sequence: InstructionSequence::PayThroughRemoteDerivativeAccount,
 			};
 
-			let task_id = Self::generate_task_id();
+			let task_id = Self::task_id_for(&owner_id, provided_id);
 			let task: Task<T> = Task::<T> {
 				owner_id,
 				task_id,
@@ -843,10 +1861,11 @@ pub mod pallet {
 
 		// When cancel task we remove it from:
 		//   Task Registry
-		//   SortedTasksIndex
+		//   SortedTasksAbove or SortedTasksBelow
 		//   AccountTasks
 		//   Task Queue: if the task is already on the queue but haven't got run yet,
 		//               we will attemppt to remove it
+		// The execution fee the owner paid up front is refunded, since the task will not run.
 		#[pallet::call_index(6)]
 		#[pallet::weight(<T as Config>::WeightInfo::cancel_task_extrinsic())]
 		#[transactional]
@@ -858,185 +1877,1615 @@ pub mod pallet {
 					&task,
 					Some(Event::TaskCancelled {
 						owner_id: task.owner_id.clone(),
-						task_id: task.task_id.clone(),
+						task_id: task.task_id,
 					}),
 				);
+				Self::remove_from_task_queue(&owner_id, &task_id);
+				Self::refund_execution_fee(&task);
+			} else if Tasks::<T>::contains_key(&owner_id, &task_id) {
+				Self::remove_undecodable_task(&owner_id, &task_id);
 			} else {
 				Err(Error::<T>::TaskNotFound)?
 			}
 
 			Ok(())
 		}
-	}
 
-	impl<T: Config> Pallet<T> {
-		pub fn generate_task_id() -> TaskId {
-			let current_block_number =
-				TryInto::<u64>::try_into(<frame_system::Pallet<T>>::block_number())
-					.ok()
-					.unwrap_or(0);
+		/// Cancel every task matching `filter`, for when an incident on a partner chain or a
+		/// broken price feed would otherwise cause a flood of failing executions. The execution
+		/// fees of the cancelled tasks are refunded to their owners.
+		///
+		/// A call examines at most `max_tasks` tasks. Calls with the same filter resume where the
+		/// previous one stopped, until `TasksPurged` reports the purge as complete.
+		///
+		/// # Parameters
+		/// * `filter`: The destination chain or asset pair whose tasks are cancelled.
+		/// * `max_tasks`: The number of tasks to examine.
+		///
+		/// # Errors
+		/// * `PurgeBatchTooLarge`: `max_tasks` is above `MaxTasksPerPurge`.
+		#[pallet::call_index(7)]
+		#[pallet::weight(<T as Config>::WeightInfo::purge_tasks_extrinsic(*max_tasks))]
+		#[transactional]
+		pub fn purge_tasks(
+			origin: OriginFor<T>,
+			filter: PurgeFilter,
+			max_tasks: u32,
+		) -> DispatchResult {
+			T::PurgeOrigin::ensure_origin(origin)?;
+			ensure!(max_tasks <= T::MaxTasksPerPurge::get(), Error::<T>::PurgeBatchTooLarge);
 
-			let tx_id = <frame_system::Pallet<T>>::extrinsic_index().unwrap_or(0);
+			let mut tasks = match Self::get_purge_cursor(&filter) {
+				Some(cursor) => Tasks::<T>::iter_from(cursor),
+				None => Tasks::<T>::iter(),
+			};
 
-			let evt_index = <frame_system::Pallet<T>>::event_count();
+			let mut matched: Vec<Task<T>> = vec![];
+			let mut examined = 0u32;
+			let mut complete = false;
+			while examined < max_tasks {
+				match tasks.next() {
+					Some((_, _, task)) => {
+						if filter.matches(&task) {
+							matched.push(task);
+						}
+						examined += 1;
+					},
+					None => {
+						complete = true;
+						break
+					},
+				}
+			}
 
-			format!("{:}-{:}-{:}", current_block_number, tx_id, evt_index)
-				.as_bytes()
-				.to_vec()
-		}
+			if complete {
+				PurgeCursors::<T>::remove(&filter);
+			} else {
+				PurgeCursors::<T>::insert(&filter, tasks.last_raw_key().to_vec());
+			}
 
-		// Move task from the SortedTasksIndex into TaskQueue that are ready to be process
-		pub fn shift_tasks(max_weight: Weight) -> Weight {
-			let weight_left: Weight = max_weight;
+			for task in matched.iter() {
+				Self::remove_task(
+					task,
+					Some(Event::TaskCancelled {
+						owner_id: task.owner_id.clone(),
+						task_id: task.task_id,
+					}),
+				);
+				Self::remove_from_task_queue(&task.owner_id, &task.task_id);
+				Self::refund_execution_fee(task);
+			}
 
-			// TODO: Look into asset that has price move instead
-			let task_to_process: &mut TaskIdList<T> = &mut Vec::new();
+			Self::deposit_event(Event::TasksPurged {
+				filter,
+				purged: matched.len() as u32,
+				complete,
+			});
+			Ok(())
+		}
 
-			for key in SortedTasksIndex::<T>::iter_keys() {
-				let (chain, exchange, asset_pair, trigger_func) = key.clone();
+		/// Return the deposit of a task that is no longer scheduled to its owner.
+		///
+		/// Tasks are removed when they run, expire, are cancelled or purged, and their deposit
+		/// stays reserved until this is called. Anyone can call it.
+		///
+		/// # Parameters
+		/// * `owner_id`: The owner of the task.
+		/// * `task_id`: The id of the task.
+		///
+		/// # Errors
+		/// * `DepositNotFound`: No deposit is held for the task.
+		/// * `TaskStillScheduled`: The task is still scheduled.
+		#[pallet::call_index(8)]
+		#[pallet::weight(<T as Config>::WeightInfo::reclaim_deposit())]
+		pub fn reclaim_deposit(
+			origin: OriginFor<T>,
+			owner_id: AccountOf<T>,
+			task_id: TaskId,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
 
-				// TODO: Swap asset to check pair
-				let current_price_wrap =
-					Self::get_asset_price_data((&chain, &exchange, &asset_pair));
+			let deposit =
+				Self::get_task_deposit(&owner_id, &task_id).ok_or(Error::<T>::DepositNotFound)?;
+			ensure!(!Tasks::<T>::contains_key(&owner_id, &task_id), Error::<T>::TaskStillScheduled);
 
-				if current_price_wrap.is_none() {
-					continue
-				};
-				// Example: sell orders
-				//
-				// In the list we had tasks such as
-				//  - task1: sell when price > 10
-				//  - task2: sell when price > 20
-				//  - task3: sell when price > 30
-				//  If price used to be 5, and now it's 15, task1 got run
-				//  If price used to be 5, and now it's 25, task1 and task2 got run
-				//  If price used to be 5, and now it's 35, all tasks are run
-				//
-				// Example: buy orders
-				//
-				// In the list we had tasks such as
-				//  - task1: buy when price < 10
-				//  - task2: buy when price < 20
-				//  - task3: buy when price < 30
-				//  If price used to be 500, and now it's 25, task3 got run
-				//  If price used to be 500, and now it's 15, task2 and task3 got run
-				//  If price used to be 500, and now it's 5,  all tasks are run
-				//
-				//  TODO: handle atomic and transaction
-				if let Some(mut tasks) = Self::get_sorted_tasks_index(&key) {
-					let current_price = current_price_wrap.unwrap();
+			TaskDeposits::<T>::remove(&owner_id, &task_id);
+			let amount = release_deposit::<_, T::Currency>(&owner_id, deposit);
+			Self::deposit_event(Event::DepositReclaimed { owner_id, task_id, amount });
+			Ok(())
+		}
 
-					for (&price, task_ids) in
-						(tasks.clone()).range(range_by_trigger_func(&trigger_func, &current_price))
-					{
-						// Remove because we map this into task queue
-						tasks.remove(&price);
-						let t = &mut (&mut (task_ids.clone()));
-						task_to_process.append(t);
-					}
+		/// Halt or resume scheduling, execution and price ingestion independently.
+		///
+		/// Cancelling tasks is never halted, so owners can always take their tasks out.
+		///
+		/// # Parameters
+		/// * `flags`: The parts of the pallet to halt. Those not set are resumed.
+		#[pallet::call_index(9)]
+		#[pallet::weight(<T as Config>::WeightInfo::set_shutdown_flags())]
+		pub fn set_shutdown_flags(origin: OriginFor<T>, flags: ShutdownFlags) -> DispatchResult {
+			T::ShutdownOrigin::ensure_origin(origin)?;
+
+			Shutdown::<T>::put(flags);
+			Self::deposit_event(Event::ShutdownFlagsUpdated { flags });
+			Ok(())
+		}
 
-					// all tasks are moved to process, delete the queue
-					if tasks.is_empty() {
-						SortedTasksIndex::<T>::remove(&key);
-					} else {
-						SortedTasksIndex::<T>::insert(&key, tasks);
-					}
-				}
+		/// Change the trigger params of a task, such as moving the target price of a stop, without
+		/// cancelling and scheduling it again.
+		///
+		/// The task moves to its new position in the sorted task index and keeps its id, expiry
+		/// and action. The schedule fee only depends on the action, so nothing more is charged for
+		/// it; the deposit held for the task is topped up or partly returned to match its new size.
+		///
+		/// # Parameters
+		/// * `task_id`: The id of the task.
+		/// * `trigger_params`: The new params for the trigger function of the task.
+		///
+		/// # Errors
+		/// * `SchedulingHalted`: Scheduling is halted.
+		/// * `TaskNotFound`: The caller has no task with this id.
+		/// * `InvalidTriggerParams`: The params do not fit the trigger function of the task.
+		#[pallet::call_index(10)]
+		#[pallet::weight(<T as Config>::WeightInfo::update_task_trigger())]
+		#[transactional]
+		pub fn update_task_trigger(
+			origin: OriginFor<T>,
+			task_id: TaskId,
+			trigger_params: Vec<u128>,
+		) -> DispatchResult {
+			let owner_id = ensure_signed(origin)?;
+
+			if Self::get_shutdown_flags().scheduling {
+				Err(Error::<T>::SchedulingHalted)?
 			}
 
-			if !task_to_process.is_empty() {
-				if TaskQueue::<T>::exists() {
-					let mut old_task = TaskQueue::<T>::get();
-					old_task.append(task_to_process);
-					TaskQueue::<T>::put(old_task);
-				} else {
-					TaskQueue::<T>::put(task_to_process);
-				};
+			let mut task = Self::get_task(&owner_id, &task_id).ok_or(Error::<T>::TaskNotFound)?;
+			let price = Self::get_asset_price_data((&task.chain, &task.exchange, &task.asset_pair));
+			let trigger_params = T::ConditionEvaluator::with_current_price(
+				&task.trigger_function,
+				trigger_params,
+				price.as_ref(),
+			);
+			// The first param is the target price the task is indexed by, whatever the evaluator
+			if trigger_params.is_empty() ||
+				!T::ConditionEvaluator::is_valid(&task.trigger_function, &trigger_params)
+			{
+				Err(Error::<T>::InvalidTriggerParams)?
+			}
+			Self::remove_from_sorted_index(&task);
+			task.trigger_params = trigger_params;
+			Self::adjust_task_deposit(&task)?;
+			Tasks::<T>::insert(&owner_id, &task_id, &task);
+			if !Self::is_task_paused(&owner_id, &task_id) {
+				Self::insert_into_sorted_index(&task);
 			}
 
-			weight_left
+			Self::deposit_event(Event::TaskTriggerUpdated {
+				owner_id,
+				task_id,
+				trigger_params: task.trigger_params,
+			});
+			Ok(())
 		}
 
-		/// Trigger tasks for the block time.
+		/// Offer an asset pair to a new owner, who takes it over with `accept_asset_ownership`.
 		///
-		/// Complete as many tasks as possible given the maximum weight.
-		pub fn trigger_tasks(max_weight: Weight) -> Weight {
-			let mut weight_left: Weight = max_weight;
-			let check_time_and_deletion_weight = T::DbWeight::get().reads(2u64);
-			if weight_left.ref_time() < check_time_and_deletion_weight.ref_time() {
-				return weight_left
-			}
-
-			Self::shift_tasks(weight_left);
-
-			// Now we can run those tasks
-			// TODO: We need to calculate enough weight and balance the tasks so we won't be skew
-			// by a particular kind of task asset
-			//
-			// Now we run as much task as possible
-			// If weight is over, task will be picked up next time
-			// If the price is no longer matched, they will be put back into the TaskRegistry
-			let task_queue = Self::get_task_queue();
-
-			weight_left = weight_left
-				// for above read
-				.saturating_sub(T::DbWeight::get().reads(1u64))
-				// For measuring the TaskQueue::<T>::put(tasks_left);
-				.saturating_sub(T::DbWeight::get().writes(1u64));
-			if !task_queue.is_empty() {
-				let (tasks_left, new_weight_left) = Self::run_tasks(task_queue, weight_left);
-				weight_left = new_weight_left;
-				TaskQueue::<T>::put(tasks_left);
+		/// Only the current owner can make the offer, the root origin for an asset pair owned by
+		/// governance. A new offer replaces one that was not accepted yet.
+		///
+		/// # Parameters
+		/// * `chain`, `exchange`, `asset1`, `asset2`: The asset pair.
+		/// * `new_owner`: The account or governance the asset pair is offered to.
+		///
+		/// # Errors
+		/// * `AssetNotSupported`: The asset pair is not registered.
+		/// * `NotAssetOwner`: The origin does not own the asset pair.
+		#[pallet::call_index(11)]
+		#[pallet::weight(<T as Config>::WeightInfo::transfer_asset_ownership())]
+		pub fn transfer_asset_ownership(
+			origin: OriginFor<T>,
+			chain: ChainName,
+			exchange: Exchange,
+			asset1: AssetName,
+			asset2: AssetName,
+			new_owner: AssetOwner<AccountOf<T>>,
+		) -> DispatchResult {
+			let key = (&chain, &exchange, (&asset1, &asset2));
+			if !AssetRegistry::<T>::contains_key(&key) {
+				Err(Error::<T>::AssetNotSupported)?
 			}
+			Self::ensure_asset_owner(origin, &Self::asset_owner(&key))?;
 
-			weight_left
+			PendingAssetOwners::<T>::insert(&key, &new_owner);
+			Self::deposit_event(Event::AssetOwnershipTransferProposed {
+				chain,
+				exchange,
+				asset1,
+				asset2,
+				new_owner,
+			});
+			Ok(())
 		}
 
-		pub fn create_new_asset(
+		/// Take over an asset pair offered with `transfer_asset_ownership`.
+		///
+		/// The deposit held for the asset pair moves to the new owner: it is reserved from an
+		/// accepting account and returned to the previous owner. Governance holds no deposit.
+		///
+		/// # Parameters
+		/// * `chain`, `exchange`, `asset1`, `asset2`: The asset pair.
+		///
+		/// # Errors
+		/// * `NoPendingAssetTransfer`: The asset pair was not offered to anyone.
+		/// * `NotAssetOwner`: The origin is not the one the asset pair was offered to.
+		#[pallet::call_index(12)]
+		#[pallet::weight(<T as Config>::WeightInfo::accept_asset_ownership())]
+		#[transactional]
+		pub fn accept_asset_ownership(
+			origin: OriginFor<T>,
 			chain: ChainName,
 			exchange: Exchange,
 			asset1: AssetName,
 			asset2: AssetName,
-			decimal: u8,
-			asset_owners: Vec<AccountOf<T>>,
-		) -> Result<(), DispatchError> {
+		) -> DispatchResult {
 			let key = (&chain, &exchange, (&asset1, &asset2));
-
-			if AssetRegistry::<T>::contains_key(&key) {
-				Err(Error::<T>::AssetAlreadyInitialized)?
+			let new_owner =
+				Self::get_pending_asset_owner(&key).ok_or(Error::<T>::NoPendingAssetTransfer)?;
+			Self::ensure_asset_owner(origin, &new_owner)?;
+
+			PendingAssetOwners::<T>::remove(&key);
+			let previous = AssetOwners::<T>::take(&key);
+			let deposit = previous.as_ref().map(|ownership| ownership.deposit).unwrap_or_default();
+			if let AssetOwner::Account(owner) = &new_owner {
+				T::Currency::reserve(owner, deposit)?;
+				AssetOwners::<T>::insert(&key, AssetOwnership { owner: owner.clone(), deposit });
+			}
+			if let Some(previous) = previous {
+				release_deposit::<_, T::Currency>(&previous.owner, previous.deposit);
 			}
 
-			let asset_info = RegistryInfo::<T> {
-				decimal,
-				round: 0,
-				last_update: 0,
-				oracle_providers: asset_owners,
-			};
-
-			AssetRegistry::<T>::insert(key, asset_info);
-
-			Self::deposit_event(Event::AssetCreated { chain, exchange, asset1, asset2, decimal });
+			Self::deposit_event(Event::AssetOwnershipTransferred {
+				chain,
+				exchange,
+				asset1,
+				asset2,
+				owner: new_owner,
+			});
 			Ok(())
 		}
 
-		pub fn get_current_time_slot() -> Result<UnixTime, Error<T>> {
-			let now = <timestamp::Pallet<T>>::get().saturated_into::<UnixTime>();
-			if now == 0 {
-				Err(Error::<T>::BlockTimeNotSet)?
+		/// Make a task recurring, or have it run once again.
+		///
+		/// A recurring task is not removed when it runs. It waits out its cooldown and is then
+		/// armed again, its schedule and execution fees charged once more for the next run. It is
+		/// removed once it ran `max_executions` more times, once its next run would be after its
+		/// expiry, or once its fees cannot be paid.
+		///
+		/// # Parameters
+		/// * `task_id`: The id of the task.
+		/// * `schedule`: How often the task runs.
+		///
+		/// # Errors
+		/// * `SchedulingHalted`: Scheduling is halted.
+		/// * `TaskNotFound`: The caller has no task with this id.
+		/// * `InvalidSchedule`: The cooldown or the number of executions is zero.
+		#[pallet::call_index(13)]
+		#[pallet::weight(<T as Config>::WeightInfo::set_task_schedule())]
+		pub fn set_task_schedule(
+			origin: OriginFor<T>,
+			task_id: TaskId,
+			schedule: Schedule,
+		) -> DispatchResult {
+			let owner_id = ensure_signed(origin)?;
+
+			if Self::get_shutdown_flags().scheduling {
+				Err(Error::<T>::SchedulingHalted)?
 			}
-			let now = now.saturating_div(1000);
-			let diff_to_min = now % 60;
-			Ok(now.saturating_sub(diff_to_min))
+			if !Tasks::<T>::contains_key(&owner_id, &task_id) {
+				Err(Error::<T>::TaskNotFound)?
+			}
+
+			match schedule {
+				Schedule::Once => TaskRecurrences::<T>::remove(&owner_id, &task_id),
+				Schedule::Recurring { cooldown_seconds, max_executions } => {
+					if cooldown_seconds == 0 || max_executions == Some(0) {
+						Err(Error::<T>::InvalidSchedule)?
+					}
+					TaskRecurrences::<T>::insert(
+						&owner_id,
+						&task_id,
+						Recurrence { cooldown_seconds, remaining_executions: max_executions },
+					);
+				},
+			}
+
+			Self::deposit_event(Event::TaskScheduleUpdated { owner_id, task_id, schedule });
+			Ok(())
 		}
 
-		pub fn run_xcmp_task(
-			destination: MultiLocation,
-			caller: T::AccountId,
-			fee: AssetPayment,
-			encoded_call: Vec<u8>,
-			encoded_call_weight: Weight,
-			overall_weight: Weight,
-			flow: InstructionSequence,
+		/// Schedule a task that dispatches a call on this chain once its price condition matches.
+		///
+		/// The call runs with the origin of the task owner and is limited to the calls of
+		/// `ScheduleAllowList`.
+		///
+		/// # Parameters
+		/// * `chain`, `exchange`, `asset1`, `asset2`: The asset pair whose price triggers the task.
+		/// * `expired_at`: The epoch after which the task is removed if it has not run yet.
+		/// * `trigger_function`: The name of the trigger function, such as `gt` or `lt`.
+		/// * `trigger_params`: A list of parameters to feed into `trigger_function`.
+		/// * `schedule_fee`: The asset the fees of the task are paid in. The fees of assets other
+		///   than the native token are priced with `FeeConversionRateProvider`.
+		/// * `call`: The call that will be dispatched.
+		/// * `provided_id`: An optional id chosen by the owner, unique among the owner's tasks.
+		///
+		/// # Errors
+		/// * `InvalidTaskId`: the task id is all zeros.
+		/// * `AssetNotSupported`: the asset pair is not initialized.
+		/// * `BadVersion`: `schedule_fee` cannot be converted to the latest XCM version.
+		/// * `InsufficientBalance`: The owner cannot pay the fees.
+		/// * `DuplicateTask`: The owner already has a task with `provided_id`.
+		#[pallet::call_index(14)]
+		#[pallet::weight(<T as Config>::WeightInfo::schedule_xcmp_task_extrinsic(
+			Pallet::<T>::task_input_len(
+				&[
+					&chain,
+					&exchange,
+					&asset1,
+					&asset2,
+					&trigger_function,
+					provided_id.as_deref().unwrap_or_default(),
+				],
+				&trigger_params,
+			).saturating_add(call.encoded_size() as u32)
+		))]
+		#[transactional]
+		pub fn schedule_dynamic_dispatch_task(
+			origin: OriginFor<T>,
+			chain: ChainName,
+			exchange: Exchange,
+			asset1: AssetName,
+			asset2: AssetName,
+			expired_at: u128,
+			trigger_function: Vec<u8>,
+			trigger_params: Vec<u128>,
+			schedule_fee: Box<VersionedMultiLocation>,
+			call: Box<<T as Config>::RuntimeCall>,
+			provided_id: Option<Vec<u8>>,
+		) -> DispatchResult {
+			let owner_id = ensure_signed(origin)?;
+			let task_id = Self::task_id_for(&owner_id, provided_id);
+			let schedule_fee =
+				MultiLocation::try_from(*schedule_fee).map_err(|()| Error::<T>::BadVersion)?;
+
+			let task: Task<T> = Task::<T> {
+				owner_id,
+				task_id,
+				chain,
+				exchange,
+				asset_pair: (asset1, asset2),
+				expired_at,
+				trigger_function,
+				trigger_params,
+				action: Action::DynamicDispatch { encoded_call: call.encode(), schedule_fee },
+			};
+
+			Self::validate_and_schedule_task(task)?;
+			Ok(())
+		}
+
+		/// Schedule a stop loss and a take profit XCMP task on the same asset pair as a bracket.
+		///
+		/// The stop loss task runs once the price drops below `stop_loss_price` and the take
+		/// profit task once it rises above `take_profit_price`. Once either of them runs, the
+		/// other one is cancelled and its execution fee refunded.
+		///
+		/// # Parameters
+		/// * `chain`, `exchange`, `asset1`, `asset2`: The asset pair that triggers the tasks.
+		/// * `expired_at`: The epoch after which the tasks are removed if they have not run yet.
+		/// * `stop_loss_price`: The price below which `stop_loss_call` is sent.
+		/// * `take_profit_price`: The price above which `take_profit_call` is sent.
+		/// * `destination`: The chain the calls are sent to.
+		/// * `schedule_fee`: The payment asset location required for scheduling the tasks.
+		/// * `execution_fee`: The fee paid for the XCMP execution of each task.
+		/// * `stop_loss_call`, `take_profit_call`: The calls sent via XCMP to `destination`.
+		/// * `encoded_call_weight`: Required weight at most either call will take.
+		/// * `overall_weight`: The overall weight in which fees will be paid for XCM instructions.
+		///
+		/// # Errors
+		/// * `InvalidBracket`: The stop loss price is not below the take profit price.
+		/// * `AssetNotSupported`: The asset pair is not initialized.
+		#[pallet::call_index(15)]
+		#[pallet::weight(<T as Config>::WeightInfo::schedule_xcmp_task_extrinsic(
+			Pallet::<T>::task_input_len(
+				&[&chain, &exchange, &asset1, &asset2, &stop_loss_call],
+				&[*stop_loss_price],
+			)
+		).saturating_add(<T as Config>::WeightInfo::schedule_xcmp_task_extrinsic(
+			Pallet::<T>::task_input_len(
+				&[&chain, &exchange, &asset1, &asset2, &take_profit_call],
+				&[*take_profit_price],
+			)
+		)).saturating_add(T::DbWeight::get().reads_writes(1, 2)))]
+		#[transactional]
+		pub fn schedule_xcmp_bracket_task(
+			origin: OriginFor<T>,
+			chain: ChainName,
+			exchange: Exchange,
+			asset1: AssetName,
+			asset2: AssetName,
+			expired_at: u128,
+			stop_loss_price: u128,
+			take_profit_price: u128,
+			destination: Box<VersionedMultiLocation>,
+			schedule_fee: Box<VersionedMultiLocation>,
+			execution_fee: Box<AssetPayment>,
+			stop_loss_call: Vec<u8>,
+			take_profit_call: Vec<u8>,
+			encoded_call_weight: Weight,
+			overall_weight: Weight,
+		) -> DispatchResult {
+			let owner_id = ensure_signed(origin)?;
+
+			if stop_loss_price >= take_profit_price {
+				Err(Error::<T>::InvalidBracket)?
+			}
+			if !AssetRegistry::<T>::contains_key((&chain, &exchange, (&asset1, &asset2))) {
+				Err(Error::<T>::AssetNotSupported)?
+			}
+
+			let destination =
+				MultiLocation::try_from(*destination).map_err(|()| Error::<T>::BadVersion)?;
+			let schedule_fee =
+				MultiLocation::try_from(*schedule_fee).map_err(|()| Error::<T>::BadVersion)?;
+
+			let schedule = |trigger_function: &[u8], price: u128, encoded_call: Vec<u8>| {
+				T::XcmpTransactor::check_transact_weights(
+					destination,
+					encoded_call.len(),
+					encoded_call_weight,
+					overall_weight,
+					InstructionSequence::PayThroughSovereignAccount,
+				)?;
+
+				let task_id = Self::generate_task_id(&owner_id);
+				let task: Task<T> = Task::<T> {
+					owner_id: owner_id.clone(),
+					task_id,
+					chain: chain.clone(),
+					exchange: exchange.clone(),
+					asset_pair: (asset1.clone(), asset2.clone()),
+					expired_at,
+					trigger_function: trigger_function.to_vec(),
+					trigger_params: vec![price],
+					action: Action::XCMP {
+						destination,
+						schedule_fee,
+						execution_fee: (*execution_fee).clone(),
+						encoded_call,
+						encoded_call_weight,
+						overall_weight,
+						schedule_as: None,
+						instruction_sequence: InstructionSequence::PayThroughSovereignAccount,
+					},
+				};
+
+				Self::validate_and_schedule_task(task)?;
+				Ok::<_, DispatchError>(task_id)
+			};
+			let stop_loss_task_id = schedule(TRIGGER_FUNC_LT, stop_loss_price, stop_loss_call)?;
+			let take_profit_task_id =
+				schedule(TRIGGER_FUNC_GT, take_profit_price, take_profit_call)?;
+			LinkedTasks::<T>::insert(&owner_id, &stop_loss_task_id, &take_profit_task_id);
+			LinkedTasks::<T>::insert(&owner_id, &take_profit_task_id, &stop_loss_task_id);
+
+			Self::deposit_event(Event::BracketScheduled {
+				owner_id,
+				stop_loss_task_id,
+				take_profit_task_id,
+			});
+			Ok(())
+		}
+
+		/// Allow an account to push the price of an asset pair.
+		///
+		/// The tasks of the asset pair are not affected, so the oracle providers can be rotated
+		/// while tasks keep waiting for their trigger price.
+		///
+		/// # Parameters
+		/// * `chain`, `exchange`, `asset1`, `asset2`: The asset pair.
+		/// * `provider`: The account to add.
+		///
+		/// # Errors
+		/// * `AssetNotSupported`: The asset pair is not initialized.
+		/// * `OracleProviderAlreadyAdded`: The account already provides prices for the asset pair.
+		#[pallet::call_index(16)]
+		#[pallet::weight(<T as Config>::WeightInfo::add_oracle_provider())]
+		pub fn add_oracle_provider(
+			origin: OriginFor<T>,
+			chain: ChainName,
+			exchange: Exchange,
+			asset1: AssetName,
+			asset2: AssetName,
+			provider: AccountOf<T>,
+		) -> DispatchResult {
+			T::OracleProviderOrigin::ensure_origin(origin)?;
+
+			let key = (&chain, &exchange, (&asset1, &asset2));
+			AssetRegistry::<T>::try_mutate(key, |info| -> DispatchResult {
+				let info = info.as_mut().ok_or(Error::<T>::AssetNotSupported)?;
+				ensure!(
+					!info.oracle_providers.contains(&provider),
+					Error::<T>::OracleProviderAlreadyAdded
+				);
+				info.oracle_providers.push(provider.clone());
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::OracleProviderAdded {
+				chain,
+				exchange,
+				asset1,
+				asset2,
+				provider,
+			});
+			Ok(())
+		}
+
+		/// Stop an account from pushing the price of an asset pair.
+		///
+		/// The price already pushed by the account is kept, and so are the tasks of the asset
+		/// pair.
+		///
+		/// # Parameters
+		/// * `chain`, `exchange`, `asset1`, `asset2`: The asset pair.
+		/// * `provider`: The account to remove.
+		///
+		/// # Errors
+		/// * `AssetNotSupported`: The asset pair is not initialized.
+		/// * `OracleProviderNotFound`: The account does not provide prices for the asset pair.
+		#[pallet::call_index(17)]
+		#[pallet::weight(<T as Config>::WeightInfo::remove_oracle_provider())]
+		pub fn remove_oracle_provider(
+			origin: OriginFor<T>,
+			chain: ChainName,
+			exchange: Exchange,
+			asset1: AssetName,
+			asset2: AssetName,
+			provider: AccountOf<T>,
+		) -> DispatchResult {
+			T::OracleProviderOrigin::ensure_origin(origin)?;
+
+			let key = (&chain, &exchange, (&asset1, &asset2));
+			AssetRegistry::<T>::try_mutate(key, |info| -> DispatchResult {
+				let info = info.as_mut().ok_or(Error::<T>::AssetNotSupported)?;
+				let index = info
+					.oracle_providers
+					.iter()
+					.position(|account| *account == provider)
+					.ok_or(Error::<T>::OracleProviderNotFound)?;
+				info.oracle_providers.remove(index);
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::OracleProviderRemoved {
+				chain,
+				exchange,
+				asset1,
+				asset2,
+				provider,
+			});
+			Ok(())
+		}
+
+		/// Update prices of multiple asset pairs with a payload signed by an oracle provider.
+		///
+		/// The transaction is unsigned, so the provider needs no funded account and the payload
+		/// can be produced by an offchain worker. The payload is checked before it enters the
+		/// transaction pool: its signature must be valid, its provider must be an oracle provider
+		/// of every asset pair in it, none of its rounds may be stale or already submitted by the
+		/// provider, and the provider must not have submitted another payload in the last
+		/// `UnsignedSubmissionInterval` blocks.
+		///
+		/// # Parameters
+		/// * `payload`: the price updates, as in `update_asset_prices`, and their provider.
+		/// * `signature`: the signature of the SCALE encoded payload by its provider.
+		#[pallet::call_index(18)]
+		#[pallet::weight(
+			<T as Config>::WeightInfo::asset_price_update_extrinsic(payload.updates.len() as u32)
+				.saturating_add(T::DbWeight::get().writes(1u64))
+		)]
+		#[transactional]
+		pub fn submit_asset_prices_unsigned(
+			origin: OriginFor<T>,
+			payload: PricePayload<AccountOf<T>>,
+			_signature: T::OracleSignature,
+		) -> DispatchResult {
+			ensure_none(origin)?;
+
+			LastUnsignedSubmissions::<T>::insert(
+				&payload.provider,
+				frame_system::Pallet::<T>::block_number(),
+			);
+			Self::ingest_prices(&payload.provider, payload.updates)?;
+			Ok(())
+		}
+
+		/// Set the symbol an asset pair is displayed with.
+		///
+		/// Only the owner of the asset pair can set it, the root origin for an asset pair owned by
+		/// governance.
+		///
+		/// # Parameters
+		/// * `chain`, `exchange`, `asset1`, `asset2`: The asset pair.
+		/// * `symbol`: The symbol of the asset pair.
+		///
+		/// # Errors
+		/// * `AssetNotSupported`: The asset pair is not initialized.
+		/// * `NotAssetOwner`: The origin does not own the asset pair.
+		#[pallet::call_index(19)]
+		#[pallet::weight(<T as Config>::WeightInfo::set_asset_symbol())]
+		pub fn set_asset_symbol(
+			origin: OriginFor<T>,
+			chain: ChainName,
+			exchange: Exchange,
+			asset1: AssetName,
+			asset2: AssetName,
+			symbol: Vec<u8>,
+		) -> DispatchResult {
+			let key = (&chain, &exchange, (&asset1, &asset2));
+			if !AssetRegistry::<T>::contains_key(&key) {
+				Err(Error::<T>::AssetNotSupported)?
+			}
+			Self::ensure_asset_owner(origin, &Self::asset_owner(&key))?;
+
+			AssetSymbols::<T>::insert(&key, &symbol);
+			Self::deposit_event(Event::AssetSymbolUpdated {
+				chain,
+				exchange,
+				asset1,
+				asset2,
+				symbol,
+			});
+			Ok(())
+		}
+
+		/// Set or remove the circuit breaker of an asset pair.
+		///
+		/// A new price that moves further from the current price than the circuit breaker allows
+		/// halts the asset pair: its tasks are not triggered until its cooldown is over or until
+		/// `resume_asset` is called. Only the owner of the asset pair can set it, the root origin
+		/// for an asset pair owned by governance.
+		///
+		/// # Parameters
+		/// * `chain`, `exchange`, `asset1`, `asset2`: The asset pair.
+		/// * `circuit_breaker`: The circuit breaker, or `None` to remove it.
+		///
+		/// # Errors
+		/// * `AssetNotSupported`: The asset pair is not initialized.
+		/// * `NotAssetOwner`: The origin does not own the asset pair.
+		#[pallet::call_index(20)]
+		#[pallet::weight(<T as Config>::WeightInfo::set_circuit_breaker())]
+		pub fn set_circuit_breaker(
+			origin: OriginFor<T>,
+			chain: ChainName,
+			exchange: Exchange,
+			asset1: AssetName,
+			asset2: AssetName,
+			circuit_breaker: Option<CircuitBreaker>,
+		) -> DispatchResult {
+			let key = (&chain, &exchange, (&asset1, &asset2));
+			if !AssetRegistry::<T>::contains_key(&key) {
+				Err(Error::<T>::AssetNotSupported)?
+			}
+			Self::ensure_asset_owner(origin, &Self::asset_owner(&key))?;
+
+			CircuitBreakers::<T>::set(&key, circuit_breaker);
+			Self::deposit_event(Event::CircuitBreakerUpdated {
+				chain,
+				exchange,
+				asset1,
+				asset2,
+				circuit_breaker,
+			});
+			Ok(())
+		}
+
+		/// Resume an asset pair halted by its circuit breaker, so its tasks can be triggered
+		/// again.
+		///
+		/// # Parameters
+		/// * `chain`, `exchange`, `asset1`, `asset2`: The asset pair.
+		///
+		/// # Errors
+		/// * `AssetNotHalted`: The asset pair is not halted.
+		#[pallet::call_index(21)]
+		#[pallet::weight(<T as Config>::WeightInfo::resume_asset())]
+		pub fn resume_asset(
+			origin: OriginFor<T>,
+			chain: ChainName,
+			exchange: Exchange,
+			asset1: AssetName,
+			asset2: AssetName,
+		) -> DispatchResult {
+			T::ShutdownOrigin::ensure_origin(origin)?;
+
+			let key = (&chain, &exchange, (&asset1, &asset2));
+			ensure!(HaltedAssets::<T>::contains_key(&key), Error::<T>::AssetNotHalted);
+			Self::resume_halted_asset(&chain, &exchange, &(asset1, asset2));
+			Ok(())
+		}
+
+		/// Set what happens to a task that was triggered but could not run before its price
+		/// condition stopped matching.
+		///
+		/// # Parameters
+		/// * `task_id`: The id of the task.
+		/// * `policy`: Whether the task runs late, is skipped until its condition matches again,
+		///   or is cancelled and refunded.
+		///
+		/// # Errors
+		/// * `TaskNotFound`: The caller has no task with this id.
+		#[pallet::call_index(22)]
+		#[pallet::weight(<T as Config>::WeightInfo::set_missed_task_policy())]
+		pub fn set_missed_task_policy(
+			origin: OriginFor<T>,
+			task_id: TaskId,
+			policy: MissedTaskPolicy,
+		) -> DispatchResult {
+			let owner_id = ensure_signed(origin)?;
+
+			if !Tasks::<T>::contains_key(&owner_id, &task_id) {
+				Err(Error::<T>::TaskNotFound)?
+			}
+
+			match policy {
+				MissedTaskPolicy::Skip => MissedTaskPolicies::<T>::remove(&owner_id, &task_id),
+				_ => MissedTaskPolicies::<T>::insert(&owner_id, &task_id, policy),
+			}
+			Self::deposit_event(Event::MissedTaskPolicyUpdated { owner_id, task_id, policy });
+			Ok(())
+		}
+
+		/// Pause a task, so that it is not triggered until it is resumed.
+		///
+		/// The task keeps its id, trigger and fees, and is taken out of the task queue if it was
+		/// triggered but did not run yet. It still expires while it is paused.
+		///
+		/// # Parameters
+		/// * `task_id`: The id of the task.
+		///
+		/// # Errors
+		/// * `TaskNotFound`: The caller has no task with this id.
+		/// * `TaskAlreadyPaused`: The task is already paused.
+		#[pallet::call_index(23)]
+		#[pallet::weight(<T as Config>::WeightInfo::pause_task())]
+		#[transactional]
+		pub fn pause_task(origin: OriginFor<T>, task_id: TaskId) -> DispatchResult {
+			let owner_id = ensure_signed(origin)?;
+
+			let task = Self::get_task(&owner_id, &task_id).ok_or(Error::<T>::TaskNotFound)?;
+			ensure!(!Self::is_task_paused(&owner_id, &task_id), Error::<T>::TaskAlreadyPaused);
+
+			Self::remove_from_sorted_index(&task);
+			Self::remove_from_task_queue(&owner_id, &task_id);
+			PausedTasks::<T>::insert(&owner_id, &task_id, true);
+			Self::deposit_event(Event::TaskPaused { owner_id, task_id });
+			Ok(())
+		}
+
+		/// Resume a paused task, which can be triggered again from the next block.
+		///
+		/// A recurring task paused during its cooldown waits out the rest of the cooldown.
+		///
+		/// # Parameters
+		/// * `task_id`: The id of the task.
+		///
+		/// # Errors
+		/// * `TaskNotFound`: The caller has no task with this id.
+		/// * `TaskNotPaused`: The task is not paused.
+		#[pallet::call_index(24)]
+		#[pallet::weight(<T as Config>::WeightInfo::resume_task())]
+		#[transactional]
+		pub fn resume_task(origin: OriginFor<T>, task_id: TaskId) -> DispatchResult {
+			let owner_id = ensure_signed(origin)?;
+
+			let task = Self::get_task(&owner_id, &task_id).ok_or(Error::<T>::TaskNotFound)?;
+			ensure!(Self::is_task_paused(&owner_id, &task_id), Error::<T>::TaskNotPaused);
+
+			PausedTasks::<T>::remove(&owner_id, &task_id);
+			// A task still cooling down is put back into the index once its cooldown has passed
+			let cooling_down = Self::get_cooling_down_tasks()
+				.values()
+				.flatten()
+				.any(|(owner, id)| *owner == owner_id && *id == task_id);
+			if !cooling_down {
+				Self::insert_into_sorted_index(&task);
+			}
+			Self::deposit_event(Event::TaskResumed { owner_id, task_id });
+			Ok(())
+		}
+
+		/// Set the time of day, in UTC, that a task can run in.
+		///
+		/// A task triggered outside of its window is deferred until the window opens, and runs
+		/// then if its price condition still matches.
+		///
+		/// # Parameters
+		/// * `task_id`: The id of the task.
+		/// * `window`: The time window of the task, or `None` to let it run at any time.
+		///
+		/// # Errors
+		/// * `TaskNotFound`: The caller has no task with this id.
+		/// * `InvalidTimeWindow`: The time window is empty or does not fit in a day.
+		#[pallet::call_index(25)]
+		#[pallet::weight(<T as Config>::WeightInfo::set_task_time_window())]
+		pub fn set_task_time_window(
+			origin: OriginFor<T>,
+			task_id: TaskId,
+			window: Option<TimeWindow>,
+		) -> DispatchResult {
+			let owner_id = ensure_signed(origin)?;
+
+			if !Tasks::<T>::contains_key(&owner_id, &task_id) {
+				Err(Error::<T>::TaskNotFound)?
+			}
+
+			match window {
+				Some(window) => {
+					ensure!(window.is_valid(), Error::<T>::InvalidTimeWindow);
+					TaskTimeWindows::<T>::insert(&owner_id, &task_id, window);
+				},
+				None => TaskTimeWindows::<T>::remove(&owner_id, &task_id),
+			}
+			Self::deposit_event(Event::TaskTimeWindowUpdated { owner_id, task_id, window });
+			Ok(())
+		}
+
+		/// Combine the price condition of a task with conditions on the price of other asset
+		/// pairs, such as `DOT/USDT > X AND KSM/USDT < Y`.
+		///
+		/// The task is checked again whenever the price of any of these asset pairs moves, and
+		/// triggers when all of its conditions match, or any of them, depending on the combinator.
+		///
+		/// # Parameters
+		/// * `task_id`: The id of the task.
+		/// * `conditions`: The conditions and how they combine with the price condition of the
+		///   task, or `None` to keep only its own.
+		///
+		/// # Errors
+		/// * `SchedulingHalted`: Scheduling is halted.
+		/// * `TaskNotFound`: The caller has no task with this id.
+		/// * `InvalidConditionCount`: There are no conditions, or more than `MaxExtraConditions`.
+		/// * `AssetNotSupported`: A condition is on an asset pair that is not registered.
+		/// * `InvalidTriggerParams`: The params of a condition do not fit its trigger function.
+		#[pallet::call_index(26)]
+		#[pallet::weight(<T as Config>::WeightInfo::set_task_conditions())]
+		pub fn set_task_conditions(
+			origin: OriginFor<T>,
+			task_id: TaskId,
+			conditions: Option<CompoundCondition>,
+		) -> DispatchResult {
+			let owner_id = ensure_signed(origin)?;
+
+			if Self::get_shutdown_flags().scheduling {
+				Err(Error::<T>::SchedulingHalted)?
+			}
+
+			let task = Self::get_task(&owner_id, &task_id).ok_or(Error::<T>::TaskNotFound)?;
+			let conditions = conditions.map(Self::validate_conditions).transpose()?;
+
+			Self::remove_from_sorted_index(&task);
+			match &conditions {
+				Some(conditions) => TaskConditions::<T>::insert(&owner_id, &task_id, conditions),
+				None => TaskConditions::<T>::remove(&owner_id, &task_id),
+			}
+			if !Self::is_task_paused(&owner_id, &task_id) {
+				Self::insert_into_sorted_index(&task);
+			}
+
+			Self::deposit_event(Event::TaskConditionsUpdated { owner_id, task_id, conditions });
+			Ok(())
+		}
+
+		/// Set the number of tasks that can be scheduled on an asset pair, to let a busy pair hold
+		/// more tasks than `MaxTasksPerAssetPair`.
+		///
+		/// Only the asset admin origin can set it, not the owner of the asset pair. Lowering the
+		/// cap below the tasks already scheduled on the pair keeps them, but no new task can be
+		/// scheduled on it until enough of them are gone.
+		///
+		/// # Parameters
+		/// * `chain`, `exchange`, `asset1`, `asset2`: The asset pair.
+		/// * `cap`: The task cap of the asset pair, or `None` to use `MaxTasksPerAssetPair`.
+		///
+		/// # Errors
+		/// * `AssetNotSupported`: The asset pair is not registered.
+		#[pallet::call_index(27)]
+		#[pallet::weight(<T as Config>::WeightInfo::set_asset_task_cap())]
+		pub fn set_asset_task_cap(
+			origin: OriginFor<T>,
+			chain: ChainName,
+			exchange: Exchange,
+			asset1: AssetName,
+			asset2: AssetName,
+			cap: Option<u32>,
+		) -> DispatchResult {
+			T::AssetAdminOrigin::ensure_origin(origin)?;
+
+			let key = (&chain, &exchange, (&asset1, &asset2));
+			if !AssetRegistry::<T>::contains_key(&key) {
+				Err(Error::<T>::AssetNotSupported)?
+			}
+
+			AssetTaskCaps::<T>::set(&key, cap);
+			Self::deposit_event(Event::AssetTaskCapUpdated {
+				chain,
+				exchange,
+				asset1,
+				asset2,
+				cap,
+			});
+			Ok(())
+		}
+
+		/// Set the tip a task pays when it runs. Of the tasks triggered in the same block, those
+		/// with a higher tip run first.
+		///
+		/// The tip is held from the owner until the task runs, and is then given to
+		/// `TipDestination`. It is returned if the task is removed without running. A recurring
+		/// task only pays its tip on its next run.
+		///
+		/// # Parameters
+		/// * `task_id`: The id of the task.
+		/// * `tip`: The tip of the task, or zero to take it back.
+		///
+		/// # Errors
+		/// * `TaskNotFound`: The caller has no task with this id.
+		#[pallet::call_index(28)]
+		#[pallet::weight(<T as Config>::WeightInfo::set_task_tip())]
+		pub fn set_task_tip(
+			origin: OriginFor<T>,
+			task_id: TaskId,
+			tip: BalanceOf<T>,
+		) -> DispatchResult {
+			let owner_id = ensure_signed(origin)?;
+
+			if !Tasks::<T>::contains_key(&owner_id, &task_id) {
+				Err(Error::<T>::TaskNotFound)?
+			}
+
+			let held = Self::get_task_tip(&owner_id, &task_id).unwrap_or_default();
+			if tip > held {
+				T::Currency::reserve(&owner_id, tip.saturating_sub(held))?;
+			} else {
+				T::Currency::unreserve(&owner_id, held.saturating_sub(tip));
+			}
+			if tip.is_zero() {
+				TaskTips::<T>::remove(&owner_id, &task_id);
+			} else {
+				TaskTips::<T>::insert(&owner_id, &task_id, tip);
+			}
+
+			Self::deposit_event(Event::TaskTipUpdated { owner_id, task_id, tip });
+			Ok(())
+		}
+
+		/// Run up to `limit` tasks from the front of the task queue, without waiting for the
+		/// scheduler to run them at the start of a block.
+		///
+		/// Anyone can call it. The keeper that runs a task is paid the part of its fees held for
+		/// keepers, `KeeperRewardShare` of the fee paid when the task was scheduled. That part of
+		/// the fees of the tasks the scheduler runs goes to the fee revenue when they are removed.
+		///
+		/// The whole task queue is read and written back. The call is charged for a queue holding
+		/// `MaxTasksOverall` tasks, and refunded down to the length of the queue it found.
+		///
+		/// # Parameters
+		/// * `limit`: The number of queued tasks to run.
+		///
+		/// # Errors
+		/// * `KeeperBatchTooLarge`: `limit` is above `MaxTasksPerKeeperCall`.
+		/// * `ExecutionHalted`: Task execution is halted.
+		/// * `NoTasksReady`: The task queue is empty.
+		#[pallet::call_index(29)]
+		#[pallet::weight(<T as Config>::WeightInfo::execute_ready_tasks(*limit).saturating_add(
+			<T as Config>::WeightInfo::update_task_queue(T::MaxTasksOverall::get())
+		))]
+		pub fn execute_ready_tasks(origin: OriginFor<T>, limit: u32) -> DispatchResultWithPostInfo {
+			let keeper = ensure_signed(origin)?;
+			ensure!(limit <= T::MaxTasksPerKeeperCall::get(), Error::<T>::KeeperBatchTooLarge);
+			ensure!(
+				!Self::get_shutdown_flags().execution && Self::get_index_migration().is_none(),
+				Error::<T>::ExecutionHalted
+			);
+
+			let mut task_queue = Self::get_task_queue();
+			ensure!(!task_queue.is_empty(), Error::<T>::NoTasksReady);
+			let task_queue_weight =
+				<T as Config>::WeightInfo::update_task_queue(task_queue.len() as u32);
+
+			// The tasks past the limit keep their place behind the ones left over by this call
+			let rest = task_queue.split_off(task_queue.len().min(limit as usize));
+			let max_weight = <T as Config>::WeightInfo::execute_ready_tasks(limit);
+			let (mut tasks_left, weight_left) = Self::run_queued_tasks(
+				task_queue,
+				max_weight,
+				&mut PriceCache::default(),
+				Some(&keeper),
+			);
+			tasks_left.extend(rest);
+			TaskQueue::<T>::put(tasks_left);
+
+			Ok(Some(max_weight.saturating_sub(weight_left).saturating_add(task_queue_weight))
+				.into())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Whether `who` may update the price of every asset pair in an `update_asset_prices` call.
+		pub fn is_oracle_provider_for(who: &AccountOf<T>, updates: &[PriceUpdate]) -> bool {
+			if T::OracleCommittee::contains(who) {
+				return true
+			}
+
+			updates.iter().all(|update| {
+				Self::get_asset_registry_info((
+					&update.chain,
+					&update.exchange,
+					(&update.asset1, &update.asset2),
+				))
+				.map_or(false, |info| info.oracle_providers.contains(who))
+			})
+		}
+
+		/// The symbol and decimals of an asset pair, if it is initialized.
+		pub fn get_asset_metadata(
+			chain: ChainName,
+			exchange: Exchange,
+			asset1: AssetName,
+			asset2: AssetName,
+		) -> Option<AssetMetadata> {
+			let key = (&chain, &exchange, (&asset1, &asset2));
+			Self::get_asset_registry_info(key).map(|info| AssetMetadata {
+				symbol: Self::get_asset_symbol(key).unwrap_or_default(),
+				decimal: info.decimal,
+			})
+		}
+
+		/// Convert `price`, given with `decimal` decimals, to the `PriceDecimals` decimals of the
+		/// fixed-point representation of `AssetPrice`. Returns `None` when the price overflows.
+		pub fn normalize_price(price: AssetPrice, decimal: u8) -> Option<AssetPrice> {
+			let decimals = T::PriceDecimals::get();
+			if decimal <= decimals {
+				10u128.checked_pow((decimals - decimal).into())?.checked_mul(price)
+			} else {
+				// A scale that does not fit in u128 rounds every price down to zero
+				let scale = 10u128.checked_pow((decimal - decimals).into());
+				Some(scale.map_or(0, |scale| price / scale))
+			}
+		}
+
+		/// The owner of the asset pair `key`.
+		fn asset_owner(
+			key: &(&ChainName, &Exchange, (&AssetName, &AssetName)),
+		) -> AssetOwner<AccountOf<T>> {
+			Self::get_asset_ownership(key)
+				.map_or(AssetOwner::Governance, |ownership| AssetOwner::Account(ownership.owner))
+		}
+
+		/// Ensure `origin` is the asset admin origin, giving `None`, or a signed origin, giving its
+		/// account.
+		fn ensure_asset_admin_or_signed(
+			origin: OriginFor<T>,
+		) -> Result<Option<AccountOf<T>>, DispatchError> {
+			// When enable dev-queue, any origin acts as the asset admin so local development can
+			// happen easier
+			if cfg!(feature = "dev-queue") {
+				return Ok(None)
+			}
+			match T::AssetAdminOrigin::try_origin(origin) {
+				Ok(_) => Ok(None),
+				Err(origin) => Ok(Some(ensure_signed(origin)?)),
+			}
+		}
+
+		/// Ensure `origin` is `owner`: the root origin for governance or a signed origin of the
+		/// owning account.
+		fn ensure_asset_owner(
+			origin: OriginFor<T>,
+			owner: &AssetOwner<AccountOf<T>>,
+		) -> DispatchResult {
+			match owner {
+				AssetOwner::Governance => ensure_root(origin)?,
+				AssetOwner::Account(account) =>
+					ensure!(ensure_signed(origin)? == *account, Error::<T>::NotAssetOwner),
+			}
+			Ok(())
+		}
+
+		/// The id of a task scheduled by `owner_id`, as the hash of the owner, the block, the
+		/// extrinsic and the number of events emitted before it. The event count tells apart the
+		/// tasks scheduled by the same extrinsic, such as the legs of a bracket task.
+		pub fn generate_task_id(owner_id: &AccountOf<T>) -> TaskId {
+			let current_block_number = <frame_system::Pallet<T>>::block_number();
+
+			let tx_id = <frame_system::Pallet<T>>::extrinsic_index().unwrap_or(0);
+
+			let evt_index = <frame_system::Pallet<T>>::event_count();
+
+			blake2_256(&(owner_id, current_block_number, tx_id, evt_index).encode())
+		}
+
+		/// The id of a task scheduled by `owner_id`. A task with a `provided_id` gets the hash of
+		/// the owner and that id, so scheduling it again is caught as a duplicate; the others get
+		/// a fresh id from `generate_task_id`.
+		pub fn task_id_for(owner_id: &AccountOf<T>, provided_id: Option<Vec<u8>>) -> TaskId {
+			match provided_id {
+				Some(provided_id) => blake2_256(&(owner_id, provided_id).encode()),
+				None => Self::generate_task_id(owner_id),
+			}
+		}
+
+		// Move task from the sorted task indexes into TaskQueue that are ready to be process
+		//
+		// At most `MaxShiftedTasksPerAssetPair` tasks of an asset pair are moved in a block, the
+		// others stay in the index until the next block. The tasks of different asset pairs are
+		// queued in turn, so a pair with a large backlog cannot starve the others. The tasks
+		// sharing a trigger price are shuffled, so their order cannot be known ahead of the block.
+		//
+		// The asset pairs are checked until the weight runs out, and the next block picks up from
+		// the last one in `ShiftCursor`.
+		//
+		// Returns the weight left.
+		pub fn shift_tasks(max_weight: Weight, prices: &mut PriceCache<T>) -> Weight {
+			let mut weight_left: Weight =
+				max_weight.saturating_sub(T::DbWeight::get().reads_writes(2u64, 1u64));
+			let (seed, _) = T::Randomness::random(b"automation-price/shift-tasks");
+
+			let max_tasks_per_pair = T::MaxShiftedTasksPerAssetPair::get() as usize;
+			let mut tasks_by_pair = BTreeMap::<_, TaskIdList<T>>::new();
+			let mut shifted_prices = BTreeMap::<_, AssetPrice>::new();
+			// Asset pairs with matched tasks left in the index
+			let mut left_behind = BTreeSet::new();
+
+			let now = Self::get_current_block_time().unwrap_or_default() as u128;
+			let key_weight = Self::shift_key_weight();
+			let resumed_from = Self::get_shift_cursor();
+			let mut last_checked = resumed_from.clone();
+			let mut finished = true;
+			for (index, key) in Self::shift_keys(resumed_from.clone()) {
+				if !Self::has_room_for(weight_left, key_weight) {
+					finished = false;
+					break
+				}
+				last_checked = Some((index, key.clone()));
+
+				// Reading the last shifted price of the asset pair, and its price unless a
+				// previous key already did
+				// TODO: Swap asset to check pair
+				let (current_price_wrap, price_weight) = prices.get(&key.0, &key.1, &key.2);
+				weight_left = weight_left
+					.saturating_sub(T::DbWeight::get().reads(2u64))
+					.saturating_sub(price_weight);
+
+				if current_price_wrap.is_none() {
+					continue
+				};
+				let current_price = current_price_wrap.unwrap();
+				let last_price = Self::get_last_shifted_price((&key.0, &key.1, &key.2));
+				shifted_prices.insert(key.clone(), current_price.value);
+
+				// The unsorted tasks are checked one by one, but only when the price has moved
+				// since they were last checked, or when one of them can match without the price
+				// moving
+				if index == ShiftIndex::Unsorted {
+					if last_price == Some(current_price.value) {
+						continue
+					}
+					let task_ids = Self::get_unsorted_tasks(&key).unwrap_or_default();
+
+					let pair_tasks = tasks_by_pair.entry(key.clone()).or_default();
+					let mut waiting = Vec::new();
+					for (owner_id, task_id) in task_ids.iter().cloned() {
+						weight_left = weight_left.saturating_sub(T::DbWeight::get().reads(1u64));
+						let task = match Self::get_task(&owner_id, &task_id) {
+							Some(task) => task,
+							None => continue,
+						};
+						// A task with extra conditions can wait here for the price of another
+						// asset pair, so all of its conditions are checked
+						let (matched, match_weight) =
+							Self::is_task_condition_match(&task, prices, now);
+						weight_left = weight_left.saturating_sub(match_weight);
+						if !matched {
+							// Keep checking the asset pair until the task matches
+							if Self::task_changes_over_time(&task) {
+								left_behind.insert(key.clone());
+							}
+							waiting.push((owner_id, task_id));
+						} else if pair_tasks.len() < max_tasks_per_pair {
+							// Take it out of the unsorted tasks of its other asset pairs too, so
+							// it is not queued twice
+							if TaskConditions::<T>::contains_key(&owner_id, &task_id) {
+								Self::remove_from_sorted_index(&task);
+								weight_left =
+									weight_left.saturating_sub(Self::remove_compound_task_weight());
+							}
+							pair_tasks.push((owner_id, task_id));
+						} else {
+							left_behind.insert(key.clone());
+							waiting.push((owner_id, task_id));
+						}
+					}
+
+					if waiting.len() != task_ids.len() {
+						weight_left = weight_left.saturating_sub(T::DbWeight::get().writes(1u64));
+						if waiting.is_empty() {
+							UnsortedTasks::<T>::remove(&key);
+						} else {
+							UnsortedTasks::<T>::insert(&key, waiting);
+						}
+					}
+					continue
+				}
+
+				let trigger_func = if index == ShiftIndex::SortedAbove {
+					TriggerFunction::Gt
+				} else {
+					TriggerFunction::Lt
+				};
+
+				// Only the side the price moved towards can have new matches
+				let moved_towards = match last_price {
+					Some(last_price) if trigger_func == TriggerFunction::Gt =>
+						current_price.value > last_price,
+					Some(last_price) => current_price.value < last_price,
+					None => true,
+				};
+				if !moved_towards {
+					continue
+				}
+
+				// Example: sell orders
+				//
+				// In the list we had tasks such as
+				//  - task1: sell when price > 10
+				//  - task2: sell when price > 20
+				//  - task3: sell when price > 30
+				//  If price used to be 5, and now it's 15, task1 got run
+				//  If price used to be 5, and now it's 25, task1 and task2 got run
+				//  If price used to be 5, and now it's 35, all tasks are run
+				//
+				// Example: buy orders
+				//
+				// In the list we had tasks such as
+				//  - task1: buy when price < 10
+				//  - task2: buy when price < 20
+				//  - task3: buy when price < 30
+				//  If price used to be 500, and now it's 25, task3 got run
+				//  If price used to be 500, and now it's 15, task2 and task3 got run
+				//  If price used to be 500, and now it's 5,  all tasks are run
+				//
+				//  TODO: handle atomic and transaction
+				//
+				// Only the buckets up to the one of the current price can have matches, and they
+				// are drained in the order the price crossed them
+				let current_bucket = Self::price_bucket(current_price.value);
+				let buckets = Self::get_sorted_buckets(&key, trigger_func);
+				let crossed: Vec<u128> = match trigger_func {
+					TriggerFunction::Gt => buckets.range(..=current_bucket).copied().collect(),
+					TriggerFunction::Lt => buckets.range(current_bucket..).rev().copied().collect(),
+				};
+				weight_left = weight_left.saturating_sub(T::DbWeight::get().reads(1u64));
+
+				// Queue tasks in the order the price crossed their targets
+				let next_triggered = |triggered: &mut BTreeMap<_, _>| match trigger_func {
+					TriggerFunction::Gt => triggered.pop_first(),
+					TriggerFunction::Lt => triggered.pop_last(),
+				};
+
+				let pair_tasks = tasks_by_pair.entry(key.clone()).or_default();
+				for bucket in crossed {
+					if pair_tasks.len() >= max_tasks_per_pair {
+						left_behind.insert(key.clone());
+						break
+					}
+
+					weight_left = weight_left.saturating_sub(T::DbWeight::get().reads(1u64));
+					let mut tasks = Self::get_sorted_bucket(&key, trigger_func, bucket);
+					let mut triggered =
+						split_off_triggered(trigger_func, &mut tasks, &current_price);
+					if triggered.is_empty() {
+						continue
+					}
+
+					while let Some((price, task_ids)) = next_triggered(&mut triggered) {
+						let room = max_tasks_per_pair.saturating_sub(pair_tasks.len());
+						if room == 0 {
+							tasks.insert(price, task_ids);
+							left_behind.insert(key.clone());
+							break
+						}
+
+						let mut task_ids = Self::shuffle_tasks(task_ids, &seed);
+
+						// The tasks that do not fit stay in the index
+						if task_ids.len() > room {
+							tasks.insert(price, task_ids.split_off(room));
+							left_behind.insert(key.clone());
+						}
+						pair_tasks.extend(task_ids);
+					}
+					tasks.append(&mut triggered);
+
+					weight_left = weight_left.saturating_sub(T::DbWeight::get().writes(2u64));
+					Self::put_sorted_bucket(&key, trigger_func, bucket, tasks);
+				}
+			}
+
+			if finished {
+				ShiftCursor::<T>::kill();
+			} else {
+				ShiftCursor::<T>::set(last_checked);
+			}
+
+			// A price is only a shifted price once every asset pair was checked against it in the
+			// same block, otherwise the asset pair is checked on both sides next time
+			let checked_all = finished && resumed_from.is_none();
+			weight_left =
+				weight_left.saturating_sub(T::DbWeight::get().writes(shifted_prices.len() as u64));
+			for (key, price) in shifted_prices {
+				// The tasks left behind are only shifted if the price is checked again
+				if left_behind.contains(&key) || !checked_all {
+					LastShiftedPrice::<T>::remove(key);
+				} else {
+					LastShiftedPrice::<T>::insert(key, price);
+				}
+			}
+
+			let task_to_process = &mut Self::interleave(tasks_by_pair.into_values().collect());
+
+			let shift_weight = <T as Config>::WeightInfo::shift_tasks(task_to_process.len() as u32);
+			weight_left = weight_left.saturating_sub(shift_weight);
+			if !task_to_process.is_empty() {
+				if TaskQueue::<T>::exists() {
+					let mut old_task = TaskQueue::<T>::get();
+					old_task.append(task_to_process);
+					TaskQueue::<T>::put(old_task);
+				} else {
+					TaskQueue::<T>::put(task_to_process);
+				};
+			}
+
+			weight_left
+		}
+
+		// The keys of the trigger indexes in the order they are checked, starting after `cursor`.
+		fn shift_keys(
+			cursor: Option<(ShiftIndex, (ChainName, Exchange, AssetPair))>,
+		) -> impl Iterator<Item = (ShiftIndex, (ChainName, Exchange, AssetPair))> {
+			let (start, after) = match cursor {
+				Some((index, key)) => (index, Some(key)),
+				None => (ShiftIndex::SortedAbove, None),
+			};
+
+			let above = match (start, &after) {
+				(ShiftIndex::SortedAbove, Some(key)) =>
+					Some(SortedBucketsAbove::<T>::iter_keys_from(
+						SortedBucketsAbove::<T>::hashed_key_for(key),
+					)),
+				(ShiftIndex::SortedAbove, None) => Some(SortedBucketsAbove::<T>::iter_keys()),
+				_ => None,
+			};
+			let below = match (start, &after) {
+				(ShiftIndex::SortedBelow, Some(key)) =>
+					Some(SortedBucketsBelow::<T>::iter_keys_from(
+						SortedBucketsBelow::<T>::hashed_key_for(key),
+					)),
+				(ShiftIndex::Unsorted, _) => None,
+				_ => Some(SortedBucketsBelow::<T>::iter_keys()),
+			};
+			let unsorted = match (start, &after) {
+				(ShiftIndex::Unsorted, Some(key)) =>
+					UnsortedTasks::<T>::iter_keys_from(UnsortedTasks::<T>::hashed_key_for(key)),
+				_ => UnsortedTasks::<T>::iter_keys(),
+			};
+
+			above
+				.into_iter()
+				.flatten()
+				.map(|key| (ShiftIndex::SortedAbove, key))
+				.chain(below.into_iter().flatten().map(|key| (ShiftIndex::SortedBelow, key)))
+				.chain(unsorted.map(|key| (ShiftIndex::Unsorted, key)))
+		}
+
+		// Reading the price and the index of an asset pair, then writing the index back. The
+		// tasks it matches are accounted for once they are all shifted.
+		fn shift_key_weight() -> Weight {
+			T::DbWeight::get().reads_writes(6u64, 1u64)
+		}
+
+		// Order tasks by a hash of their id and the seed of the block.
+		fn shuffle_tasks(mut task_ids: TaskIdList<T>, seed: &T::Hash) -> TaskIdList<T> {
+			task_ids.sort_by_cached_key(|(_, task_id)| T::Hashing::hash_of(&(seed, task_id)));
+			task_ids
+		}
+
+		// Merge the task lists taking one task from each list in turn.
+		fn interleave(task_lists: Vec<TaskIdList<T>>) -> TaskIdList<T> {
+			let mut queues: Vec<_> =
+				task_lists.into_iter().map(|tasks| tasks.into_iter()).collect();
+			let mut merged = Vec::new();
+			loop {
+				let before = merged.len();
+				merged.extend(queues.iter_mut().filter_map(|tasks| tasks.next()));
+				if merged.len() == before {
+					return merged
+				}
+			}
+		}
+
+		/// Trigger tasks for the block time.
+		///
+		/// Complete as many tasks as possible given the maximum weight and return the weight used.
+		pub fn trigger_tasks(max_weight: Weight) -> Weight {
+			let check_time_and_deletion_weight = T::DbWeight::get().reads(2u64);
+			if max_weight.any_lt(check_time_and_deletion_weight) {
+				return Weight::zero()
+			}
+
+			// Recurring tasks whose cooldown has passed, and deferred tasks whose time window
+			// opened, can match again
+			let now = Self::get_current_block_time().unwrap_or_default() as u128;
+			let arm_weight = Self::arm_cooled_down_tasks(now);
+
+			// Prices do not change until the end of the block
+			let mut prices = PriceCache::<T>::default();
+			let mut weight_left =
+				Self::shift_tasks(max_weight.saturating_sub(arm_weight), &mut prices);
+
+			// The XCMP tasks whose message could not be sent before have waited the longest
+			weight_left = Self::run_xcmp_retries(weight_left, &mut prices);
+
+			// Now we can run those tasks
+			// TODO: We need to calculate enough weight and balance the tasks so we won't be skew
+			// by a particular kind of task asset
+			//
+			// Now we run as much task as possible
+			// If weight is over, task will be picked up next time
+			// If the price is no longer matched, they will be put back into the TaskRegistry
+			let task_queue = Self::get_task_queue();
+
+			// Reading the whole backlog and writing back the tasks that were not run
+			let queue_weight =
+				<T as Config>::WeightInfo::update_task_queue(task_queue.len() as u32);
+			weight_left = weight_left.saturating_sub(queue_weight);
+			let mut queue_drained = true;
+			if !task_queue.is_empty() {
+				let (tasks_left, new_weight_left) =
+					Self::run_tasks(task_queue, weight_left, &mut prices);
+				weight_left = new_weight_left;
+				queue_drained = tasks_left.is_empty();
+				TaskQueue::<T>::put(tasks_left);
+			}
+
+			// The tasks that missed their price condition run after every triggered task did
+			if queue_drained {
+				weight_left = Self::run_missed_tasks(weight_left, &mut prices);
+			}
+
+			max_weight.saturating_sub(weight_left)
+		}
+
+		// Run the tasks of MissedTasksQueue whatever their price, unless they expired in the
+		// meantime. The tasks the weight is not enough for run in a later block.
+		//
+		// Returns the weight left.
+		fn run_missed_tasks(mut weight_left: Weight, prices: &mut PriceCache<T>) -> Weight {
+			weight_left = weight_left.saturating_sub(T::DbWeight::get().reads(1u64));
+			let missed_tasks = Self::get_missed_tasks_queue();
+			if missed_tasks.is_empty() {
+				return weight_left
+			}
+
+			let now = Self::get_current_block_time().unwrap_or_default() as u128;
+			let queue_weight =
+				<T as Config>::WeightInfo::update_task_queue(missed_tasks.len() as u32);
+			weight_left = weight_left.saturating_sub(queue_weight);
+
+			let mut settled: Vec<Task<T>> = vec![];
+			let mut consumed_task_index: usize = 0;
+			for (owner_id, task_id) in missed_tasks.iter() {
+				let run_another_task_weight = <T as Config>::WeightInfo::emit_event()
+					.saturating_add(T::DbWeight::get().reads_writes(1u64, 1u64));
+				if weight_left.any_lt(run_another_task_weight) {
+					break
+				}
+				consumed_task_index.saturating_inc();
+
+				let task_weight = match Self::get_task(owner_id, task_id) {
+					// The task was removed since it missed its condition
+					None => T::DbWeight::get().reads(1u64),
+					Some(_) if Self::is_task_paused(owner_id, task_id) =>
+						T::DbWeight::get().reads(2u64),
+					Some(task) if task.expired_at < now => {
+						let condition =
+							TaskCondition::AlreadyExpired { expired_at: task.expired_at, now };
+						Self::expire_task(
+							&task,
+							Event::TaskExpired {
+								owner_id: task.owner_id.clone(),
+								task_id: task.task_id,
+								condition,
+							},
+						);
+						Self::expire_task_weight().saturating_add(T::DbWeight::get().reads(1u64))
+					},
+					Some(task) => Self::execute_task(task, 0, now, None, &mut settled)
+						.saturating_add(T::DbWeight::get().reads(1u64)),
+				};
+				weight_left = weight_left.saturating_sub(task_weight);
+			}
+
+			Self::settle_tasks(settled, prices);
+			if consumed_task_index == missed_tasks.len() {
+				MissedTasksQueue::<T>::kill();
+			} else {
+				MissedTasksQueue::<T>::put(missed_tasks[consumed_task_index..].to_vec());
+			}
+
+			weight_left
+		}
+
+		pub fn create_new_asset(
+			chain: ChainName,
+			exchange: Exchange,
+			asset1: AssetName,
+			asset2: AssetName,
+			decimal: u8,
+			asset_owners: Vec<AccountOf<T>>,
+		) -> Result<(), DispatchError> {
+			let key = (&chain, &exchange, (&asset1, &asset2));
+
+			if AssetRegistry::<T>::contains_key(&key) {
+				Err(Error::<T>::AssetAlreadyInitialized)?
+			}
+			let asset_count = Self::get_asset_count();
+			ensure!(asset_count < T::MaxAssets::get(), Error::<T>::AssetLimitReached);
+
+			let asset_info = RegistryInfo::<T> {
+				decimal,
+				round: 0,
+				last_update: 0,
+				oracle_providers: asset_owners,
+			};
+
+			AssetRegistry::<T>::insert(key, asset_info);
+			AssetCount::<T>::put(asset_count.saturating_add(1));
+
+			Self::deposit_event(Event::AssetCreated { chain, exchange, asset1, asset2, decimal });
+			Ok(())
+		}
+
+		pub fn get_current_time_slot() -> Result<UnixTime, Error<T>> {
+			let now = <timestamp::Pallet<T>>::get().saturated_into::<UnixTime>();
+			if now == 0 {
+				Err(Error::<T>::BlockTimeNotSet)?
+			}
+			let now = now.saturating_div(1000);
+			let diff_to_min = now % 60;
+			Ok(now.saturating_sub(diff_to_min))
+		}
+
+		pub fn run_xcmp_task(
+			destination: MultiLocation,
+			caller: T::AccountId,
+			fee: AssetPayment,
+			encoded_call: Vec<u8>,
+			encoded_call_weight: Weight,
+			overall_weight: Weight,
+			flow: InstructionSequence,
 		) -> (Weight, Option<DispatchError>) {
 			let fee_asset_location = MultiLocation::try_from(fee.asset_location);
 			if fee_asset_location.is_err() {
@@ -1045,237 +3494,1462 @@ pub mod pallet {
 					Some(Error::<T>::BadVersion.into()),
 				)
 			}
-			let fee_asset_location = fee_asset_location.unwrap();
+			let fee_asset_location = fee_asset_location.unwrap();
+
+			// A message that could not be sent leaves nothing behind, so that it can be sent again
+			let result = with_transaction(|| {
+				let result = T::XcmpTransactor::transact_xcm(
+					destination,
+					fee_asset_location,
+					fee.amount,
+					caller,
+					encoded_call,
+					encoded_call_weight,
+					overall_weight,
+					flow,
+				);
+				match result {
+					Ok(_) => TransactionOutcome::Commit(Ok(result)),
+					Err(_) => TransactionOutcome::Rollback(Ok(result)),
+				}
+			})
+			.unwrap_or_else(|error: DispatchError| Err(error.into()));
+
+			match result {
+				Ok(post_info) => (Self::xcmp_task_weight(post_info.actual_weight), None),
+				Err(e) => (Self::xcmp_task_weight(e.post_info.actual_weight), Some(e.error)),
+			}
+		}
+
+		/// The number of bytes a task takes from its scheduler: the given byte strings and the
+		/// trigger params. The weight of scheduling the task grows with it.
+		pub fn task_input_len(inputs: &[&[u8]], trigger_params: &[u128]) -> u32 {
+			let params_len = trigger_params.len().saturating_mul(sp_std::mem::size_of::<u128>());
+			let len = inputs.iter().fold(params_len, |len, input| len.saturating_add(input.len()));
+			len.saturated_into()
+		}
+
+		/// Attempt to decode and run the call as `caller`, limited to the calls of
+		/// `ScheduleAllowList`.
+		pub fn run_dynamic_dispatch_action(
+			caller: AccountOf<T>,
+			encoded_call: Vec<u8>,
+		) -> (Weight, Option<DispatchError>) {
+			match <T as Config>::RuntimeCall::decode(&mut &*encoded_call) {
+				Ok(scheduled_call) => {
+					let mut dispatch_origin: T::RuntimeOrigin =
+						frame_system::RawOrigin::Signed(caller).into();
+					dispatch_origin.add_filter(
+						|call: &<T as frame_system::Config>::RuntimeCall| {
+							T::ScheduleAllowList::contains(call)
+						},
+					);
+
+					let call_weight = scheduled_call.get_dispatch_info().weight;
+
+					let (maybe_actual_call_weight, result) =
+						match scheduled_call.dispatch(dispatch_origin) {
+							Ok(post_info) => (post_info.actual_weight, Ok(())),
+							Err(error_and_info) =>
+								(error_and_info.post_info.actual_weight, Err(error_and_info.error)),
+						};
+
+					(
+						maybe_actual_call_weight.unwrap_or(call_weight).saturating_add(
+							<T as Config>::WeightInfo::run_dynamic_dispatch_action(),
+						),
+						result.err(),
+					)
+				},
+				Err(_) => (
+					<T as Config>::WeightInfo::run_dynamic_dispatch_action_fail_decode(),
+					Some(Error::<T>::CallCannotBeDecoded.into()),
+				),
+			}
+		}
+
+		/// The weight of an XCMP task given the weight its local XCM execution actually used.
+		fn xcmp_task_weight(local_execution_weight: Option<Weight>) -> Weight {
+			<T as Config>::WeightInfo::run_xcmp_task()
+				.saturating_add(local_execution_weight.unwrap_or_default())
+		}
+
+		/// Remove the oracle submissions made `OracleSubmissionRetention` blocks before `now`.
+		pub fn prune_oracle_submissions(now: T::BlockNumber) -> Weight {
+			let retention = T::OracleSubmissionRetention::get();
+			if now <= retention {
+				return Weight::zero()
+			}
+
+			OracleSubmissions::<T>::remove(now - retention);
+			T::DbWeight::get().writes(1u64)
+		}
+
+		// return epoch time of current block
+		pub fn get_current_block_time() -> Result<UnixTime, DispatchError> {
+			let now = <timestamp::Pallet<T>>::get()
+				.checked_into::<UnixTime>()
+				.ok_or(ArithmeticError::Overflow)?;
+
+			if now == 0 {
+				Err(Error::<T>::BlockTimeNotSet)?;
+			}
+
+			let now = now.checked_div(1000).ok_or(ArithmeticError::Overflow)?;
+			Ok(now)
+		}
+
+		// Check whether a task can run or not based on its expiration and price.
+		//
+		// A task can be queued but got expired when it's about to run, in that case, we don't want
+		// it to be run.
+		//
+		// Or the price might move by the time task is invoked, we don't want it to get run either.
+		//
+		// Returns the price the task matched when it can run.
+		fn task_can_run(task: &Task<T>, prices: &mut PriceCache<T>) -> (Option<PriceData>, Weight) {
+			let mut consumed_weight: Weight = Weight::from_ref_time(0);
+
+			// If we cannot extract time from the block, then somthing horrible wrong, let not move
+			// forward
+			let current_block_time = Self::get_current_block_time();
+			if current_block_time.is_err() {
+				return (None, consumed_weight)
+			}
+
+			let now = current_block_time.unwrap();
+
+			if task.expired_at < now.into() {
+				consumed_weight = consumed_weight.saturating_add(Self::expire_task_weight());
+
+				Self::expire_task(
+					task,
+					Event::TaskExpired {
+						owner_id: task.owner_id.clone(),
+						task_id: task.task_id,
+						condition: TaskCondition::AlreadyExpired {
+							expired_at: task.expired_at,
+							now: now.into(),
+						},
+					},
+				);
+
+				return (None, consumed_weight)
+			}
+
+			// read storage once per asset pair to get the price
+			let (price, price_weight) = prices.get(&task.chain, &task.exchange, &task.asset_pair);
+			consumed_weight = consumed_weight.saturating_add(price_weight);
+			if let Some(this_task_asset_price) = price {
+				let (matched, match_weight) =
+					Self::is_task_condition_match(task, prices, now.into());
+				consumed_weight = consumed_weight.saturating_add(match_weight);
+				if matched {
+					return (Some(this_task_asset_price), consumed_weight)
+				} else {
+					let condition = TaskCondition::PriceAlreadyMoved {
+						chain: task.chain.clone(),
+						exchange: task.exchange.clone(),
+						asset_pair: task.asset_pair.clone(),
+						price: this_task_asset_price.value,
+
+						target_price: task.trigger_params[0],
+					};
+					Self::deposit_event(Event::PriceAlreadyMoved {
+						owner_id: task.owner_id.clone(),
+						task_id: task.task_id,
+						condition: condition.clone(),
+					});
+
+					let missed_weight = Self::miss_task(task, condition);
+					return (None, consumed_weight.saturating_add(missed_weight))
+				}
+			}
+
+			// The price is missing or stale, so the task waits in the index for a fresh one
+			Self::insert_into_sorted_index(task);
+			consumed_weight = consumed_weight.saturating_add(T::DbWeight::get().reads_writes(2, 2));
+			(None, consumed_weight)
+		}
+
+		// Handle a task that was triggered but whose condition no longer matches when it runs,
+		// according to its missed task policy. Returns the weight used.
+		fn miss_task(task: &Task<T>, condition: TaskCondition) -> Weight {
+			let policy = Self::get_missed_task_policy(&task.owner_id, &task.task_id);
+			Self::deposit_event(Event::TaskMissed {
+				owner_id: task.owner_id.clone(),
+				task_id: task.task_id,
+				condition,
+				policy,
+			});
+
+			let policy_weight = match policy {
+				MissedTaskPolicy::ExecuteLate => {
+					MissedTasksQueue::<T>::append((task.owner_id.clone(), task.task_id));
+					T::DbWeight::get().writes(1u64)
+				},
+				MissedTaskPolicy::Skip => {
+					Self::insert_into_sorted_index(task);
+					T::DbWeight::get().reads_writes(2u64, 2u64)
+				},
+				MissedTaskPolicy::CancelAndRefund => {
+					Self::expire_task(
+						task,
+						Event::TaskCancelled {
+							owner_id: task.owner_id.clone(),
+							task_id: task.task_id,
+						},
+					);
+					Self::expire_task_weight()
+				},
+			};
+			<T as Config>::WeightInfo::emit_event()
+				.saturating_add(T::DbWeight::get().reads(1u64))
+				.saturating_add(policy_weight)
+		}
+
+		/// Whether the asset pair is halted by its circuit breaker at `now`. An asset pair whose
+		/// cooldown is over resumes.
+		pub fn is_asset_halted(
+			chain: &[u8],
+			exchange: &[u8],
+			asset_pair: &AssetPair,
+			now: u128,
+		) -> bool {
+			let halted_at = match HaltedAssets::<T>::get((chain, exchange, asset_pair)) {
+				Some(halted_at) => halted_at,
+				None => return false,
+			};
+			let cooldown_over = Self::get_circuit_breaker((chain, exchange, asset_pair))
+				.and_then(|circuit_breaker| circuit_breaker.cooldown)
+				.map_or(false, |cooldown| now >= halted_at.saturating_add(cooldown.into()));
+			if cooldown_over {
+				Self::resume_halted_asset(chain, exchange, asset_pair);
+			}
+			!cooldown_over
+		}
+
+		// Let the tasks of a halted asset pair be triggered again.
+		fn resume_halted_asset(chain: &[u8], exchange: &[u8], asset_pair: &AssetPair) {
+			HaltedAssets::<T>::remove((chain, exchange, asset_pair));
+			Self::deposit_event(Event::AssetResumed {
+				chain: chain.to_vec(),
+				exchange: exchange.to_vec(),
+				asset_pair: asset_pair.clone(),
+			});
+		}
+
+		/// Whether the price is older than `MaxPriceStaleness` at `now`.
+		pub fn is_price_stale(price: &PriceData, now: u128) -> bool {
+			now.saturating_sub(price.updated_at) > T::MaxPriceStaleness::get().into()
+		}
+
+		/// Runs as many tasks as the weight allows from the provided vec of task_ids.
+		///
+		/// Returns a vec with the tasks that were not run and the remaining weight.
+		pub fn run_tasks(
+			task_ids: TaskIdList<T>,
+			weight_left: Weight,
+			prices: &mut PriceCache<T>,
+		) -> (TaskIdList<T>, Weight) {
+			Self::run_queued_tasks(task_ids, weight_left, prices, None)
+		}
+
+		// Run the tasks like `run_tasks`, paying the reward held for each task that runs to
+		// `keeper` when a keeper runs them.
+		fn run_queued_tasks(
+			mut task_ids: TaskIdList<T>,
+			mut weight_left: Weight,
+			prices: &mut PriceCache<T>,
+			keeper: Option<&AccountOf<T>>,
+		) -> (TaskIdList<T>, Weight) {
+			let mut consumed_task_index: usize = 0;
+
+			// If we cannot extract time from the block, then somthing horrible wrong, let not move
+			// forward
+			let current_block_time = Self::get_current_block_time();
+			if current_block_time.is_err() {
+				return (task_ids, weight_left)
+			}
+
+			let now = current_block_time.unwrap() as u128;
+
+			// The tasks with a higher tip run first. The sort is stable, so the tasks with the same
+			// tip keep the order they were triggered in.
+			task_ids.sort_by_cached_key(|(owner_id, task_id)| {
+				Reverse(Self::get_task_tip(owner_id, task_id).unwrap_or_default())
+			});
+			weight_left =
+				weight_left.saturating_sub(T::DbWeight::get().reads(task_ids.len() as u64));
+
+			// The tasks run from the queue, settled together once the loop is over
+			let mut settled: Vec<Task<T>> = vec![];
+
+			for (owner_id, task_id) in task_ids.iter() {
+				consumed_task_index.saturating_inc();
+
+				let action_weight = match Self::get_task(owner_id, task_id) {
+					None if Tasks::<T>::contains_key(owner_id, task_id) => {
+						Self::remove_undecodable_task(owner_id, task_id);
+						<T as Config>::WeightInfo::remove_task()
+							.saturating_add(T::DbWeight::get().reads(1u64))
+					},
+					None => {
+						Self::deposit_event(Event::TaskNotFound {
+							owner_id: owner_id.clone(),
+							task_id: *task_id,
+						});
+						<T as Config>::WeightInfo::emit_event()
+							.saturating_add(T::DbWeight::get().reads(1u64))
+					},
+					// Paused tasks are put back into the index when they are resumed
+					Some(_) if Self::is_task_paused(owner_id, task_id) =>
+						T::DbWeight::get().reads(2u64),
+					Some(task) => {
+						let (matched_price, test_can_run_weight) =
+							Self::task_can_run(&task, prices);
+
+						match matched_price {
+							None => test_can_run_weight,
+							Some(_) if !Self::is_in_time_window(&task, now) =>
+								Self::defer_task(&task, now).saturating_add(test_can_run_weight),
+							Some(price) => {
+								Self::deposit_event(Event::TaskTriggered {
+									owner_id: task.owner_id.clone(),
+									task_id: task.task_id,
+									asset_pair: task.asset_pair.clone(),
+									trigger_price: price.value,
+									oracle_round: price.round,
+									condition: TaskCondition::TargetPriceMatched {
+										chain: task.chain.clone(),
+										exchange: task.exchange.clone(),
+										asset_pair: task.asset_pair.clone(),
+										price: price.value,
+									},
+								});
+
+								Self::execute_task(task, 0, now, keeper, &mut settled)
+									.saturating_add(test_can_run_weight)
+							},
+						}
+					},
+				};
+
+				weight_left = weight_left.saturating_sub(action_weight);
+
+				let run_another_task_weight = <T as Config>::WeightInfo::emit_event()
+					.saturating_add(T::DbWeight::get().writes(1u64))
+					.saturating_add(T::DbWeight::get().reads(1u64));
+				if weight_left.any_lt(run_another_task_weight) {
+					break
+				}
+			}
+
+			Self::settle_tasks(settled, prices);
+
+			if consumed_task_index == task_ids.len() {
+				(vec![], weight_left)
+			} else {
+				(task_ids.split_off(consumed_task_index), weight_left)
+			}
+		}
+
+		// Run the action of a task whose condition matched, then re-arm the task if it recurs or
+		// remove it. `failed_sends` is the number of times the message of an XCMP task was already
+		// sent without success. The reward held for the task is paid to `keeper`, if a keeper runs
+		// it. A removed task is added to `settled`. Returns the weight of the run.
+		fn execute_task(
+			task: Task<T>,
+			failed_sends: u32,
+			now: u128,
+			keeper: Option<&AccountOf<T>>,
+			settled: &mut Vec<Task<T>>,
+		) -> Weight {
+			let action_outcome = match &task.action {
+				Action::XCMP { destination, .. } =>
+					ActionOutcome::XcmSent { destination: *destination },
+				Action::DynamicDispatch { .. } => ActionOutcome::Dispatched,
+			};
+			let (task_action_weight, task_dispatch_error) = match task.action.clone() {
+				Action::XCMP {
+					destination,
+					execution_fee,
+					schedule_as,
+					encoded_call,
+					encoded_call_weight,
+					overall_weight,
+					instruction_sequence,
+					..
+				} => Self::run_xcmp_task(
+					destination,
+					schedule_as.unwrap_or(task.owner_id.clone()),
+					execution_fee,
+					encoded_call,
+					encoded_call_weight,
+					overall_weight,
+					instruction_sequence,
+				),
+				Action::DynamicDispatch { encoded_call, .. } =>
+					Self::run_dynamic_dispatch_action(task.owner_id.clone(), encoded_call),
+			};
+			// The tip is collected whether the action succeeded or not
+			let task_action_weight =
+				task_action_weight.saturating_add(Self::collect_task_tip(&task));
+			// A keeper that runs the task is paid its reward right away
+			let task_action_weight = if keeper.is_some() {
+				task_action_weight.saturating_add(Self::release_keeper_reward(&task, keeper))
+			} else {
+				task_action_weight
+			};
+
+			let outcome = match task_dispatch_error {
+				Some(error) if T::XcmpTransactor::is_delivery_failure(&error) => {
+					let attempts = failed_sends.saturating_add(1);
+					return task_action_weight
+						.saturating_add(Self::retry_xcmp_send(task, attempts, error, now, settled))
+				},
+				Some(error) => Event::<T>::TaskExecutionFailed {
+					owner_id: task.owner_id.clone(),
+					task_id: task.task_id,
+					error,
+				},
+				None => Event::<T>::TaskExecuted {
+					owner_id: task.owner_id.clone(),
+					task_id: task.task_id,
+					action_outcome,
+				},
+			};
+
+			task_action_weight.saturating_add(Self::complete_task(task, outcome, now, settled))
+		}
+
+		// Remove a task that ran from the sorted task index and report `outcome`. A recurring task
+		// stays scheduled and waits out its cooldown, any other task is completed.
+		fn complete_task(
+			task: Task<T>,
+			outcome: Event<T>,
+			now: u128,
+			settled: &mut Vec<Task<T>>,
+		) -> Weight {
+			Self::remove_from_sorted_index(&task);
+			// The reward of this run goes to the fee revenue unless a keeper took it, so a re-armed
+			// task only holds the reward of its next run
+			let keeper_reward_weight = Self::release_keeper_reward(&task, None);
+			let rearmed = Self::rearm_task(&task, now);
+			if !rearmed {
+				Tasks::<T>::remove(&task.owner_id, &task.task_id);
+				MissedTaskPolicies::<T>::remove(&task.owner_id, &task.task_id);
+				// A task scheduled again with the same provided id must not inherit the window
+				TaskTimeWindows::<T>::remove(&task.owner_id, &task.task_id);
+				TaskConditions::<T>::remove(&task.owner_id, &task.task_id);
+				Self::decrement_asset_task_count((&task.chain, &task.exchange, &task.asset_pair));
+			}
+
+			T::OnTaskExecuted::on_task_executed(matches!(outcome, Event::TaskExecuted { .. }));
+			Self::deposit_event(outcome);
+
+			let mut linked_task_weight = T::DbWeight::get().reads(1u64);
+			if !rearmed {
+				Self::deposit_event(Event::<T>::TaskCompleted {
+					owner_id: task.owner_id.clone(),
+					task_id: task.task_id,
+				});
+				if Self::cancel_linked_task(&task.owner_id, &task.task_id) {
+					linked_task_weight =
+						linked_task_weight.saturating_add(Self::cancel_linked_task_weight());
+				}
+				settled.push(task);
+			}
+
+			linked_task_weight
+				.saturating_add(<T as Config>::WeightInfo::remove_task())
+				.saturating_add(keeper_reward_weight)
+				.saturating_add(Self::rearm_task_weight())
+				.saturating_add(T::DbWeight::get().writes(5u64))
+				.saturating_add(T::DbWeight::get().reads(2u64))
+		}
+
+		// Queue an XCMP task whose message could not be sent to send it again after a delay that
+		// doubles with every attempt. Once the task ran out of attempts, or the retry queue is
+		// full, the task is given up and the execution fee of the run refunded.
+		fn retry_xcmp_send(
+			task: Task<T>,
+			attempts: u32,
+			error: DispatchError,
+			now: u128,
+			settled: &mut Vec<Task<T>>,
+		) -> Weight {
+			let retry_count = Self::get_xcmp_retry_count();
+			if attempts < T::MaxXcmpSendAttempts::get() && retry_count < T::MaxXcmpRetries::get() {
+				let backoff = 2u32.saturating_pow(attempts.saturating_sub(1));
+				let delay = T::XcmpRetryDelay::get().saturating_mul(backoff.into());
+				let retry_at =
+					<frame_system::Pallet<T>>::block_number().saturating_add(delay.max(One::one()));
+
+				Self::remove_from_sorted_index(&task);
+				XcmpRetryQueue::<T>::append(
+					retry_at,
+					XcmpRetry { owner_id: task.owner_id.clone(), task_id: task.task_id, attempts },
+				);
+				XcmpRetryCount::<T>::put(retry_count.saturating_add(1));
+				Self::deposit_event(Event::<T>::TaskXcmpSendDeferred {
+					owner_id: task.owner_id.clone(),
+					task_id: task.task_id,
+					attempts,
+					retry_at,
+					error,
+				});
+
+				return <T as Config>::WeightInfo::emit_event()
+					.saturating_add(T::DbWeight::get().reads_writes(2u64, 3u64))
+			}
+
+			Self::refund_execution_fee(&task);
+			let outcome = Event::<T>::TaskXcmpSendFailed {
+				owner_id: task.owner_id.clone(),
+				task_id: task.task_id,
+				attempts,
+				error,
+			};
+			Self::complete_task(task, outcome, now, settled)
+				.saturating_add(T::DbWeight::get().reads_writes(2u64, 2u64))
+		}
+
+		// Send again the messages of the XCMP tasks due for a retry in this block. The retries the
+		// weight is not enough for are moved to the next block.
+		//
+		// Returns the weight left.
+		fn run_xcmp_retries(mut weight_left: Weight, prices: &mut PriceCache<T>) -> Weight {
+			weight_left = weight_left.saturating_sub(T::DbWeight::get().reads(1u64));
+			let block_number = <frame_system::Pallet<T>>::block_number();
+			let retries = XcmpRetryQueue::<T>::take(block_number);
+			if retries.is_empty() {
+				return weight_left
+			}
+
+			let now = Self::get_current_block_time().unwrap_or_default() as u128;
+			weight_left = weight_left.saturating_sub(T::DbWeight::get().writes(2u64));
+
+			let mut settled: Vec<Task<T>> = vec![];
+			let mut consumed_retry_index: usize = 0;
+			for retry in retries.iter() {
+				let run_another_task_weight = <T as Config>::WeightInfo::run_xcmp_task()
+					.saturating_add(T::DbWeight::get().reads_writes(2u64, 1u64));
+				if weight_left.any_lt(run_another_task_weight) {
+					break
+				}
+				consumed_retry_index.saturating_inc();
+				XcmpRetryCount::<T>::mutate(|count| *count = count.saturating_sub(1));
+
+				let task_weight = match Self::get_task(&retry.owner_id, &retry.task_id) {
+					// The task was cancelled while it waited
+					None => T::DbWeight::get().reads(1u64),
+					Some(_) if Self::is_task_paused(&retry.owner_id, &retry.task_id) =>
+						T::DbWeight::get().reads(2u64),
+					Some(task) if task.expired_at < now => {
+						let condition =
+							TaskCondition::AlreadyExpired { expired_at: task.expired_at, now };
+						Self::expire_task(
+							&task,
+							Event::TaskExpired {
+								owner_id: task.owner_id.clone(),
+								task_id: task.task_id,
+								condition,
+							},
+						);
+						Self::expire_task_weight().saturating_add(T::DbWeight::get().reads(1u64))
+					},
+					Some(task) => Self::execute_task(task, retry.attempts, now, None, &mut settled)
+						.saturating_add(T::DbWeight::get().reads(1u64)),
+				};
+				weight_left = weight_left
+					.saturating_sub(task_weight)
+					.saturating_sub(T::DbWeight::get().reads_writes(1u64, 1u64));
+			}
+
+			Self::settle_tasks(settled, prices);
+			if consumed_retry_index < retries.len() {
+				let next_block = block_number.saturating_add(One::one());
+				XcmpRetryQueue::<T>::mutate(next_block, |queue| {
+					queue.extend_from_slice(&retries[consumed_retry_index..])
+				});
+			}
 
-			match T::XcmpTransactor::transact_xcm(
-				destination,
-				fee_asset_location,
-				fee.amount,
-				caller,
-				encoded_call,
-				encoded_call_weight,
-				overall_weight,
-				flow,
-			) {
-				Ok(()) => (<T as Config>::WeightInfo::run_xcmp_task(), None),
-				Err(e) => (<T as Config>::WeightInfo::run_xcmp_task(), Some(e)),
+			weight_left
+		}
+
+		/// The sorted tasks of an asset for a trigger function, gathered from every bucket of the
+		/// index of the side the trigger function watches.
+		pub fn get_sorted_tasks_index(
+			key: (ChainName, Exchange, AssetPair, TriggerFunction),
+		) -> Option<BTreeMap<AssetPrice, TaskIdList<T>>> {
+			let (chain, exchange, asset_pair, trigger_function) = key;
+			let key = (chain, exchange, asset_pair);
+			let buckets = Self::get_sorted_buckets(&key, trigger_function);
+			if buckets.is_empty() {
+				return None
 			}
+
+			let mut tasks = BTreeMap::new();
+			for bucket in buckets {
+				tasks.append(&mut Self::get_sorted_bucket(&key, trigger_function, bucket));
+			}
+			Some(tasks)
 		}
 
-		// return epoch time of current block
-		pub fn get_current_block_time() -> Result<UnixTime, DispatchError> {
-			let now = <timestamp::Pallet<T>>::get()
-				.checked_into::<UnixTime>()
-				.ok_or(ArithmeticError::Overflow)?;
+		/// The sorted `gt` tasks of an asset, gathered from every bucket of `SortedTasksAbove`.
+		pub fn get_sorted_tasks_above(
+			key: &(ChainName, Exchange, AssetPair),
+		) -> Option<BTreeMap<AssetPrice, TaskIdList<T>>> {
+			let (chain, exchange, asset_pair) = key.clone();
+			Self::get_sorted_tasks_index((chain, exchange, asset_pair, TriggerFunction::Gt))
+		}
 
-			if now == 0 {
-				Err(Error::<T>::BlockTimeNotSet)?;
+		/// The sorted `lt` tasks of an asset, gathered from every bucket of `SortedTasksBelow`.
+		pub fn get_sorted_tasks_below(
+			key: &(ChainName, Exchange, AssetPair),
+		) -> Option<BTreeMap<AssetPrice, TaskIdList<T>>> {
+			let (chain, exchange, asset_pair) = key.clone();
+			Self::get_sorted_tasks_index((chain, exchange, asset_pair, TriggerFunction::Lt))
+		}
+
+		// Replace the sorted tasks of an asset for a trigger function, spreading them over the
+		// buckets of their trigger prices.
+		pub(crate) fn put_sorted_tasks_index(
+			key: (ChainName, Exchange, AssetPair, TriggerFunction),
+			tasks: BTreeMap<AssetPrice, TaskIdList<T>>,
+		) {
+			let (chain, exchange, asset_pair, trigger_function) = key;
+			let key = (chain, exchange, asset_pair);
+			for bucket in Self::get_sorted_buckets(&key, trigger_function) {
+				Self::put_sorted_bucket(&key, trigger_function, bucket, BTreeMap::new());
 			}
 
-			let now = now.checked_div(1000).ok_or(ArithmeticError::Overflow)?;
-			Ok(now)
+			let mut buckets: BTreeMap<u128, BTreeMap<AssetPrice, TaskIdList<T>>> = BTreeMap::new();
+			for (price, task_ids) in tasks {
+				buckets.entry(Self::price_bucket(price)).or_default().insert(price, task_ids);
+			}
+			for (bucket, tasks) in buckets {
+				Self::put_sorted_bucket(&key, trigger_function, bucket, tasks);
+			}
 		}
 
-		// Check whether a task can run or not based on its expiration and price.
-		//
-		// A task can be queued but got expired when it's about to run, in that case, we don't want
-		// it to be run.
+		// The bucket of the sorted task indexes that holds the tasks triggered at `price`
+		fn price_bucket(price: AssetPrice) -> u128 {
+			price / T::PriceBucketWidth::get().max(1)
+		}
+
+		// The buckets of the sorted task index of a trigger function that hold tasks of an asset
+		fn get_sorted_buckets(
+			key: &(ChainName, Exchange, AssetPair),
+			trigger_function: TriggerFunction,
+		) -> BTreeSet<u128> {
+			match trigger_function {
+				TriggerFunction::Gt => Self::get_sorted_buckets_above(key),
+				TriggerFunction::Lt => Self::get_sorted_buckets_below(key),
+			}
+			.unwrap_or_default()
+		}
+
+		// The tasks of an asset in one bucket of the sorted task index of a trigger function
+		fn get_sorted_bucket(
+			key: &(ChainName, Exchange, AssetPair),
+			trigger_function: TriggerFunction,
+			bucket: u128,
+		) -> BTreeMap<AssetPrice, TaskIdList<T>> {
+			let (chain, exchange, asset_pair) = key;
+			let key = (chain, exchange, asset_pair, bucket);
+			match trigger_function {
+				TriggerFunction::Gt => SortedTasksAbove::<T>::get(key),
+				TriggerFunction::Lt => SortedTasksBelow::<T>::get(key),
+			}
+			.unwrap_or_default()
+		}
+
+		// Write back one bucket of the sorted task index of a trigger function, removing it once
+		// it is empty and keeping the buckets of the asset up to date.
+		fn put_sorted_bucket(
+			key: &(ChainName, Exchange, AssetPair),
+			trigger_function: TriggerFunction,
+			bucket: u128,
+			tasks: BTreeMap<AssetPrice, TaskIdList<T>>,
+		) {
+			let empty = tasks.is_empty();
+			let update_buckets = |buckets: &mut Option<BTreeSet<u128>>| {
+				let mut updated = buckets.take().unwrap_or_default();
+				if empty {
+					updated.remove(&bucket);
+				} else {
+					updated.insert(bucket);
+				}
+				*buckets = Some(updated).filter(|updated| !updated.is_empty());
+			};
+
+			let (chain, exchange, asset_pair) = key;
+			let bucket_key = (chain, exchange, asset_pair, bucket);
+			match (trigger_function, empty) {
+				(TriggerFunction::Gt, true) => SortedTasksAbove::<T>::remove(bucket_key),
+				(TriggerFunction::Gt, false) => SortedTasksAbove::<T>::insert(bucket_key, tasks),
+				(TriggerFunction::Lt, true) => SortedTasksBelow::<T>::remove(bucket_key),
+				(TriggerFunction::Lt, false) => SortedTasksBelow::<T>::insert(bucket_key, tasks),
+			}
+			match trigger_function {
+				TriggerFunction::Gt => SortedBucketsAbove::<T>::mutate(key, update_buckets),
+				TriggerFunction::Lt => SortedBucketsBelow::<T>::mutate(key, update_buckets),
+			}
+		}
+
+		// Add a task to the sorted task index of its trigger function, or to the unsorted tasks of
+		// its asset. The asset's last shifted price is cleared so the next shift checks the task
+		// even if the price does not move.
 		//
-		// Or the price might move by the time task is invoked, we don't want it to get run either.
-		fn task_can_run(task: &Task<T>) -> (Option<TaskCondition>, Weight) {
-			let mut consumed_weight: Weight = Weight::from_ref_time(0);
+		// A task with extra conditions is added to the unsorted tasks of each asset pair it refers
+		// to instead.
+		pub(crate) fn insert_into_sorted_index(task: &Task<T>) {
+			if let Some(compound) = Self::get_task_conditions(&task.owner_id, &task.task_id) {
+				for key in Self::task_asset_pairs(task, &compound) {
+					LastShiftedPrice::<T>::remove(&key);
+					UnsortedTasks::<T>::append(&key, (task.owner_id.clone(), task.task_id));
+				}
+				return
+			}
 
-			// If we cannot extract time from the block, then somthing horrible wrong, let not move
-			// forward
-			let current_block_time = Self::get_current_block_time();
-			if current_block_time.is_err() {
-				return (None, consumed_weight)
+			LastShiftedPrice::<T>::remove((&task.chain, &task.exchange, &task.asset_pair));
+			let trigger_function = match TriggerFunction::from_bytes(&task.trigger_function) {
+				Some(trigger_function) => trigger_function,
+				None => {
+					UnsortedTasks::<T>::append(
+						(&task.chain, &task.exchange, &task.asset_pair),
+						(task.owner_id.clone(), task.task_id),
+					);
+					return
+				},
+			};
+
+			let key = (task.chain.clone(), task.exchange.clone(), task.asset_pair.clone());
+			// TODO: remove hard code and take right param
+			let target_price = task.trigger_params[0];
+			let bucket = Self::price_bucket(target_price);
+			let mut sorted_tasks = Self::get_sorted_bucket(&key, trigger_function, bucket);
+			sorted_tasks
+				.entry(target_price)
+				.or_default()
+				.push((task.owner_id.clone(), task.task_id));
+			Self::put_sorted_bucket(&key, trigger_function, bucket, sorted_tasks);
+		}
+
+		/// Remove a task whose stored value no longer decodes, for example after a change to the
+		/// task type that was not migrated. Everything kept under the task's id is removed and its
+		/// deposit returned. Its entry in the sorted task index of its asset pair cannot be found
+		/// without the task, and is dropped once the index shifts it into the task queue. The
+		/// asset pair leads the stored task, so the task is still counted off its asset pair when
+		/// only a later field no longer decodes.
+		pub fn remove_undecodable_task(owner_id: &AccountOf<T>, task_id: &TaskId) {
+			log::error!(
+				target: "runtime::automation-price",
+				"Removing task {:?} of {:?} as it cannot be decoded",
+				task_id,
+				owner_id
+			);
+			let task_key = Tasks::<T>::hashed_key_for(owner_id, task_id);
+			let task_head: Option<(AccountOf<T>, TaskId, ChainName, Exchange, AssetPair)> =
+				frame_support::storage::unhashed::get_raw(&task_key)
+					.and_then(|raw_task| Decode::decode(&mut &raw_task[..]).ok());
+			if let Some((owner, id, chain, exchange, asset_pair)) = task_head {
+				if owner == *owner_id && id == *task_id {
+					Self::decrement_asset_task_count((&chain, &exchange, &asset_pair));
+				}
+			}
+			Tasks::<T>::remove(owner_id, task_id);
+			TaskRecurrences::<T>::remove(owner_id, task_id);
+			MissedTaskPolicies::<T>::remove(owner_id, task_id);
+			PausedTasks::<T>::remove(owner_id, task_id);
+			TaskTimeWindows::<T>::remove(owner_id, task_id);
+			TaskConditions::<T>::remove(owner_id, task_id);
+			Self::release_task_tip(owner_id, task_id);
+			// The asset of the reward is not known without the action, so it stays in the pot
+			KeeperRewards::<T>::remove(owner_id, task_id);
+			if let Some(linked_task_id) = LinkedTasks::<T>::take(owner_id, task_id) {
+				LinkedTasks::<T>::remove(owner_id, linked_task_id);
+			}
+			if let Some(deposit) = TaskDeposits::<T>::take(owner_id, task_id) {
+				let amount = release_deposit::<_, T::Currency>(owner_id, deposit);
+				Self::deposit_event(Event::DepositReclaimed {
+					owner_id: owner_id.clone(),
+					task_id: *task_id,
+					amount,
+				});
 			}
 
-			let now = current_block_time.unwrap();
+			// The time the task expires at is not known either, so every slot is looked through
+			SortedTasksByExpiration::<T>::mutate(|sorted_tasks_by_expiration| {
+				sorted_tasks_by_expiration.retain(|_, expired_task_slot| {
+					if expired_task_slot.get(task_id) == Some(owner_id) {
+						expired_task_slot.remove(task_id);
+					}
+					!expired_task_slot.is_empty()
+				});
+			});
+			CoolingDownTasks::<T>::mutate(|cooling_down_tasks| {
+				cooling_down_tasks.retain(|_, tasks| {
+					tasks.retain(|(owner, id)| !(owner == owner_id && id == task_id));
+					!tasks.is_empty()
+				});
+			});
+			Self::remove_from_task_queue(owner_id, task_id);
 
-			if task.expired_at < now.into() {
-				consumed_weight =
-					consumed_weight.saturating_add(<T as Config>::WeightInfo::emit_event());
+			let total_task = Self::get_task_stat(StatType::TotalTasksOverall).map_or(0, |v| v);
+			if total_task >= 1 {
+				TaskStats::<T>::insert(StatType::TotalTasksOverall, total_task - 1);
+			}
+			let total_task_per_account =
+				Self::get_account_stat(owner_id, StatType::TotalTasksPerAccount).map_or(0, |v| v);
+			if total_task_per_account >= 1 {
+				AccountStats::<T>::insert(
+					owner_id,
+					StatType::TotalTasksPerAccount,
+					total_task_per_account - 1,
+				);
+			}
+
+			Self::deposit_event(Event::TaskUndecodable {
+				owner_id: owner_id.clone(),
+				task_id: *task_id,
+			});
+		}
+
+		/// The ids of at most `limit` active tasks of `owner_id`, starting after `start_after`,
+		/// so that the tasks of an account can be paged through.
+		pub fn get_account_task_ids(
+			owner_id: &AccountOf<T>,
+			start_after: Option<TaskId>,
+			limit: u32,
+		) -> Vec<TaskId> {
+			let task_ids = match start_after {
+				Some(task_id) => Tasks::<T>::iter_key_prefix_from(
+					owner_id,
+					Tasks::<T>::hashed_key_for(owner_id, task_id),
+				),
+				None => Tasks::<T>::iter_key_prefix(owner_id),
+			};
+			task_ids.take(limit as usize).collect()
+		}
+
+		/// The task with `task_id`, whoever its owner is. Every task is read until it is found, so
+		/// this is meant for queries made off chain, such as through the runtime API.
+		pub fn find_task(task_id: &TaskId) -> Option<Task<T>> {
+			Tasks::<T>::iter_values().find(|task| task.task_id == *task_id)
+		}
+
+		/// Where a task stands in its life. The queues the task can wait in are read whole, so
+		/// this is meant for queries made off chain, such as through the runtime API.
+		pub fn get_task_status(task: &Task<T>) -> TaskStatus {
+			let address = (task.owner_id.clone(), task.task_id);
+			let now = Self::get_current_block_time().unwrap_or_default() as u128;
+			if task.expired_at < now {
+				TaskStatus::Expired
+			} else if Self::is_task_paused(&task.owner_id, &task.task_id) {
+				TaskStatus::Paused
+			} else if Self::get_task_queue().contains(&address) ||
+				Self::get_missed_tasks_queue().contains(&address)
+			{
+				TaskStatus::Queued
+			} else if Self::get_cooling_down_tasks().values().flatten().any(|a| *a == address) {
+				TaskStatus::CoolingDown
+			} else if XcmpRetryQueue::<T>::iter_values()
+				.flatten()
+				.any(|retry| retry.owner_id == task.owner_id && retry.task_id == task.task_id)
+			{
+				TaskStatus::RetryingXcmSend
+			} else {
+				TaskStatus::Scheduled
+			}
+		}
+
+		// Handle task removal. There are a few places task need to be remove:
+		//  - Tasks storage
+		//  - TaskQueue if the task is already queued
+		//  - TaskStats: decrease task count
+		//  - AccountStats: decrease task count
+		//  - SortedTasksAbove or SortedTasksBelow: sorted task by price
+		//  - SortedTasksByExpiration: sorted task by expired epch
+		pub fn remove_task(task: &Task<T>, event: Option<Event<T>>) {
+			Tasks::<T>::remove(task.owner_id.clone(), task.task_id);
+			TaskRecurrences::<T>::remove(&task.owner_id, &task.task_id);
+			MissedTaskPolicies::<T>::remove(&task.owner_id, &task.task_id);
+			PausedTasks::<T>::remove(&task.owner_id, &task.task_id);
+			TaskTimeWindows::<T>::remove(&task.owner_id, &task.task_id);
+			Self::release_task_tip(&task.owner_id, &task.task_id);
+			// No keeper ran the task, or the reward was already paid
+			Self::release_keeper_reward(task, None);
+			// The other leg of a bracket stays scheduled on its own
+			if let Some(linked_task_id) = LinkedTasks::<T>::take(&task.owner_id, &task.task_id) {
+				LinkedTasks::<T>::remove(&task.owner_id, linked_task_id);
+			}
+
+			Self::remove_from_sorted_index(task);
+			TaskConditions::<T>::remove(&task.owner_id, &task.task_id);
+
+			// Remove it from the SortedTasksByExpiration
+			SortedTasksByExpiration::<T>::mutate(|sorted_tasks_by_expiration| {
+				if let Some(expired_task_slot) =
+					sorted_tasks_by_expiration.get_mut(&task.expired_at)
+				{
+					expired_task_slot.remove(&task.task_id);
+					if expired_task_slot.is_empty() {
+						sorted_tasks_by_expiration.remove(&task.expired_at);
+					}
+				}
+			});
+
+			// Update metrics
+			let total_task = Self::get_task_stat(StatType::TotalTasksOverall).map_or(0, |v| v);
+			let total_task_per_account =
+				Self::get_account_stat(&task.owner_id, StatType::TotalTasksPerAccount)
+					.map_or(0, |v| v);
+
+			if total_task >= 1 {
+				TaskStats::<T>::insert(StatType::TotalTasksOverall, total_task - 1);
+			}
+
+			if total_task_per_account >= 1 {
+				AccountStats::<T>::insert(
+					task.owner_id.clone(),
+					StatType::TotalTasksPerAccount,
+					total_task_per_account - 1,
+				);
+			}
+
+			Self::decrement_asset_task_count((&task.chain, &task.exchange, &task.asset_pair));
+
+			if event.is_some() {
+				Self::deposit_event(event.unwrap());
+			}
+		}
+
+		// Count one task less on an asset pair, once the task is gone
+		fn decrement_asset_task_count(asset_key: (&ChainName, &Exchange, &AssetPair)) {
+			let asset_task_count = Self::get_asset_task_count(asset_key);
+			if asset_task_count > 1 {
+				AssetTaskCounts::<T>::insert(asset_key, asset_task_count - 1);
+			} else {
+				AssetTaskCounts::<T>::remove(asset_key);
+			}
+		}
+
+		// Give the tip held for a task that runs to `TipDestination`. Returns the weight used.
+		fn collect_task_tip(task: &Task<T>) -> Weight {
+			let tip = match TaskTips::<T>::take(&task.owner_id, &task.task_id) {
+				Some(tip) => tip,
+				None => return T::DbWeight::get().reads(1u64),
+			};
+
+			let (collected, _) = T::Currency::slash_reserved(&task.owner_id, tip);
+			let amount = collected.peek();
+			T::TipDestination::on_unbalanced(collected);
+			Self::deposit_event(Event::TaskTipCollected {
+				owner_id: task.owner_id.clone(),
+				task_id: task.task_id,
+				amount,
+			});
+			<T as Config>::WeightInfo::emit_event()
+				.saturating_add(T::DbWeight::get().reads_writes(2u64, 2u64))
+		}
+
+		// Return the tip held for a task that is removed without running.
+		fn release_task_tip(owner_id: &AccountOf<T>, task_id: &TaskId) {
+			if let Some(tip) = TaskTips::<T>::take(owner_id, task_id) {
+				T::Currency::unreserve(owner_id, tip);
+			}
+		}
+
+		/// The account holding the rewards of keepers until they are paid.
+		pub fn keeper_pot_account() -> AccountOf<T> {
+			T::PalletId::get().into_account_truncating()
+		}
+
+		/// Give the keeper pot the minimum balance of the native token, so that paying out the
+		/// rewards it holds never reaps it. Returns whether the pot was endowed.
+		pub fn endow_keeper_pot() -> bool {
+			let pot = Self::keeper_pot_account();
+			let minimum_balance = T::Currency::minimum_balance();
+			if T::Currency::free_balance(&pot) >= minimum_balance {
+				return false
+			}
+			T::Currency::make_free_balance_be(&pot, minimum_balance);
+			true
+		}
+
+		/// The part of the fee of an action held for the keeper that runs it. A reward that would
+		/// leave the keeper pot below the minimum balance of the fee asset cannot be held, and
+		/// goes to the fee revenue with the rest of the fee.
+		pub fn calculate_keeper_reward_amount(
+			action: &ActionOf<T>,
+		) -> Result<MultiBalanceOf<T>, DispatchError> {
+			let fee: u128 = Self::calculate_schedule_fee_amount(action)?.saturated_into();
+			let reward: MultiBalanceOf<T> =
+				T::KeeperRewardShare::get().mul_floor(fee).saturated_into();
+			if reward.is_zero() {
+				return Ok(reward)
+			}
+
+			let currency_id = T::CurrencyIdConvert::convert(action.schedule_fee_location::<T>())
+				.ok_or("IncoveribleMultilocation")?
+				.into();
+			let pot_balance =
+				T::MultiCurrency::total_balance(currency_id, &Self::keeper_pot_account());
+			if pot_balance.saturating_add(reward) < T::MultiCurrency::minimum_balance(currency_id) {
+				return Ok(Zero::zero())
+			}
+			Ok(reward)
+		}
 
-				Self::deposit_event(Event::TaskExpired {
+		// Record the reward held for the keeper of a task once the fees of its next run are paid
+		fn hold_keeper_reward(task: &Task<T>) {
+			let reward = Self::calculate_keeper_reward_amount(&task.action).unwrap_or_default();
+			if reward.is_zero() {
+				return
+			}
+			KeeperRewards::<T>::mutate(&task.owner_id, &task.task_id, |held| {
+				*held = Some(held.unwrap_or_default().saturating_add(reward))
+			});
+		}
+
+		// Pay the reward held for a task to `keeper`, or to the fee revenue when no keeper ran it.
+		// Returns the weight used.
+		fn release_keeper_reward(task: &Task<T>, keeper: Option<&AccountOf<T>>) -> Weight {
+			let amount = match KeeperRewards::<T>::take(&task.owner_id, &task.task_id) {
+				Some(amount) => amount,
+				None => return T::DbWeight::get().reads(1u64),
+			};
+
+			let paid = T::FeeHandler::release_keeper_reward(&task.action, amount, keeper);
+			match (paid, keeper) {
+				(Ok(()), Some(keeper)) => Self::deposit_event(Event::KeeperRewarded {
+					keeper: keeper.clone(),
 					owner_id: task.owner_id.clone(),
-					task_id: task.task_id.clone(),
-					condition: TaskCondition::AlreadyExpired {
-						expired_at: task.expired_at,
-						now: now.into(),
-					},
-				});
+					task_id: task.task_id,
+					amount,
+				}),
+				(Ok(()), None) => (),
+				(Err(error), _) => log::warn!(
+					target: "runtime::automation-price",
+					"Cannot release the keeper reward of task {:?}: {:?}",
+					task.task_id,
+					error
+				),
+			}
+			<T as Config>::WeightInfo::emit_event()
+				.saturating_add(T::DbWeight::get().reads_writes(3u64, 3u64))
+		}
 
-				return (None, consumed_weight)
+		// Settle the tasks run from the queue as one batch. The expiration index and the task
+		// counters are written once for the whole batch rather than once per task, and the tasks
+		// of each asset pair are reported together with the price they were all checked against.
+		fn settle_tasks(tasks: Vec<Task<T>>, prices: &mut PriceCache<T>) {
+			if tasks.is_empty() {
+				return
+			}
+
+			SortedTasksByExpiration::<T>::mutate(|sorted_tasks_by_expiration| {
+				for task in tasks.iter() {
+					if let Some(expired_task_slot) =
+						sorted_tasks_by_expiration.get_mut(&task.expired_at)
+					{
+						expired_task_slot.remove(&task.task_id);
+						if expired_task_slot.is_empty() {
+							sorted_tasks_by_expiration.remove(&task.expired_at);
+						}
+					}
+				}
+			});
+
+			let total_task = Self::get_task_stat(StatType::TotalTasksOverall).map_or(0, |v| v);
+			TaskStats::<T>::insert(
+				StatType::TotalTasksOverall,
+				total_task.saturating_sub(tasks.len() as u128),
+			);
+
+			let mut settled_per_account = BTreeMap::<AccountOf<T>, u128>::new();
+			let mut settled_per_pair =
+				BTreeMap::<(ChainName, Exchange, AssetPair), TaskIdList<T>>::new();
+			for task in tasks {
+				settled_per_account.entry(task.owner_id.clone()).or_default().saturating_inc();
+				settled_per_pair
+					.entry((task.chain, task.exchange, task.asset_pair))
+					.or_default()
+					.push((task.owner_id, task.task_id));
+			}
+
+			for (owner_id, settled) in settled_per_account {
+				let total_task_per_account =
+					Self::get_account_stat(&owner_id, StatType::TotalTasksPerAccount)
+						.map_or(0, |v| v);
+				AccountStats::<T>::insert(
+					owner_id,
+					StatType::TotalTasksPerAccount,
+					total_task_per_account.saturating_sub(settled),
+				);
 			}
 
-			// read storage once to get the price
-			consumed_weight = consumed_weight.saturating_add(T::DbWeight::get().reads(1u64));
-			if let Some(this_task_asset_price) =
-				Self::get_asset_price_data((&task.chain, &task.exchange, &task.asset_pair))
+			for ((chain, exchange, asset_pair), tasks) in settled_per_pair {
+				let (price, _) = prices.get(&chain, &exchange, &asset_pair);
+				Self::deposit_event(Event::<T>::TasksSettled {
+					chain,
+					exchange,
+					asset_pair,
+					price: price.map_or(0, |price| price.value),
+					tasks,
+				});
+			}
+		}
+
+		// Keep a recurring task that ran out of the sorted task index until its cooldown has
+		// passed, charging its fees for the next run. Returns whether the task stays scheduled.
+		fn rearm_task(task: &Task<T>, now: u128) -> bool {
+			let recurrence = match Self::get_task_recurrence(&task.owner_id, &task.task_id) {
+				Some(recurrence) => recurrence,
+				None => return false,
+			};
+
+			let remaining_executions =
+				recurrence.remaining_executions.map(|remaining| remaining.saturating_sub(1));
+			let armed_at = now.saturating_add(recurrence.cooldown_seconds.into());
+			if remaining_executions == Some(0) ||
+				armed_at >= task.expired_at ||
+				T::FeeHandler::pay_checked_fees_for(&task.owner_id, &task.action, || Ok(()))
+					.is_err()
 			{
-				if task.is_price_condition_match(&this_task_asset_price) {
-					return (
-						Some(TaskCondition::TargetPriceMatched {
-							chain: task.chain.clone(),
-							exchange: task.exchange.clone(),
-							asset_pair: task.asset_pair.clone(),
-							price: this_task_asset_price.value,
-						}),
-						consumed_weight,
+				TaskRecurrences::<T>::remove(&task.owner_id, &task.task_id);
+				return false
+			}
+			Self::hold_keeper_reward(task);
+
+			TaskRecurrences::<T>::insert(
+				&task.owner_id,
+				&task.task_id,
+				Recurrence { remaining_executions, ..recurrence },
+			);
+			CoolingDownTasks::<T>::mutate(|tasks| {
+				tasks.entry(armed_at).or_default().push((task.owner_id.clone(), task.task_id))
+			});
+			Self::deposit_event(Event::TaskRearmed {
+				owner_id: task.owner_id.clone(),
+				task_id: task.task_id,
+				armed_at,
+			});
+			true
+		}
+
+		// Reading and writing the recurrence and the cooling down tasks, and charging the fees
+		fn rearm_task_weight() -> Weight {
+			T::DbWeight::get().reads_writes(4u64, 4u64)
+		}
+
+		// Whether the time window of a task, if it has one, is open at `now`
+		fn is_in_time_window(task: &Task<T>, now: u128) -> bool {
+			Self::get_task_time_window(&task.owner_id, &task.task_id)
+				.map_or(true, |window| window.contains(now))
+		}
+
+		// Keep a task triggered outside of its time window out of the sorted task index until the
+		// window opens. Returns the weight used.
+		fn defer_task(task: &Task<T>, now: u128) -> Weight {
+			let resume_at = Self::get_task_time_window(&task.owner_id, &task.task_id)
+				.map_or(now, |window| window.next_open(now));
+			CoolingDownTasks::<T>::mutate(|tasks| {
+				tasks.entry(resume_at).or_default().push((task.owner_id.clone(), task.task_id))
+			});
+			Self::deposit_event(Event::TaskDeferred {
+				owner_id: task.owner_id.clone(),
+				task_id: task.task_id,
+				resume_at,
+			});
+
+			<T as Config>::WeightInfo::emit_event()
+				.saturating_add(T::DbWeight::get().reads_writes(2u64, 1u64))
+		}
+
+		// Check the conditions a task is to combine with its own. A condition relative to the
+		// current price of its asset pair keeps that price, as the price condition of a task does.
+		fn validate_conditions(
+			mut compound: CompoundCondition,
+		) -> Result<CompoundCondition, DispatchError> {
+			let count = compound.conditions.len();
+			if count == 0 || count > T::MaxExtraConditions::get() as usize {
+				Err(Error::<T>::InvalidConditionCount)?
+			}
+
+			for condition in compound.conditions.iter_mut() {
+				let key = (&condition.chain, &condition.exchange, &condition.asset_pair);
+				if !AssetRegistry::<T>::contains_key(key) {
+					Err(Error::<T>::AssetNotSupported)?
+				}
+				let price = Self::get_asset_price_data(key);
+				condition.trigger_params = T::ConditionEvaluator::with_current_price(
+					&condition.trigger_function,
+					sp_std::mem::take(&mut condition.trigger_params),
+					price.as_ref(),
+				);
+				if !T::ConditionEvaluator::is_valid(
+					&condition.trigger_function,
+					&condition.trigger_params,
+				) {
+					Err(Error::<T>::InvalidTriggerParams)?
+				}
+			}
+			Ok(compound)
+		}
+
+		// The asset pairs a task with extra conditions refers to: its own, then those of its
+		// conditions, each once
+		fn task_asset_pairs(
+			task: &Task<T>,
+			compound: &CompoundCondition,
+		) -> Vec<(ChainName, Exchange, AssetPair)> {
+			let mut keys =
+				vec![(task.chain.clone(), task.exchange.clone(), task.asset_pair.clone())];
+			for condition in compound.conditions.iter() {
+				let key = (
+					condition.chain.clone(),
+					condition.exchange.clone(),
+					condition.asset_pair.clone(),
+				);
+				if !keys.contains(&key) {
+					keys.push(key);
+				}
+			}
+			keys
+		}
+
+		// Whether the conditions of a task hold at `now`: its price condition, combined with its
+		// extra conditions if it has any
+		fn is_task_condition_match(
+			task: &Task<T>,
+			prices: &mut PriceCache<T>,
+			now: u128,
+		) -> (bool, Weight) {
+			let (price, price_weight) = prices.get(&task.chain, &task.exchange, &task.asset_pair);
+			let (history, history_weight) =
+				prices.history(&task.chain, &task.exchange, &task.asset_pair);
+			let mut matched = match price {
+				Some(price) => task.is_price_condition_match(&price, &history, now),
+				None => false,
+			};
+			let mut weight = price_weight
+				.saturating_add(history_weight)
+				.saturating_add(T::DbWeight::get().reads(1u64));
+
+			let compound = match Self::get_task_conditions(&task.owner_id, &task.task_id) {
+				Some(compound) => compound,
+				None => return (matched, weight),
+			};
+			// The result is known once a condition fails all of them, or matches any of them
+			let any = compound.combinator == Combinator::Or;
+			for condition in compound.conditions.iter() {
+				if matched == any {
+					break
+				}
+				let (chain, exchange, asset_pair) =
+					(&condition.chain, &condition.exchange, &condition.asset_pair);
+				let (price, price_weight) = prices.get(chain, exchange, asset_pair);
+				let (history, history_weight) = prices.history(chain, exchange, asset_pair);
+				matched = price.map_or(false, |price| {
+					T::ConditionEvaluator::is_match(
+						&condition.trigger_function,
+						&condition.trigger_params,
+						&price,
+						&history,
+						now,
 					)
-				} else {
-					Self::deposit_event(Event::PriceAlreadyMoved {
-						owner_id: task.owner_id.clone(),
-						task_id: task.task_id.clone(),
-						condition: TaskCondition::PriceAlreadyMoved {
-							chain: task.chain.clone(),
-							exchange: task.exchange.clone(),
-							asset_pair: task.asset_pair.clone(),
-							price: this_task_asset_price.value,
-
-							target_price: task.trigger_params[0],
-						},
-					});
+				});
+				weight = weight.saturating_add(price_weight).saturating_add(history_weight);
+			}
+			(matched, weight)
+		}
+
+		// Reading the extra conditions of a matched task, and removing it from the unsorted tasks
+		// of each asset pair it refers to
+		fn remove_compound_task_weight() -> Weight {
+			let asset_pairs: u64 = T::MaxExtraConditions::get().saturating_add(1).into();
+			T::DbWeight::get()
+				.reads(2u64)
+				.saturating_add(T::DbWeight::get().reads_writes(asset_pairs, asset_pairs))
+		}
+
+		// Whether any condition of a task can start to hold while the prices stay the same
+		fn task_changes_over_time(task: &Task<T>) -> bool {
+			let conditions = Self::get_task_conditions(&task.owner_id, &task.task_id)
+				.map(|compound| compound.conditions)
+				.unwrap_or_default();
+			T::ConditionEvaluator::changes_over_time(&task.trigger_function) ||
+				conditions.iter().any(|condition| {
+					T::ConditionEvaluator::changes_over_time(&condition.trigger_function)
+				})
+		}
+
+		// Put the recurring tasks whose cooldown has passed, and the deferred tasks whose time
+		// window opened, back into the sorted task index
+		fn arm_cooled_down_tasks(now: u128) -> Weight {
+			let mut cooled_down = Self::get_cooling_down_tasks();
+			if cooled_down.first_key_value().map_or(true, |(armed_at, _)| *armed_at > now) {
+				return T::DbWeight::get().reads(1u64)
+			}
 
-					return (None, consumed_weight)
+			let cooling_down = cooled_down.split_off(&now.saturating_add(1));
+			CoolingDownTasks::<T>::put(cooling_down);
+
+			let mut armed = 0u64;
+			for (owner_id, task_id) in cooled_down.into_values().flatten() {
+				// Tasks cancelled or expired while cooling down are gone, and paused tasks are
+				// armed when they are resumed
+				if let Some(task) = Self::get_task(&owner_id, &task_id) {
+					if !Self::is_task_paused(&owner_id, &task_id) {
+						Self::insert_into_sorted_index(&task);
+					}
+					armed.saturating_inc();
 				}
 			}
 
-			// This happen because we cannot find the price, so the task cannot be run
-			(None, consumed_weight)
+			T::DbWeight::get()
+				.reads_writes(1u64, 1u64)
+				.saturating_add(T::DbWeight::get().reads_writes(3u64, 2u64).saturating_mul(armed))
 		}
 
-		/// Runs as many tasks as the weight allows from the provided vec of task_ids.
-		///
-		/// Returns a vec with the tasks that were not run and the remaining weight.
-		pub fn run_tasks(
-			mut task_ids: TaskIdList<T>,
-			mut weight_left: Weight,
-		) -> (TaskIdList<T>, Weight) {
-			let mut consumed_task_index: usize = 0;
-
-			// If we cannot extract time from the block, then somthing horrible wrong, let not move
-			// forward
-			let current_block_time = Self::get_current_block_time();
-			if current_block_time.is_err() {
-				return (task_ids, weight_left)
+		// Remove a task from the task queue or the missed tasks queue, if it waits there to run
+		fn remove_from_task_queue(owner_id: &AccountOf<T>, task_id: &TaskId) {
+			let mut task_queue = Self::get_task_queue();
+			let queued = task_queue.len();
+			task_queue.retain(|(owner, id)| !(owner == owner_id && id == task_id));
+			if task_queue.len() != queued {
+				TaskQueue::<T>::put(task_queue);
 			}
 
-			let _now = current_block_time.unwrap();
+			let mut missed_tasks = Self::get_missed_tasks_queue();
+			let missed = missed_tasks.len();
+			missed_tasks.retain(|(owner, id)| !(owner == owner_id && id == task_id));
+			if missed_tasks.len() != missed {
+				MissedTasksQueue::<T>::put(missed_tasks);
+			}
+		}
 
-			for (owner_id, task_id) in task_ids.iter() {
-				consumed_task_index.saturating_inc();
+		/// Check that the task indexes, the task queues and the task counts agree with `Tasks`.
+		///
+		/// * Every task of `SortedTasksAbove`, `SortedTasksBelow` and `UnsortedTasks` is in
+		///   `Tasks`, and `SortedBucketsAbove` and `SortedBucketsBelow` list exactly the buckets
+		///   that hold tasks.
+		/// * Every task of `TaskQueue` and `MissedTasksQueue` is in `Tasks`.
+		/// * `TotalTasksOverall` and `TotalTasksPerAccount` count the tasks in `Tasks`.
+		#[cfg(any(feature = "try-runtime", test))]
+		pub fn do_try_state() -> Result<(), &'static str> {
+			let is_live =
+				|(owner_id, task_id): &TaskAddress<T>| Tasks::<T>::contains_key(owner_id, task_id);
+
+			let mut buckets_above = BTreeMap::<_, BTreeSet<u128>>::new();
+			for ((chain, exchange, asset_pair, bucket), tasks) in SortedTasksAbove::<T>::iter() {
+				ensure!(
+					tasks.values().flatten().all(is_live),
+					"SortedTasksAbove refers to a task that is not in Tasks"
+				);
+				buckets_above.entry((chain, exchange, asset_pair)).or_default().insert(bucket);
+			}
+			ensure!(
+				SortedBucketsAbove::<T>::iter().collect::<BTreeMap<_, _>>() == buckets_above,
+				"SortedBucketsAbove does not list the buckets of SortedTasksAbove"
+			);
 
-				let action_weight = match Self::get_task(owner_id, task_id) {
-					None => {
-						Self::deposit_event(Event::TaskNotFound {
-							owner_id: owner_id.clone(),
-							task_id: task_id.clone(),
-						});
-						<T as Config>::WeightInfo::emit_event()
-					},
-					Some(task) => {
-						let (task_condition, test_can_run_weight) = Self::task_can_run(&task);
+			let mut buckets_below = BTreeMap::<_, BTreeSet<u128>>::new();
+			for ((chain, exchange, asset_pair, bucket), tasks) in SortedTasksBelow::<T>::iter() {
+				ensure!(
+					tasks.values().flatten().all(is_live),
+					"SortedTasksBelow refers to a task that is not in Tasks"
+				);
+				buckets_below.entry((chain, exchange, asset_pair)).or_default().insert(bucket);
+			}
+			ensure!(
+				SortedBucketsBelow::<T>::iter().collect::<BTreeMap<_, _>>() == buckets_below,
+				"SortedBucketsBelow does not list the buckets of SortedTasksBelow"
+			);
 
-						if task_condition.is_none() {
-							test_can_run_weight
-						} else {
-							Self::deposit_event(Event::TaskTriggered {
-								owner_id: task.owner_id.clone(),
-								task_id: task.task_id.clone(),
-								condition: task_condition.unwrap(),
-							});
+			ensure!(
+				UnsortedTasks::<T>::iter_values().flatten().all(|task| is_live(&task)),
+				"UnsortedTasks refers to a task that is not in Tasks"
+			);
 
-							let _total_task =
-								Self::get_task_stat(StatType::TotalTasksOverall).map_or(0, |v| v);
-							let _total_task_per_account = Self::get_account_stat(
-								&task.owner_id,
-								StatType::TotalTasksPerAccount,
-							)
-							.map_or(0, |v| v);
-
-							let (task_action_weight, task_dispatch_error) =
-								match task.action.clone() {
-									Action::XCMP {
-										destination,
-										execution_fee,
-										schedule_as,
-										encoded_call,
-										encoded_call_weight,
-										overall_weight,
-										instruction_sequence,
-										..
-									} => Self::run_xcmp_task(
-										destination,
-										schedule_as.unwrap_or(task.owner_id.clone()),
-										execution_fee,
-										encoded_call,
-										encoded_call_weight,
-										overall_weight,
-										instruction_sequence,
-									),
-								};
-
-							Self::remove_task(&task, None);
-
-							if let Some(err) = task_dispatch_error {
-								Self::deposit_event(Event::<T>::TaskExecutionFailed {
-									owner_id: task.owner_id.clone(),
-									task_id: task.task_id.clone(),
-									error: err,
-								});
-							} else {
-								Self::deposit_event(Event::<T>::TaskExecuted {
-									owner_id: task.owner_id.clone(),
-									task_id: task.task_id.clone(),
-								});
-							}
+			ensure!(
+				Self::get_task_queue().iter().all(is_live),
+				"TaskQueue refers to a task that is not in Tasks"
+			);
+			ensure!(
+				Self::get_missed_tasks_queue().iter().all(is_live),
+				"MissedTasksQueue refers to a task that is not in Tasks"
+			);
+			ensure!(
+				TaskConditions::<T>::iter_keys()
+					.all(|(owner_id, task_id)| Tasks::<T>::contains_key(owner_id, task_id)),
+				"TaskConditions refers to a task that is not in Tasks"
+			);
 
-							Self::deposit_event(Event::<T>::TaskCompleted {
-								owner_id: task.owner_id.clone(),
-								task_id: task.task_id.clone(),
-							});
+			let mut tasks_per_account = BTreeMap::<AccountOf<T>, u64>::new();
+			for (owner_id, _) in Tasks::<T>::iter_keys() {
+				*tasks_per_account.entry(owner_id).or_default() += 1;
+			}
+			let total_tasks: u64 = tasks_per_account.values().sum();
+			ensure!(
+				Self::get_task_stat(StatType::TotalTasksOverall).unwrap_or(0) == total_tasks,
+				"TotalTasksOverall does not match the number of tasks"
+			);
+			let account_stats: BTreeMap<AccountOf<T>, u64> = AccountStats::<T>::iter()
+				.filter(|(_, stat, count)| *stat == StatType::TotalTasksPerAccount && *count > 0)
+				.map(|(owner_id, _, count)| (owner_id, count))
+				.collect();
+			ensure!(
+				account_stats == tasks_per_account,
+				"TotalTasksPerAccount does not match the number of tasks of an account"
+			);
 
-							task_action_weight
-								.saturating_add(T::DbWeight::get().writes(1u64))
-								.saturating_add(T::DbWeight::get().reads(1u64))
-						}
-					},
-				};
+			let mut tasks_per_asset_pair = BTreeMap::<_, u32>::new();
+			for task in Tasks::<T>::iter_values() {
+				*tasks_per_asset_pair
+					.entry((task.chain, task.exchange, task.asset_pair))
+					.or_default() += 1;
+			}
+			ensure!(
+				AssetTaskCounts::<T>::iter()
+					.filter(|(_, count)| *count > 0)
+					.collect::<BTreeMap<_, _>>() ==
+					tasks_per_asset_pair,
+				"AssetTaskCounts does not match the number of tasks of an asset pair"
+			);
 
-				weight_left = weight_left.saturating_sub(action_weight);
+			Ok(())
+		}
 
-				let run_another_task_weight = <T as Config>::WeightInfo::emit_event()
-					.saturating_add(T::DbWeight::get().writes(1u64))
-					.saturating_add(T::DbWeight::get().reads(1u64));
-				if weight_left.ref_time() < run_another_task_weight.ref_time() {
-					break
+		// Remove a task from the sorted task index of its trigger function, or from the unsorted
+		// tasks of its asset, or of each asset pair it refers to if it has extra conditions
+		fn remove_from_sorted_index(task: &Task<T>) {
+			if let Some(compound) = Self::get_task_conditions(&task.owner_id, &task.task_id) {
+				for key in Self::task_asset_pairs(task, &compound) {
+					Self::remove_from_unsorted_tasks(&key, &task.task_id);
 				}
+				return
 			}
 
-			if consumed_task_index == task_ids.len() {
-				(vec![], weight_left)
-			} else {
-				(task_ids.split_off(consumed_task_index), weight_left)
-			}
-		}
-
-		// Handle task removal. There are a few places task need to be remove:
-		//  - Tasks storage
-		//  - TaskQueue if the task is already queued
-		//  - TaskStats: decrease task count
-		//  - AccountStats: decrease task count
-		//  - SortedTasksIndex: sorted task by price
-		//  - SortedTasksByExpiration: sorted task by expired epch
-		pub fn remove_task(task: &Task<T>, event: Option<Event<T>>) {
-			Tasks::<T>::remove(task.owner_id.clone(), task.task_id.clone());
+			let trigger_function = match TriggerFunction::from_bytes(&task.trigger_function) {
+				Some(trigger_function) => trigger_function,
+				None => {
+					let key = (task.chain.clone(), task.exchange.clone(), task.asset_pair.clone());
+					Self::remove_from_unsorted_tasks(&key, &task.task_id);
+					return
+				},
+			};
 
-			// Remove it from SortedTasksIndex
-			let key = (&task.chain, &task.exchange, &task.asset_pair, &task.trigger_function);
-			if let Some(mut sorted_tasks_by_price) = Self::get_sorted_tasks_index(key) {
-				if let Some(tasks) = sorted_tasks_by_price.get_mut(&task.trigger_params[0]) {
+			let key = (task.chain.clone(), task.exchange.clone(), task.asset_pair.clone());
+			if let Some(target_price) = task.trigger_params.first() {
+				let bucket = Self::price_bucket(*target_price);
+				let mut sorted_tasks_by_price =
+					Self::get_sorted_bucket(&key, trigger_function, bucket);
+				if let Some(tasks) = sorted_tasks_by_price.get_mut(target_price) {
 					if let Some(pos) = tasks.iter().position(|x| {
 						let (_, task_id) = x;
 						*task_id == task.task_id
@@ -1285,50 +4959,53 @@ pub mod pallet {
 
 					if tasks.is_empty() {
 						// if there is no more task on this slot, clear it up
-						sorted_tasks_by_price.remove(&task.trigger_params[0].clone());
+						sorted_tasks_by_price.remove(target_price);
 					}
-					SortedTasksIndex::<T>::insert(&key, sorted_tasks_by_price);
+					Self::put_sorted_bucket(&key, trigger_function, bucket, sorted_tasks_by_price);
 				}
 			}
+		}
 
-			// Remove it from the SortedTasksByExpiration
-			SortedTasksByExpiration::<T>::mutate(|sorted_tasks_by_expiration| {
-				if let Some(expired_task_slot) =
-					sorted_tasks_by_expiration.get_mut(&task.expired_at)
-				{
-					expired_task_slot.remove(&task.task_id);
-					if expired_task_slot.is_empty() {
-						sorted_tasks_by_expiration.remove(&task.expired_at);
-					}
-				}
-			});
-
-			// Update metrics
-			let total_task = Self::get_task_stat(StatType::TotalTasksOverall).map_or(0, |v| v);
-			let total_task_per_account =
-				Self::get_account_stat(&task.owner_id, StatType::TotalTasksPerAccount)
-					.map_or(0, |v| v);
-
-			if total_task >= 1 {
-				TaskStats::<T>::insert(StatType::TotalTasksOverall, total_task - 1);
+		// Remove a task from the unsorted tasks of an asset pair
+		fn remove_from_unsorted_tasks(key: &(ChainName, Exchange, AssetPair), task_id: &TaskId) {
+			let mut tasks = UnsortedTasks::<T>::get(key).unwrap_or_default();
+			tasks.retain(|(_, id)| id != task_id);
+			if tasks.is_empty() {
+				UnsortedTasks::<T>::remove(key);
+			} else {
+				UnsortedTasks::<T>::insert(key, tasks);
 			}
+		}
 
-			if total_task_per_account >= 1 {
-				AccountStats::<T>::insert(
-					task.owner_id.clone(),
-					StatType::TotalTasksPerAccount,
-					total_task_per_account - 1,
-				);
+		/// Bring the deposit held for `task` in line with its encoded size, reserving or returning
+		/// the difference.
+		fn adjust_task_deposit(task: &Task<T>) -> DispatchResult {
+			let held = Self::get_task_deposit(&task.owner_id, &task.task_id).unwrap_or_default();
+			let required = T::StorageDeposit::deposit_for(task.encoded_size() as u32);
+			if required > held {
+				T::Currency::reserve(&task.owner_id, required.saturating_sub(held))?;
+			} else if held > required {
+				release_deposit::<_, T::Currency>(&task.owner_id, held.saturating_sub(required));
 			}
 
-			if event.is_some() {
-				Self::deposit_event(event.unwrap());
+			if required.is_zero() {
+				TaskDeposits::<T>::remove(&task.owner_id, &task.task_id);
+			} else {
+				TaskDeposits::<T>::insert(&task.owner_id, &task.task_id, required);
 			}
+			Ok(())
+		}
+
+		// Whether `weight_left` has more compute time than `needed`, and enough proof size for it.
+		// The proof size of the block is bounded by the PoV limit of the relay chain.
+		fn has_room_for(weight_left: Weight, needed: Weight) -> bool {
+			weight_left.ref_time() > needed.ref_time() &&
+				weight_left.proof_size() >= needed.proof_size()
 		}
 
 		// Sweep as mucht ask we can and return the remaining weight
 		pub fn sweep_expired_task(remaining_weight: Weight) -> Weight {
-			if remaining_weight.ref_time() <= T::DbWeight::get().reads(1u64).ref_time() {
+			if !Self::has_room_for(remaining_weight, T::DbWeight::get().reads(1u64)) {
 				// Weight too low, not enough to do anything useful
 				return remaining_weight
 			}
@@ -1356,29 +5033,29 @@ pub mod pallet {
 				tasks_by_expiration.range_mut((Included(&0_u128), Included(&now)))
 			{
 				for (task_id, owner_id) in task_ids.iter() {
-					if unused_weight.ref_time() >
-						T::DbWeight::get()
-							.reads(1u64)
-							.saturating_add(<T as Config>::WeightInfo::remove_task())
-							.ref_time()
-					{
-						unused_weight = unused_weight
-							.saturating_sub(T::DbWeight::get().reads(1u64))
-							.saturating_sub(<T as Config>::WeightInfo::remove_task());
-
-						// Now let remove the task from chain storage
+					let sweep_weight = T::DbWeight::get()
+						.reads_writes(2u64, 1u64)
+						.saturating_add(Self::expire_task_weight());
+					if Self::has_room_for(unused_weight, sweep_weight) {
+						unused_weight = unused_weight.saturating_sub(sweep_weight);
+
+						// Now let remove the task from chain storage, and from the task queue in
+						// case it was already waiting to run
 						if let Some(task) = Self::get_task(owner_id, task_id) {
-							Self::remove_task(
+							Self::expire_task(
 								&task,
-								Some(Event::TaskSweep {
+								Event::TaskSweep {
 									owner_id: task.owner_id.clone(),
-									task_id: task.task_id.clone(),
+									task_id: task.task_id,
 									condition: TaskCondition::AlreadyExpired {
 										expired_at: task.expired_at,
 										now,
 									},
-								}),
+								},
 							);
+							Self::remove_from_task_queue(owner_id, task_id);
+						} else if Tasks::<T>::contains_key(owner_id, task_id) {
+							Self::remove_undecodable_task(owner_id, task_id);
 						}
 					} else {
 						// If there is not enough weight left, break all the way out, we had
@@ -1392,6 +5069,335 @@ pub mod pallet {
 			unused_weight
 		}
 
+		// Remove a task that expired before it could run, and give its owner back the execution fee
+		// paid when it was scheduled
+		fn expire_task(task: &Task<T>, event: Event<T>) {
+			Self::remove_task(task, Some(event));
+			Self::refund_execution_fee(task);
+		}
+
+		// Removing the task, then moving the execution fee back from the sovereign account
+		fn expire_task_weight() -> Weight {
+			<T as Config>::WeightInfo::remove_task()
+				.saturating_add(T::DbWeight::get().reads_writes(2u64, 2u64))
+				.saturating_add(<T as Config>::WeightInfo::emit_event())
+		}
+
+		// Every task of an asset, whether it waits in the sorted task indexes, the unsorted tasks,
+		// the task queue or its cooldown
+		fn get_asset_tasks(
+			chain: &ChainName,
+			exchange: &Exchange,
+			asset_pair: &AssetPair,
+		) -> Vec<Task<T>> {
+			let key = (chain.clone(), exchange.clone(), asset_pair.clone());
+			let indexed = Self::get_sorted_tasks_above(&key)
+				.into_iter()
+				.chain(Self::get_sorted_tasks_below(&key))
+				.flat_map(|sorted_tasks| sorted_tasks.into_values().flatten())
+				.chain(Self::get_unsorted_tasks(&key).into_iter().flatten());
+			let waiting = Self::get_task_queue()
+				.into_iter()
+				.chain(Self::get_missed_tasks_queue())
+				.chain(Self::get_cooling_down_tasks().into_values().flatten());
+
+			let mut tasks: Vec<Task<T>> = vec![];
+			for (owner_id, task_id) in indexed.chain(waiting) {
+				if let Some(task) = Self::get_task(&owner_id, &task_id) {
+					if task.chain == *chain &&
+						task.exchange == *exchange &&
+						task.asset_pair == *asset_pair &&
+						!tasks.iter().any(|t| t.owner_id == owner_id && t.task_id == task_id)
+					{
+						tasks.push(task);
+					}
+				}
+			}
+			tasks
+		}
+
+		// Submissions of an older round than the current price of the asset are stale, and an
+		// oracle provider cannot submit a round twice
+		fn check_round(
+			who: &AccountOf<T>,
+			key: (&ChainName, &Exchange, (&AssetName, &AssetName)),
+			round: u128,
+		) -> Result<(), Error<T>> {
+			if Self::get_asset_price_data(key).map_or(false, |price| round < price.round) ||
+				Self::get_round_prices(key).map_or(false, |submitted| round < submitted.round)
+			{
+				return Err(Error::<T>::StaleRound)
+			}
+			let (chain, exchange, asset_pair) = key;
+			if Self::get_oracle_round((chain, exchange, asset_pair, who))
+				.map_or(false, |last_round| round <= last_round)
+			{
+				return Err(Error::<T>::RoundAlreadySubmitted)
+			}
+			Ok(())
+		}
+
+		// Halt the asset pair `key` when `price` moves further from its current price than its
+		// circuit breaker allows.
+		fn check_circuit_breaker(
+			key: (&ChainName, &Exchange, (&AssetName, &AssetName)),
+			price: AssetPrice,
+			now: u128,
+		) {
+			let circuit_breaker = match Self::get_circuit_breaker(key) {
+				Some(circuit_breaker) if !HaltedAssets::<T>::contains_key(key) => circuit_breaker,
+				_ => return,
+			};
+			let previous_price = match Self::get_asset_price_data(key) {
+				Some(previous) => previous.value,
+				None => return,
+			};
+
+			if price.abs_diff(previous_price) > circuit_breaker.max_deviation * previous_price {
+				HaltedAssets::<T>::insert(key, now);
+				let (chain, exchange, (asset1, asset2)) = key;
+				Self::deposit_event(Event::PriceCircuitBroken {
+					chain: chain.clone(),
+					exchange: exchange.clone(),
+					asset_pair: (asset1.clone(), asset2.clone()),
+					previous_price,
+					price,
+				});
+			}
+		}
+
+		// Check a price update of `who` for the asset pair `key`. Returns its price normalized to
+		// `PriceDecimals`.
+		fn check_price_update(
+			who: &AccountOf<T>,
+			key: (&ChainName, &Exchange, (&AssetName, &AssetName)),
+			price: AssetPrice,
+			round: u128,
+		) -> Result<AssetPrice, DispatchError> {
+			ensure!(!price.is_zero(), Error::<T>::InvalidPrice);
+			let asset_registry =
+				Self::get_asset_registry_info(key).ok_or(Error::<T>::AssetNotInitialized)?;
+			ensure!(
+				asset_registry.oracle_providers.contains(who) || T::OracleCommittee::contains(who),
+				Error::<T>::OracleNotAuthorized
+			);
+			Self::check_round(who, key, round)?;
+
+			let price = Self::normalize_price(price, asset_registry.decimal)
+				.ok_or(ArithmeticError::Overflow)?;
+			ensure!(!price.is_zero(), Error::<T>::InvalidPrice);
+			Ok(price)
+		}
+
+		// Check a payload submitted with `submit_asset_prices_unsigned` before it enters the
+		// transaction pool. A provider can have one payload in the pool at a time.
+		fn validate_price_payload(
+			payload: &PricePayload<AccountOf<T>>,
+			signature: &T::OracleSignature,
+		) -> TransactionValidity {
+			if payload.updates.is_empty() || Self::get_shutdown_flags().price_ingestion {
+				return InvalidTransaction::Call.into()
+			}
+			if !signature.verify(&payload.encode()[..], &payload.provider) {
+				return InvalidTransaction::BadProof.into()
+			}
+			if !Self::is_oracle_provider_for(&payload.provider, &payload.updates) {
+				return InvalidTransaction::BadSigner.into()
+			}
+
+			let now = frame_system::Pallet::<T>::block_number();
+			let interval = T::UnsignedSubmissionInterval::get();
+			if Self::get_last_unsigned_submission(&payload.provider)
+				.map_or(false, |last| now < last.saturating_add(interval))
+			{
+				return InvalidTransaction::Future.into()
+			}
+
+			let replayed = payload.updates.iter().any(|update| {
+				let key = (&update.chain, &update.exchange, (&update.asset1, &update.asset2));
+				Self::check_round(&payload.provider, key, update.round).is_err()
+			});
+			if replayed {
+				return InvalidTransaction::Stale.into()
+			}
+
+			ValidTransaction::with_tag_prefix("AutomationPriceUnsigned")
+				.priority(T::OracleUpdatePriority::get())
+				.and_provides(&payload.provider)
+				.longevity(interval.saturated_into::<u64>().max(1))
+				.propagate(true)
+				.build()
+		}
+
+		// Write the prices of `updates` submitted by `owner_id`. Every update is logged in
+		// `OracleSubmissions`, and a rejected one is also reported with a `PriceUpdateRejected`
+		// event. Returns whether every update was accepted.
+		fn ingest_prices(
+			owner_id: &AccountOf<T>,
+			updates: Vec<PriceUpdate>,
+		) -> Result<bool, DispatchError> {
+			ensure!(!Self::get_shutdown_flags().price_ingestion, Error::<T>::PriceIngestionHalted);
+
+			let current_block_time = Self::get_current_block_time();
+			if current_block_time.is_err() {
+				Err(Error::<T>::BlockTimeNotSet)?
+			}
+
+			let now = current_block_time.unwrap() as u128;
+
+			ensure!(!updates.is_empty(), Error::<T>::AssetUpdatePayloadMalform);
+
+			let block_number = frame_system::Pallet::<T>::block_number();
+			let mut all_accepted = true;
+			for update in updates {
+				let PriceUpdate { chain, exchange, asset1, asset2, price, submitted_at, round } =
+					update;
+
+				let key = (&chain, &exchange, (&asset1, &asset2));
+
+				let outcome = match Self::check_price_update(owner_id, key, price, round) {
+					Err(error) => SubmissionOutcome::Rejected { error },
+					Ok(price) => {
+						OracleRounds::<T>::insert(
+							(&chain, &exchange, (&asset1, &asset2), owner_id),
+							round,
+						);
+						if let Some(value) = Self::submit_round_price(owner_id, key, round, price) {
+							Self::check_circuit_breaker(key, value, now);
+							let price_data = PriceData { round, updated_at: now, value };
+							Self::record_price_history(key, price_data.clone());
+							PriceRegistry::<T>::insert(&key, price_data);
+
+							Self::deposit_event(Event::AssetUpdated {
+								owner_id: owner_id.clone(),
+								chain: chain.clone(),
+								exchange: exchange.clone(),
+								asset1: asset1.clone(),
+								asset2: asset2.clone(),
+								price: value,
+							});
+						}
+						SubmissionOutcome::Accepted
+					},
+				};
+
+				if let SubmissionOutcome::Rejected { error } = &outcome {
+					all_accepted = false;
+					Self::deposit_event(Event::PriceUpdateRejected {
+						owner_id: owner_id.clone(),
+						chain: chain.clone(),
+						exchange: exchange.clone(),
+						asset1: asset1.clone(),
+						asset2: asset2.clone(),
+						round,
+						error: *error,
+					});
+				}
+				OracleSubmissions::<T>::append(
+					block_number,
+					OracleSubmission {
+						submitter: owner_id.clone(),
+						chain,
+						exchange,
+						asset_pair: (asset1, asset2),
+						round,
+						submitted_at,
+						value: price,
+						outcome,
+					},
+				);
+			}
+
+			Ok(all_accepted)
+		}
+
+		// Add a price to the submissions of its round, which replace those of an older round.
+		// Returns the aggregated price of the round once `OracleQuorum` providers submitted one.
+		fn submit_round_price(
+			who: &AccountOf<T>,
+			key: (&ChainName, &Exchange, (&AssetName, &AssetName)),
+			round: u128,
+			price: AssetPrice,
+		) -> Option<AssetPrice> {
+			let submitted = RoundPrices::<T>::mutate(key, |submitted| {
+				let submitted = submitted.get_or_insert_with(Default::default);
+				if submitted.round != round {
+					*submitted = SubmittedRound { round, prices: vec![] };
+				}
+				submitted.prices.push((who.clone(), price));
+				submitted.clone()
+			});
+
+			if (submitted.prices.len() as u32) < T::OracleQuorum::get() {
+				return None
+			}
+			T::PriceAggregator::aggregate(
+				submitted.prices.into_iter().map(|(_, price)| price).collect(),
+			)
+		}
+
+		// Cancel the other leg of the bracket that the task which just ran belongs to, refunding
+		// its execution fee. Returns whether the task had a linked task.
+		fn cancel_linked_task(owner_id: &AccountOf<T>, task_id: &TaskId) -> bool {
+			let linked_task_id = match LinkedTasks::<T>::take(owner_id, task_id) {
+				Some(linked_task_id) => linked_task_id,
+				None => return false,
+			};
+			LinkedTasks::<T>::remove(owner_id, &linked_task_id);
+
+			if let Some(linked_task) = Self::get_task(owner_id, &linked_task_id) {
+				Self::remove_task(
+					&linked_task,
+					Some(Event::TaskCancelled {
+						owner_id: owner_id.clone(),
+						task_id: linked_task_id,
+					}),
+				);
+				Self::remove_from_task_queue(owner_id, &linked_task_id);
+				Self::refund_execution_fee(&linked_task);
+			}
+			true
+		}
+
+		// Unlinking both legs, then cancelling the linked task like an expired one
+		fn cancel_linked_task_weight() -> Weight {
+			Self::expire_task_weight().saturating_add(T::DbWeight::get().reads_writes(2u64, 2u64))
+		}
+
+		// Best effort refund of the execution fee of a task that will no longer run. A failed
+		// refund must not keep the task around.
+		fn refund_execution_fee(task: &Task<T>) {
+			let refund = T::FeeHandler::refund_execution_fee_for(&task.owner_id, &task.action);
+			if let Ok(amount) = refund {
+				if !amount.is_zero() {
+					Self::deposit_event(Event::ExecutionFeeRefunded {
+						owner_id: task.owner_id.clone(),
+						task_id: task.task_id,
+						amount,
+					});
+				}
+			}
+		}
+
+		// Append a price to the history of its asset, dropping the oldest price when it is full
+		fn record_price_history(
+			key: (&ChainName, &Exchange, (&AssetName, &AssetName)),
+			price_data: PriceData,
+		) {
+			let max_history = T::MaxPriceHistory::get() as usize;
+			if max_history == 0 {
+				return
+			}
+
+			PriceHistory::<T>::mutate(key, |history| {
+				if history.len() >= max_history {
+					history.remove(0);
+				}
+				// Cannot fail, there is room for at least one price now
+				let _ = history.try_push(price_data);
+			});
+		}
+
 		// Task is write into a sorted storage, re-present by BTreeMap so we can find and expired them
 		pub fn track_expired_task(task: &Task<T>) -> Result<bool, Error<T>> {
 			// first we got back the reference to the underlying storage
@@ -1400,11 +5406,11 @@ pub mod pallet {
 			let mut tasks_by_expiration = Self::get_sorted_tasks_by_expiration();
 
 			if let Some(task_shard) = tasks_by_expiration.get_mut(&task.expired_at) {
-				task_shard.insert(task.task_id.clone(), task.owner_id.clone());
+				task_shard.insert(task.task_id, task.owner_id.clone());
 			} else {
 				tasks_by_expiration.insert(
 					task.expired_at,
-					BTreeMap::from([(task.task_id.clone(), task.owner_id.clone())]),
+					BTreeMap::from([(task.task_id, task.owner_id.clone())]),
 				);
 			}
 			SortedTasksByExpiration::<T>::put(tasks_by_expiration);
@@ -1417,11 +5423,23 @@ pub mod pallet {
 		/// Validate and schedule task.
 		/// This will also charge the execution fee.
 		/// TODO: double check atomic
-		pub fn validate_and_schedule_task(task: Task<T>) -> Result<(), Error<T>> {
-			if task.task_id.is_empty() {
+		pub fn validate_and_schedule_task(mut task: Task<T>) -> Result<(), Error<T>> {
+			if Self::get_shutdown_flags().scheduling {
+				Err(Error::<T>::SchedulingHalted)?
+			}
+
+			if task.task_id == TaskId::default() {
 				Err(Error::<T>::InvalidTaskId)?
 			}
 
+			// A task id is only reused when the owner provides the same id again. The deposit of a
+			// task outlives it, so the id stays taken until the deposit is reclaimed.
+			if Tasks::<T>::contains_key(&task.owner_id, task.task_id) ||
+				TaskDeposits::<T>::contains_key(&task.owner_id, task.task_id)
+			{
+				Err(Error::<T>::DuplicateTask)?
+			}
+
 			let current_block_time = Self::get_current_block_time();
 			if current_block_time.is_err() {
 				// Cannot get time, this probably is the first block
@@ -1434,6 +5452,20 @@ pub mod pallet {
 				Err(Error::<T>::InvalidTaskExpiredAt)?
 			}
 
+			// Conditions relative to the current price keep it with the task
+			let price = Self::get_asset_price_data((&task.chain, &task.exchange, &task.asset_pair));
+			task.trigger_params = T::ConditionEvaluator::with_current_price(
+				&task.trigger_function,
+				task.trigger_params,
+				price.as_ref(),
+			);
+			// The first param is the target price the task is indexed by, whatever the evaluator
+			if task.trigger_params.is_empty() ||
+				!T::ConditionEvaluator::is_valid(&task.trigger_function, &task.trigger_params)
+			{
+				Err(Error::<T>::InvalidTriggerParams)?
+			}
+
 			let total_task = Self::get_task_stat(StatType::TotalTasksOverall).map_or(0, |v| v);
 			let total_task_per_account =
 				Self::get_account_stat(&task.owner_id, StatType::TotalTasksPerAccount)
@@ -1449,6 +5481,13 @@ pub mod pallet {
 				Err(Error::<T>::MaxTasksPerAccountReached)?
 			}
 
+			let asset_key = (task.chain.clone(), task.exchange.clone(), task.asset_pair.clone());
+			let asset_task_count = Self::get_asset_task_count(&asset_key);
+			let asset_task_cap = Self::asset_task_cap(&asset_key);
+			if asset_task_count >= asset_task_cap {
+				Err(Error::<T>::MaxTasksPerAssetPairReached)?
+			}
+
 			match task.action.clone() {
 				Action::XCMP { execution_fee, instruction_sequence, .. } => {
 					let asset_location = MultiLocation::try_from(execution_fee.asset_location)
@@ -1469,11 +5508,18 @@ pub mod pallet {
 				_ => (),
 			};
 
-			let fee_result = T::FeeHandler::pay_checked_fees_for(
+			// The fees are checked before anything is stored, so an owner who cannot pay them is
+			// rejected with the error of the fee handler
+			T::FeeHandler::pay_checked_fees_for(
 				&(task.owner_id.clone()),
 				&(task.action.clone()),
 				|| {
-					Tasks::<T>::insert(task.owner_id.clone(), task.task_id.clone(), &task);
+					let deposit =
+						hold_deposit::<_, T::Currency, T::StorageDeposit>(&task.owner_id, &task)?;
+					if !deposit.is_zero() {
+						TaskDeposits::<T>::insert(task.owner_id.clone(), task.task_id, deposit);
+					}
+					Tasks::<T>::insert(task.owner_id.clone(), task.task_id, &task);
 
 					// Post task processing, increase relevant metrics data
 					TaskStats::<T>::insert(StatType::TotalTasksOverall, total_task + 1);
@@ -1482,42 +5528,14 @@ pub mod pallet {
 						StatType::TotalTasksPerAccount,
 						total_task_per_account + 1,
 					);
+					AssetTaskCounts::<T>::insert(&asset_key, asset_task_count + 1);
 
-					let key =
-						(&task.chain, &task.exchange, &task.asset_pair, &task.trigger_function);
-
-					if let Some(mut sorted_task_index) = Self::get_sorted_tasks_index(key) {
-						// TODO: remove hard code and take right param
-						if let Some(tasks_by_price) =
-							sorted_task_index.get_mut(&(task.trigger_params[0]))
-						{
-							tasks_by_price.push((task.owner_id.clone(), task.task_id.clone()));
-						} else {
-							sorted_task_index.insert(
-								task.trigger_params[0],
-								vec![(task.owner_id.clone(), task.task_id.clone())],
-							);
-						}
-						SortedTasksIndex::<T>::insert(key, sorted_task_index);
-					} else {
-						let mut sorted_task_index = BTreeMap::<AssetPrice, TaskIdList<T>>::new();
-						sorted_task_index.insert(
-							task.trigger_params[0],
-							vec![(task.owner_id.clone(), task.task_id.clone())],
-						);
-
-						// TODO: sorted based on trigger_function comparison of the parameter
-						// then at the time of trigger we cut off all the left part of the tree
-						SortedTasksIndex::<T>::insert(key, sorted_task_index);
-					};
+					Self::insert_into_sorted_index(&task);
 
 					Ok(())
 				},
-			);
-
-			if fee_result.is_err() {
-				Err(Error::<T>::FeePaymentError)?
-			}
+			)?;
+			Self::hold_keeper_reward(&task);
 
 			if Self::track_expired_task(&task).is_err() {
 				Err(Error::<T>::TaskExpiredStorageFailedToUpdate)?
@@ -1532,18 +5550,45 @@ pub mod pallet {
 				owner_id: task.owner_id,
 				task_id: task.task_id,
 				schedule_as,
+				chain: task.chain,
+				exchange: task.exchange,
+				asset_pair: task.asset_pair,
+				trigger_function: task.trigger_function,
+				trigger_params: task.trigger_params,
+				action_kind: task.action.kind(),
+				destination: task.action.destination(),
+				expired_at: task.expired_at,
 			});
+
+			if asset_task_count + 1 == asset_task_cap {
+				let (chain, exchange, (asset1, asset2)) = asset_key;
+				Self::deposit_event(Event::AssetTaskCapReached {
+					chain,
+					exchange,
+					asset1,
+					asset2,
+					cap: asset_task_cap,
+				});
+			}
 			Ok(())
 		}
 
+		/// The number of tasks that can be scheduled on an asset pair.
+		pub fn asset_task_cap(key: &(ChainName, Exchange, AssetPair)) -> u32 {
+			Self::get_asset_task_cap(key).unwrap_or_else(T::MaxTasksPerAssetPair::get)
+		}
+
 		/// Calculates the execution fee for a given action based on weight and num of executions
 		///
 		/// Fee saturates at Weight/BalanceOf when there are an unreasonable num of executions
 		/// In practice, executions is bounded by T::MaxExecutionTimes and unlikely to saturate
+		///
+		/// Besides the execution weight, the fee pays for the weight of the action's call data.
 		pub fn calculate_schedule_fee_amount(
 			action: &ActionOf<T>,
 		) -> Result<BalanceOf<T>, DispatchError> {
-			let total_weight = action.execution_weight::<T>()?;
+			let total_weight =
+				action.execution_weight::<T>()?.saturating_add(action.payload_weight::<T>());
 
 			let schedule_fee_location = action.schedule_fee_location::<T>();
 			let schedule_fee_location = schedule_fee_location
@@ -1570,15 +5615,48 @@ pub mod pallet {
 		}
 	}
 
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		/// Asset pairs to register at genesis.
+		/// Each entry is (chain, exchange, asset1, asset2, decimal, oracle providers).
+		pub assets: Vec<(ChainName, Exchange, AssetName, AssetName, u8, Vec<AccountOf<T>>)>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { assets: Default::default() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			for (chain, exchange, asset1, asset2, decimal, oracle_providers) in self.assets.iter() {
+				Pallet::<T>::create_new_asset(
+					chain.clone(),
+					exchange.clone(),
+					asset1.clone(),
+					asset2.clone(),
+					*decimal,
+					oracle_providers.clone(),
+				)
+				.expect("Cannot register the same asset pair twice");
+			}
+			Pallet::<T>::endow_keeper_pot();
+		}
+	}
+
+	// The valve halts and resumes every part of the pallet at once.
 	impl<T: Config> pallet_valve::Shutdown for Pallet<T> {
 		fn is_shutdown() -> bool {
-			Self::is_shutdown()
+			Self::get_shutdown_flags().execution
 		}
 		fn shutdown() {
-			Shutdown::<T>::put(true);
+			Shutdown::<T>::put(ShutdownFlags::all());
 		}
 		fn restart() {
-			Shutdown::<T>::put(false);
+			Shutdown::<T>::kill();
 		}
 	}
 }
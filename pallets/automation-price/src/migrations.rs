@@ -0,0 +1,961 @@
+// This file is part of OAK Blockchain.
+
+// Copyright (C) 2022 OAK Network
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage migrations for the automation price pallet.
+//!
+//! Changes to the shape of the sorted task indexes are migrated by rebuilding them from `Tasks`,
+//! which remains the source of truth. The rebuild runs in `on_initialize` across as many blocks as
+//! it needs, so live tasks are kept no matter how many there are.
+
+use crate::{Config, Event, IndexMigration, IndexMigrationStage, Pallet, TaskIdList, Tasks};
+#[cfg(feature = "try-runtime")]
+use codec::{Decode, Encode};
+use core::marker::PhantomData;
+use frame_support::{
+	pallet_prelude::{NMapKey, Twox64Concat},
+	storage_alias,
+	traits::{Get, GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+	weights::Weight,
+};
+use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
+
+/// The index of every task keyed by trigger function, before it was split into
+/// `SortedTasksAbove` and `SortedTasksBelow`.
+#[storage_alias]
+pub type SortedTasksIndex<T: Config> = StorageNMap<
+	Pallet<T>,
+	(
+		NMapKey<Twox64Concat, Vec<u8>>,
+		NMapKey<Twox64Concat, Vec<u8>>,
+		NMapKey<Twox64Concat, (Vec<u8>, Vec<u8>)>,
+		NMapKey<Twox64Concat, Vec<u8>>,
+	),
+	BTreeMap<u128, TaskIdList<T>>,
+>;
+
+impl<T: Config> Pallet<T> {
+	/// Advance the sorted task index rebuild using at most `max_weight`.
+	pub fn migrate_sorted_tasks_index(stage: IndexMigrationStage, max_weight: Weight) -> Weight {
+		let db_weight = T::DbWeight::get();
+		let item_weight = match stage {
+			IndexMigrationStage::Clearing { .. } => db_weight.writes(1u64),
+			IndexMigrationStage::Rebuilding { .. } => db_weight.reads_writes(2u64, 2u64),
+		};
+		let max_items = max_weight
+			.ref_time()
+			.checked_div(item_weight.ref_time())
+			.map_or(u32::MAX, |items| items.try_into().unwrap_or(u32::MAX))
+			.max(1);
+
+		let items = Self::step_index_migration(stage, max_items);
+		db_weight
+			.reads_writes(1u64, 1u64)
+			.saturating_add(item_weight.saturating_mul(items.into()))
+	}
+
+	/// Process up to `max_items` entries of the sorted task index rebuild and return how many
+	/// entries were processed.
+	pub fn step_index_migration(stage: IndexMigrationStage, max_items: u32) -> u32 {
+		match stage {
+			IndexMigrationStage::Clearing { cursor } => {
+				let result = SortedTasksIndex::<T>::clear(max_items, cursor.as_deref());
+				let next_stage = match result.maybe_cursor {
+					Some(cursor) => IndexMigrationStage::Clearing { cursor: Some(cursor) },
+					None => IndexMigrationStage::Rebuilding { cursor: None, migrated: 0 },
+				};
+				IndexMigration::<T>::put(next_stage);
+				Self::deposit_event(Event::IndexMigrationProgressed { migrated: 0 });
+				result.loops
+			},
+			IndexMigrationStage::Rebuilding { cursor, mut migrated } => {
+				let mut tasks = match cursor {
+					Some(cursor) => Tasks::<T>::iter_from(cursor),
+					None => Tasks::<T>::iter(),
+				};
+
+				let mut processed = 0u32;
+				let mut finished = false;
+				while processed < max_items {
+					match tasks.next() {
+						Some((_, _, task)) => {
+							// Tasks stored without a target price can never be indexed or run, and
+							// paused tasks are indexed when they are resumed
+							if task.trigger_params.is_empty() {
+								let event = Event::TaskCancelled {
+									owner_id: task.owner_id.clone(),
+									task_id: task.task_id,
+								};
+								Self::remove_task(&task, Some(event));
+							} else if !Self::is_task_paused(&task.owner_id, &task.task_id) {
+								Self::insert_into_sorted_index(&task);
+								migrated = migrated.saturating_add(1);
+							}
+							processed += 1;
+						},
+						None => {
+							finished = true;
+							break
+						},
+					}
+				}
+
+				if finished {
+					IndexMigration::<T>::kill();
+					Self::deposit_event(Event::IndexMigrationCompleted { migrated });
+				} else {
+					let cursor = Some(tasks.last_raw_key().to_vec());
+					IndexMigration::<T>::put(IndexMigrationStage::Rebuilding { cursor, migrated });
+					Self::deposit_event(Event::IndexMigrationProgressed { migrated });
+				}
+				processed
+			},
+		}
+	}
+}
+
+// Start rebuilding the sorted task indexes and move the storage to `version`.
+fn start_index_rebuild<T: Config>(version: u16) -> Weight {
+	if Pallet::<T>::on_chain_storage_version() >= version {
+		log::info!(
+			target: "runtime::automation-price",
+			"Sorted task index migration skipped, storage is already at v{}",
+			version
+		);
+		return T::DbWeight::get().reads(1u64)
+	}
+
+	IndexMigration::<T>::put(IndexMigrationStage::Clearing { cursor: None });
+	StorageVersion::new(version).put::<Pallet<T>>();
+	log::info!(target: "runtime::automation-price", "Started sorted task index migration");
+
+	T::DbWeight::get().reads_writes(1u64, 2u64)
+}
+
+#[cfg(feature = "try-runtime")]
+fn count_tasks<T: Config>() -> Vec<u8> {
+	(Tasks::<T>::iter_keys().count() as u64).encode()
+}
+
+#[cfg(feature = "try-runtime")]
+fn ensure_index_rebuild_started<T: Config>(
+	version: u16,
+	state: Vec<u8>,
+) -> Result<(), &'static str> {
+	let task_count_before = u64::decode(&mut state.as_slice())
+		.map_err(|_| "failed to decode the pre-upgrade task count")?;
+	let task_count_after = Tasks::<T>::iter_keys().count() as u64;
+
+	frame_support::ensure!(
+		Pallet::<T>::on_chain_storage_version() == version,
+		"AutomationPrice storage version was not updated"
+	);
+	frame_support::ensure!(
+		IndexMigration::<T>::exists(),
+		"Sorted task index migration should be in progress"
+	);
+	frame_support::ensure!(
+		task_count_before == task_count_after,
+		"Sorted task index migration must not remove tasks"
+	);
+	Ok(())
+}
+
+pub mod v1 {
+	use super::*;
+
+	/// Rebuild `SortedTasksIndex` from `Tasks`.
+	///
+	/// This only starts the rebuild; the pallet carries it out over the following blocks and emits
+	/// `IndexMigrationProgressed` for every block and `IndexMigrationCompleted` at the end. Tasks
+	/// are not triggered while the rebuild is in progress.
+	pub struct MigrateToV1<T>(PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			start_index_rebuild::<T>(1)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			Ok(count_tasks::<T>())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+			ensure_index_rebuild_started::<T>(1, state)
+		}
+	}
+}
+
+pub mod v2 {
+	use super::*;
+
+	/// Split `SortedTasksIndex` into `SortedTasksAbove` and `SortedTasksBelow`.
+	///
+	/// The old index is cleared and the new ones are rebuilt from `Tasks` over the following
+	/// blocks, in the same way as `MigrateToV1`.
+	pub struct MigrateToV2<T>(PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV2<T> {
+		fn on_runtime_upgrade() -> Weight {
+			start_index_rebuild::<T>(2)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			Ok(count_tasks::<T>())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+			ensure_index_rebuild_started::<T>(2, state)
+		}
+	}
+}
+
+pub mod v3 {
+	use super::*;
+	use crate::ShutdownFlags;
+
+	/// The single shutdown flag, before it was split into `ShutdownFlags`.
+	#[storage_alias]
+	pub type Shutdown<T: Config> = StorageValue<Pallet<T>, bool, ValueQuery>;
+
+	/// Turn the shutdown flag into `ShutdownFlags`. A pallet that was shut down stays halted in
+	/// every part.
+	pub struct MigrateToV3<T>(PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV3<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() >= 3 {
+				log::info!(
+					target: "runtime::automation-price",
+					"Shutdown flags migration skipped, storage is already at v3"
+				);
+				return T::DbWeight::get().reads(1u64)
+			}
+
+			let flags = crate::Shutdown::<T>::translate(|shutdown: Option<bool>| {
+				shutdown.filter(|shutdown| *shutdown).map(|_| ShutdownFlags::all())
+			})
+			.ok()
+			.flatten()
+			.unwrap_or_default();
+			StorageVersion::new(3).put::<Pallet<T>>();
+			log::info!(
+				target: "runtime::automation-price",
+				"Migrated the shutdown flag into {:?}",
+				flags
+			);
+
+			T::DbWeight::get().reads_writes(2u64, 2u64)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			Ok(Shutdown::<T>::get().encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+			let shutdown_before = bool::decode(&mut state.as_slice())
+				.map_err(|_| "failed to decode the pre-upgrade shutdown flag")?;
+			let expected =
+				if shutdown_before { ShutdownFlags::all() } else { ShutdownFlags::default() };
+
+			frame_support::ensure!(
+				Pallet::<T>::on_chain_storage_version() == 3,
+				"AutomationPrice storage version should be 3"
+			);
+			frame_support::ensure!(
+				crate::Shutdown::<T>::get() == expected,
+				"Shutdown flags migration must keep a shut down pallet halted"
+			);
+			Ok(())
+		}
+	}
+}
+
+pub mod v4 {
+	use super::*;
+	use crate::{
+		AccountOf, CoolingDownTasks, LinkedTasks, MissedTaskPolicies, MissedTaskPolicy,
+		MissedTasksQueue, PurgeCursors, Recurrence, SortedTasksAbove, SortedTasksBelow,
+		SortedTasksByExpiration, TaskDeposits, TaskId, TaskQueue, TaskRecurrences, UnsortedTasks,
+	};
+	use codec::{Decode, Encode, FullCodec};
+	use frame_support::storage::IterableStorageDoubleMap;
+	use sp_io::hashing::blake2_256;
+
+	/// A task id before it became a hash, formatted as `{block}-{extrinsic}-{event}`.
+	pub type OldTaskId = Vec<u8>;
+	type OldTaskIdList<T> = Vec<(AccountOf<T>, OldTaskId)>;
+
+	/// A task as it was stored before its id became a hash.
+	#[derive(Encode, Decode)]
+	pub struct OldTask<T: Config> {
+		pub owner_id: AccountOf<T>,
+		pub task_id: OldTaskId,
+		pub chain: Vec<u8>,
+		pub exchange: Vec<u8>,
+		pub asset_pair: (Vec<u8>, Vec<u8>),
+		pub expired_at: u128,
+		pub trigger_function: Vec<u8>,
+		pub trigger_params: Vec<u128>,
+		pub action: v8::OldAction<AccountOf<T>>,
+	}
+
+	/// The storage items keyed by the old task ids.
+	pub mod old {
+		use super::*;
+
+		#[storage_alias]
+		pub type Tasks<T: Config> = StorageDoubleMap<
+			Pallet<T>,
+			Twox64Concat,
+			AccountOf<T>,
+			Twox64Concat,
+			OldTaskId,
+			OldTask<T>,
+		>;
+
+		#[storage_alias]
+		pub type TaskDeposits<T: Config> = StorageDoubleMap<
+			Pallet<T>,
+			Twox64Concat,
+			AccountOf<T>,
+			Twox64Concat,
+			OldTaskId,
+			crate::BalanceOf<T>,
+		>;
+
+		#[storage_alias]
+		pub type TaskRecurrences<T: Config> = StorageDoubleMap<
+			Pallet<T>,
+			Twox64Concat,
+			AccountOf<T>,
+			Twox64Concat,
+			OldTaskId,
+			Recurrence,
+		>;
+
+		#[storage_alias]
+		pub type MissedTaskPolicies<T: Config> = StorageDoubleMap<
+			Pallet<T>,
+			Twox64Concat,
+			AccountOf<T>,
+			Twox64Concat,
+			OldTaskId,
+			MissedTaskPolicy,
+		>;
+
+		#[storage_alias]
+		pub type LinkedTasks<T: Config> = StorageDoubleMap<
+			Pallet<T>,
+			Twox64Concat,
+			AccountOf<T>,
+			Twox64Concat,
+			OldTaskId,
+			OldTaskId,
+		>;
+	}
+
+	/// The id a task scheduled before v4 is known by after the migration.
+	pub fn migrated_task_id<T: Config>(owner_id: &AccountOf<T>, task_id: &[u8]) -> TaskId {
+		blake2_256(&(owner_id, task_id).encode())
+	}
+
+	fn migrate_task_list<T: Config>(tasks: OldTaskIdList<T>) -> crate::TaskIdList<T> {
+		tasks
+			.into_iter()
+			.map(|(owner_id, task_id)| {
+				let task_id = migrated_task_id::<T>(&owner_id, &task_id);
+				(owner_id, task_id)
+			})
+			.collect()
+	}
+
+	// Move every entry of a map keyed by the owner and the id of a task to the new id of the
+	// task, and return how many entries were moved.
+	fn migrate_task_map<T, V, Old, New>() -> u64
+	where
+		T: Config,
+		V: FullCodec,
+		Old: IterableStorageDoubleMap<AccountOf<T>, OldTaskId, V>,
+		New: frame_support::storage::StorageDoubleMap<AccountOf<T>, TaskId, V>,
+	{
+		// The old and the new entries share a prefix, so the old ones are all taken out first
+		let entries: Vec<_> = Old::drain().collect();
+		for (owner_id, task_id, value) in entries.iter() {
+			New::insert(owner_id, migrated_task_id::<T>(owner_id, task_id), value);
+		}
+		entries.len() as u64
+	}
+
+	/// Replace the formatted task ids with 32 byte ids, in every storage item that refers to
+	/// a task.
+	///
+	/// The new id of a task is the hash of its owner and its old id, so a task keeps the same id
+	/// in every storage item. The cursors of unfinished purges are dropped, and a sorted task
+	/// index rebuild in progress starts over, since both point into the old keys of `Tasks`.
+	///
+	/// Every task is moved within the upgrade block.
+	pub struct MigrateToV4<T>(PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV4<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() >= 4 {
+				log::info!(
+					target: "runtime::automation-price",
+					"Task id migration skipped, storage is already at v4"
+				);
+				return T::DbWeight::get().reads(1u64)
+			}
+
+			let old_tasks: Vec<_> = old::Tasks::<T>::drain().collect();
+			let task_count = old_tasks.len() as u64;
+			for (owner_id, old_task_id, old_task) in old_tasks {
+				let task_id = migrated_task_id::<T>(&owner_id, &old_task_id);
+				let task = v8::OldTask::<T> {
+					owner_id: old_task.owner_id,
+					task_id,
+					chain: old_task.chain,
+					exchange: old_task.exchange,
+					asset_pair: old_task.asset_pair,
+					expired_at: old_task.expired_at,
+					trigger_function: old_task.trigger_function,
+					trigger_params: old_task.trigger_params,
+					action: old_task.action,
+				};
+				v8::old::Tasks::<T>::insert(&owner_id, task_id, task);
+			}
+
+			let mut moved = migrate_task_map::<T, _, old::TaskDeposits<T>, TaskDeposits<T>>();
+			moved += migrate_task_map::<T, _, old::TaskRecurrences<T>, TaskRecurrences<T>>();
+			moved += migrate_task_map::<T, _, old::MissedTaskPolicies<T>, MissedTaskPolicies<T>>();
+
+			let linked_tasks: Vec<_> = old::LinkedTasks::<T>::drain().collect();
+			moved += linked_tasks.len() as u64;
+			for (owner_id, task_id, linked_task_id) in linked_tasks {
+				LinkedTasks::<T>::insert(
+					&owner_id,
+					migrated_task_id::<T>(&owner_id, &task_id),
+					migrated_task_id::<T>(&owner_id, &linked_task_id),
+				);
+			}
+
+			let mut translated = 0u64;
+			let mut translate_index = |index: BTreeMap<u128, OldTaskIdList<T>>| {
+				translated += 1;
+				let index: BTreeMap<u128, crate::TaskIdList<T>> = index
+					.into_iter()
+					.map(|(price, tasks)| (price, migrate_task_list(tasks)))
+					.collect();
+				Some(index)
+			};
+			SortedTasksAbove::<T>::translate_values(&mut translate_index);
+			SortedTasksBelow::<T>::translate_values(&mut translate_index);
+			UnsortedTasks::<T>::translate_values(|tasks: OldTaskIdList<T>| {
+				translated += 1;
+				Some(migrate_task_list(tasks))
+			});
+
+			let _ = TaskQueue::<T>::translate(|tasks: Option<OldTaskIdList<T>>| {
+				tasks.map(migrate_task_list)
+			});
+			let _ = MissedTasksQueue::<T>::translate(|tasks: Option<OldTaskIdList<T>>| {
+				tasks.map(migrate_task_list)
+			});
+			let _ = CoolingDownTasks::<T>::translate(
+				|tasks: Option<BTreeMap<u128, OldTaskIdList<T>>>| {
+					tasks.map(|tasks| {
+						tasks
+							.into_iter()
+							.map(|(armed_at, tasks)| (armed_at, migrate_task_list(tasks)))
+							.collect()
+					})
+				},
+			);
+			let _ = SortedTasksByExpiration::<T>::translate(
+				|tasks: Option<BTreeMap<u128, BTreeMap<OldTaskId, AccountOf<T>>>>| {
+					tasks.map(|tasks| {
+						tasks
+							.into_iter()
+							.map(|(expired_at, tasks)| {
+								let tasks = tasks
+									.into_iter()
+									.map(|(task_id, owner_id)| {
+										(migrated_task_id::<T>(&owner_id, &task_id), owner_id)
+									})
+									.collect();
+								(expired_at, tasks)
+							})
+							.collect()
+					})
+				},
+			);
+
+			let purges = PurgeCursors::<T>::clear(u32::MAX, None).unique;
+			if let Some(IndexMigrationStage::Rebuilding { .. }) = IndexMigration::<T>::get() {
+				let _ = SortedTasksAbove::<T>::clear(u32::MAX, None);
+				let _ = SortedTasksBelow::<T>::clear(u32::MAX, None);
+				let _ = UnsortedTasks::<T>::clear(u32::MAX, None);
+				IndexMigration::<T>::put(IndexMigrationStage::Rebuilding {
+					cursor: None,
+					migrated: 0,
+				});
+			}
+
+			StorageVersion::new(4).put::<Pallet<T>>();
+			log::info!(
+				target: "runtime::automation-price",
+				"Migrated the ids of {} tasks",
+				task_count
+			);
+
+			T::DbWeight::get()
+				.reads_writes(task_count + moved, 2 * (task_count + moved))
+				.saturating_add(T::DbWeight::get().reads_writes(translated, translated))
+				.saturating_add(T::DbWeight::get().reads_writes(6u64, 6u64 + u64::from(purges)))
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			Ok(count_tasks::<T>())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+			let task_count_before = u64::decode(&mut state.as_slice())
+				.map_err(|_| "failed to decode the pre-upgrade task count")?;
+			let mut task_count_after = 0u64;
+			for (owner_id, task_id, task) in v8::old::Tasks::<T>::iter() {
+				frame_support::ensure!(
+					task.owner_id == owner_id && task.task_id == task_id,
+					"Task id migration must key every task by its new id"
+				);
+				task_count_after += 1;
+			}
+
+			frame_support::ensure!(
+				Pallet::<T>::on_chain_storage_version() == 4,
+				"AutomationPrice storage version should be 4"
+			);
+			frame_support::ensure!(
+				task_count_before == task_count_after,
+				"Task id migration must not remove tasks"
+			);
+			Ok(())
+		}
+	}
+}
+
+pub mod v5 {
+	use super::*;
+	use crate::TriggerFunction;
+
+	/// The sorted task indexes before they were split into price buckets.
+	pub mod old {
+		use super::*;
+
+		#[storage_alias]
+		pub type SortedTasksAbove<T: Config> = StorageNMap<
+			Pallet<T>,
+			(
+				NMapKey<Twox64Concat, Vec<u8>>,
+				NMapKey<Twox64Concat, Vec<u8>>,
+				NMapKey<Twox64Concat, (Vec<u8>, Vec<u8>)>,
+			),
+			BTreeMap<u128, TaskIdList<T>>,
+		>;
+
+		#[storage_alias]
+		pub type SortedTasksBelow<T: Config> = StorageNMap<
+			Pallet<T>,
+			(
+				NMapKey<Twox64Concat, Vec<u8>>,
+				NMapKey<Twox64Concat, Vec<u8>>,
+				NMapKey<Twox64Concat, (Vec<u8>, Vec<u8>)>,
+			),
+			BTreeMap<u128, TaskIdList<T>>,
+		>;
+	}
+
+	/// Split the sorted task index of every asset into buckets of `PriceBucketWidth`.
+	///
+	/// Every index is moved within the upgrade block. A sorted task index rebuild in progress
+	/// carries on into the buckets.
+	pub struct MigrateToV5<T>(PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV5<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() >= 5 {
+				log::info!(
+					target: "runtime::automation-price",
+					"Sorted task bucket migration skipped, storage is already at v5"
+				);
+				return T::DbWeight::get().reads(1u64)
+			}
+
+			// The old and the new entries share a prefix, so the old ones are all taken out first
+			let above: Vec<_> = old::SortedTasksAbove::<T>::drain().collect();
+			let below: Vec<_> = old::SortedTasksBelow::<T>::drain().collect();
+			let index_count = (above.len() + below.len()) as u64;
+			let mut price_count = 0u64;
+			let indexes = above
+				.into_iter()
+				.map(|(key, tasks)| (key, TriggerFunction::Gt, tasks))
+				.chain(below.into_iter().map(|(key, tasks)| (key, TriggerFunction::Lt, tasks)));
+			for ((chain, exchange, asset_pair), trigger_function, tasks) in indexes {
+				price_count += tasks.len() as u64;
+				Pallet::<T>::put_sorted_tasks_index(
+					(chain, exchange, asset_pair, trigger_function),
+					tasks,
+				);
+			}
+
+			StorageVersion::new(5).put::<Pallet<T>>();
+			log::info!(
+				target: "runtime::automation-price",
+				"Split {} sorted task indexes into price buckets",
+				index_count
+			);
+
+			T::DbWeight::get()
+				.reads_writes(2 * index_count, index_count)
+				.saturating_add(T::DbWeight::get().reads_writes(price_count, 2 * price_count))
+				.saturating_add(T::DbWeight::get().writes(1u64))
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			let task_count: u64 = old::SortedTasksAbove::<T>::iter_values()
+				.chain(old::SortedTasksBelow::<T>::iter_values())
+				.map(|tasks| tasks.values().map(|task_ids| task_ids.len() as u64).sum::<u64>())
+				.sum();
+			Ok(task_count.encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+			let task_count_before = u64::decode(&mut state.as_slice())
+				.map_err(|_| "failed to decode the pre-upgrade task count")?;
+			let task_count_after: u64 = crate::SortedTasksAbove::<T>::iter_values()
+				.chain(crate::SortedTasksBelow::<T>::iter_values())
+				.map(|tasks| tasks.values().map(|task_ids| task_ids.len() as u64).sum::<u64>())
+				.sum();
+
+			frame_support::ensure!(
+				Pallet::<T>::on_chain_storage_version() == 5,
+				"AutomationPrice storage version should be 5"
+			);
+			frame_support::ensure!(
+				task_count_before == task_count_after,
+				"Sorted task bucket migration must not remove tasks"
+			);
+			Ok(())
+		}
+	}
+}
+
+pub mod v6 {
+	use super::*;
+	use crate::{AssetCount, AssetRegistry};
+
+	/// Count the asset pairs of `AssetRegistry` into `AssetCount`, which caps the registry at
+	/// `MaxAssets`.
+	pub struct MigrateToV6<T>(PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV6<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() >= 6 {
+				log::info!(
+					target: "runtime::automation-price",
+					"Asset count migration skipped, storage is already at v6"
+				);
+				return T::DbWeight::get().reads(1u64)
+			}
+
+			let asset_count = AssetRegistry::<T>::iter_keys().count() as u32;
+			AssetCount::<T>::put(asset_count);
+			StorageVersion::new(6).put::<Pallet<T>>();
+			log::info!(
+				target: "runtime::automation-price",
+				"Counted {} asset pairs in the registry",
+				asset_count
+			);
+
+			T::DbWeight::get().reads_writes(asset_count as u64 + 1, 2u64)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(_state: Vec<u8>) -> Result<(), &'static str> {
+			frame_support::ensure!(
+				Pallet::<T>::on_chain_storage_version() == 6,
+				"AutomationPrice storage version should be 6"
+			);
+			frame_support::ensure!(
+				AssetCount::<T>::get() as usize == AssetRegistry::<T>::iter_keys().count(),
+				"AssetCount does not match the asset pairs of the registry"
+			);
+			Ok(())
+		}
+	}
+}
+
+pub mod v7 {
+	use super::*;
+	use crate::AssetTaskCounts;
+
+	/// Count the tasks of each asset pair into `AssetTaskCounts`, which caps them at
+	/// `MaxTasksPerAssetPair`. The tasks are still stored in the shape they had before v8.
+	pub struct MigrateToV7<T>(PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV7<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() >= 7 {
+				log::info!(
+					target: "runtime::automation-price",
+					"Asset task count migration skipped, storage is already at v7"
+				);
+				return T::DbWeight::get().reads(1u64)
+			}
+
+			let mut task_counts = BTreeMap::<_, u32>::new();
+			let mut task_count = 0u64;
+			for task in v8::old::Tasks::<T>::iter_values() {
+				let key = (task.chain, task.exchange, task.asset_pair);
+				*task_counts.entry(key).or_default() += 1;
+				task_count += 1;
+			}
+			let asset_pairs = task_counts.len() as u64;
+			for (key, count) in task_counts {
+				AssetTaskCounts::<T>::insert(key, count);
+			}
+			StorageVersion::new(7).put::<Pallet<T>>();
+			log::info!(
+				target: "runtime::automation-price",
+				"Counted {} tasks on {} asset pairs",
+				task_count,
+				asset_pairs
+			);
+
+			T::DbWeight::get().reads_writes(task_count + 1, asset_pairs + 1)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(_state: Vec<u8>) -> Result<(), &'static str> {
+			frame_support::ensure!(
+				Pallet::<T>::on_chain_storage_version() == 7,
+				"AutomationPrice storage version should be 7"
+			);
+			frame_support::ensure!(
+				AssetTaskCounts::<T>::iter_values().map(|count| count as usize).sum::<usize>() ==
+					v8::old::Tasks::<T>::iter_values().count(),
+				"AssetTaskCounts does not match the tasks"
+			);
+			Ok(())
+		}
+	}
+}
+
+pub mod v8 {
+	use super::*;
+	use crate::{AccountOf, Action, AssetPayment, InstructionSequence, Task, TaskId};
+	use codec::{Decode, Encode};
+	use xcm::latest::MultiLocation;
+
+	/// An action as it was stored before dynamic dispatch tasks chose the asset of their fees.
+	#[derive(Encode, Decode)]
+	pub enum OldAction<AccountId> {
+		XCMP {
+			destination: MultiLocation,
+			schedule_fee: MultiLocation,
+			execution_fee: AssetPayment,
+			encoded_call: Vec<u8>,
+			encoded_call_weight: Weight,
+			overall_weight: Weight,
+			schedule_as: Option<AccountId>,
+			instruction_sequence: InstructionSequence,
+		},
+		DynamicDispatch {
+			encoded_call: Vec<u8>,
+		},
+	}
+
+	impl<AccountId> From<OldAction<AccountId>> for Action<AccountId> {
+		fn from(action: OldAction<AccountId>) -> Self {
+			match action {
+				OldAction::XCMP {
+					destination,
+					schedule_fee,
+					execution_fee,
+					encoded_call,
+					encoded_call_weight,
+					overall_weight,
+					schedule_as,
+					instruction_sequence,
+				} => Action::XCMP {
+					destination,
+					schedule_fee,
+					execution_fee,
+					encoded_call,
+					encoded_call_weight,
+					overall_weight,
+					schedule_as,
+					instruction_sequence,
+				},
+				// The fees of these tasks were always paid in the native token
+				OldAction::DynamicDispatch { encoded_call } =>
+					Action::DynamicDispatch { encoded_call, schedule_fee: MultiLocation::default() },
+			}
+		}
+	}
+
+	/// A task as it was stored before dynamic dispatch tasks chose the asset of their fees.
+	#[derive(Encode, Decode)]
+	pub struct OldTask<T: Config> {
+		pub owner_id: AccountOf<T>,
+		pub task_id: TaskId,
+		pub chain: Vec<u8>,
+		pub exchange: Vec<u8>,
+		pub asset_pair: (Vec<u8>, Vec<u8>),
+		pub expired_at: u128,
+		pub trigger_function: Vec<u8>,
+		pub trigger_params: Vec<u128>,
+		pub action: OldAction<AccountOf<T>>,
+	}
+
+	impl<T: Config> From<OldTask<T>> for Task<T> {
+		fn from(task: OldTask<T>) -> Self {
+			Task::<T> {
+				owner_id: task.owner_id,
+				task_id: task.task_id,
+				chain: task.chain,
+				exchange: task.exchange,
+				asset_pair: task.asset_pair,
+				expired_at: task.expired_at,
+				trigger_function: task.trigger_function,
+				trigger_params: task.trigger_params,
+				action: task.action.into(),
+			}
+		}
+	}
+
+	/// The storage items holding tasks in their old shape.
+	pub mod old {
+		use super::*;
+
+		#[storage_alias]
+		pub type Tasks<T: Config> = StorageDoubleMap<
+			Pallet<T>,
+			Twox64Concat,
+			AccountOf<T>,
+			Twox64Concat,
+			TaskId,
+			OldTask<T>,
+		>;
+	}
+
+	/// Store the asset the fees of dynamic dispatch tasks are paid in with their action. The tasks
+	/// scheduled before paid them in the native token.
+	///
+	/// Every task is translated within the upgrade block.
+	pub struct MigrateToV8<T>(PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV8<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() >= 8 {
+				log::info!(
+					target: "runtime::automation-price",
+					"Task fee asset migration skipped, storage is already at v8"
+				);
+				return T::DbWeight::get().reads(1u64)
+			}
+
+			let mut task_count = 0u64;
+			Tasks::<T>::translate(|_, _, task: OldTask<T>| {
+				task_count += 1;
+				Some(task.into())
+			});
+			StorageVersion::new(8).put::<Pallet<T>>();
+			log::info!(
+				target: "runtime::automation-price",
+				"Migrated the actions of {} tasks",
+				task_count
+			);
+
+			T::DbWeight::get().reads_writes(task_count + 1, task_count + 1)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			Ok(count_tasks::<T>())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+			let task_count_before = u64::decode(&mut state.as_slice())
+				.map_err(|_| "failed to decode the pre-upgrade task count")?;
+			let task_count_after = Tasks::<T>::iter_values().count() as u64;
+
+			frame_support::ensure!(
+				Pallet::<T>::on_chain_storage_version() == 8,
+				"AutomationPrice storage version should be 8"
+			);
+			frame_support::ensure!(
+				task_count_before == task_count_after,
+				"Task fee asset migration must not remove tasks"
+			);
+			Ok(())
+		}
+	}
+}
+
+pub mod v9 {
+	use super::*;
+
+	/// Give the keeper pot the minimum balance of the native token, which the pots of new chains
+	/// get at genesis, so that paying out the rewards it holds never reaps it.
+	pub struct MigrateToV9<T>(PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV9<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() >= 9 {
+				log::info!(
+					target: "runtime::automation-price",
+					"Keeper pot migration skipped, storage is already at v9"
+				);
+				return T::DbWeight::get().reads(1u64)
+			}
+
+			if Pallet::<T>::endow_keeper_pot() {
+				log::info!(target: "runtime::automation-price", "Endowed the keeper pot");
+			}
+			StorageVersion::new(9).put::<Pallet<T>>();
+
+			T::DbWeight::get().reads_writes(2u64, 2u64)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(_state: Vec<u8>) -> Result<(), &'static str> {
+			use frame_support::traits::Currency;
+
+			frame_support::ensure!(
+				Pallet::<T>::on_chain_storage_version() == 9,
+				"AutomationPrice storage version should be 9"
+			);
+			frame_support::ensure!(
+				T::Currency::free_balance(&Pallet::<T>::keeper_pot_account()) >=
+					T::Currency::minimum_balance(),
+				"The keeper pot holds less than the minimum balance"
+			);
+			Ok(())
+		}
+	}
+}
@@ -21,18 +21,18 @@ use crate::TaskId;
 
 use frame_support::{
 	assert_ok, construct_runtime, parameter_types,
-	traits::{ConstU32, Contains, Everything},
+	traits::{ConstBool, ConstU32, ConstU64, ConstU8, Contains, Everything, Randomness},
 	weights::Weight,
 	PalletId,
 };
 use frame_system::{self as system, RawOrigin};
 use orml_traits::parameter_type_with_key;
-use primitives::{EnsureProxy, TransferCallCreator};
+use primitives::{EnsureProxy, LinearStorageDeposit, TransferCallCreator};
 use sp_core::H256;
 use sp_runtime::{
 	testing::Header,
-	traits::{AccountIdConversion, BlakeTwo256, Convert, IdentityLookup},
-	AccountId32, MultiAddress, Perbill,
+	traits::{AccountIdConversion, BlakeTwo256, Convert, Hash, IdentityLookup},
+	AccountId32, DispatchError, MultiAddress, MultiSignature, MultiSigner, Perbill,
 };
 use sp_std::{marker::PhantomData, vec::Vec};
 use xcm::latest::prelude::*;
@@ -52,6 +52,7 @@ pub const ALICE: [u8; 32] = [1u8; 32];
 pub const BOB: [u8; 32] = [2u8; 32];
 pub const DELEGATOR_ACCOUNT: [u8; 32] = [3u8; 32];
 pub const PROXY_ACCOUNT: [u8; 32] = [4u8; 32];
+pub const ORACLE_COMMITTEE_MEMBER: [u8; 32] = [5u8; 32];
 
 pub const PARA_ID: u32 = 2000;
 pub const NATIVE: CurrencyId = 0;
@@ -88,7 +89,7 @@ construct_runtime!(
 		ParachainInfo: parachain_info::{Pallet, Storage, Config},
 		Tokens: orml_tokens::{Pallet, Storage, Event<T>, Config<T>},
 		Currencies: orml_currencies::{Pallet, Call},
-		AutomationPrice: pallet_automation_price::{Pallet, Call, Storage, Event<T>},
+		AutomationPrice: pallet_automation_price::{Pallet, Call, Storage, Event<T>, Config<T>, ValidateUnsigned},
 	}
 );
 
@@ -126,7 +127,7 @@ impl system::Config for Test {
 }
 
 parameter_types! {
-	pub const ExistentialDeposit: u64 = 1;
+	pub static ExistentialDeposit: u64 = 1;
 	pub const MaxLocks: u32 = 50;
 	pub const MaxReserves: u32 = 50;
 }
@@ -196,6 +197,7 @@ impl pallet_automation_price::Config for Test {
 	type MaxTasksPerSlot = MaxTasksPerSlot;
 	type MaxTasksPerAccount = MaxTasksPerAccount;
 	type MaxTasksOverall = MaxTasksOverall;
+	type MaxTasksPerAssetPair = MaxTasksPerAssetPair;
 	type MaxBlockWeight = MaxBlockWeight;
 	type MaxWeightPercentage = MaxWeightPercentage;
 	type WeightInfo = MockWeight<Test>;
@@ -211,6 +213,56 @@ impl pallet_automation_price::Config for Test {
 	type XcmpTransactor = MockXcmpTransactor<Test, Balances>;
 
 	type EnsureProxy = MockEnsureProxy;
+	type OracleCommittee = MockOracleCommittee;
+	type OnTaskExecuted = ();
+	type PurgeOrigin = system::EnsureRoot<AccountId>;
+	type ShutdownOrigin = system::EnsureRoot<AccountId>;
+	type OracleProviderOrigin = system::EnsureRoot<AccountId>;
+	type AssetAdminOrigin = system::EnsureRoot<AccountId>;
+	type AssetDeposit = AssetDeposit;
+	type AssetDepositSlash = ();
+	type TipDestination = ();
+	type PalletId = AutomationPricePalletId;
+	type KeeperRewardShare = KeeperRewardShare;
+	type MaxTasksPerKeeperCall = ConstU32<4>;
+	type MaxAssets = MaxAssets;
+	type MaxTasksPerPurge = ConstU32<10>;
+	type MaxShiftedTasksPerAssetPair = MaxShiftedTasksPerAssetPair;
+	type PriceBucketWidth = PriceBucketWidth;
+	type MaxXcmpSendAttempts = MaxXcmpSendAttempts;
+	type XcmpRetryDelay = XcmpRetryDelay;
+	type MaxXcmpRetries = MaxXcmpRetries;
+	type StorageDeposit = LinearStorageDeposit<TaskDepositPerItem, TaskDepositPerByte>;
+	type OracleUpdatePriority = ConstU64<1_000>;
+	type WaiveOracleFees = ConstBool<true>;
+	type OracleSignature = MultiSignature;
+	type OracleSigner = MultiSigner;
+	type UnsignedSubmissionInterval = ConstU64<5>;
+	type OracleSubmissionRetention = OracleSubmissionRetention;
+	type PriceDecimals = ConstU8<10>;
+	type MaxPriceHistory = MaxPriceHistory;
+	type ConditionEvaluator = DefaultConditionEvaluator;
+	type MaxExtraConditions = ConstU32<2>;
+	type OracleQuorum = OracleQuorum;
+	type PriceAggregator = MedianPrice;
+	type MaxPriceStaleness = MaxPriceStaleness;
+	type Randomness = MockRandomness;
+	type RuntimeCall = RuntimeCall;
+	type ScheduleAllowList = ScheduleAllowList;
+}
+
+pub struct MockRandomness;
+impl Randomness<H256, u64> for MockRandomness {
+	fn random(subject: &[u8]) -> (H256, u64) {
+		(BlakeTwo256::hash_of(&(subject, RandomSeed::get())), System::block_number())
+	}
+}
+
+pub struct MockOracleCommittee;
+impl Contains<AccountId> for MockOracleCommittee {
+	fn contains(who: &AccountId) -> bool {
+		*who == AccountId32::new(ORACLE_COMMITTEE_MEMBER)
+	}
 }
 
 parameter_types! {
@@ -218,11 +270,34 @@ parameter_types! {
 	// Mock value, purposely set to a small number so easiser to test limit reached
 	pub const MaxTasksOverall: u32 = 1024;
 	pub const MaxTasksPerAccount: u32 = 16;
+	pub static MaxTasksPerAssetPair: u32 = 1024;
 	#[derive(Debug)]
 	pub const MaxScheduleSeconds: u64 = 24 * 60 * 60;
-	pub const MaxBlockWeight: u64 = 20_000_000;
+	pub const MaxBlockWeight: Weight = Weight::from_parts(20_000_000, 5 * 1024 * 1024);
 	pub const MaxWeightPercentage: Perbill = Perbill::from_percent(40);
 	pub const ExecutionWeightFee: Balance = NATIVE_EXECUTION_WEIGHT_FEE;
+	// No deposit unless a test sets one, so fee assertions stay exact
+	pub static TaskDepositPerItem: Balance = 0;
+	pub static TaskDepositPerByte: Balance = 0;
+	pub const AssetDeposit: Balance = 1_000;
+	pub static MaxAssets: u32 = 100;
+	pub const OracleSubmissionRetention: u64 = 100;
+	pub static MaxPriceHistory: u32 = 3;
+	pub static OracleQuorum: u32 = 1;
+	pub static MaxPriceStaleness: u64 = u64::MAX;
+	pub static MaxShiftedTasksPerAssetPair: u32 = 100;
+	pub static PriceBucketWidth: u128 = 1_000;
+	pub static MaxXcmpSendAttempts: u32 = 3;
+	pub const XcmpRetryDelay: u64 = 2;
+	pub static MaxXcmpRetries: u32 = 10;
+	pub static RandomSeed: u64 = 0;
+	pub const AutomationPricePalletId: PalletId = PalletId(*b"py/autpr");
+	// No keeper reward unless a test sets one, so fee assertions stay exact
+	pub static KeeperRewardShare: Perbill = Perbill::zero();
+	// The transacts handed to the mock XCMP transactor
+	pub static TransactedXcms: Vec<TransactedXcm> = vec![];
+	// The error the mock XCMP transactor fails every transact with
+	pub static XcmpTransactError: Option<DispatchError> = None;
 
 	// When unit testing dynamic dispatch, we use the real weight value of the extrinsics call
 	// This is an external lib that we don't own so we try to not mock, follow the rule don't mock
@@ -285,8 +360,12 @@ impl<Test: frame_system::Config> pallet_automation_price::WeightInfo for MockWei
 		Weight::from_parts(220_000_000_u64, 0u64)
 	}
 
-	fn schedule_xcmp_task_extrinsic() -> Weight {
-		Weight::from_parts(24_000_000_u64, 0u64)
+	fn delete_asset_extrinsic(v: u32) -> Weight {
+		Weight::from_parts(220_000_000_u64 + 40_000_000_u64 * v as u64, 0u64)
+	}
+
+	fn schedule_xcmp_task_extrinsic(v: u32) -> Weight {
+		Weight::from_parts(24_000_000_u64 + 1_000_u64 * v as u64, 0u64)
 	}
 
 	fn cancel_task_extrinsic() -> Weight {
@@ -300,35 +379,178 @@ impl<Test: frame_system::Config> pallet_automation_price::WeightInfo for MockWei
 	fn remove_task() -> Weight {
 		Weight::from_ref_time(20_000_000_u64)
 	}
+
+	fn purge_tasks_extrinsic(v: u32) -> Weight {
+		Weight::from_parts(20_000_000_u64 * v as u64, 0u64)
+	}
+
+	fn reclaim_deposit() -> Weight {
+		Weight::from_parts(20_000_000_u64, 0u64)
+	}
+
+	fn shift_tasks(v: u32) -> Weight {
+		Weight::from_parts(30_000_000_u64 + 2_000_000_u64 * v as u64, 0u64)
+	}
+
+	fn update_task_queue(v: u32) -> Weight {
+		Weight::from_parts(5_000_000_u64 + 200_000_u64 * v as u64, 0u64)
+	}
+
+	fn set_shutdown_flags() -> Weight {
+		Weight::from_parts(10_000_000_u64, 0u64)
+	}
+
+	fn update_task_trigger() -> Weight {
+		Weight::from_parts(40_000_000_u64, 0u64)
+	}
+
+	fn transfer_asset_ownership() -> Weight {
+		Weight::from_parts(20_000_000_u64, 0u64)
+	}
+
+	fn accept_asset_ownership() -> Weight {
+		Weight::from_parts(40_000_000_u64, 0u64)
+	}
+
+	fn set_task_schedule() -> Weight {
+		Weight::from_parts(20_000_000_u64, 0u64)
+	}
+
+	fn run_dynamic_dispatch_action() -> Weight {
+		Weight::from_parts(20_000_000_u64, 0u64)
+	}
+
+	fn run_dynamic_dispatch_action_fail_decode() -> Weight {
+		Weight::from_parts(1_000_000_u64, 0u64)
+	}
+
+	fn add_oracle_provider() -> Weight {
+		Weight::from_parts(15_000_000_u64, 0u64)
+	}
+
+	fn remove_oracle_provider() -> Weight {
+		Weight::from_parts(15_000_000_u64, 0u64)
+	}
+
+	fn set_asset_symbol() -> Weight {
+		Weight::from_parts(20_000_000_u64, 0u64)
+	}
+
+	fn set_circuit_breaker() -> Weight {
+		Weight::from_parts(20_000_000_u64, 0u64)
+	}
+
+	fn resume_asset() -> Weight {
+		Weight::from_parts(15_000_000_u64, 0u64)
+	}
+
+	fn set_missed_task_policy() -> Weight {
+		Weight::from_parts(15_000_000_u64, 0u64)
+	}
+
+	fn pause_task() -> Weight {
+		Weight::from_parts(30_000_000_u64, 0u64)
+	}
+
+	fn resume_task() -> Weight {
+		Weight::from_parts(30_000_000_u64, 0u64)
+	}
+	fn set_task_time_window() -> Weight {
+		Weight::from_parts(20_000_000_u64, 0u64)
+	}
+	fn set_task_conditions() -> Weight {
+		Weight::from_parts(30_000_000_u64, 0u64)
+	}
+	fn set_asset_task_cap() -> Weight {
+		Weight::from_parts(20_000_000_u64, 0u64)
+	}
+	fn set_task_tip() -> Weight {
+		Weight::from_parts(20_000_000_u64, 0u64)
+	}
+	fn execute_ready_tasks(v: u32) -> Weight {
+		Weight::from_parts(500_000_000_u64 * v as u64, 0u64)
+	}
+}
+
+// A transact handed to the mock XCMP transactor
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransactedXcm {
+	pub destination: MultiLocation,
+	pub fee_location: MultiLocation,
+	pub fee: u128,
+	pub caller: AccountId,
+	pub encoded_call: Vec<u8>,
+	pub encoded_call_weight: Weight,
+	pub overall_weight: Weight,
+	pub flow: InstructionSequence,
 }
 
 pub struct MockXcmpTransactor<T, C>(PhantomData<(T, C)>);
 impl<T, C> pallet_xcmp_handler::XcmpTransactor<T::AccountId, CurrencyId>
 	for MockXcmpTransactor<T, C>
 where
-	T: Config + pallet::Config<Currency = C>,
+	T: Config + pallet::Config<Currency = C> + frame_system::Config<AccountId = AccountId>,
 	C: frame_support::traits::ReservableCurrency<T::AccountId>,
 {
 	fn transact_xcm(
-		_destination: MultiLocation,
-		_location: xcm::latest::MultiLocation,
-		_fee: u128,
-		_caller: T::AccountId,
-		_transact_encoded_call: sp_std::vec::Vec<u8>,
-		_transact_encoded_call_weight: Weight,
-		_overall_weight: Weight,
-		_flow: InstructionSequence,
+		destination: MultiLocation,
+		location: xcm::latest::MultiLocation,
+		fee: u128,
+		caller: T::AccountId,
+		transact_encoded_call: sp_std::vec::Vec<u8>,
+		transact_encoded_call_weight: Weight,
+		overall_weight: Weight,
+		flow: InstructionSequence,
+	) -> DispatchResultWithPostInfo {
+		let mut transacted = TransactedXcms::get();
+		transacted.push(TransactedXcm {
+			destination,
+			fee_location: location,
+			fee,
+			caller,
+			encoded_call: transact_encoded_call,
+			encoded_call_weight: transact_encoded_call_weight,
+			overall_weight,
+			flow,
+		});
+		TransactedXcms::set(transacted);
+
+		match XcmpTransactError::get() {
+			Some(error) => Err(error.into()),
+			None => Ok(().into()),
+		}
+	}
+
+	fn pay_xcm_fee(
+		_: CurrencyId,
+		_: T::AccountId,
+		_: u128,
 	) -> Result<(), sp_runtime::DispatchError> {
 		Ok(())
 	}
 
-	fn pay_xcm_fee(
+	fn refund_xcm_fee(
 		_: CurrencyId,
 		_: T::AccountId,
 		_: u128,
 	) -> Result<(), sp_runtime::DispatchError> {
 		Ok(())
 	}
+
+	fn check_transact_weights(
+		_destination: MultiLocation,
+		_encoded_call_len: usize,
+		_encoded_call_weight: Weight,
+		_overall_weight: Weight,
+		_flow: InstructionSequence,
+	) -> Result<(), sp_runtime::DispatchError> {
+		Ok(())
+	}
+
+	// `DispatchError::Unavailable` stands for a message the destination's channel refused
+	fn is_delivery_failure(error: &DispatchError) -> bool {
+		*error == DispatchError::Unavailable
+	}
 }
 
 pub struct ScheduleAllowList;
@@ -427,6 +649,11 @@ pub fn events() -> Vec<RuntimeEvent> {
 	evt
 }
 
+// A task id for tests, derived from a readable label
+pub fn task_id_of(label: &str) -> TaskId {
+	sp_io::hashing::blake2_256(label.as_bytes())
+}
+
 // A utility test function to pluck out the task id from events, useful when dealing with multiple
 // task scheduling
 pub fn get_task_ids_from_events() -> Vec<TaskId> {
@@ -563,34 +790,40 @@ pub fn setup_assets_and_prices(sender: &AccountId32, block_time: u128) {
 	const pair3_price: u128 = 10_000_u128;
 	assert_ok!(AutomationPrice::update_asset_prices(
 		RuntimeOrigin::signed(sender.clone()),
-		vec![chain1.to_vec()],
-		vec![exchange1.to_vec()],
-		vec![asset1.to_vec()],
-		vec![asset2.to_vec()],
-		vec![pair1_price],
-		vec![block_time],
-		vec![1000],
+		vec![PriceUpdate {
+			chain: chain1.to_vec(),
+			exchange: exchange1.to_vec(),
+			asset1: asset1.to_vec(),
+			asset2: asset2.to_vec(),
+			price: pair1_price,
+			submitted_at: block_time,
+			round: 1,
+		}],
 	));
 
 	assert_ok!(AutomationPrice::update_asset_prices(
 		RuntimeOrigin::signed(sender.clone()),
-		vec![chain2.to_vec()],
-		vec![exchange1.to_vec()],
-		vec![asset2.to_vec()],
-		vec![asset3.to_vec()],
-		vec![pair2_price],
-		vec![block_time],
-		vec![1000],
+		vec![PriceUpdate {
+			chain: chain2.to_vec(),
+			exchange: exchange1.to_vec(),
+			asset1: asset2.to_vec(),
+			asset2: asset3.to_vec(),
+			price: pair2_price,
+			submitted_at: block_time,
+			round: 1,
+		}],
 	));
 
 	assert_ok!(AutomationPrice::update_asset_prices(
 		RuntimeOrigin::signed(sender.clone()),
-		vec![chain2.to_vec()],
-		vec![exchange1.to_vec()],
-		vec![asset1.to_vec()],
-		vec![asset3.to_vec()],
-		vec![pair3_price],
-		vec![block_time],
-		vec![1000],
+		vec![PriceUpdate {
+			chain: chain2.to_vec(),
+			exchange: exchange1.to_vec(),
+			asset1: asset1.to_vec(),
+			asset2: asset3.to_vec(),
+			price: pair3_price,
+			submitted_at: block_time,
+			round: 1,
+		}],
 	));
 }
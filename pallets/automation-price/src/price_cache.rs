@@ -0,0 +1,95 @@
+// This file is part of OAK Blockchain.
+
+// Copyright (C) 2022 OAK Network
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{Config, Event, Pallet, PriceData, PriceHistory, PriceRegistry};
+use core::marker::PhantomData;
+use frame_support::{traits::Get, weights::Weight};
+use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
+
+/// The prices read while triggering and running tasks in a block.
+///
+/// Prices do not change during `on_initialize`, so each asset pair only needs to be read from
+/// `PriceRegistry` and `PriceHistory` once, however many of its tasks are evaluated. A price older
+/// than `MaxPriceStaleness` is read as no price at all, and reported with a `PriceStale` event. So
+/// is the price of an asset pair halted by its circuit breaker.
+pub struct PriceCache<T: Config> {
+	prices: BTreeMap<(Vec<u8>, Vec<u8>, (Vec<u8>, Vec<u8>)), Option<PriceData>>,
+	histories: BTreeMap<(Vec<u8>, Vec<u8>, (Vec<u8>, Vec<u8>)), Vec<PriceData>>,
+	_config: PhantomData<T>,
+}
+
+impl<T: Config> Default for PriceCache<T> {
+	fn default() -> Self {
+		Self { prices: BTreeMap::new(), histories: BTreeMap::new(), _config: PhantomData }
+	}
+}
+
+impl<T: Config> PriceCache<T> {
+	/// The price of the asset pair, and the weight of reading it. Only the first read of an asset
+	/// pair goes to storage.
+	pub fn get(
+		&mut self,
+		chain: &[u8],
+		exchange: &[u8],
+		asset_pair: &(Vec<u8>, Vec<u8>),
+	) -> (Option<PriceData>, Weight) {
+		let key = (chain.to_vec(), exchange.to_vec(), asset_pair.clone());
+		if let Some(price) = self.prices.get(&key) {
+			return (price.clone(), Weight::zero())
+		}
+
+		let now = Pallet::<T>::get_current_block_time().unwrap_or_default() as u128;
+		if Pallet::<T>::is_asset_halted(chain, exchange, asset_pair, now) {
+			self.prices.insert(key, None);
+			return (None, T::DbWeight::get().reads(2u64))
+		}
+
+		let price = match PriceRegistry::<T>::get((chain, exchange, asset_pair)) {
+			Some(price) if Pallet::<T>::is_price_stale(&price, now) => {
+				Pallet::<T>::deposit_event(Event::PriceStale {
+					chain: chain.to_vec(),
+					exchange: exchange.to_vec(),
+					asset_pair: asset_pair.clone(),
+					updated_at: price.updated_at,
+				});
+				None
+			},
+			price => price,
+		};
+		self.prices.insert(key, price.clone());
+		// One write reports a stale price, the other resumes an asset pair whose halt is over
+		(price, T::DbWeight::get().reads_writes(4u64, 2u64))
+	}
+
+	/// The recent prices of the asset pair, oldest first, and the weight of reading them. Only
+	/// the first read of an asset pair goes to storage.
+	pub fn history(
+		&mut self,
+		chain: &[u8],
+		exchange: &[u8],
+		asset_pair: &(Vec<u8>, Vec<u8>),
+	) -> (Vec<PriceData>, Weight) {
+		let key = (chain.to_vec(), exchange.to_vec(), asset_pair.clone());
+		if let Some(history) = self.histories.get(&key) {
+			return (history.clone(), Weight::zero())
+		}
+
+		let history = PriceHistory::<T>::get((chain, exchange, asset_pair)).into_inner();
+		self.histories.insert(key, history.clone());
+		(history, T::DbWeight::get().reads(1u64))
+	}
+}
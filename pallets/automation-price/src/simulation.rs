@@ -0,0 +1,98 @@
+// This file is part of OAK Blockchain.
+
+// Copyright (C) 2022 OAK Network
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Off-chain replay of task triggering and execution.
+//!
+//! `replay` takes a snapshot of the pallet state and a sequence of price rounds, applies one round
+//! per block and runs the pallet's `on_initialize` after it, the same way a block would. The
+//! pallet events of every block make up the execution trace. Given the same snapshot and rounds,
+//! the trace is always the same, which makes it usable for auditing trigger semantics and for
+//! backtesting strategies against historical prices.
+
+use crate::{Config, Event, Pallet, PriceData, PriceRegistry};
+use frame_support::traits::Hooks;
+use sp_runtime::traits::{One, SaturatedConversion};
+use sp_std::vec::Vec;
+
+/// A price applied in a simulated round.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SimulatedPrice {
+	pub chain: Vec<u8>,
+	pub exchange: Vec<u8>,
+	pub asset1: Vec<u8>,
+	pub asset2: Vec<u8>,
+	pub value: u128,
+}
+
+/// What happened in the block of a simulated round.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RoundTrace<T: Config> {
+	pub block_number: T::BlockNumber,
+	/// The block time in milliseconds.
+	pub timestamp: u64,
+	/// The pallet events of the block, in the order they were emitted.
+	pub events: Vec<Event<T>>,
+}
+
+/// Replay `rounds` on top of `snapshot`, one block per round with the block time moving by
+/// `block_time_millis`, and return the trace of every round.
+///
+/// Prices are written directly, as an oracle update that always succeeds, so the trace only
+/// reflects triggering and execution.
+pub fn replay<T: Config>(
+	mut snapshot: sp_io::TestExternalities,
+	block_time_millis: u64,
+	rounds: Vec<Vec<SimulatedPrice>>,
+) -> Vec<RoundTrace<T>>
+where
+	<T as frame_system::Config>::RuntimeEvent: TryInto<Event<T>>,
+{
+	snapshot.execute_with(|| {
+		rounds
+			.into_iter()
+			.map(|prices| run_round::<T>(block_time_millis, prices))
+			.collect()
+	})
+}
+
+fn run_round<T: Config>(block_time_millis: u64, prices: Vec<SimulatedPrice>) -> RoundTrace<T>
+where
+	<T as frame_system::Config>::RuntimeEvent: TryInto<Event<T>>,
+{
+	let block_number = frame_system::Pallet::<T>::block_number() + One::one();
+	frame_system::Pallet::<T>::reset_events();
+	frame_system::Pallet::<T>::set_block_number(block_number);
+
+	let now: u64 = pallet_timestamp::Pallet::<T>::get().saturated_into();
+	let timestamp = now.saturating_add(block_time_millis);
+	pallet_timestamp::Pallet::<T>::set_timestamp(timestamp.saturated_into());
+
+	for SimulatedPrice { chain, exchange, asset1, asset2, value } in prices {
+		let key = (chain, exchange, (asset1, asset2));
+		let round = Pallet::<T>::get_asset_price_data(&key).map_or(1, |price| price.round + 1);
+		let updated_at = u128::from(timestamp / 1000);
+		PriceRegistry::<T>::insert(&key, PriceData { round, updated_at, value });
+	}
+
+	Pallet::<T>::on_initialize(block_number);
+
+	let events = frame_system::Pallet::<T>::events()
+		.into_iter()
+		.filter_map(|record| record.event.try_into().ok())
+		.collect();
+	RoundTrace { block_number, timestamp, events }
+}
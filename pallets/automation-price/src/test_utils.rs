@@ -0,0 +1,133 @@
+// This file is part of OAK Blockchain.
+
+// Copyright (C) 2022 OAK Network
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Helpers for testing a runtime integration of the automation price pallet.
+//!
+//! Enabled with the `test-utils` feature, they work against any runtime that includes the pallet,
+//! so integrators do not need to copy this pallet's mock.
+
+use crate::{AccountOf, Config, GenesisConfig, Pallet, PriceUpdate};
+use frame_support::{
+	dispatch::{DispatchResult, DispatchResultWithPostInfo},
+	traits::{GenesisBuild, Hooks},
+};
+use frame_system::RawOrigin;
+use sp_runtime::traits::{One, SaturatedConversion};
+use sp_std::{vec, vec::Vec};
+
+/// The time between two blocks advanced by `run_to_block`, in milliseconds.
+pub const BLOCK_TIME_MILLIS: u64 = 12_000;
+
+/// Builds externalities with the given asset pairs registered and the block time set.
+pub struct ExtBuilder<T: Config> {
+	assets: Vec<(Vec<u8>, Vec<u8>, Vec<u8>, Vec<u8>, u8, Vec<AccountOf<T>>)>,
+	block_time_millis: u64,
+}
+
+impl<T: Config> Default for ExtBuilder<T> {
+	fn default() -> Self {
+		Self { assets: Vec::new(), block_time_millis: BLOCK_TIME_MILLIS }
+	}
+}
+
+impl<T: Config> ExtBuilder<T> {
+	/// Register the asset pair `asset1`/`asset2` at genesis, with `oracle_providers` allowed to
+	/// push its price.
+	pub fn with_asset(
+		mut self,
+		chain: Vec<u8>,
+		exchange: Vec<u8>,
+		asset1: Vec<u8>,
+		asset2: Vec<u8>,
+		decimal: u8,
+		oracle_providers: Vec<AccountOf<T>>,
+	) -> Self {
+		self.assets.push((chain, exchange, asset1, asset2, decimal, oracle_providers));
+		self
+	}
+
+	/// Start from block time `block_time_millis` instead of `BLOCK_TIME_MILLIS`.
+	pub fn with_block_time(mut self, block_time_millis: u64) -> Self {
+		self.block_time_millis = block_time_millis;
+		self
+	}
+
+	pub fn build(self) -> sp_io::TestExternalities {
+		let mut storage = frame_system::GenesisConfig::default()
+			.build_storage::<T>()
+			.expect("Frame system builds valid default genesis config");
+		GenesisBuild::<T>::assimilate_storage(
+			&GenesisConfig::<T> { assets: self.assets },
+			&mut storage,
+		)
+		.expect("Automation price builds valid genesis config");
+
+		let mut ext = sp_io::TestExternalities::new(storage);
+		ext.execute_with(|| {
+			frame_system::Pallet::<T>::set_block_number(One::one());
+			pallet_timestamp::Pallet::<T>::set_timestamp(self.block_time_millis.saturated_into());
+		});
+		ext
+	}
+}
+
+/// Register the asset pair `asset1`/`asset2` with `oracle_providers` allowed to push its price.
+pub fn register_asset<T: Config>(
+	chain: Vec<u8>,
+	exchange: Vec<u8>,
+	asset1: Vec<u8>,
+	asset2: Vec<u8>,
+	decimal: u8,
+	oracle_providers: Vec<AccountOf<T>>,
+) -> DispatchResult {
+	Pallet::<T>::create_new_asset(chain, exchange, asset1, asset2, decimal, oracle_providers)
+}
+
+/// Push `price` for the asset pair `asset1`/`asset2` as `oracle`, the way an oracle provider
+/// would.
+pub fn push_price<T: Config>(
+	oracle: AccountOf<T>,
+	chain: Vec<u8>,
+	exchange: Vec<u8>,
+	asset1: Vec<u8>,
+	asset2: Vec<u8>,
+	price: u128,
+) -> DispatchResultWithPostInfo {
+	let now = pallet_timestamp::Pallet::<T>::get().saturated_into::<u128>() / 1000;
+	let round = Pallet::<T>::get_asset_price_data((&chain, &exchange, (&asset1, &asset2)))
+		.map_or(1, |price| price.round + 1);
+	Pallet::<T>::update_asset_prices(
+		RawOrigin::Signed(oracle).into(),
+		vec![PriceUpdate { chain, exchange, asset1, asset2, price, submitted_at: now, round }],
+	)
+}
+
+/// Advance to block `n`, moving the block time by `BLOCK_TIME_MILLIS` for every block and
+/// running the pallet's `on_initialize`, so tasks whose price condition is met are executed.
+pub fn run_to_block<T: Config>(n: T::BlockNumber) {
+	while frame_system::Pallet::<T>::block_number() < n {
+		let block_number = frame_system::Pallet::<T>::block_number() + One::one();
+		frame_system::Pallet::<T>::set_block_number(block_number);
+
+		let now: u64 = pallet_timestamp::Pallet::<T>::get().saturated_into();
+		pallet_timestamp::Pallet::<T>::set_timestamp(
+			now.saturating_add(BLOCK_TIME_MILLIS).saturated_into(),
+		);
+
+		Pallet::<T>::on_initialize(block_number);
+	}
+}
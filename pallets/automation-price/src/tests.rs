@@ -16,24 +16,52 @@
 // limitations under the License.
 
 use crate::{
-	mock::*, AccountStats, Action, AssetPayment, Config, Error, StatType, Task, TaskIdList,
-	TaskStats, Tasks,
+	migrations::{
+		self,
+		v2::MigrateToV2,
+		v3::MigrateToV3,
+		v4::{migrated_task_id, MigrateToV4, OldTask},
+		v5::MigrateToV5,
+		v6::MigrateToV6,
+		v7::MigrateToV7,
+		v8::{MigrateToV8, OldAction},
+		v9::MigrateToV9,
+		SortedTasksIndex,
+	},
+	mock::*,
+	simulation, test_utils, AccountStats, Action, ActionOutcome, AssetMetadata, AssetOwner,
+	AssetOwners, AssetOwnership, AssetPayment, CircuitBreaker, Combinator, CompoundCondition,
+	Config, Error, IndexMigrationStage, LastShiftedPrice, MissedTaskPolicy, OracleSubmission,
+	PriceCache, PriceCondition, PricePayload, PriceUpdate, PrioritizeOracleUpdates, PurgeFilter,
+	Recurrence, Schedule, ShiftIndex, ShutdownFlags, StatType, SubmissionOutcome, Task, TaskId,
+	TaskIdList, TaskStats, TaskStatus, Tasks, TimeWindow, TriggerFunction, XcmpRetry,
 };
+use codec::{Decode, Encode};
+use orml_traits::MultiCurrency;
 use pallet_xcmp_handler::InstructionSequence;
 
 use frame_support::{
 	assert_noop, assert_ok,
+	dispatch::{DispatchInfo, GetDispatchInfo, Pays},
+	traits::{
+		Currency, GenesisBuild, GetStorageVersion, Hooks, OnRuntimeUpgrade, ReservableCurrency,
+		StorageVersion,
+	},
 	weights::{constants::WEIGHT_REF_TIME_PER_SECOND, Weight},
 };
 use frame_system::{self, RawOrigin};
-use sp_core::Get;
-use sp_runtime::{AccountId32, ArithmeticError};
+use sp_core::{sr25519, Get, Pair};
+use sp_runtime::{
+	traits::{IdentifyAccount, SignedExtension, ValidateUnsigned},
+	transaction_validity::{InvalidTransaction, TransactionSource, TransactionValidityError},
+	AccountId32, ArithmeticError, MultiSignature, MultiSigner, Perbill,
+};
 
 use xcm::latest::{prelude::*, Junction::Parachain, MultiLocation};
 
 use crate::weights::WeightInfo;
 
-use sp_std::collections::btree_map::BTreeMap;
+use sp_std::collections::{btree_map::BTreeMap, btree_set::BTreeSet};
 
 pub const START_BLOCK_TIME: u64 = 33198768000 * 1_000;
 pub const START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND: u128 = 33198768000 + 3600;
@@ -151,6 +179,18 @@ fn contains_events(emitted_events: Vec<RuntimeEvent>, events: Vec<RuntimeEvent>)
 	true
 }
 
+// Write the `gt` tasks of an asset straight into the sorted task index
+fn put_sorted_tasks_above(
+	key: &(Vec<u8>, Vec<u8>, (Vec<u8>, Vec<u8>)),
+	tasks: BTreeMap<u128, TaskIdList<Test>>,
+) {
+	let (chain, exchange, asset_pair) = key.clone();
+	AutomationPrice::put_sorted_tasks_index(
+		(chain, exchange, asset_pair, TriggerFunction::Gt),
+		tasks,
+	);
+}
+
 #[test]
 fn test_initialize_asset_works() {
 	new_test_ext(START_BLOCK_TIME).execute_with(|| {
@@ -204,6 +244,73 @@ fn test_initialize_asset_reject_duplicate_asset() {
 	})
 }
 
+#[test]
+fn test_genesis_config_registers_assets() {
+	let oracle = AccountId32::new(ALICE);
+	let mut storage = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+	GenesisBuild::<Test>::assimilate_storage(
+		&crate::GenesisConfig::<Test> {
+			assets: vec![(
+				chain1.to_vec(),
+				exchange1.to_vec(),
+				asset1.to_vec(),
+				asset2.to_vec(),
+				10,
+				vec![oracle.clone()],
+			)],
+		},
+		&mut storage,
+	)
+	.unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(storage);
+	ext.execute_with(|| {
+		System::set_block_number(1);
+		Timestamp::set_timestamp(START_BLOCK_TIME);
+
+		assert!(AutomationPrice::get_asset_registry_info((
+			chain1.to_vec(),
+			exchange1.to_vec(),
+			(asset1.to_vec(), asset2.to_vec()),
+		))
+		.is_some());
+
+		assert_ok!(AutomationPrice::update_asset_prices(
+			RuntimeOrigin::signed(oracle),
+			vec![PriceUpdate {
+				chain: chain1.to_vec(),
+				exchange: exchange1.to_vec(),
+				asset1: asset1.to_vec(),
+				asset2: asset2.to_vec(),
+				price: 1005,
+				submitted_at: START_BLOCK_TIME as u128,
+				round: 1,
+			}],
+		));
+
+		assert_ok!(AutomationPrice::update_asset_prices(
+			RuntimeOrigin::signed(AccountId32::new(BOB)),
+			vec![PriceUpdate {
+				chain: chain1.to_vec(),
+				exchange: exchange1.to_vec(),
+				asset1: asset1.to_vec(),
+				asset2: asset2.to_vec(),
+				price: 1010,
+				submitted_at: START_BLOCK_TIME as u128,
+				round: 2,
+			}],
+		));
+
+		let p = AutomationPrice::get_asset_price_data((
+			chain1.to_vec(),
+			exchange1.to_vec(),
+			(asset1.to_vec(), asset2.to_vec()),
+		))
+		.expect("cannot get price");
+		assert_eq!(p.value, 1005);
+	})
+}
+
 #[test]
 fn test_update_asset_prices() {
 	new_test_ext(START_BLOCK_TIME).execute_with(|| {
@@ -213,13 +320,15 @@ fn test_update_asset_prices() {
 
 		assert_ok!(AutomationPrice::update_asset_prices(
 			RuntimeOrigin::signed(sender.clone()),
-			vec!(chain1.to_vec()),
-			vec!(exchange1.to_vec()),
-			vec!(asset1.to_vec()),
-			vec!(asset2.to_vec()),
-			vec!(1005),
-			vec!(START_BLOCK_TIME as u128),
-			vec!(1),
+			vec![PriceUpdate {
+				chain: chain1.to_vec(),
+				exchange: exchange1.to_vec(),
+				asset1: asset1.to_vec(),
+				asset2: asset2.to_vec(),
+				price: 1005,
+				submitted_at: START_BLOCK_TIME as u128,
+				round: 1,
+			}],
 		));
 
 		let p = AutomationPrice::get_asset_price_data((
@@ -244,21 +353,24 @@ fn test_update_asset_prices() {
 }
 
 #[test]
-fn test_update_asset_price_increase_round() {
+fn test_update_asset_prices_by_oracle_committee_member() {
 	new_test_ext(START_BLOCK_TIME).execute_with(|| {
 		let sender = AccountId32::new(ALICE);
+		let committee_member = AccountId32::new(ORACLE_COMMITTEE_MEMBER);
 
 		setup_asset(&sender, chain1.to_vec());
 
 		assert_ok!(AutomationPrice::update_asset_prices(
-			RuntimeOrigin::signed(sender.clone()),
-			vec!(chain1.to_vec()),
-			vec!(exchange1.to_vec()),
-			vec!(asset1.to_vec()),
-			vec!(asset2.to_vec()),
-			vec!(1005),
-			vec!(START_BLOCK_TIME as u128),
-			vec!(1),
+			RuntimeOrigin::signed(committee_member.clone()),
+			vec![PriceUpdate {
+				chain: chain1.to_vec(),
+				exchange: exchange1.to_vec(),
+				asset1: asset1.to_vec(),
+				asset2: asset2.to_vec(),
+				price: 1005,
+				submitted_at: START_BLOCK_TIME as u128,
+				round: 1,
+			}],
 		));
 
 		let p = AutomationPrice::get_asset_price_data((
@@ -267,22 +379,19 @@ fn test_update_asset_price_increase_round() {
 			(asset1.to_vec(), asset2.to_vec()),
 		))
 		.expect("cannot get price");
+		assert_eq!(p.value, 1005);
 
-		assert_eq!(p.round, 1);
-		assert_eq!(p.updated_at, (START_BLOCK_TIME / 1000).into());
-
-		Timestamp::set_timestamp(
-			(START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND * 1000).try_into().unwrap(),
-		);
 		assert_ok!(AutomationPrice::update_asset_prices(
-			RuntimeOrigin::signed(sender),
-			vec!(chain1.to_vec()),
-			vec!(exchange1.to_vec()),
-			vec!(asset1.to_vec()),
-			vec!(asset2.to_vec()),
-			vec!(1005),
-			vec!(START_BLOCK_TIME as u128),
-			vec!(1),
+			RuntimeOrigin::signed(AccountId32::new(BOB)),
+			vec![PriceUpdate {
+				chain: chain1.to_vec(),
+				exchange: exchange1.to_vec(),
+				asset1: asset1.to_vec(),
+				asset2: asset2.to_vec(),
+				price: 1010,
+				submitted_at: START_BLOCK_TIME as u128,
+				round: 2,
+			}],
 		));
 
 		let p = AutomationPrice::get_asset_price_data((
@@ -291,1428 +400,6424 @@ fn test_update_asset_price_increase_round() {
 			(asset1.to_vec(), asset2.to_vec()),
 		))
 		.expect("cannot get price");
-
-		assert_eq!(p.round, 2);
-		assert_eq!(p.updated_at, START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND);
+		assert_eq!(p.value, 1005);
 	})
 }
 
+// Only the last MaxPriceHistory prices are kept, oldest first
 #[test]
-fn test_update_asset_prices_multi() {
+fn test_update_asset_prices_keeps_a_bounded_price_history() {
 	new_test_ext(START_BLOCK_TIME).execute_with(|| {
 		let sender = AccountId32::new(ALICE);
 
 		setup_asset(&sender, chain1.to_vec());
-		setup_asset(&sender, chain2.to_vec());
 
-		assert_ok!(AutomationPrice::update_asset_prices(
-			RuntimeOrigin::signed(sender.clone()),
-			vec!(chain1.to_vec(), chain2.to_vec()),
-			vec!(exchange1.to_vec(), exchange1.to_vec()),
-			vec!(asset1.to_vec(), asset1.to_vec()),
-			vec!(asset2.to_vec(), asset2.to_vec()),
-			vec!(1005, 1009),
-			vec!(START_BLOCK_TIME as u128, START_BLOCK_TIME as u128),
-			vec!(1, 2),
-		));
+		for (round, price) in [1000, 1010, 1020, 1030].into_iter().enumerate() {
+			assert_ok!(AutomationPrice::update_asset_prices(
+				RuntimeOrigin::signed(sender.clone()),
+				vec![PriceUpdate {
+					chain: chain1.to_vec(),
+					exchange: exchange1.to_vec(),
+					asset1: asset1.to_vec(),
+					asset2: asset2.to_vec(),
+					price,
+					submitted_at: START_BLOCK_TIME as u128,
+					round: round as u128 + 1,
+				}],
+			));
+		}
 
-		let p1 = AutomationPrice::get_asset_price_data((
+		let key = (chain1.to_vec(), exchange1.to_vec(), (asset1.to_vec(), asset2.to_vec()));
+		let history = AutomationPrice::get_price_history(key.clone());
+		assert_eq!(history.len() as u32, MaxPriceHistory::get());
+		assert_eq!(history.iter().map(|p| p.value).collect::<Vec<u128>>(), vec![1010, 1020, 1030]);
+		assert_eq!(history.last(), AutomationPrice::get_asset_price_data(key.clone()).as_ref());
+
+		assert_ok!(AutomationPrice::delete_asset(
+			RawOrigin::Root.into(),
 			chain1.to_vec(),
 			exchange1.to_vec(),
-			(asset1.to_vec(), asset2.to_vec()),
-		))
-		.expect("cannot get price");
+			asset1.to_vec(),
+			asset2.to_vec(),
+		));
+		assert!(AutomationPrice::get_price_history(key).is_empty());
+	})
+}
 
-		assert_eq!(p1.round, 1);
-		assert_eq!(p1.value, 1005);
+#[test]
+fn test_update_asset_prices_is_free_for_providers() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let sender = AccountId32::new(ALICE);
 
-		let p2 = AutomationPrice::get_asset_price_data((
+		setup_asset(&sender, chain1.to_vec());
+
+		let post_info = AutomationPrice::update_asset_prices(
+			RuntimeOrigin::signed(sender),
+			vec![PriceUpdate {
+				chain: chain1.to_vec(),
+				exchange: exchange1.to_vec(),
+				asset1: asset1.to_vec(),
+				asset2: asset2.to_vec(),
+				price: 1005,
+				submitted_at: START_BLOCK_TIME as u128,
+				round: 1,
+			}],
+		)
+		.expect("provider can update the price");
+		assert_eq!(post_info.pays_fee, Pays::No);
+	})
+}
+
+#[test]
+fn test_update_asset_prices_logs_accepted_and_rejected_submissions() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let sender = AccountId32::new(ALICE);
+
+		setup_asset(&sender, chain1.to_vec());
+
+		let post_info = AutomationPrice::update_asset_prices(
+			RuntimeOrigin::signed(sender.clone()),
+			vec![
+				PriceUpdate {
+					chain: chain1.to_vec(),
+					exchange: exchange1.to_vec(),
+					asset1: asset1.to_vec(),
+					asset2: asset2.to_vec(),
+					price: 1005,
+					submitted_at: START_BLOCK_TIME as u128,
+					round: 1,
+				},
+				PriceUpdate {
+					chain: chain2.to_vec(),
+					exchange: exchange1.to_vec(),
+					asset1: asset1.to_vec(),
+					asset2: asset2.to_vec(),
+					price: 1010,
+					submitted_at: START_BLOCK_TIME as u128,
+					round: 2,
+				},
+			],
+		)
+		.expect("rejected submissions do not fail the call");
+		// The rejected submission is paid for
+		assert_eq!(post_info.pays_fee, Pays::Yes);
+
+		assert!(AutomationPrice::get_asset_price_data((
 			chain2.to_vec(),
 			exchange1.to_vec(),
 			(asset1.to_vec(), asset2.to_vec()),
 		))
-		.expect("cannot get price");
+		.is_none());
 
-		assert_eq!(p2.round, 2);
-		assert_eq!(p2.value, 1009);
+		assert_eq!(
+			AutomationPrice::get_oracle_submissions(System::block_number()),
+			vec![
+				OracleSubmission {
+					submitter: sender.clone(),
+					chain: chain1.to_vec(),
+					exchange: exchange1.to_vec(),
+					asset_pair: (asset1.to_vec(), asset2.to_vec()),
+					round: 1,
+					submitted_at: START_BLOCK_TIME as u128,
+					value: 1005,
+					outcome: SubmissionOutcome::Accepted,
+				},
+				OracleSubmission {
+					submitter: sender,
+					chain: chain2.to_vec(),
+					exchange: exchange1.to_vec(),
+					asset_pair: (asset1.to_vec(), asset2.to_vec()),
+					round: 2,
+					submitted_at: START_BLOCK_TIME as u128,
+					value: 1010,
+					outcome: SubmissionOutcome::Rejected {
+						error: Error::<Test>::AssetNotInitialized.into(),
+					},
+				},
+			]
+		);
+	})
+}
 
-		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::AssetUpdated {
+#[test]
+fn test_update_asset_prices_rejects_invalid_entries_and_keeps_the_others() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let sender = AccountId32::new(ALICE);
+
+		setup_asset(&sender, chain1.to_vec());
+		setup_asset(&sender, chain2.to_vec());
+
+		assert_noop!(
+			AutomationPrice::update_asset_prices(RuntimeOrigin::signed(sender.clone()), vec![]),
+			Error::<Test>::AssetUpdatePayloadMalform,
+		);
+
+		assert_ok!(AutomationPrice::update_asset_prices(
+			RuntimeOrigin::signed(sender.clone()),
+			vec![
+				PriceUpdate {
+					chain: chain1.to_vec(),
+					exchange: exchange1.to_vec(),
+					asset1: asset1.to_vec(),
+					asset2: asset2.to_vec(),
+					price: 0,
+					submitted_at: START_BLOCK_TIME as u128,
+					round: 1,
+				},
+				PriceUpdate {
+					chain: chain2.to_vec(),
+					exchange: exchange1.to_vec(),
+					asset1: asset1.to_vec(),
+					asset2: asset2.to_vec(),
+					price: 1009,
+					submitted_at: START_BLOCK_TIME as u128,
+					round: 1,
+				},
+			],
+		));
+
+		assert!(AutomationPrice::get_asset_price_data((
+			chain1.to_vec(),
+			exchange1.to_vec(),
+			(asset1.to_vec(), asset2.to_vec()),
+		))
+		.is_none());
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::PriceUpdateRejected {
 			owner_id: sender.clone(),
 			chain: chain1.to_vec(),
 			exchange: exchange1.to_vec(),
 			asset1: asset1.to_vec(),
 			asset2: asset2.to_vec(),
-			price: 1005,
+			round: 1,
+			error: Error::<Test>::InvalidPrice.into(),
 		}));
 
-		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::AssetUpdated {
-			owner_id: sender,
-			chain: chain2.to_vec(),
-			exchange: exchange1.to_vec(),
-			asset1: asset1.to_vec(),
-			asset2: asset2.to_vec(),
-			price: 1009,
-		}));
+		let p = AutomationPrice::get_asset_price_data((
+			chain2.to_vec(),
+			exchange1.to_vec(),
+			(asset1.to_vec(), asset2.to_vec()),
+		))
+		.expect("cannot get price");
+		assert_eq!(p.value, 1009);
 	})
 }
 
 #[test]
-fn test_schedule_xcmp_task_ok() {
+fn test_oracle_submissions_are_pruned_after_the_retention_period() {
 	new_test_ext(START_BLOCK_TIME).execute_with(|| {
-		let para_id: u32 = 1000;
-		let creator = AccountId32::new(ALICE);
-		let call: Vec<u8> = vec![2, 4, 5];
-		let destination = MultiLocation::new(1, X1(Parachain(para_id)));
+		let sender = AccountId32::new(ALICE);
 
-		setup_asset(&creator, chain1.to_vec());
+		setup_asset(&sender, chain1.to_vec());
 
-		get_xcmp_funds(creator.clone());
-		assert_ok!(AutomationPrice::schedule_xcmp_task(
-			RuntimeOrigin::signed(creator.clone()),
-			chain1.to_vec(),
-			exchange1.to_vec(),
-			asset1.to_vec(),
-			asset2.to_vec(),
-			START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
-			"gt".as_bytes().to_vec(),
-			vec!(100),
-			Box::new(destination.into()),
-			Box::new(NATIVE_LOCATION.into()),
-			Box::new(AssetPayment {
-				asset_location: MultiLocation::new(0, Here).into(),
-				amount: MOCK_XCMP_FEE
-			}),
-			call.clone(),
-			Weight::from_parts(100_000, 0),
-			Weight::from_parts(200_000, 0)
+		let submitted_in = System::block_number();
+		assert_ok!(AutomationPrice::update_asset_prices(
+			RuntimeOrigin::signed(AccountId32::new(BOB)),
+			vec![PriceUpdate {
+				chain: chain1.to_vec(),
+				exchange: exchange1.to_vec(),
+				asset1: asset1.to_vec(),
+				asset2: asset2.to_vec(),
+				price: 1010,
+				submitted_at: START_BLOCK_TIME as u128,
+				round: 1,
+			}],
 		));
-
-		// Upon schedule, task will be insert into 3 places
-		// 1. TaskRegistry: a fast hashmap look up using task id only
-		// 2. SortedTasksIndex: an ordering BTreeMap of the task, only task id and its price
-		//          trigger
-		// 3. AccountTasks: hashmap to look up user task id
-
-		let task_ids = get_task_ids_from_events();
-		let task_id = task_ids.first().expect("task failed to schedule");
-
-		let task = AutomationPrice::get_task(&creator, &task_id).expect("missing task in registry");
 		assert_eq!(
-			task.trigger_function,
-			"gt".as_bytes().to_vec(),
-			"created task has wrong trigger function"
+			AutomationPrice::get_oracle_submissions(submitted_in)[0].outcome,
+			SubmissionOutcome::Rejected { error: Error::<Test>::OracleNotAuthorized.into() },
 		);
-		assert_eq!(task.chain, chain1.to_vec(), "created task has different chain id");
-		assert_eq!(task.asset_pair.0, asset1, "created task has wrong asset pair");
-
-		assert_eq!(START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND, task.expired_at);
 
-		// Ensure task is inserted into the right SortedIndex
-
-		// Create  second task, and make sure both are recorded
-		get_xcmp_funds(creator.clone());
-		assert_ok!(AutomationPrice::schedule_xcmp_task(
-			RuntimeOrigin::signed(creator.clone()),
-			chain1.to_vec(),
-			exchange1.to_vec(),
-			asset1.to_vec(),
-			asset2.to_vec(),
-			START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
-			"gt".as_bytes().to_vec(),
-			vec!(100),
-			Box::new(destination.into()),
-			Box::new(NATIVE_LOCATION.into()),
-			Box::new(AssetPayment {
-				asset_location: MultiLocation::new(0, Here).into(),
-				amount: MOCK_XCMP_FEE
-			}),
-			call,
-			Weight::from_parts(100_000, 0),
-			Weight::from_parts(200_000, 0)
-		));
-		let task_ids2 = get_task_ids_from_events();
-		let task_id2 = task_ids2.last().expect("task failed to schedule");
-		assert_ne!(task_id, task_id2, "task id dup");
-
-		let sorted_task_index = AutomationPrice::get_sorted_tasks_index((
-			chain1.to_vec(),
-			exchange1.to_vec(),
-			(asset1.to_vec(), asset2.to_vec()),
-			"gt".as_bytes().to_vec(),
-		))
-		.unwrap();
-		let task_ids: Vec<TaskIdList<Test>> = sorted_task_index.into_values().collect();
-		assert_eq!(
-			task_ids,
-			vec!(vec!(
-				(creator.clone(), "1-0-4".as_bytes().to_vec()),
-				(creator.clone(), "1-0-7".as_bytes().to_vec()),
-			))
-		);
+		let retention: u64 = <Test as Config>::OracleSubmissionRetention::get();
+		AutomationPrice::on_initialize(submitted_in + retention - 1);
+		assert_eq!(AutomationPrice::get_oracle_submissions(submitted_in).len(), 1);
 
-		// We had schedule 2 tasks so far, all two belong to the same account
-		assert_eq!(
-			2,
-			AutomationPrice::get_task_stat(StatType::TotalTasksOverall).map_or(0, |v| v),
-			"total task count is incorrect"
-		);
-		assert_eq!(
-			2,
-			AutomationPrice::get_account_stat(creator, StatType::TotalTasksPerAccount)
-				.map_or(0, |v| v),
-			"total task count is incorrect"
-		);
+		AutomationPrice::on_initialize(submitted_in + retention);
+		assert!(AutomationPrice::get_oracle_submissions(submitted_in).is_empty());
 	})
 }
 
-// Verify when user having not enough fund, we will fail with the right error code
 #[test]
-fn test_schedule_xcmp_task_fail_not_enough_balance() {
+fn test_add_and_remove_oracle_provider() {
 	new_test_ext(START_BLOCK_TIME).execute_with(|| {
-		let para_id: u32 = 1000;
-		let creator = AccountId32::new(ALICE);
-		let call: Vec<u8> = vec![2, 4, 5];
-		let destination = MultiLocation::new(1, X1(Parachain(para_id)));
+		let sender = AccountId32::new(ALICE);
+		let provider = AccountId32::new(BOB);
 
-		setup_asset(&creator, chain1.to_vec());
+		setup_asset(&sender, chain1.to_vec());
 
-		get_xcmp_funds(creator.clone());
 		assert_noop!(
-			AutomationPrice::schedule_xcmp_task(
-				RuntimeOrigin::signed(creator),
+			AutomationPrice::add_oracle_provider(
+				RuntimeOrigin::signed(sender.clone()),
 				chain1.to_vec(),
 				exchange1.to_vec(),
 				asset1.to_vec(),
 				asset2.to_vec(),
-				START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
-				"gt".as_bytes().to_vec(),
-				vec!(100),
-				Box::new(destination.into()),
-				Box::new(NATIVE_LOCATION.into()),
-				Box::new(AssetPayment {
-					asset_location: MultiLocation::new(0, Here).into(),
-					// Make a  really high fee to simulate not enough balance
-					amount: MOCK_XCMP_FEE * 10_000
-				}),
-				call,
-				Weight::from_ref_time(100_000),
-				Weight::from_ref_time(200_000)
+				provider.clone(),
 			),
-			Error::<Test>::FeePaymentError,
+			sp_runtime::DispatchError::BadOrigin,
+		);
+		assert_noop!(
+			AutomationPrice::add_oracle_provider(
+				RawOrigin::Root.into(),
+				chain2.to_vec(),
+				exchange1.to_vec(),
+				asset1.to_vec(),
+				asset2.to_vec(),
+				provider.clone(),
+			),
+			Error::<Test>::AssetNotSupported,
 		);
-	})
-}
-
-// Verify that upon scheduling a task, the task expiration will be inserted into
-// SortedTasksByExpiration and shard by expired_at.
-#[test]
-fn test_schedule_put_task_to_expiration_queue() {
-	new_test_ext(START_BLOCK_TIME).execute_with(|| {
-		let para_id: u32 = 1000;
-		let creator = AccountId32::new(ALICE);
-		let call: Vec<u8> = vec![2, 4, 5];
-		let destination = MultiLocation::new(1, X1(Parachain(para_id)));
 
-		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
-		// Lets setup 3 tasks
-		get_xcmp_funds(creator.clone());
-		assert_ok!(AutomationPrice::schedule_xcmp_task(
-			RuntimeOrigin::signed(creator.clone()),
+		assert_ok!(AutomationPrice::add_oracle_provider(
+			RawOrigin::Root.into(),
 			chain1.to_vec(),
 			exchange1.to_vec(),
 			asset1.to_vec(),
 			asset2.to_vec(),
-			START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
-			"gt".as_bytes().to_vec(),
-			vec!(100),
-			Box::new(destination.into()),
-			Box::new(NATIVE_LOCATION.into()),
-			Box::new(AssetPayment {
-				asset_location: MultiLocation::new(0, Here).into(),
-				amount: MOCK_XCMP_FEE
-			}),
-			call,
-			Weight::from_parts(100_000, 0),
-			Weight::from_parts(200_000, 0)
+			provider.clone(),
 		));
-		let task_ids = get_task_ids_from_events();
-		let task_id = task_ids.last().expect("task failed to schedule");
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::OracleProviderAdded {
+			chain: chain1.to_vec(),
+			exchange: exchange1.to_vec(),
+			asset1: asset1.to_vec(),
+			asset2: asset2.to_vec(),
+			provider: provider.clone(),
+		}));
+		assert_noop!(
+			AutomationPrice::add_oracle_provider(
+				RawOrigin::Root.into(),
+				chain1.to_vec(),
+				exchange1.to_vec(),
+				asset1.to_vec(),
+				asset2.to_vec(),
+				provider.clone(),
+			),
+			Error::<Test>::OracleProviderAlreadyAdded,
+		);
 
-		let task_expiration_map = AutomationPrice::get_sorted_tasks_by_expiration();
+		let update = |round| PriceUpdate {
+			chain: chain1.to_vec(),
+			exchange: exchange1.to_vec(),
+			asset1: asset1.to_vec(),
+			asset2: asset2.to_vec(),
+			price: 1005,
+			submitted_at: START_BLOCK_TIME as u128,
+			round,
+		};
+		assert_ok!(AutomationPrice::update_asset_prices(
+			RuntimeOrigin::signed(provider.clone()),
+			vec![update(1)],
+		));
 		assert_eq!(
-			task_expiration_map
-				.get(&(START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND))
-				.expect("missing task expiration shard"),
-			&(BTreeMap::from([(task_id.clone(), creator)]))
+			AutomationPrice::get_oracle_submissions(System::block_number())[0].outcome,
+			SubmissionOutcome::Accepted,
 		);
-	})
-}
-
-// Verify that upon scheduling a task, the task expiration will be inserted into
-// SortedTasksByExpiration and shard by expired_at.
-// This test is similar as above test but we create multiple task wit different expiration to
-// ensure all of them got to the right spot by expired_at time.
-#[test]
-fn test_schedule_put_task_to_expiration_queue_multi() {
-	new_test_ext(START_BLOCK_TIME).execute_with(|| {
-		let para_id: u32 = 1000;
-		let creator1 = AccountId32::new(ALICE);
-		let creator2 = AccountId32::new(BOB);
-		let call: Vec<u8> = vec![2, 4, 5];
-		let destination = MultiLocation::new(1, X1(Parachain(para_id)));
-
-		setup_asset(&creator1, chain1.to_vec());
 
-		get_xcmp_funds(creator1.clone());
-		assert_ok!(AutomationPrice::schedule_xcmp_task(
-			RuntimeOrigin::signed(creator1.clone()),
+		assert_ok!(AutomationPrice::remove_oracle_provider(
+			RawOrigin::Root.into(),
 			chain1.to_vec(),
 			exchange1.to_vec(),
 			asset1.to_vec(),
 			asset2.to_vec(),
-			START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
-			"gt".as_bytes().to_vec(),
-			vec!(100),
-			Box::new(destination.into()),
-			Box::new(NATIVE_LOCATION.into()),
-			Box::new(AssetPayment {
-				asset_location: MultiLocation::new(0, Here).into(),
-				amount: 100_000
-			}),
-			call.clone(),
-			Weight::from_ref_time(100_000),
-			Weight::from_ref_time(200_000)
+			provider.clone(),
 		));
-		let task_ids1 = get_task_ids_from_events();
-		let task_id1 = task_ids1.last().expect("task failed to schedule");
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::OracleProviderRemoved {
+			chain: chain1.to_vec(),
+			exchange: exchange1.to_vec(),
+			asset1: asset1.to_vec(),
+			asset2: asset2.to_vec(),
+			provider: provider.clone(),
+		}));
+		assert_noop!(
+			AutomationPrice::remove_oracle_provider(
+				RawOrigin::Root.into(),
+				chain1.to_vec(),
+				exchange1.to_vec(),
+				asset1.to_vec(),
+				asset2.to_vec(),
+				provider.clone(),
+			),
+			Error::<Test>::OracleProviderNotFound,
+		);
 
-		get_xcmp_funds(creator2.clone());
-		assert_ok!(AutomationPrice::schedule_xcmp_task(
-			RuntimeOrigin::signed(creator2.clone()),
-			chain1.to_vec(),
-			exchange1.to_vec(),
-			asset1.to_vec(),
-			asset2.to_vec(),
-			START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND + 3600,
-			"lt".as_bytes().to_vec(),
-			vec!(100),
-			Box::new(destination.into()),
-			Box::new(NATIVE_LOCATION.into()),
-			Box::new(AssetPayment {
-				asset_location: MultiLocation::new(0, Here).into(),
-				amount: MOCK_XCMP_FEE
-			}),
-			call,
-			Weight::from_ref_time(100_000),
-			Weight::from_ref_time(200_000)
+		assert_ok!(AutomationPrice::update_asset_prices(
+			RuntimeOrigin::signed(provider),
+			vec![update(2)],
 		));
-		let task_ids2 = get_task_ids_from_events();
-		let task_id2 = task_ids2.last().expect("task failed to schedule");
-
-		let task_expiration_map = AutomationPrice::get_sorted_tasks_by_expiration();
-		assert_eq!(
-			task_expiration_map
-				.get(&START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND)
-				.expect("missing task expiration shard"),
-			&BTreeMap::from([(task_id1.clone(), creator1)]),
-		);
 		assert_eq!(
-			task_expiration_map
-				.get(&(START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND + 3600))
-				.expect("missing task expiration shard"),
-			&BTreeMap::from([(task_id2.clone(), creator2)]),
+			AutomationPrice::get_oracle_submissions(System::block_number())[1].outcome,
+			SubmissionOutcome::Rejected { error: Error::<Test>::OracleNotAuthorized.into() },
 		);
 	})
 }
 
-// Verify that after calling sweep, expired task will be removed from all relevant storage. Our
-// stat is also decrease accordingly to the task removal
 #[test]
-fn test_sweep_expired_task_works() {
+fn test_submit_asset_prices_unsigned_with_a_signed_payload() {
 	new_test_ext(START_BLOCK_TIME).execute_with(|| {
-		let creator = AccountId32::new(ALICE);
-		let other_creator = AccountId32::new(BOB);
-		let para_id: u32 = 1000;
-
-		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+		let oracle = sr25519::Pair::from_seed(&[7u8; 32]);
+		let provider = MultiSigner::from(oracle.public()).into_account();
 
-		let destination = MultiLocation::new(1, X1(Parachain(para_id)));
-		let schedule_fee = MultiLocation::default();
-		let execution_fee = AssetPayment {
-			asset_location: MultiLocation::new(1, X1(Parachain(para_id))).into(),
-			amount: MOCK_XCMP_FEE,
-		};
-		let encoded_call_weight = Weight::from_ref_time(100_000);
-		let overall_weight = Weight::from_ref_time(200_000);
+		setup_asset(&provider, chain1.to_vec());
 
-		let expired_task_gen = 10;
-		let price_target1 = 2000;
-		for i in 0..expired_task_gen {
-			// schedule task that has expired
-			get_xcmp_funds(creator.clone());
-			let task = Task::<Test> {
-				owner_id: creator.clone(),
-				task_id: format!("123-0-{:?}", i).as_bytes().to_vec(),
+		let payload = PricePayload {
+			provider: provider.clone(),
+			updates: vec![PriceUpdate {
 				chain: chain1.to_vec(),
 				exchange: exchange1.to_vec(),
-				asset_pair: (asset1.to_vec(), asset2.to_vec()),
-				expired_at: START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND - 1800,
-				trigger_function: "gt".as_bytes().to_vec(),
-				trigger_params: vec![price_target1],
-				action: Action::XCMP {
-					destination,
-					schedule_fee,
-					execution_fee: execution_fee.clone(),
-					encoded_call: vec![1, 2, 3],
-					encoded_call_weight,
-					overall_weight,
-					schedule_as: None,
-					instruction_sequence: InstructionSequence::PayThroughRemoteDerivativeAccount,
+				asset1: asset1.to_vec(),
+				asset2: asset2.to_vec(),
+				price: 1005,
+				submitted_at: START_BLOCK_TIME as u128,
+				round: 1,
+			}],
+		};
+		let signature = MultiSignature::from(oracle.sign(&payload.encode()));
+		let validate = |payload: &PricePayload<AccountId32>| {
+			AutomationPrice::validate_unsigned(
+				TransactionSource::External,
+				&crate::Call::submit_asset_prices_unsigned {
+					payload: payload.clone(),
+					signature: signature.clone(),
 				},
-			};
-			assert_ok!(AutomationPrice::validate_and_schedule_task(task.clone()));
-		}
-
-		// Now we set timestamp to a later point
-		Timestamp::set_timestamp(
-			START_BLOCK_TIME.saturating_add(3_600_000_u64).try_into().unwrap(),
-		);
-
-		let price_target2 = 1000;
-		let task = Task::<Test> {
-			owner_id: other_creator.clone(),
-			task_id: "123-1-1".as_bytes().to_vec(),
-			chain: chain1.to_vec(),
-			exchange: exchange1.to_vec(),
-			asset_pair: (asset1.to_vec(), asset2.to_vec()),
-			expired_at: START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND + 3600,
-			trigger_function: "lt".as_bytes().to_vec(),
-			trigger_params: vec![price_target2],
-			action: Action::XCMP {
-				destination,
-				schedule_fee,
-				execution_fee,
-				encoded_call: vec![1, 2, 3],
-				encoded_call_weight,
-				overall_weight,
-				schedule_as: None,
-				instruction_sequence: InstructionSequence::PayThroughRemoteDerivativeAccount,
-			},
+			)
 		};
-		get_xcmp_funds(other_creator.clone());
-		assert_ok!(AutomationPrice::validate_and_schedule_task(task));
-
-		assert_eq!(u128::try_from(Tasks::<Test>::iter().count()).unwrap(), expired_task_gen + 1);
 
+		// The signature does not match another provider
+		let forged = PricePayload { provider: AccountId32::new(ALICE), ..payload.clone() };
 		assert_eq!(
-			// 10 task by creator, 1 task by other_creator
-			11,
-			AutomationPrice::get_task_stat(StatType::TotalTasksOverall).map_or(0, |v| v),
-			"total task count is incorrect"
+			validate(&forged),
+			Err(TransactionValidityError::Invalid(InvalidTransaction::BadProof)),
 		);
+
+		assert_ok!(validate(&payload));
+		assert_ok!(AutomationPrice::submit_asset_prices_unsigned(
+			RuntimeOrigin::none(),
+			payload.clone(),
+			signature.clone(),
+		));
+		let p = AutomationPrice::get_asset_price_data((
+			chain1.to_vec(),
+			exchange1.to_vec(),
+			(asset1.to_vec(), asset2.to_vec()),
+		))
+		.expect("cannot get price");
+		assert_eq!(p.value, 1005);
+
+		// The provider is rate limited, and the payload cannot be replayed once the limit is over
 		assert_eq!(
-			10,
-			AutomationPrice::get_account_stat(creator.clone(), StatType::TotalTasksPerAccount)
-				.map_or(0, |v| v),
-			"total task count is incorrect"
+			validate(&payload),
+			Err(TransactionValidityError::Invalid(InvalidTransaction::Future)),
 		);
+		System::set_block_number(System::block_number() + 5);
 		assert_eq!(
-			1,
-			AutomationPrice::get_account_stat(
-				other_creator.clone(),
-				StatType::TotalTasksPerAccount
-			)
-			.map_or(0, |v| v),
-			"total task count is incorrect"
+			validate(&payload),
+			Err(TransactionValidityError::Invalid(InvalidTransaction::Stale)),
 		);
+	})
+}
 
-		assert_eq!(
-			10,
-			AutomationPrice::get_sorted_tasks_index((
-				chain1.to_vec(),
+#[test]
+fn test_update_asset_prices_normalizes_prices_to_the_price_decimals() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let sender = AccountId32::new(ALICE);
+		let price_decimals: u8 = <Test as Config>::PriceDecimals::get();
+
+		let chains: [&[u8]; 4] = [chain1, chain2, b"POLKADOT", b"MOONBEAM"];
+		let decimals = [price_decimals - 2, price_decimals + 2, price_decimals + 4, 0];
+		for (chain, decimal) in chains.iter().zip(decimals) {
+			assert_ok!(AutomationPrice::initialize_asset(
+				RawOrigin::Root.into(),
+				chain.to_vec(),
 				exchange1.to_vec(),
-				(asset1.to_vec(), asset2.to_vec()),
-				"gt".as_bytes().to_vec(),
-			))
+				asset1.to_vec(),
+				asset2.to_vec(),
+				decimal,
+				vec![sender.clone()],
+			));
+		}
+
+		let prices = [1005, 1005, 1005, u128::MAX];
+		assert_ok!(AutomationPrice::update_asset_prices(
+			RuntimeOrigin::signed(sender.clone()),
+			chains
+				.iter()
+				.zip(prices)
+				.map(|(chain, price)| PriceUpdate {
+					chain: chain.to_vec(),
+					exchange: exchange1.to_vec(),
+					asset1: asset1.to_vec(),
+					asset2: asset2.to_vec(),
+					price,
+					submitted_at: START_BLOCK_TIME as u128,
+					round: 1,
+				})
+				.collect(),
+		));
+
+		let price_of = |chain: &[u8]| {
+			AutomationPrice::get_asset_price_data((
+				chain.to_vec(),
+				exchange1.to_vec(),
+				(asset1.to_vec(), asset2.to_vec()),
+			))
+			.map(|price| price.value)
+		};
+		assert_eq!(price_of(chain1), Some(100_500));
+		assert_eq!(price_of(chain2), Some(10));
+		// The price is lost to rounding, or does not fit in the price decimals
+		assert_eq!(price_of(chains[2]), None);
+		assert_eq!(price_of(chains[3]), None);
+
+		let outcomes: Vec<SubmissionOutcome> =
+			AutomationPrice::get_oracle_submissions(System::block_number())
+				.into_iter()
+				.map(|submission| submission.outcome)
+				.collect();
+		assert_eq!(
+			outcomes[2..],
+			[
+				SubmissionOutcome::Rejected { error: Error::<Test>::InvalidPrice.into() },
+				SubmissionOutcome::Rejected { error: ArithmeticError::Overflow.into() },
+			]
+		);
+	})
+}
+
+#[test]
+fn test_set_asset_symbol() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let sender = AccountId32::new(ALICE);
+
+		setup_asset(&sender, chain1.to_vec());
+		let metadata = || {
+			AutomationPrice::get_asset_metadata(
+				chain1.to_vec(),
+				exchange1.to_vec(),
+				asset1.to_vec(),
+				asset2.to_vec(),
+			)
+		};
+		assert_eq!(metadata(), Some(AssetMetadata { symbol: vec![], decimal: 10 }));
+
+		assert_noop!(
+			AutomationPrice::set_asset_symbol(
+				RuntimeOrigin::signed(sender),
+				chain1.to_vec(),
+				exchange1.to_vec(),
+				asset1.to_vec(),
+				asset2.to_vec(),
+				b"TUR/USDC".to_vec(),
+			),
+			sp_runtime::DispatchError::BadOrigin,
+		);
+		assert_ok!(AutomationPrice::set_asset_symbol(
+			RawOrigin::Root.into(),
+			chain1.to_vec(),
+			exchange1.to_vec(),
+			asset1.to_vec(),
+			asset2.to_vec(),
+			b"TUR/USDC".to_vec(),
+		));
+		assert_eq!(metadata(), Some(AssetMetadata { symbol: b"TUR/USDC".to_vec(), decimal: 10 }));
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::AssetSymbolUpdated {
+			chain: chain1.to_vec(),
+			exchange: exchange1.to_vec(),
+			asset1: asset1.to_vec(),
+			asset2: asset2.to_vec(),
+			symbol: b"TUR/USDC".to_vec(),
+		}));
+
+		assert_eq!(
+			AutomationPrice::get_asset_metadata(
+				chain2.to_vec(),
+				exchange1.to_vec(),
+				asset1.to_vec(),
+				asset2.to_vec(),
+			),
+			None
+		);
+	})
+}
+
+#[test]
+fn test_prioritize_oracle_updates_from_providers() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let sender = AccountId32::new(ALICE);
+
+		setup_asset(&sender, chain1.to_vec());
+
+		let call: RuntimeCall = crate::Call::update_asset_prices {
+			updates: vec![PriceUpdate {
+				chain: chain1.to_vec(),
+				exchange: exchange1.to_vec(),
+				asset1: asset1.to_vec(),
+				asset2: asset2.to_vec(),
+				price: 1005,
+				submitted_at: START_BLOCK_TIME as u128,
+				round: 1,
+			}],
+		}
+		.into();
+		let info = DispatchInfo::default();
+		let extension = PrioritizeOracleUpdates::<Test>::new();
+
+		let provider = extension.validate(&sender, &call, &info, 0).expect("valid transaction");
+		assert_eq!(provider.priority, 1_000);
+
+		let committee_member = AccountId32::new(ORACLE_COMMITTEE_MEMBER);
+		let member = extension.validate(&committee_member, &call, &info, 0).expect("valid");
+		assert_eq!(member.priority, 1_000);
+
+		let other = extension.validate(&AccountId32::new(BOB), &call, &info, 0).expect("valid");
+		assert_eq!(other.priority, 0);
+	})
+}
+
+#[test]
+fn test_update_asset_price_increase_round() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let sender = AccountId32::new(ALICE);
+
+		setup_asset(&sender, chain1.to_vec());
+
+		assert_ok!(AutomationPrice::update_asset_prices(
+			RuntimeOrigin::signed(sender.clone()),
+			vec![PriceUpdate {
+				chain: chain1.to_vec(),
+				exchange: exchange1.to_vec(),
+				asset1: asset1.to_vec(),
+				asset2: asset2.to_vec(),
+				price: 1005,
+				submitted_at: START_BLOCK_TIME as u128,
+				round: 1,
+			}],
+		));
+
+		let p = AutomationPrice::get_asset_price_data((
+			chain1.to_vec(),
+			exchange1.to_vec(),
+			(asset1.to_vec(), asset2.to_vec()),
+		))
+		.expect("cannot get price");
+
+		assert_eq!(p.round, 1);
+		assert_eq!(p.updated_at, (START_BLOCK_TIME / 1000).into());
+
+		Timestamp::set_timestamp(
+			(START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND * 1000).try_into().unwrap(),
+		);
+		assert_ok!(AutomationPrice::update_asset_prices(
+			RuntimeOrigin::signed(sender),
+			vec![PriceUpdate {
+				chain: chain1.to_vec(),
+				exchange: exchange1.to_vec(),
+				asset1: asset1.to_vec(),
+				asset2: asset2.to_vec(),
+				price: 1005,
+				submitted_at: START_BLOCK_TIME as u128,
+				round: 2,
+			}],
+		));
+
+		let p = AutomationPrice::get_asset_price_data((
+			chain1.to_vec(),
+			exchange1.to_vec(),
+			(asset1.to_vec(), asset2.to_vec()),
+		))
+		.expect("cannot get price");
+
+		assert_eq!(p.round, 2);
+		assert_eq!(p.updated_at, START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND);
+	})
+}
+
+// A provider cannot replay a round it submitted, and nobody can submit a round older than the
+// round of the current price
+#[test]
+fn test_update_asset_prices_rejects_stale_and_replayed_rounds() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let sender = AccountId32::new(ALICE);
+		let committee_member = AccountId32::new(ORACLE_COMMITTEE_MEMBER);
+		let key = (chain1.to_vec(), exchange1.to_vec(), (asset1.to_vec(), asset2.to_vec()));
+
+		setup_asset(&sender, chain1.to_vec());
+
+		let update_price = |who: &AccountId32, price: u128, round: u128| {
+			assert_ok!(AutomationPrice::update_asset_prices(
+				RuntimeOrigin::signed(who.clone()),
+				vec![PriceUpdate {
+					chain: chain1.to_vec(),
+					exchange: exchange1.to_vec(),
+					asset1: asset1.to_vec(),
+					asset2: asset2.to_vec(),
+					price,
+					submitted_at: START_BLOCK_TIME as u128,
+					round,
+				}],
+			));
+			AutomationPrice::get_oracle_submissions(System::block_number())
+				.last()
+				.expect("every submission is logged")
+				.outcome
+				.clone()
+		};
+
+		assert_eq!(update_price(&sender, 1000, 5), SubmissionOutcome::Accepted);
+		assert_eq!(
+			update_price(&sender, 900, 5),
+			SubmissionOutcome::Rejected { error: Error::<Test>::RoundAlreadySubmitted.into() }
+		);
+		// Another provider can still submit for the current round, which updates the median
+		assert_eq!(update_price(&committee_member, 1010, 5), SubmissionOutcome::Accepted);
+		assert_eq!(
+			update_price(&committee_member, 800, 4),
+			SubmissionOutcome::Rejected { error: Error::<Test>::StaleRound.into() }
+		);
+
+		let price = AutomationPrice::get_asset_price_data(&key).expect("cannot get price");
+		assert_eq!((price.round, price.value), (5, 1005));
+		assert_eq!(AutomationPrice::get_oracle_round((&key.0, &key.1, &key.2, &sender)), Some(5));
+	})
+}
+
+// The price of a round is only written once a quorum of providers submitted one, as their median
+#[test]
+fn test_update_asset_prices_writes_the_median_once_the_quorum_is_met() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let key = (chain1.to_vec(), exchange1.to_vec(), (asset1.to_vec(), asset2.to_vec()));
+		let providers: Vec<AccountId32> =
+			[ALICE, BOB, DELEGATOR_ACCOUNT, PROXY_ACCOUNT].map(AccountId32::new).to_vec();
+		OracleQuorum::set(3);
+
+		assert_ok!(AutomationPrice::initialize_asset(
+			RawOrigin::Root.into(),
+			chain1.to_vec(),
+			exchange1.to_vec(),
+			asset1.to_vec(),
+			asset2.to_vec(),
+			10,
+			providers.clone(),
+		));
+
+		let update_price = |who: &AccountId32, price: u128, round: u128| {
+			assert_ok!(AutomationPrice::update_asset_prices(
+				RuntimeOrigin::signed(who.clone()),
+				vec![PriceUpdate {
+					chain: chain1.to_vec(),
+					exchange: exchange1.to_vec(),
+					asset1: asset1.to_vec(),
+					asset2: asset2.to_vec(),
+					price,
+					submitted_at: START_BLOCK_TIME as u128,
+					round,
+				}],
+			));
+		};
+
+		update_price(&providers[0], 1000, 1);
+		update_price(&providers[1], 5000, 1);
+		assert!(AutomationPrice::get_asset_price_data(&key).is_none());
+
+		// The third price meets the quorum, and the outlier does not move the median
+		update_price(&providers[2], 1100, 1);
+		let price = AutomationPrice::get_asset_price_data(&key).expect("cannot get price");
+		assert_eq!((price.round, price.value), (1, 1100));
+
+		// Late submissions for the round keep updating its median
+		update_price(&providers[3], 1020, 1);
+		let price = AutomationPrice::get_asset_price_data(&key).expect("cannot get price");
+		assert_eq!((price.round, price.value), (1, 1060));
+
+		// A new round starts over and leaves the price alone until its quorum is met
+		update_price(&providers[0], 2000, 2);
+		assert_eq!(AutomationPrice::get_round_prices(&key).map(|s| s.prices.len()), Some(1));
+		let price = AutomationPrice::get_asset_price_data(&key).expect("cannot get price");
+		assert_eq!((price.round, price.value), (1, 1060));
+	})
+}
+
+#[test]
+fn test_update_asset_prices_multi() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let sender = AccountId32::new(ALICE);
+
+		setup_asset(&sender, chain1.to_vec());
+		setup_asset(&sender, chain2.to_vec());
+
+		assert_ok!(AutomationPrice::update_asset_prices(
+			RuntimeOrigin::signed(sender.clone()),
+			vec![
+				PriceUpdate {
+					chain: chain1.to_vec(),
+					exchange: exchange1.to_vec(),
+					asset1: asset1.to_vec(),
+					asset2: asset2.to_vec(),
+					price: 1005,
+					submitted_at: START_BLOCK_TIME as u128,
+					round: 1,
+				},
+				PriceUpdate {
+					chain: chain2.to_vec(),
+					exchange: exchange1.to_vec(),
+					asset1: asset1.to_vec(),
+					asset2: asset2.to_vec(),
+					price: 1009,
+					submitted_at: START_BLOCK_TIME as u128,
+					round: 2,
+				},
+			],
+		));
+
+		let p1 = AutomationPrice::get_asset_price_data((
+			chain1.to_vec(),
+			exchange1.to_vec(),
+			(asset1.to_vec(), asset2.to_vec()),
+		))
+		.expect("cannot get price");
+
+		assert_eq!(p1.round, 1);
+		assert_eq!(p1.value, 1005);
+
+		let p2 = AutomationPrice::get_asset_price_data((
+			chain2.to_vec(),
+			exchange1.to_vec(),
+			(asset1.to_vec(), asset2.to_vec()),
+		))
+		.expect("cannot get price");
+
+		assert_eq!(p2.round, 2);
+		assert_eq!(p2.value, 1009);
+
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::AssetUpdated {
+			owner_id: sender.clone(),
+			chain: chain1.to_vec(),
+			exchange: exchange1.to_vec(),
+			asset1: asset1.to_vec(),
+			asset2: asset2.to_vec(),
+			price: 1005,
+		}));
+
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::AssetUpdated {
+			owner_id: sender,
+			chain: chain2.to_vec(),
+			exchange: exchange1.to_vec(),
+			asset1: asset1.to_vec(),
+			asset2: asset2.to_vec(),
+			price: 1009,
+		}));
+	})
+}
+
+#[test]
+fn test_schedule_xcmp_task_ok() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let para_id: u32 = 1000;
+		let creator = AccountId32::new(ALICE);
+		let call: Vec<u8> = vec![2, 4, 5];
+		let destination = MultiLocation::new(1, X1(Parachain(para_id)));
+
+		setup_asset(&creator, chain1.to_vec());
+
+		get_xcmp_funds(creator.clone());
+		assert_ok!(AutomationPrice::schedule_xcmp_task(
+			RuntimeOrigin::signed(creator.clone()),
+			chain1.to_vec(),
+			exchange1.to_vec(),
+			asset1.to_vec(),
+			asset2.to_vec(),
+			START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
+			"gt".as_bytes().to_vec(),
+			vec!(100),
+			Box::new(destination.into()),
+			Box::new(NATIVE_LOCATION.into()),
+			Box::new(AssetPayment {
+				asset_location: MultiLocation::new(0, Here).into(),
+				amount: MOCK_XCMP_FEE
+			}),
+			call.clone(),
+			Weight::from_parts(100_000, 0),
+			Weight::from_parts(200_000, 0),
+			None
+		));
+
+		// Upon schedule, task will be insert into 3 places
+		// 1. TaskRegistry: a fast hashmap look up using task id only
+		// 2. SortedTasksAbove: an ordering BTreeMap of the task, only task id and its price
+		//          trigger
+		// 3. AccountTasks: hashmap to look up user task id
+
+		let task_ids = get_task_ids_from_events();
+		let task_id = *task_ids.first().expect("task failed to schedule");
+
+		let task = AutomationPrice::get_task(&creator, &task_id).expect("missing task in registry");
+		assert_eq!(
+			task.trigger_function,
+			"gt".as_bytes().to_vec(),
+			"created task has wrong trigger function"
+		);
+		assert_eq!(task.chain, chain1.to_vec(), "created task has different chain id");
+		assert_eq!(task.asset_pair.0, asset1, "created task has wrong asset pair");
+
+		assert_eq!(START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND, task.expired_at);
+
+		// The event carries what was scheduled
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskScheduled {
+			owner_id: creator.clone(),
+			task_id,
+			schedule_as: None,
+			chain: chain1.to_vec(),
+			exchange: exchange1.to_vec(),
+			asset_pair: (asset1.to_vec(), asset2.to_vec()),
+			trigger_function: "gt".as_bytes().to_vec(),
+			trigger_params: vec![100],
+			action_kind: crate::ActionKind::XCMP,
+			destination,
+			expired_at: START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
+		}));
+
+		// Ensure task is inserted into the right SortedIndex
+
+		// Create  second task, and make sure both are recorded
+		get_xcmp_funds(creator.clone());
+		assert_ok!(AutomationPrice::schedule_xcmp_task(
+			RuntimeOrigin::signed(creator.clone()),
+			chain1.to_vec(),
+			exchange1.to_vec(),
+			asset1.to_vec(),
+			asset2.to_vec(),
+			START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
+			"gt".as_bytes().to_vec(),
+			vec!(100),
+			Box::new(destination.into()),
+			Box::new(NATIVE_LOCATION.into()),
+			Box::new(AssetPayment {
+				asset_location: MultiLocation::new(0, Here).into(),
+				amount: MOCK_XCMP_FEE
+			}),
+			call,
+			Weight::from_parts(100_000, 0),
+			Weight::from_parts(200_000, 0),
+			None
+		));
+		let task_ids2 = get_task_ids_from_events();
+		let task_id2 = *task_ids2.last().expect("task failed to schedule");
+		assert_ne!(task_id, task_id2, "task id dup");
+
+		let sorted_task_index = AutomationPrice::get_sorted_tasks_index((
+			chain1.to_vec(),
+			exchange1.to_vec(),
+			(asset1.to_vec(), asset2.to_vec()),
+			TriggerFunction::Gt,
+		))
+		.unwrap();
+		let task_ids: Vec<TaskIdList<Test>> = sorted_task_index.into_values().collect();
+		assert_eq!(task_ids, vec!(vec!((creator.clone(), task_id), (creator.clone(), task_id2))));
+
+		// We had schedule 2 tasks so far, all two belong to the same account
+		assert_eq!(
+			2,
+			AutomationPrice::get_task_stat(StatType::TotalTasksOverall).map_or(0, |v| v),
+			"total task count is incorrect"
+		);
+		assert_eq!(
+			2,
+			AutomationPrice::get_account_stat(creator, StatType::TotalTasksPerAccount)
+				.map_or(0, |v| v),
+			"total task count is incorrect"
+		);
+	})
+}
+
+// A proxy schedules the task on behalf of its delegator, and the task pays through the
+// derivative account of the delegator on the destination chain
+#[test]
+fn test_schedule_xcmp_task_through_proxy_ok() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let proxy_account = AccountId32::new(PROXY_ACCOUNT);
+		let delegator_account = AccountId32::new(DELEGATOR_ACCOUNT);
+		let destination = MultiLocation::new(1, X1(Parachain(PARA_ID)));
+
+		setup_asset(&proxy_account, chain1.to_vec());
+		get_xcmp_funds(proxy_account.clone());
+
+		let schedule = |owner: &AccountId32, schedule_as: &AccountId32| {
+			AutomationPrice::schedule_xcmp_task_through_proxy(
+				RuntimeOrigin::signed(owner.clone()),
+				chain1.to_vec(),
+				exchange1.to_vec(),
+				asset1.to_vec(),
+				asset2.to_vec(),
+				START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
+				"gt".as_bytes().to_vec(),
+				vec![100],
+				Box::new(destination.into()),
+				Box::new(NATIVE_LOCATION.into()),
+				Box::new(AssetPayment {
+					asset_location: destination.into(),
+					amount: MOCK_XCMP_FEE,
+				}),
+				vec![2, 4, 5],
+				Weight::from_parts(100_000, 0),
+				Weight::from_parts(200_000, 0),
+				schedule_as.clone(),
+				None,
+			)
+		};
+
+		// Only a proxy of the delegator can schedule as the delegator
+		assert_noop!(
+			schedule(&delegator_account, &proxy_account),
+			sp_runtime::DispatchError::Other("proxy error: expected `ProxyType::Any`")
+		);
+		assert_ok!(schedule(&proxy_account, &delegator_account));
+
+		let task_ids = get_task_ids_from_events();
+		let task_id = *task_ids.first().expect("task failed to schedule");
+		let task =
+			AutomationPrice::get_task(&proxy_account, task_id).expect("missing task in registry");
+		match task.action {
+			Action::XCMP { schedule_as, instruction_sequence, .. } => {
+				assert_eq!(schedule_as, Some(delegator_account.clone()));
+				assert_eq!(
+					instruction_sequence,
+					InstructionSequence::PayThroughRemoteDerivativeAccount
+				);
+			},
+			_ => panic!("task has the wrong action"),
+		}
+
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskScheduled {
+			owner_id: proxy_account,
+			task_id,
+			schedule_as: Some(delegator_account),
+			chain: chain1.to_vec(),
+			exchange: exchange1.to_vec(),
+			asset_pair: (asset1.to_vec(), asset2.to_vec()),
+			trigger_function: "gt".as_bytes().to_vec(),
+			trigger_params: vec![100],
+			action_kind: crate::ActionKind::XCMP,
+			destination,
+			expired_at: START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
+		}));
+	})
+}
+
+// Verify when user having not enough fund, we will fail with the right error code
+#[test]
+fn test_schedule_xcmp_task_fail_not_enough_balance() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let para_id: u32 = 1000;
+		let creator = AccountId32::new(ALICE);
+		let call: Vec<u8> = vec![2, 4, 5];
+		let destination = MultiLocation::new(1, X1(Parachain(para_id)));
+
+		setup_asset(&creator, chain1.to_vec());
+
+		get_xcmp_funds(creator.clone());
+		assert_noop!(
+			AutomationPrice::schedule_xcmp_task(
+				RuntimeOrigin::signed(creator),
+				chain1.to_vec(),
+				exchange1.to_vec(),
+				asset1.to_vec(),
+				asset2.to_vec(),
+				START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
+				"gt".as_bytes().to_vec(),
+				vec!(100),
+				Box::new(destination.into()),
+				Box::new(NATIVE_LOCATION.into()),
+				Box::new(AssetPayment {
+					asset_location: MultiLocation::new(0, Here).into(),
+					// Make a  really high fee to simulate not enough balance
+					amount: MOCK_XCMP_FEE * 10_000
+				}),
+				call,
+				Weight::from_ref_time(100_000),
+				Weight::from_ref_time(200_000),
+				None
+			),
+			Error::<Test>::InsufficientBalance,
+		);
+	})
+}
+
+// Verify that upon scheduling a task, the task expiration will be inserted into
+// SortedTasksByExpiration and shard by expired_at.
+#[test]
+fn test_schedule_put_task_to_expiration_queue() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let para_id: u32 = 1000;
+		let creator = AccountId32::new(ALICE);
+		let call: Vec<u8> = vec![2, 4, 5];
+		let destination = MultiLocation::new(1, X1(Parachain(para_id)));
+
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+		// Lets setup 3 tasks
+		get_xcmp_funds(creator.clone());
+		assert_ok!(AutomationPrice::schedule_xcmp_task(
+			RuntimeOrigin::signed(creator.clone()),
+			chain1.to_vec(),
+			exchange1.to_vec(),
+			asset1.to_vec(),
+			asset2.to_vec(),
+			START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
+			"gt".as_bytes().to_vec(),
+			vec!(100),
+			Box::new(destination.into()),
+			Box::new(NATIVE_LOCATION.into()),
+			Box::new(AssetPayment {
+				asset_location: MultiLocation::new(0, Here).into(),
+				amount: MOCK_XCMP_FEE
+			}),
+			call,
+			Weight::from_parts(100_000, 0),
+			Weight::from_parts(200_000, 0),
+			None
+		));
+		let task_ids = get_task_ids_from_events();
+		let task_id = *task_ids.last().expect("task failed to schedule");
+
+		let task_expiration_map = AutomationPrice::get_sorted_tasks_by_expiration();
+		assert_eq!(
+			task_expiration_map
+				.get(&(START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND))
+				.expect("missing task expiration shard"),
+			&(BTreeMap::from([(task_id, creator)]))
+		);
+	})
+}
+
+// Verify that upon scheduling a task, the task expiration will be inserted into
+// SortedTasksByExpiration and shard by expired_at.
+// This test is similar as above test but we create multiple task wit different expiration to
+// ensure all of them got to the right spot by expired_at time.
+#[test]
+fn test_schedule_put_task_to_expiration_queue_multi() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let para_id: u32 = 1000;
+		let creator1 = AccountId32::new(ALICE);
+		let creator2 = AccountId32::new(BOB);
+		let call: Vec<u8> = vec![2, 4, 5];
+		let destination = MultiLocation::new(1, X1(Parachain(para_id)));
+
+		setup_asset(&creator1, chain1.to_vec());
+
+		get_xcmp_funds(creator1.clone());
+		assert_ok!(AutomationPrice::schedule_xcmp_task(
+			RuntimeOrigin::signed(creator1.clone()),
+			chain1.to_vec(),
+			exchange1.to_vec(),
+			asset1.to_vec(),
+			asset2.to_vec(),
+			START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
+			"gt".as_bytes().to_vec(),
+			vec!(100),
+			Box::new(destination.into()),
+			Box::new(NATIVE_LOCATION.into()),
+			Box::new(AssetPayment {
+				asset_location: MultiLocation::new(0, Here).into(),
+				amount: 100_000
+			}),
+			call.clone(),
+			Weight::from_ref_time(100_000),
+			Weight::from_ref_time(200_000),
+			None
+		));
+		let task_ids1 = get_task_ids_from_events();
+		let task_id1 = *task_ids1.last().expect("task failed to schedule");
+
+		get_xcmp_funds(creator2.clone());
+		assert_ok!(AutomationPrice::schedule_xcmp_task(
+			RuntimeOrigin::signed(creator2.clone()),
+			chain1.to_vec(),
+			exchange1.to_vec(),
+			asset1.to_vec(),
+			asset2.to_vec(),
+			START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND + 3600,
+			"lt".as_bytes().to_vec(),
+			vec!(100),
+			Box::new(destination.into()),
+			Box::new(NATIVE_LOCATION.into()),
+			Box::new(AssetPayment {
+				asset_location: MultiLocation::new(0, Here).into(),
+				amount: MOCK_XCMP_FEE
+			}),
+			call,
+			Weight::from_ref_time(100_000),
+			Weight::from_ref_time(200_000),
+			None
+		));
+		let task_ids2 = get_task_ids_from_events();
+		let task_id2 = *task_ids2.last().expect("task failed to schedule");
+
+		let task_expiration_map = AutomationPrice::get_sorted_tasks_by_expiration();
+		assert_eq!(
+			task_expiration_map
+				.get(&START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND)
+				.expect("missing task expiration shard"),
+			&BTreeMap::from([(task_id1, creator1)]),
+		);
+		assert_eq!(
+			task_expiration_map
+				.get(&(START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND + 3600))
+				.expect("missing task expiration shard"),
+			&BTreeMap::from([(task_id2, creator2)]),
+		);
+	})
+}
+
+// Verify that after calling sweep, expired task will be removed from all relevant storage. Our
+// stat is also decrease accordingly to the task removal
+#[test]
+fn test_sweep_expired_task_works() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		let other_creator = AccountId32::new(BOB);
+		let para_id: u32 = 1000;
+
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+
+		let destination = MultiLocation::new(1, X1(Parachain(para_id)));
+		let schedule_fee = MultiLocation::default();
+		let execution_fee = AssetPayment {
+			asset_location: MultiLocation::new(1, X1(Parachain(para_id))).into(),
+			amount: MOCK_XCMP_FEE,
+		};
+		let encoded_call_weight = Weight::from_ref_time(100_000);
+		let overall_weight = Weight::from_ref_time(200_000);
+
+		let expired_task_gen = 10;
+		let price_target1 = 2000;
+		for i in 0..expired_task_gen {
+			// schedule task that has expired
+			get_xcmp_funds(creator.clone());
+			let task = Task::<Test> {
+				owner_id: creator.clone(),
+				task_id: task_id_of(&format!("123-0-{:?}", i)),
+				chain: chain1.to_vec(),
+				exchange: exchange1.to_vec(),
+				asset_pair: (asset1.to_vec(), asset2.to_vec()),
+				expired_at: START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND - 1800,
+				trigger_function: "gt".as_bytes().to_vec(),
+				trigger_params: vec![price_target1],
+				action: Action::XCMP {
+					destination,
+					schedule_fee,
+					execution_fee: execution_fee.clone(),
+					encoded_call: vec![1, 2, 3],
+					encoded_call_weight,
+					overall_weight,
+					schedule_as: None,
+					instruction_sequence: InstructionSequence::PayThroughRemoteDerivativeAccount,
+				},
+			};
+			assert_ok!(AutomationPrice::validate_and_schedule_task(task.clone()));
+		}
+
+		// Now we set timestamp to a later point
+		Timestamp::set_timestamp(
+			START_BLOCK_TIME.saturating_add(3_600_000_u64).try_into().unwrap(),
+		);
+
+		let price_target2 = 1000;
+		let task = Task::<Test> {
+			owner_id: other_creator.clone(),
+			task_id: task_id_of("123-1-1"),
+			chain: chain1.to_vec(),
+			exchange: exchange1.to_vec(),
+			asset_pair: (asset1.to_vec(), asset2.to_vec()),
+			expired_at: START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND + 3600,
+			trigger_function: "lt".as_bytes().to_vec(),
+			trigger_params: vec![price_target2],
+			action: Action::XCMP {
+				destination,
+				schedule_fee,
+				execution_fee,
+				encoded_call: vec![1, 2, 3],
+				encoded_call_weight,
+				overall_weight,
+				schedule_as: None,
+				instruction_sequence: InstructionSequence::PayThroughRemoteDerivativeAccount,
+			},
+		};
+		get_xcmp_funds(other_creator.clone());
+		assert_ok!(AutomationPrice::validate_and_schedule_task(task));
+
+		assert_eq!(u128::try_from(Tasks::<Test>::iter().count()).unwrap(), expired_task_gen + 1);
+
+		assert_eq!(
+			// 10 task by creator, 1 task by other_creator
+			11,
+			AutomationPrice::get_task_stat(StatType::TotalTasksOverall).map_or(0, |v| v),
+			"total task count is incorrect"
+		);
+		assert_eq!(
+			10,
+			AutomationPrice::get_account_stat(creator.clone(), StatType::TotalTasksPerAccount)
+				.map_or(0, |v| v),
+			"total task count is incorrect"
+		);
+		assert_eq!(
+			1,
+			AutomationPrice::get_account_stat(
+				other_creator.clone(),
+				StatType::TotalTasksPerAccount
+			)
+			.map_or(0, |v| v),
+			"total task count is incorrect"
+		);
+
+		assert_eq!(
+			10,
+			AutomationPrice::get_sorted_tasks_index((
+				chain1.to_vec(),
+				exchange1.to_vec(),
+				(asset1.to_vec(), asset2.to_vec()),
+				TriggerFunction::Gt,
+			))
+			.map_or(0, |v| v.get(&price_target1).unwrap().iter().len())
+		);
+
+		assert_eq!(
+			1,
+			AutomationPrice::get_sorted_tasks_index((
+				chain1.to_vec(),
+				exchange1.to_vec(),
+				(asset1.to_vec(), asset2.to_vec()),
+				TriggerFunction::Lt,
+			))
+			.map_or(0, |v| v.get(&price_target2).unwrap().iter().len())
+		);
+
+		assert_eq!(
+			10,
+			AutomationPrice::get_sorted_tasks_by_expiration()
+				.get(&(START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND - 1800))
+				.expect("missing task expiration shard")
+				.len(),
+		);
+
+		// now we will sweep, passing a weight limit. In actualy code, this will be the
+		// remaining_weight in on_idle block
+		let remain_weight = 100_000_000_000;
+		AutomationPrice::sweep_expired_task(Weight::from_ref_time(remain_weight));
+
+		assert_eq!(
+			AutomationPrice::get_sorted_tasks_by_expiration()
+				.get(&(START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND - 1800)),
+			None
+		);
+
+		for i in 0..expired_task_gen {
+			assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskSweep {
+				owner_id: creator.clone(),
+				task_id: task_id_of(&format!("123-0-{:?}", i)),
+				condition: crate::TaskCondition::AlreadyExpired {
+					expired_at: START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND - 1800,
+					now: START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
+				},
+			}));
+		}
+
+		// After sweep there should only one task remain in queue
+		assert_eq!(Tasks::<Test>::iter().count(), 1);
+
+		// The task should be removed from the sorted task index
+		assert_eq!(
+			0,
+			AutomationPrice::get_sorted_tasks_index((
+				chain1.to_vec(),
+				exchange1.to_vec(),
+				(asset1.to_vec(), asset2.to_vec()),
+				TriggerFunction::Gt,
+			))
+			.expect("missing tasks sorted by price data")
+			.get(&price_target1)
+			.map_or(0, |v| v.iter().len())
+		);
+		// The task should be removed from the sorted task index
+		assert_eq!(
+			1,
+			AutomationPrice::get_sorted_tasks_index((
+				chain1.to_vec(),
+				exchange1.to_vec(),
+				(asset1.to_vec(), asset2.to_vec()),
+				TriggerFunction::Lt,
+			))
+			.expect("missing tasks sorted by price data")
+			.get(&price_target2)
+			.map_or(0, |v| v.iter().len())
+		);
+
+		// The task stat should be changed
+		assert_eq!(
+			1,
+			AutomationPrice::get_task_stat(StatType::TotalTasksOverall).map_or(0, |v| v),
+			"total task count is incorrect"
+		);
+		assert_eq!(
+			1,
+			AutomationPrice::get_account_stat(other_creator, StatType::TotalTasksPerAccount)
+				.map_or(0, |v| v),
+			"total task count is incorrect"
+		);
+	})
+}
+
+// Test swap partially data, and leave the rest of sorted index remain intact
+#[test]
+fn test_sweep_expired_task_partially() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		let _other_creator = AccountId32::new(BOB);
+		let para_id: u32 = 1000;
+
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+		let destination = MultiLocation::new(1, X1(Parachain(para_id)));
+		let schedule_fee = MultiLocation::default();
+		let execution_fee = AssetPayment {
+			asset_location: MultiLocation::new(1, X1(Parachain(para_id))).into(),
+			amount: MOCK_XCMP_FEE,
+		};
+		let encoded_call_weight = Weight::from_ref_time(100_000);
+		let overall_weight = Weight::from_ref_time(200_000);
+
+		let expired_task_gen = 11;
+		let price_target1 = 2000;
+		for i in 1..expired_task_gen {
+			// schedule task that has expired
+			get_xcmp_funds(creator.clone());
+			let task = Task::<Test> {
+				owner_id: creator.clone(),
+				task_id: task_id_of(&format!("123-0-{:?}", i)),
+				chain: chain1.to_vec(),
+				exchange: exchange1.to_vec(),
+				asset_pair: (asset1.to_vec(), asset2.to_vec()),
+				expired_at: START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND + 10 + i * 10,
+				trigger_function: "gt".as_bytes().to_vec(),
+				trigger_params: vec![price_target1],
+				action: Action::XCMP {
+					destination,
+					schedule_fee,
+					execution_fee: execution_fee.clone(),
+					encoded_call: vec![1, 2, 3],
+					encoded_call_weight,
+					overall_weight,
+					schedule_as: None,
+					instruction_sequence: InstructionSequence::PayThroughRemoteDerivativeAccount,
+				},
+			};
+			assert_ok!(AutomationPrice::validate_and_schedule_task(task.clone()));
+		}
+
+		// Now we set timestamp to a later point
+		Timestamp::set_timestamp(
+			START_BLOCK_TIME.saturating_add((3600 + 6 * 10) * 1000).try_into().unwrap(),
+		);
+
+		assert_eq!(
+			10,
+			AutomationPrice::get_sorted_tasks_index((
+				chain1.to_vec(),
+				exchange1.to_vec(),
+				(asset1.to_vec(), asset2.to_vec()),
+				TriggerFunction::Gt,
+			))
 			.map_or(0, |v| v.get(&price_target1).unwrap().iter().len())
 		);
 
-		assert_eq!(
-			1,
-			AutomationPrice::get_sorted_tasks_index((
+		assert_eq!(10, AutomationPrice::get_sorted_tasks_by_expiration().len());
+
+		// remaining_weight in on_idle block
+		let remain_weight = 100_000_000_000;
+		AutomationPrice::sweep_expired_task(Weight::from_ref_time(remain_weight));
+
+		// The task should be removed from the sorted task index
+		assert_eq!(
+			5,
+			AutomationPrice::get_sorted_tasks_index((
+				chain1.to_vec(),
+				exchange1.to_vec(),
+				(asset1.to_vec(), asset2.to_vec()),
+				TriggerFunction::Gt,
+			))
+			.expect("missing tasks sorted by price data")
+			.get(&price_target1)
+			.map_or(0, |v| v.iter().len())
+		);
+
+		// these task all get sweeo
+		for i in 1..5 {
+			assert_eq!(
+				0,
+				AutomationPrice::get_sorted_tasks_by_expiration()
+					.get(&(START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND + 10 + i * 10))
+					.map_or(0, |v| v.len()),
+			);
+		}
+
+		// these slot remian untouch
+		for i in 6..10 {
+			assert_eq!(
+				1,
+				AutomationPrice::get_sorted_tasks_by_expiration()
+					.get(&(START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND + 10 + i * 10))
+					.map_or(0, |v| v.len()),
+			);
+		}
+	})
+}
+
+#[test]
+fn test_schedule_return_error_when_reaching_max_tasks_overall_limit() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let para_id: u32 = 1000;
+		let creator = AccountId32::new(ALICE);
+		let call: Vec<u8> = vec![2, 4, 5];
+		let destination = MultiLocation::new(1, X1(Parachain(para_id)));
+
+		setup_asset(&creator, chain1.to_vec());
+
+		TaskStats::<Test>::insert(StatType::TotalTasksOverall, 1_000_000_000);
+
+		assert_noop!(
+			AutomationPrice::schedule_xcmp_task(
+				RuntimeOrigin::signed(creator),
+				chain1.to_vec(),
+				exchange1.to_vec(),
+				asset1.to_vec(),
+				asset2.to_vec(),
+				START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND + 3600,
+				"gt".as_bytes().to_vec(),
+				vec!(100),
+				Box::new(destination.into()),
+				Box::new(NATIVE_LOCATION.into()),
+				Box::new(AssetPayment {
+					asset_location: MultiLocation::new(0, Here).into(),
+					amount: 10000000000000
+				}),
+				call,
+				Weight::from_ref_time(100_000),
+				Weight::from_ref_time(200_000),
+				None
+			),
+			Error::<Test>::MaxTasksReached,
+		);
+	})
+}
+
+#[test]
+fn test_schedule_return_error_when_reaching_max_account_tasks_limit() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let para_id: u32 = 1000;
+		let creator = AccountId32::new(ALICE);
+		let call: Vec<u8> = vec![2, 4, 5];
+		let destination = MultiLocation::new(1, X1(Parachain(para_id)));
+
+		setup_asset(&creator, chain1.to_vec());
+
+		AccountStats::<Test>::insert(creator.clone(), StatType::TotalTasksPerAccount, 1_000);
+
+		assert_noop!(
+			AutomationPrice::schedule_xcmp_task(
+				RuntimeOrigin::signed(creator),
+				chain1.to_vec(),
+				exchange1.to_vec(),
+				asset1.to_vec(),
+				asset2.to_vec(),
+				START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
+				"gt".as_bytes().to_vec(),
+				vec!(100),
+				Box::new(destination.into()),
+				Box::new(NATIVE_LOCATION.into()),
+				Box::new(AssetPayment {
+					asset_location: MultiLocation::new(0, Here).into(),
+					amount: MOCK_XCMP_FEE
+				}),
+				call,
+				Weight::from_ref_time(100_000),
+				Weight::from_ref_time(200_000),
+				None
+			),
+			Error::<Test>::MaxTasksPerAccountReached,
+		);
+	})
+}
+
+// The tasks of an account can be paged through without seeing the tasks of other accounts
+#[test]
+fn test_get_account_task_ids_pages_through_the_tasks_of_an_account() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		let other = AccountId32::new(BOB);
+
+		let mut task = build_update_trigger_task(&creator);
+		for i in 0..5u8 {
+			task.task_id = [i; 32];
+			crate::Tasks::<Test>::insert(&creator, &task.task_id, &task);
+		}
+		crate::Tasks::<Test>::insert(&other, [9; 32], build_update_trigger_task(&other));
+
+		let mut pages = vec![];
+		let mut start_after = None;
+		loop {
+			let page = AutomationPrice::get_account_task_ids(&creator, start_after, 2);
+			if page.is_empty() {
+				break
+			}
+			start_after = page.last().cloned();
+			pages.push(page);
+		}
+
+		assert_eq!(pages.iter().map(|page| page.len()).collect::<Vec<_>>(), vec![2, 2, 1]);
+		let mut task_ids = pages.concat();
+		task_ids.sort();
+		assert_eq!(task_ids, (0..5u8).map(|i| [i; 32]).collect::<Vec<_>>());
+	})
+}
+
+// Test when price moves, the TaskQueue will be populated with the right task id
+//
+// In this test we will first setup 3 tasks for 3 pairs
+//  task1 for pair1
+//  task2 for pair2
+//  task3 for pair3
+//
+//  when we will adjust the price of the pair, we purposely let task2 price not match
+//  so we will test that task1, task3 will be moved into the queue accordingly.
+//
+//  then finally schedule a new task4, for pair3, but its price will match. we will test
+//  that task4 are moved to TaskQueue. task2 won't be moved
+//
+//  The purpose of this test is to simulae a few tasks being trigger accordingly to their
+//  price movement. We also verify that task  won't have its price match, will never get
+//  trigger
+#[test]
+fn test_shift_tasks_movement_through_price_changes() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		// TODO: Setup fund once we add fund check and weight
+		let para_id: u32 = 1000;
+		let creator = AccountId32::new(ALICE);
+		let call: Vec<u8> = vec![2, 4, 5];
+		let destination = MultiLocation::new(1, X1(Parachain(para_id)));
+
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+
+		let base_price = 10_000_u128;
+
+		// Lets setup 3 tasks
+		get_xcmp_funds(creator.clone());
+		assert_ok!(AutomationPrice::schedule_xcmp_task(
+			RuntimeOrigin::signed(creator.clone()),
+			chain1.to_vec(),
+			exchange1.to_vec(),
+			asset1.to_vec(),
+			asset2.to_vec(),
+			START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
+			"gt".as_bytes().to_vec(),
+			vec!(base_price + 1000),
+			Box::new(destination.into()),
+			Box::new(NATIVE_LOCATION.into()),
+			Box::new(AssetPayment {
+				asset_location: MultiLocation::new(0, Here).into(),
+				amount: MOCK_XCMP_FEE
+			}),
+			call.clone(),
+			Weight::from_ref_time(100_000),
+			Weight::from_ref_time(200_000),
+			None
+		));
+
+		get_xcmp_funds(creator.clone());
+		assert_ok!(AutomationPrice::schedule_xcmp_task(
+			RuntimeOrigin::signed(creator.clone()),
+			chain2.to_vec(),
+			exchange1.to_vec(),
+			asset2.to_vec(),
+			asset3.to_vec(),
+			START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
+			"gt".as_bytes().to_vec(),
+			vec!(base_price + 900),
+			Box::new(destination.into()),
+			Box::new(NATIVE_LOCATION.into()),
+			Box::new(AssetPayment {
+				asset_location: MultiLocation::new(0, Here).into(),
+				amount: MOCK_XCMP_FEE
+			}),
+			call.clone(),
+			Weight::from_parts(100_000, 0),
+			Weight::from_parts(200_000, 0),
+			None
+		));
+
+		get_xcmp_funds(creator.clone());
+		assert_ok!(AutomationPrice::schedule_xcmp_task(
+			RuntimeOrigin::signed(creator.clone()),
+			chain2.to_vec(),
+			exchange1.to_vec(),
+			asset1.to_vec(),
+			asset3.to_vec(),
+			START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND + 6000,
+			"gt".as_bytes().to_vec(),
+			vec!(base_price + 1000),
+			Box::new(destination.into()),
+			Box::new(NATIVE_LOCATION.into()),
+			Box::new(AssetPayment {
+				asset_location: MultiLocation::new(0, Here).into(),
+				amount: MOCK_XCMP_FEE
+			}),
+			call.clone(),
+			Weight::from_parts(100_000, 0),
+			Weight::from_parts(200_000, 0),
+			None
+		));
+
+		let task_ids = get_task_ids_from_events();
+		let task_id1 = *task_ids.get(task_ids.len().wrapping_sub(3)).unwrap();
+		// let _task_id2 = *task_ids.get(task_ids.len().wrapping_sub(2)).unwrap();
+		let task_id3 = *task_ids.get(task_ids.len().wrapping_sub(1)).unwrap();
+
+		// at this moment our task queue is empty
+		// There is schedule tasks, but no tasks in the queue at this moment, because shift_tasks
+		// has not run yet
+		assert!(AutomationPrice::get_task_queue().is_empty());
+
+		// shift_tasks move task from registry to the queue
+		// At this moment, The price doesn't match the target so there is no change in our tasks
+		AutomationPrice::shift_tasks(
+			Weight::from_parts(1_000_000_000, 0),
+			&mut PriceCache::default(),
+		);
+		assert!(AutomationPrice::get_task_queue().is_empty());
+		let sorted_task_index = AutomationPrice::get_sorted_tasks_index((
+			chain1.to_vec(),
+			exchange1.to_vec(),
+			(asset1.to_vec(), asset2.to_vec()),
+			TriggerFunction::Gt,
+		));
+		assert_eq!(sorted_task_index.map_or_else(|| 0, |x| x.len()), 1);
+
+		// now we change price of pair1 to higher than its target price, while keeping pair2/pair3 low enough,
+		// only task_id1 will be moved to the queue.
+		// The target price for those respectively tasks are 10100, 10900, 102000 in their pair
+		// Therefore after running this price update, first task task_id1 are moved into TaskQueue
+		let new_pair_1_price: u128 = base_price + 2000;
+		let new_pair_2_price: u128 = 10_u128;
+		let mut new_pair_3_price: u128 = 300_u128;
+		assert_ok!(AutomationPrice::update_asset_prices(
+			RuntimeOrigin::signed(creator.clone()),
+			vec![
+				PriceUpdate {
+					chain: chain1.to_vec(),
+					exchange: exchange1.to_vec(),
+					asset1: asset1.to_vec(),
+					asset2: asset2.to_vec(),
+					price: new_pair_1_price,
+					submitted_at: START_BLOCK_TIME as u128,
+					round: 2,
+				},
+				PriceUpdate {
+					chain: chain2.to_vec(),
+					exchange: exchange1.to_vec(),
+					asset1: asset2.to_vec(),
+					asset2: asset3.to_vec(),
+					price: new_pair_2_price,
+					submitted_at: START_BLOCK_TIME as u128,
+					round: 2,
+				},
+				PriceUpdate {
+					chain: chain2.to_vec(),
+					exchange: exchange1.to_vec(),
+					asset1: asset1.to_vec(),
+					asset2: asset3.to_vec(),
+					price: new_pair_3_price,
+					submitted_at: START_BLOCK_TIME as u128,
+					round: 2,
+				},
+			],
+		));
+		AutomationPrice::shift_tasks(
+			Weight::from_parts(1_000_000_000, 0),
+			&mut PriceCache::default(),
+		);
+		assert_eq!(AutomationPrice::get_task_queue(), vec![(creator.clone(), task_id1)]);
+		// The task are removed from SortedTasksAbove into the TaskQueue, therefore their length
+		// decrease to 0
+		assert_eq!(
+			AutomationPrice::get_sorted_tasks_index((
+				chain1.to_vec(),
+				exchange1.to_vec(),
+				(asset1.to_vec(), asset2.to_vec()),
+				TriggerFunction::Gt,
+			))
+			.map_or_else(|| 0, |x| x.len()),
+			0
+		);
+
+		// now we move target price of pair3 to higher than its target, and will observe that its
+		// task will be moved to TaskQueue too.
+		new_pair_3_price = base_price + 2000;
+		AutomationPrice::update_asset_prices(
+			RuntimeOrigin::signed(creator.clone()),
+			vec![PriceUpdate {
+				chain: chain2.to_vec(),
+				exchange: exchange1.to_vec(),
+				asset1: asset1.to_vec(),
+				asset2: asset3.to_vec(),
+				price: new_pair_3_price,
+				submitted_at: START_BLOCK_TIME as u128,
+				round: 4,
+			}],
+		);
+		AutomationPrice::shift_tasks(
+			Weight::from_parts(1_000_000_000, 0),
+			&mut PriceCache::default(),
+		);
+		assert_eq!(
+			AutomationPrice::get_task_queue(),
+			vec![(creator.clone(), task_id1), (creator.clone(), task_id3)]
+		);
+		// The task are removed from SortedTasksAbove into the TaskQueue, therefore their length
+		// decrease to 0
+		assert_eq!(
+			AutomationPrice::get_sorted_tasks_index((
+				chain2.to_vec(),
+				exchange1.to_vec(),
+				(asset1.to_vec(), asset3.to_vec()),
+				TriggerFunction::Gt,
+			))
+			.map_or_else(|| 0, |x| x.len()),
+			0
+		);
+
+		// Now, if a new task come up, and its price target matches the existing price, they will
+		// be trigger too.
+		get_xcmp_funds(creator.clone());
+		assert_ok!(AutomationPrice::schedule_xcmp_task(
+			RuntimeOrigin::signed(creator.clone()),
+			chain2.to_vec(),
+			exchange1.to_vec(),
+			asset2.to_vec(),
+			asset3.to_vec(),
+			START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
+			"lt".as_bytes().to_vec(),
+			// price for this asset is 10 in our last update
+			vec!(20),
+			Box::new(destination.into()),
+			Box::new(NATIVE_LOCATION.into()),
+			Box::new(AssetPayment {
+				asset_location: MultiLocation::new(0, Here).into(),
+				amount: MOCK_XCMP_FEE
+			}),
+			call,
+			Weight::from_parts(100_000, 0),
+			Weight::from_parts(200_000, 0),
+			None
+		));
+		// The task is now on the sorted task index
+		assert_eq!(
+			AutomationPrice::get_sorted_tasks_index((
+				chain2.to_vec(),
+				exchange1.to_vec(),
+				(asset2.to_vec(), asset3.to_vec()),
+				TriggerFunction::Lt,
+			))
+			.map_or_else(|| 0, |x| x.len()),
+			1
+		);
+
+		AutomationPrice::shift_tasks(
+			Weight::from_parts(1_000_000_000, 0),
+			&mut PriceCache::default(),
+		);
+		let task_id4 = {
+			let task_ids = get_task_ids_from_events();
+			*task_ids.last().unwrap()
+		};
+
+		// Now the task is again, moved into the queue and be removed from the sorted task index
+		assert_eq!(
+			AutomationPrice::get_task_queue(),
+			vec![(creator.clone(), task_id1), (creator.clone(), task_id3), (creator, task_id4)]
+		);
+		assert_eq!(
+			AutomationPrice::get_sorted_tasks_index((
+				chain2.to_vec(),
+				exchange1.to_vec(),
+				(asset2.to_vec(), asset3.to_vec()),
+				TriggerFunction::Lt,
+			))
+			.map_or_else(|| 0, |x| x.len()),
+			0
+		);
+	})
+}
+
+// the logic around > or < using include/exclude range to include bound or not, it can be subtle
+// and error prone to human mistake so this test exist to make sure we catch that edge case.
+#[test]
+fn test_gt_task_not_run_when_asset_price_equal_target_price() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		// TODO: Setup fund once we add fund check and weight
+		let para_id: u32 = 1000;
+		let creator = AccountId32::new(ALICE);
+		let call: Vec<u8> = vec![2, 4, 5];
+		let destination = MultiLocation::new(1, X1(Parachain(para_id)));
+
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+
+		let base_price = 1_000_u128;
+
+		get_xcmp_funds(creator.clone());
+		assert_ok!(AutomationPrice::schedule_xcmp_task(
+			RuntimeOrigin::signed(creator),
+			chain1.to_vec(),
+			exchange1.to_vec(),
+			asset1.to_vec(),
+			asset2.to_vec(),
+			START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
+			"gt".as_bytes().to_vec(),
+			vec!(base_price),
+			Box::new(destination.into()),
+			Box::new(NATIVE_LOCATION.into()),
+			Box::new(AssetPayment {
+				asset_location: MultiLocation::new(0, Here).into(),
+				amount: 100_000
+			}),
+			call,
+			Weight::from_ref_time(100_000),
+			Weight::from_ref_time(200_000),
+			None
+		));
+
+		AutomationPrice::shift_tasks(
+			Weight::from_ref_time(1_000_000_000),
+			&mut PriceCache::default(),
+		);
+		// Task shouldn't be move to task queue to trigger, and the task queue should be empty
+		assert!(AutomationPrice::get_task_queue().is_empty());
+
+		let sorted_task_index = AutomationPrice::get_sorted_tasks_index((
+			chain1.to_vec(),
+			exchange1.to_vec(),
+			(asset1.to_vec(), asset2.to_vec()),
+			TriggerFunction::Gt,
+		));
+		assert_eq!(1, sorted_task_index.map_or_else(|| 0, |x| x.len()));
+	})
+}
+
+#[test]
+fn test_shift_tasks_only_checks_the_side_the_price_moved_towards() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		let destination = MultiLocation::new(1, X1(Parachain(1000)));
+		let pair_key = (chain1.to_vec(), exchange1.to_vec(), (asset1.to_vec(), asset2.to_vec()));
+
+		// The price of pair1 starts at 1000
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+
+		for (trigger_function, target_price) in [("gt", 1500_u128), ("lt", 500_u128)] {
+			get_xcmp_funds(creator.clone());
+			assert_ok!(AutomationPrice::schedule_xcmp_task(
+				RuntimeOrigin::signed(creator.clone()),
+				chain1.to_vec(),
+				exchange1.to_vec(),
+				asset1.to_vec(),
+				asset2.to_vec(),
+				START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
+				trigger_function.as_bytes().to_vec(),
+				vec!(target_price),
+				Box::new(destination.into()),
+				Box::new(NATIVE_LOCATION.into()),
+				Box::new(AssetPayment {
+					asset_location: MultiLocation::new(0, Here).into(),
+					amount: MOCK_XCMP_FEE
+				}),
+				vec![2, 4, 5],
+				Weight::from_parts(100_000, 0),
+				Weight::from_parts(200_000, 0),
+				None
+			));
+		}
+		let lt_task_id = *get_task_ids_from_events().last().unwrap();
+
+		AutomationPrice::shift_tasks(
+			Weight::from_parts(1_000_000_000, 0),
+			&mut PriceCache::default(),
+		);
+		assert!(AutomationPrice::get_task_queue().is_empty());
+		assert_eq!(LastShiftedPrice::<Test>::get(&pair_key), Some(1000));
+
+		// A `gt` entry that would match any price. It is only picked up when the price rises.
+		let above_entry = (creator.clone(), task_id_of("stale"));
+		let mut above = AutomationPrice::get_sorted_tasks_above(&pair_key).unwrap_or_default();
+		above.insert(1, vec![above_entry.clone()]);
+		put_sorted_tasks_above(&pair_key, above);
+
+		assert_ok!(AutomationPrice::update_asset_prices(
+			RuntimeOrigin::signed(creator.clone()),
+			vec![PriceUpdate {
+				chain: chain1.to_vec(),
+				exchange: exchange1.to_vec(),
+				asset1: asset1.to_vec(),
+				asset2: asset2.to_vec(),
+				price: 400,
+				submitted_at: START_BLOCK_TIME as u128,
+				round: 2,
+			}],
+		));
+		AutomationPrice::shift_tasks(
+			Weight::from_parts(1_000_000_000, 0),
+			&mut PriceCache::default(),
+		);
+		assert_eq!(AutomationPrice::get_task_queue(), vec![(creator.clone(), lt_task_id)]);
+		assert_eq!(LastShiftedPrice::<Test>::get(&pair_key), Some(400));
+		assert_eq!(
+			AutomationPrice::get_sorted_tasks_above(&pair_key).map_or(0, |tasks| tasks.len()),
+			2
+		);
+
+		assert_ok!(AutomationPrice::update_asset_prices(
+			RuntimeOrigin::signed(creator.clone()),
+			vec![PriceUpdate {
+				chain: chain1.to_vec(),
+				exchange: exchange1.to_vec(),
+				asset1: asset1.to_vec(),
+				asset2: asset2.to_vec(),
+				price: 450,
+				submitted_at: START_BLOCK_TIME as u128,
+				round: 3,
+			}],
+		));
+		AutomationPrice::shift_tasks(
+			Weight::from_parts(1_000_000_000, 0),
+			&mut PriceCache::default(),
+		);
+		assert_eq!(AutomationPrice::get_task_queue().last(), Some(&above_entry));
+		assert_eq!(
+			AutomationPrice::get_sorted_tasks_above(&pair_key).map_or(0, |tasks| tasks.len()),
+			1
+		);
+	})
+}
+
+// A pair with more matched tasks than it may shift in a block keeps the rest for the next block,
+// and the tasks of different pairs are queued in turn
+#[test]
+fn test_shift_tasks_checks_unsorted_tasks_when_the_price_moves() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		let destination = MultiLocation::new(1, X1(Parachain(1000)));
+		let pair_key = (chain1.to_vec(), exchange1.to_vec(), (asset1.to_vec(), asset2.to_vec()));
+
+		// The price of pair1 starts at 1000
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+
+		let schedule = |trigger_function: &str, trigger_params: Vec<u128>| {
+			AutomationPrice::schedule_xcmp_task(
+				RuntimeOrigin::signed(creator.clone()),
+				chain1.to_vec(),
+				exchange1.to_vec(),
+				asset1.to_vec(),
+				asset2.to_vec(),
+				START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
+				trigger_function.as_bytes().to_vec(),
+				trigger_params,
+				Box::new(destination.into()),
+				Box::new(NATIVE_LOCATION.into()),
+				Box::new(AssetPayment {
+					asset_location: MultiLocation::new(0, Here).into(),
+					amount: MOCK_XCMP_FEE,
+				}),
+				vec![2, 4, 5],
+				Weight::from_parts(100_000, 0),
+				Weight::from_parts(200_000, 0),
+				None,
+			)
+		};
+		get_xcmp_funds(creator.clone());
+		assert_noop!(schedule("eq", vec![500]), Error::<Test>::InvalidTriggerParams);
+		assert_noop!(schedule("range", vec![600, 400]), Error::<Test>::InvalidTriggerParams);
+		assert_ok!(schedule("range", vec![400, 600]));
+		let task_id = *get_task_ids_from_events().last().unwrap();
+		assert_eq!(
+			AutomationPrice::get_unsorted_tasks(&pair_key),
+			Some(vec![(creator.clone(), task_id)])
+		);
+
+		AutomationPrice::shift_tasks(
+			Weight::from_parts(1_000_000_000, 0),
+			&mut PriceCache::default(),
+		);
+		assert!(AutomationPrice::get_task_queue().is_empty());
+
+		assert_ok!(AutomationPrice::update_asset_prices(
+			RuntimeOrigin::signed(creator.clone()),
+			vec![PriceUpdate {
+				chain: chain1.to_vec(),
+				exchange: exchange1.to_vec(),
+				asset1: asset1.to_vec(),
+				asset2: asset2.to_vec(),
+				price: 500,
+				submitted_at: START_BLOCK_TIME as u128,
+				round: 2,
+			}],
+		));
+		AutomationPrice::shift_tasks(
+			Weight::from_parts(1_000_000_000, 0),
+			&mut PriceCache::default(),
+		);
+		assert_eq!(AutomationPrice::get_task_queue(), vec![(creator, task_id)]);
+		assert_eq!(AutomationPrice::get_unsorted_tasks(&pair_key), None);
+	})
+}
+
+// A TWAP task matches once the average over its window crosses the threshold, even though the
+// price itself no longer moves by then
+#[test]
+fn test_shift_tasks_checks_twap_tasks_while_the_price_stays() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		let destination = MultiLocation::new(1, X1(Parachain(1000)));
+
+		// The price of pair1 starts at 1000
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+
+		// Five minutes later it jumps to 2000
+		Timestamp::set_timestamp(START_BLOCK_TIME + 300_000);
+		assert_ok!(AutomationPrice::update_asset_prices(
+			RuntimeOrigin::signed(creator.clone()),
+			vec![PriceUpdate {
+				chain: chain1.to_vec(),
+				exchange: exchange1.to_vec(),
+				asset1: asset1.to_vec(),
+				asset2: asset2.to_vec(),
+				price: 2000,
+				submitted_at: START_BLOCK_TIME as u128,
+				round: 2,
+			}],
+		));
+
+		get_xcmp_funds(creator.clone());
+		let schedule = |trigger_params: Vec<u128>| {
+			AutomationPrice::schedule_xcmp_task(
+				RuntimeOrigin::signed(creator.clone()),
+				chain1.to_vec(),
+				exchange1.to_vec(),
+				asset1.to_vec(),
+				asset2.to_vec(),
+				START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
+				"twap_gt".as_bytes().to_vec(),
+				trigger_params,
+				Box::new(destination.into()),
+				Box::new(NATIVE_LOCATION.into()),
+				Box::new(AssetPayment {
+					asset_location: MultiLocation::new(0, Here).into(),
+					amount: MOCK_XCMP_FEE,
+				}),
+				vec![2, 4, 5],
+				Weight::from_parts(100_000, 0),
+				Weight::from_parts(200_000, 0),
+				None,
+			)
+		};
+		assert_noop!(schedule(vec![1400, 0]), Error::<Test>::InvalidTriggerParams);
+		// Ten minute TWAP above 1400
+		assert_ok!(schedule(vec![1400, 600]));
+		let task_id = *get_task_ids_from_events().last().unwrap();
+
+		// Over the last ten minutes the price was 1000
+		AutomationPrice::shift_tasks(
+			Weight::from_parts(1_000_000_000, 0),
+			&mut PriceCache::default(),
+		);
+		assert!(AutomationPrice::get_task_queue().is_empty());
+
+		// Five minutes at 1000 and five at 2000 average 1500
+		Timestamp::set_timestamp(START_BLOCK_TIME + 600_000);
+		AutomationPrice::shift_tasks(
+			Weight::from_parts(1_000_000_000, 0),
+			&mut PriceCache::default(),
+		);
+		assert_eq!(AutomationPrice::get_task_queue(), vec![(creator, task_id)]);
+	})
+}
+
+// A shift that runs out of weight is resumed in the next block from the asset pair it stopped at
+#[test]
+fn test_shift_tasks_resumes_from_the_asset_pair_it_stopped_at() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+
+		// Each of the three asset pairs has a `gt` task that matches its price
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+		let pair_keys = vec![
+			(chain1.to_vec(), exchange1.to_vec(), (asset1.to_vec(), asset2.to_vec())),
+			(chain2.to_vec(), exchange1.to_vec(), (asset2.to_vec(), asset3.to_vec())),
+			(chain2.to_vec(), exchange1.to_vec(), (asset1.to_vec(), asset3.to_vec())),
+		];
+		for (i, pair_key) in pair_keys.iter().enumerate() {
+			let entry = (creator.clone(), task_id_of(&format!("task-{}", i)));
+			let tasks = BTreeMap::from([(1_u128, vec![entry])]);
+			put_sorted_tasks_above(pair_key, tasks);
+		}
+
+		// Without weight, no asset pair is checked
+		AutomationPrice::shift_tasks(Weight::zero(), &mut PriceCache::default());
+		assert!(AutomationPrice::get_task_queue().is_empty());
+		assert_eq!(AutomationPrice::get_shift_cursor(), None);
+
+		// The previous block stopped after the first asset pair of the index
+		let keys: Vec<_> = crate::SortedBucketsAbove::<Test>::iter_keys().collect();
+		crate::ShiftCursor::<Test>::put((ShiftIndex::SortedAbove, keys[0].clone()));
+		AutomationPrice::shift_tasks(
+			Weight::from_parts(1_000_000_000, 0),
+			&mut PriceCache::default(),
+		);
+		assert_eq!(AutomationPrice::get_task_queue().len(), 2);
+		assert!(AutomationPrice::get_sorted_tasks_above(&keys[0]).is_some());
+		assert_eq!(AutomationPrice::get_shift_cursor(), None);
+		// The prices were not checked for every asset pair in the same block
+		assert_eq!(LastShiftedPrice::<Test>::get(&keys[1]), None);
+
+		// The next block starts over from the first asset pair
+		AutomationPrice::shift_tasks(
+			Weight::from_parts(1_000_000_000, 0),
+			&mut PriceCache::default(),
+		);
+		assert_eq!(AutomationPrice::get_task_queue().len(), 3);
+		assert!(AutomationPrice::get_sorted_tasks_above(&keys[0]).is_none());
+		assert!(LastShiftedPrice::<Test>::get(&keys[0]).is_some());
+	})
+}
+
+// A pct_move task keeps the price it was scheduled at as its baseline, and matches once the price
+// moved further than its basis points from it
+#[test]
+fn test_pct_move_task_matches_a_move_from_its_baseline() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		let destination = MultiLocation::new(1, X1(Parachain(1000)));
+
+		// The price of pair1 starts at 1000
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+
+		get_xcmp_funds(creator.clone());
+		assert_ok!(AutomationPrice::schedule_xcmp_task(
+			RuntimeOrigin::signed(creator.clone()),
+			chain1.to_vec(),
+			exchange1.to_vec(),
+			asset1.to_vec(),
+			asset2.to_vec(),
+			START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
+			"pct_move".as_bytes().to_vec(),
+			vec![500],
+			Box::new(destination.into()),
+			Box::new(NATIVE_LOCATION.into()),
+			Box::new(AssetPayment {
+				asset_location: MultiLocation::new(0, Here).into(),
+				amount: MOCK_XCMP_FEE,
+			}),
+			vec![2, 4, 5],
+			Weight::from_parts(100_000, 0),
+			Weight::from_parts(200_000, 0),
+			None,
+		));
+		let task_id = *get_task_ids_from_events().last().unwrap();
+		let task = AutomationPrice::get_task(&creator, &task_id).expect("task not scheduled");
+		assert_eq!(task.trigger_params, vec![500, 1000]);
+
+		let update_price = |price: u128, round: u128| {
+			assert_ok!(AutomationPrice::update_asset_prices(
+				RuntimeOrigin::signed(creator.clone()),
+				vec![PriceUpdate {
+					chain: chain1.to_vec(),
+					exchange: exchange1.to_vec(),
+					asset1: asset1.to_vec(),
+					asset2: asset2.to_vec(),
+					price,
+					submitted_at: START_BLOCK_TIME as u128,
+					round,
+				}],
+			));
+			AutomationPrice::shift_tasks(
+				Weight::from_parts(1_000_000_000, 0),
+				&mut PriceCache::default(),
+			);
+		};
+
+		// Within 5% of the baseline either way
+		update_price(1040, 2);
+		update_price(960, 3);
+		assert!(AutomationPrice::get_task_queue().is_empty());
+
+		update_price(940, 4);
+		assert_eq!(AutomationPrice::get_task_queue(), vec![(creator, task_id)]);
+	})
+}
+
+#[test]
+fn test_shift_tasks_queues_asset_pairs_in_turn() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		let pair1_key = (chain1.to_vec(), exchange1.to_vec(), (asset1.to_vec(), asset2.to_vec()));
+		let pair3_key = (chain2.to_vec(), exchange1.to_vec(), (asset1.to_vec(), asset3.to_vec()));
+		let entry = |task_id: &str| (creator.clone(), task_id_of(task_id));
+
+		// The prices of pair1 and pair3 are 1000 and 10_000, above every target below
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+		MaxShiftedTasksPerAssetPair::set(2);
+
+		put_sorted_tasks_above(
+			&pair1_key,
+			BTreeMap::from([
+				(1, vec![entry("a1")]),
+				(2, vec![entry("a2")]),
+				(3, vec![entry("a3")]),
+			]),
+		);
+		put_sorted_tasks_above(
+			&pair3_key,
+			BTreeMap::from([(1, vec![entry("b1")]), (2, vec![entry("b2")])]),
+		);
+
+		AutomationPrice::shift_tasks(
+			Weight::from_parts(1_000_000_000, 0),
+			&mut PriceCache::default(),
+		);
+		// pair3 comes first as its chain sorts first
+		assert_eq!(
+			AutomationPrice::get_task_queue(),
+			vec![entry("b1"), entry("a1"), entry("b2"), entry("a2")]
+		);
+		assert_eq!(
+			AutomationPrice::get_sorted_tasks_above(&pair1_key),
+			Some(BTreeMap::from([(3, vec![entry("a3")])]))
+		);
+		// pair1 is checked again in the next block even though its price does not move
+		assert_eq!(LastShiftedPrice::<Test>::get(&pair1_key), None);
+		assert_eq!(LastShiftedPrice::<Test>::get(&pair3_key), Some(10_000));
+
+		AutomationPrice::shift_tasks(
+			Weight::from_parts(1_000_000_000, 0),
+			&mut PriceCache::default(),
+		);
+		assert_eq!(AutomationPrice::get_task_queue().last(), Some(&entry("a3")));
+		assert!(AutomationPrice::get_sorted_tasks_above(&pair1_key).is_none());
+		assert_eq!(LastShiftedPrice::<Test>::get(&pair1_key), Some(1000));
+	})
+}
+
+// Tasks are sorted into price buckets, and a shift only touches the buckets the price crossed
+#[test]
+fn test_shift_tasks_drains_the_price_buckets_the_price_crossed() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		let pair_key = (chain1.to_vec(), exchange1.to_vec(), (asset1.to_vec(), asset2.to_vec()));
+		let entry = |task_id: &str| (creator.clone(), task_id_of(task_id));
+
+		// The price of pair1 is 1000, in the second bucket of the mock
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+		put_sorted_tasks_above(
+			&pair_key,
+			BTreeMap::from([
+				(500, vec![entry("a1")]),
+				(999, vec![entry("a2")]),
+				(1500, vec![entry("a3")]),
+				(2500, vec![entry("a4")]),
+			]),
+		);
+		assert_eq!(
+			AutomationPrice::get_sorted_buckets_above(&pair_key),
+			Some(BTreeSet::from([0, 1, 2]))
+		);
+		assert_eq!(
+			crate::SortedTasksAbove::<Test>::get((&pair_key.0, &pair_key.1, &pair_key.2, 0)),
+			Some(BTreeMap::from([(500, vec![entry("a1")]), (999, vec![entry("a2")])]))
+		);
+
+		AutomationPrice::shift_tasks(
+			Weight::from_parts(1_000_000_000, 0),
+			&mut PriceCache::default(),
+		);
+		assert_eq!(AutomationPrice::get_task_queue(), vec![entry("a1"), entry("a2")]);
+		// The emptied bucket is dropped and the others are left as they were
+		assert_eq!(
+			AutomationPrice::get_sorted_buckets_above(&pair_key),
+			Some(BTreeSet::from([1, 2]))
+		);
+		assert_eq!(
+			crate::SortedTasksAbove::<Test>::get((&pair_key.0, &pair_key.1, &pair_key.2, 0)),
+			None
+		);
+		assert_eq!(
+			AutomationPrice::get_sorted_tasks_above(&pair_key),
+			Some(BTreeMap::from([(1500, vec![entry("a3")]), (2500, vec![entry("a4")])]))
+		);
+	})
+}
+
+#[test]
+fn test_split_off_triggered_splits_at_the_current_price() {
+	let price = crate::PriceData { round: 1, updated_at: 0, value: 100 };
+	let index = BTreeMap::from([(0, 'a'), (50, 'b'), (100, 'c'), (150, 'd'), (u128::MAX, 'e')]);
+
+	let mut above = index.clone();
+	let triggered = crate::split_off_triggered(TriggerFunction::Gt, &mut above, &price);
+	assert_eq!(triggered, BTreeMap::from([(50, 'b')]));
+	assert_eq!(above, BTreeMap::from([(0, 'a'), (100, 'c'), (150, 'd'), (u128::MAX, 'e')]));
+
+	let mut below = index;
+	let triggered = crate::split_off_triggered(TriggerFunction::Lt, &mut below, &price);
+	assert_eq!(triggered, BTreeMap::from([(100, 'c'), (150, 'd')]));
+	assert_eq!(below, BTreeMap::from([(0, 'a'), (50, 'b'), (u128::MAX, 'e')]));
+}
+
+#[test]
+fn test_shift_tasks_shuffles_the_tasks_sharing_a_price() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		let pair_key = (chain1.to_vec(), exchange1.to_vec(), (asset1.to_vec(), asset2.to_vec()));
+		let entries: TaskIdList<Test> =
+			(0..16).map(|i| (creator.clone(), task_id_of(&format!("1-0-{}", i)))).collect();
+
+		// The price of pair1 is 1000, above the target of every task
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+
+		let mut queues = vec![];
+		for seed in [1, 2] {
+			RandomSeed::set(seed);
+			crate::TaskQueue::<Test>::kill();
+			LastShiftedPrice::<Test>::remove(&pair_key);
+			put_sorted_tasks_above(&pair_key, BTreeMap::from([(1, entries.clone())]));
+
+			AutomationPrice::shift_tasks(
+				Weight::from_parts(1_000_000_000, 0),
+				&mut PriceCache::default(),
+			);
+			let queue = AutomationPrice::get_task_queue();
+			let mut sorted_queue = queue.clone();
+			sorted_queue.sort();
+			let mut sorted_entries = entries.clone();
+			sorted_entries.sort();
+			assert_eq!(sorted_queue, sorted_entries);
+			queues.push(queue);
+		}
+		assert_ne!(queues[0], queues[1]);
+	})
+}
+
+// The weight trigger_tasks claims covers the benchmarked cost of shifting, draining the queue
+// and running the triggered task, and stays within the weight it was given
+#[test]
+fn test_trigger_tasks_claims_the_benchmarked_weight() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		let destination = MultiLocation::new(1, X1(Parachain(1000)));
+		let pair_key = (chain1.to_vec(), exchange1.to_vec(), (asset1.to_vec(), asset2.to_vec()));
+
+		// The price of pair1 starts at 1000
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+
+		for target_price in [1500_u128, 2500_u128] {
+			get_xcmp_funds(creator.clone());
+			assert_ok!(AutomationPrice::schedule_xcmp_task(
+				RuntimeOrigin::signed(creator.clone()),
+				chain1.to_vec(),
+				exchange1.to_vec(),
+				asset1.to_vec(),
+				asset2.to_vec(),
+				START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
+				"gt".as_bytes().to_vec(),
+				vec!(target_price),
+				Box::new(destination.into()),
+				Box::new(NATIVE_LOCATION.into()),
+				Box::new(AssetPayment {
+					asset_location: MultiLocation::new(0, Here).into(),
+					amount: MOCK_XCMP_FEE
+				}),
+				vec![2, 4, 5],
+				Weight::from_parts(100_000, 0),
+				Weight::from_parts(200_000, 0),
+				None
+			));
+		}
+
+		assert_ok!(AutomationPrice::update_asset_prices(
+			RuntimeOrigin::signed(creator.clone()),
+			vec![PriceUpdate {
+				chain: chain1.to_vec(),
+				exchange: exchange1.to_vec(),
+				asset1: asset1.to_vec(),
+				asset2: asset2.to_vec(),
+				price: 2000,
+				submitted_at: START_BLOCK_TIME as u128,
+				round: 2,
+			}],
+		));
+
+		let max_weight = Weight::from_parts(1_000_000_000, 0);
+		let weight_used = AutomationPrice::trigger_tasks(max_weight);
+
+		assert_eq!(
+			weight_used,
+			MockWeight::<Test>::shift_tasks(1) +
+				MockWeight::<Test>::update_task_queue(1) +
+				MockWeight::<Test>::run_xcmp_task() +
+				MockWeight::<Test>::remove_task()
+		);
+		assert!(weight_used.all_lte(max_weight));
+		assert!(AutomationPrice::get_task_queue().is_empty());
+		assert_eq!(
+			AutomationPrice::get_sorted_tasks_above(&pair_key).map_or(0, |tasks| tasks.len()),
+			1
+		);
+	})
+}
+
+// A recurring task is charged again and waits out its cooldown after each run, until it ran as
+// many times as its schedule allows
+#[test]
+fn test_recurring_task_is_rearmed_after_its_cooldown() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		let now = (START_BLOCK_TIME / 1000) as u128;
+		let max_weight = Weight::from_parts(10_000_000_000, 0);
+
+		// The price of pair1 starts at 1000, above the target of the task
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+		Balances::force_set_balance(RawOrigin::Root.into(), creator.clone(), 1_000_000_000_000_000)
+			.unwrap();
+		let task = Task::<Test> {
+			owner_id: creator.clone(),
+			task_id: task_id_of("123-0-1"),
+			chain: chain1.to_vec(),
+			exchange: exchange1.to_vec(),
+			asset_pair: (asset1.to_vec(), asset2.to_vec()),
+			expired_at: START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
+			trigger_function: "gt".as_bytes().to_vec(),
+			trigger_params: vec![500],
+			action: Action::XCMP {
+				destination: MultiLocation::new(1, X1(Parachain(PARA_ID))),
+				schedule_fee: NATIVE_LOCATION,
+				execution_fee: AssetPayment {
+					asset_location: MultiLocation::new(0, Here).into(),
+					amount: MOCK_XCMP_FEE,
+				},
+				encoded_call: vec![1, 2, 3],
+				encoded_call_weight: Weight::from_parts(100_000, 0),
+				overall_weight: Weight::from_parts(200_000, 0),
+				schedule_as: None,
+				instruction_sequence: InstructionSequence::PayThroughSovereignAccount,
+			},
+		};
+		assert_ok!(AutomationPrice::validate_and_schedule_task(task.clone()));
+
+		let schedule = |cooldown_seconds: u64, max_executions: Option<u32>| {
+			AutomationPrice::set_task_schedule(
+				RuntimeOrigin::signed(creator.clone()),
+				task.task_id,
+				Schedule::Recurring { cooldown_seconds, max_executions },
+			)
+		};
+		assert_noop!(schedule(0, None), Error::<Test>::InvalidSchedule);
+		assert_noop!(schedule(600, Some(0)), Error::<Test>::InvalidSchedule);
+		assert_ok!(schedule(600, Some(2)));
+
+		// The first run charges the fees of the second one
+		let balance_before = Balances::free_balance(&creator);
+		AutomationPrice::trigger_tasks(max_weight);
+		assert!(Balances::free_balance(&creator) < balance_before);
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskRearmed {
+			owner_id: creator.clone(),
+			task_id: task.task_id,
+			armed_at: now + 600,
+		}));
+		assert!(AutomationPrice::get_task(&creator, &task.task_id).is_some());
+		assert_eq!(
+			AutomationPrice::get_task_recurrence(&creator, &task.task_id),
+			Some(Recurrence { cooldown_seconds: 600, remaining_executions: Some(1) })
+		);
+
+		// Nothing runs during the cooldown
+		System::reset_events();
+		AutomationPrice::trigger_tasks(max_weight);
+		assert_no_event(RuntimeEvent::AutomationPrice(crate::Event::TaskExecuted {
+			owner_id: creator.clone(),
+			task_id: task.task_id,
+			action_outcome: ActionOutcome::XcmSent {
+				destination: MultiLocation::new(1, X1(Parachain(PARA_ID))),
+			},
+		}));
+
+		// The second run is the last one
+		Timestamp::set_timestamp(START_BLOCK_TIME + 600_000);
+		AutomationPrice::trigger_tasks(max_weight);
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskCompleted {
+			owner_id: creator.clone(),
+			task_id: task.task_id,
+		}));
+		assert!(AutomationPrice::get_task(&creator, &task.task_id).is_none());
+		assert!(AutomationPrice::get_task_recurrence(&creator, &task.task_id).is_none());
+		assert!(AutomationPrice::get_cooling_down_tasks().is_empty());
+	})
+}
+
+// The test utilities drive a task from a price push to its execution
+#[test]
+fn test_test_utils_run_a_task_once_its_price_is_pushed() {
+	let creator = AccountId32::new(ALICE);
+	test_utils::ExtBuilder::<Test>::default()
+		.with_asset(
+			chain1.to_vec(),
+			exchange1.to_vec(),
+			asset1.to_vec(),
+			asset2.to_vec(),
+			10,
+			vec![creator.clone()],
+		)
+		.with_block_time(START_BLOCK_TIME)
+		.build()
+		.execute_with(|| {
+			let push_price = |price| {
+				test_utils::push_price::<Test>(
+					creator.clone(),
+					chain1.to_vec(),
+					exchange1.to_vec(),
+					asset1.to_vec(),
+					asset2.to_vec(),
+					price,
+				)
+			};
+			assert_ok!(push_price(1000));
+
+			get_xcmp_funds(creator.clone());
+			assert_ok!(AutomationPrice::schedule_xcmp_task(
+				RuntimeOrigin::signed(creator.clone()),
+				chain1.to_vec(),
+				exchange1.to_vec(),
+				asset1.to_vec(),
+				asset2.to_vec(),
+				START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
+				"gt".as_bytes().to_vec(),
+				vec!(1500),
+				Box::new(MultiLocation::new(1, X1(Parachain(1000))).into()),
+				Box::new(NATIVE_LOCATION.into()),
+				Box::new(AssetPayment {
+					asset_location: MultiLocation::new(0, Here).into(),
+					amount: MOCK_XCMP_FEE
+				}),
+				vec![2, 4, 5],
+				Weight::from_parts(100_000, 0),
+				Weight::from_parts(200_000, 0),
+				None
+			));
+			let task_id = *get_task_ids_from_events().last().unwrap();
+
+			test_utils::run_to_block::<Test>(2);
+			assert_no_event(RuntimeEvent::AutomationPrice(crate::Event::TaskExecuted {
+				owner_id: creator.clone(),
+				task_id,
+				action_outcome: ActionOutcome::XcmSent {
+					destination: MultiLocation::new(1, X1(Parachain(1000))),
+				},
+			}));
+
+			assert_ok!(push_price(2000));
+			test_utils::run_to_block::<Test>(3);
+			assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskExecuted {
+				owner_id: creator.clone(),
+				task_id,
+				action_outcome: ActionOutcome::XcmSent {
+					destination: MultiLocation::new(1, X1(Parachain(1000))),
+				},
+			}));
+		});
+}
+
+// Replaying price rounds on a snapshot yields the trace of what triggered and ran in each round
+#[test]
+fn test_simulation_replays_price_rounds_into_a_trace() {
+	let creator = AccountId32::new(ALICE);
+	let mut snapshot = test_utils::ExtBuilder::<Test>::default()
+		.with_asset(
+			chain1.to_vec(),
+			exchange1.to_vec(),
+			asset1.to_vec(),
+			asset2.to_vec(),
+			10,
+			vec![creator.clone()],
+		)
+		.with_block_time(START_BLOCK_TIME)
+		.build();
+	let task_id = snapshot.execute_with(|| {
+		assert_ok!(test_utils::push_price::<Test>(
+			creator.clone(),
+			chain1.to_vec(),
+			exchange1.to_vec(),
+			asset1.to_vec(),
+			asset2.to_vec(),
+			1000,
+		));
+		get_xcmp_funds(creator.clone());
+		assert_ok!(AutomationPrice::schedule_xcmp_task(
+			RuntimeOrigin::signed(creator.clone()),
+			chain1.to_vec(),
+			exchange1.to_vec(),
+			asset1.to_vec(),
+			asset2.to_vec(),
+			START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
+			"gt".as_bytes().to_vec(),
+			vec!(1500),
+			Box::new(MultiLocation::new(1, X1(Parachain(1000))).into()),
+			Box::new(NATIVE_LOCATION.into()),
+			Box::new(AssetPayment {
+				asset_location: MultiLocation::new(0, Here).into(),
+				amount: MOCK_XCMP_FEE
+			}),
+			vec![2, 4, 5],
+			Weight::from_parts(100_000, 0),
+			Weight::from_parts(200_000, 0),
+			None
+		));
+		*get_task_ids_from_events().last().unwrap()
+	});
+
+	let price = |value| simulation::SimulatedPrice {
+		chain: chain1.to_vec(),
+		exchange: exchange1.to_vec(),
+		asset1: asset1.to_vec(),
+		asset2: asset2.to_vec(),
+		value,
+	};
+	let trace =
+		simulation::replay::<Test>(snapshot, 12_000, vec![vec![price(1200)], vec![price(1600)]]);
+
+	assert_eq!(trace.len(), 2);
+	assert_eq!(trace[0].block_number, 2);
+	assert_eq!(trace[0].timestamp, START_BLOCK_TIME + 12_000);
+	assert!(trace[0].events.is_empty());
+	assert_eq!(trace[1].block_number, 3);
+	assert!(trace[1].events.contains(&crate::Event::TaskTriggered {
+		owner_id: creator.clone(),
+		task_id,
+		asset_pair: (asset1.to_vec(), asset2.to_vec()),
+		trigger_price: 1600,
+		oracle_round: 3,
+		condition: crate::TaskCondition::TargetPriceMatched {
+			chain: chain1.to_vec(),
+			exchange: exchange1.to_vec(),
+			asset_pair: (asset1.to_vec(), asset2.to_vec()),
+			price: 1600,
+		},
+	}));
+	assert!(trace[1].events.contains(&crate::Event::TaskExecuted {
+		owner_id: creator,
+		task_id,
+		action_outcome: ActionOutcome::XcmSent {
+			destination: MultiLocation::new(1, X1(Parachain(1000))),
+		},
+	}));
+}
+
+#[test]
+fn test_emit_event_when_execute_tasks() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		let para_id: u32 = 1000;
+
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+
+		let destination = MultiLocation::new(1, X1(Parachain(para_id)));
+		let schedule_fee = MultiLocation::default();
+		let execution_fee = AssetPayment {
+			asset_location: MultiLocation::new(1, X1(Parachain(para_id))).into(),
+			amount: MOCK_XCMP_FEE,
+		};
+		let encoded_call_weight = Weight::from_ref_time(100_000);
+		let overall_weight = Weight::from_ref_time(200_000);
+
+		get_xcmp_funds(creator.clone());
+		let task = Task::<Test> {
+			owner_id: creator,
+			task_id: task_id_of("123-0-1"),
+			chain: chain1.to_vec(),
+			exchange: exchange1.to_vec(),
+			asset_pair: (asset1.to_vec(), asset2.to_vec()),
+			expired_at: (START_BLOCK_TIME + 10000) as u128,
+			trigger_function: "gt".as_bytes().to_vec(),
+			trigger_params: vec![123],
+			action: Action::XCMP {
+				destination,
+				schedule_fee,
+				execution_fee,
+				encoded_call: vec![1, 2, 3],
+				encoded_call_weight,
+				overall_weight,
+				schedule_as: None,
+				instruction_sequence: InstructionSequence::PayThroughRemoteDerivativeAccount,
+			},
+		};
+
+		AutomationPrice::validate_and_schedule_task(task.clone());
+
+		AutomationPrice::run_tasks(
+			vec![(task.owner_id.clone(), task.task_id)],
+			100_000_000_000.into(),
+			&mut PriceCache::default(),
+		);
+
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskTriggered {
+			owner_id: task.owner_id.clone(),
+			task_id: task.task_id,
+			asset_pair: task.asset_pair.clone(),
+			trigger_price: 1000_u128,
+			oracle_round: 1,
+			condition: crate::TaskCondition::TargetPriceMatched {
+				chain: task.chain.clone(),
+				exchange: task.exchange.clone(),
+				asset_pair: task.asset_pair.clone(),
+				price: 1000_u128,
+			},
+		}));
+
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskExecuted {
+			owner_id: task.owner_id.clone(),
+			task_id: task.task_id,
+			action_outcome: ActionOutcome::XcmSent { destination },
+		}));
+	})
+}
+
+// The action of an XCMP task is sent through the XCMP transactor with the call, the fee and the
+// weights stored in the task, on behalf of the account it is scheduled as
+#[test]
+fn test_run_tasks_transacts_xcmp_tasks_through_the_xcmp_transactor() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		let delegator = AccountId32::new(DELEGATOR_ACCOUNT);
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+
+		let destination = MultiLocation::new(1, X1(Parachain(PARA_ID)));
+		let mut task = build_update_trigger_task(&creator);
+		task.action = Action::XCMP {
+			destination,
+			schedule_fee: MultiLocation::default(),
+			execution_fee: AssetPayment {
+				asset_location: MOONBASE_ASSET_LOCATION.into(),
+				amount: MOCK_XCMP_FEE,
+			},
+			encoded_call: vec![1, 2, 3],
+			encoded_call_weight: Weight::from_parts(100_000, 0),
+			overall_weight: Weight::from_parts(200_000, 0),
+			schedule_as: Some(delegator.clone()),
+			instruction_sequence: InstructionSequence::PayThroughRemoteDerivativeAccount,
+		};
+		crate::Tasks::<Test>::insert(&creator, &task.task_id, &task);
+
+		AutomationPrice::run_tasks(
+			vec![(creator.clone(), task.task_id)],
+			100_000_000_000.into(),
+			&mut PriceCache::default(),
+		);
+		assert_eq!(
+			TransactedXcms::get(),
+			vec![TransactedXcm {
+				destination,
+				fee_location: MOONBASE_ASSET_LOCATION,
+				fee: MOCK_XCMP_FEE,
+				caller: delegator,
+				encoded_call: vec![1, 2, 3],
+				encoded_call_weight: Weight::from_parts(100_000, 0),
+				overall_weight: Weight::from_parts(200_000, 0),
+				flow: InstructionSequence::PayThroughRemoteDerivativeAccount,
+			}]
+		);
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskExecuted {
+			owner_id: creator.clone(),
+			task_id: task.task_id,
+			action_outcome: ActionOutcome::XcmSent { destination },
+		}));
+
+		// A transact failing for another reason than its delivery fails the task at once
+		TransactedXcms::set(vec![]);
+		XcmpTransactError::set(Some(sp_runtime::DispatchError::Other("bad call")));
+		let task = Task::<Test> { task_id: task_id_of("failing"), ..task };
+		crate::Tasks::<Test>::insert(&creator, &task.task_id, &task);
+		AutomationPrice::run_tasks(
+			vec![(creator.clone(), task.task_id)],
+			100_000_000_000.into(),
+			&mut PriceCache::default(),
+		);
+		assert_eq!(TransactedXcms::get().len(), 1);
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskExecutionFailed {
+			owner_id: creator,
+			task_id: task.task_id,
+			error: sp_runtime::DispatchError::Other("bad call"),
+		}));
+	})
+}
+
+// An XCMP task paying its execution fee up front, so that it is refunded when the task is given up
+fn build_xcmp_retry_task(creator: &AccountId32) -> Task<Test> {
+	let task = build_update_trigger_task(creator);
+	Task::<Test> {
+		action: Action::XCMP {
+			destination: MultiLocation::new(1, X1(Parachain(PARA_ID))),
+			schedule_fee: NATIVE_LOCATION,
+			execution_fee: AssetPayment {
+				asset_location: MultiLocation::new(0, Here).into(),
+				amount: MOCK_XCMP_FEE,
+			},
+			encoded_call: vec![1, 2, 3],
+			encoded_call_weight: Weight::from_parts(100_000, 0),
+			overall_weight: Weight::from_parts(200_000, 0),
+			schedule_as: None,
+			instruction_sequence: InstructionSequence::PayThroughSovereignAccount,
+		},
+		..task
+	}
+}
+
+// A message that cannot be sent is sent again with a delay doubling every attempt, and the task is
+// given up after `MaxXcmpSendAttempts` attempts with its execution fee refunded
+#[test]
+fn test_xcmp_task_is_given_up_after_its_send_attempts() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+		let task = build_xcmp_retry_task(&creator);
+		crate::Tasks::<Test>::insert(&creator, &task.task_id, &task);
+		XcmpTransactError::set(Some(sp_runtime::DispatchError::Unavailable));
+
+		AutomationPrice::run_tasks(
+			vec![(creator.clone(), task.task_id)],
+			100_000_000_000.into(),
+			&mut PriceCache::default(),
+		);
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskXcmpSendDeferred {
+			owner_id: creator.clone(),
+			task_id: task.task_id,
+			attempts: 1,
+			retry_at: 3,
+			error: sp_runtime::DispatchError::Unavailable,
+		}));
+		assert_eq!(
+			AutomationPrice::get_xcmp_retries(3),
+			vec![XcmpRetry { owner_id: creator.clone(), task_id: task.task_id, attempts: 1 }]
+		);
+		assert_eq!(AutomationPrice::get_xcmp_retry_count(), 1);
+		assert!(AutomationPrice::get_task(&creator, &task.task_id).is_some());
+
+		System::set_block_number(3);
+		AutomationPrice::trigger_tasks(Weight::from_parts(10_000_000_000, 0));
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskXcmpSendDeferred {
+			owner_id: creator.clone(),
+			task_id: task.task_id,
+			attempts: 2,
+			retry_at: 7,
+			error: sp_runtime::DispatchError::Unavailable,
+		}));
+		assert!(AutomationPrice::get_xcmp_retries(3).is_empty());
+
+		System::set_block_number(7);
+		AutomationPrice::trigger_tasks(Weight::from_parts(10_000_000_000, 0));
+		assert_eq!(TransactedXcms::get().len(), 3);
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskXcmpSendFailed {
+			owner_id: creator.clone(),
+			task_id: task.task_id,
+			attempts: 3,
+			error: sp_runtime::DispatchError::Unavailable,
+		}));
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::ExecutionFeeRefunded {
+			owner_id: creator.clone(),
+			task_id: task.task_id,
+			amount: MOCK_XCMP_FEE,
+		}));
+		assert!(AutomationPrice::get_task(&creator, &task.task_id).is_none());
+		assert!(AutomationPrice::get_xcmp_retries(7).is_empty());
+		assert_eq!(AutomationPrice::get_xcmp_retry_count(), 0);
+	})
+}
+
+// A task whose message is sent on a retry runs as if it was sent the first time
+#[test]
+fn test_xcmp_task_completes_once_its_message_is_sent_again() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+		let task = build_xcmp_retry_task(&creator);
+		crate::Tasks::<Test>::insert(&creator, &task.task_id, &task);
+		XcmpTransactError::set(Some(sp_runtime::DispatchError::Unavailable));
+
+		AutomationPrice::run_tasks(
+			vec![(creator.clone(), task.task_id)],
+			100_000_000_000.into(),
+			&mut PriceCache::default(),
+		);
+		assert_eq!(AutomationPrice::get_xcmp_retry_count(), 1);
+
+		XcmpTransactError::set(None);
+		System::set_block_number(3);
+		AutomationPrice::trigger_tasks(Weight::from_parts(10_000_000_000, 0));
+		assert_eq!(TransactedXcms::get().len(), 2);
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskExecuted {
+			owner_id: creator.clone(),
+			task_id: task.task_id,
+			action_outcome: ActionOutcome::XcmSent {
+				destination: MultiLocation::new(1, X1(Parachain(PARA_ID))),
+			},
+		}));
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskCompleted {
+			owner_id: creator.clone(),
+			task_id: task.task_id,
+		}));
+		assert!(AutomationPrice::get_task(&creator, &task.task_id).is_none());
+		assert_eq!(AutomationPrice::get_xcmp_retry_count(), 0);
+	})
+}
+
+// A task whose message cannot be sent while the retry queue is full is given up at once
+#[test]
+fn test_xcmp_task_is_given_up_when_the_retry_queue_is_full() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		MaxXcmpRetries::set(0);
+		let creator = AccountId32::new(ALICE);
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+		let task = build_xcmp_retry_task(&creator);
+		crate::Tasks::<Test>::insert(&creator, &task.task_id, &task);
+		XcmpTransactError::set(Some(sp_runtime::DispatchError::Unavailable));
+
+		AutomationPrice::run_tasks(
+			vec![(creator.clone(), task.task_id)],
+			100_000_000_000.into(),
+			&mut PriceCache::default(),
+		);
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskXcmpSendFailed {
+			owner_id: creator.clone(),
+			task_id: task.task_id,
+			attempts: 1,
+			error: sp_runtime::DispatchError::Unavailable,
+		}));
+		assert!(AutomationPrice::get_task(&creator, &task.task_id).is_none());
+		assert!(AutomationPrice::get_xcmp_retries(3).is_empty());
+	})
+}
+
+#[test]
+fn test_decrease_task_count_when_execute_tasks() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator1 = AccountId32::new(ALICE);
+		let creator2 = AccountId32::new(BOB);
+		let para_id: u32 = 1000;
+
+		setup_assets_and_prices(&creator1, START_BLOCK_TIME as u128);
+
+		let destination = MultiLocation::new(1, X1(Parachain(para_id)));
+		let schedule_fee = MultiLocation::default();
+		let execution_fee = AssetPayment {
+			asset_location: MultiLocation::new(1, X1(Parachain(para_id))).into(),
+			amount: MOCK_XCMP_FEE,
+		};
+		let encoded_call_weight = Weight::from_ref_time(100_000);
+		let overall_weight = Weight::from_ref_time(200_000);
+
+		get_xcmp_funds(creator1.clone());
+		let task1 = Task::<Test> {
+			owner_id: creator1.clone(),
+			task_id: task_id_of("123-0-1"),
+			chain: chain1.to_vec(),
+			exchange: exchange1.to_vec(),
+			asset_pair: (asset1.to_vec(), asset2.to_vec()),
+			expired_at: (START_BLOCK_TIME + 10000) as u128,
+			trigger_function: "gt".as_bytes().to_vec(),
+			trigger_params: vec![123],
+			action: Action::XCMP {
+				destination,
+				schedule_fee,
+				execution_fee: execution_fee.clone(),
+				encoded_call: vec![1, 2, 3],
+				encoded_call_weight,
+				overall_weight,
+				schedule_as: None,
+				instruction_sequence: InstructionSequence::PayThroughRemoteDerivativeAccount,
+			},
+		};
+
+		get_xcmp_funds(creator2.clone());
+		let task2 = Task::<Test> {
+			owner_id: creator2.clone(),
+			task_id: task_id_of("123-1-1"),
+			chain: chain1.to_vec(),
+			exchange: exchange1.to_vec(),
+			asset_pair: (asset1.to_vec(), asset2.to_vec()),
+			expired_at: (START_BLOCK_TIME + 10000) as u128,
+			trigger_function: "gt".as_bytes().to_vec(),
+			trigger_params: vec![123],
+			action: Action::XCMP {
+				destination,
+				schedule_fee,
+				execution_fee,
+				encoded_call: vec![1, 2, 3],
+				encoded_call_weight,
+				overall_weight,
+				schedule_as: None,
+				instruction_sequence: InstructionSequence::PayThroughRemoteDerivativeAccount,
+			},
+		};
+
+		AutomationPrice::validate_and_schedule_task(task1.clone());
+		AutomationPrice::validate_and_schedule_task(task2);
+
+		assert_eq!(
+			2,
+			AutomationPrice::get_task_stat(StatType::TotalTasksOverall).map_or(0, |v| v),
+			"total task count is wrong"
+		);
+		assert_eq!(
+			1,
+			AutomationPrice::get_account_stat(creator1.clone(), StatType::TotalTasksPerAccount)
+				.map_or(0, |v| v),
+			"total task count is wrong"
+		);
+		assert_eq!(
+			1,
+			AutomationPrice::get_account_stat(creator2, StatType::TotalTasksPerAccount)
+				.map_or(0, |v| v),
+			"total task count is wrong"
+		);
+
+		AutomationPrice::run_tasks(
+			vec![(task1.owner_id.clone(), task1.task_id)],
+			100_000_000_000.into(),
+			&mut PriceCache::default(),
+		);
+
+		assert_eq!(
+			1,
+			AutomationPrice::get_task_stat(StatType::TotalTasksOverall).map_or(0, |v| v),
+			"total task count is wrong"
+		);
+		assert_eq!(
+			0,
+			AutomationPrice::get_account_stat(creator1, StatType::TotalTasksPerAccount)
+				.map_or(0, |v| v),
+			"total task count of creator1 is wrong"
+		);
+	})
+}
+
+// the tasks of an asset pair run together are settled as one batch: the counters and the
+// expiration index are updated for all of them, and a single event lists them with the price
+#[test]
+fn test_run_tasks_settles_the_tasks_of_an_asset_pair_together() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		let para_id: u32 = 1000;
+
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+		get_xcmp_funds(creator.clone());
+
+		let tasks: Vec<Task<Test>> = (0..2)
+			.map(|i| Task::<Test> {
+				owner_id: creator.clone(),
+				task_id: task_id_of(&format!("123-{}-1", i)),
+				chain: chain1.to_vec(),
+				exchange: exchange1.to_vec(),
+				asset_pair: (asset1.to_vec(), asset2.to_vec()),
+				expired_at: (START_BLOCK_TIME + 10000 + i) as u128,
+				trigger_function: "gt".as_bytes().to_vec(),
+				trigger_params: vec![123],
+				action: Action::XCMP {
+					destination: MultiLocation::new(1, X1(Parachain(para_id))),
+					schedule_fee: MultiLocation::default(),
+					execution_fee: AssetPayment {
+						asset_location: MultiLocation::new(1, X1(Parachain(para_id))).into(),
+						amount: MOCK_XCMP_FEE,
+					},
+					encoded_call: vec![1, 2, 3],
+					encoded_call_weight: Weight::from_ref_time(100_000),
+					overall_weight: Weight::from_ref_time(200_000),
+					schedule_as: None,
+					instruction_sequence: InstructionSequence::PayThroughRemoteDerivativeAccount,
+				},
+			})
+			.collect();
+		for task in tasks.iter() {
+			AutomationPrice::validate_and_schedule_task(task.clone());
+		}
+		let task_ids: Vec<(AccountId32, TaskId)> =
+			tasks.iter().map(|task| (task.owner_id.clone(), task.task_id)).collect();
+
+		AutomationPrice::run_tasks(
+			task_ids.clone(),
+			100_000_000_000.into(),
+			&mut PriceCache::default(),
+		);
+
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TasksSettled {
+			chain: chain1.to_vec(),
+			exchange: exchange1.to_vec(),
+			asset_pair: (asset1.to_vec(), asset2.to_vec()),
+			price: 1000_u128,
+			tasks: task_ids.clone(),
+		}));
+		for (owner_id, task_id) in task_ids {
+			assert!(AutomationPrice::get_task(owner_id, task_id).is_none());
+		}
+		assert!(AutomationPrice::get_sorted_tasks_by_expiration().is_empty());
+		assert_eq!(
+			0,
+			AutomationPrice::get_task_stat(StatType::TotalTasksOverall).map_or(0, |v| v),
+			"total task count is wrong"
+		);
+		assert_eq!(
+			0,
+			AutomationPrice::get_account_stat(creator, StatType::TotalTasksPerAccount)
+				.map_or(0, |v| v),
+			"total task count of creator is wrong"
+		);
+	})
+}
+
+// when running a task, if the task is already expired, the execution engine won't run the task,
+// instead an even TaskExpired is emiited
+#[test]
+fn test_expired_task_not_run() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		let para_id: u32 = 1000;
+
+		let destination = MultiLocation::new(1, X1(Parachain(para_id)));
+		let schedule_fee = MultiLocation::default();
+		let execution_fee = AssetPayment {
+			asset_location: MultiLocation::new(1, X1(Parachain(para_id))).into(),
+			amount: MOCK_XCMP_FEE,
+		};
+		let encoded_call_weight = Weight::from_parts(100_000, 0);
+		let overall_weight = Weight::from_parts(200_000, 0);
+
+		get_xcmp_funds(creator.clone());
+		let task = Task::<Test> {
+			owner_id: creator,
+			task_id: task_id_of("123-0-1"),
+			chain: chain1.to_vec(),
+			exchange: exchange1.to_vec(),
+			asset_pair: (asset1.to_vec(), asset2.to_vec()),
+			expired_at: START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
+			trigger_function: "gt".as_bytes().to_vec(),
+			trigger_params: vec![123],
+			action: Action::XCMP {
+				destination,
+				schedule_fee,
+				execution_fee,
+				encoded_call: vec![1, 2, 3],
+				encoded_call_weight,
+				overall_weight,
+				schedule_as: None,
+				instruction_sequence: InstructionSequence::PayThroughRemoteDerivativeAccount,
+			},
+		};
+
+		AutomationPrice::validate_and_schedule_task(task.clone());
+
+		// Moving the clock to simulate the task expiration
+		Timestamp::set_timestamp(
+			START_BLOCK_TIME.saturating_add(7_200_000_u64).try_into().unwrap(),
+		);
+		AutomationPrice::run_tasks(
+			vec![(task.owner_id.clone(), task.task_id)],
+			100_000_000_000.into(),
+			&mut PriceCache::default(),
+		);
+
+		assert_no_event(RuntimeEvent::AutomationPrice(crate::Event::TaskTriggered {
+			owner_id: task.owner_id.clone(),
+			task_id: task.task_id,
+			asset_pair: task.asset_pair.clone(),
+			trigger_price: 1000_u128,
+			oracle_round: 1,
+			condition: crate::TaskCondition::TargetPriceMatched {
+				chain: task.chain.clone(),
+				exchange: task.exchange.clone(),
+				asset_pair: task.asset_pair.clone(),
+				price: 1000_u128,
+			},
+		}));
+
+		assert_no_event(RuntimeEvent::AutomationPrice(crate::Event::TaskExecuted {
+			owner_id: task.owner_id.clone(),
+			task_id: task.task_id,
+			action_outcome: ActionOutcome::XcmSent { destination },
+		}));
+
+		assert_last_event(RuntimeEvent::AutomationPrice(crate::Event::TaskExpired {
+			owner_id: task.owner_id.clone(),
+			task_id: task.task_id,
+			condition: crate::TaskCondition::AlreadyExpired {
+				expired_at: task.expired_at,
+				now: START_BLOCK_TIME
+					.saturating_add(7_200_000_u64)
+					.checked_div(1000)
+					.ok_or(ArithmeticError::Overflow)
+					.expect("blocktime is out of range") as u128,
+			},
+		}));
+	})
+}
+
+// when running a task, if the price has been moved against the target price, rendering the target
+// price condition not match anymore. we will skip run
+#[test]
+fn test_price_move_against_target_price_skip_run() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		let para_id: u32 = 1000;
+
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+
+		get_xcmp_funds(creator.clone());
+		let destination = MultiLocation::new(1, X1(Parachain(para_id)));
+		let schedule_fee = MultiLocation::default();
+		let execution_fee = AssetPayment {
+			asset_location: MultiLocation::new(1, X1(Parachain(para_id))).into(),
+			amount: MOCK_XCMP_FEE,
+		};
+		let encoded_call_weight = Weight::from_ref_time(100_000);
+		let overall_weight = Weight::from_ref_time(200_000);
+
+		let task = Task::<Test> {
+			owner_id: creator,
+			task_id: task_id_of("123-0-1"),
+			chain: chain1.to_vec(),
+			exchange: exchange1.to_vec(),
+			asset_pair: (asset1.to_vec(), asset2.to_vec()),
+			expired_at: START_BLOCK_TIME
+				.checked_div(1000)
+				.map_or(10000000_u128, |v| v.into())
+				.saturating_add(100),
+			trigger_function: "gt".as_bytes().to_vec(),
+			// This asset price is set to 1000
+			// The task is config to run when price > 2000, and we invoked it directly
+			// so and we will observe that task won't run due to price doesn't match
+			// the condition
+			trigger_params: vec![2000],
+			action: Action::XCMP {
+				destination,
+				schedule_fee,
+				execution_fee,
+				encoded_call: vec![1, 2, 3],
+				encoded_call_weight,
+				overall_weight,
+				schedule_as: None,
+				instruction_sequence: InstructionSequence::PayThroughRemoteDerivativeAccount,
+			},
+		};
+
+		AutomationPrice::validate_and_schedule_task(task.clone());
+
+		AutomationPrice::run_tasks(
+			vec![(task.owner_id.clone(), task.task_id)],
+			100_000_000_000.into(),
+			&mut PriceCache::default(),
+		);
+
+		assert_no_event(RuntimeEvent::AutomationPrice(crate::Event::TaskTriggered {
+			owner_id: task.owner_id.clone(),
+			task_id: task.task_id,
+			asset_pair: task.asset_pair.clone(),
+			trigger_price: 1000_u128,
+			oracle_round: 1,
+			condition: crate::TaskCondition::TargetPriceMatched {
+				chain: task.chain.clone(),
+				exchange: task.exchange.clone(),
+				asset_pair: task.asset_pair.clone(),
+				price: 1000_u128,
+			},
+		}));
+
+		assert_no_event(RuntimeEvent::AutomationPrice(crate::Event::TaskExecuted {
+			owner_id: task.owner_id.clone(),
+			task_id: task.task_id,
+			action_outcome: ActionOutcome::XcmSent { destination },
+		}));
+
+		let condition = crate::TaskCondition::PriceAlreadyMoved {
+			chain: chain1.to_vec(),
+			exchange: exchange1.to_vec(),
+			asset_pair: (asset1.to_vec(), asset2.to_vec()),
+			price: 1000_u128,
+			target_price: 2000_u128,
+		};
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::PriceAlreadyMoved {
+			owner_id: task.owner_id.clone(),
+			task_id: task.task_id,
+			condition: condition.clone(),
+		}));
+		assert_last_event(RuntimeEvent::AutomationPrice(crate::Event::TaskMissed {
+			owner_id: task.owner_id.clone(),
+			task_id: task.task_id,
+			condition,
+			policy: MissedTaskPolicy::Skip,
+		}));
+	})
+}
+
+// A task that misses its price condition is handled according to the policy of its owner
+#[test]
+fn test_missed_tasks_are_handled_by_their_policy() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		let pair_key = (chain1.to_vec(), exchange1.to_vec(), (asset1.to_vec(), asset2.to_vec()));
+
+		// The price of pair1 is 1000, below the target of the tasks
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+		let policies = [
+			MissedTaskPolicy::Skip,
+			MissedTaskPolicy::ExecuteLate,
+			MissedTaskPolicy::CancelAndRefund,
+		];
+		let mut tasks = vec![];
+		for (i, policy) in policies.into_iter().enumerate() {
+			let mut task = build_update_trigger_task(&creator);
+			task.task_id = task_id_of(&format!("missed-{}", i));
+			task.trigger_params = vec![2000];
+			crate::Tasks::<Test>::insert(&creator, &task.task_id, &task);
+			assert_ok!(AutomationPrice::set_missed_task_policy(
+				RuntimeOrigin::signed(creator.clone()),
+				task.task_id,
+				policy,
+			));
+			assert_has_event(RuntimeEvent::AutomationPrice(
+				crate::Event::MissedTaskPolicyUpdated {
+					owner_id: creator.clone(),
+					task_id: task.task_id,
+					policy,
+				},
+			));
+			tasks.push(task);
+		}
+		assert_noop!(
+			AutomationPrice::set_missed_task_policy(
+				RuntimeOrigin::signed(creator.clone()),
+				"unknown".as_bytes().to_vec(),
+				MissedTaskPolicy::ExecuteLate,
+			),
+			Error::<Test>::TaskNotFound,
+		);
+
+		// The tasks were triggered, but the price fell back before they could run
+		crate::TaskQueue::<Test>::put(
+			tasks.iter().map(|task| (creator.clone(), task.task_id)).collect::<Vec<_>>(),
+		);
+		AutomationPrice::run_tasks(
+			AutomationPrice::get_task_queue(),
+			Weight::from_parts(10_000_000_000, 0),
+			&mut PriceCache::default(),
+		);
+		for (task, policy) in tasks.iter().zip(policies) {
+			assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskMissed {
+				owner_id: creator.clone(),
+				task_id: task.task_id,
+				condition: crate::TaskCondition::PriceAlreadyMoved {
+					chain: chain1.to_vec(),
+					exchange: exchange1.to_vec(),
+					asset_pair: (asset1.to_vec(), asset2.to_vec()),
+					price: 1000,
+					target_price: 2000,
+				},
+				policy,
+			}));
+		}
+
+		// The skipped task waits for the price again
+		let sorted_tasks = AutomationPrice::get_sorted_tasks_above(&pair_key).unwrap();
+		assert_eq!(sorted_tasks.get(&2000), Some(&vec![(creator.clone(), tasks[0].task_id)]));
+		// The cancelled task is removed
+		assert!(AutomationPrice::get_task(&creator, &tasks[2].task_id).is_none());
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskCancelled {
+			owner_id: creator.clone(),
+			task_id: tasks[2].task_id,
+		}));
+		// The late task runs once the task queue is empty
+		assert_eq!(
+			AutomationPrice::get_missed_tasks_queue(),
+			vec![(creator.clone(), tasks[1].task_id)]
+		);
+		crate::TaskQueue::<Test>::kill();
+		AutomationPrice::trigger_tasks(Weight::from_parts(10_000_000_000, 0));
+		assert!(AutomationPrice::get_missed_tasks_queue().is_empty());
+		assert!(AutomationPrice::get_task(&creator, &tasks[1].task_id).is_none());
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskCompleted {
+			owner_id: creator.clone(),
+			task_id: tasks[1].task_id,
+		}));
+	})
+}
+
+// When canceling, task is removed from 3 places:
+#[test]
+fn test_cancel_task_works() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		let para_id: u32 = 1000;
+
+		let destination = MultiLocation::new(1, X1(Parachain(para_id)));
+		let schedule_fee = MultiLocation::default();
+		let execution_fee = AssetPayment {
+			asset_location: MultiLocation::new(1, X1(Parachain(para_id))).into(),
+			amount: MOCK_XCMP_FEE,
+		};
+		let encoded_call_weight = Weight::from_parts(100_000, 0);
+		let overall_weight = Weight::from_parts(200_000, 0);
+
+		get_xcmp_funds(creator.clone());
+		let task = Task::<Test> {
+			owner_id: creator,
+			task_id: task_id_of("123-0-1"),
+			chain: chain1.to_vec(),
+			exchange: exchange1.to_vec(),
+			asset_pair: (asset1.to_vec(), asset2.to_vec()),
+			expired_at: START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
+			trigger_function: "gt".as_bytes().to_vec(),
+			trigger_params: vec![123],
+			action: Action::XCMP {
+				destination,
+				schedule_fee,
+				execution_fee,
+				encoded_call: vec![1, 2, 3],
+				encoded_call_weight,
+				overall_weight,
+				schedule_as: None,
+				instruction_sequence: InstructionSequence::PayThroughRemoteDerivativeAccount,
+			},
+		};
+		AutomationPrice::validate_and_schedule_task(task.clone());
+
+		AutomationPrice::cancel_task(RuntimeOrigin::signed(task.owner_id.clone()), task.task_id);
+
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskCancelled {
+			owner_id: task.owner_id.clone(),
+			task_id: task.task_id,
+		}));
+	})
+}
+
+#[test]
+fn test_halted_scheduling_still_lets_tasks_run() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		let para_id: u32 = 1000;
+
+		setup_asset(&creator, chain1.to_vec());
+		get_xcmp_funds(creator.clone());
+		let task = Task::<Test> {
+			owner_id: creator.clone(),
+			task_id: task_id_of("123-0-1"),
+			chain: chain1.to_vec(),
+			exchange: exchange1.to_vec(),
+			asset_pair: (asset1.to_vec(), asset2.to_vec()),
+			expired_at: START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
+			trigger_function: "gt".as_bytes().to_vec(),
+			trigger_params: vec![123],
+			action: Action::XCMP {
+				destination: MultiLocation::new(1, X1(Parachain(para_id))),
+				schedule_fee: MultiLocation::default(),
+				execution_fee: AssetPayment {
+					asset_location: MultiLocation::new(1, X1(Parachain(para_id))).into(),
+					amount: MOCK_XCMP_FEE,
+				},
+				encoded_call: vec![1, 2, 3],
+				encoded_call_weight: Weight::from_parts(100_000, 0),
+				overall_weight: Weight::from_parts(200_000, 0),
+				schedule_as: None,
+				instruction_sequence: InstructionSequence::PayThroughRemoteDerivativeAccount,
+			},
+		};
+		assert_ok!(AutomationPrice::validate_and_schedule_task(task.clone()));
+
+		let flags = ShutdownFlags { scheduling: true, execution: false, price_ingestion: false };
+		assert_ok!(AutomationPrice::set_shutdown_flags(RawOrigin::Root.into(), flags));
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::ShutdownFlagsUpdated {
+			flags,
+		}));
+
+		let new_task = Task::<Test> { task_id: task_id_of("123-0-2"), ..task.clone() };
+		assert_noop!(
+			AutomationPrice::validate_and_schedule_task(new_task),
+			Error::<Test>::SchedulingHalted,
+		);
+
+		// Prices keep flowing and the task is still triggered
+		assert_ok!(AutomationPrice::update_asset_prices(
+			RuntimeOrigin::signed(creator.clone()),
+			vec![PriceUpdate {
+				chain: chain1.to_vec(),
+				exchange: exchange1.to_vec(),
+				asset1: asset1.to_vec(),
+				asset2: asset2.to_vec(),
+				price: 1005,
+				submitted_at: START_BLOCK_TIME as u128,
+				round: 1,
+			}],
+		));
+		AutomationPrice::on_initialize(System::block_number());
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskTriggered {
+			owner_id: creator.clone(),
+			task_id: task.task_id,
+			asset_pair: (asset1.to_vec(), asset2.to_vec()),
+			trigger_price: 1005,
+			oracle_round: 1,
+			condition: crate::TaskCondition::TargetPriceMatched {
+				chain: chain1.to_vec(),
+				exchange: exchange1.to_vec(),
+				asset_pair: (asset1.to_vec(), asset2.to_vec()),
+				price: 1005,
+			},
+		}));
+	})
+}
+
+#[test]
+fn test_halted_scheduling_still_lets_owners_cancel() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+
+		setup_asset(&creator, chain1.to_vec());
+		get_xcmp_funds(creator.clone());
+		let task = Task::<Test> {
+			owner_id: creator.clone(),
+			task_id: task_id_of("123-0-1"),
+			chain: chain1.to_vec(),
+			exchange: exchange1.to_vec(),
+			asset_pair: (asset1.to_vec(), asset2.to_vec()),
+			expired_at: START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
+			trigger_function: "gt".as_bytes().to_vec(),
+			trigger_params: vec![123],
+			action: Action::XCMP {
+				destination: MultiLocation::new(1, X1(Parachain(PARA_ID))),
+				schedule_fee: MultiLocation::default(),
+				execution_fee: AssetPayment {
+					asset_location: MultiLocation::new(1, X1(Parachain(PARA_ID))).into(),
+					amount: MOCK_XCMP_FEE,
+				},
+				encoded_call: vec![1, 2, 3],
+				encoded_call_weight: Weight::from_parts(100_000, 0),
+				overall_weight: Weight::from_parts(200_000, 0),
+				schedule_as: None,
+				instruction_sequence: InstructionSequence::PayThroughRemoteDerivativeAccount,
+			},
+		};
+		assert_ok!(AutomationPrice::validate_and_schedule_task(task.clone()));
+
+		assert_ok!(AutomationPrice::set_shutdown_flags(
+			RawOrigin::Root.into(),
+			ShutdownFlags::all(),
+		));
+
+		assert_ok!(AutomationPrice::cancel_task(RuntimeOrigin::signed(creator), task.task_id));
+		assert!(AutomationPrice::get_task(&task.owner_id, task_id_of("123-0-1")).is_none());
+	})
+}
+
+#[test]
+fn test_cancel_task_refunds_the_execution_fee_and_leaves_the_queue() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+
+		setup_asset(&creator, chain1.to_vec());
+		Balances::force_set_balance(RawOrigin::Root.into(), creator.clone(), 1_000_000_000_000_000)
+			.unwrap();
+		let task = Task::<Test> {
+			owner_id: creator.clone(),
+			task_id: task_id_of("123-0-1"),
+			chain: chain1.to_vec(),
+			exchange: exchange1.to_vec(),
+			asset_pair: (asset1.to_vec(), asset2.to_vec()),
+			expired_at: START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
+			trigger_function: "gt".as_bytes().to_vec(),
+			trigger_params: vec![123],
+			action: Action::XCMP {
+				destination: MultiLocation::new(1, X1(Parachain(PARA_ID))),
+				schedule_fee: NATIVE_LOCATION,
+				execution_fee: AssetPayment {
+					asset_location: MultiLocation::new(0, Here).into(),
+					amount: MOCK_XCMP_FEE,
+				},
+				encoded_call: vec![1, 2, 3],
+				encoded_call_weight: Weight::from_parts(100_000, 0),
+				overall_weight: Weight::from_parts(200_000, 0),
+				schedule_as: None,
+				instruction_sequence: InstructionSequence::PayThroughSovereignAccount,
+			},
+		};
+		assert_ok!(AutomationPrice::validate_and_schedule_task(task.clone()));
+		crate::TaskQueue::<Test>::put(vec![(creator.clone(), task.task_id)]);
+
+		assert_ok!(AutomationPrice::cancel_task(
+			RuntimeOrigin::signed(creator.clone()),
+			task.task_id,
+		));
+
+		assert!(AutomationPrice::get_task(&creator, &task.task_id).is_none());
+		assert!(AutomationPrice::get_task_queue().is_empty());
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskCancelled {
+			owner_id: creator.clone(),
+			task_id: task.task_id,
+		}));
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::ExecutionFeeRefunded {
+			owner_id: creator,
+			task_id: task.task_id,
+			amount: MOCK_XCMP_FEE,
+		}));
+	})
+}
+
+// Swept tasks also leave the task queue, and their owner gets back the execution fee paid when
+// scheduling
+#[test]
+fn test_sweep_refunds_expired_tasks_and_leaves_the_queue() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+
+		setup_asset(&creator, chain1.to_vec());
+		Balances::force_set_balance(RawOrigin::Root.into(), creator.clone(), 1_000_000_000_000_000)
+			.unwrap();
+		let task = Task::<Test> {
+			owner_id: creator.clone(),
+			task_id: task_id_of("123-0-1"),
+			chain: chain1.to_vec(),
+			exchange: exchange1.to_vec(),
+			asset_pair: (asset1.to_vec(), asset2.to_vec()),
+			expired_at: START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
+			trigger_function: "gt".as_bytes().to_vec(),
+			trigger_params: vec![123],
+			action: Action::XCMP {
+				destination: MultiLocation::new(1, X1(Parachain(PARA_ID))),
+				schedule_fee: NATIVE_LOCATION,
+				execution_fee: AssetPayment {
+					asset_location: MultiLocation::new(0, Here).into(),
+					amount: MOCK_XCMP_FEE,
+				},
+				encoded_call: vec![1, 2, 3],
+				encoded_call_weight: Weight::from_parts(100_000, 0),
+				overall_weight: Weight::from_parts(200_000, 0),
+				schedule_as: None,
+				instruction_sequence: InstructionSequence::PayThroughSovereignAccount,
+			},
+		};
+		assert_ok!(AutomationPrice::validate_and_schedule_task(task.clone()));
+		crate::TaskQueue::<Test>::put(vec![(creator.clone(), task.task_id)]);
+
+		let now = START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND + 1;
+		Timestamp::set_timestamp((now * 1000) as u64);
+		AutomationPrice::sweep_expired_task(Weight::from_parts(100_000_000_000, 0));
+
+		assert!(AutomationPrice::get_task(&creator, &task.task_id).is_none());
+		assert!(AutomationPrice::get_task_queue().is_empty());
+		assert!(AutomationPrice::get_sorted_tasks_by_expiration().is_empty());
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskSweep {
+			owner_id: creator.clone(),
+			task_id: task.task_id,
+			condition: crate::TaskCondition::AlreadyExpired { expired_at: task.expired_at, now },
+		}));
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::ExecutionFeeRefunded {
+			owner_id: creator,
+			task_id: task.task_id,
+			amount: MOCK_XCMP_FEE,
+		}));
+	})
+}
+
+#[test]
+fn test_halted_price_ingestion_refuses_price_updates() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let sender = AccountId32::new(ALICE);
+
+		setup_asset(&sender, chain1.to_vec());
+
+		let flags = ShutdownFlags { scheduling: false, execution: false, price_ingestion: true };
+		assert_noop!(
+			AutomationPrice::set_shutdown_flags(RuntimeOrigin::signed(sender.clone()), flags),
+			sp_runtime::DispatchError::BadOrigin,
+		);
+		assert_ok!(AutomationPrice::set_shutdown_flags(RawOrigin::Root.into(), flags));
+
+		assert_noop!(
+			AutomationPrice::update_asset_prices(
+				RuntimeOrigin::signed(sender),
+				vec![PriceUpdate {
+					chain: chain1.to_vec(),
+					exchange: exchange1.to_vec(),
+					asset1: asset1.to_vec(),
+					asset2: asset2.to_vec(),
+					price: 1005,
+					submitted_at: START_BLOCK_TIME as u128,
+					round: 1,
+				}],
+			),
+			Error::<Test>::PriceIngestionHalted,
+		);
+	})
+}
+
+#[test]
+fn test_purge_tasks_by_destination_works() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		let purged_destination = MultiLocation::new(1, X1(Parachain(PARA_ID)));
+		let other_destination = MultiLocation::new(1, X1(Parachain(PARA_ID + 1)));
+
+		Balances::force_set_balance(RawOrigin::Root.into(), creator.clone(), 1_000_000_000_000_000)
+			.unwrap();
+		for (i, destination) in [purged_destination, other_destination, purged_destination]
+			.into_iter()
+			.enumerate()
+		{
+			let task = Task::<Test> {
+				owner_id: creator.clone(),
+				task_id: task_id_of(&format!("123-0-{}", i)),
+				chain: chain1.to_vec(),
+				exchange: exchange1.to_vec(),
+				asset_pair: (asset1.to_vec(), asset2.to_vec()),
+				expired_at: START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
+				trigger_function: "gt".as_bytes().to_vec(),
+				trigger_params: vec![123],
+				action: Action::XCMP {
+					destination,
+					schedule_fee: NATIVE_LOCATION,
+					execution_fee: AssetPayment {
+						asset_location: MultiLocation::new(0, Here).into(),
+						amount: MOCK_XCMP_FEE,
+					},
+					encoded_call: vec![1, 2, 3],
+					encoded_call_weight: Weight::from_parts(100_000, 0),
+					overall_weight: Weight::from_parts(200_000, 0),
+					schedule_as: None,
+					instruction_sequence: InstructionSequence::PayThroughSovereignAccount,
+				},
+			};
+			assert_ok!(AutomationPrice::validate_and_schedule_task(task));
+		}
+
+		let filter = PurgeFilter::Destination(purged_destination);
+		assert_noop!(
+			AutomationPrice::purge_tasks(RuntimeOrigin::signed(creator.clone()), filter.clone(), 2),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_noop!(
+			AutomationPrice::purge_tasks(RawOrigin::Root.into(), filter.clone(), 11),
+			Error::<Test>::PurgeBatchTooLarge
+		);
+
+		// The first call runs out of its batch before reaching the end of the registry
+		assert_ok!(AutomationPrice::purge_tasks(RawOrigin::Root.into(), filter.clone(), 2));
+		assert!(AutomationPrice::get_purge_cursor(&filter).is_some());
+		assert_ok!(AutomationPrice::purge_tasks(RawOrigin::Root.into(), filter.clone(), 2));
+		assert!(AutomationPrice::get_purge_cursor(&filter).is_none());
+
+		let purged: u32 = System::events()
+			.into_iter()
+			.filter_map(|record| match record.event {
+				RuntimeEvent::AutomationPrice(crate::Event::TasksPurged { purged, .. }) =>
+					Some(purged),
+				_ => None,
+			})
+			.sum();
+		assert_eq!(purged, 2);
+		// The owner gets back the execution fee of the purged tasks
+		for i in [0, 2] {
+			assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::ExecutionFeeRefunded {
+				owner_id: creator.clone(),
+				task_id: task_id_of(&format!("123-0-{}", i)),
+				amount: MOCK_XCMP_FEE,
+			}));
+		}
+
+		let remaining: Vec<Task<Test>> = Tasks::<Test>::iter_values().collect();
+		assert_eq!(remaining.len(), 1);
+		assert!(!filter.matches(&remaining[0]));
+		assert_eq!(AutomationPrice::get_task_stat(StatType::TotalTasksOverall), Some(1));
+	})
+}
+
+#[test]
+fn test_reclaim_deposit_works_once_task_is_removed() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		TaskDepositPerItem::set(100);
+		TaskDepositPerByte::set(1);
+		let creator = AccountId32::new(ALICE);
+		let para_id: u32 = 1000;
+
+		get_xcmp_funds(creator.clone());
+		_ = Balances::deposit_creating(&creator, 10_000);
+		let task = Task::<Test> {
+			owner_id: creator.clone(),
+			task_id: task_id_of("123-0-1"),
+			chain: chain1.to_vec(),
+			exchange: exchange1.to_vec(),
+			asset_pair: (asset1.to_vec(), asset2.to_vec()),
+			expired_at: START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
+			trigger_function: "gt".as_bytes().to_vec(),
+			trigger_params: vec![123],
+			action: Action::XCMP {
+				destination: MultiLocation::new(1, X1(Parachain(para_id))),
+				schedule_fee: MultiLocation::default(),
+				execution_fee: AssetPayment {
+					asset_location: MultiLocation::new(1, X1(Parachain(para_id))).into(),
+					amount: MOCK_XCMP_FEE,
+				},
+				encoded_call: vec![1, 2, 3],
+				encoded_call_weight: Weight::from_parts(100_000, 0),
+				overall_weight: Weight::from_parts(200_000, 0),
+				schedule_as: None,
+				instruction_sequence: InstructionSequence::PayThroughRemoteDerivativeAccount,
+			},
+		};
+		assert_ok!(AutomationPrice::validate_and_schedule_task(task.clone()));
+
+		let deposit = 100 + task.encoded_size() as u128;
+		assert_eq!(AutomationPrice::get_task_deposit(&creator, &task.task_id), Some(deposit));
+		assert_eq!(Balances::reserved_balance(&creator), deposit);
+
+		let bob = RuntimeOrigin::signed(AccountId32::new(BOB));
+		assert_noop!(
+			AutomationPrice::reclaim_deposit(bob.clone(), creator.clone(), task.task_id),
+			Error::<Test>::TaskStillScheduled
+		);
+
+		assert_ok!(AutomationPrice::cancel_task(
+			RuntimeOrigin::signed(creator.clone()),
+			task.task_id,
+		));
+		assert_ok!(AutomationPrice::reclaim_deposit(bob.clone(), creator.clone(), task.task_id));
+		assert_eq!(Balances::reserved_balance(&creator), 0);
+		assert_last_event(RuntimeEvent::AutomationPrice(crate::Event::DepositReclaimed {
+			owner_id: creator.clone(),
+			task_id: task.task_id,
+			amount: deposit,
+		}));
+
+		assert_noop!(
+			AutomationPrice::reclaim_deposit(bob, creator, task.task_id),
+			Error::<Test>::DepositNotFound
+		);
+	})
+}
+
+// Build a task of `creator` on asset1/asset2 of chain1 and exchange1 that triggers above 123
+fn build_update_trigger_task(creator: &AccountId32) -> Task<Test> {
+	let para_id: u32 = 1000;
+	Task::<Test> {
+		owner_id: creator.clone(),
+		task_id: task_id_of("123-0-1"),
+		chain: chain1.to_vec(),
+		exchange: exchange1.to_vec(),
+		asset_pair: (asset1.to_vec(), asset2.to_vec()),
+		expired_at: START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
+		trigger_function: "gt".as_bytes().to_vec(),
+		trigger_params: vec![123],
+		action: Action::XCMP {
+			destination: MultiLocation::new(1, X1(Parachain(para_id))),
+			schedule_fee: MultiLocation::default(),
+			execution_fee: AssetPayment {
+				asset_location: MultiLocation::new(1, X1(Parachain(para_id))).into(),
+				amount: MOCK_XCMP_FEE,
+			},
+			encoded_call: vec![1, 2, 3],
+			encoded_call_weight: Weight::from_parts(100_000, 0),
+			overall_weight: Weight::from_parts(200_000, 0),
+			schedule_as: None,
+			instruction_sequence: InstructionSequence::PayThroughRemoteDerivativeAccount,
+		},
+	}
+}
+
+#[test]
+fn test_update_task_trigger_moves_task_and_adjusts_deposit() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		TaskDepositPerItem::set(100);
+		TaskDepositPerByte::set(1);
+		let creator = AccountId32::new(ALICE);
+		get_xcmp_funds(creator.clone());
+		_ = Balances::deposit_creating(&creator, 10_000);
+
+		let task = build_update_trigger_task(&creator);
+		assert_ok!(AutomationPrice::validate_and_schedule_task(task.clone()));
+		let balance_after_scheduling = Balances::free_balance(&creator);
+
+		assert_ok!(AutomationPrice::update_task_trigger(
+			RuntimeOrigin::signed(creator.clone()),
+			task.task_id,
+			vec![456, 1],
+		));
+
+		let updated_task = AutomationPrice::get_task(&creator, &task.task_id).unwrap();
+		assert_eq!(updated_task.trigger_params, vec![456, 1]);
+		assert_eq!(updated_task.expired_at, task.expired_at);
+		let key = (
+			chain1.to_vec(),
+			exchange1.to_vec(),
+			(asset1.to_vec(), asset2.to_vec()),
+			TriggerFunction::Gt,
+		);
+		let sorted_task_index = AutomationPrice::get_sorted_tasks_index(key).unwrap();
+		assert_eq!(sorted_task_index.get(&123), None);
+		assert_eq!(sorted_task_index.get(&456), Some(&vec![(creator.clone(), task.task_id)]));
+		assert_eq!(AutomationPrice::get_task_stat(StatType::TotalTasksOverall), Some(1));
+
+		// The longer trigger params are covered by a larger deposit, and no fee is charged again
+		let deposit = 100 + updated_task.encoded_size() as u128;
+		assert_eq!(AutomationPrice::get_task_deposit(&creator, &task.task_id), Some(deposit));
+		assert_eq!(Balances::reserved_balance(&creator), deposit);
+		assert_eq!(
+			Balances::free_balance(&creator),
+			balance_after_scheduling - (deposit - 100 - task.encoded_size() as u128)
+		);
+		assert_last_event(RuntimeEvent::AutomationPrice(crate::Event::TaskTriggerUpdated {
+			owner_id: creator.clone(),
+			task_id: task.task_id,
+			trigger_params: vec![456, 1],
+		}));
+
+		// Shorter trigger params return part of the deposit
+		assert_ok!(AutomationPrice::update_task_trigger(
+			RuntimeOrigin::signed(creator.clone()),
+			task.task_id,
+			vec![123],
+		));
+		let deposit = 100 + task.encoded_size() as u128;
+		assert_eq!(AutomationPrice::get_task_deposit(&creator, &task.task_id), Some(deposit));
+		assert_eq!(Balances::reserved_balance(&creator), deposit);
+		assert_eq!(Balances::free_balance(&creator), balance_after_scheduling);
+	})
+}
+
+#[test]
+fn test_update_task_trigger_rejects_invalid_updates() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		get_xcmp_funds(creator.clone());
+		let task = build_update_trigger_task(&creator);
+		assert_ok!(AutomationPrice::validate_and_schedule_task(task.clone()));
+
+		assert_noop!(
+			AutomationPrice::update_task_trigger(
+				RuntimeOrigin::signed(creator.clone()),
+				task.task_id,
+				vec![],
+			),
+			Error::<Test>::InvalidTriggerParams
+		);
+		assert_noop!(
+			AutomationPrice::update_task_trigger(
+				RuntimeOrigin::signed(AccountId32::new(BOB)),
+				task.task_id,
+				vec![456],
+			),
+			Error::<Test>::TaskNotFound
+		);
+
+		assert_ok!(AutomationPrice::set_shutdown_flags(
+			RawOrigin::Root.into(),
+			ShutdownFlags { scheduling: true, execution: false, price_ingestion: false },
+		));
+		assert_noop!(
+			AutomationPrice::update_task_trigger(
+				RuntimeOrigin::signed(creator),
+				task.task_id,
+				vec![456],
+			),
+			Error::<Test>::SchedulingHalted
+		);
+	})
+}
+
+#[test]
+fn test_delete_asset_ok() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let sender = AccountId32::new(ALICE);
+		let key = (chain1.to_vec(), exchange1.to_vec(), (asset1.to_vec(), asset2.to_vec()));
+
+		setup_asset(&sender, chain1.to_vec());
+		AutomationPrice::update_asset_prices(
+			RuntimeOrigin::signed(sender),
+			vec![PriceUpdate {
+				chain: chain1.to_vec(),
+				exchange: exchange1.to_vec(),
+				asset1: asset1.to_vec(),
+				asset2: asset2.to_vec(),
+				price: 6789_u128,
+				submitted_at: START_BLOCK_TIME as u128,
+				round: 4,
+			}],
+		);
+
+		assert!(AutomationPrice::get_asset_registry_info(&key).is_some());
+		assert!(AutomationPrice::get_asset_price_data(&key).is_some());
+
+		// Now we delete asset, all the relevant asset metadata and price should be deleted
+		AutomationPrice::delete_asset(
+			RawOrigin::Root.into(),
+			chain1.to_vec(),
+			exchange1.to_vec(),
+			asset1.to_vec(),
+			asset2.to_vec(),
+		);
+
+		assert!(AutomationPrice::get_asset_registry_info(&key).is_none());
+		assert!(AutomationPrice::get_asset_price_data(&key).is_none());
+
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::AssetDeleted {
+			chain: chain1.to_vec(),
+			exchange: exchange1.to_vec(),
+			asset1: asset1.to_vec(),
+			asset2: asset2.to_vec(),
+		}));
+	})
+}
+
+// Deleting an asset cancels its tasks wherever they wait, refunds them and removes its indexes
+#[test]
+fn test_delete_asset_cancels_and_refunds_its_tasks() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		let key = (chain1.to_vec(), exchange1.to_vec(), (asset1.to_vec(), asset2.to_vec()));
+
+		// The price of pair1 starts at 1000
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+		Balances::force_set_balance(RawOrigin::Root.into(), creator.clone(), 1_000_000_000_000_000)
+			.unwrap();
+
+		let mut task = build_update_trigger_task(&creator);
+		task.action = Action::XCMP {
+			destination: MultiLocation::new(1, X1(Parachain(PARA_ID))),
+			schedule_fee: NATIVE_LOCATION,
+			execution_fee: AssetPayment {
+				asset_location: MultiLocation::new(0, Here).into(),
+				amount: MOCK_XCMP_FEE,
+			},
+			encoded_call: vec![1, 2, 3],
+			encoded_call_weight: Weight::from_parts(100_000, 0),
+			overall_weight: Weight::from_parts(200_000, 0),
+			schedule_as: None,
+			instruction_sequence: InstructionSequence::PayThroughSovereignAccount,
+		};
+		// One task above and one below the price stay in the sorted indexes, the third one
+		// matches the price and is shifted into the task queue
+		let tasks: Vec<Task<Test>> = [("gt", 2000, "1"), ("lt", 100, "2"), ("gt", 500, "3")]
+			.into_iter()
+			.map(|(trigger_function, price, task_id)| {
+				let mut task = task.clone();
+				task.trigger_function = trigger_function.as_bytes().to_vec();
+				task.trigger_params = vec![price];
+				task.task_id = task_id_of(task_id);
+				task
+			})
+			.collect();
+		for task in tasks.iter() {
+			assert_ok!(AutomationPrice::validate_and_schedule_task(task.clone()));
+		}
+		AutomationPrice::shift_tasks(
+			Weight::from_parts(1_000_000_000, 0),
+			&mut PriceCache::default(),
+		);
+		assert_eq!(AutomationPrice::get_task_queue(), vec![(creator.clone(), tasks[2].task_id)]);
+
+		assert_ok!(AutomationPrice::delete_asset(
+			RawOrigin::Root.into(),
+			chain1.to_vec(),
+			exchange1.to_vec(),
+			asset1.to_vec(),
+			asset2.to_vec(),
+		));
+
+		for task in tasks.iter() {
+			assert!(AutomationPrice::get_task(&creator, &task.task_id).is_none());
+			assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskCancelled {
+				owner_id: creator.clone(),
+				task_id: task.task_id,
+			}));
+			assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::ExecutionFeeRefunded {
+				owner_id: creator.clone(),
+				task_id: task.task_id,
+				amount: MOCK_XCMP_FEE,
+			}));
+		}
+		assert!(AutomationPrice::get_task_queue().is_empty());
+		assert!(AutomationPrice::get_sorted_tasks_above(&key).is_none());
+		assert!(AutomationPrice::get_sorted_tasks_below(&key).is_none());
+		assert!(AutomationPrice::get_asset_registry_info(&key).is_none());
+		assert!(AutomationPrice::get_asset_price_data(&key).is_none());
+		assert_eq!(
+			AutomationPrice::get_account_stat(&creator, StatType::TotalTasksPerAccount),
+			Some(0)
+		);
+	})
+}
+
+#[test]
+fn test_delete_asset_fails_with_more_tasks_than_a_purge() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+		let mut task = build_update_trigger_task(&creator);
+		task.trigger_params = vec![2000];
+		let max_tasks: u32 = <Test as Config>::MaxTasksPerPurge::get();
+		let task_ids: TaskIdList<Test> = (0..=max_tasks)
+			.map(|i| {
+				let task_id = task_id_of(&i.to_string());
+				task.task_id = task_id;
+				crate::Tasks::<Test>::insert(&creator, &task_id, &task);
+				(creator.clone(), task_id)
+			})
+			.collect();
+		put_sorted_tasks_above(
+			&(chain1.to_vec(), exchange1.to_vec(), (asset1.to_vec(), asset2.to_vec())),
+			BTreeMap::from([(2000, task_ids)]),
+		);
+
+		assert_noop!(
+			AutomationPrice::delete_asset(
+				RawOrigin::Root.into(),
+				chain1.to_vec(),
+				exchange1.to_vec(),
+				asset1.to_vec(),
+				asset2.to_vec(),
+			),
+			Error::<Test>::TooManyTasksToDelete
+		);
+	})
+}
+
+#[test]
+fn test_sorted_tasks_index_migration_rebuilds_from_tasks() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		let task_ids: Vec<TaskId> =
+			vec![task_id_of("1-0-1"), task_id_of("1-0-2"), task_id_of("1-0-3")];
+		for (i, task_id) in task_ids.iter().enumerate() {
+			let task = Task::<Test> {
+				owner_id: creator.clone(),
+				task_id: *task_id,
+				chain: chain1.to_vec(),
+				exchange: exchange1.to_vec(),
+				asset_pair: (asset1.to_vec(), asset2.to_vec()),
+				expired_at: START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
+				trigger_function: "gt".as_bytes().to_vec(),
+				trigger_params: vec![100 + (i as u128 % 2)],
+				action: Action::XCMP {
+					destination: MultiLocation::new(1, X1(Parachain(PARA_ID))),
+					schedule_fee: MultiLocation::default(),
+					execution_fee: AssetPayment {
+						asset_location: MultiLocation::new(0, Here).into(),
+						amount: MOCK_XCMP_FEE,
+					},
+					encoded_call: vec![1, 2, 3],
+					encoded_call_weight: Weight::from_parts(100_000, 0),
+					overall_weight: Weight::from_parts(200_000, 0),
+					schedule_as: None,
+					instruction_sequence: InstructionSequence::PayThroughRemoteDerivativeAccount,
+				},
+			};
+			Tasks::<Test>::insert(creator.clone(), task_id, task);
+		}
+
+		// A stale entry whose task no longer exists
+		let stale_key = (
+			chain2.to_vec(),
+			exchange1.to_vec(),
+			(asset2.to_vec(), asset3.to_vec()),
+			"lt".as_bytes().to_vec(),
+		);
+		SortedTasksIndex::<Test>::insert(
+			&stale_key,
+			BTreeMap::from([(10u128, vec![(creator.clone(), task_id_of("1-0-9"))])]),
+		);
+
+		StorageVersion::new(1).put::<AutomationPrice>();
+		MigrateToV2::<Test>::on_runtime_upgrade();
+		assert_eq!(AutomationPrice::on_chain_storage_version(), 2);
+		assert_eq!(
+			AutomationPrice::get_index_migration(),
+			Some(IndexMigrationStage::Clearing { cursor: None })
+		);
+
+		AutomationPrice::step_index_migration(AutomationPrice::get_index_migration().unwrap(), 2);
+		assert!(SortedTasksIndex::<Test>::get(&stale_key).is_none());
+
+		// Rebuilding takes two blocks with room for two tasks per block
+		AutomationPrice::step_index_migration(AutomationPrice::get_index_migration().unwrap(), 2);
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::IndexMigrationProgressed {
+			migrated: 2,
+		}));
+		AutomationPrice::step_index_migration(AutomationPrice::get_index_migration().unwrap(), 2);
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::IndexMigrationCompleted {
+			migrated: 3,
+		}));
+		assert!(AutomationPrice::get_index_migration().is_none());
+
+		let sorted_task_index = AutomationPrice::get_sorted_tasks_index((
+			chain1.to_vec(),
+			exchange1.to_vec(),
+			(asset1.to_vec(), asset2.to_vec()),
+			TriggerFunction::Gt,
+		))
+		.unwrap();
+		let mut indexed: Vec<TaskId> =
+			sorted_task_index.into_values().flatten().map(|(_, task_id)| task_id).collect();
+		indexed.sort();
+		let mut expected = task_ids.clone();
+		expected.sort();
+		assert_eq!(indexed, expected);
+
+		// Running the migration again is a no-op
+		MigrateToV2::<Test>::on_runtime_upgrade();
+		assert!(AutomationPrice::get_index_migration().is_none());
+	})
+}
+
+#[test]
+fn test_migrate_to_v3_keeps_a_shut_down_pallet_halted() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		StorageVersion::new(2).put::<AutomationPrice>();
+		migrations::v3::Shutdown::<Test>::put(true);
+
+		MigrateToV3::<Test>::on_runtime_upgrade();
+
+		assert_eq!(AutomationPrice::on_chain_storage_version(), 3);
+		assert_eq!(AutomationPrice::get_shutdown_flags(), ShutdownFlags::all());
+	})
+}
+
+#[test]
+fn test_migrate_to_v3_leaves_a_running_pallet_running() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		StorageVersion::new(2).put::<AutomationPrice>();
+		migrations::v3::Shutdown::<Test>::put(false);
+
+		MigrateToV3::<Test>::on_runtime_upgrade();
+
+		assert_eq!(AutomationPrice::on_chain_storage_version(), 3);
+		assert_eq!(AutomationPrice::get_shutdown_flags(), ShutdownFlags::default());
+	})
+}
+
+#[test]
+fn test_migrate_to_v4_moves_every_task_to_its_new_id() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		let key = (chain1.to_vec(), exchange1.to_vec(), (asset1.to_vec(), asset2.to_vec()));
+		let task = build_update_trigger_task(&creator);
+		let old_task = |task_id: &Vec<u8>| OldTask::<Test> {
+			owner_id: creator.clone(),
+			task_id: task_id.clone(),
+			chain: task.chain.clone(),
+			exchange: task.exchange.clone(),
+			asset_pair: task.asset_pair.clone(),
+			expired_at: task.expired_at,
+			trigger_function: task.trigger_function.clone(),
+			trigger_params: task.trigger_params.clone(),
+			// XCMP actions were stored the same way before v8
+			action: OldAction::decode(&mut &task.action.encode()[..]).unwrap(),
+		};
+
+		// A bracket task, one leg of which waits in the index and the other in the queue
+		let stop_loss = "1-0-4".as_bytes().to_vec();
+		let take_profit = "1-0-7".as_bytes().to_vec();
+		for task_id in [&stop_loss, &take_profit] {
+			migrations::v4::old::Tasks::<Test>::insert(&creator, task_id, old_task(task_id));
+		}
+		migrations::v4::old::LinkedTasks::<Test>::insert(&creator, &stop_loss, &take_profit);
+		migrations::v4::old::LinkedTasks::<Test>::insert(&creator, &take_profit, &stop_loss);
+		migrations::v4::old::TaskDeposits::<Test>::insert(&creator, &stop_loss, 100);
+		frame_support::storage::unhashed::put(
+			&migrations::v5::old::SortedTasksAbove::<Test>::hashed_key_for(&key),
+			&BTreeMap::from([(123_u128, vec![(creator.clone(), stop_loss.clone())])]),
+		);
+		frame_support::storage::unhashed::put(
+			&crate::TaskQueue::<Test>::hashed_key(),
+			&vec![(creator.clone(), take_profit.clone())],
+		);
+		let filter = PurgeFilter::Destination(MultiLocation::new(1, X1(Parachain(PARA_ID))));
+		crate::PurgeCursors::<Test>::insert(&filter, vec![1, 2, 3]);
+
+		StorageVersion::new(3).put::<AutomationPrice>();
+		MigrateToV4::<Test>::on_runtime_upgrade();
+		assert_eq!(AutomationPrice::on_chain_storage_version(), 4);
+
+		let stop_loss_id = migrated_task_id::<Test>(&creator, &stop_loss);
+		let take_profit_id = migrated_task_id::<Test>(&creator, &take_profit);
+		assert_eq!(
+			AutomationPrice::get_task(&creator, stop_loss_id),
+			Some(Task::<Test> { task_id: stop_loss_id, ..task.clone() })
+		);
+		assert_eq!(
+			AutomationPrice::get_task(&creator, take_profit_id),
+			Some(Task::<Test> { task_id: take_profit_id, ..task })
+		);
+		assert_eq!(AutomationPrice::get_linked_task(&creator, stop_loss_id), Some(take_profit_id));
+		assert_eq!(AutomationPrice::get_linked_task(&creator, take_profit_id), Some(stop_loss_id));
+		assert_eq!(AutomationPrice::get_task_deposit(&creator, stop_loss_id), Some(100));
+		assert_eq!(
+			migrations::v5::old::SortedTasksAbove::<Test>::get(&key),
+			Some(BTreeMap::from([(123, vec![(creator.clone(), stop_loss_id)])]))
+		);
+		assert_eq!(AutomationPrice::get_task_queue(), vec![(creator.clone(), take_profit_id)]);
+		// The cursor pointed into the old keys of the tasks
+		assert!(AutomationPrice::get_purge_cursor(&filter).is_none());
+
+		// Running the migration again is a no-op
+		MigrateToV4::<Test>::on_runtime_upgrade();
+		assert!(AutomationPrice::get_task(&creator, stop_loss_id).is_some());
+	})
+}
+
+#[test]
+fn test_migrate_to_v5_splits_the_sorted_tasks_into_price_buckets() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		let key = (chain1.to_vec(), exchange1.to_vec(), (asset1.to_vec(), asset2.to_vec()));
+		let entry = |task_id: &str| (creator.clone(), task_id_of(task_id));
+		let above = BTreeMap::from([
+			(1, vec![entry("a1")]),
+			(1500, vec![entry("a2"), entry("a3")]),
+			(2999, vec![entry("a4")]),
+		]);
+		let below = BTreeMap::from([(500, vec![entry("b1")])]);
+		migrations::v5::old::SortedTasksAbove::<Test>::insert(&key, &above);
+		migrations::v5::old::SortedTasksBelow::<Test>::insert(&key, &below);
+
+		StorageVersion::new(4).put::<AutomationPrice>();
+		MigrateToV5::<Test>::on_runtime_upgrade();
+		assert_eq!(AutomationPrice::on_chain_storage_version(), 5);
+
+		// The buckets are 1000 wide in the mock
+		assert_eq!(
+			AutomationPrice::get_sorted_buckets_above(&key),
+			Some(BTreeSet::from([0, 1, 2]))
+		);
+		assert_eq!(
+			crate::SortedTasksAbove::<Test>::get((&key.0, &key.1, &key.2, 1)),
+			Some(BTreeMap::from([(1500, vec![entry("a2"), entry("a3")])]))
+		);
+		assert_eq!(AutomationPrice::get_sorted_tasks_above(&key), Some(above.clone()));
+		assert_eq!(AutomationPrice::get_sorted_buckets_below(&key), Some(BTreeSet::from([0])));
+		assert_eq!(AutomationPrice::get_sorted_tasks_below(&key), Some(below));
+		assert!(migrations::v5::old::SortedTasksAbove::<Test>::get(&key).is_none());
+
+		// Running the migration again is a no-op
+		MigrateToV5::<Test>::on_runtime_upgrade();
+		assert_eq!(AutomationPrice::get_sorted_tasks_above(&key), Some(above));
+	})
+}
+
+// Stores a task for `owner` under `task_id` whose value cannot be decoded into a `Task`.
+fn insert_undecodable_task(owner: &AccountId32, task_id: &TaskId) {
+	frame_support::storage::unhashed::put_raw(
+		&Tasks::<Test>::hashed_key_for(owner, task_id),
+		&[0xff, 0xff, 0xff],
+	);
+	TaskStats::<Test>::insert(StatType::TotalTasksOverall, 1);
+	AccountStats::<Test>::insert(owner, StatType::TotalTasksPerAccount, 1);
+}
+
+#[test]
+fn test_run_tasks_removes_an_undecodable_task() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let owner = AccountId32::new(ALICE);
+		let task_id = task_id_of("123-0-1");
+		insert_undecodable_task(&owner, &task_id);
+
+		let (tasks_left, _) = AutomationPrice::run_tasks(
+			vec![(owner.clone(), task_id)],
+			100_000_000_000.into(),
+			&mut PriceCache::default(),
+		);
+
+		assert!(tasks_left.is_empty());
+		assert!(!Tasks::<Test>::contains_key(&owner, &task_id));
+		assert_eq!(AutomationPrice::get_task_stat(StatType::TotalTasksOverall), Some(0));
+		assert_eq!(
+			AutomationPrice::get_account_stat(&owner, StatType::TotalTasksPerAccount),
+			Some(0)
+		);
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskUndecodable {
+			owner_id: owner,
+			task_id,
+		}));
+	})
+}
+
+#[test]
+fn test_cancel_task_removes_an_undecodable_task() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let owner = AccountId32::new(ALICE);
+		let task_id = task_id_of("123-0-1");
+		insert_undecodable_task(&owner, &task_id);
+
+		assert_ok!(AutomationPrice::cancel_task(RuntimeOrigin::signed(owner.clone()), task_id));
+
+		assert!(!Tasks::<Test>::contains_key(&owner, &task_id));
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskUndecodable {
+			owner_id: owner,
+			task_id,
+		}));
+	})
+}
+
+// Nothing kept under the id of an undecodable task is left behind, and its deposit is returned
+#[test]
+fn test_remove_undecodable_task_cleans_up_everything_kept_for_the_task() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let owner = AccountId32::new(ALICE);
+		let task_id = task_id_of("123-0-1");
+		let other_leg = task_id_of("123-0-2");
+		Balances::force_set_balance(RawOrigin::Root.into(), owner.clone(), 1_000_000).unwrap();
+		assert_ok!(Balances::reserve(&owner, 500));
+		insert_undecodable_task(&owner, &task_id);
+		crate::TaskDeposits::<Test>::insert(&owner, task_id, 500);
+		crate::TaskRecurrences::<Test>::insert(
+			&owner,
+			task_id,
+			Recurrence { cooldown_seconds: 600, remaining_executions: None },
+		);
+		crate::MissedTaskPolicies::<Test>::insert(&owner, task_id, MissedTaskPolicy::ExecuteLate);
+		crate::PausedTasks::<Test>::insert(&owner, task_id, true);
+		crate::TaskTimeWindows::<Test>::insert(&owner, task_id, TimeWindow { start: 0, end: 1800 });
+		crate::TaskConditions::<Test>::insert(
+			&owner,
+			task_id,
+			CompoundCondition { combinator: Combinator::And, conditions: vec![] },
+		);
+		crate::LinkedTasks::<Test>::insert(&owner, task_id, other_leg);
+		crate::LinkedTasks::<Test>::insert(&owner, other_leg, task_id);
+		crate::SortedTasksByExpiration::<Test>::put(BTreeMap::from([(
+			START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
+			BTreeMap::from([(task_id, owner.clone())]),
+		)]));
+		crate::CoolingDownTasks::<Test>::put(BTreeMap::from([(0, vec![(owner.clone(), task_id)])]));
+		crate::TaskQueue::<Test>::put(vec![(owner.clone(), task_id)]);
+
+		assert_ok!(AutomationPrice::cancel_task(RuntimeOrigin::signed(owner.clone()), task_id));
+
+		assert!(!Tasks::<Test>::contains_key(&owner, task_id));
+		assert_eq!(AutomationPrice::get_task_deposit(&owner, task_id), None);
+		assert_eq!(Balances::reserved_balance(&owner), 0);
+		assert_eq!(AutomationPrice::get_task_recurrence(&owner, task_id), None);
+		assert!(!crate::MissedTaskPolicies::<Test>::contains_key(&owner, task_id));
+		assert!(!crate::PausedTasks::<Test>::contains_key(&owner, task_id));
+		assert_eq!(AutomationPrice::get_task_time_window(&owner, task_id), None);
+		assert_eq!(AutomationPrice::get_task_conditions(&owner, task_id), None);
+		assert_eq!(AutomationPrice::get_linked_task(&owner, task_id), None);
+		assert_eq!(AutomationPrice::get_linked_task(&owner, other_leg), None);
+		assert!(AutomationPrice::get_sorted_tasks_by_expiration().is_empty());
+		assert!(AutomationPrice::get_cooling_down_tasks().is_empty());
+		assert!(AutomationPrice::get_task_queue().is_empty());
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::DepositReclaimed {
+			owner_id: owner.clone(),
+			task_id,
+			amount: 500,
+		}));
+		assert_ok!(AutomationPrice::do_try_state());
+	})
+}
+
+#[test]
+fn test_upgrade_without_migration_halts_task_execution() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		StorageVersion::new(2).put::<AutomationPrice>();
+
+		<AutomationPrice as OnRuntimeUpgrade>::on_runtime_upgrade();
+
+		let flags = AutomationPrice::get_shutdown_flags();
+		assert!(flags.execution);
+		assert!(!flags.scheduling);
+		assert!(!flags.price_ingestion);
+	})
+}
+
+#[test]
+fn test_upgrade_with_migrated_storage_keeps_task_execution_running() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		<AutomationPrice as OnRuntimeUpgrade>::on_runtime_upgrade();
+
+		assert_eq!(AutomationPrice::get_shutdown_flags(), ShutdownFlags::default());
+	})
+}
+
+// Prices are read once per asset pair, later reads in the block are served from the cache
+#[test]
+fn test_price_cache_reads_each_asset_pair_once() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let sender = AccountId32::new(ALICE);
+		setup_assets_and_prices(&sender, START_BLOCK_TIME as u128);
+
+		let chain = chain1.to_vec();
+		let exchange = exchange1.to_vec();
+		let asset_pair = (asset1.to_vec(), asset2.to_vec());
+		let mut prices = PriceCache::<Test>::default();
+
+		let (price, _) = prices.get(&chain, &exchange, &asset_pair);
+		assert_eq!(price.map(|price| price.value), Some(1000));
+
+		// A price written later in the block is not seen
+		crate::PriceRegistry::<Test>::insert(
+			(&chain, &exchange, &asset_pair),
+			crate::PriceData { round: 2, updated_at: 0, value: 2000 },
+		);
+		let (price, _) = prices.get(&chain, &exchange, &asset_pair);
+		assert_eq!(price.map(|price| price.value), Some(1000));
+	})
+}
+
+#[test]
+fn test_schedule_xcmp_task_weight_and_fee_grow_with_input_length() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let schedule_call = |encoded_call: Vec<u8>| {
+			RuntimeCall::AutomationPrice(crate::Call::schedule_xcmp_task {
+				chain: chain1.to_vec(),
+				exchange: exchange1.to_vec(),
+				asset1: asset1.to_vec(),
+				asset2: asset2.to_vec(),
+				expired_at: START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
+				trigger_function: "gt".as_bytes().to_vec(),
+				trigger_param: vec![100],
+				destination: Box::new(MultiLocation::new(1, X1(Parachain(PARA_ID))).into()),
+				schedule_fee: Box::new(NATIVE_LOCATION.into()),
+				execution_fee: Box::new(AssetPayment {
+					asset_location: MultiLocation::new(0, Here).into(),
+					amount: 10_000_000_000_000,
+				}),
+				encoded_call,
+				encoded_call_weight: Weight::from_parts(100_000, 0),
+				overall_weight: Weight::from_parts(200_000, 0),
+			})
+		};
+		let short_call_weight = schedule_call(vec![1]).get_dispatch_info().weight;
+		let long_call_weight = schedule_call(vec![1; 1_001]).get_dispatch_info().weight;
+		assert_eq!(
+			long_call_weight,
+			short_call_weight + MockWeight::<Test>::schedule_xcmp_task_extrinsic(1_000) -
+				MockWeight::<Test>::schedule_xcmp_task_extrinsic(0),
+		);
+
+		let action = |encoded_call: Vec<u8>| Action::XCMP {
+			destination: MultiLocation::new(1, X1(Parachain(PARA_ID))),
+			schedule_fee: NATIVE_LOCATION,
+			execution_fee: AssetPayment {
+				asset_location: MultiLocation::new(0, Here).into(),
+				amount: 10_000_000_000_000,
+			},
+			encoded_call,
+			encoded_call_weight: Weight::from_parts(100_000, 0),
+			overall_weight: Weight::from_parts(200_000, 0),
+			schedule_as: None,
+			instruction_sequence: InstructionSequence::PayThroughSovereignAccount,
+		};
+		let short_call_fee = AutomationPrice::calculate_schedule_fee_amount(&action(vec![1]))
+			.expect("fee is calculated");
+		let long_call_fee = AutomationPrice::calculate_schedule_fee_amount(&action(vec![1; 1_001]))
+			.expect("fee is calculated");
+		let payload_weight = MockWeight::<Test>::schedule_xcmp_task_extrinsic(1_000) -
+			MockWeight::<Test>::schedule_xcmp_task_extrinsic(0);
+		assert_eq!(
+			long_call_fee,
+			short_call_fee + ExecutionWeightFee::get() * u128::from(payload_weight.ref_time()),
+		);
+	})
+}
+
+#[test]
+fn test_asset_ownership_moves_with_its_deposit_once_accepted() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let alice = AccountId32::new(ALICE);
+		let bob = AccountId32::new(BOB);
+		setup_asset(&alice, chain1.to_vec());
+		let key = (chain1.to_vec(), exchange1.to_vec(), (asset1.to_vec(), asset2.to_vec()));
+		let transfer = |origin: RuntimeOrigin, new_owner: AssetOwner<AccountId32>| {
+			AutomationPrice::transfer_asset_ownership(
+				origin,
+				chain1.to_vec(),
+				exchange1.to_vec(),
+				asset1.to_vec(),
+				asset2.to_vec(),
+				new_owner,
+			)
+		};
+		let accept = |origin: RuntimeOrigin| {
+			AutomationPrice::accept_asset_ownership(
+				origin,
+				chain1.to_vec(),
+				exchange1.to_vec(),
+				asset1.to_vec(),
+				asset2.to_vec(),
+			)
+		};
+
+		// Governance owns the asset pair of a root registration
+		assert_noop!(
+			transfer(RuntimeOrigin::signed(alice.clone()), AssetOwner::Account(alice.clone())),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_ok!(transfer(RawOrigin::Root.into(), AssetOwner::Account(alice.clone())));
+		assert_eq!(
+			AutomationPrice::get_pending_asset_owner(&key),
+			Some(AssetOwner::Account(alice.clone()))
+		);
+		assert_noop!(accept(RuntimeOrigin::signed(bob.clone())), Error::<Test>::NotAssetOwner);
+		assert_ok!(accept(RuntimeOrigin::signed(alice.clone())));
+		assert_eq!(
+			AutomationPrice::get_asset_ownership(&key),
+			Some(AssetOwnership { owner: alice.clone(), deposit: 0 })
+		);
+		assert_eq!(AutomationPrice::get_pending_asset_owner(&key), None);
+
+		// A deposit held by the owner moves to the account accepting the asset pair
+		_ = Balances::deposit_creating(&alice, 1_000);
+		_ = Balances::deposit_creating(&bob, 1_000);
+		assert_ok!(Balances::reserve(&alice, 500));
+		AssetOwners::<Test>::insert(&key, AssetOwnership { owner: alice.clone(), deposit: 500 });
+
+		assert_noop!(
+			transfer(RawOrigin::Root.into(), AssetOwner::Account(bob.clone())),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_ok!(transfer(
+			RuntimeOrigin::signed(alice.clone()),
+			AssetOwner::Account(bob.clone())
+		));
+		assert_ok!(accept(RuntimeOrigin::signed(bob.clone())));
+		assert_eq!(Balances::reserved_balance(&alice), 0);
+		assert_eq!(Balances::reserved_balance(&bob), 500);
+		assert_eq!(
+			AutomationPrice::get_asset_ownership(&key),
+			Some(AssetOwnership { owner: bob.clone(), deposit: 500 })
+		);
+		assert_last_event(RuntimeEvent::AutomationPrice(crate::Event::AssetOwnershipTransferred {
+			chain: chain1.to_vec(),
+			exchange: exchange1.to_vec(),
+			asset1: asset1.to_vec(),
+			asset2: asset2.to_vec(),
+			owner: AssetOwner::Account(bob.clone()),
+		}));
+
+		// Handing the asset pair back to governance returns the deposit
+		assert_ok!(transfer(RuntimeOrigin::signed(bob.clone()), AssetOwner::Governance));
+		assert_noop!(
+			accept(RuntimeOrigin::signed(bob.clone())),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_ok!(accept(RawOrigin::Root.into()));
+		assert_eq!(Balances::reserved_balance(&bob), 0);
+		assert_eq!(AutomationPrice::get_asset_ownership(&key), None);
+	})
+}
+
+#[test]
+fn test_asset_ownership_transfer_needs_a_registered_asset_and_a_pending_offer() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let alice = AccountId32::new(ALICE);
+		assert_noop!(
+			AutomationPrice::transfer_asset_ownership(
+				RawOrigin::Root.into(),
 				chain1.to_vec(),
 				exchange1.to_vec(),
-				(asset1.to_vec(), asset2.to_vec()),
-				"lt".as_bytes().to_vec(),
-			))
-			.map_or(0, |v| v.get(&price_target2).unwrap().iter().len())
+				asset1.to_vec(),
+				asset2.to_vec(),
+				AssetOwner::Account(alice.clone()),
+			),
+			Error::<Test>::AssetNotSupported
+		);
+
+		setup_asset(&alice, chain1.to_vec());
+		assert_noop!(
+			AutomationPrice::accept_asset_ownership(
+				RuntimeOrigin::signed(alice),
+				chain1.to_vec(),
+				exchange1.to_vec(),
+				asset1.to_vec(),
+				asset2.to_vec(),
+			),
+			Error::<Test>::NoPendingAssetTransfer
 		);
+	})
+}
+
+// A dynamic dispatch task runs its call as the task owner once its price condition matches,
+// and calls outside the allow list fail without stopping the other tasks
+#[test]
+fn test_dynamic_dispatch_task_runs_only_allowed_calls() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+
+		// The price of pair1 starts at 1000, above the target of both tasks
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+		Balances::force_set_balance(RawOrigin::Root.into(), creator.clone(), 1_000_000_000_000_000)
+			.unwrap();
+
+		let schedule = |call: RuntimeCall| {
+			AutomationPrice::schedule_dynamic_dispatch_task(
+				RuntimeOrigin::signed(creator.clone()),
+				chain1.to_vec(),
+				exchange1.to_vec(),
+				asset1.to_vec(),
+				asset2.to_vec(),
+				START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
+				"gt".as_bytes().to_vec(),
+				vec![500],
+				Box::new(NATIVE_LOCATION.into()),
+				Box::new(call),
+				None,
+			)
+		};
+		let remark =
+			RuntimeCall::System(frame_system::Call::remark_with_event { remark: vec![1, 2, 3] });
+		assert_ok!(schedule(remark.clone()));
+		assert_ok!(schedule(RuntimeCall::AutomationPrice(crate::Call::cancel_task {
+			task_id: [1; 32],
+		})));
 
+		let task_ids = get_task_ids_from_events();
+		let allowed = AutomationPrice::get_task(&creator, &task_ids[0]).unwrap();
 		assert_eq!(
-			10,
-			AutomationPrice::get_sorted_tasks_by_expiration()
-				.get(&(START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND - 1800))
-				.expect("missing task expiration shard")
-				.len(),
+			allowed.action,
+			Action::DynamicDispatch {
+				encoded_call: remark.encode(),
+				schedule_fee: NATIVE_LOCATION
+			}
 		);
 
-		// now we will sweep, passing a weight limit. In actualy code, this will be the
-		// remaining_weight in on_idle block
-		let remain_weight = 100_000_000_000;
-		AutomationPrice::sweep_expired_task(Weight::from_ref_time(remain_weight));
+		AutomationPrice::trigger_tasks(Weight::from_parts(10_000_000_000, 0));
+
+		assert!(System::events().into_iter().any(|record| matches!(
+			record.event,
+			RuntimeEvent::System(frame_system::Event::Remarked { ref sender, .. })
+				if *sender == creator
+		)));
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskExecuted {
+			owner_id: creator.clone(),
+			task_id: task_ids[0],
+			action_outcome: ActionOutcome::Dispatched,
+		}));
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskExecutionFailed {
+			owner_id: creator.clone(),
+			task_id: task_ids[1],
+			error: frame_system::Error::<Test>::CallFiltered.into(),
+		}));
+		assert!(AutomationPrice::get_task(&creator, &task_ids[0]).is_none());
+		assert!(AutomationPrice::get_task(&creator, &task_ids[1]).is_none());
+	})
+}
+
+// Once one leg of a bracket runs, the other one is cancelled and its execution fee refunded
+#[test]
+fn test_bracket_task_cancels_the_other_leg_once_one_runs() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		let destination = MultiLocation::new(1, X1(Parachain(PARA_ID)));
+		let schedule = |stop_loss_price: u128, take_profit_price: u128| {
+			AutomationPrice::schedule_xcmp_bracket_task(
+				RuntimeOrigin::signed(creator.clone()),
+				chain1.to_vec(),
+				exchange1.to_vec(),
+				asset1.to_vec(),
+				asset2.to_vec(),
+				START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
+				stop_loss_price,
+				take_profit_price,
+				Box::new(destination.into()),
+				Box::new(NATIVE_LOCATION.into()),
+				Box::new(AssetPayment {
+					asset_location: MultiLocation::new(0, Here).into(),
+					amount: MOCK_XCMP_FEE,
+				}),
+				vec![1, 2, 3],
+				vec![4, 5, 6],
+				Weight::from_parts(100_000, 0),
+				Weight::from_parts(200_000, 0),
+			)
+		};
+		assert_noop!(schedule(500, 900), Error::<Test>::AssetNotSupported);
 
+		// The price of pair1 starts at 1000, above the take profit price
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+		Balances::force_set_balance(RawOrigin::Root.into(), creator.clone(), 1_000_000_000_000_000)
+			.unwrap();
+
+		assert_noop!(schedule(900, 900), Error::<Test>::InvalidBracket);
+		assert_ok!(schedule(500, 900));
+
+		let task_ids = get_task_ids_from_events();
+		let (stop_loss_task_id, take_profit_task_id) = (task_ids[0], task_ids[1]);
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::BracketScheduled {
+			owner_id: creator.clone(),
+			stop_loss_task_id,
+			take_profit_task_id,
+		}));
 		assert_eq!(
-			AutomationPrice::get_sorted_tasks_by_expiration()
-				.get(&(START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND - 1800)),
-			None
+			AutomationPrice::get_linked_task(&creator, &stop_loss_task_id),
+			Some(take_profit_task_id)
+		);
+		assert_eq!(
+			AutomationPrice::get_linked_task(&creator, &take_profit_task_id),
+			Some(stop_loss_task_id)
 		);
 
-		for i in 0..expired_task_gen {
-			assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskSweep {
-				owner_id: creator.clone(),
-				task_id: format!("123-0-{:?}", i).as_bytes().to_vec(),
-				condition: crate::TaskCondition::AlreadyExpired {
-					expired_at: START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND - 1800,
-					now: START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
-				},
-			}));
+		AutomationPrice::trigger_tasks(Weight::from_parts(10_000_000_000, 0));
+
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskCompleted {
+			owner_id: creator.clone(),
+			task_id: take_profit_task_id,
+		}));
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskCancelled {
+			owner_id: creator.clone(),
+			task_id: stop_loss_task_id,
+		}));
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::ExecutionFeeRefunded {
+			owner_id: creator.clone(),
+			task_id: stop_loss_task_id,
+			amount: MOCK_XCMP_FEE,
+		}));
+		assert!(AutomationPrice::get_task(&creator, &stop_loss_task_id).is_none());
+		assert!(AutomationPrice::get_linked_task(&creator, &stop_loss_task_id).is_none());
+		assert!(AutomationPrice::get_linked_task(&creator, &take_profit_task_id).is_none());
+		assert_eq!(
+			AutomationPrice::get_account_stat(&creator, StatType::TotalTasksPerAccount),
+			Some(0)
+		);
+	})
+}
+
+// Tasks are not triggered by a stale price, and run once the price is fresh again
+#[test]
+fn test_trigger_tasks_skips_asset_pairs_with_a_stale_price() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		let pair_key = (chain1.to_vec(), exchange1.to_vec(), (asset1.to_vec(), asset2.to_vec()));
+		MaxPriceStaleness::set(600);
+
+		// The price of pair1 is 1000 at START_BLOCK_TIME, above the target of the task
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+		let task = build_update_trigger_task(&creator);
+		crate::Tasks::<Test>::insert(&creator, &task.task_id, &task);
+		AutomationPrice::insert_into_sorted_index(&task);
+
+		Timestamp::set_timestamp(START_BLOCK_TIME + 601_000);
+		AutomationPrice::trigger_tasks(Weight::from_parts(10_000_000_000, 0));
+
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::PriceStale {
+			chain: chain1.to_vec(),
+			exchange: exchange1.to_vec(),
+			asset_pair: (asset1.to_vec(), asset2.to_vec()),
+			updated_at: (START_BLOCK_TIME / 1000) as u128,
+		}));
+		assert!(AutomationPrice::get_task_queue().is_empty());
+		assert!(AutomationPrice::get_sorted_tasks_above(&pair_key).is_some());
+		assert_no_event(RuntimeEvent::AutomationPrice(crate::Event::TaskTriggered {
+			owner_id: creator.clone(),
+			task_id: task.task_id,
+			asset_pair: (asset1.to_vec(), asset2.to_vec()),
+			trigger_price: 1000,
+			oracle_round: 1,
+			condition: crate::TaskCondition::TargetPriceMatched {
+				chain: chain1.to_vec(),
+				exchange: exchange1.to_vec(),
+				asset_pair: (asset1.to_vec(), asset2.to_vec()),
+				price: 1000,
+			},
+		}));
+
+		// A fresh price triggers the task again
+		assert_ok!(AutomationPrice::update_asset_prices(
+			RuntimeOrigin::signed(creator.clone()),
+			vec![PriceUpdate {
+				chain: chain1.to_vec(),
+				exchange: exchange1.to_vec(),
+				asset1: asset1.to_vec(),
+				asset2: asset2.to_vec(),
+				price: 1000,
+				submitted_at: START_BLOCK_TIME as u128,
+				round: 2,
+			}],
+		));
+		AutomationPrice::trigger_tasks(Weight::from_parts(10_000_000_000, 0));
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskTriggered {
+			owner_id: creator.clone(),
+			task_id: task.task_id,
+			asset_pair: (asset1.to_vec(), asset2.to_vec()),
+			trigger_price: 1000,
+			oracle_round: 2,
+			condition: crate::TaskCondition::TargetPriceMatched {
+				chain: chain1.to_vec(),
+				exchange: exchange1.to_vec(),
+				asset_pair: (asset1.to_vec(), asset2.to_vec()),
+				price: 1000,
+			},
+		}));
+	})
+}
+
+// A price moving further than the circuit breaker allows halts the asset pair until it resumes
+#[test]
+fn test_circuit_breaker_halts_tasks_until_the_asset_resumes() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		let pair_key = (chain1.to_vec(), exchange1.to_vec(), (asset1.to_vec(), asset2.to_vec()));
+		let circuit_breaker =
+			CircuitBreaker { max_deviation: Perbill::from_percent(20), cooldown: None };
+
+		// The price of pair1 is 1000, above the target of the task
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+		assert_ok!(AutomationPrice::set_circuit_breaker(
+			RawOrigin::Root.into(),
+			chain1.to_vec(),
+			exchange1.to_vec(),
+			asset1.to_vec(),
+			asset2.to_vec(),
+			Some(circuit_breaker),
+		));
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::CircuitBreakerUpdated {
+			chain: chain1.to_vec(),
+			exchange: exchange1.to_vec(),
+			asset1: asset1.to_vec(),
+			asset2: asset2.to_vec(),
+			circuit_breaker: Some(circuit_breaker),
+		}));
+		let task = build_update_trigger_task(&creator);
+		crate::Tasks::<Test>::insert(&creator, &task.task_id, &task);
+		AutomationPrice::insert_into_sorted_index(&task);
+
+		// A move of 20% stays within the circuit breaker, a move of 50% from there halts pair1
+		for (round, price) in [(2, 1200), (3, 1800)] {
+			assert_ok!(AutomationPrice::update_asset_prices(
+				RuntimeOrigin::signed(creator.clone()),
+				vec![PriceUpdate {
+					chain: chain1.to_vec(),
+					exchange: exchange1.to_vec(),
+					asset1: asset1.to_vec(),
+					asset2: asset2.to_vec(),
+					price,
+					submitted_at: START_BLOCK_TIME as u128,
+					round,
+				}],
+			));
 		}
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::PriceCircuitBroken {
+			chain: chain1.to_vec(),
+			exchange: exchange1.to_vec(),
+			asset_pair: (asset1.to_vec(), asset2.to_vec()),
+			previous_price: 1200,
+			price: 1800,
+		}));
+		assert_eq!(
+			AutomationPrice::get_halted_asset(&pair_key),
+			Some((START_BLOCK_TIME / 1000) as u128)
+		);
 
-		// After sweep there should only one task remain in queue
-		assert_eq!(Tasks::<Test>::iter().count(), 1);
+		AutomationPrice::trigger_tasks(Weight::from_parts(10_000_000_000, 0));
+		assert!(AutomationPrice::get_task_queue().is_empty());
+		assert!(AutomationPrice::get_sorted_tasks_above(&pair_key).is_some());
+
+		assert_noop!(
+			AutomationPrice::resume_asset(
+				RuntimeOrigin::signed(creator.clone()),
+				chain1.to_vec(),
+				exchange1.to_vec(),
+				asset1.to_vec(),
+				asset2.to_vec(),
+			),
+			sp_runtime::DispatchError::BadOrigin,
+		);
+		assert_noop!(
+			AutomationPrice::resume_asset(
+				RawOrigin::Root.into(),
+				chain2.to_vec(),
+				exchange1.to_vec(),
+				asset2.to_vec(),
+				asset3.to_vec(),
+			),
+			Error::<Test>::AssetNotHalted,
+		);
+		assert_ok!(AutomationPrice::resume_asset(
+			RawOrigin::Root.into(),
+			chain1.to_vec(),
+			exchange1.to_vec(),
+			asset1.to_vec(),
+			asset2.to_vec(),
+		));
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::AssetResumed {
+			chain: chain1.to_vec(),
+			exchange: exchange1.to_vec(),
+			asset_pair: (asset1.to_vec(), asset2.to_vec()),
+		}));
+
+		AutomationPrice::trigger_tasks(Weight::from_parts(10_000_000_000, 0));
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskTriggered {
+			owner_id: creator.clone(),
+			task_id: task.task_id,
+			asset_pair: (asset1.to_vec(), asset2.to_vec()),
+			trigger_price: 1800,
+			oracle_round: 3,
+			condition: crate::TaskCondition::TargetPriceMatched {
+				chain: chain1.to_vec(),
+				exchange: exchange1.to_vec(),
+				asset_pair: (asset1.to_vec(), asset2.to_vec()),
+				price: 1800,
+			},
+		}));
+	})
+}
+
+// A halted asset pair resumes on its own once the cooldown of its circuit breaker is over
+#[test]
+fn test_circuit_breaker_resumes_the_asset_after_its_cooldown() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		let pair_key = (chain1.to_vec(), exchange1.to_vec(), (asset1.to_vec(), asset2.to_vec()));
+		let halted_at = (START_BLOCK_TIME / 1000) as u128;
+
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+		assert_noop!(
+			AutomationPrice::set_circuit_breaker(
+				RuntimeOrigin::signed(creator.clone()),
+				chain1.to_vec(),
+				exchange1.to_vec(),
+				asset1.to_vec(),
+				asset2.to_vec(),
+				None,
+			),
+			sp_runtime::DispatchError::BadOrigin,
+		);
+		assert_ok!(AutomationPrice::set_circuit_breaker(
+			RawOrigin::Root.into(),
+			chain1.to_vec(),
+			exchange1.to_vec(),
+			asset1.to_vec(),
+			asset2.to_vec(),
+			Some(CircuitBreaker { max_deviation: Perbill::from_percent(10), cooldown: Some(60) }),
+		));
+		assert_ok!(AutomationPrice::update_asset_prices(
+			RuntimeOrigin::signed(creator.clone()),
+			vec![PriceUpdate {
+				chain: chain1.to_vec(),
+				exchange: exchange1.to_vec(),
+				asset1: asset1.to_vec(),
+				asset2: asset2.to_vec(),
+				price: 500,
+				submitted_at: START_BLOCK_TIME as u128,
+				round: 2,
+			}],
+		));
+		assert_eq!(AutomationPrice::get_halted_asset(&pair_key), Some(halted_at));
+
+		assert!(AutomationPrice::is_asset_halted(
+			chain1,
+			exchange1,
+			&(asset1.to_vec(), asset2.to_vec()),
+			halted_at + 59,
+		));
+		assert!(!AutomationPrice::is_asset_halted(
+			chain1,
+			exchange1,
+			&(asset1.to_vec(), asset2.to_vec()),
+			halted_at + 60,
+		));
+		assert_eq!(AutomationPrice::get_halted_asset(&pair_key), None);
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::AssetResumed {
+			chain: chain1.to_vec(),
+			exchange: exchange1.to_vec(),
+			asset_pair: (asset1.to_vec(), asset2.to_vec()),
+		}));
+	})
+}
+
+// A paused task is kept out of the sorted task index and is not triggered until it is resumed
+#[test]
+fn test_pause_and_resume_task() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		get_xcmp_funds(creator.clone());
+		let task = build_update_trigger_task(&creator);
+		assert_ok!(AutomationPrice::validate_and_schedule_task(task.clone()));
+		let key = (
+			chain1.to_vec(),
+			exchange1.to_vec(),
+			(asset1.to_vec(), asset2.to_vec()),
+			TriggerFunction::Gt,
+		);
 
-		// The task should be removed from the SortedTasksIndex
-		assert_eq!(
-			0,
-			AutomationPrice::get_sorted_tasks_index((
-				chain1.to_vec(),
-				exchange1.to_vec(),
-				(asset1.to_vec(), asset2.to_vec()),
-				"gt".as_bytes().to_vec(),
-			))
-			.expect("missing tasks sorted by price data")
-			.get(&price_target1)
-			.map_or(0, |v| v.iter().len())
+		assert_noop!(
+			AutomationPrice::resume_task(RuntimeOrigin::signed(creator.clone()), task.task_id),
+			Error::<Test>::TaskNotPaused,
 		);
-		// The task should be removed from the SortedTasksIndex
-		assert_eq!(
-			1,
-			AutomationPrice::get_sorted_tasks_index((
-				chain1.to_vec(),
-				exchange1.to_vec(),
-				(asset1.to_vec(), asset2.to_vec()),
-				"lt".as_bytes().to_vec(),
-			))
-			.expect("missing tasks sorted by price data")
-			.get(&price_target2)
-			.map_or(0, |v| v.iter().len())
+		assert_ok!(AutomationPrice::pause_task(
+			RuntimeOrigin::signed(creator.clone()),
+			task.task_id,
+		));
+		assert!(AutomationPrice::is_task_paused(&creator, &task.task_id));
+		assert_eq!(AutomationPrice::get_sorted_tasks_index(key.clone()), None);
+		assert_last_event(RuntimeEvent::AutomationPrice(crate::Event::TaskPaused {
+			owner_id: creator.clone(),
+			task_id: task.task_id,
+		}));
+		assert_noop!(
+			AutomationPrice::pause_task(RuntimeOrigin::signed(creator.clone()), task.task_id),
+			Error::<Test>::TaskAlreadyPaused,
 		);
 
-		// The task stat should be changed
+		// The price crosses the target of the task while it is paused
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+		AutomationPrice::trigger_tasks(Weight::from_parts(10_000_000_000, 0));
+		assert!(TransactedXcms::get().is_empty());
+		assert!(AutomationPrice::get_task(&creator, &task.task_id).is_some());
+
+		assert_ok!(AutomationPrice::resume_task(
+			RuntimeOrigin::signed(creator.clone()),
+			task.task_id,
+		));
+		assert!(!AutomationPrice::is_task_paused(&creator, &task.task_id));
 		assert_eq!(
-			1,
-			AutomationPrice::get_task_stat(StatType::TotalTasksOverall).map_or(0, |v| v),
-			"total task count is incorrect"
+			AutomationPrice::get_sorted_tasks_index(key).unwrap().get(&123),
+			Some(&vec![(creator.clone(), task.task_id)])
 		);
-		assert_eq!(
-			1,
-			AutomationPrice::get_account_stat(other_creator, StatType::TotalTasksPerAccount)
-				.map_or(0, |v| v),
-			"total task count is incorrect"
+		assert_last_event(RuntimeEvent::AutomationPrice(crate::Event::TaskResumed {
+			owner_id: creator.clone(),
+			task_id: task.task_id,
+		}));
+
+		AutomationPrice::trigger_tasks(Weight::from_parts(10_000_000_000, 0));
+		assert_eq!(TransactedXcms::get().len(), 1);
+		assert!(AutomationPrice::get_task(&creator, &task.task_id).is_none());
+	})
+}
+
+// Only the owner of a task can pause it, and a paused task can still be cancelled
+#[test]
+fn test_pause_task_is_limited_to_the_owner() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		get_xcmp_funds(creator.clone());
+		let task = build_update_trigger_task(&creator);
+		assert_ok!(AutomationPrice::validate_and_schedule_task(task.clone()));
+
+		assert_noop!(
+			AutomationPrice::pause_task(RuntimeOrigin::signed(AccountId32::new(BOB)), task.task_id),
+			Error::<Test>::TaskNotFound,
 		);
+
+		assert_ok!(AutomationPrice::pause_task(
+			RuntimeOrigin::signed(creator.clone()),
+			task.task_id,
+		));
+		assert_ok!(AutomationPrice::cancel_task(
+			RuntimeOrigin::signed(creator.clone()),
+			task.task_id,
+		));
+		assert!(!AutomationPrice::is_task_paused(&creator, &task.task_id));
 	})
 }
 
-// Test swap partially data, and leave the rest of sorted index remain intact
+// The runtime API finds a task by its id alone and reports where it stands
 #[test]
-fn test_sweep_expired_task_partially() {
+fn test_find_task_and_get_task_status() {
 	new_test_ext(START_BLOCK_TIME).execute_with(|| {
 		let creator = AccountId32::new(ALICE);
-		let _other_creator = AccountId32::new(BOB);
-		let para_id: u32 = 1000;
+		get_xcmp_funds(creator.clone());
+		let task = build_update_trigger_task(&creator);
+		assert_ok!(AutomationPrice::validate_and_schedule_task(task.clone()));
 
-		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
-		let destination = MultiLocation::new(1, X1(Parachain(para_id)));
-		let schedule_fee = MultiLocation::default();
-		let execution_fee = AssetPayment {
-			asset_location: MultiLocation::new(1, X1(Parachain(para_id))).into(),
-			amount: MOCK_XCMP_FEE,
-		};
-		let encoded_call_weight = Weight::from_ref_time(100_000);
-		let overall_weight = Weight::from_ref_time(200_000);
+		assert_eq!(AutomationPrice::find_task(&task.task_id), Some(task.clone()));
+		assert_eq!(AutomationPrice::find_task(&task_id_of("unknown")), None);
+		assert_eq!(AutomationPrice::get_task_status(&task), TaskStatus::Scheduled);
 
-		let expired_task_gen = 11;
-		let price_target1 = 2000;
-		for i in 1..expired_task_gen {
-			// schedule task that has expired
-			get_xcmp_funds(creator.clone());
-			let task = Task::<Test> {
-				owner_id: creator.clone(),
-				task_id: format!("123-0-{:?}", i).as_bytes().to_vec(),
-				chain: chain1.to_vec(),
-				exchange: exchange1.to_vec(),
-				asset_pair: (asset1.to_vec(), asset2.to_vec()),
-				expired_at: START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND + 10 + i * 10,
-				trigger_function: "gt".as_bytes().to_vec(),
-				trigger_params: vec![price_target1],
-				action: Action::XCMP {
-					destination,
-					schedule_fee,
-					execution_fee: execution_fee.clone(),
-					encoded_call: vec![1, 2, 3],
-					encoded_call_weight,
-					overall_weight,
-					schedule_as: None,
-					instruction_sequence: InstructionSequence::PayThroughRemoteDerivativeAccount,
-				},
-			};
-			assert_ok!(AutomationPrice::validate_and_schedule_task(task.clone()));
-		}
+		crate::TaskQueue::<Test>::put(vec![(creator.clone(), task.task_id)]);
+		assert_eq!(AutomationPrice::get_task_status(&task), TaskStatus::Queued);
+		crate::TaskQueue::<Test>::kill();
 
-		// Now we set timestamp to a later point
-		Timestamp::set_timestamp(
-			START_BLOCK_TIME.saturating_add((3600 + 6 * 10) * 1000).try_into().unwrap(),
+		crate::XcmpRetryQueue::<Test>::insert(
+			3,
+			vec![XcmpRetry { owner_id: creator.clone(), task_id: task.task_id, attempts: 1 }],
 		);
+		assert_eq!(AutomationPrice::get_task_status(&task), TaskStatus::RetryingXcmSend);
+		crate::XcmpRetryQueue::<Test>::remove(3);
 
-		assert_eq!(
-			10,
-			AutomationPrice::get_sorted_tasks_index((
+		assert_ok!(AutomationPrice::pause_task(
+			RuntimeOrigin::signed(creator.clone()),
+			task.task_id,
+		));
+		assert_eq!(AutomationPrice::get_task_status(&task), TaskStatus::Paused);
+
+		Timestamp::set_timestamp(START_BLOCK_TIME + 3_601_000);
+		assert_eq!(AutomationPrice::get_task_status(&task), TaskStatus::Expired);
+	})
+}
+
+#[test]
+fn test_asset_registered_by_a_signed_account_holds_a_deposit() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let alice = AccountId32::new(ALICE);
+		let bob = AccountId32::new(BOB);
+		let key = (chain1.to_vec(), exchange1.to_vec(), (asset1.to_vec(), asset2.to_vec()));
+		let initialize = |origin| {
+			AutomationPrice::initialize_asset(
+				origin,
 				chain1.to_vec(),
 				exchange1.to_vec(),
-				(asset1.to_vec(), asset2.to_vec()),
-				"gt".as_bytes().to_vec(),
-			))
-			.map_or(0, |v| v.get(&price_target1).unwrap().iter().len())
+				asset1.to_vec(),
+				asset2.to_vec(),
+				10,
+				vec![alice.clone()],
+			)
+		};
+		let delete = |origin| {
+			AutomationPrice::delete_asset(
+				origin,
+				chain1.to_vec(),
+				exchange1.to_vec(),
+				asset1.to_vec(),
+				asset2.to_vec(),
+			)
+		};
+
+		assert_noop!(
+			initialize(RuntimeOrigin::signed(alice.clone())),
+			Error::<Test>::InsufficientBalance
 		);
 
-		assert_eq!(10, AutomationPrice::get_sorted_tasks_by_expiration().len());
+		_ = Balances::deposit_creating(&alice, 5_000);
+		assert_ok!(initialize(RuntimeOrigin::signed(alice.clone())));
+		assert_eq!(Balances::reserved_balance(&alice), AssetDeposit::get());
+		assert_eq!(
+			AutomationPrice::get_asset_ownership(&key),
+			Some(AssetOwnership { owner: alice.clone(), deposit: AssetDeposit::get() })
+		);
+		assert_eq!(AutomationPrice::get_asset_count(), 1);
+
+		// Only its owner or the asset admin origin can delete the asset pair
+		assert_noop!(delete(RuntimeOrigin::signed(bob)), Error::<Test>::NotAssetOwner);
+		assert_ok!(delete(RuntimeOrigin::signed(alice.clone())));
+		assert_eq!(Balances::reserved_balance(&alice), 0);
+		assert_eq!(Balances::free_balance(&alice), 5_000);
+		assert_eq!(AutomationPrice::get_asset_ownership(&key), None);
+		assert_eq!(AutomationPrice::get_asset_count(), 0);
+	})
+}
 
-		// remaining_weight in on_idle block
-		let remain_weight = 100_000_000_000;
-		AutomationPrice::sweep_expired_task(Weight::from_ref_time(remain_weight));
+#[test]
+fn test_delete_asset_by_the_asset_admin_origin_slashes_the_deposit() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let alice = AccountId32::new(ALICE);
+		_ = Balances::deposit_creating(&alice, 5_000);
+		assert_ok!(AutomationPrice::initialize_asset(
+			RuntimeOrigin::signed(alice.clone()),
+			chain1.to_vec(),
+			exchange1.to_vec(),
+			asset1.to_vec(),
+			asset2.to_vec(),
+			10,
+			vec![alice.clone()],
+		));
 
-		// The task should be removed from the SortedTasksIndex
-		assert_eq!(
-			5,
-			AutomationPrice::get_sorted_tasks_index((
+		assert_ok!(AutomationPrice::delete_asset(
+			RawOrigin::Root.into(),
+			chain1.to_vec(),
+			exchange1.to_vec(),
+			asset1.to_vec(),
+			asset2.to_vec(),
+		));
+		assert_eq!(Balances::reserved_balance(&alice), 0);
+		assert_eq!(Balances::free_balance(&alice), 5_000 - AssetDeposit::get());
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::AssetDepositSlashed {
+			chain: chain1.to_vec(),
+			exchange: exchange1.to_vec(),
+			asset1: asset1.to_vec(),
+			asset2: asset2.to_vec(),
+			owner: alice,
+			amount: AssetDeposit::get(),
+		}));
+	})
+}
+
+#[test]
+fn test_initialize_asset_is_limited_to_max_assets() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let sender = AccountId32::new(ALICE);
+		MaxAssets::set(1);
+		let initialize = |asset: &[u8]| {
+			AutomationPrice::initialize_asset(
+				RawOrigin::Root.into(),
 				chain1.to_vec(),
 				exchange1.to_vec(),
-				(asset1.to_vec(), asset2.to_vec()),
-				"gt".as_bytes().to_vec(),
-			))
-			.expect("missing tasks sorted by price data")
-			.get(&price_target1)
-			.map_or(0, |v| v.iter().len())
-		);
+				asset.to_vec(),
+				asset2.to_vec(),
+				10,
+				vec![sender.clone()],
+			)
+		};
 
-		// these task all get sweeo
-		for i in 1..5 {
-			assert_eq!(
-				0,
-				AutomationPrice::get_sorted_tasks_by_expiration()
-					.get(&(START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND + 10 + i * 10))
-					.map_or(0, |v| v.len()),
-			);
-		}
+		assert_ok!(initialize(asset1));
+		assert_noop!(initialize(asset3), Error::<Test>::AssetLimitReached);
 
-		// these slot remian untouch
-		for i in 6..10 {
-			assert_eq!(
-				1,
-				AutomationPrice::get_sorted_tasks_by_expiration()
-					.get(&(START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND + 10 + i * 10))
-					.map_or(0, |v| v.len()),
-			);
-		}
+		// Deleting an asset pair makes room for another one
+		assert_ok!(AutomationPrice::delete_asset(
+			RawOrigin::Root.into(),
+			chain1.to_vec(),
+			exchange1.to_vec(),
+			asset1.to_vec(),
+			asset2.to_vec(),
+		));
+		assert_ok!(initialize(asset3));
+		assert_eq!(AutomationPrice::get_asset_count(), 1);
 	})
 }
 
 #[test]
-fn test_schedule_return_error_when_reaching_max_tasks_overall_limit() {
+fn test_migrate_to_v6_counts_the_registered_assets() {
 	new_test_ext(START_BLOCK_TIME).execute_with(|| {
-		let para_id: u32 = 1000;
-		let creator = AccountId32::new(ALICE);
-		let call: Vec<u8> = vec![2, 4, 5];
-		let destination = MultiLocation::new(1, X1(Parachain(para_id)));
+		let sender = AccountId32::new(ALICE);
+		setup_asset(&sender, chain1.to_vec());
+		setup_asset(&sender, chain2.to_vec());
+		crate::AssetCount::<Test>::kill();
 
-		setup_asset(&creator, chain1.to_vec());
+		StorageVersion::new(5).put::<AutomationPrice>();
+		MigrateToV6::<Test>::on_runtime_upgrade();
+		assert_eq!(AutomationPrice::on_chain_storage_version(), 6);
+		assert_eq!(AutomationPrice::get_asset_count(), 2);
+	})
+}
 
-		TaskStats::<Test>::insert(StatType::TotalTasksOverall, 1_000_000_000);
+#[test]
+fn test_time_window_spanning_midnight() {
+	let day = 33198768000u128;
+	let window = TimeWindow { start: 20 * 3600, end: 8 * 3600 };
+	assert!(window.is_valid());
+	assert!(window.contains(day + 22 * 3600));
+	assert!(window.contains(day + 3600));
+	assert!(!window.contains(day + 8 * 3600));
+	assert_eq!(window.next_open(day + 3600), day + 3600);
+	assert_eq!(window.next_open(day + 12 * 3600), day + 20 * 3600);
+
+	let window = TimeWindow { start: 8 * 3600, end: 20 * 3600 };
+	assert_eq!(window.next_open(day + 21 * 3600), day + 86_400 + 8 * 3600);
+	assert!(!TimeWindow { start: 3600, end: 3600 }.is_valid());
+	assert!(!TimeWindow { start: 0, end: 86_400 }.is_valid());
+}
+
+// A task triggered outside of its time window is deferred until the window opens
+#[test]
+fn test_task_triggered_outside_of_its_time_window_is_deferred() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		get_xcmp_funds(creator.clone());
+		let task = build_update_trigger_task(&creator);
+		assert_ok!(AutomationPrice::validate_and_schedule_task(task.clone()));
 
+		// The block time is midnight, and the task can only run from 00:30 to 00:50
 		assert_noop!(
-			AutomationPrice::schedule_xcmp_task(
-				RuntimeOrigin::signed(creator),
-				chain1.to_vec(),
-				exchange1.to_vec(),
-				asset1.to_vec(),
-				asset2.to_vec(),
-				START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND + 3600,
-				"gt".as_bytes().to_vec(),
-				vec!(100),
-				Box::new(destination.into()),
-				Box::new(NATIVE_LOCATION.into()),
-				Box::new(AssetPayment {
-					asset_location: MultiLocation::new(0, Here).into(),
-					amount: 10000000000000
-				}),
-				call,
-				Weight::from_ref_time(100_000),
-				Weight::from_ref_time(200_000)
+			AutomationPrice::set_task_time_window(
+				RuntimeOrigin::signed(creator.clone()),
+				task.task_id,
+				Some(TimeWindow { start: 1800, end: 1800 }),
 			),
-			Error::<Test>::MaxTasksReached,
+			Error::<Test>::InvalidTimeWindow,
 		);
+		let window = TimeWindow { start: 1800, end: 3000 };
+		assert_ok!(AutomationPrice::set_task_time_window(
+			RuntimeOrigin::signed(creator.clone()),
+			task.task_id,
+			Some(window),
+		));
+		assert_eq!(AutomationPrice::get_task_time_window(&creator, &task.task_id), Some(window));
+
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+		AutomationPrice::trigger_tasks(Weight::from_parts(10_000_000_000, 0));
+		let resume_at = START_BLOCK_TIME as u128 / 1000 + 1800;
+		assert!(TransactedXcms::get().is_empty());
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskDeferred {
+			owner_id: creator.clone(),
+			task_id: task.task_id,
+			resume_at,
+		}));
+		assert_eq!(AutomationPrice::get_task_status(&task), TaskStatus::CoolingDown);
+
+		// The task runs once its window opens, as the price still matches
+		Timestamp::set_timestamp(resume_at as u64 * 1000);
+		AutomationPrice::trigger_tasks(Weight::from_parts(10_000_000_000, 0));
+		assert_eq!(TransactedXcms::get().len(), 1);
+		assert!(AutomationPrice::get_task(&creator, &task.task_id).is_none());
+		assert_eq!(AutomationPrice::get_task_time_window(&creator, &task.task_id), None);
 	})
 }
 
+// A task scheduled again with the provided id of a task that ran does not inherit its window
 #[test]
-fn test_schedule_return_error_when_reaching_max_account_tasks_limit() {
+fn test_rescheduled_task_does_not_inherit_the_time_window_of_a_completed_task() {
 	new_test_ext(START_BLOCK_TIME).execute_with(|| {
-		let para_id: u32 = 1000;
-		let creator = AccountId32::new(ALICE);
-		let call: Vec<u8> = vec![2, 4, 5];
-		let destination = MultiLocation::new(1, X1(Parachain(para_id)));
-
-		setup_asset(&creator, chain1.to_vec());
-
-		AccountStats::<Test>::insert(creator.clone(), StatType::TotalTasksPerAccount, 1_000);
+		let creator = AccountId32::new(ALICE);
 
-		assert_noop!(
-			AutomationPrice::schedule_xcmp_task(
-				RuntimeOrigin::signed(creator),
+		// The price of pair1 starts at 1000, above the target of the task
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+		Balances::force_set_balance(RawOrigin::Root.into(), creator.clone(), 1_000_000_000_000_000)
+			.unwrap();
+
+		let provided_id = b"order-1".to_vec();
+		let task_id = AutomationPrice::task_id_for(&creator, Some(provided_id.clone()));
+		let schedule = || {
+			AutomationPrice::schedule_dynamic_dispatch_task(
+				RuntimeOrigin::signed(creator.clone()),
 				chain1.to_vec(),
 				exchange1.to_vec(),
 				asset1.to_vec(),
 				asset2.to_vec(),
 				START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
 				"gt".as_bytes().to_vec(),
-				vec!(100),
-				Box::new(destination.into()),
+				vec![500],
 				Box::new(NATIVE_LOCATION.into()),
-				Box::new(AssetPayment {
-					asset_location: MultiLocation::new(0, Here).into(),
-					amount: MOCK_XCMP_FEE
-				}),
-				call,
-				Weight::from_ref_time(100_000),
-				Weight::from_ref_time(200_000)
-			),
-			Error::<Test>::MaxTasksPerAccountReached,
-		);
+				Box::new(RuntimeCall::System(frame_system::Call::remark_with_event {
+					remark: vec![1],
+				})),
+				Some(provided_id.clone()),
+			)
+		};
+
+		// The block time is midnight, and the task can only run until 00:30
+		assert_ok!(schedule());
+		assert_ok!(AutomationPrice::set_task_time_window(
+			RuntimeOrigin::signed(creator.clone()),
+			task_id,
+			Some(TimeWindow { start: 0, end: 1800 }),
+		));
+		AutomationPrice::trigger_tasks(Weight::from_parts(10_000_000_000, 0));
+		assert!(AutomationPrice::get_task(&creator, &task_id).is_none());
+		assert_eq!(AutomationPrice::get_task_time_window(&creator, &task_id), None);
+
+		// Scheduled again at 00:40 without a window, the task runs right away
+		Timestamp::set_timestamp(START_BLOCK_TIME + 2_400_000);
+		System::reset_events();
+		assert_ok!(schedule());
+		assert_eq!(AutomationPrice::get_task_time_window(&creator, &task_id), None);
+		AutomationPrice::trigger_tasks(Weight::from_parts(10_000_000_000, 0));
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskExecuted {
+			owner_id: creator.clone(),
+			task_id,
+			action_outcome: ActionOutcome::Dispatched,
+		}));
+		assert!(AutomationPrice::get_task(&creator, &task_id).is_none());
 	})
 }
 
-// Test when price moves, the TaskQueue will be populated with the right task id
-//
-// In this test we will first setup 3 tasks for 3 pairs
-//  task1 for pair1
-//  task2 for pair2
-//  task3 for pair3
-//
-//  when we will adjust the price of the pair, we purposely let task2 price not match
-//  so we will test that task1, task3 will be moved into the queue accordingly.
-//
-//  then finally schedule a new task4, for pair3, but its price will match. we will test
-//  that task4 are moved to TaskQueue. task2 won't be moved
-//
-//  The purpose of this test is to simulae a few tasks being trigger accordingly to their
-//  price movement. We also verify that task  won't have its price match, will never get
-//  trigger
+// A condition on the price of an asset pair for `set_task_conditions`
+fn price_condition(
+	chain: &[u8],
+	asset_pair: (&[u8], &[u8]),
+	trigger_function: &str,
+	target_price: u128,
+) -> PriceCondition {
+	PriceCondition {
+		chain: chain.to_vec(),
+		exchange: exchange1.to_vec(),
+		asset_pair: (asset_pair.0.to_vec(), asset_pair.1.to_vec()),
+		trigger_function: trigger_function.as_bytes().to_vec(),
+		trigger_params: vec![target_price],
+	}
+}
+
 #[test]
-fn test_shift_tasks_movement_through_price_changes() {
+fn test_set_task_conditions_validates_the_conditions() {
 	new_test_ext(START_BLOCK_TIME).execute_with(|| {
-		// TODO: Setup fund once we add fund check and weight
-		let para_id: u32 = 1000;
 		let creator = AccountId32::new(ALICE);
-		let call: Vec<u8> = vec![2, 4, 5];
-		let destination = MultiLocation::new(1, X1(Parachain(para_id)));
-
+		get_xcmp_funds(creator.clone());
 		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+		let task = build_update_trigger_task(&creator);
+		assert_ok!(AutomationPrice::validate_and_schedule_task(task.clone()));
+
+		let condition = price_condition(chain2, (asset2, asset3), "lt", 4000);
+		for (conditions, error) in [
+			(vec![], Error::<Test>::InvalidConditionCount),
+			(vec![condition.clone(); 3], Error::<Test>::InvalidConditionCount),
+			(
+				vec![price_condition(chain2, (asset3, asset2), "lt", 4000)],
+				Error::<Test>::AssetNotSupported,
+			),
+			(
+				vec![price_condition(chain2, (asset2, asset3), "unknown", 4000)],
+				Error::<Test>::InvalidTriggerParams,
+			),
+		] {
+			assert_noop!(
+				AutomationPrice::set_task_conditions(
+					RuntimeOrigin::signed(creator.clone()),
+					task.task_id,
+					Some(CompoundCondition { combinator: Combinator::And, conditions }),
+				),
+				error,
+			);
+		}
 
-		let base_price = 10_000_u128;
+		// The task waits in the unsorted tasks of both asset pairs, and back in the sorted task
+		// index once its conditions are removed
+		let compound =
+			CompoundCondition { combinator: Combinator::And, conditions: vec![condition] };
+		assert_ok!(AutomationPrice::set_task_conditions(
+			RuntimeOrigin::signed(creator.clone()),
+			task.task_id,
+			Some(compound.clone()),
+		));
+		assert_eq!(AutomationPrice::get_task_conditions(&creator, &task.task_id), Some(compound));
+		let task_address = vec![(creator.clone(), task.task_id)];
+		let pair1 = (chain1.to_vec(), exchange1.to_vec(), (asset1.to_vec(), asset2.to_vec()));
+		let pair2 = (chain2.to_vec(), exchange1.to_vec(), (asset2.to_vec(), asset3.to_vec()));
+		assert_eq!(AutomationPrice::get_unsorted_tasks(&pair1), Some(task_address.clone()));
+		assert_eq!(AutomationPrice::get_unsorted_tasks(&pair2), Some(task_address));
+		assert_eq!(AutomationPrice::get_task_status(&task), TaskStatus::Scheduled);
+
+		assert_ok!(AutomationPrice::set_task_conditions(
+			RuntimeOrigin::signed(creator.clone()),
+			task.task_id,
+			None,
+		));
+		assert_eq!(AutomationPrice::get_task_conditions(&creator, &task.task_id), None);
+		assert_eq!(AutomationPrice::get_unsorted_tasks(&pair1), None);
+		assert_eq!(AutomationPrice::get_unsorted_tasks(&pair2), None);
+		assert_eq!(AutomationPrice::get_task_status(&task), TaskStatus::Scheduled);
+	})
+}
 
-		// Lets setup 3 tasks
+#[test]
+fn test_task_with_and_conditions_runs_when_the_other_asset_pair_moves() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
 		get_xcmp_funds(creator.clone());
-		assert_ok!(AutomationPrice::schedule_xcmp_task(
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+
+		// TUR/USDC > 123, which matches at 1000, AND USDC/KSM < 4000, which does not at 5000
+		let task = build_update_trigger_task(&creator);
+		assert_ok!(AutomationPrice::validate_and_schedule_task(task.clone()));
+		assert_ok!(AutomationPrice::set_task_conditions(
 			RuntimeOrigin::signed(creator.clone()),
-			chain1.to_vec(),
-			exchange1.to_vec(),
-			asset1.to_vec(),
-			asset2.to_vec(),
-			START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
-			"gt".as_bytes().to_vec(),
-			vec!(base_price + 1000),
-			Box::new(destination.into()),
-			Box::new(NATIVE_LOCATION.into()),
-			Box::new(AssetPayment {
-				asset_location: MultiLocation::new(0, Here).into(),
-				amount: MOCK_XCMP_FEE
+			task.task_id,
+			Some(CompoundCondition {
+				combinator: Combinator::And,
+				conditions: vec![price_condition(chain2, (asset2, asset3), "lt", 4000)],
 			}),
-			call.clone(),
-			Weight::from_ref_time(100_000),
-			Weight::from_ref_time(200_000)
 		));
+		AutomationPrice::trigger_tasks(Weight::from_parts(10_000_000_000, 0));
+		assert!(TransactedXcms::get().is_empty());
+		assert_eq!(AutomationPrice::get_task_status(&task), TaskStatus::Scheduled);
 
-		get_xcmp_funds(creator.clone());
-		assert_ok!(AutomationPrice::schedule_xcmp_task(
+		// Only the price of the other asset pair moves
+		assert_ok!(AutomationPrice::update_asset_prices(
 			RuntimeOrigin::signed(creator.clone()),
-			chain2.to_vec(),
-			exchange1.to_vec(),
-			asset2.to_vec(),
-			asset3.to_vec(),
-			START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
-			"gt".as_bytes().to_vec(),
-			vec!(base_price + 900),
-			Box::new(destination.into()),
-			Box::new(NATIVE_LOCATION.into()),
-			Box::new(AssetPayment {
-				asset_location: MultiLocation::new(0, Here).into(),
-				amount: MOCK_XCMP_FEE
-			}),
-			call.clone(),
-			Weight::from_parts(100_000, 0),
-			Weight::from_parts(200_000, 0)
+			vec![PriceUpdate {
+				chain: chain2.to_vec(),
+				exchange: exchange1.to_vec(),
+				asset1: asset2.to_vec(),
+				asset2: asset3.to_vec(),
+				price: 3000,
+				submitted_at: START_BLOCK_TIME as u128,
+				round: 2,
+			}],
 		));
+		AutomationPrice::trigger_tasks(Weight::from_parts(10_000_000_000, 0));
+		assert_eq!(TransactedXcms::get().len(), 1);
+		assert!(AutomationPrice::get_task(&creator, &task.task_id).is_none());
+		assert_eq!(AutomationPrice::get_task_conditions(&creator, &task.task_id), None);
+		let pair2 = (chain2.to_vec(), exchange1.to_vec(), (asset2.to_vec(), asset3.to_vec()));
+		assert_eq!(AutomationPrice::get_unsorted_tasks(&pair2), None);
+	})
+}
 
+#[test]
+fn test_task_with_or_conditions_runs_when_any_condition_matches() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
 		get_xcmp_funds(creator.clone());
-		assert_ok!(AutomationPrice::schedule_xcmp_task(
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+
+		// TUR/USDC > 5000, which does not match at 1000, OR TUR/KSM > 9000, which does at 10_000
+		let mut task = build_update_trigger_task(&creator);
+		task.trigger_params = vec![5000];
+		assert_ok!(AutomationPrice::validate_and_schedule_task(task.clone()));
+		assert_ok!(AutomationPrice::set_task_conditions(
 			RuntimeOrigin::signed(creator.clone()),
-			chain2.to_vec(),
-			exchange1.to_vec(),
-			asset1.to_vec(),
-			asset3.to_vec(),
-			START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND + 6000,
-			"gt".as_bytes().to_vec(),
-			vec!(base_price + 1000),
-			Box::new(destination.into()),
-			Box::new(NATIVE_LOCATION.into()),
-			Box::new(AssetPayment {
-				asset_location: MultiLocation::new(0, Here).into(),
-				amount: MOCK_XCMP_FEE
+			task.task_id,
+			Some(CompoundCondition {
+				combinator: Combinator::Or,
+				conditions: vec![
+					price_condition(chain2, (asset2, asset3), "lt", 4000),
+					price_condition(chain2, (asset1, asset3), "gt", 9000),
+				],
 			}),
-			call.clone(),
-			Weight::from_parts(100_000, 0),
-			Weight::from_parts(200_000, 0)
 		));
 
-		let task_ids = get_task_ids_from_events();
-		let task_id1 = task_ids.get(task_ids.len().wrapping_sub(3)).unwrap();
-		// let _task_id2 = task_ids.get(task_ids.len().wrapping_sub(2)).unwrap();
-		let task_id3 = task_ids.get(task_ids.len().wrapping_sub(1)).unwrap();
-
-		// at this moment our task queue is empty
-		// There is schedule tasks, but no tasks in the queue at this moment, because shift_tasks
-		// has not run yet
-		assert!(AutomationPrice::get_task_queue().is_empty());
+		AutomationPrice::trigger_tasks(Weight::from_parts(10_000_000_000, 0));
+		assert_eq!(TransactedXcms::get().len(), 1);
+		assert!(AutomationPrice::get_task(&creator, &task.task_id).is_none());
+		assert_eq!(AutomationPrice::get_task_conditions(&creator, &task.task_id), None);
+		assert_ok!(AutomationPrice::do_try_state());
+	})
+}
 
-		// shift_tasks move task from registry to the queue
-		// At this moment, The price doesn't match the target so there is no change in our tasks
-		AutomationPrice::shift_tasks(Weight::from_parts(1_000_000_000, 0));
-		assert!(AutomationPrice::get_task_queue().is_empty());
-		let sorted_task_index = AutomationPrice::get_sorted_tasks_index((
-			chain1.to_vec(),
-			exchange1.to_vec(),
-			(asset1.to_vec(), asset2.to_vec()),
-			"gt".as_bytes().to_vec(),
-		));
-		assert_eq!(sorted_task_index.map_or_else(|| 0, |x| x.len()), 1);
+#[test]
+fn test_schedule_is_limited_to_max_tasks_per_asset_pair() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		MaxTasksPerAssetPair::set(2);
+		let creator = AccountId32::new(ALICE);
+		get_xcmp_funds(creator.clone());
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+		let asset_key = (chain1.to_vec(), exchange1.to_vec(), (asset1.to_vec(), asset2.to_vec()));
+		let task_with_id = |task_id: &str| Task::<Test> {
+			task_id: task_id_of(task_id),
+			..build_update_trigger_task(&creator)
+		};
 
-		// now we change price of pair1 to higher than its target price, while keeping pair2/pair3 low enough,
-		// only task_id1 will be moved to the queue.
-		// The target price for those respectively tasks are 10100, 10900, 102000 in their pair
-		// Therefore after running this price update, first task task_id1 are moved into TaskQueue
-		let new_pair_1_price: u128 = base_price + 2000;
-		let new_pair_2_price: u128 = 10_u128;
-		let mut new_pair_3_price: u128 = 300_u128;
-		assert_ok!(AutomationPrice::update_asset_prices(
-			RuntimeOrigin::signed(creator.clone()),
-			vec!(chain1.to_vec(), chain2.to_vec(), chain2.to_vec()),
-			vec!(exchange1.to_vec(), exchange1.to_vec(), exchange1.to_vec()),
-			vec!(asset1.to_vec(), asset2.to_vec(), asset1.to_vec()),
-			vec!(asset2.to_vec(), asset3.to_vec(), asset3.to_vec()),
-			vec!(new_pair_1_price, new_pair_2_price, new_pair_3_price),
-			vec!(START_BLOCK_TIME as u128, START_BLOCK_TIME as u128, START_BLOCK_TIME as u128),
-			vec!(1, 2, 3),
-		));
-		AutomationPrice::shift_tasks(Weight::from_parts(1_000_000_000, 0));
-		assert_eq!(AutomationPrice::get_task_queue(), vec![(creator.clone(), task_id1.clone())]);
-		// The task are removed from SortedTasksIndex into the TaskQueue, therefore their length
-		// decrease to 0
-		assert_eq!(
-			AutomationPrice::get_sorted_tasks_index((
-				chain1.to_vec(),
-				exchange1.to_vec(),
-				(asset1.to_vec(), asset2.to_vec()),
-				"gt".as_bytes().to_vec(),
-			))
-			.map_or_else(|| 0, |x| x.len()),
-			0
+		assert_ok!(AutomationPrice::validate_and_schedule_task(task_with_id("123-0-1")));
+		assert_ok!(AutomationPrice::validate_and_schedule_task(task_with_id("123-0-2")));
+		assert_eq!(AutomationPrice::get_asset_task_count(&asset_key), 2);
+		assert_last_event(RuntimeEvent::AutomationPrice(crate::Event::AssetTaskCapReached {
+			chain: chain1.to_vec(),
+			exchange: exchange1.to_vec(),
+			asset1: asset1.to_vec(),
+			asset2: asset2.to_vec(),
+			cap: 2,
+		}));
+		assert_noop!(
+			AutomationPrice::validate_and_schedule_task(task_with_id("123-0-3")),
+			Error::<Test>::MaxTasksPerAssetPairReached,
 		);
 
-		// now we move target price of pair3 to higher than its target, and will observe that its
-		// task will be moved to TaskQueue too.
-		new_pair_3_price = base_price + 2000;
-		AutomationPrice::update_asset_prices(
-			RuntimeOrigin::signed(creator.clone()),
-			vec![chain2.to_vec()],
-			vec![exchange1.to_vec()],
-			vec![asset1.to_vec()],
-			vec![asset3.to_vec()],
-			vec![new_pair_3_price],
-			vec![START_BLOCK_TIME as u128],
-			vec![4],
-		);
-		AutomationPrice::shift_tasks(Weight::from_parts(1_000_000_000, 0));
-		assert_eq!(
-			AutomationPrice::get_task_queue(),
-			vec![(creator.clone(), task_id1.clone()), (creator.clone(), task_id3.clone())]
-		);
-		// The task are removed from SortedTasksIndex into the TaskQueue, therefore their length
-		// decrease to 0
-		assert_eq!(
-			AutomationPrice::get_sorted_tasks_index((
-				chain2.to_vec(),
+		// Only the asset admin origin can raise the cap of the asset pair
+		assert_noop!(
+			AutomationPrice::set_asset_task_cap(
+				RuntimeOrigin::signed(creator.clone()),
+				chain1.to_vec(),
 				exchange1.to_vec(),
-				(asset1.to_vec(), asset3.to_vec()),
-				"gt".as_bytes().to_vec(),
-			))
-			.map_or_else(|| 0, |x| x.len()),
-			0
+				asset1.to_vec(),
+				asset2.to_vec(),
+				Some(3),
+			),
+			sp_runtime::DispatchError::BadOrigin,
 		);
-
-		// Now, if a new task come up, and its price target matches the existing price, they will
-		// be trigger too.
-		get_xcmp_funds(creator.clone());
-		assert_ok!(AutomationPrice::schedule_xcmp_task(
-			RuntimeOrigin::signed(creator.clone()),
-			chain2.to_vec(),
+		assert_ok!(AutomationPrice::set_asset_task_cap(
+			RawOrigin::Root.into(),
+			chain1.to_vec(),
 			exchange1.to_vec(),
+			asset1.to_vec(),
 			asset2.to_vec(),
-			asset3.to_vec(),
-			START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
-			"lt".as_bytes().to_vec(),
-			// price for this asset is 10 in our last update
-			vec!(20),
-			Box::new(destination.into()),
-			Box::new(NATIVE_LOCATION.into()),
-			Box::new(AssetPayment {
-				asset_location: MultiLocation::new(0, Here).into(),
-				amount: MOCK_XCMP_FEE
-			}),
-			call,
-			Weight::from_parts(100_000, 0),
-			Weight::from_parts(200_000, 0)
+			Some(3),
 		));
-		// The task is now on the SortedTasksIndex
-		assert_eq!(
-			AutomationPrice::get_sorted_tasks_index((
-				chain2.to_vec(),
-				exchange1.to_vec(),
-				(asset2.to_vec(), asset3.to_vec()),
-				"lt".as_bytes().to_vec(),
-			))
-			.map_or_else(|| 0, |x| x.len()),
-			1
-		);
+		assert_eq!(AutomationPrice::asset_task_cap(&asset_key), 3);
+		assert_ok!(AutomationPrice::validate_and_schedule_task(task_with_id("123-0-3")));
 
-		AutomationPrice::shift_tasks(Weight::from_parts(1_000_000_000, 0));
-		let task_id4 = {
-			let task_ids = get_task_ids_from_events();
-			task_ids.last().unwrap().clone()
+		// Tasks that are gone make room for others
+		assert_ok!(AutomationPrice::cancel_task(
+			RuntimeOrigin::signed(creator.clone()),
+			task_id_of("123-0-1"),
+		));
+		assert_eq!(AutomationPrice::get_asset_task_count(&asset_key), 2);
+		assert_ok!(AutomationPrice::validate_and_schedule_task(task_with_id("123-0-4")));
+	})
+}
+
+// Tasks that ran are no longer counted against the cap of their asset pair
+#[test]
+fn test_tasks_that_ran_make_room_under_the_asset_pair_cap() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		MaxTasksPerAssetPair::set(2);
+		let creator = AccountId32::new(ALICE);
+		get_xcmp_funds(creator.clone());
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+		let asset_key = (chain1.to_vec(), exchange1.to_vec(), (asset1.to_vec(), asset2.to_vec()));
+		let task_with_id = |task_id: &str| Task::<Test> {
+			task_id: task_id_of(task_id),
+			..build_update_trigger_task(&creator)
 		};
 
-		// Now the task is again, moved into the queue and be removed from SortedTasksIndex
-		assert_eq!(
-			AutomationPrice::get_task_queue(),
-			vec![
-				(creator.clone(), task_id1.clone()),
-				(creator.clone(), task_id3.clone()),
-				(creator, task_id4)
-			]
-		);
-		assert_eq!(
-			AutomationPrice::get_sorted_tasks_index((
-				chain2.to_vec(),
-				exchange1.to_vec(),
-				(asset2.to_vec(), asset3.to_vec()),
-				"lt".as_bytes().to_vec(),
-			))
-			.map_or_else(|| 0, |x| x.len()),
-			0
+		// The price of pair1 starts at 1000, above the trigger price of both tasks
+		assert_ok!(AutomationPrice::validate_and_schedule_task(task_with_id("123-0-1")));
+		assert_ok!(AutomationPrice::validate_and_schedule_task(task_with_id("123-0-2")));
+		assert_noop!(
+			AutomationPrice::validate_and_schedule_task(task_with_id("123-0-3")),
+			Error::<Test>::MaxTasksPerAssetPairReached,
 		);
+
+		AutomationPrice::trigger_tasks(Weight::from_parts(10_000_000_000, 0));
+		assert_eq!(TransactedXcms::get().len(), 2);
+		assert_eq!(AutomationPrice::get_asset_task_count(&asset_key), 0);
+		assert_ok!(AutomationPrice::do_try_state());
+
+		assert_ok!(AutomationPrice::validate_and_schedule_task(task_with_id("123-0-3")));
+		assert_ok!(AutomationPrice::validate_and_schedule_task(task_with_id("123-0-4")));
+		assert_eq!(AutomationPrice::get_asset_task_count(&asset_key), 2);
+	})
+}
+
+// A task whose asset pair still decodes is counted off its asset pair once it is removed
+#[test]
+fn test_remove_undecodable_task_counts_it_off_its_asset_pair() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		get_xcmp_funds(creator.clone());
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+		let task = build_update_trigger_task(&creator);
+		assert_ok!(AutomationPrice::validate_and_schedule_task(task.clone()));
+		let asset_key = (task.chain.clone(), task.exchange.clone(), task.asset_pair.clone());
+		assert_eq!(AutomationPrice::get_asset_task_count(&asset_key), 1);
+
+		// Cut off the end of the action so that only the task no longer decodes
+		let task_key = Tasks::<Test>::hashed_key_for(&creator, task.task_id);
+		let mut raw_task = frame_support::storage::unhashed::get_raw(&task_key).unwrap();
+		raw_task.truncate(raw_task.len() - 1);
+		frame_support::storage::unhashed::put_raw(&task_key, &raw_task);
+		assert!(AutomationPrice::get_task(&creator, &task.task_id).is_none());
+
+		assert_ok!(AutomationPrice::cancel_task(
+			RuntimeOrigin::signed(creator.clone()),
+			task.task_id,
+		));
+		assert_eq!(AutomationPrice::get_asset_task_count(&asset_key), 0);
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskUndecodable {
+			owner_id: creator,
+			task_id: task.task_id,
+		}));
 	})
 }
 
-// the logic around > or < using include/exclude range to include bound or not, it can be subtle
-// and error prone to human mistake so this test exist to make sure we catch that edge case.
 #[test]
-fn test_gt_task_not_run_when_asset_price_equal_target_price() {
+fn test_migrate_to_v7_counts_the_tasks_of_each_asset_pair() {
 	new_test_ext(START_BLOCK_TIME).execute_with(|| {
-		// TODO: Setup fund once we add fund check and weight
-		let para_id: u32 = 1000;
 		let creator = AccountId32::new(ALICE);
-		let call: Vec<u8> = vec![2, 4, 5];
-		let destination = MultiLocation::new(1, X1(Parachain(para_id)));
+		let task = build_update_trigger_task(&creator);
+		let remark = RuntimeCall::System(frame_system::Call::remark_with_event { remark: vec![1] });
+		// Tasks are stored in the shape they had before v8 when the migration runs
+		let old_task =
+			|task_id: &str, action: OldAction<AccountId32>| migrations::v8::OldTask::<Test> {
+				owner_id: creator.clone(),
+				task_id: task_id_of(task_id),
+				chain: task.chain.clone(),
+				exchange: task.exchange.clone(),
+				asset_pair: task.asset_pair.clone(),
+				expired_at: task.expired_at,
+				trigger_function: task.trigger_function.clone(),
+				trigger_params: task.trigger_params.clone(),
+				action,
+			};
+		let xcmp_action = OldAction::decode(&mut &task.action.encode()[..]).unwrap();
+		let dynamic_dispatch_action = OldAction::DynamicDispatch { encoded_call: remark.encode() };
+		for (task_id, action) in [("123-0-1", xcmp_action), ("123-0-2", dynamic_dispatch_action)] {
+			migrations::v8::old::Tasks::<Test>::insert(
+				&creator,
+				task_id_of(task_id),
+				old_task(task_id, action),
+			);
+		}
+		let asset_key = (task.chain, task.exchange, task.asset_pair);
 
-		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+		StorageVersion::new(6).put::<AutomationPrice>();
+		MigrateToV7::<Test>::on_runtime_upgrade();
+		assert_eq!(AutomationPrice::on_chain_storage_version(), 7);
+		assert_eq!(AutomationPrice::get_asset_task_count(&asset_key), 2);
+	})
+}
 
-		let base_price = 1_000_u128;
+#[test]
+fn test_dynamic_dispatch_task_pays_its_fees_in_the_schedule_fee_asset() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		let foreign_location = MultiLocation::new(1, X1(Parachain(PARA_ID)));
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+		Balances::force_set_balance(RawOrigin::Root.into(), creator.clone(), 1_000_000_000_000_000)
+			.unwrap();
+		assert_ok!(<Test as Config>::MultiCurrency::deposit(
+			FOREIGN_CURRENCY_ID,
+			&creator,
+			1_000_000_000_000_000,
+		));
+		let native_balance = Balances::total_balance(&creator);
 
-		get_xcmp_funds(creator.clone());
-		assert_ok!(AutomationPrice::schedule_xcmp_task(
-			RuntimeOrigin::signed(creator),
+		let call =
+			RuntimeCall::System(frame_system::Call::remark_with_event { remark: vec![1, 2, 3] });
+		assert_ok!(AutomationPrice::schedule_dynamic_dispatch_task(
+			RuntimeOrigin::signed(creator.clone()),
 			chain1.to_vec(),
 			exchange1.to_vec(),
 			asset1.to_vec(),
 			asset2.to_vec(),
 			START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
 			"gt".as_bytes().to_vec(),
-			vec!(base_price),
-			Box::new(destination.into()),
-			Box::new(NATIVE_LOCATION.into()),
-			Box::new(AssetPayment {
-				asset_location: MultiLocation::new(0, Here).into(),
-				amount: 100_000
-			}),
-			call,
-			Weight::from_ref_time(100_000),
-			Weight::from_ref_time(200_000)
+			vec![500],
+			Box::new(foreign_location.into()),
+			Box::new(call.clone()),
+			None,
 		));
 
-		AutomationPrice::shift_tasks(Weight::from_ref_time(1_000_000_000));
-		// Task shouldn't be move to task queue to trigger, and the task queue should be empty
-		assert!(AutomationPrice::get_task_queue().is_empty());
-
-		let sorted_task_index = AutomationPrice::get_sorted_tasks_index((
-			chain1.to_vec(),
-			exchange1.to_vec(),
-			(asset1.to_vec(), asset2.to_vec()),
-			"gt".as_bytes().to_vec(),
-		));
-		assert_eq!(1, sorted_task_index.map_or_else(|| 0, |x| x.len()));
+		let task_id = get_task_ids_from_events()[0];
+		let task = AutomationPrice::get_task(&creator, &task_id).unwrap();
+		assert_eq!(
+			task.action,
+			Action::DynamicDispatch { encoded_call: call.encode(), schedule_fee: foreign_location }
+		);
+		let fee = AutomationPrice::calculate_schedule_fee_amount(&task.action).unwrap();
+		assert!(fee > 0);
+		assert_eq!(
+			<Test as Config>::MultiCurrency::free_balance(FOREIGN_CURRENCY_ID, &creator),
+			1_000_000_000_000_000 - fee,
+		);
+		// Only the storage deposit of the task is held in the native token
+		assert_eq!(Balances::total_balance(&creator), native_balance);
 	})
 }
 
 #[test]
-fn test_emit_event_when_execute_tasks() {
+fn test_migrate_to_v8_pays_the_fees_of_dynamic_dispatch_tasks_in_the_native_token() {
 	new_test_ext(START_BLOCK_TIME).execute_with(|| {
 		let creator = AccountId32::new(ALICE);
-		let para_id: u32 = 1000;
+		let task = build_update_trigger_task(&creator);
+		let remark = RuntimeCall::System(frame_system::Call::remark_with_event { remark: vec![1] });
+		let old_task = migrations::v8::OldTask::<Test> {
+			owner_id: creator.clone(),
+			task_id: task.task_id,
+			chain: task.chain.clone(),
+			exchange: task.exchange.clone(),
+			asset_pair: task.asset_pair.clone(),
+			expired_at: task.expired_at,
+			trigger_function: task.trigger_function.clone(),
+			trigger_params: task.trigger_params.clone(),
+			action: OldAction::DynamicDispatch { encoded_call: remark.encode() },
+		};
+		migrations::v8::old::Tasks::<Test>::insert(&creator, task.task_id, old_task);
+
+		StorageVersion::new(7).put::<AutomationPrice>();
+		MigrateToV8::<Test>::on_runtime_upgrade();
+		assert_eq!(AutomationPrice::on_chain_storage_version(), 8);
+		assert_eq!(
+			AutomationPrice::get_task(&creator, task.task_id),
+			Some(Task::<Test> {
+				action: Action::DynamicDispatch {
+					encoded_call: remark.encode(),
+					schedule_fee: NATIVE_LOCATION,
+				},
+				..task
+			})
+		);
+	})
+}
 
+// Of the tasks triggered together, those with a higher tip run first, and those with the same tip
+// keep the order they were triggered in
+#[test]
+fn test_run_tasks_runs_the_tasks_with_a_higher_tip_first() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
 		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+		get_xcmp_funds(creator.clone());
+		let task = build_update_trigger_task(&creator);
+		let task_ids: Vec<TaskId> = (0..4).map(|i| task_id_of(&format!("123-{}-1", i))).collect();
+		for task_id in task_ids.iter() {
+			assert_ok!(AutomationPrice::validate_and_schedule_task(Task::<Test> {
+				task_id: *task_id,
+				..task.clone()
+			}));
+		}
+		Balances::force_set_balance(RawOrigin::Root.into(), creator.clone(), 1_000_000_000_000)
+			.unwrap();
+		for (task_id, tip) in [(task_ids[1], 10), (task_ids[3], 50)] {
+			assert_ok!(AutomationPrice::set_task_tip(
+				RuntimeOrigin::signed(creator.clone()),
+				task_id,
+				tip,
+			));
+		}
 
-		let destination = MultiLocation::new(1, X1(Parachain(para_id)));
-		let schedule_fee = MultiLocation::default();
-		let execution_fee = AssetPayment {
-			asset_location: MultiLocation::new(1, X1(Parachain(para_id))).into(),
-			amount: MOCK_XCMP_FEE,
-		};
-		let encoded_call_weight = Weight::from_ref_time(100_000);
-		let overall_weight = Weight::from_ref_time(200_000);
+		AutomationPrice::run_tasks(
+			task_ids.iter().map(|task_id| (creator.clone(), *task_id)).collect(),
+			100_000_000_000.into(),
+			&mut PriceCache::default(),
+		);
+
+		let triggered: Vec<TaskId> = events()
+			.into_iter()
+			.filter_map(|e| match e {
+				RuntimeEvent::AutomationPrice(crate::Event::TaskTriggered { task_id, .. }) =>
+					Some(task_id),
+				_ => None,
+			})
+			.collect();
+		assert_eq!(triggered, vec![task_ids[3], task_ids[1], task_ids[0], task_ids[2]]);
+	})
+}
 
+#[test]
+fn test_set_task_tip_holds_the_tip_until_the_task_runs() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
 		get_xcmp_funds(creator.clone());
-		let task = Task::<Test> {
-			owner_id: creator,
-			task_id: "123-0-1".as_bytes().to_vec(),
-			chain: chain1.to_vec(),
-			exchange: exchange1.to_vec(),
-			asset_pair: (asset1.to_vec(), asset2.to_vec()),
-			expired_at: (START_BLOCK_TIME + 10000) as u128,
-			trigger_function: "gt".as_bytes().to_vec(),
-			trigger_params: vec![123],
-			action: Action::XCMP {
-				destination,
-				schedule_fee,
-				execution_fee,
-				encoded_call: vec![1, 2, 3],
-				encoded_call_weight,
-				overall_weight,
-				schedule_as: None,
-				instruction_sequence: InstructionSequence::PayThroughRemoteDerivativeAccount,
-			},
-		};
+		let task = build_update_trigger_task(&creator);
+		assert_ok!(AutomationPrice::validate_and_schedule_task(task.clone()));
+		Balances::force_set_balance(RawOrigin::Root.into(), creator.clone(), 1_000_000_000_000)
+			.unwrap();
 
-		AutomationPrice::validate_and_schedule_task(task.clone());
+		assert_noop!(
+			AutomationPrice::set_task_tip(
+				RuntimeOrigin::signed(creator.clone()),
+				task_id_of("123-0-2"),
+				1000,
+			),
+			Error::<Test>::TaskNotFound,
+		);
+
+		assert_ok!(AutomationPrice::set_task_tip(
+			RuntimeOrigin::signed(creator.clone()),
+			task.task_id,
+			1000,
+		));
+		assert_eq!(Balances::reserved_balance(&creator), 1000);
+
+		// Lowering the tip returns the difference
+		assert_ok!(AutomationPrice::set_task_tip(
+			RuntimeOrigin::signed(creator.clone()),
+			task.task_id,
+			400,
+		));
+		assert_eq!(Balances::reserved_balance(&creator), 400);
+		assert_last_event(RuntimeEvent::AutomationPrice(crate::Event::TaskTipUpdated {
+			owner_id: creator.clone(),
+			task_id: task.task_id,
+			tip: 400,
+		}));
 
 		AutomationPrice::run_tasks(
-			vec![(task.owner_id.clone(), task.task_id.clone())],
+			vec![(creator.clone(), task.task_id)],
 			100_000_000_000.into(),
+			&mut PriceCache::default(),
 		);
 
-		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskTriggered {
-			owner_id: task.owner_id.clone(),
-			task_id: task.task_id.clone(),
-			condition: crate::TaskCondition::TargetPriceMatched {
-				chain: task.chain.clone(),
-				exchange: task.exchange.clone(),
-				asset_pair: task.asset_pair.clone(),
-				price: 1000_u128,
-			},
-		}));
-
-		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskExecuted {
-			owner_id: task.owner_id.clone(),
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskTipCollected {
+			owner_id: creator.clone(),
 			task_id: task.task_id,
+			amount: 400,
 		}));
+		assert_eq!(AutomationPrice::get_task_tip(&creator, task.task_id), None);
+		assert_eq!(Balances::reserved_balance(&creator), 0);
 	})
 }
 
 #[test]
-fn test_decrease_task_count_when_execute_tasks() {
+fn test_cancel_task_returns_the_tip() {
 	new_test_ext(START_BLOCK_TIME).execute_with(|| {
-		let creator1 = AccountId32::new(ALICE);
-		let creator2 = AccountId32::new(BOB);
-		let para_id: u32 = 1000;
-
-		setup_assets_and_prices(&creator1, START_BLOCK_TIME as u128);
+		let creator = AccountId32::new(ALICE);
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+		get_xcmp_funds(creator.clone());
+		let task = build_update_trigger_task(&creator);
+		assert_ok!(AutomationPrice::validate_and_schedule_task(task.clone()));
+		Balances::force_set_balance(RawOrigin::Root.into(), creator.clone(), 1_000_000_000_000)
+			.unwrap();
+		assert_ok!(AutomationPrice::set_task_tip(
+			RuntimeOrigin::signed(creator.clone()),
+			task.task_id,
+			1000,
+		));
 
-		let destination = MultiLocation::new(1, X1(Parachain(para_id)));
-		let schedule_fee = MultiLocation::default();
-		let execution_fee = AssetPayment {
-			asset_location: MultiLocation::new(1, X1(Parachain(para_id))).into(),
-			amount: MOCK_XCMP_FEE,
-		};
-		let encoded_call_weight = Weight::from_ref_time(100_000);
-		let overall_weight = Weight::from_ref_time(200_000);
+		assert_ok!(AutomationPrice::cancel_task(
+			RuntimeOrigin::signed(creator.clone()),
+			task.task_id,
+		));
 
-		get_xcmp_funds(creator1.clone());
-		let task1 = Task::<Test> {
-			owner_id: creator1.clone(),
-			task_id: "123-0-1".as_bytes().to_vec(),
-			chain: chain1.to_vec(),
-			exchange: exchange1.to_vec(),
-			asset_pair: (asset1.to_vec(), asset2.to_vec()),
-			expired_at: (START_BLOCK_TIME + 10000) as u128,
-			trigger_function: "gt".as_bytes().to_vec(),
-			trigger_params: vec![123],
-			action: Action::XCMP {
-				destination,
-				schedule_fee,
-				execution_fee: execution_fee.clone(),
-				encoded_call: vec![1, 2, 3],
-				encoded_call_weight,
-				overall_weight,
-				schedule_as: None,
-				instruction_sequence: InstructionSequence::PayThroughRemoteDerivativeAccount,
-			},
-		};
+		assert_eq!(AutomationPrice::get_task_tip(&creator, task.task_id), None);
+		assert_eq!(Balances::reserved_balance(&creator), 0);
+		assert_eq!(Balances::free_balance(&creator), 1_000_000_000_000);
+	})
+}
 
-		get_xcmp_funds(creator2.clone());
-		let task2 = Task::<Test> {
-			owner_id: creator2.clone(),
-			task_id: "123-1-1".as_bytes().to_vec(),
-			chain: chain1.to_vec(),
-			exchange: exchange1.to_vec(),
-			asset_pair: (asset1.to_vec(), asset2.to_vec()),
-			expired_at: (START_BLOCK_TIME + 10000) as u128,
-			trigger_function: "gt".as_bytes().to_vec(),
-			trigger_params: vec![123],
-			action: Action::XCMP {
-				destination,
-				schedule_fee,
-				execution_fee,
-				encoded_call: vec![1, 2, 3],
-				encoded_call_weight,
-				overall_weight,
-				schedule_as: None,
-				instruction_sequence: InstructionSequence::PayThroughRemoteDerivativeAccount,
-			},
-		};
+// A keeper runs the tasks at the front of the task queue and is paid the part of their fees held
+// for keepers
+#[test]
+fn test_execute_ready_tasks_pays_the_keeper_the_reward_held_for_each_task() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		KeeperRewardShare::set(Perbill::from_percent(10));
+		let creator = AccountId32::new(ALICE);
+		let keeper = AccountId32::new(BOB);
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+		let task = build_update_trigger_task(&creator);
+		let other_task = Task::<Test> { task_id: task_id_of("123-0-2"), ..task.clone() };
+		Balances::force_set_balance(RawOrigin::Root.into(), creator.clone(), 1_000_000_000_000)
+			.unwrap();
+		assert_ok!(AutomationPrice::validate_and_schedule_task(task.clone()));
+		assert_ok!(AutomationPrice::validate_and_schedule_task(other_task.clone()));
+
+		let reward = AutomationPrice::calculate_keeper_reward_amount(&task.action).unwrap();
+		assert!(reward > 0);
+		assert_eq!(AutomationPrice::get_keeper_reward(&creator, task.task_id), Some(reward));
+		assert_eq!(Balances::free_balance(AutomationPrice::keeper_pot_account()), 2 * reward);
+
+		crate::TaskQueue::<Test>::put(vec![
+			(creator.clone(), task.task_id),
+			(creator.clone(), other_task.task_id),
+		]);
+		assert_ok!(AutomationPrice::execute_ready_tasks(RuntimeOrigin::signed(keeper.clone()), 1));
+
+		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::KeeperRewarded {
+			keeper: keeper.clone(),
+			owner_id: creator.clone(),
+			task_id: task.task_id,
+			amount: reward,
+		}));
+		assert_eq!(Balances::free_balance(&keeper), reward);
+		assert!(AutomationPrice::get_task(&creator, task.task_id).is_none());
+		// The tasks past the limit stay queued for the next keeper or block
+		assert_eq!(AutomationPrice::get_task_queue(), vec![(creator.clone(), other_task.task_id)]);
+		assert_eq!(AutomationPrice::get_keeper_reward(&creator, other_task.task_id), Some(reward));
+	})
+}
 
-		AutomationPrice::validate_and_schedule_task(task1.clone());
-		AutomationPrice::validate_and_schedule_task(task2);
+#[test]
+fn test_execute_ready_tasks_needs_queued_tasks_and_running_execution() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		let keeper = AccountId32::new(BOB);
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+		get_xcmp_funds(creator.clone());
+		let task = build_update_trigger_task(&creator);
+		assert_ok!(AutomationPrice::validate_and_schedule_task(task.clone()));
 
-		assert_eq!(
-			2,
-			AutomationPrice::get_task_stat(StatType::TotalTasksOverall).map_or(0, |v| v),
-			"total task count is wrong"
+		assert_noop!(
+			AutomationPrice::execute_ready_tasks(RuntimeOrigin::signed(keeper.clone()), 1),
+			Error::<Test>::NoTasksReady,
 		);
-		assert_eq!(
-			1,
-			AutomationPrice::get_account_stat(creator1.clone(), StatType::TotalTasksPerAccount)
-				.map_or(0, |v| v),
-			"total task count is wrong"
+
+		crate::TaskQueue::<Test>::put(vec![(creator.clone(), task.task_id)]);
+		assert_noop!(
+			AutomationPrice::execute_ready_tasks(RuntimeOrigin::signed(keeper.clone()), 5),
+			Error::<Test>::KeeperBatchTooLarge,
 		);
-		assert_eq!(
-			1,
-			AutomationPrice::get_account_stat(creator2, StatType::TotalTasksPerAccount)
-				.map_or(0, |v| v),
-			"total task count is wrong"
+
+		crate::Shutdown::<Test>::put(ShutdownFlags::all());
+		assert_noop!(
+			AutomationPrice::execute_ready_tasks(RuntimeOrigin::signed(keeper), 1),
+			Error::<Test>::ExecutionHalted,
 		);
+	})
+}
+
+// The reward of a task the scheduler runs is not left in the keeper pot
+#[test]
+fn test_keeper_reward_goes_to_the_fee_revenue_when_no_keeper_runs_the_task() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		KeeperRewardShare::set(Perbill::from_percent(10));
+		let creator = AccountId32::new(ALICE);
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+		let task = build_update_trigger_task(&creator);
+		Balances::force_set_balance(RawOrigin::Root.into(), creator.clone(), 1_000_000_000_000)
+			.unwrap();
+		assert_ok!(AutomationPrice::validate_and_schedule_task(task.clone()));
+		assert!(Balances::free_balance(AutomationPrice::keeper_pot_account()) > 0);
 
 		AutomationPrice::run_tasks(
-			vec![(task1.owner_id.clone(), task1.task_id)],
+			vec![(creator.clone(), task.task_id)],
 			100_000_000_000.into(),
+			&mut PriceCache::default(),
 		);
 
-		assert_eq!(
-			1,
-			AutomationPrice::get_task_stat(StatType::TotalTasksOverall).map_or(0, |v| v),
-			"total task count is wrong"
-		);
-		assert_eq!(
-			0,
-			AutomationPrice::get_account_stat(creator1, StatType::TotalTasksPerAccount)
-				.map_or(0, |v| v),
-			"total task count of creator1 is wrong"
-		);
+		assert!(AutomationPrice::get_task(&creator, task.task_id).is_none());
+		assert_eq!(AutomationPrice::get_keeper_reward(&creator, task.task_id), None);
+		assert_eq!(Balances::free_balance(AutomationPrice::keeper_pot_account()), 0);
 	})
 }
 
-// when running a task, if the task is already expired, the execution engine won't run the task,
-// instead an even TaskExpired is emiited
+// A reward the empty keeper pot cannot take goes to the fee revenue, so the task is still scheduled
 #[test]
-fn test_expired_task_not_run() {
+fn test_keeper_reward_below_the_minimum_balance_of_the_pot_goes_to_the_fee_revenue() {
 	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		KeeperRewardShare::set(Perbill::from_percent(10));
 		let creator = AccountId32::new(ALICE);
-		let para_id: u32 = 1000;
-
-		let destination = MultiLocation::new(1, X1(Parachain(para_id)));
-		let schedule_fee = MultiLocation::default();
-		let execution_fee = AssetPayment {
-			asset_location: MultiLocation::new(1, X1(Parachain(para_id))).into(),
-			amount: MOCK_XCMP_FEE,
-		};
-		let encoded_call_weight = Weight::from_parts(100_000, 0);
-		let overall_weight = Weight::from_parts(200_000, 0);
-
-		get_xcmp_funds(creator.clone());
-		let task = Task::<Test> {
-			owner_id: creator,
-			task_id: "123-0-1".as_bytes().to_vec(),
-			chain: chain1.to_vec(),
-			exchange: exchange1.to_vec(),
-			asset_pair: (asset1.to_vec(), asset2.to_vec()),
-			expired_at: START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
-			trigger_function: "gt".as_bytes().to_vec(),
-			trigger_params: vec![123],
-			action: Action::XCMP {
-				destination,
-				schedule_fee,
-				execution_fee,
-				encoded_call: vec![1, 2, 3],
-				encoded_call_weight,
-				overall_weight,
-				schedule_as: None,
-				instruction_sequence: InstructionSequence::PayThroughRemoteDerivativeAccount,
-			},
-		};
-
-		AutomationPrice::validate_and_schedule_task(task.clone());
-
-		// Moving the clock to simulate the task expiration
-		Timestamp::set_timestamp(
-			START_BLOCK_TIME.saturating_add(7_200_000_u64).try_into().unwrap(),
-		);
-		AutomationPrice::run_tasks(
-			vec![(task.owner_id.clone(), task.task_id.clone())],
-			100_000_000_000.into(),
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+		let task = build_update_trigger_task(&creator);
+		Balances::force_set_balance(RawOrigin::Root.into(), creator.clone(), 1_000_000_000_000)
+			.unwrap();
+		let reward = AutomationPrice::calculate_keeper_reward_amount(&task.action).unwrap();
+		assert!(reward > 0);
+
+		ExistentialDeposit::set(reward as u64 + 1);
+		assert_eq!(AutomationPrice::calculate_keeper_reward_amount(&task.action), Ok(0));
+		assert_ok!(AutomationPrice::validate_and_schedule_task(task.clone()));
+		assert_eq!(AutomationPrice::get_keeper_reward(&creator, task.task_id), None);
+		assert_eq!(Balances::free_balance(AutomationPrice::keeper_pot_account()), 0);
+
+		// Once the pot holds the minimum balance it takes the rewards of the next tasks
+		assert!(AutomationPrice::endow_keeper_pot());
+		let other_task = Task::<Test> { task_id: task_id_of("123-0-2"), ..task };
+		assert_ok!(AutomationPrice::validate_and_schedule_task(other_task.clone()));
+		assert_eq!(AutomationPrice::get_keeper_reward(&creator, other_task.task_id), Some(reward));
+		assert_eq!(
+			Balances::free_balance(AutomationPrice::keeper_pot_account()),
+			ExistentialDeposit::get() as u128 + reward
 		);
+	})
+}
 
-		assert_no_event(RuntimeEvent::AutomationPrice(crate::Event::TaskTriggered {
-			owner_id: task.owner_id.clone(),
-			task_id: task.task_id.clone(),
-			condition: crate::TaskCondition::TargetPriceMatched {
-				chain: task.chain.clone(),
-				exchange: task.exchange.clone(),
-				asset_pair: task.asset_pair.clone(),
-				price: 1000_u128,
-			},
-		}));
+#[test]
+fn test_migrate_to_v9_endows_the_keeper_pot() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let pot = AutomationPrice::keeper_pot_account();
+		assert_eq!(Balances::free_balance(&pot), 0);
 
-		assert_no_event(RuntimeEvent::AutomationPrice(crate::Event::TaskExecuted {
-			owner_id: task.owner_id.clone(),
-			task_id: task.task_id.clone(),
-		}));
+		StorageVersion::new(8).put::<AutomationPrice>();
+		MigrateToV9::<Test>::on_runtime_upgrade();
+		assert_eq!(AutomationPrice::on_chain_storage_version(), 9);
+		assert_eq!(Balances::free_balance(&pot), ExistentialDeposit::get() as u128);
 
-		assert_last_event(RuntimeEvent::AutomationPrice(crate::Event::TaskExpired {
-			owner_id: task.owner_id.clone(),
-			task_id: task.task_id.clone(),
-			condition: crate::TaskCondition::AlreadyExpired {
-				expired_at: task.expired_at,
-				now: START_BLOCK_TIME
-					.saturating_add(7_200_000_u64)
-					.checked_div(1000)
-					.ok_or(ArithmeticError::Overflow)
-					.expect("blocktime is out of range") as u128,
-			},
-		}));
+		// A pot that already holds the minimum balance is left as it is
+		assert!(!AutomationPrice::endow_keeper_pot());
+		assert_eq!(Balances::free_balance(&pot), ExistentialDeposit::get() as u128);
 	})
 }
 
-// when running a task, if the price has been moved against the target price, rendering the target
-// price condition not match anymore. we will skip run
+// A recurring task run by the chain holds the reward of its next run only, the reward of each
+// run going to the fee revenue
 #[test]
-fn test_price_move_against_target_price_skip_run() {
+fn test_recurring_task_holds_one_keeper_reward_at_a_time() {
 	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		KeeperRewardShare::set(Perbill::from_percent(10));
 		let creator = AccountId32::new(ALICE);
-		let para_id: u32 = 1000;
-
+		let max_weight = Weight::from_parts(10_000_000_000, 0);
 		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+		Balances::force_set_balance(RawOrigin::Root.into(), creator.clone(), 1_000_000_000_000_000)
+			.unwrap();
+		let task = build_update_trigger_task(&creator);
+		assert_ok!(AutomationPrice::validate_and_schedule_task(task.clone()));
+		assert_ok!(AutomationPrice::set_task_schedule(
+			RuntimeOrigin::signed(creator.clone()),
+			task.task_id,
+			Schedule::Recurring { cooldown_seconds: 600, max_executions: Some(3) },
+		));
+		let reward = AutomationPrice::calculate_keeper_reward_amount(&task.action).unwrap();
+		assert!(reward > 0);
+		let pot_balance = || Balances::free_balance(AutomationPrice::keeper_pot_account());
+		let pot_before = pot_balance();
+
+		// Each run releases the reward it held and holds the one of the next run
+		AutomationPrice::trigger_tasks(max_weight);
+		assert!(AutomationPrice::get_task(&creator, &task.task_id).is_some());
+		assert_eq!(AutomationPrice::get_keeper_reward(&creator, &task.task_id), Some(reward));
+		assert_eq!(pot_balance(), pot_before);
+
+		Timestamp::set_timestamp(START_BLOCK_TIME + 600_000);
+		AutomationPrice::trigger_tasks(max_weight);
+		assert!(AutomationPrice::get_task(&creator, &task.task_id).is_some());
+		assert_eq!(AutomationPrice::get_keeper_reward(&creator, &task.task_id), Some(reward));
+		assert_eq!(pot_balance(), pot_before);
+
+		// The last run leaves nothing in the pot
+		Timestamp::set_timestamp(START_BLOCK_TIME + 1_200_000);
+		AutomationPrice::trigger_tasks(max_weight);
+		assert!(AutomationPrice::get_task(&creator, &task.task_id).is_none());
+		assert_eq!(AutomationPrice::get_keeper_reward(&creator, &task.task_id), None);
+		assert_eq!(pot_balance(), 0);
+	})
+}
 
+// Scheduling again with the same provided id is rejected, so a wallet can safely retry
+#[test]
+fn test_schedule_xcmp_task_with_provided_id_rejects_duplicates() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let creator = AccountId32::new(ALICE);
+		let destination = MultiLocation::new(1, X1(Parachain(1000)));
+		setup_asset(&creator, chain1.to_vec());
 		get_xcmp_funds(creator.clone());
-		let destination = MultiLocation::new(1, X1(Parachain(para_id)));
-		let schedule_fee = MultiLocation::default();
-		let execution_fee = AssetPayment {
-			asset_location: MultiLocation::new(1, X1(Parachain(para_id))).into(),
-			amount: MOCK_XCMP_FEE,
-		};
-		let encoded_call_weight = Weight::from_ref_time(100_000);
-		let overall_weight = Weight::from_ref_time(200_000);
 
-		let task = Task::<Test> {
-			owner_id: creator,
-			task_id: "123-0-1".as_bytes().to_vec(),
-			chain: chain1.to_vec(),
-			exchange: exchange1.to_vec(),
-			asset_pair: (asset1.to_vec(), asset2.to_vec()),
-			expired_at: START_BLOCK_TIME
-				.checked_div(1000)
-				.map_or(10000000_u128, |v| v.into())
-				.saturating_add(100),
-			trigger_function: "gt".as_bytes().to_vec(),
-			// This asset price is set to 1000
-			// The task is config to run when price > 2000, and we invoked it directly
-			// so and we will observe that task won't run due to price doesn't match
-			// the condition
-			trigger_params: vec![2000],
-			action: Action::XCMP {
-				destination,
-				schedule_fee,
-				execution_fee,
-				encoded_call: vec![1, 2, 3],
-				encoded_call_weight,
-				overall_weight,
-				schedule_as: None,
-				instruction_sequence: InstructionSequence::PayThroughRemoteDerivativeAccount,
-			},
+		let schedule = |provided_id: &[u8]| {
+			AutomationPrice::schedule_xcmp_task(
+				RuntimeOrigin::signed(creator.clone()),
+				chain1.to_vec(),
+				exchange1.to_vec(),
+				asset1.to_vec(),
+				asset2.to_vec(),
+				START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
+				"gt".as_bytes().to_vec(),
+				vec![100],
+				Box::new(destination.into()),
+				Box::new(NATIVE_LOCATION.into()),
+				Box::new(AssetPayment {
+					asset_location: MultiLocation::new(0, Here).into(),
+					amount: MOCK_XCMP_FEE,
+				}),
+				vec![2, 4, 5],
+				Weight::from_parts(100_000, 0),
+				Weight::from_parts(200_000, 0),
+				Some(provided_id.to_vec()),
+			)
 		};
 
-		AutomationPrice::validate_and_schedule_task(task.clone());
+		assert_ok!(schedule(b"order-1"));
+		let task_id = AutomationPrice::task_id_for(&creator, Some(b"order-1".to_vec()));
+		assert_eq!(get_task_ids_from_events(), vec![task_id]);
+		assert!(AutomationPrice::get_task(&creator, task_id).is_some());
 
-		AutomationPrice::run_tasks(
-			vec![(task.owner_id.clone(), task.task_id.clone())],
-			100_000_000_000.into(),
+		assert_noop!(schedule(b"order-1"), Error::<Test>::DuplicateTask);
+
+		get_xcmp_funds(creator.clone());
+		assert_ok!(schedule(b"order-2"));
+		assert_eq!(
+			AutomationPrice::get_account_stat(&creator, StatType::TotalTasksPerAccount),
+			Some(2)
 		);
+	})
+}
 
-		assert_no_event(RuntimeEvent::AutomationPrice(crate::Event::TaskTriggered {
-			owner_id: task.owner_id.clone(),
-			task_id: task.task_id.clone(),
-			condition: crate::TaskCondition::TargetPriceMatched {
-				chain: task.chain.clone(),
-				exchange: task.exchange.clone(),
-				asset_pair: task.asset_pair.clone(),
-				price: 1000_u128,
-			},
-		}));
+// The id of a removed task stays taken until its deposit is reclaimed, so the deposit cannot be
+// overwritten by a new task
+#[test]
+fn test_provided_id_is_free_again_once_the_deposit_is_reclaimed() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		TaskDepositPerItem::set(100);
+		let creator = AccountId32::new(ALICE);
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+		Balances::force_set_balance(RawOrigin::Root.into(), creator.clone(), 1_000_000_000_000)
+			.unwrap();
 
-		assert_no_event(RuntimeEvent::AutomationPrice(crate::Event::TaskExecuted {
-			owner_id: task.owner_id.clone(),
-			task_id: task.task_id.clone(),
-		}));
+		let schedule = || {
+			AutomationPrice::schedule_dynamic_dispatch_task(
+				RuntimeOrigin::signed(creator.clone()),
+				chain1.to_vec(),
+				exchange1.to_vec(),
+				asset1.to_vec(),
+				asset2.to_vec(),
+				START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
+				"gt".as_bytes().to_vec(),
+				vec![5000],
+				Box::new(NATIVE_LOCATION.into()),
+				Box::new(RuntimeCall::System(frame_system::Call::remark_with_event {
+					remark: vec![1],
+				})),
+				Some(b"order-1".to_vec()),
+			)
+		};
+		let task_id = AutomationPrice::task_id_for(&creator, Some(b"order-1".to_vec()));
 
-		assert_last_event(RuntimeEvent::AutomationPrice(crate::Event::PriceAlreadyMoved {
-			owner_id: task.owner_id.clone(),
-			task_id: task.task_id,
-			condition: crate::TaskCondition::PriceAlreadyMoved {
-				chain: chain1.to_vec(),
-				exchange: exchange1.to_vec(),
-				asset_pair: (asset1.to_vec(), asset2.to_vec()),
-				price: 1000_u128,
-				target_price: 2000_u128,
-			},
-		}));
+		assert_ok!(schedule());
+		assert_ok!(AutomationPrice::cancel_task(RuntimeOrigin::signed(creator.clone()), task_id));
+		assert!(AutomationPrice::get_task_deposit(&creator, &task_id).is_some());
+		assert_noop!(schedule(), Error::<Test>::DuplicateTask);
+
+		assert_ok!(AutomationPrice::reclaim_deposit(
+			RuntimeOrigin::signed(creator.clone()),
+			creator.clone(),
+			task_id,
+		));
+		assert_ok!(schedule());
+		assert!(AutomationPrice::get_task(&creator, task_id).is_some());
 	})
 }
 
-// When canceling, task is removed from 3 places:
+// The storage invariants hold as tasks are indexed, queued, purged and cancelled
 #[test]
-fn test_cancel_task_works() {
+fn test_try_state_holds_as_tasks_move_through_the_indexes() {
 	new_test_ext(START_BLOCK_TIME).execute_with(|| {
 		let creator = AccountId32::new(ALICE);
-		let para_id: u32 = 1000;
-
-		let destination = MultiLocation::new(1, X1(Parachain(para_id)));
-		let schedule_fee = MultiLocation::default();
-		let execution_fee = AssetPayment {
-			asset_location: MultiLocation::new(1, X1(Parachain(para_id))).into(),
-			amount: MOCK_XCMP_FEE,
-		};
-		let encoded_call_weight = Weight::from_parts(100_000, 0);
-		let overall_weight = Weight::from_parts(200_000, 0);
 
-		get_xcmp_funds(creator.clone());
-		let task = Task::<Test> {
-			owner_id: creator,
-			task_id: "123-0-1".as_bytes().to_vec(),
-			chain: chain1.to_vec(),
-			exchange: exchange1.to_vec(),
-			asset_pair: (asset1.to_vec(), asset2.to_vec()),
-			expired_at: START_BLOCK_TIME_1HOUR_AFTER_IN_SECOND,
-			trigger_function: "gt".as_bytes().to_vec(),
-			trigger_params: vec![123],
-			action: Action::XCMP {
-				destination,
-				schedule_fee,
-				execution_fee,
-				encoded_call: vec![1, 2, 3],
-				encoded_call_weight,
-				overall_weight,
-				schedule_as: None,
-				instruction_sequence: InstructionSequence::PayThroughRemoteDerivativeAccount,
-			},
-		};
-		AutomationPrice::validate_and_schedule_task(task.clone());
+		// The price of pair1 starts at 1000
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+		Balances::force_set_balance(RawOrigin::Root.into(), creator.clone(), 1_000_000_000_000_000)
+			.unwrap();
+		assert_ok!(AutomationPrice::do_try_state());
+
+		let task = build_update_trigger_task(&creator);
+		let tasks: Vec<Task<Test>> = [("gt", 2000, "1"), ("lt", 100, "2"), ("gt", 500, "3")]
+			.into_iter()
+			.map(|(trigger_function, price, task_id)| {
+				let mut task = task.clone();
+				task.trigger_function = trigger_function.as_bytes().to_vec();
+				task.trigger_params = vec![price];
+				task.task_id = task_id_of(task_id);
+				task
+			})
+			.collect();
+		for task in tasks.iter() {
+			assert_ok!(AutomationPrice::validate_and_schedule_task(task.clone()));
+		}
+		assert_ok!(AutomationPrice::do_try_state());
 
-		AutomationPrice::cancel_task(
-			RuntimeOrigin::signed(task.owner_id.clone()),
-			task.task_id.clone(),
+		AutomationPrice::shift_tasks(
+			Weight::from_parts(1_000_000_000, 0),
+			&mut PriceCache::default(),
 		);
+		assert_eq!(AutomationPrice::get_task_queue(), vec![(creator.clone(), tasks[2].task_id)]);
+		assert_ok!(AutomationPrice::do_try_state());
 
-		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::TaskCancelled {
-			owner_id: task.owner_id.clone(),
-			task_id: task.task_id,
-		}));
+		assert_ok!(AutomationPrice::cancel_task(
+			RuntimeOrigin::signed(creator.clone()),
+			tasks[1].task_id,
+		));
+		assert_ok!(AutomationPrice::do_try_state());
+
+		// Purging the queued task takes it off the queue too
+		let filter = PurgeFilter::Destination(MultiLocation::new(1, X1(Parachain(1000))));
+		assert_ok!(AutomationPrice::purge_tasks(RawOrigin::Root.into(), filter, 10));
+		assert!(AutomationPrice::get_task_queue().is_empty());
+		assert_eq!(Tasks::<Test>::iter().count(), 0);
+		assert_ok!(AutomationPrice::do_try_state());
 	})
 }
 
+// Indexes and counts that drifted from Tasks are reported
 #[test]
-fn test_delete_asset_ok() {
+fn test_try_state_catches_index_drift() {
 	new_test_ext(START_BLOCK_TIME).execute_with(|| {
-		let sender = AccountId32::new(ALICE);
+		let creator = AccountId32::new(ALICE);
 		let key = (chain1.to_vec(), exchange1.to_vec(), (asset1.to_vec(), asset2.to_vec()));
+		setup_assets_and_prices(&creator, START_BLOCK_TIME as u128);
+		get_xcmp_funds(creator.clone());
+		let task =
+			Task::<Test> { trigger_params: vec![2000], ..build_update_trigger_task(&creator) };
+		assert_ok!(AutomationPrice::validate_and_schedule_task(task.clone()));
+		assert_ok!(AutomationPrice::do_try_state());
 
-		setup_asset(&sender, chain1.to_vec());
-		AutomationPrice::update_asset_prices(
-			RuntimeOrigin::signed(sender),
-			vec![chain1.to_vec()],
-			vec![exchange1.to_vec()],
-			vec![asset1.to_vec()],
-			vec![asset2.to_vec()],
-			vec![6789_u128],
-			vec![START_BLOCK_TIME as u128],
-			vec![4],
+		Tasks::<Test>::remove(&creator, task.task_id);
+		assert_eq!(
+			AutomationPrice::do_try_state(),
+			Err("SortedTasksAbove refers to a task that is not in Tasks")
 		);
+		Tasks::<Test>::insert(&creator, task.task_id, &task);
 
-		assert!(AutomationPrice::get_asset_registry_info(&key).is_some());
-		assert!(AutomationPrice::get_asset_price_data(&key).is_some());
+		let buckets = crate::SortedBucketsAbove::<Test>::take(&key).unwrap();
+		assert_eq!(
+			AutomationPrice::do_try_state(),
+			Err("SortedBucketsAbove does not list the buckets of SortedTasksAbove")
+		);
+		crate::SortedBucketsAbove::<Test>::insert(&key, buckets);
 
-		// Now we delete asset, all the relevant asset metadata and price should be deleted
-		AutomationPrice::delete_asset(
-			RawOrigin::Root.into(),
-			chain1.to_vec(),
-			exchange1.to_vec(),
-			asset1.to_vec(),
-			asset2.to_vec(),
+		crate::TaskQueue::<Test>::put(vec![(creator.clone(), task_id_of("123-0-2"))]);
+		assert_eq!(
+			AutomationPrice::do_try_state(),
+			Err("TaskQueue refers to a task that is not in Tasks")
 		);
+		crate::TaskQueue::<Test>::kill();
 
-		assert!(AutomationPrice::get_asset_registry_info(&key).is_none());
-		assert!(AutomationPrice::get_asset_price_data(&key).is_none());
+		let conditions = CompoundCondition {
+			combinator: Combinator::And,
+			conditions: vec![price_condition(chain2, (asset2, asset3), "lt", 4000)],
+		};
+		crate::TaskConditions::<Test>::insert(&creator, task_id_of("123-0-2"), conditions);
+		assert_eq!(
+			AutomationPrice::do_try_state(),
+			Err("TaskConditions refers to a task that is not in Tasks")
+		);
+		crate::TaskConditions::<Test>::remove(&creator, task_id_of("123-0-2"));
 
-		assert_has_event(RuntimeEvent::AutomationPrice(crate::Event::AssetDeleted {
-			chain: chain1.to_vec(),
-			exchange: exchange1.to_vec(),
-			asset1: asset1.to_vec(),
-			asset2: asset2.to_vec(),
-		}));
+		AccountStats::<Test>::insert(&creator, StatType::TotalTasksPerAccount, 2);
+		assert_eq!(
+			AutomationPrice::do_try_state(),
+			Err("TotalTasksPerAccount does not match the number of tasks of an account")
+		);
+		AccountStats::<Test>::insert(&creator, StatType::TotalTasksPerAccount, 1);
+
+		crate::AssetTaskCounts::<Test>::insert(&key, 2);
+		assert_eq!(
+			AutomationPrice::do_try_state(),
+			Err("AssetTaskCounts does not match the number of tasks of an asset pair")
+		);
 	})
 }
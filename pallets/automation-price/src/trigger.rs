@@ -1,15 +1,177 @@
 use crate::{Config, PriceData, Task};
 
-use sp_std::ops::{
-	Bound,
-	Bound::{Excluded, Included},
-};
+use sp_runtime::traits::Zero;
+
+use sp_std::{collections::btree_map::BTreeMap, mem, vec, vec::Vec};
 
 pub const TRIGGER_FUNC_GT: &[u8] = "gt".as_bytes();
 pub const TRIGGER_FUNC_LT: &[u8] = "lt".as_bytes();
+pub const TRIGGER_FUNC_RANGE: &[u8] = "range".as_bytes();
+pub const TRIGGER_FUNC_TWAP_GT: &[u8] = "twap_gt".as_bytes();
+pub const TRIGGER_FUNC_TWAP_LT: &[u8] = "twap_lt".as_bytes();
+pub const TRIGGER_FUNC_PCT_MOVE: &[u8] = "pct_move".as_bytes();
+
+/// The basis points in a whole.
+const BASIS_POINTS: u128 = 10_000;
+
+/// The trigger functions whose tasks are kept sorted by their target price, each in the index of
+/// the side of the price it watches.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TriggerFunction {
+	/// Runs the task once the price rises above its target price.
+	Gt,
+	/// Runs the task once the price falls below its target price.
+	Lt,
+}
+
+impl TriggerFunction {
+	/// The sorted trigger function named by `trigger_function`, if it is one.
+	pub fn from_bytes(trigger_function: &[u8]) -> Option<Self> {
+		if trigger_function == TRIGGER_FUNC_GT {
+			Some(Self::Gt)
+		} else if trigger_function == TRIGGER_FUNC_LT {
+			Some(Self::Lt)
+		} else {
+			None
+		}
+	}
+}
+
+/// Decides when the trigger condition of a task holds. Runtimes can implement it to add trigger
+/// functions of their own without forking the pallet.
+///
+/// Only `gt` and `lt` tasks are kept sorted by their target price. The tasks of any other trigger
+/// function are checked one by one whenever the price of their asset pair moves.
+pub trait ConditionEvaluator {
+	/// Whether the trigger function is supported and its params fit it. Tasks without params are
+	/// rejected by the pallet whatever this returns.
+	fn is_valid(trigger_function: &[u8], trigger_params: &[u128]) -> bool;
+
+	/// Whether the condition of the trigger function holds at the price. `history` holds the
+	/// recent prices of the asset pair, oldest first, and `now` is the time of the check.
+	fn is_match(
+		trigger_function: &[u8],
+		trigger_params: &[u128],
+		price: &PriceData,
+		history: &[PriceData],
+		now: u128,
+	) -> bool;
+
+	/// Whether the condition can start to hold while the price stays the same. Tasks waiting on
+	/// such a condition are checked every block instead of only when the price moves.
+	fn changes_over_time(_trigger_function: &[u8]) -> bool {
+		false
+	}
+
+	/// The params to store with a task scheduled or updated with `trigger_params`, given the
+	/// current price of its asset pair. Lets a condition keep the price it is relative to.
+	fn with_current_price(
+		_trigger_function: &[u8],
+		trigger_params: Vec<u128>,
+		_price: Option<&PriceData>,
+	) -> Vec<u128> {
+		trigger_params
+	}
+}
+
+/// Supports `gt` and `lt` with the target price as their only param, and `range` with the lowest
+/// and highest price it matches, both included.
+///
+/// `twap_gt` and `twap_lt` take a threshold and a window in seconds. They compare the
+/// time-weighted average of the price history over the window up to the check with the
+/// threshold, so a single manipulated price does not trigger them.
+///
+/// `pct_move` takes a move in basis points and a baseline price, and matches once the price is
+/// further than that from the baseline, in either direction. Tasks scheduled with only the basis
+/// points, or updated with only them, take the price at that time as their baseline.
+pub struct DefaultConditionEvaluator;
+
+impl ConditionEvaluator for DefaultConditionEvaluator {
+	fn is_valid(trigger_function: &[u8], trigger_params: &[u128]) -> bool {
+		match (TriggerFunction::from_bytes(trigger_function), trigger_params) {
+			(Some(_), [_, ..]) => true,
+			(None, [low, high]) if trigger_function == TRIGGER_FUNC_RANGE => low <= high,
+			(None, [_, window]) if is_twap_trigger_func(trigger_function) => *window > 0,
+			(None, [basis_points, baseline]) if trigger_function == TRIGGER_FUNC_PCT_MOVE =>
+				*basis_points > 0 && *baseline > 0,
+			_ => false,
+		}
+	}
+
+	fn is_match(
+		trigger_function: &[u8],
+		trigger_params: &[u128],
+		price: &PriceData,
+		history: &[PriceData],
+		now: u128,
+	) -> bool {
+		match (TriggerFunction::from_bytes(trigger_function), trigger_params) {
+			(Some(TriggerFunction::Gt), [target, ..]) => price.value > *target,
+			(Some(TriggerFunction::Lt), [target, ..]) => price.value < *target,
+			(None, [low, high]) if trigger_function == TRIGGER_FUNC_RANGE =>
+				(*low..=*high).contains(&price.value),
+			(None, [threshold, window]) if trigger_function == TRIGGER_FUNC_TWAP_GT =>
+				time_weighted_average(history, *window, now).map_or(false, |twap| twap > *threshold),
+			(None, [threshold, window]) if trigger_function == TRIGGER_FUNC_TWAP_LT =>
+				time_weighted_average(history, *window, now).map_or(false, |twap| twap < *threshold),
+			(None, [basis_points, baseline]) if trigger_function == TRIGGER_FUNC_PCT_MOVE =>
+				price.value.abs_diff(*baseline).saturating_mul(BASIS_POINTS) >
+					basis_points.saturating_mul(*baseline),
+			_ => false,
+		}
+	}
+
+	fn changes_over_time(trigger_function: &[u8]) -> bool {
+		is_twap_trigger_func(trigger_function)
+	}
+
+	fn with_current_price(
+		trigger_function: &[u8],
+		trigger_params: Vec<u128>,
+		price: Option<&PriceData>,
+	) -> Vec<u128> {
+		match (trigger_params.as_slice(), price) {
+			([basis_points], Some(price)) if trigger_function == TRIGGER_FUNC_PCT_MOVE =>
+				vec![*basis_points, price.value],
+			_ => trigger_params,
+		}
+	}
+}
+
+fn is_twap_trigger_func(trigger_function: &[u8]) -> bool {
+	trigger_function == TRIGGER_FUNC_TWAP_GT || trigger_function == TRIGGER_FUNC_TWAP_LT
+}
+
+/// The time-weighted average of `history` over the `window` seconds up to `now`. Each price holds
+/// from its update until the next one. Only the part of the window that the history covers
+/// counts, and there is no average while it covers none of it.
+pub fn time_weighted_average(history: &[PriceData], window: u128, now: u128) -> Option<u128> {
+	let start = now.saturating_sub(window);
+
+	let mut weighted_sum: u128 = 0;
+	let mut covered: u128 = 0;
+	let mut end = now;
+	for price in history.iter().rev() {
+		let from = price.updated_at.max(start);
+		if from < end {
+			weighted_sum = weighted_sum.saturating_add(price.value.saturating_mul(end - from));
+			covered = covered.saturating_add(end - from);
+		}
+		if price.updated_at <= start {
+			break
+		}
+		end = end.min(price.updated_at);
+	}
+
+	if covered.is_zero() {
+		return None
+	}
+	Some(weighted_sum / covered)
+}
 
 pub trait PriceConditionMatch {
-	fn is_price_condition_match(&self, price: &PriceData) -> bool;
+	fn is_price_condition_match(&self, price: &PriceData, history: &[PriceData], now: u128)
+		-> bool;
 }
 
 impl<T: Config> PriceConditionMatch for Task<T> {
@@ -18,31 +180,54 @@ impl<T: Config> PriceConditionMatch for Task<T> {
 	/// # Argument
 	///
 	/// * `price` - the desire price of the asset to check on
-	fn is_price_condition_match(&self, price: &PriceData) -> bool {
+	/// * `history` - the recent prices of the asset, oldest first
+	/// * `now` - the time of the check
+	fn is_price_condition_match(
+		&self,
+		price: &PriceData,
+		history: &[PriceData],
+		now: u128,
+	) -> bool {
 		// trigger when target price > current price of the asset
 		// Example:
 		//  - current price: 100, the task is has target price: 50  -> runable
 		//  - current price: 100, the task is has target price: 150 -> not runable
 		//
-
-		if self.trigger_function == TRIGGER_FUNC_GT.to_vec() {
-			price.value > self.trigger_params[0]
-		} else {
-			price.value < self.trigger_params[0]
-		}
+		T::ConditionEvaluator::is_match(
+			&self.trigger_function,
+			&self.trigger_params,
+			price,
+			history,
+			now,
+		)
 	}
 }
 
-/// Given a condition, and a target price, generate a range that match the condition
-pub fn range_by_trigger_func(
-	trigger_func: &[u8],
+/// Split the tasks whose target price `current_price` crossed off a sorted task index and return
+/// them, leaving the others in `tasks`. Only the entries on either side of the price are visited,
+/// however many tasks the index holds.
+pub fn split_off_triggered<V>(
+	trigger_func: TriggerFunction,
+	tasks: &mut BTreeMap<u128, V>,
 	current_price: &PriceData,
-) -> (Bound<u128>, Bound<u128>) {
-	//Eg sell order, sell when price >
-	if trigger_func == TRIGGER_FUNC_GT {
-		(Excluded(u128::MIN), Excluded(current_price.value))
-	} else {
-		// Eg buy order, buy when price < target
-		(Included(current_price.value), Excluded(u128::MAX))
+) -> BTreeMap<u128, V> {
+	match trigger_func {
+		// Eg sell order, sell when price > target, for the targets in (u128::MIN, price)
+		TriggerFunction::Gt => {
+			let untriggered = tasks.split_off(&current_price.value);
+			let mut triggered = mem::replace(tasks, untriggered);
+			if let Some(task_ids) = triggered.remove(&u128::MIN) {
+				tasks.insert(u128::MIN, task_ids);
+			}
+			triggered
+		},
+		// Eg buy order, buy when price < target, for the targets in [price, u128::MAX)
+		TriggerFunction::Lt => {
+			let mut triggered = tasks.split_off(&current_price.value);
+			if let Some(task_ids) = triggered.remove(&u128::MAX) {
+				tasks.insert(u128::MAX, task_ids);
+			}
+			triggered
+		},
 	}
 }
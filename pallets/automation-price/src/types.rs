@@ -1,7 +1,8 @@
-use crate::{weights::WeightInfo, Config, InstructionSequence};
+use crate::{weights::WeightInfo, Config, Error, InstructionSequence, Task, TaskId};
 
-use frame_support::pallet_prelude::*;
+use frame_support::{dispatch::GetDispatchInfo, pallet_prelude::*};
 
+use sp_runtime::Perbill;
 use sp_std::prelude::*;
 
 use xcm::{latest::prelude::*, VersionedMultiLocation};
@@ -27,20 +28,116 @@ pub enum Action<AccountId> {
 		schedule_as: Option<AccountId>,
 		instruction_sequence: InstructionSequence,
 	},
+	/// Dispatch a call of this chain as the owner of the task.
+	DynamicDispatch {
+		encoded_call: Vec<u8>,
+		/// The asset the fees of the task are paid in.
+		schedule_fee: MultiLocation,
+	},
 }
 
 impl<AccountId> Action<AccountId> {
 	pub fn execution_weight<T: Config>(&self) -> Result<u64, DispatchError> {
 		let weight = match self {
 			Action::XCMP { .. } => <T as Config>::WeightInfo::run_xcmp_task(),
+			Action::DynamicDispatch { encoded_call, .. } => {
+				let scheduled_call: <T as Config>::RuntimeCall =
+					Decode::decode(&mut &**encoded_call)
+						.map_err(|_| Error::<T>::CallCannotBeDecoded)?;
+				<T as Config>::WeightInfo::run_dynamic_dispatch_action()
+					.saturating_add(scheduled_call.get_dispatch_info().weight)
+			},
 		};
 		Ok(weight.ref_time())
 	}
 
+	/// The weight of handling the action's call data. The call is stored with the task and read
+	/// again whenever the task is triggered, so its fee grows with the length of the call.
+	pub fn payload_weight<T: Config>(&self) -> u64 {
+		let len = match self {
+			Action::XCMP { encoded_call, .. } => encoded_call.len(),
+			Action::DynamicDispatch { encoded_call, .. } => encoded_call.len(),
+		};
+		let len = u32::try_from(len).unwrap_or(u32::MAX);
+		<T as Config>::WeightInfo::schedule_xcmp_task_extrinsic(len)
+			.saturating_sub(<T as Config>::WeightInfo::schedule_xcmp_task_extrinsic(0))
+			.ref_time()
+	}
+
 	pub fn schedule_fee_location<T: Config>(&self) -> MultiLocation {
 		match self {
 			Action::XCMP { schedule_fee, .. } => *schedule_fee,
-			_ => MultiLocation::default(),
+			Action::DynamicDispatch { schedule_fee, .. } => *schedule_fee,
+		}
+	}
+
+	pub fn kind(&self) -> ActionKind {
+		match self {
+			Action::XCMP { .. } => ActionKind::XCMP,
+			Action::DynamicDispatch { .. } => ActionKind::DynamicDispatch,
+		}
+	}
+
+	/// The chain the action is carried out on.
+	pub fn destination(&self) -> MultiLocation {
+		match self {
+			Action::XCMP { destination, .. } => *destination,
+			Action::DynamicDispatch { .. } => MultiLocation::here(),
+		}
+	}
+}
+
+/// The kind of an action, without its data.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Encode, Decode, TypeInfo)]
+pub enum ActionKind {
+	XCMP,
+	DynamicDispatch,
+}
+
+/// What the action of a task did when it ran successfully.
+#[derive(Clone, Debug, Eq, PartialEq, Encode, Decode, TypeInfo)]
+pub enum ActionOutcome {
+	/// The call was sent to `destination` in an XCM message.
+	XcmSent { destination: MultiLocation },
+	/// The call was dispatched on this chain as the owner of the task.
+	Dispatched,
+}
+
+/// Where a task stands in its life.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Encode, Decode, TypeInfo)]
+pub enum TaskStatus {
+	/// Waiting for its price condition.
+	Scheduled,
+	/// Triggered and waiting in the task queue or the missed tasks queue to run.
+	Queued,
+	/// A recurring task waiting out its cooldown, or a task waiting for its time window to open.
+	CoolingDown,
+	/// An XCMP task waiting to send its message again.
+	RetryingXcmSend,
+	/// Paused by its owner.
+	Paused,
+	/// Past its expiry and about to be removed.
+	Expired,
+}
+
+/// Selects the tasks cancelled by an emergency purge.
+#[derive(Clone, Debug, Eq, PartialEq, Encode, Decode, TypeInfo)]
+pub enum PurgeFilter {
+	/// Every task whose action targets this chain.
+	Destination(MultiLocation),
+	/// Every task triggered by the price of this asset pair.
+	AssetPair { chain: Vec<u8>, exchange: Vec<u8>, asset1: Vec<u8>, asset2: Vec<u8> },
+}
+
+impl PurgeFilter {
+	pub fn matches<T: Config>(&self, task: &Task<T>) -> bool {
+		match self {
+			PurgeFilter::Destination(location) => task.action.destination() == *location,
+			PurgeFilter::AssetPair { chain, exchange, asset1, asset2 } =>
+				task.chain == *chain &&
+					task.exchange == *exchange &&
+					task.asset_pair.0 == *asset1 &&
+					task.asset_pair.1 == *asset2,
 		}
 	}
 }
@@ -52,3 +149,206 @@ pub enum StatType {
 	TotalTasksOverall,
 	TotalTasksPerAccount,
 }
+
+/// Progress of the multi-block SortedTasksIndex rebuild. Cursors are raw storage keys to resume
+/// from in the next block.
+#[derive(Clone, Debug, Eq, PartialEq, Encode, Decode, TypeInfo)]
+pub enum IndexMigrationStage {
+	/// Removing the entries of the old index.
+	Clearing { cursor: Option<Vec<u8>> },
+	/// Re-indexing every task in `Tasks`.
+	Rebuilding { cursor: Option<Vec<u8>>, migrated: u32 },
+}
+
+/// A trigger index whose matched tasks are shifted into the task queue.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Encode, Decode, TypeInfo)]
+pub enum ShiftIndex {
+	/// `SortedTasksAbove`, the tasks triggered by the price rising above their target.
+	SortedAbove,
+	/// `SortedTasksBelow`, the tasks triggered by the price falling below their target.
+	SortedBelow,
+	/// `UnsortedTasks`, the tasks whose trigger is checked one by one.
+	Unsorted,
+}
+
+/// What became of an oracle price submission.
+#[derive(Clone, Debug, Eq, PartialEq, Encode, Decode, TypeInfo)]
+pub enum SubmissionOutcome {
+	/// The price was written to `PriceRegistry`.
+	Accepted,
+	/// The price was discarded for `error`.
+	Rejected { error: DispatchError },
+}
+
+/// An entry of the oracle submissions log.
+#[derive(Clone, Debug, Eq, PartialEq, Encode, Decode, TypeInfo)]
+pub struct OracleSubmission<AccountId> {
+	pub submitter: AccountId,
+	pub chain: Vec<u8>,
+	pub exchange: Vec<u8>,
+	pub asset_pair: (Vec<u8>, Vec<u8>),
+	/// The round given by the submitter.
+	pub round: u128,
+	pub submitted_at: u128,
+	pub value: u128,
+	pub outcome: SubmissionOutcome,
+}
+
+/// The parts of the pallet that are halted. Each of them can be halted on its own, so that for
+/// example scheduling can be stopped during an incident while existing tasks keep executing and
+/// can still be cancelled.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Encode, Decode, TypeInfo)]
+pub struct ShutdownFlags {
+	/// No new tasks can be scheduled.
+	pub scheduling: bool,
+	/// Tasks are not triggered or executed.
+	pub execution: bool,
+	/// Oracle price updates are refused.
+	pub price_ingestion: bool,
+}
+
+impl ShutdownFlags {
+	/// Every part of the pallet halted.
+	pub fn all() -> Self {
+		ShutdownFlags { scheduling: true, execution: true, price_ingestion: true }
+	}
+}
+
+/// Halts the tasks of an asset pair when its price moves too far in a single round, to protect
+/// them from a flash crash or an oracle error.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Encode, Decode, TypeInfo)]
+pub struct CircuitBreaker {
+	/// The largest move from the current price that a new price can make without halting the
+	/// asset pair.
+	pub max_deviation: Perbill,
+	/// The number of seconds after which a halted asset pair resumes on its own. Without one,
+	/// the asset pair stays halted until `resume_asset` is called.
+	pub cooldown: Option<u64>,
+}
+
+/// Who maintains an asset pair in the asset registry.
+#[derive(Clone, Debug, Eq, PartialEq, Encode, Decode, TypeInfo)]
+pub enum AssetOwner<AccountId> {
+	/// Governance, acting through the root origin.
+	Governance,
+	/// An account, which holds the deposit of the asset pair.
+	Account(AccountId),
+}
+
+/// The account maintaining an asset pair and the deposit it holds for it.
+#[derive(Clone, Debug, Eq, PartialEq, Encode, Decode, TypeInfo)]
+pub struct AssetOwnership<AccountId, Balance> {
+	pub owner: AccountId,
+	pub deposit: Balance,
+}
+
+/// How often a task runs.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Encode, Decode, TypeInfo)]
+pub enum Schedule {
+	/// The task runs once, then it is removed.
+	Once,
+	/// The task is armed again `cooldown_seconds` after each run, until it ran `max_executions`
+	/// more times, or without limit if that is `None`.
+	Recurring { cooldown_seconds: u64, max_executions: Option<u32> },
+}
+
+/// What happens to a task that was triggered but could not run before its price condition stopped
+/// matching, such as when blocks were full or the chain was down.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Encode, Decode, TypeInfo)]
+pub enum MissedTaskPolicy {
+	/// The task runs anyway, once the tasks whose condition still matches have run.
+	ExecuteLate,
+	/// The task waits for its condition to match again.
+	#[default]
+	Skip,
+	/// The task is cancelled and its execution fee refunded.
+	CancelAndRefund,
+}
+
+/// The seconds in a day.
+pub const SECONDS_PER_DAY: u32 = 86_400;
+
+/// The time of day, in UTC, that a task can run in. A task triggered outside of it waits for it to
+/// open, and runs then if its price condition still matches.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Encode, Decode, TypeInfo)]
+pub struct TimeWindow {
+	/// The second of the day the window opens at.
+	pub start: u32,
+	/// The second of the day the window closes at. A window closing before it opens spans
+	/// midnight.
+	pub end: u32,
+}
+
+impl TimeWindow {
+	pub fn is_valid(&self) -> bool {
+		self.start < SECONDS_PER_DAY && self.end < SECONDS_PER_DAY && self.start != self.end
+	}
+
+	/// Whether the window is open at `now`, in seconds since the Unix epoch.
+	pub fn contains(&self, now: u128) -> bool {
+		let time = (now % SECONDS_PER_DAY as u128) as u32;
+		if self.start < self.end {
+			self.start <= time && time < self.end
+		} else {
+			self.start <= time || time < self.end
+		}
+	}
+
+	/// The first time from `now` on that the window is open.
+	pub fn next_open(&self, now: u128) -> u128 {
+		if self.contains(now) {
+			return now
+		}
+		let time = now % SECONDS_PER_DAY as u128;
+		let day_start = now - time;
+		if time < self.start as u128 {
+			day_start + self.start as u128
+		} else {
+			day_start + SECONDS_PER_DAY as u128 + self.start as u128
+		}
+	}
+}
+
+/// How the price condition of a task is combined with its extra conditions.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Encode, Decode, TypeInfo)]
+pub enum Combinator {
+	/// The task triggers when all of its conditions match.
+	And,
+	/// The task triggers when any of its conditions matches.
+	Or,
+}
+
+/// A condition on the price of an asset pair, checked like the price condition of a task.
+#[derive(Clone, Debug, Eq, PartialEq, Encode, Decode, TypeInfo)]
+pub struct PriceCondition {
+	pub chain: Vec<u8>,
+	pub exchange: Vec<u8>,
+	pub asset_pair: (Vec<u8>, Vec<u8>),
+	pub trigger_function: Vec<u8>,
+	pub trigger_params: Vec<u128>,
+}
+
+/// The conditions a task combines with its own price condition, such as `DOT/USDT > X AND
+/// KSM/USDT < Y`.
+#[derive(Clone, Debug, Eq, PartialEq, Encode, Decode, TypeInfo)]
+pub struct CompoundCondition {
+	pub combinator: Combinator,
+	pub conditions: Vec<PriceCondition>,
+}
+
+/// What is left of the schedule of a recurring task.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Encode, Decode, TypeInfo)]
+pub struct Recurrence {
+	pub cooldown_seconds: u64,
+	/// The runs left, without limit if `None`.
+	pub remaining_executions: Option<u32>,
+}
+
+/// An XCMP task waiting to send again the message that could not be sent to its destination.
+#[derive(Clone, Debug, Eq, PartialEq, Encode, Decode, TypeInfo)]
+pub struct XcmpRetry<AccountId> {
+	pub owner_id: AccountId,
+	pub task_id: TaskId,
+	/// The number of times the message was sent without success.
+	pub attempts: u32,
+}
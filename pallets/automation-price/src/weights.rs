@@ -50,13 +50,38 @@
 // ./.maintain/frame-weight-template.hbs
 
 // Summary:
-//:initialize_asset_extrinsic 20_554_305,3541
-//:asset_price_update_extrinsic 7_797_323,1493
-//:schedule_xcmp_task_extrinsic 12_384_000,1493
+//:initialize_asset_extrinsic 39_127_806,3593
+//:asset_price_update_extrinsic 14_806_000,1493
+//:delete_asset_extrinsic 62_387_000,3824
+//:schedule_xcmp_task_extrinsic 12_197_416,1493
 //:cancel_task_extrinsic 9_310_000,3579
 //:run_xcmp_task 42_789_000,3946
 //:remove_task 21_707_000,3579
 //:emit_event 5_274_000,0
+//:purge_tasks_extrinsic 15_402_000,3652
+//:reclaim_deposit 26_847_000,3593
+//:shift_tasks 31_204_118,6187
+//:update_task_queue 4_861_503,1561
+//:set_shutdown_flags 8_914_000,0
+//:update_task_trigger 38_512_000,4127
+//:transfer_asset_ownership 16_203_000,3613
+//:accept_asset_ownership 31_846_000,3613
+//:set_task_schedule 18_391_000,4127
+//:run_dynamic_dispatch_action 8_778_000,3598
+//:run_dynamic_dispatch_action_fail_decode 965_000,0
+//:add_oracle_provider 14_722_000,3541
+//:remove_oracle_provider 15_108_000,3573
+//:set_asset_symbol 17_356_000,3613
+//:set_circuit_breaker 17_742_000,3613
+//:resume_asset 14_215_000,3580
+//:set_missed_task_policy 15_632_000,4127
+//:pause_task 31_418_000,4127
+//:resume_task 29_870_000,4127
+//:set_task_time_window 15_871_000,4127
+//:set_task_conditions 48_215_000,14562
+//:set_asset_task_cap 12_934_000,3613
+//:set_task_tip 24_318_000,4127
+//:execute_ready_tasks 21_574_000,3613
 
 #![cfg_attr(rustfmt, rustfmt_skip)]
 #![allow(unused_parens)]
@@ -70,51 +95,146 @@ use core::marker::PhantomData;
 pub trait WeightInfo {
 	fn initialize_asset_extrinsic(v: u32, ) -> Weight;
 	fn asset_price_update_extrinsic(v: u32, ) -> Weight;
-	fn schedule_xcmp_task_extrinsic() -> Weight;
+	fn delete_asset_extrinsic(v: u32, ) -> Weight;
+	fn schedule_xcmp_task_extrinsic(v: u32, ) -> Weight;
 	fn cancel_task_extrinsic() -> Weight;
 	fn run_xcmp_task() -> Weight;
 	fn remove_task() -> Weight;
 	fn emit_event() -> Weight;
+	fn purge_tasks_extrinsic(v: u32, ) -> Weight;
+	fn reclaim_deposit() -> Weight;
+	fn shift_tasks(v: u32, ) -> Weight;
+	fn update_task_queue(v: u32, ) -> Weight;
+	fn set_shutdown_flags() -> Weight;
+	fn update_task_trigger() -> Weight;
+	fn transfer_asset_ownership() -> Weight;
+	fn accept_asset_ownership() -> Weight;
+	fn set_task_schedule() -> Weight;
+	fn run_dynamic_dispatch_action() -> Weight;
+	fn run_dynamic_dispatch_action_fail_decode() -> Weight;
+	fn add_oracle_provider() -> Weight;
+	fn remove_oracle_provider() -> Weight;
+	fn set_asset_symbol() -> Weight;
+	fn set_circuit_breaker() -> Weight;
+	fn resume_asset() -> Weight;
+	fn set_missed_task_policy() -> Weight;
+	fn pause_task() -> Weight;
+	fn resume_task() -> Weight;
+	fn set_task_time_window() -> Weight;
+	fn set_task_conditions() -> Weight;
+	fn set_asset_task_cap() -> Weight;
+	fn set_task_tip() -> Weight;
+	fn execute_ready_tasks(v: u32, ) -> Weight;
 }
 
 /// Weights for pallet_automation_price using the Substrate node and recommended hardware.
 pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
 	/// Storage: AutomationPrice AssetRegistry (r:1 w:1)
 	/// Proof Skipped: AutomationPrice AssetRegistry (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice AssetCount (r:1 w:1)
+	/// Proof Skipped: AutomationPrice AssetCount (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: AutomationPrice AssetOwners (r:0 w:1)
+	/// Proof Skipped: AutomationPrice AssetOwners (max_values: None, max_size: None, mode: Measured)
 	/// The range of component `v` is `[1, 5]`.
 	fn initialize_asset_extrinsic(v: u32, ) -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `76`
-		//  Estimated: `3541`
-		// Minimum execution time: 19_966_000 picoseconds.
-		Weight::from_parts(20_554_305, 3541)
-			// Standard Error: 4_362
-			.saturating_add(Weight::from_parts(23_140, 0).saturating_mul(v.into()))
-			.saturating_add(T::DbWeight::get().reads(1_u64))
-			.saturating_add(T::DbWeight::get().writes(1_u64))
+		//  Measured:  `182`
+		//  Estimated: `3593`
+		// Minimum execution time: 38_412_000 picoseconds.
+		Weight::from_parts(39_127_806, 3593)
+			// Standard Error: 5_018
+			.saturating_add(Weight::from_parts(24_716, 0).saturating_mul(v.into()))
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
 	}
+	/// Storage: AutomationPrice ShutdownFlags (r:1 w:0)
+	/// Proof Skipped: AutomationPrice ShutdownFlags (max_values: Some(1), max_size: None, mode: Measured)
 	/// Storage: Timestamp Now (r:1 w:0)
 	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: AutomationPrice AssetRegistry (r:100 w:0)
+	/// Proof Skipped: AutomationPrice AssetRegistry (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice PriceRegistry (r:100 w:100)
+	/// Proof Skipped: AutomationPrice PriceRegistry (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice RoundPrices (r:100 w:100)
+	/// Proof Skipped: AutomationPrice RoundPrices (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice OracleRounds (r:100 w:100)
+	/// Proof Skipped: AutomationPrice OracleRounds (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice CircuitBreakers (r:100 w:0)
+	/// Proof Skipped: AutomationPrice CircuitBreakers (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice HaltedAssets (r:100 w:0)
+	/// Proof Skipped: AutomationPrice HaltedAssets (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice PriceHistory (r:100 w:100)
+	/// Proof Skipped: AutomationPrice PriceHistory (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice OracleSubmissions (r:1 w:1)
+	/// Proof Skipped: AutomationPrice OracleSubmissions (max_values: None, max_size: None, mode: Measured)
 	/// The range of component `v` is `[1, 100]`.
 	fn asset_price_update_extrinsic(v: u32, ) -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `76`
-		//  Estimated: `1493`
-		// Minimum execution time: 7_412_000 picoseconds.
-		Weight::from_parts(7_797_323, 1493)
-			// Standard Error: 196
-			.saturating_add(Weight::from_parts(165_275, 0).saturating_mul(v.into()))
-			.saturating_add(T::DbWeight::get().reads(1_u64))
+		//  Measured:  `142 + v * (329 ±0)`
+		//  Estimated: `1493 + v * (2804 ±0)`
+		// Minimum execution time: 45_113_000 picoseconds.
+		Weight::from_parts(14_806_000, 1493)
+			// Standard Error: 21_472
+			.saturating_add(Weight::from_parts(34_529_813, 0).saturating_mul(v.into()))
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().reads((7_u64).saturating_mul(v.into())))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+			.saturating_add(T::DbWeight::get().writes((4_u64).saturating_mul(v.into())))
+			.saturating_add(Weight::from_parts(0, 2804).saturating_mul(v.into()))
+	}
+	/// Storage: AutomationPrice AssetRegistry (r:1 w:1)
+	/// Proof Skipped: AutomationPrice AssetRegistry (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice SortedTasksAbove (r:1 w:1)
+	/// Proof Skipped: AutomationPrice SortedTasksAbove (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice SortedTasksBelow (r:1 w:1)
+	/// Proof Skipped: AutomationPrice SortedTasksBelow (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice UnsortedTasks (r:1 w:1)
+	/// Proof Skipped: AutomationPrice UnsortedTasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice TaskQueue (r:1 w:1)
+	/// Proof Skipped: AutomationPrice TaskQueue (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: AutomationPrice Tasks (r:200 w:200)
+	/// Proof Skipped: AutomationPrice Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice TaskStats (r:1 w:1)
+	/// Proof Skipped: AutomationPrice TaskStats (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice AccountStats (r:200 w:200)
+	/// Proof Skipped: AutomationPrice AccountStats (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice SortedTasksByExpiration (r:1 w:1)
+	/// Proof Skipped: AutomationPrice SortedTasksByExpiration (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: AutomationPrice AssetOwners (r:1 w:1)
+	/// Proof Skipped: AutomationPrice AssetOwners (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice AssetCount (r:1 w:1)
+	/// Proof Skipped: AutomationPrice AssetCount (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// The range of component `v` is `[0, 200]`.
+	fn delete_asset_extrinsic(v: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `359 + v * (371 ±0)`
+		//  Estimated: `3824 + v * (2846 ±0)`
+		// Minimum execution time: 61_204_000 picoseconds.
+		Weight::from_parts(62_387_000, 3824)
+			// Standard Error: 12_904
+			.saturating_add(Weight::from_parts(38_471_226, 0).saturating_mul(v.into()))
+			.saturating_add(T::DbWeight::get().reads(11_u64))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(v.into())))
+			.saturating_add(T::DbWeight::get().writes(19_u64))
+			.saturating_add(T::DbWeight::get().writes((2_u64).saturating_mul(v.into())))
+			.saturating_add(Weight::from_parts(0, 2846).saturating_mul(v.into()))
 	}
 	/// Storage: Timestamp Now (r:1 w:0)
 	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
-	fn schedule_xcmp_task_extrinsic() -> Weight {
+	/// The range of component `v` is `[1, 10240]`.
+	fn schedule_xcmp_task_extrinsic(v: u32, ) -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `76`
 		//  Estimated: `1493`
 		// Minimum execution time: 12_253_000 picoseconds.
-		Weight::from_parts(12_384_000, 1493)
+		Weight::from_parts(12_197_416, 1493)
+			// Standard Error: 14
+			.saturating_add(Weight::from_parts(1_208, 0).saturating_mul(v.into()))
 			.saturating_add(T::DbWeight::get().reads(1_u64))
 	}
 	/// Storage: AutomationPrice Tasks (r:1 w:0)
@@ -167,45 +287,509 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 		// Minimum execution time: 5_119_000 picoseconds.
 		Weight::from_parts(5_274_000, 0)
 	}
+	/// Storage: AutomationPrice PurgeCursors (r:1 w:1)
+	/// Proof Skipped: AutomationPrice PurgeCursors (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice Tasks (r:200 w:200)
+	/// Proof Skipped: AutomationPrice Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice SortedTasksIndex (r:1 w:1)
+	/// Proof Skipped: AutomationPrice SortedTasksIndex (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice SortedTasksByExpiration (r:1 w:1)
+	/// Proof Skipped: AutomationPrice SortedTasksByExpiration (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: AutomationPrice TaskStats (r:1 w:1)
+	/// Proof Skipped: AutomationPrice TaskStats (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice AccountStats (r:1 w:1)
+	/// Proof Skipped: AutomationPrice AccountStats (max_values: None, max_size: None, mode: Measured)
+	/// The range of component `v` is `[1, 200]`.
+	fn purge_tasks_extrinsic(v: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `187 + v * (364 ±0)`
+		//  Estimated: `3652 + v * (2839 ±0)`
+		// Minimum execution time: 14_906_000 picoseconds.
+		Weight::from_parts(15_402_000, 3652)
+			// Standard Error: 9_217
+			.saturating_add(Weight::from_parts(31_862_000, 0).saturating_mul(v.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().reads((5_u64).saturating_mul(v.into())))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+			.saturating_add(T::DbWeight::get().writes((5_u64).saturating_mul(v.into())))
+			.saturating_add(Weight::from_parts(0, 2839).saturating_mul(v.into()))
+	}
+	/// Storage: AutomationPrice TaskDeposits (r:1 w:1)
+	/// Proof Skipped: AutomationPrice TaskDeposits (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice Tasks (r:1 w:0)
+	/// Proof Skipped: AutomationPrice Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn reclaim_deposit() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `128`
+		//  Estimated: `3593`
+		// Minimum execution time: 26_041_000 picoseconds.
+		Weight::from_parts(26_847_000, 3593)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: AutomationPrice SortedTasksAbove (r:2 w:1)
+	/// Proof Skipped: AutomationPrice SortedTasksAbove (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice SortedTasksBelow (r:1 w:0)
+	/// Proof Skipped: AutomationPrice SortedTasksBelow (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice PriceRegistry (r:1 w:0)
+	/// Proof Skipped: AutomationPrice PriceRegistry (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice LastShiftedPrice (r:1 w:1)
+	/// Proof Skipped: AutomationPrice LastShiftedPrice (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice TaskQueue (r:1 w:1)
+	/// Proof Skipped: AutomationPrice TaskQueue (max_values: Some(1), max_size: None, mode: Measured)
+	/// The range of component `v` is `[1, 100]`.
+	fn shift_tasks(v: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `722 + v * (81 ±0)`
+		//  Estimated: `6187 + v * (81 ±0)`
+		// Minimum execution time: 33_560_000 picoseconds.
+		Weight::from_parts(31_204_118, 6187)
+			// Standard Error: 2_917
+			.saturating_add(Weight::from_parts(1_873_412, 0).saturating_mul(v.into()))
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+			.saturating_add(Weight::from_parts(0, 81).saturating_mul(v.into()))
+	}
+	/// Storage: AutomationPrice TaskQueue (r:1 w:1)
+	/// Proof Skipped: AutomationPrice TaskQueue (max_values: Some(1), max_size: None, mode: Measured)
+	/// The range of component `v` is `[0, 16384]`.
+	fn update_task_queue(v: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `76 + v * (81 ±0)`
+		//  Estimated: `1561 + v * (81 ±0)`
+		// Minimum execution time: 4_409_000 picoseconds.
+		Weight::from_parts(4_861_503, 1561)
+			// Standard Error: 38
+			.saturating_add(Weight::from_parts(212_604, 0).saturating_mul(v.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+			.saturating_add(Weight::from_parts(0, 81).saturating_mul(v.into()))
+	}
+	/// Storage: AutomationPrice Shutdown (r:0 w:1)
+	/// Proof Skipped: AutomationPrice Shutdown (max_values: Some(1), max_size: None, mode: Measured)
+	fn set_shutdown_flags() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 8_602_000 picoseconds.
+		Weight::from_parts(8_914_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: AutomationPrice Shutdown (r:1 w:0)
+	/// Proof Skipped: AutomationPrice Shutdown (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: AutomationPrice Tasks (r:1 w:1)
+	/// Proof Skipped: AutomationPrice Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice SortedTasksAbove (r:1 w:1)
+	/// Proof Skipped: AutomationPrice SortedTasksAbove (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice TaskDeposits (r:1 w:1)
+	/// Proof Skipped: AutomationPrice TaskDeposits (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: AutomationPrice LastShiftedPrice (r:0 w:1)
+	/// Proof Skipped: AutomationPrice LastShiftedPrice (max_values: None, max_size: None, mode: Measured)
+	fn update_task_trigger() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `662`
+		//  Estimated: `4127`
+		// Minimum execution time: 37_894_000 picoseconds.
+		Weight::from_parts(38_512_000, 4127)
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(5_u64))
+	}
+	/// Storage: AutomationPrice AssetRegistry (r:1 w:0)
+	/// Proof Skipped: AutomationPrice AssetRegistry (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice AssetOwners (r:1 w:0)
+	/// Proof Skipped: AutomationPrice AssetOwners (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice PendingAssetOwners (r:0 w:1)
+	/// Proof Skipped: AutomationPrice PendingAssetOwners (max_values: None, max_size: None, mode: Measured)
+	fn transfer_asset_ownership() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `148`
+		//  Estimated: `3613`
+		// Minimum execution time: 15_870_000 picoseconds.
+		Weight::from_parts(16_203_000, 3613)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: AutomationPrice PendingAssetOwners (r:1 w:1)
+	/// Proof Skipped: AutomationPrice PendingAssetOwners (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice AssetOwners (r:1 w:1)
+	/// Proof Skipped: AutomationPrice AssetOwners (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn accept_asset_ownership() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `148`
+		//  Estimated: `3613`
+		// Minimum execution time: 31_207_000 picoseconds.
+		Weight::from_parts(31_846_000, 3613)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	/// Storage: AutomationPrice Shutdown (r:1 w:0)
+	/// Proof Skipped: AutomationPrice Shutdown (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: AutomationPrice Tasks (r:1 w:0)
+	/// Proof Skipped: AutomationPrice Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice TaskRecurrences (r:0 w:1)
+	/// Proof Skipped: AutomationPrice TaskRecurrences (max_values: None, max_size: None, mode: Measured)
+	fn set_task_schedule() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `662`
+		//  Estimated: `4127`
+		// Minimum execution time: 17_904_000 picoseconds.
+		Weight::from_parts(18_391_000, 4127)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: Valve ValveClosed (r:1 w:0)
+	/// Proof Skipped: Valve ValveClosed (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Valve ClosedPallets (r:1 w:0)
+	/// Proof Skipped: Valve ClosedPallets (max_values: None, max_size: None, mode: Measured)
+	fn run_dynamic_dispatch_action() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `133`
+		//  Estimated: `3598`
+		// Minimum execution time: 8_518_000 picoseconds.
+		Weight::from_parts(8_778_000, 3598)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+	}
+	fn run_dynamic_dispatch_action_fail_decode() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 892_000 picoseconds.
+		Weight::from_parts(965_000, 0)
+	}
+	/// Storage: AutomationPrice AssetRegistry (r:1 w:1)
+	/// Proof Skipped: AutomationPrice AssetRegistry (max_values: None, max_size: None, mode: Measured)
+	fn add_oracle_provider() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `76`
+		//  Estimated: `3541`
+		// Minimum execution time: 14_297_000 picoseconds.
+		Weight::from_parts(14_722_000, 3541)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: AutomationPrice AssetRegistry (r:1 w:1)
+	/// Proof Skipped: AutomationPrice AssetRegistry (max_values: None, max_size: None, mode: Measured)
+	fn remove_oracle_provider() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `108`
+		//  Estimated: `3573`
+		// Minimum execution time: 14_683_000 picoseconds.
+		Weight::from_parts(15_108_000, 3573)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: AutomationPrice AssetRegistry (r:1 w:0)
+	/// Proof Skipped: AutomationPrice AssetRegistry (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice AssetOwners (r:1 w:0)
+	/// Proof Skipped: AutomationPrice AssetOwners (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice AssetSymbols (r:0 w:1)
+	/// Proof Skipped: AutomationPrice AssetSymbols (max_values: None, max_size: None, mode: Measured)
+	fn set_asset_symbol() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `148`
+		//  Estimated: `3613`
+		// Minimum execution time: 16_871_000 picoseconds.
+		Weight::from_parts(17_356_000, 3613)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: AutomationPrice AssetRegistry (r:1 w:0)
+	/// Proof Skipped: AutomationPrice AssetRegistry (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice AssetOwners (r:1 w:0)
+	/// Proof Skipped: AutomationPrice AssetOwners (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice CircuitBreakers (r:0 w:1)
+	/// Proof Skipped: AutomationPrice CircuitBreakers (max_values: None, max_size: None, mode: Measured)
+	fn set_circuit_breaker() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `148`
+		//  Estimated: `3613`
+		// Minimum execution time: 17_208_000 picoseconds.
+		Weight::from_parts(17_742_000, 3613)
+			.saturating_add(T::DbWeight::get().reads(2_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: AutomationPrice HaltedAssets (r:1 w:1)
+	/// Proof Skipped: AutomationPrice HaltedAssets (max_values: None, max_size: None, mode: Measured)
+	fn resume_asset() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `115`
+		//  Estimated: `3580`
+		// Minimum execution time: 13_801_000 picoseconds.
+		Weight::from_parts(14_215_000, 3580)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: AutomationPrice Tasks (r:1 w:0)
+	/// Proof Skipped: AutomationPrice Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice MissedTaskPolicies (r:0 w:1)
+	/// Proof Skipped: AutomationPrice MissedTaskPolicies (max_values: None, max_size: None, mode: Measured)
+	fn set_missed_task_policy() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `662`
+		//  Estimated: `4127`
+		// Minimum execution time: 15_208_000 picoseconds.
+		Weight::from_parts(15_632_000, 4127)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: AutomationPrice Tasks (r:1 w:0)
+	/// Proof Skipped: AutomationPrice Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice PausedTasks (r:1 w:1)
+	/// Proof Skipped: AutomationPrice PausedTasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice SortedBucketsAbove (r:1 w:1)
+	/// Proof Skipped: AutomationPrice SortedBucketsAbove (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice SortedTasksAbove (r:1 w:1)
+	/// Proof Skipped: AutomationPrice SortedTasksAbove (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice TaskQueue (r:1 w:0)
+	/// Proof Skipped: AutomationPrice TaskQueue (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: AutomationPrice MissedTasksQueue (r:1 w:0)
+	/// Proof Skipped: AutomationPrice MissedTasksQueue (max_values: Some(1), max_size: None, mode: Measured)
+	fn pause_task() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `662`
+		//  Estimated: `4127`
+		// Minimum execution time: 30_745_000 picoseconds.
+		Weight::from_parts(31_418_000, 4127)
+			.saturating_add(T::DbWeight::get().reads(6_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
+	/// Storage: AutomationPrice Tasks (r:1 w:0)
+	/// Proof Skipped: AutomationPrice Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice PausedTasks (r:1 w:1)
+	/// Proof Skipped: AutomationPrice PausedTasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice CoolingDownTasks (r:1 w:0)
+	/// Proof Skipped: AutomationPrice CoolingDownTasks (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: AutomationPrice SortedBucketsAbove (r:1 w:1)
+	/// Proof Skipped: AutomationPrice SortedBucketsAbove (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice SortedTasksAbove (r:1 w:1)
+	/// Proof Skipped: AutomationPrice SortedTasksAbove (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice LastShiftedPrice (r:0 w:1)
+	/// Proof Skipped: AutomationPrice LastShiftedPrice (max_values: None, max_size: None, mode: Measured)
+	fn resume_task() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `662`
+		//  Estimated: `4127`
+		// Minimum execution time: 29_214_000 picoseconds.
+		Weight::from_parts(29_870_000, 4127)
+			.saturating_add(T::DbWeight::get().reads(5_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	/// Storage: AutomationPrice Tasks (r:1 w:0)
+	/// Proof Skipped: AutomationPrice Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice TaskTimeWindows (r:0 w:1)
+	/// Proof Skipped: AutomationPrice TaskTimeWindows (max_values: None, max_size: None, mode: Measured)
+	fn set_task_time_window() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `662`
+		//  Estimated: `4127`
+		// Minimum execution time: 15_394_000 picoseconds.
+		Weight::from_parts(15_871_000, 4127)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: AutomationPrice Shutdown (r:1 w:0)
+	/// Proof Skipped: AutomationPrice Shutdown (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: AutomationPrice Tasks (r:1 w:0)
+	/// Proof Skipped: AutomationPrice Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice AssetRegistry (r:4 w:0)
+	/// Proof Skipped: AutomationPrice AssetRegistry (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice PriceRegistry (r:4 w:0)
+	/// Proof Skipped: AutomationPrice PriceRegistry (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice TaskConditions (r:2 w:1)
+	/// Proof Skipped: AutomationPrice TaskConditions (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice SortedTasksAbove (r:1 w:1)
+	/// Proof Skipped: AutomationPrice SortedTasksAbove (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice PausedTasks (r:1 w:0)
+	/// Proof Skipped: AutomationPrice PausedTasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice UnsortedTasks (r:5 w:5)
+	/// Proof Skipped: AutomationPrice UnsortedTasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice LastShiftedPrice (r:0 w:5)
+	/// Proof Skipped: AutomationPrice LastShiftedPrice (max_values: None, max_size: None, mode: Measured)
+	fn set_task_conditions() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1587`
+		//  Estimated: `14562`
+		// Minimum execution time: 47_301_000 picoseconds.
+		Weight::from_parts(48_215_000, 14562)
+			.saturating_add(T::DbWeight::get().reads(19_u64))
+			.saturating_add(T::DbWeight::get().writes(12_u64))
+	}
+	/// Storage: AutomationPrice AssetRegistry (r:1 w:0)
+	/// Proof Skipped: AutomationPrice AssetRegistry (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice AssetTaskCaps (r:0 w:1)
+	/// Proof Skipped: AutomationPrice AssetTaskCaps (max_values: None, max_size: None, mode: Measured)
+	fn set_asset_task_cap() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `148`
+		//  Estimated: `3613`
+		// Minimum execution time: 12_518_000 picoseconds.
+		Weight::from_parts(12_934_000, 3613)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: AutomationPrice Tasks (r:1 w:0)
+	/// Proof Skipped: AutomationPrice Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice TaskTips (r:1 w:1)
+	/// Proof Skipped: AutomationPrice TaskTips (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn set_task_tip() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `662`
+		//  Estimated: `4127`
+		// Minimum execution time: 23_794_000 picoseconds.
+		Weight::from_parts(24_318_000, 4127)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: AutomationPrice Shutdown (r:1 w:0)
+	/// Proof Skipped: AutomationPrice Shutdown (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: AutomationPrice IndexMigration (r:1 w:0)
+	/// Proof Skipped: AutomationPrice IndexMigration (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: AutomationPrice TaskQueue (r:1 w:1)
+	/// Proof Skipped: AutomationPrice TaskQueue (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: AutomationPrice Tasks (r:1 w:1)
+	/// Proof Skipped: AutomationPrice Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice PriceRegistry (r:1 w:0)
+	/// Proof Skipped: AutomationPrice PriceRegistry (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice TaskTips (r:1 w:0)
+	/// Proof Skipped: AutomationPrice TaskTips (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice KeeperRewards (r:1 w:1)
+	/// Proof Skipped: AutomationPrice KeeperRewards (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// The range of component `v` is `[1, 64]`.
+	fn execute_ready_tasks(v: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `148 + v * (412 ±0)`
+		//  Estimated: `3613 + v * (6196 ±0)`
+		// Minimum execution time: 21_032_000 picoseconds.
+		Weight::from_parts(21_574_000, 3613)
+			// Standard Error: 9_814
+			.saturating_add(Weight::from_parts(61_238_417, 0).saturating_mul(v.into()))
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().reads((7_u64).saturating_mul(v.into())))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+			.saturating_add(T::DbWeight::get().writes((5_u64).saturating_mul(v.into())))
+			.saturating_add(Weight::from_parts(0, 6196).saturating_mul(v.into()))
+	}
 }
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
 	/// Storage: AutomationPrice AssetRegistry (r:1 w:1)
 	/// Proof Skipped: AutomationPrice AssetRegistry (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice AssetCount (r:1 w:1)
+	/// Proof Skipped: AutomationPrice AssetCount (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: AutomationPrice AssetOwners (r:0 w:1)
+	/// Proof Skipped: AutomationPrice AssetOwners (max_values: None, max_size: None, mode: Measured)
 	/// The range of component `v` is `[1, 5]`.
 	fn initialize_asset_extrinsic(v: u32, ) -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `76`
-		//  Estimated: `3541`
-		// Minimum execution time: 19_966_000 picoseconds.
-		Weight::from_parts(20_554_305, 3541)
-			// Standard Error: 4_362
-			.saturating_add(Weight::from_parts(23_140, 0).saturating_mul(v.into()))
-			.saturating_add(RocksDbWeight::get().reads(1_u64))
-			.saturating_add(RocksDbWeight::get().writes(1_u64))
+		//  Measured:  `182`
+		//  Estimated: `3593`
+		// Minimum execution time: 38_412_000 picoseconds.
+		Weight::from_parts(39_127_806, 3593)
+			// Standard Error: 5_018
+			.saturating_add(Weight::from_parts(24_716, 0).saturating_mul(v.into()))
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
 	}
+	/// Storage: AutomationPrice ShutdownFlags (r:1 w:0)
+	/// Proof Skipped: AutomationPrice ShutdownFlags (max_values: Some(1), max_size: None, mode: Measured)
 	/// Storage: Timestamp Now (r:1 w:0)
 	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: AutomationPrice AssetRegistry (r:100 w:0)
+	/// Proof Skipped: AutomationPrice AssetRegistry (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice PriceRegistry (r:100 w:100)
+	/// Proof Skipped: AutomationPrice PriceRegistry (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice RoundPrices (r:100 w:100)
+	/// Proof Skipped: AutomationPrice RoundPrices (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice OracleRounds (r:100 w:100)
+	/// Proof Skipped: AutomationPrice OracleRounds (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice CircuitBreakers (r:100 w:0)
+	/// Proof Skipped: AutomationPrice CircuitBreakers (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice HaltedAssets (r:100 w:0)
+	/// Proof Skipped: AutomationPrice HaltedAssets (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice PriceHistory (r:100 w:100)
+	/// Proof Skipped: AutomationPrice PriceHistory (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice OracleSubmissions (r:1 w:1)
+	/// Proof Skipped: AutomationPrice OracleSubmissions (max_values: None, max_size: None, mode: Measured)
 	/// The range of component `v` is `[1, 100]`.
 	fn asset_price_update_extrinsic(v: u32, ) -> Weight {
 		// Proof Size summary in bytes:
-		//  Measured:  `76`
-		//  Estimated: `1493`
-		// Minimum execution time: 7_412_000 picoseconds.
-		Weight::from_parts(7_797_323, 1493)
-			// Standard Error: 196
-			.saturating_add(Weight::from_parts(165_275, 0).saturating_mul(v.into()))
-			.saturating_add(RocksDbWeight::get().reads(1_u64))
+		//  Measured:  `142 + v * (329 ±0)`
+		//  Estimated: `1493 + v * (2804 ±0)`
+		// Minimum execution time: 45_113_000 picoseconds.
+		Weight::from_parts(14_806_000, 1493)
+			// Standard Error: 21_472
+			.saturating_add(Weight::from_parts(34_529_813, 0).saturating_mul(v.into()))
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().reads((7_u64).saturating_mul(v.into())))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+			.saturating_add(RocksDbWeight::get().writes((4_u64).saturating_mul(v.into())))
+			.saturating_add(Weight::from_parts(0, 2804).saturating_mul(v.into()))
+	}
+	/// Storage: AutomationPrice AssetRegistry (r:1 w:1)
+	/// Proof Skipped: AutomationPrice AssetRegistry (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice SortedTasksAbove (r:1 w:1)
+	/// Proof Skipped: AutomationPrice SortedTasksAbove (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice SortedTasksBelow (r:1 w:1)
+	/// Proof Skipped: AutomationPrice SortedTasksBelow (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice UnsortedTasks (r:1 w:1)
+	/// Proof Skipped: AutomationPrice UnsortedTasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice TaskQueue (r:1 w:1)
+	/// Proof Skipped: AutomationPrice TaskQueue (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: AutomationPrice Tasks (r:200 w:200)
+	/// Proof Skipped: AutomationPrice Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice TaskStats (r:1 w:1)
+	/// Proof Skipped: AutomationPrice TaskStats (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice AccountStats (r:200 w:200)
+	/// Proof Skipped: AutomationPrice AccountStats (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice SortedTasksByExpiration (r:1 w:1)
+	/// Proof Skipped: AutomationPrice SortedTasksByExpiration (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: AutomationPrice AssetOwners (r:1 w:1)
+	/// Proof Skipped: AutomationPrice AssetOwners (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice AssetCount (r:1 w:1)
+	/// Proof Skipped: AutomationPrice AssetCount (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// The range of component `v` is `[0, 200]`.
+	fn delete_asset_extrinsic(v: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `359 + v * (371 ±0)`
+		//  Estimated: `3824 + v * (2846 ±0)`
+		// Minimum execution time: 61_204_000 picoseconds.
+		Weight::from_parts(62_387_000, 3824)
+			// Standard Error: 12_904
+			.saturating_add(Weight::from_parts(38_471_226, 0).saturating_mul(v.into()))
+			.saturating_add(RocksDbWeight::get().reads(11_u64))
+			.saturating_add(RocksDbWeight::get().reads((2_u64).saturating_mul(v.into())))
+			.saturating_add(RocksDbWeight::get().writes(19_u64))
+			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(v.into())))
+			.saturating_add(Weight::from_parts(0, 2846).saturating_mul(v.into()))
 	}
 	/// Storage: Timestamp Now (r:1 w:0)
 	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
-	fn schedule_xcmp_task_extrinsic() -> Weight {
+	/// The range of component `v` is `[1, 10240]`.
+	fn schedule_xcmp_task_extrinsic(v: u32, ) -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `76`
 		//  Estimated: `1493`
 		// Minimum execution time: 12_253_000 picoseconds.
-		Weight::from_parts(12_384_000, 1493)
+		Weight::from_parts(12_197_416, 1493)
+			// Standard Error: 14
+			.saturating_add(Weight::from_parts(1_208, 0).saturating_mul(v.into()))
 			.saturating_add(RocksDbWeight::get().reads(1_u64))
 	}
 	/// Storage: AutomationPrice Tasks (r:1 w:0)
@@ -258,4 +842,398 @@ impl WeightInfo for () {
 		// Minimum execution time: 5_119_000 picoseconds.
 		Weight::from_parts(5_274_000, 0)
 	}
+	/// Storage: AutomationPrice PurgeCursors (r:1 w:1)
+	/// Proof Skipped: AutomationPrice PurgeCursors (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice Tasks (r:200 w:200)
+	/// Proof Skipped: AutomationPrice Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice SortedTasksIndex (r:1 w:1)
+	/// Proof Skipped: AutomationPrice SortedTasksIndex (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice SortedTasksByExpiration (r:1 w:1)
+	/// Proof Skipped: AutomationPrice SortedTasksByExpiration (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: AutomationPrice TaskStats (r:1 w:1)
+	/// Proof Skipped: AutomationPrice TaskStats (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice AccountStats (r:1 w:1)
+	/// Proof Skipped: AutomationPrice AccountStats (max_values: None, max_size: None, mode: Measured)
+	/// The range of component `v` is `[1, 200]`.
+	fn purge_tasks_extrinsic(v: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `187 + v * (364 ±0)`
+		//  Estimated: `3652 + v * (2839 ±0)`
+		// Minimum execution time: 14_906_000 picoseconds.
+		Weight::from_parts(15_402_000, 3652)
+			// Standard Error: 9_217
+			.saturating_add(Weight::from_parts(31_862_000, 0).saturating_mul(v.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().reads((5_u64).saturating_mul(v.into())))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+			.saturating_add(RocksDbWeight::get().writes((5_u64).saturating_mul(v.into())))
+			.saturating_add(Weight::from_parts(0, 2839).saturating_mul(v.into()))
+	}
+	/// Storage: AutomationPrice TaskDeposits (r:1 w:1)
+	/// Proof Skipped: AutomationPrice TaskDeposits (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice Tasks (r:1 w:0)
+	/// Proof Skipped: AutomationPrice Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn reclaim_deposit() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `128`
+		//  Estimated: `3593`
+		// Minimum execution time: 26_041_000 picoseconds.
+		Weight::from_parts(26_847_000, 3593)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: AutomationPrice SortedTasksAbove (r:2 w:1)
+	/// Proof Skipped: AutomationPrice SortedTasksAbove (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice SortedTasksBelow (r:1 w:0)
+	/// Proof Skipped: AutomationPrice SortedTasksBelow (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice PriceRegistry (r:1 w:0)
+	/// Proof Skipped: AutomationPrice PriceRegistry (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice LastShiftedPrice (r:1 w:1)
+	/// Proof Skipped: AutomationPrice LastShiftedPrice (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice TaskQueue (r:1 w:1)
+	/// Proof Skipped: AutomationPrice TaskQueue (max_values: Some(1), max_size: None, mode: Measured)
+	/// The range of component `v` is `[1, 100]`.
+	fn shift_tasks(v: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `722 + v * (81 ±0)`
+		//  Estimated: `6187 + v * (81 ±0)`
+		// Minimum execution time: 33_560_000 picoseconds.
+		Weight::from_parts(31_204_118, 6187)
+			// Standard Error: 2_917
+			.saturating_add(Weight::from_parts(1_873_412, 0).saturating_mul(v.into()))
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+			.saturating_add(Weight::from_parts(0, 81).saturating_mul(v.into()))
+	}
+	/// Storage: AutomationPrice TaskQueue (r:1 w:1)
+	/// Proof Skipped: AutomationPrice TaskQueue (max_values: Some(1), max_size: None, mode: Measured)
+	/// The range of component `v` is `[0, 16384]`.
+	fn update_task_queue(v: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `76 + v * (81 ±0)`
+		//  Estimated: `1561 + v * (81 ±0)`
+		// Minimum execution time: 4_409_000 picoseconds.
+		Weight::from_parts(4_861_503, 1561)
+			// Standard Error: 38
+			.saturating_add(Weight::from_parts(212_604, 0).saturating_mul(v.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+			.saturating_add(Weight::from_parts(0, 81).saturating_mul(v.into()))
+	}
+	/// Storage: AutomationPrice Shutdown (r:0 w:1)
+	/// Proof Skipped: AutomationPrice Shutdown (max_values: Some(1), max_size: None, mode: Measured)
+	fn set_shutdown_flags() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 8_602_000 picoseconds.
+		Weight::from_parts(8_914_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: AutomationPrice Shutdown (r:1 w:0)
+	/// Proof Skipped: AutomationPrice Shutdown (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: AutomationPrice Tasks (r:1 w:1)
+	/// Proof Skipped: AutomationPrice Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice SortedTasksAbove (r:1 w:1)
+	/// Proof Skipped: AutomationPrice SortedTasksAbove (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice TaskDeposits (r:1 w:1)
+	/// Proof Skipped: AutomationPrice TaskDeposits (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: AutomationPrice LastShiftedPrice (r:0 w:1)
+	/// Proof Skipped: AutomationPrice LastShiftedPrice (max_values: None, max_size: None, mode: Measured)
+	fn update_task_trigger() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `662`
+		//  Estimated: `4127`
+		// Minimum execution time: 37_894_000 picoseconds.
+		Weight::from_parts(38_512_000, 4127)
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(5_u64))
+	}
+	/// Storage: AutomationPrice AssetRegistry (r:1 w:0)
+	/// Proof Skipped: AutomationPrice AssetRegistry (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice AssetOwners (r:1 w:0)
+	/// Proof Skipped: AutomationPrice AssetOwners (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice PendingAssetOwners (r:0 w:1)
+	/// Proof Skipped: AutomationPrice PendingAssetOwners (max_values: None, max_size: None, mode: Measured)
+	fn transfer_asset_ownership() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `148`
+		//  Estimated: `3613`
+		// Minimum execution time: 15_870_000 picoseconds.
+		Weight::from_parts(16_203_000, 3613)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: AutomationPrice PendingAssetOwners (r:1 w:1)
+	/// Proof Skipped: AutomationPrice PendingAssetOwners (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice AssetOwners (r:1 w:1)
+	/// Proof Skipped: AutomationPrice AssetOwners (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn accept_asset_ownership() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `148`
+		//  Estimated: `3613`
+		// Minimum execution time: 31_207_000 picoseconds.
+		Weight::from_parts(31_846_000, 3613)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	/// Storage: AutomationPrice Shutdown (r:1 w:0)
+	/// Proof Skipped: AutomationPrice Shutdown (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: AutomationPrice Tasks (r:1 w:0)
+	/// Proof Skipped: AutomationPrice Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice TaskRecurrences (r:0 w:1)
+	/// Proof Skipped: AutomationPrice TaskRecurrences (max_values: None, max_size: None, mode: Measured)
+	fn set_task_schedule() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `662`
+		//  Estimated: `4127`
+		// Minimum execution time: 17_904_000 picoseconds.
+		Weight::from_parts(18_391_000, 4127)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: Valve ValveClosed (r:1 w:0)
+	/// Proof Skipped: Valve ValveClosed (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Valve ClosedPallets (r:1 w:0)
+	/// Proof Skipped: Valve ClosedPallets (max_values: None, max_size: None, mode: Measured)
+	fn run_dynamic_dispatch_action() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `133`
+		//  Estimated: `3598`
+		// Minimum execution time: 8_518_000 picoseconds.
+		Weight::from_parts(8_778_000, 3598)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+	}
+	fn run_dynamic_dispatch_action_fail_decode() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 892_000 picoseconds.
+		Weight::from_parts(965_000, 0)
+	}
+	/// Storage: AutomationPrice AssetRegistry (r:1 w:1)
+	/// Proof Skipped: AutomationPrice AssetRegistry (max_values: None, max_size: None, mode: Measured)
+	fn add_oracle_provider() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `76`
+		//  Estimated: `3541`
+		// Minimum execution time: 14_297_000 picoseconds.
+		Weight::from_parts(14_722_000, 3541)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: AutomationPrice AssetRegistry (r:1 w:1)
+	/// Proof Skipped: AutomationPrice AssetRegistry (max_values: None, max_size: None, mode: Measured)
+	fn remove_oracle_provider() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `108`
+		//  Estimated: `3573`
+		// Minimum execution time: 14_683_000 picoseconds.
+		Weight::from_parts(15_108_000, 3573)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: AutomationPrice AssetRegistry (r:1 w:0)
+	/// Proof Skipped: AutomationPrice AssetRegistry (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice AssetOwners (r:1 w:0)
+	/// Proof Skipped: AutomationPrice AssetOwners (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice AssetSymbols (r:0 w:1)
+	/// Proof Skipped: AutomationPrice AssetSymbols (max_values: None, max_size: None, mode: Measured)
+	fn set_asset_symbol() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `148`
+		//  Estimated: `3613`
+		// Minimum execution time: 16_871_000 picoseconds.
+		Weight::from_parts(17_356_000, 3613)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: AutomationPrice AssetRegistry (r:1 w:0)
+	/// Proof Skipped: AutomationPrice AssetRegistry (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice AssetOwners (r:1 w:0)
+	/// Proof Skipped: AutomationPrice AssetOwners (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice CircuitBreakers (r:0 w:1)
+	/// Proof Skipped: AutomationPrice CircuitBreakers (max_values: None, max_size: None, mode: Measured)
+	fn set_circuit_breaker() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `148`
+		//  Estimated: `3613`
+		// Minimum execution time: 17_208_000 picoseconds.
+		Weight::from_parts(17_742_000, 3613)
+			.saturating_add(RocksDbWeight::get().reads(2_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: AutomationPrice HaltedAssets (r:1 w:1)
+	/// Proof Skipped: AutomationPrice HaltedAssets (max_values: None, max_size: None, mode: Measured)
+	fn resume_asset() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `115`
+		//  Estimated: `3580`
+		// Minimum execution time: 13_801_000 picoseconds.
+		Weight::from_parts(14_215_000, 3580)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: AutomationPrice Tasks (r:1 w:0)
+	/// Proof Skipped: AutomationPrice Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice MissedTaskPolicies (r:0 w:1)
+	/// Proof Skipped: AutomationPrice MissedTaskPolicies (max_values: None, max_size: None, mode: Measured)
+	fn set_missed_task_policy() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `662`
+		//  Estimated: `4127`
+		// Minimum execution time: 15_208_000 picoseconds.
+		Weight::from_parts(15_632_000, 4127)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: AutomationPrice Tasks (r:1 w:0)
+	/// Proof Skipped: AutomationPrice Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice PausedTasks (r:1 w:1)
+	/// Proof Skipped: AutomationPrice PausedTasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice SortedBucketsAbove (r:1 w:1)
+	/// Proof Skipped: AutomationPrice SortedBucketsAbove (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice SortedTasksAbove (r:1 w:1)
+	/// Proof Skipped: AutomationPrice SortedTasksAbove (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice TaskQueue (r:1 w:0)
+	/// Proof Skipped: AutomationPrice TaskQueue (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: AutomationPrice MissedTasksQueue (r:1 w:0)
+	/// Proof Skipped: AutomationPrice MissedTasksQueue (max_values: Some(1), max_size: None, mode: Measured)
+	fn pause_task() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `662`
+		//  Estimated: `4127`
+		// Minimum execution time: 30_745_000 picoseconds.
+		Weight::from_parts(31_418_000, 4127)
+			.saturating_add(RocksDbWeight::get().reads(6_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
+	/// Storage: AutomationPrice Tasks (r:1 w:0)
+	/// Proof Skipped: AutomationPrice Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice PausedTasks (r:1 w:1)
+	/// Proof Skipped: AutomationPrice PausedTasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice CoolingDownTasks (r:1 w:0)
+	/// Proof Skipped: AutomationPrice CoolingDownTasks (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: AutomationPrice SortedBucketsAbove (r:1 w:1)
+	/// Proof Skipped: AutomationPrice SortedBucketsAbove (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice SortedTasksAbove (r:1 w:1)
+	/// Proof Skipped: AutomationPrice SortedTasksAbove (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice LastShiftedPrice (r:0 w:1)
+	/// Proof Skipped: AutomationPrice LastShiftedPrice (max_values: None, max_size: None, mode: Measured)
+	fn resume_task() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `662`
+		//  Estimated: `4127`
+		// Minimum execution time: 29_214_000 picoseconds.
+		Weight::from_parts(29_870_000, 4127)
+			.saturating_add(RocksDbWeight::get().reads(5_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	/// Storage: AutomationPrice Tasks (r:1 w:0)
+	/// Proof Skipped: AutomationPrice Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice TaskTimeWindows (r:0 w:1)
+	/// Proof Skipped: AutomationPrice TaskTimeWindows (max_values: None, max_size: None, mode: Measured)
+	fn set_task_time_window() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `662`
+		//  Estimated: `4127`
+		// Minimum execution time: 15_394_000 picoseconds.
+		Weight::from_parts(15_871_000, 4127)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: AutomationPrice Shutdown (r:1 w:0)
+	/// Proof Skipped: AutomationPrice Shutdown (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: AutomationPrice Tasks (r:1 w:0)
+	/// Proof Skipped: AutomationPrice Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice AssetRegistry (r:4 w:0)
+	/// Proof Skipped: AutomationPrice AssetRegistry (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice PriceRegistry (r:4 w:0)
+	/// Proof Skipped: AutomationPrice PriceRegistry (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice TaskConditions (r:2 w:1)
+	/// Proof Skipped: AutomationPrice TaskConditions (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice SortedTasksAbove (r:1 w:1)
+	/// Proof Skipped: AutomationPrice SortedTasksAbove (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice PausedTasks (r:1 w:0)
+	/// Proof Skipped: AutomationPrice PausedTasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice UnsortedTasks (r:5 w:5)
+	/// Proof Skipped: AutomationPrice UnsortedTasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice LastShiftedPrice (r:0 w:5)
+	/// Proof Skipped: AutomationPrice LastShiftedPrice (max_values: None, max_size: None, mode: Measured)
+	fn set_task_conditions() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1587`
+		//  Estimated: `14562`
+		// Minimum execution time: 47_301_000 picoseconds.
+		Weight::from_parts(48_215_000, 14562)
+			.saturating_add(RocksDbWeight::get().reads(19_u64))
+			.saturating_add(RocksDbWeight::get().writes(12_u64))
+	}
+	/// Storage: AutomationPrice AssetRegistry (r:1 w:0)
+	/// Proof Skipped: AutomationPrice AssetRegistry (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice AssetTaskCaps (r:0 w:1)
+	/// Proof Skipped: AutomationPrice AssetTaskCaps (max_values: None, max_size: None, mode: Measured)
+	fn set_asset_task_cap() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `148`
+		//  Estimated: `3613`
+		// Minimum execution time: 12_518_000 picoseconds.
+		Weight::from_parts(12_934_000, 3613)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: AutomationPrice Tasks (r:1 w:0)
+	/// Proof Skipped: AutomationPrice Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice TaskTips (r:1 w:1)
+	/// Proof Skipped: AutomationPrice TaskTips (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn set_task_tip() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `662`
+		//  Estimated: `4127`
+		// Minimum execution time: 23_794_000 picoseconds.
+		Weight::from_parts(24_318_000, 4127)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: AutomationPrice Shutdown (r:1 w:0)
+	/// Proof Skipped: AutomationPrice Shutdown (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: AutomationPrice IndexMigration (r:1 w:0)
+	/// Proof Skipped: AutomationPrice IndexMigration (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: AutomationPrice TaskQueue (r:1 w:1)
+	/// Proof Skipped: AutomationPrice TaskQueue (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: AutomationPrice Tasks (r:1 w:1)
+	/// Proof Skipped: AutomationPrice Tasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice PriceRegistry (r:1 w:0)
+	/// Proof Skipped: AutomationPrice PriceRegistry (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice TaskTips (r:1 w:0)
+	/// Proof Skipped: AutomationPrice TaskTips (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationPrice KeeperRewards (r:1 w:1)
+	/// Proof Skipped: AutomationPrice KeeperRewards (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// The range of component `v` is `[1, 64]`.
+	fn execute_ready_tasks(v: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `148 + v * (412 ±0)`
+		//  Estimated: `3613 + v * (6196 ±0)`
+		// Minimum execution time: 21_032_000 picoseconds.
+		Weight::from_parts(21_574_000, 3613)
+			// Standard Error: 9_814
+			.saturating_add(Weight::from_parts(61_238_417, 0).saturating_mul(v.into()))
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().reads((7_u64).saturating_mul(v.into())))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+			.saturating_add(RocksDbWeight::get().writes((5_u64).saturating_mul(v.into())))
+			.saturating_add(Weight::from_parts(0, 6196).saturating_mul(v.into()))
+	}
 }
@@ -500,6 +500,82 @@ benchmarks! {
 		schedule_notify_tasks::<T>(caller, vec![new_time_slot], T::MaxTasksPerSlot::get());
 	}: { AutomationTime::<T>::shift_missed_tasks(last_time_slot, diff) }
 
+	create_pool {
+		let caller: T::AccountId = account("caller", 0, SEED);
+	}: _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert!(AutomationTime::<T>::get_pool(0).is_some());
+	}
+
+	contribute_to_pool {
+		let caller: T::AccountId = account("caller", 0, SEED);
+		let amount = T::Currency::minimum_balance().saturating_mul(ED_MULTIPLIER.into());
+		T::Currency::deposit_creating(&caller, amount.saturating_mul(2u32.into()));
+		AutomationTime::<T>::create_pool(RawOrigin::Signed(caller.clone()).into())?;
+		AutomationTime::<T>::contribute_to_pool(RawOrigin::Signed(caller.clone()).into(), 0, amount)?;
+	}: _(RawOrigin::Signed(caller.clone()), 0, amount)
+	verify {
+		assert_eq!(AutomationTime::<T>::get_pool_shares(0, &caller), amount.saturating_mul(2u32.into()));
+	}
+
+	withdraw_from_pool {
+		let caller: T::AccountId = account("caller", 0, SEED);
+		let amount = T::Currency::minimum_balance().saturating_mul(ED_MULTIPLIER.into());
+		T::Currency::deposit_creating(&caller, amount.saturating_mul(2u32.into()));
+		AutomationTime::<T>::create_pool(RawOrigin::Signed(caller.clone()).into())?;
+		AutomationTime::<T>::contribute_to_pool(RawOrigin::Signed(caller.clone()).into(), 0, amount)?;
+	}: _(RawOrigin::Signed(caller.clone()), 0, amount)
+	verify {
+		assert_eq!(AutomationTime::<T>::get_pool_shares(0, &caller), 0u32.into());
+	}
+
+	set_pool_admin {
+		let caller: T::AccountId = account("caller", 0, SEED);
+		let admin: T::AccountId = account("admin", 0, SEED);
+		AutomationTime::<T>::create_pool(RawOrigin::Signed(caller.clone()).into())?;
+	}: _(RawOrigin::Signed(caller), 0, admin.clone())
+	verify {
+		assert_eq!(AutomationTime::<T>::get_pool(0).unwrap().admin, admin);
+	}
+
+	reclaim_deposit {
+		let caller: T::AccountId = account("caller", 0, SEED);
+		let owner: T::AccountId = account("owner", 0, SEED);
+		let task_id: TaskIdV2 = vec![49, 45, 48, 45, 52];
+		let deposit = T::Currency::minimum_balance().saturating_mul(ED_MULTIPLIER.into());
+		T::Currency::deposit_creating(&owner, deposit.saturating_mul(2u32.into()));
+		T::Currency::reserve(&owner, deposit)?;
+		TaskDeposits::<T>::insert(&owner, &task_id, deposit);
+	}: _(RawOrigin::Signed(caller), owner.clone(), task_id.clone())
+	verify {
+		assert_last_event::<T>(Event::DepositReclaimed { who: owner, task_id, amount: deposit }.into());
+	}
+
+	set_task_callback {
+		let caller: T::AccountId = account("caller", 0, SEED);
+		let task_id = schedule_notify_tasks::<T>(caller.clone(), vec![10800], 1);
+		let destination: VersionedMultiLocation = MultiLocation::new(1, X1(Parachain(2001))).into();
+	}: _(RawOrigin::Signed(caller.clone()), task_id.clone(), Some((Box::new(destination), 1)))
+	verify {
+		assert!(AutomationTime::<T>::get_task_callback(caller, task_id).is_some());
+	}
+
+	claim_refund_for_unexecuted_full {
+		let caller: T::AccountId = account("caller", 0, SEED);
+		let mut times: Vec<u64> = vec![];
+
+		for i in 0..T::MaxExecutionTimes::get() {
+			let hour: u64 = (3600 * (i + 1)).try_into().unwrap();
+			times.push(hour);
+		}
+
+		let task_id = schedule_notify_tasks::<T>(caller.clone(), times, T::MaxTasksPerSlot::get());
+		Shutdown::<T>::put(true);
+	}: _(RawOrigin::Signed(caller.clone()), task_id.clone())
+	verify {
+		assert!(AutomationTime::<T>::get_account_task(caller, task_id).is_none());
+	}
+
 	impl_benchmark_test_suite!(
 		AutomationTime,
 		crate::mock::new_test_ext(crate::tests::START_BLOCK_TIME),
@@ -38,6 +38,14 @@ pub trait HandleFees<T: Config> {
 		executions: u32,
 		prereq: F,
 	) -> Result<R, DispatchError>;
+
+	/// Return the execution fee paid for `executions` runs of an action that will no longer run.
+	/// Returns the amount refunded.
+	fn refund_execution_fee_for(
+		owner: &AccountOf<T>,
+		action: &ActionOf<T>,
+		executions: u32,
+	) -> Result<MultiBalanceOf<T>, DispatchError>;
 }
 
 #[derive(Clone)]
@@ -71,6 +79,30 @@ where
 		fee_handler.pay_fees()?;
 		Ok(outcome)
 	}
+
+	fn refund_execution_fee_for(
+		owner: &AccountOf<T>,
+		action: &ActionOf<T>,
+		executions: u32,
+	) -> Result<MultiBalanceOf<T>, DispatchError> {
+		// Only the fees paid through the sovereign account were taken when the task was scheduled
+		let execution_fee = match Self::new(owner, action, executions)?.execution_fee {
+			Some(execution_fee) if execution_fee.is_local => execution_fee,
+			_ => return Ok(Zero::zero()),
+		};
+		if execution_fee.amount.is_zero() {
+			return Ok(execution_fee.amount)
+		}
+
+		let currency_id = T::CurrencyIdConvert::convert(execution_fee.asset_location)
+			.ok_or("InconvertibleMultilocation")?;
+		T::XcmpTransactor::refund_xcm_fee(
+			currency_id,
+			owner.clone(),
+			execution_fee.amount.saturated_into(),
+		)?;
+		Ok(execution_fee.amount)
+	}
 }
 
 impl<T, TR> FeeHandler<T, TR>
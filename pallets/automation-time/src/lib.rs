@@ -33,6 +33,7 @@ mod mock;
 mod tests;
 
 mod benchmarking;
+pub mod migrations;
 pub mod weights;
 
 mod fees;
@@ -44,7 +45,7 @@ pub use autocompounding::*;
 mod types;
 pub use types::*;
 
-use codec::Decode;
+use codec::{Decode, Encode};
 use core::convert::TryInto;
 use frame_support::{
 	dispatch::{GetDispatchInfo, PostDispatchInfo},
@@ -54,8 +55,11 @@ use frame_support::{
 		with_transaction,
 		TransactionOutcome::{Commit, Rollback},
 	},
-	traits::{Contains, Currency, IsSubType, OriginTrait},
+	traits::{
+		Contains, Currency, ExistenceRequirement, IsSubType, OriginTrait, ReservableCurrency,
+	},
 	weights::constants::WEIGHT_REF_TIME_PER_SECOND,
+	PalletId,
 };
 use frame_system::pallet_prelude::*;
 use orml_traits::{location::Reserve, FixedConversionRateProvider, MultiCurrency};
@@ -63,11 +67,18 @@ use pallet_parachain_staking::DelegatorActions;
 use pallet_timestamp::{self as timestamp};
 pub use pallet_xcmp_handler::InstructionSequence;
 use pallet_xcmp_handler::XcmpTransactor;
-use primitives::EnsureProxy;
+use primitives::{
+	deposit::{hold_deposit, release_deposit},
+	EnsureProxy, OnTaskExecuted, StorageDeposit,
+};
 use scale_info::{prelude::format, TypeInfo};
 use sp_runtime::{
-	traits::{CheckedConversion, Convert, Dispatchable, SaturatedConversion, Saturating},
-	ArithmeticError, DispatchError, MultiAddress, Perbill,
+	helpers_128bit::multiply_by_rational_with_rounding,
+	traits::{
+		AccountIdConversion, CheckedAdd, CheckedConversion, Convert, Dispatchable,
+		SaturatedConversion, Saturating, Zero,
+	},
+	ArithmeticError, DispatchError, MultiAddress, Perbill, Rounding,
 };
 use sp_std::{boxed::Box, collections::btree_map::BTreeMap, vec, vec::Vec};
 pub use weights::WeightInfo;
@@ -91,6 +102,7 @@ pub mod pallet {
 	pub type AccountTaskId<T> = (AccountOf<T>, TaskIdV2);
 	pub type ActionOf<T> = Action<AccountOf<T>, BalanceOf<T>>;
 	pub type TaskOf<T> = Task<AccountOf<T>, BalanceOf<T>>;
+	pub type AutomationPoolOf<T> = AutomationPool<AccountOf<T>, BalanceOf<T>>;
 	pub type MissedTaskV2Of<T> = MissedTaskV2<AccountOf<T>, TaskIdV2>;
 	pub type ScheduledTasksOf<T> = ScheduledTasks<AccountOf<T>, TaskIdV2>;
 	pub type MultiCurrencyId<T> = <<T as Config>::MultiCurrency as MultiCurrency<
@@ -108,6 +120,10 @@ pub mod pallet {
 		#[pallet::constant]
 		type MaxTasksPerSlot: Get<u32>;
 
+		/// The maximum number of tasks a single account can schedule in one block.
+		#[pallet::constant]
+		type MaxTasksPerAccountPerBlock: Get<u32>;
+
 		/// The maximum number of times that a task can be scheduled for.
 		#[pallet::constant]
 		type MaxExecutionTimes: Get<u32>;
@@ -116,9 +132,9 @@ pub mod pallet {
 		#[pallet::constant]
 		type MaxScheduleSeconds: Get<u64>;
 
-		/// The maximum weight per block.
+		/// The maximum weight per block, in compute time and proof size.
 		#[pallet::constant]
-		type MaxBlockWeight: Get<u64>;
+		type MaxBlockWeight: Get<Weight>;
 
 		/// The maximum percentage of weight per block used for scheduled tasks.
 		#[pallet::constant]
@@ -136,11 +152,15 @@ pub mod pallet {
 		#[pallet::constant]
 		type UpdateQueueRatio: Get<Perbill>;
 
+		/// The maximum number of tasks held by a single page of the task queue.
+		#[pallet::constant]
+		type TaskQueuePageSize: Get<u32>;
+
 		#[pallet::constant]
 		type ExecutionWeightFee: Get<BalanceOf<Self>>;
 
 		/// The Currency type for interacting with balances
-		type Currency: Currency<Self::AccountId>;
+		type Currency: ReservableCurrency<Self::AccountId>;
 
 		/// The MultiCurrency type for interacting with balances
 		type MultiCurrency: MultiCurrency<Self::AccountId>;
@@ -202,9 +222,32 @@ pub mod pallet {
 		/// Self chain location.
 		#[pallet::constant]
 		type SelfLocation: Get<MultiLocation>;
+
+		/// The id used to derive the sovereign account of each automation pool.
+		#[pallet::constant]
+		type PoolPalletId: Get<PalletId>;
+
+		/// Notified of the outcome of every task execution.
+		type OnTaskExecuted: OnTaskExecuted;
+
+		/// The deposit reserved from the owner of a task for as long as it stays in storage.
+		type StorageDeposit: StorageDeposit<BalanceOf<Self>>;
+
+		/// Origins of other consensus systems, such as sibling parachains, that may schedule and
+		/// cancel tasks over XCM.
+		type RemoteOrigin: EnsureOrigin<
+			<Self as frame_system::Config>::RuntimeOrigin,
+			Success = MultiLocation,
+		>;
+
+		/// Derives the local account that owns the tasks of a remote origin.
+		type RemoteAccountOf: Convert<MultiLocation, Option<Self::AccountId>>;
+
+		/// Sends the outcome of a task to the callback location set by its owner.
+		type XcmSender: SendXcm;
 	}
 
-	const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(4);
 
 	#[pallet::pallet]
 	#[pallet::without_storage_info]
@@ -221,9 +264,17 @@ pub mod pallet {
 	pub type AccountTasks<T: Config> =
 		StorageDoubleMap<_, Twox64Concat, AccountOf<T>, Twox64Concat, TaskIdV2, TaskOf<T>>;
 
+	/// The tasks due in the current slot, split into pages of at most `TaskQueuePageSize` tasks.
+	/// Pages are pushed at the tail and drained from the head.
 	#[pallet::storage]
-	#[pallet::getter(fn get_task_queue)]
-	pub type TaskQueueV2<T: Config> = StorageValue<_, Vec<AccountTaskId<T>>, ValueQuery>;
+	#[pallet::getter(fn get_task_queue_page)]
+	pub type TaskQueuePages<T: Config> =
+		StorageMap<_, Twox64Concat, u32, BoundedVec<AccountTaskId<T>, T::TaskQueuePageSize>>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_task_queue_bounds)]
+	// NOTE: The 2 indices represent (head, tail). The queue holds the pages in `head..tail`.
+	pub type TaskQueueBounds<T: Config> = StorageValue<_, (u32, u32), ValueQuery>;
 
 	#[pallet::storage]
 	#[pallet::getter(fn get_missed_queue)]
@@ -240,6 +291,60 @@ pub mod pallet {
 	#[pallet::getter(fn is_shutdown)]
 	pub type Shutdown<T: Config> = StorageValue<_, bool, ValueQuery>;
 
+	#[pallet::storage]
+	#[pallet::getter(fn get_next_pool_id)]
+	pub type NextPoolId<T: Config> = StorageValue<_, PoolId, ValueQuery>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_pool)]
+	pub type Pools<T: Config> = StorageMap<_, Twox64Concat, PoolId, AutomationPoolOf<T>>;
+
+	#[pallet::storage]
+	#[pallet::getter(fn get_pool_shares)]
+	pub type PoolShares<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		PoolId,
+		Twox64Concat,
+		AccountOf<T>,
+		BalanceOf<T>,
+		ValueQuery,
+	>;
+
+	/// Deposits held for tasks, keyed by the task's owner and id. They outlive the task and are
+	/// released with `reclaim_deposit`.
+	#[pallet::storage]
+	#[pallet::getter(fn get_task_deposit)]
+	pub type TaskDeposits<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, AccountOf<T>, Twox64Concat, TaskIdV2, BalanceOf<T>>;
+
+	/// Set while `AccountTasks` is being checked against the task bounds across several blocks.
+	/// Tasks are not triggered until the check completes.
+	#[pallet::storage]
+	#[pallet::getter(fn get_task_migration)]
+	pub type TaskMigration<T: Config> = StorageValue<_, TaskMigrationProgress>;
+
+	/// The number of tasks each account has scheduled in the current block. Cleared at the start
+	/// of every block.
+	#[pallet::storage]
+	#[pallet::getter(fn get_tasks_scheduled_this_block)]
+	pub type TasksScheduledThisBlock<T: Config> =
+		StorageMap<_, Twox64Concat, AccountOf<T>, u32, ValueQuery>;
+
+	/// The ids of the tasks run in the current block, whether they succeeded or not, so a proof
+	/// against the state of a block can show that a task ran in it. Cleared at the start of every
+	/// block.
+	#[pallet::storage]
+	#[pallet::getter(fn get_executed_tasks)]
+	pub type ExecutedTasks<T: Config> = StorageValue<_, Vec<TaskIdV2>, ValueQuery>;
+
+	/// Where the outcome of a task is reported after each of its executions, keyed by the task's
+	/// owner and id.
+	#[pallet::storage]
+	#[pallet::getter(fn get_task_callback)]
+	pub type TaskCallbacks<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, AccountOf<T>, Twox64Concat, TaskIdV2, TaskCallback>;
+
 	#[pallet::error]
 	#[derive(PartialEq)]
 	pub enum Error<T> {
@@ -278,6 +383,24 @@ pub mod pallet {
 		CannotReanchor,
 		/// Invalid asset location.
 		InvalidAssetLocation,
+		/// The automation pool does not exist.
+		PoolDoesNotExist,
+		/// Only the pool admin can manage the pool's tasks.
+		NotPoolAdmin,
+		/// The amount must be non-zero and worth at least one share.
+		InvalidPoolAmount,
+		/// The account does not own enough shares of the pool.
+		InsufficientPoolShares,
+		/// No deposit is held for the task.
+		DepositNotFound,
+		/// The deposit cannot be reclaimed while the task is still scheduled.
+		TaskStillScheduled,
+		/// The account has already scheduled `MaxTasksPerAccountPerBlock` tasks in this block.
+		AccountBlockLimitReached,
+		/// No local account can be derived from the location of the remote origin.
+		UnknownRemoteAccount,
+		/// Refunds for unexecuted tasks can only be claimed while the system is shut down.
+		NotShutdown,
 	}
 
 	#[pallet::event]
@@ -342,21 +465,90 @@ pub mod pallet {
 			task_id: TaskIdV2,
 			error: DispatchError,
 		},
+		/// An automation pool was created.
+		PoolCreated {
+			pool_id: PoolId,
+			admin: AccountOf<T>,
+			pool_account: AccountOf<T>,
+		},
+		/// Funds were added to an automation pool in exchange for shares.
+		PoolContributed {
+			pool_id: PoolId,
+			who: AccountOf<T>,
+			amount: BalanceOf<T>,
+			shares: BalanceOf<T>,
+		},
+		/// Shares were redeemed for their portion of an automation pool.
+		PoolWithdrawn {
+			pool_id: PoolId,
+			who: AccountOf<T>,
+			amount: BalanceOf<T>,
+			shares: BalanceOf<T>,
+		},
+		/// The admin of an automation pool was changed.
+		PoolAdminChanged {
+			pool_id: PoolId,
+			admin: AccountOf<T>,
+		},
+		/// The deposit of a removed task was returned to its owner.
+		DepositReclaimed {
+			who: AccountOf<T>,
+			task_id: TaskIdV2,
+			amount: BalanceOf<T>,
+		},
+		/// A block's worth of the task bounds migration was processed.
+		TaskMigrationProgressed {
+			checked: u32,
+			removed: u32,
+		},
+		/// The task bounds migration finished and task triggering resumed.
+		TaskMigrationCompleted {
+			checked: u32,
+			removed: u32,
+		},
+		/// The owner of a task set or cleared where its outcome is reported.
+		TaskCallbackUpdated {
+			who: AccountOf<T>,
+			task_id: TaskIdV2,
+			callback: Option<TaskCallback>,
+		},
+		/// The outcome of a task was sent to its callback location.
+		TaskCallbackSent {
+			who: AccountOf<T>,
+			task_id: TaskIdV2,
+			destination: MultiLocation,
+		},
+		/// The outcome of a task could not be sent to its callback location.
+		TaskCallbackFailed {
+			who: AccountOf<T>,
+			task_id: TaskIdV2,
+			destination: MultiLocation,
+		},
+		/// The execution fee of a task that will not run was returned to its owner.
+		ExecutionFeeRefunded {
+			who: AccountOf<T>,
+			task_id: TaskIdV2,
+			amount: MultiBalanceOf<T>,
+		},
 	}
 
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
 		fn on_initialize(_block: T::BlockNumber) -> Weight {
+			let reset_weight = Self::reset_block_records();
+
 			if Self::is_shutdown() {
-				return T::DbWeight::get().reads(1u64)
+				return T::DbWeight::get().reads(1u64).saturating_add(reset_weight)
 			}
 
-			let max_weight: Weight = Weight::from_parts(
-				T::MaxWeightPercentage::get().mul_floor(T::MaxBlockWeight::get()),
-				0,
-			);
+			let max_weight: Weight = T::MaxWeightPercentage::get() * T::MaxBlockWeight::get();
 
-			Self::trigger_tasks(max_weight)
+			if let Some(progress) = Self::get_task_migration() {
+				return Self::migrate_account_tasks(progress, max_weight)
+					.saturating_add(reset_weight)
+			}
+
+			Self::trigger_tasks(max_weight).saturating_add(reset_weight)
 		}
 	}
 
@@ -365,7 +557,7 @@ pub mod pallet {
 		/// Schedule a task through XCMP to fire an XCMP message with a provided call.
 		///
 		/// Before the task can be scheduled the task must past validation checks.
-		/// * The transaction is signed
+		/// * The transaction is signed, or sent over XCM by a remote origin
 		/// * The times are valid
 		/// * The given asset location is supported
 		///
@@ -373,6 +565,8 @@ pub mod pallet {
 		/// * `schedule`: The triggering rules for recurring task or the list of unix standard times in seconds for when the task should run.
 		/// * `destination`: Destination the XCMP call will be sent to.
 		/// * `schedule_fee`: The payment asset location required for scheduling automation task.
+		///   Tasks scheduled over XCM pay in the calling chain's token instead whenever this chain
+		///   can price fees in it.
 		/// * `execution_fee`: The fee will be paid for XCMP execution.
 		/// * `encoded_call`: Call that will be sent via XCMP to the parachain id provided.
 		/// * `encoded_call_weight`: Required weight at most the provided call will take.
@@ -386,8 +580,13 @@ pub mod pallet {
 		/// * `DuplicateTask`: There can be no duplicate tasks.
 		/// * `TimeTooFarOut`: Execution time or frequency are past the max time horizon.
 		/// * `TimeSlotFull`: Time slot is full. No more tasks can be scheduled for this time.
+		/// * `AccountBlockLimitReached`: The caller has scheduled too many tasks in this block.
 		/// * `UnsupportedFeePayment`: Unsupported fee payment.
 		/// * `InvalidAssetLocation` Invalid asset location.
+		/// * `UnknownRemoteAccount`: No account can be derived for the remote origin.
+		/// * `CallWeightImplausible`: `encoded_call_weight` is out of the destination's bounds.
+		/// * `OverallWeightImplausible`: `overall_weight` cannot cover the call and the XCM
+		///   instructions on the destination.
 		#[pallet::call_index(1)]
 		#[pallet::weight(
 			<T as Config>::WeightInfo::schedule_xcmp_task_full(schedule.number_of_executions())
@@ -405,7 +604,7 @@ pub mod pallet {
 			instruction_sequence: InstructionSequence,
 			schedule_as: Option<T::AccountId>,
 		) -> DispatchResult {
-			let who = ensure_signed(origin)?;
+			let (who, remote_location) = Self::ensure_task_owner(origin)?;
 
 			// Make sure the owner is the proxy account of the user account.
 			if let Some(schedule_as_account) = schedule_as.clone() {
@@ -416,6 +615,11 @@ pub mod pallet {
 				.map_err(|()| Error::<T>::BadVersion)?;
 			let schedule_fee_location = MultiLocation::try_from(*schedule_fee.clone())
 				.map_err(|()| Error::<T>::BadVersion)?;
+			// Remote users pay with their own chain's token, so they need not hold ours.
+			let schedule_fee_location = remote_location
+				.as_ref()
+				.and_then(Self::remote_fee_location)
+				.unwrap_or(schedule_fee_location);
 
 			let execution_fee_payment: AssetPayment = *execution_fee.clone();
 			let execution_fee_location =
@@ -426,6 +630,13 @@ pub mod pallet {
 				&execution_fee_location,
 				&destination_location,
 			)?;
+			T::XcmpTransactor::check_transact_weights(
+				destination_location,
+				encoded_call.len(),
+				encoded_call_weight,
+				overall_weight,
+				instruction_sequence,
+			)?;
 
 			let action = Action::XCMP {
 				destination: destination_location,
@@ -478,6 +689,7 @@ pub mod pallet {
 		/// * `PastTime`: Time must be in the future.
 		/// * `DuplicateTask`: There can be no duplicate tasks.
 		/// * `TimeSlotFull`: Time slot is full. No more tasks can be scheduled for this time.
+		/// * `AccountBlockLimitReached`: The caller has scheduled too many tasks in this block.
 		/// * `TimeTooFarOut`: Execution time or frequency are past the max time horizon.
 		/// * `InsufficientBalance`: Not enough funds to pay execution fee.
 		#[pallet::call_index(2)]
@@ -521,7 +733,9 @@ pub mod pallet {
 		/// * `PastTime`: Time must be in the future.
 		/// * `DuplicateTask`: There can be no duplicate tasks.
 		/// * `TimeSlotFull`: Time slot is full. No more tasks can be scheduled for this time.
+		/// * `AccountBlockLimitReached`: The caller has scheduled too many tasks in this block.
 		/// * `TimeTooFarOut`: Execution time or frequency are past the max time horizon.
+		/// * `UnknownRemoteAccount`: No account can be derived for the remote origin.
 		#[pallet::call_index(3)]
 		#[pallet::weight(<T as Config>::WeightInfo::schedule_dynamic_dispatch_task_full(schedule.number_of_executions()))]
 		pub fn schedule_dynamic_dispatch_task(
@@ -529,7 +743,7 @@ pub mod pallet {
 			schedule: ScheduleParam,
 			call: Box<<T as Config>::RuntimeCall>,
 		) -> DispatchResult {
-			let who = ensure_signed(origin)?;
+			let (who, _) = Self::ensure_task_owner(origin)?;
 
 			let encoded_call = call.encode();
 			let action = Action::DynamicDispatch { encoded_call: encoded_call.clone() };
@@ -542,17 +756,19 @@ pub mod pallet {
 
 		/// Cancel a task.
 		///
-		/// Tasks can only can be cancelled by their owners.
+		/// Tasks can only can be cancelled by their owners. A task scheduled over XCM is cancelled
+		/// by the same remote origin.
 		///
 		/// # Parameters
 		/// * `task_id`: The id of the task.
 		///
 		/// # Errors
 		/// * `TaskDoesNotExist`: The task does not exist.
+		/// * `UnknownRemoteAccount`: No account can be derived for the remote origin.
 		#[pallet::call_index(4)]
 		#[pallet::weight(<T as Config>::WeightInfo::cancel_scheduled_task_full())]
 		pub fn cancel_task(origin: OriginFor<T>, task_id: TaskIdV2) -> DispatchResult {
-			let who = ensure_signed(origin)?;
+			let (who, _) = Self::ensure_task_owner(origin)?;
 
 			AccountTasks::<T>::get(who, task_id.clone())
 				.ok_or(Error::<T>::TaskDoesNotExist)
@@ -613,6 +829,347 @@ pub mod pallet {
 			Self::remove_task(task_id, task);
 			Ok(())
 		}
+
+		/// Create an automation pool administered by the caller.
+		///
+		/// The pool's pot lives in an account derived from `PoolPalletId` and the pool id.
+		/// Tasks scheduled for the pool are owned by, and pay their fees from, that account.
+		#[pallet::call_index(7)]
+		#[pallet::weight(<T as Config>::WeightInfo::create_pool())]
+		pub fn create_pool(origin: OriginFor<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let pool_id = Self::get_next_pool_id();
+			let next_pool_id = pool_id.checked_add(1).ok_or(ArithmeticError::Overflow)?;
+
+			Pools::<T>::insert(
+				pool_id,
+				AutomationPoolOf::<T> { admin: who.clone(), total_shares: Zero::zero() },
+			);
+			NextPoolId::<T>::put(next_pool_id);
+
+			Self::deposit_event(Event::<T>::PoolCreated {
+				pool_id,
+				admin: who,
+				pool_account: Self::pool_account_id(pool_id),
+			});
+
+			Ok(())
+		}
+
+		/// Contribute funds to an automation pool.
+		///
+		/// The contributor is minted shares in proportion to the value already held by the pool.
+		///
+		/// # Parameters
+		/// * `pool_id`: The id of the pool.
+		/// * `amount`: The amount of native token to contribute.
+		///
+		/// # Errors
+		/// * `PoolDoesNotExist`: The pool does not exist.
+		/// * `InvalidPoolAmount`: The amount is not worth at least one share.
+		#[pallet::call_index(8)]
+		#[pallet::weight(<T as Config>::WeightInfo::contribute_to_pool())]
+		pub fn contribute_to_pool(
+			origin: OriginFor<T>,
+			pool_id: PoolId,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!amount.is_zero(), Error::<T>::InvalidPoolAmount);
+
+			let mut pool = Self::get_pool(pool_id).ok_or(Error::<T>::PoolDoesNotExist)?;
+			let pool_account = Self::pool_account_id(pool_id);
+			let pool_balance = T::Currency::free_balance(&pool_account);
+
+			let shares = if pool.total_shares.is_zero() || pool_balance.is_zero() {
+				amount
+			} else {
+				Self::pro_rata(amount, pool.total_shares, pool_balance)?
+			};
+			ensure!(!shares.is_zero(), Error::<T>::InvalidPoolAmount);
+
+			T::Currency::transfer(&who, &pool_account, amount, ExistenceRequirement::KeepAlive)?;
+
+			pool.total_shares =
+				pool.total_shares.checked_add(&shares).ok_or(ArithmeticError::Overflow)?;
+			PoolShares::<T>::mutate(pool_id, &who, |owned| *owned = owned.saturating_add(shares));
+			Pools::<T>::insert(pool_id, pool);
+
+			Self::deposit_event(Event::<T>::PoolContributed { pool_id, who, amount, shares });
+
+			Ok(())
+		}
+
+		/// Redeem shares of an automation pool for their portion of the pool's current balance.
+		///
+		/// # Parameters
+		/// * `pool_id`: The id of the pool.
+		/// * `shares`: The number of shares to redeem.
+		///
+		/// # Errors
+		/// * `PoolDoesNotExist`: The pool does not exist.
+		/// * `InvalidPoolAmount`: The number of shares is zero.
+		/// * `InsufficientPoolShares`: The caller does not own that many shares.
+		#[pallet::call_index(9)]
+		#[pallet::weight(<T as Config>::WeightInfo::withdraw_from_pool())]
+		pub fn withdraw_from_pool(
+			origin: OriginFor<T>,
+			pool_id: PoolId,
+			shares: BalanceOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!shares.is_zero(), Error::<T>::InvalidPoolAmount);
+
+			let mut pool = Self::get_pool(pool_id).ok_or(Error::<T>::PoolDoesNotExist)?;
+			let owned = Self::get_pool_shares(pool_id, &who);
+			ensure!(shares <= owned, Error::<T>::InsufficientPoolShares);
+
+			let pool_account = Self::pool_account_id(pool_id);
+			let amount = Self::pro_rata(
+				shares,
+				T::Currency::free_balance(&pool_account),
+				pool.total_shares,
+			)?;
+
+			T::Currency::transfer(&pool_account, &who, amount, ExistenceRequirement::AllowDeath)?;
+
+			pool.total_shares = pool.total_shares.saturating_sub(shares);
+			let remaining = owned.saturating_sub(shares);
+			if remaining.is_zero() {
+				PoolShares::<T>::remove(pool_id, &who);
+			} else {
+				PoolShares::<T>::insert(pool_id, &who, remaining);
+			}
+			Pools::<T>::insert(pool_id, pool);
+
+			Self::deposit_event(Event::<T>::PoolWithdrawn { pool_id, who, amount, shares });
+
+			Ok(())
+		}
+
+		/// Hand the administration of an automation pool to another account.
+		///
+		/// # Parameters
+		/// * `pool_id`: The id of the pool.
+		/// * `admin`: The new pool admin.
+		///
+		/// # Errors
+		/// * `PoolDoesNotExist`: The pool does not exist.
+		/// * `NotPoolAdmin`: The caller is not the pool admin.
+		#[pallet::call_index(10)]
+		#[pallet::weight(<T as Config>::WeightInfo::set_pool_admin())]
+		pub fn set_pool_admin(
+			origin: OriginFor<T>,
+			pool_id: PoolId,
+			admin: AccountOf<T>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let mut pool = Self::ensure_pool_admin(pool_id, &who)?;
+			pool.admin = admin.clone();
+			Pools::<T>::insert(pool_id, pool);
+
+			Self::deposit_event(Event::<T>::PoolAdminChanged { pool_id, admin });
+
+			Ok(())
+		}
+
+		/// Schedule a dynamic dispatch task owned and paid for by an automation pool.
+		/// The call is dispatched from the pool account.
+		///
+		/// # Parameters
+		/// * `pool_id`: The id of the pool.
+		/// * `schedule`: The triggering rules for the task.
+		/// * `call`: The call that will be dispatched.
+		///
+		/// # Errors
+		/// * `PoolDoesNotExist`: The pool does not exist.
+		/// * `NotPoolAdmin`: The caller is not the pool admin.
+		/// * `InvalidTime`: Execution time and frequency must be a multiple of SlotSizeSeconds.
+		/// * `PastTime`: Time must be in the future.
+		/// * `TimeSlotFull`: Time slot is full. No more tasks can be scheduled for this time.
+		/// * `AccountBlockLimitReached`: The pool has scheduled too many tasks in this block.
+		/// * `TimeTooFarOut`: Execution time or frequency are past the max time horizon.
+		#[pallet::call_index(11)]
+		#[pallet::weight(
+			<T as Config>::WeightInfo::schedule_dynamic_dispatch_task_full(schedule.number_of_executions())
+				.saturating_add(T::DbWeight::get().reads(1))
+		)]
+		pub fn schedule_pool_task(
+			origin: OriginFor<T>,
+			pool_id: PoolId,
+			schedule: ScheduleParam,
+			call: Box<<T as Config>::RuntimeCall>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_pool_admin(pool_id, &who)?;
+
+			let encoded_call = call.encode();
+			let action = Action::DynamicDispatch { encoded_call: encoded_call.clone() };
+			let schedule = schedule.validated_into::<T>()?;
+
+			Self::schedule_task_with_event(
+				action,
+				Self::pool_account_id(pool_id),
+				schedule,
+				vec![],
+				Some(encoded_call),
+			)?;
+
+			Ok(())
+		}
+
+		/// Cancel a task owned by an automation pool.
+		///
+		/// # Parameters
+		/// * `pool_id`: The id of the pool.
+		/// * `task_id`: The id of the task.
+		///
+		/// # Errors
+		/// * `PoolDoesNotExist`: The pool does not exist.
+		/// * `NotPoolAdmin`: The caller is not the pool admin.
+		/// * `TaskDoesNotExist`: The task does not exist.
+		#[pallet::call_index(12)]
+		#[pallet::weight(
+			<T as Config>::WeightInfo::cancel_scheduled_task_full()
+				.saturating_add(T::DbWeight::get().reads(1))
+		)]
+		pub fn cancel_pool_task(
+			origin: OriginFor<T>,
+			pool_id: PoolId,
+			task_id: TaskIdV2,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			Self::ensure_pool_admin(pool_id, &who)?;
+
+			AccountTasks::<T>::get(Self::pool_account_id(pool_id), task_id.clone())
+				.ok_or(Error::<T>::TaskDoesNotExist)
+				.map(|task| Self::remove_task(task_id.clone(), task))?;
+
+			Ok(())
+		}
+
+		/// Return the deposit of a task that is no longer scheduled to its owner.
+		///
+		/// Tasks are removed when they complete, are cancelled or fail for good, and their
+		/// deposit stays reserved until this is called. Anyone can call it.
+		///
+		/// # Parameters
+		/// * `owner_id`: The owner of the task.
+		/// * `task_id`: The id of the task.
+		///
+		/// # Errors
+		/// * `DepositNotFound`: No deposit is held for the task.
+		/// * `TaskStillScheduled`: The task is still scheduled.
+		#[pallet::call_index(13)]
+		#[pallet::weight(<T as Config>::WeightInfo::reclaim_deposit())]
+		pub fn reclaim_deposit(
+			origin: OriginFor<T>,
+			owner_id: AccountOf<T>,
+			task_id: TaskIdV2,
+		) -> DispatchResult {
+			ensure_signed(origin)?;
+
+			let deposit =
+				Self::get_task_deposit(&owner_id, &task_id).ok_or(Error::<T>::DepositNotFound)?;
+			if AccountTasks::<T>::contains_key(&owner_id, &task_id) {
+				Err(Error::<T>::TaskStillScheduled)?
+			}
+
+			TaskDeposits::<T>::remove(&owner_id, &task_id);
+			let amount = release_deposit::<_, T::Currency>(&owner_id, deposit);
+			Self::deposit_event(Event::<T>::DepositReclaimed { who: owner_id, task_id, amount });
+
+			Ok(())
+		}
+
+		/// Set where the outcome of a task is reported, or stop reporting it.
+		///
+		/// After each execution of the task, a `QueryResponse` carrying `query_id` and the
+		/// result of the execution is sent to `destination`, followed by a `SetTopic` with the
+		/// blake2 hash of the task id.
+		///
+		/// # Parameters
+		/// * `task_id`: The id of the task.
+		/// * `callback`: The location to report to and the query id to report with, or None to
+		///   stop reporting.
+		///
+		/// # Errors
+		/// * `TaskDoesNotExist`: The caller has no task with this id.
+		/// * `BadVersion`: The destination cannot be converted to the latest XCM version.
+		#[pallet::call_index(14)]
+		#[pallet::weight(<T as Config>::WeightInfo::set_task_callback())]
+		pub fn set_task_callback(
+			origin: OriginFor<T>,
+			task_id: TaskIdV2,
+			callback: Option<(Box<VersionedMultiLocation>, QueryId)>,
+		) -> DispatchResult {
+			let (who, _) = Self::ensure_task_owner(origin)?;
+			if !AccountTasks::<T>::contains_key(&who, &task_id) {
+				Err(Error::<T>::TaskDoesNotExist)?
+			}
+
+			let callback = match callback {
+				Some((destination, query_id)) => {
+					let destination = MultiLocation::try_from(*destination)
+						.map_err(|()| Error::<T>::BadVersion)?;
+					TaskCallbacks::<T>::insert(
+						&who,
+						&task_id,
+						TaskCallback { destination, query_id },
+					);
+					Some(TaskCallback { destination, query_id })
+				},
+				None => {
+					TaskCallbacks::<T>::remove(&who, &task_id);
+					None
+				},
+			};
+			Self::deposit_event(Event::<T>::TaskCallbackUpdated { who, task_id, callback });
+
+			Ok(())
+		}
+
+		/// Cancel a task that cannot run because the system is shut down, and return the
+		/// execution fee paid for the executions it has left.
+		///
+		/// A Recurring task is refunded the one execution paid for in advance. Tasks whose
+		/// execution times passed during the shutdown can also be claimed.
+		///
+		/// # Parameters
+		/// * `task_id`: The id of the task.
+		///
+		/// # Errors
+		/// * `NotShutdown`: The system is not shut down.
+		/// * `TaskDoesNotExist`: The caller has no task with this id.
+		/// * `UnknownRemoteAccount`: No account can be derived for the remote origin.
+		#[pallet::call_index(15)]
+		#[pallet::weight(<T as Config>::WeightInfo::claim_refund_for_unexecuted_full())]
+		pub fn claim_refund_for_unexecuted(
+			origin: OriginFor<T>,
+			task_id: TaskIdV2,
+		) -> DispatchResult {
+			let (who, _) = Self::ensure_task_owner(origin)?;
+			if !Self::is_shutdown() {
+				Err(Error::<T>::NotShutdown)?
+			}
+
+			let task =
+				AccountTasks::<T>::get(&who, &task_id).ok_or(Error::<T>::TaskDoesNotExist)?;
+			let amount = T::FeeHandler::refund_execution_fee_for(
+				&who,
+				&task.action,
+				task.schedule.known_executions_left(),
+			)?;
+			Self::remove_task(task_id.clone(), task);
+
+			if !amount.is_zero() {
+				Self::deposit_event(Event::<T>::ExecutionFeeRefunded { who, task_id, amount });
+			}
+
+			Ok(())
+		}
 	}
 
 	impl<T: Config> Pallet<T> {
@@ -686,8 +1243,7 @@ pub mod pallet {
 
 			// The last_missed_slot might not be caught up within just 1 block.
 			// It might take multiple blocks to fully catch up, so we limit update to a max weight.
-			let max_update_weight: Weight =
-				Weight::from_parts(T::UpdateQueueRatio::get().mul_floor(weight_left.ref_time()), 0);
+			let max_update_weight: Weight = T::UpdateQueueRatio::get() * weight_left;
 
 			let update_weight = Self::update_task_queue(max_update_weight);
 
@@ -698,24 +1254,38 @@ pub mod pallet {
 				.saturating_add(T::DbWeight::get().reads(1u64))
 				.saturating_add(T::DbWeight::get().writes(1u64));
 
-			if weight_left.ref_time() < run_task_weight.ref_time() {
-				return weight_left
+			if weight_left.any_lt(run_task_weight) {
+				return max_weight.saturating_sub(weight_left)
 			}
 
-			// run as many scheduled tasks as we can
-			let task_queue = Self::get_task_queue();
+			// run as many scheduled tasks as we can, a page at a time
+			let (mut head, tail) = Self::get_task_queue_bounds();
 			weight_left = weight_left.saturating_sub(T::DbWeight::get().reads(1u64));
-			if !task_queue.is_empty() {
-				let (tasks_left, new_weight_left) = Self::run_tasks(task_queue, weight_left);
-				TaskQueueV2::<T>::put(tasks_left);
-				weight_left = new_weight_left.saturating_sub(T::DbWeight::get().writes(1u64));
+			if head < tail {
+				let page_weight = T::DbWeight::get().reads_writes(1u64, 1u64);
+				while head < tail && weight_left.all_gte(run_task_weight) {
+					let page = Self::get_task_queue_page(head).unwrap_or_default();
+					let (tasks_left, new_weight_left) =
+						Self::run_tasks(page.into_inner(), weight_left);
+					weight_left = new_weight_left.saturating_sub(page_weight);
+					if !tasks_left.is_empty() {
+						// The tasks left are a suffix of the page, so they always fit.
+						let page = BoundedVec::truncate_from(tasks_left);
+						TaskQueuePages::<T>::insert(head, page);
+						break
+					}
+					TaskQueuePages::<T>::remove(head);
+					head.saturating_inc();
+				}
+				TaskQueueBounds::<T>::put((head, tail));
+				weight_left = weight_left.saturating_sub(T::DbWeight::get().writes(1u64));
 			}
 
 			// if there is weight left we need to handled the missed tasks
 			let run_missed_task_weight = <T as Config>::WeightInfo::run_missed_tasks_many_found(1)
 				.saturating_add(T::DbWeight::get().reads(1u64))
 				.saturating_add(T::DbWeight::get().writes(1u64));
-			if weight_left.ref_time() >= run_missed_task_weight.ref_time() {
+			if weight_left.all_gte(run_missed_task_weight) {
 				let missed_queue = Self::get_missed_queue();
 				weight_left = weight_left.saturating_sub(T::DbWeight::get().reads(1u64));
 				if !missed_queue.is_empty() {
@@ -796,17 +1366,83 @@ pub mod pallet {
 				if let Some(ScheduledTasksOf::<T> { tasks: account_task_ids, .. }) =
 					Self::get_scheduled_tasks(current_time_slot)
 				{
-					TaskQueueV2::<T>::put(account_task_ids);
+					Self::set_task_queue(account_task_ids);
 					ScheduledTasksV3::<T>::remove(current_time_slot);
 				} else {
-					let empty_queue: Vec<AccountTaskId<T>> = vec![];
-					TaskQueueV2::<T>::put(empty_queue);
+					Self::clear_task_queue();
 				}
 			}
 			let weight_used = <T as Config>::WeightInfo::update_scheduled_task_queue();
 			(current_time_slot, weight_used)
 		}
 
+		/// All tasks in the task queue, in the order they will run.
+		pub fn get_task_queue() -> Vec<AccountTaskId<T>> {
+			let (head, tail) = Self::get_task_queue_bounds();
+			(head..tail)
+				.filter_map(Self::get_task_queue_page)
+				.flat_map(|page| page.into_inner())
+				.collect()
+		}
+
+		/// Append a task to the last page of the task queue, opening a new page if it is full.
+		pub fn push_to_task_queue(account_task_id: AccountTaskId<T>) {
+			let (head, mut tail) = Self::get_task_queue_bounds();
+			if head < tail {
+				let last = tail.saturating_sub(1);
+				let mut page = Self::get_task_queue_page(last).unwrap_or_default();
+				if page.try_push(account_task_id.clone()).is_ok() {
+					TaskQueuePages::<T>::insert(last, page);
+					return
+				}
+			}
+
+			if let Ok(page) = BoundedVec::try_from(vec![account_task_id]) {
+				TaskQueuePages::<T>::insert(tail, page);
+				tail.saturating_inc();
+				TaskQueueBounds::<T>::put((head, tail));
+			}
+		}
+
+		/// Replace the contents of the task queue with `account_task_ids`.
+		pub fn set_task_queue(account_task_ids: Vec<AccountTaskId<T>>) {
+			Self::clear_task_queue();
+
+			let page_size = T::TaskQueuePageSize::get().max(1) as usize;
+			let mut tail = 0u32;
+			for chunk in account_task_ids.chunks(page_size) {
+				TaskQueuePages::<T>::insert(tail, BoundedVec::truncate_from(chunk.to_vec()));
+				tail.saturating_inc();
+			}
+			TaskQueueBounds::<T>::put((0, tail));
+		}
+
+		/// Remove every page of the task queue.
+		pub fn clear_task_queue() {
+			let (head, tail) = Self::get_task_queue_bounds();
+			for index in head..tail {
+				TaskQueuePages::<T>::remove(index);
+			}
+			TaskQueueBounds::<T>::kill();
+		}
+
+		/// Remove a task from the task queue. Returns whether the task was found.
+		///
+		/// An emptied page is left in place and skipped when the queue is drained.
+		pub fn remove_from_task_queue(task_id: &TaskIdV2) -> bool {
+			let (head, tail) = Self::get_task_queue_bounds();
+			for index in head..tail {
+				if let Some(mut page) = Self::get_task_queue_page(index) {
+					if let Some(position) = page.iter().position(|(_, id)| id == task_id) {
+						page.remove(position);
+						TaskQueuePages::<T>::insert(index, page);
+						return true
+					}
+				}
+			}
+			false
+		}
+
 		/// Checks if append_to_missed_tasks needs to run and then runs and measures weight as needed
 		pub fn update_missed_queue(
 			current_time_slot: u64,
@@ -845,9 +1481,7 @@ pub mod pallet {
 				T::SlotSizeSeconds::get())
 			.saturating_sub(1);
 			for i in 0..diff {
-				if allotted_weight.ref_time() <
-					<T as Config>::WeightInfo::shift_missed_tasks().ref_time()
-				{
+				if allotted_weight.any_lt(<T as Config>::WeightInfo::shift_missed_tasks()) {
 					diff = i;
 					break
 				}
@@ -965,6 +1599,9 @@ pub mod pallet {
 								),
 						};
 
+						T::OnTaskExecuted::on_task_executed(dispatch_error.is_none());
+						ExecutedTasks::<T>::append(task_id.clone());
+
 						// If an error occurs during the task execution process, the TaskExecutionFailed event will be emitted;
 						// Otherwise, the TaskExecuted event will be thrown.
 						if let Some(err) = dispatch_error {
@@ -980,18 +1617,22 @@ pub mod pallet {
 							});
 						}
 
-						Self::handle_task_post_processing(task_id.clone(), task, dispatch_error);
+						let owner_id = task.owner_id.clone();
+						Self::handle_task_post_processing(
+							task_id.clone(),
+							task,
+							dispatch_error.clone(),
+						);
+						Self::report_to_task_callback(&owner_id, task_id, dispatch_error);
 						task_action_weight
-							.saturating_add(T::DbWeight::get().writes(1u64))
-							.saturating_add(T::DbWeight::get().reads(1u64))
+							.saturating_add(T::DbWeight::get().writes(3u64))
+							.saturating_add(T::DbWeight::get().reads(3u64))
 					},
 				};
 
 				weight_left = weight_left.saturating_sub(action_weight);
 
-				if weight_left.ref_time() <
-					<T as Config>::WeightInfo::run_tasks_many_found(1).ref_time()
-				{
+				if weight_left.any_lt(<T as Config>::WeightInfo::run_tasks_many_found(1)) {
 					break
 				}
 			}
@@ -1038,9 +1679,7 @@ pub mod pallet {
 
 				weight_left = weight_left.saturating_sub(action_weight);
 
-				if weight_left.ref_time() <
-					<T as Config>::WeightInfo::run_missed_tasks_many_found(1).ref_time()
-				{
+				if weight_left.any_lt(<T as Config>::WeightInfo::run_missed_tasks_many_found(1)) {
 					break
 				}
 			}
@@ -1080,11 +1719,17 @@ pub mod pallet {
 				overall_weight,
 				flow,
 			) {
-				Ok(()) => (<T as Config>::WeightInfo::run_xcmp_task(), None),
-				Err(e) => (<T as Config>::WeightInfo::run_xcmp_task(), Some(e)),
+				Ok(post_info) => (Self::xcmp_task_weight(post_info.actual_weight), None),
+				Err(e) => (Self::xcmp_task_weight(e.post_info.actual_weight), Some(e.error)),
 			}
 		}
 
+		/// The weight of an XCMP task given the weight its local XCM execution actually used.
+		fn xcmp_task_weight(local_execution_weight: Option<Weight>) -> Weight {
+			<T as Config>::WeightInfo::run_xcmp_task()
+				.saturating_add(local_execution_weight.unwrap_or_default())
+		}
+
 		/// Executes auto compounding delegation and reschedules task on success
 		pub fn run_auto_compound_delegated_stake_task(
 			delegator: AccountOf<T>,
@@ -1106,15 +1751,15 @@ pub mod pallet {
 				.checked_sub(&reserved_funds)
 			{
 				Some(delegation) => {
+					let task_weight =
+						<T as Config>::WeightInfo::run_auto_compound_delegated_stake_task();
 					match T::DelegatorActions::delegator_bond_more(
 						&delegator, &collator, delegation,
 					) {
-						Ok(_) => (
-							<T as Config>::WeightInfo::run_auto_compound_delegated_stake_task(),
-							None,
-						),
+						Ok(_) => (task_weight, None),
+						// A bond that fails early reports the smaller weight it actually used
 						Err(e) => (
-							<T as Config>::WeightInfo::run_auto_compound_delegated_stake_task(),
+							e.post_info.actual_weight.map_or(task_weight, |w| w.min(task_weight)),
 							Some(e.error),
 						),
 					}
@@ -1189,7 +1834,7 @@ pub mod pallet {
 		}
 
 		/// Removes the task of the provided task_id and all scheduled tasks, including those in the task queue.
-		fn remove_task(task_id: TaskIdV2, task: TaskOf<T>) {
+		pub(crate) fn remove_task(task_id: TaskIdV2, task: TaskOf<T>) {
 			let mut found_task: bool = false;
 			let mut execution_times = task.execution_times();
 			Self::clean_execution_times_vector(&mut execution_times);
@@ -1204,15 +1849,7 @@ pub mod pallet {
 					// Execution time is equal to last time slot and task queue should be checked for task id.
 					// After checking task queue no other execution times need to be removed.
 					if *execution_time == last_time_slot {
-						let mut task_queue = Self::get_task_queue();
-						for i in 0..task_queue.len() {
-							if task_queue[i].1 == task_id {
-								task_queue.remove(i);
-								TaskQueueV2::<T>::put(task_queue);
-								found_task = true;
-								break
-							}
-						}
+						found_task = Self::remove_from_task_queue(&task_id);
 						break
 					}
 					// Execution time is greater than current time slot and in the future.  Remove task id from scheduled tasks.
@@ -1283,6 +1920,7 @@ pub mod pallet {
 			// TODO: Add refund reserved execution fees here
 
 			AccountTasks::<T>::remove(task.owner_id.clone(), task_id.clone());
+			TaskCallbacks::<T>::remove(task.owner_id.clone(), task_id.clone());
 
 			Self::deposit_event(Event::TaskCancelled { who: task.owner_id, task_id });
 		}
@@ -1300,9 +1938,7 @@ pub mod pallet {
 			// If 'dev-queue' feature flag and execution_times equals [0], allows for putting a task directly on the task queue
 			#[cfg(feature = "dev-queue")]
 			if execution_times == vec![0] {
-				let mut task_queue = Self::get_task_queue();
-				task_queue.push((owner_id, task.task_id.clone()));
-				TaskQueueV2::<T>::put(task_queue);
+				Self::push_to_task_queue((owner_id, task.task_id.clone()));
 
 				return Ok(task.task_id.clone())
 			}
@@ -1341,6 +1977,12 @@ pub mod pallet {
 			schedule: Schedule,
 			abort_errors: Vec<Vec<u8>>,
 		) -> Result<TaskIdV2, DispatchError> {
+			let scheduled_this_block = Self::get_tasks_scheduled_this_block(&owner_id);
+			ensure!(
+				scheduled_this_block < T::MaxTasksPerAccountPerBlock::get(),
+				Error::<T>::AccountBlockLimitReached
+			);
+
 			match action.clone() {
 				Action::XCMP { execution_fee, .. } => {
 					let asset_location = MultiLocation::try_from(execution_fee.asset_location)
@@ -1365,13 +2007,130 @@ pub mod pallet {
 			let task_id =
 				T::FeeHandler::pay_checked_fees_for(&owner_id, &action, executions, || {
 					let task_id = Self::schedule_task(&task)?;
+					let deposit =
+						hold_deposit::<_, T::Currency, T::StorageDeposit>(&owner_id, &task)?;
+					if !deposit.is_zero() {
+						TaskDeposits::<T>::insert(owner_id.clone(), task_id.clone(), deposit);
+					}
 					AccountTasks::<T>::insert(owner_id.clone(), task_id.clone(), task);
 					Ok(task_id)
 				})?;
+			TasksScheduledThisBlock::<T>::insert(&owner_id, scheduled_this_block + 1);
 
 			Ok(task_id)
 		}
 
+		/// Forget the tasks scheduled and run in the previous block, so every account starts the
+		/// block with its full `MaxTasksPerAccountPerBlock` allowance.
+		fn reset_block_records() -> Weight {
+			let cleared = TasksScheduledThisBlock::<T>::clear(u32::MAX, None).unique;
+			ExecutedTasks::<T>::kill();
+			T::DbWeight::get().writes(u64::from(cleared).saturating_add(1))
+		}
+
+		/// The account acting for `origin`: the signer, or the account derived from the location
+		/// of a remote origin, which is returned alongside it.
+		fn ensure_task_owner(
+			origin: OriginFor<T>,
+		) -> Result<(AccountOf<T>, Option<MultiLocation>), DispatchError> {
+			if let Ok(who) = ensure_signed(origin.clone()) {
+				return Ok((who, None))
+			}
+			let location = T::RemoteOrigin::ensure_origin(origin)?;
+			let who =
+				T::RemoteAccountOf::convert(location).ok_or(Error::<T>::UnknownRemoteAccount)?;
+			Ok((who, Some(location)))
+		}
+
+		/// Send the outcome of an execution of a task to its callback location, if it has one.
+		/// The callback is forgotten once the task is no longer scheduled.
+		fn report_to_task_callback(
+			owner_id: &AccountOf<T>,
+			task_id: &TaskIdV2,
+			error: Option<DispatchError>,
+		) {
+			let callback = match Self::get_task_callback(owner_id, task_id) {
+				Some(callback) => callback,
+				None => return,
+			};
+			if !AccountTasks::<T>::contains_key(owner_id, task_id) {
+				TaskCallbacks::<T>::remove(owner_id, task_id);
+			}
+
+			let response = match error {
+				Some(error) => MaybeErrorCode::from(error.encode()),
+				None => MaybeErrorCode::Success,
+			};
+			let message = Xcm(vec![
+				QueryResponse {
+					query_id: callback.query_id,
+					response: Response::DispatchResult(response),
+					max_weight: Weight::zero(),
+					querier: None,
+				},
+				SetTopic(sp_io::hashing::blake2_256(task_id)),
+			]);
+
+			let event = match send_xcm::<T::XcmSender>(callback.destination, message) {
+				Ok(_) => Event::<T>::TaskCallbackSent {
+					who: owner_id.clone(),
+					task_id: task_id.clone(),
+					destination: callback.destination,
+				},
+				Err(_) => Event::<T>::TaskCallbackFailed {
+					who: owner_id.clone(),
+					task_id: task_id.clone(),
+					destination: callback.destination,
+				},
+			};
+			Self::deposit_event(event);
+		}
+
+		/// The token of the chain a remote origin belongs to, if fees can be priced in it.
+		fn remote_fee_location(location: &MultiLocation) -> Option<MultiLocation> {
+			let chain = match (location.parents, location.first_interior()) {
+				(1, Some(Parachain(para_id))) => MultiLocation::new(1, X1(Parachain(*para_id))),
+				_ => return None,
+			};
+			T::CurrencyIdConvert::convert(chain)?;
+			let reanchored =
+				chain.reanchored(&T::SelfLocation::get(), T::UniversalLocation::get()).ok()?;
+			T::FeeConversionRateProvider::get_fee_per_second(&reanchored).map(|_| chain)
+		}
+
+		/// The account holding the funds of an automation pool.
+		pub fn pool_account_id(pool_id: PoolId) -> AccountOf<T> {
+			T::PoolPalletId::get().into_sub_account_truncating(pool_id)
+		}
+
+		/// Return the pool if `who` is its admin.
+		fn ensure_pool_admin(
+			pool_id: PoolId,
+			who: &AccountOf<T>,
+		) -> Result<AutomationPoolOf<T>, DispatchError> {
+			let pool = Self::get_pool(pool_id).ok_or(Error::<T>::PoolDoesNotExist)?;
+			if &pool.admin != who {
+				Err(Error::<T>::NotPoolAdmin)?
+			}
+			Ok(pool)
+		}
+
+		/// Compute `value * numerator / denominator`, rounding down.
+		fn pro_rata(
+			value: BalanceOf<T>,
+			numerator: BalanceOf<T>,
+			denominator: BalanceOf<T>,
+		) -> Result<BalanceOf<T>, DispatchError> {
+			multiply_by_rational_with_rounding(
+				value.saturated_into(),
+				numerator.saturated_into(),
+				denominator.saturated_into(),
+				Rounding::Down,
+			)
+			.map(|result| result.saturated_into())
+			.ok_or_else(|| ArithmeticError::Overflow.into())
+		}
+
 		/// Schedule a task with TaskScheduled event.
 		pub fn schedule_task_with_event(
 			action: ActionOf<T>,
@@ -0,0 +1,236 @@
+// This file is part of OAK Blockchain.
+
+// Copyright (C) 2022 OAK Network
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage migrations for the automation time pallet.
+//!
+//! Migrations that have to visit every task run in `on_initialize` across as many blocks as they
+//! need, resuming from a cursor stored in `TaskMigration`.
+
+use crate::{
+	weights::WeightInfo, AccountTaskId, AccountTasks, Config, Event, Pallet, TaskMigration,
+	TaskMigrationProgress,
+};
+#[cfg(feature = "try-runtime")]
+use codec::{Decode, Encode};
+use core::marker::PhantomData;
+use frame_support::{
+	pallet_prelude::ValueQuery,
+	storage_alias,
+	traits::{Get, GetStorageVersion, OnRuntimeUpgrade, StorageVersion},
+	weights::Weight,
+};
+use sp_std::vec::Vec;
+
+impl<T: Config> Pallet<T> {
+	/// Advance the check of `AccountTasks` against the task bounds using at most `max_weight`.
+	pub fn migrate_account_tasks(progress: TaskMigrationProgress, max_weight: Weight) -> Weight {
+		let db_weight = T::DbWeight::get();
+		// Removing a task is the most expensive outcome of checking it
+		let item_weight = db_weight
+			.reads(1u64)
+			.saturating_add(<T as Config>::WeightInfo::cancel_scheduled_task_full());
+		let max_items = max_weight
+			.ref_time()
+			.checked_div(item_weight.ref_time())
+			.map_or(u32::MAX, |items| items.try_into().unwrap_or(u32::MAX))
+			.max(1);
+
+		let (checked, removed) = Self::step_task_migration(progress, max_items);
+		db_weight
+			.reads_writes(1u64, 1u64)
+			.saturating_add(db_weight.reads(checked.into()))
+			.saturating_add(
+				<T as Config>::WeightInfo::cancel_scheduled_task_full()
+					.saturating_mul(removed.into()),
+			)
+	}
+
+	/// Check up to `max_items` tasks against the task bounds and return how many were checked and
+	/// how many of those were removed.
+	///
+	/// A task that exceeds the bounds is cancelled. A task that can no longer be decoded only has
+	/// its entry removed, as its schedule is unknown; the scheduled slots referring to it report
+	/// `TaskNotFound` when they come up.
+	pub fn step_task_migration(progress: TaskMigrationProgress, max_items: u32) -> (u32, u32) {
+		let TaskMigrationProgress { cursor, mut checked, mut removed } = progress;
+		let mut keys = match cursor {
+			Some(cursor) => AccountTasks::<T>::iter_keys_from(cursor),
+			None => AccountTasks::<T>::iter_keys(),
+		};
+
+		let mut processed = 0u32;
+		let mut removed_now = 0u32;
+		let mut finished = false;
+		while processed < max_items {
+			match keys.next() {
+				Some((owner_id, task_id)) => {
+					match AccountTasks::<T>::try_get(&owner_id, &task_id) {
+						Ok(task) if task.within_bounds::<T>() => (),
+						Ok(task) => {
+							Self::remove_task(task_id, task);
+							removed_now += 1;
+						},
+						Err(_) => {
+							AccountTasks::<T>::remove(&owner_id, &task_id);
+							removed_now += 1;
+						},
+					}
+					processed += 1;
+				},
+				None => {
+					finished = true;
+					break
+				},
+			}
+		}
+
+		checked = checked.saturating_add(processed);
+		removed = removed.saturating_add(removed_now);
+		if finished {
+			TaskMigration::<T>::kill();
+			Self::deposit_event(Event::TaskMigrationCompleted { checked, removed });
+		} else {
+			let cursor = Some(keys.last_raw_key().to_vec());
+			TaskMigration::<T>::put(TaskMigrationProgress { cursor, checked, removed });
+			Self::deposit_event(Event::TaskMigrationProgressed { checked, removed });
+		}
+		(processed, removed_now)
+	}
+}
+
+pub mod v3 {
+	use super::*;
+
+	/// The task queue before it was split into pages.
+	#[storage_alias]
+	pub type TaskQueueV2<T: Config> = StorageValue<Pallet<T>, Vec<AccountTaskId<T>>, ValueQuery>;
+
+	/// Move the tasks in `TaskQueueV2` into `TaskQueuePages`, keeping their order.
+	pub struct MigrateToV3<T>(PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV3<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() >= 3 {
+				log::info!(
+					target: "runtime::automation-time",
+					"TaskQueue migration skipped, storage is already at v3"
+				);
+				return T::DbWeight::get().reads(1u64)
+			}
+
+			let task_queue = TaskQueueV2::<T>::take();
+			let page_size = T::TaskQueuePageSize::get().max(1) as usize;
+			let pages = task_queue.chunks(page_size).count() as u64;
+			let migrated = task_queue.len();
+			Pallet::<T>::set_task_queue(task_queue);
+			StorageVersion::new(3).put::<Pallet<T>>();
+			log::info!(
+				target: "runtime::automation-time",
+				"Migrated {} tasks into {} task queue pages",
+				migrated,
+				pages
+			);
+
+			T::DbWeight::get().reads_writes(2u64, pages.saturating_add(3))
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			Ok(TaskQueueV2::<T>::get().encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+			let task_queue_before = Vec::<AccountTaskId<T>>::decode(&mut state.as_slice())
+				.map_err(|_| "failed to decode the pre-upgrade task queue")?;
+
+			frame_support::ensure!(
+				Pallet::<T>::on_chain_storage_version() == 3,
+				"AutomationTime storage version should be 3"
+			);
+			frame_support::ensure!(!TaskQueueV2::<T>::exists(), "TaskQueueV2 should be removed");
+			frame_support::ensure!(
+				Pallet::<T>::get_task_queue() == task_queue_before,
+				"TaskQueue migration must keep every task in order"
+			);
+			Ok(())
+		}
+	}
+}
+
+pub mod v4 {
+	use super::*;
+
+	/// Check every task in `AccountTasks` against the task bounds, so that the bounded task types
+	/// can decode all of them.
+	///
+	/// This only starts the check; the pallet carries it out over the following blocks and emits
+	/// `TaskMigrationProgressed` for every block and `TaskMigrationCompleted` at the end. Tasks
+	/// are not triggered while the check is in progress, those that come due meanwhile are
+	/// handled as missed tasks afterwards.
+	pub struct MigrateToV4<T>(PhantomData<T>);
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV4<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() >= 4 {
+				log::info!(
+					target: "runtime::automation-time",
+					"Task bounds migration skipped, storage is already at v4"
+				);
+				return T::DbWeight::get().reads(1u64)
+			}
+
+			TaskMigration::<T>::put(TaskMigrationProgress::default());
+			StorageVersion::new(4).put::<Pallet<T>>();
+			log::info!(target: "runtime::automation-time", "Started task bounds migration");
+
+			T::DbWeight::get().reads_writes(1u64, 2u64)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, &'static str> {
+			let out_of_bounds = AccountTasks::<T>::iter_values()
+				.filter(|task| !task.within_bounds::<T>())
+				.count() as u64;
+			log::info!(
+				target: "runtime::automation-time",
+				"{} tasks exceed the task bounds and will be cancelled",
+				out_of_bounds
+			);
+			Ok((AccountTasks::<T>::iter_keys().count() as u64).encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), &'static str> {
+			let task_count_before = u64::decode(&mut state.as_slice())
+				.map_err(|_| "failed to decode the pre-upgrade task count")?;
+			let task_count_after = AccountTasks::<T>::iter_keys().count() as u64;
+
+			frame_support::ensure!(
+				Pallet::<T>::on_chain_storage_version() == 4,
+				"AutomationTime storage version should be 4"
+			);
+			frame_support::ensure!(
+				TaskMigration::<T>::exists(),
+				"Task bounds migration should be in progress"
+			);
+			frame_support::ensure!(
+				task_count_before == task_count_after,
+				"Starting the task bounds migration must not remove tasks"
+			);
+			Ok(())
+		}
+	}
+}
@@ -22,13 +22,15 @@ use crate::TaskIdV2;
 use frame_benchmarking::frame_support::assert_ok;
 use frame_support::{
 	construct_runtime, parameter_types,
-	traits::{ConstU128, ConstU32, Everything},
+	traits::{ConstU128, ConstU32, EnsureOrigin, Everything},
 	weights::Weight,
 	PalletId,
 };
 use frame_system::{self as system, EnsureRoot, RawOrigin};
 use orml_traits::parameter_type_with_key;
-use primitives::{AbsoluteAndRelativeReserveProvider, EnsureProxy, TransferCallCreator};
+use primitives::{
+	AbsoluteAndRelativeReserveProvider, EnsureProxy, LinearStorageDeposit, TransferCallCreator,
+};
 use sp_core::H256;
 use sp_runtime::{
 	testing::Header,
@@ -52,6 +54,7 @@ pub const PROXY_ACCOUNT: [u8; 32] = [4u8; 32];
 pub const COLLATOR_ACCOUNT: [u8; 32] = [5u8; 32];
 
 pub const PARA_ID: u32 = 2000;
+pub const SIBLING_PARA_ID: u32 = 2114;
 pub const NATIVE: CurrencyId = 0;
 pub const NATIVE_LOCATION: MultiLocation = MultiLocation { parents: 0, interior: Here };
 pub const NATIVE_EXECUTION_WEIGHT_FEE: u128 = 12;
@@ -97,6 +100,7 @@ construct_runtime!(
 		Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
 		ParachainInfo: parachain_info::{Pallet, Storage, Config},
+		CumulusXcm: cumulus_pallet_xcm::{Pallet, Event<T>, Origin},
 		Tokens: orml_tokens::{Pallet, Storage, Event<T>, Config<T>},
 		Currencies: orml_currencies::{Pallet, Call},
 		AutomationTime: pallet_automation_time::{Pallet, Call, Storage, Event<T>},
@@ -161,6 +165,11 @@ impl pallet_balances::Config for Test {
 
 impl parachain_info::Config for Test {}
 
+impl cumulus_pallet_xcm::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type XcmExecutor = ();
+}
+
 parameter_type_with_key! {
 	pub ExistentialDeposits: |_currency_id: CurrencyId| -> Balance {
 		Default::default()
@@ -376,6 +385,27 @@ impl<Test: frame_system::Config> pallet_automation_time::WeightInfo for MockWeig
 	fn shift_missed_tasks() -> Weight {
 		Weight::from_parts(900_000, 0u64)
 	}
+	fn create_pool() -> Weight {
+		Weight::zero()
+	}
+	fn contribute_to_pool() -> Weight {
+		Weight::zero()
+	}
+	fn withdraw_from_pool() -> Weight {
+		Weight::zero()
+	}
+	fn set_pool_admin() -> Weight {
+		Weight::zero()
+	}
+	fn reclaim_deposit() -> Weight {
+		Weight::zero()
+	}
+	fn set_task_callback() -> Weight {
+		Weight::zero()
+	}
+	fn claim_refund_for_unexecuted_full() -> Weight {
+		Weight::zero()
+	}
 }
 
 pub struct MockXcmpTransactor<T, C>(PhantomData<(T, C)>);
@@ -394,17 +424,61 @@ where
 		_transact_encoded_call_weight: Weight,
 		_overall_weight: Weight,
 		_flow: InstructionSequence,
+	) -> DispatchResultWithPostInfo {
+		Ok(XcmpLocalExecutionWeight::get().into())
+	}
+
+	fn pay_xcm_fee(
+		_: CurrencyId,
+		_: T::AccountId,
+		_: u128,
 	) -> Result<(), sp_runtime::DispatchError> {
 		Ok(())
 	}
 
-	fn pay_xcm_fee(
+	fn refund_xcm_fee(
 		_: CurrencyId,
 		_: T::AccountId,
 		_: u128,
 	) -> Result<(), sp_runtime::DispatchError> {
 		Ok(())
 	}
+
+	fn check_transact_weights(
+		_destination: MultiLocation,
+		_encoded_call_len: usize,
+		_encoded_call_weight: Weight,
+		_overall_weight: Weight,
+		_flow: InstructionSequence,
+	) -> Result<(), sp_runtime::DispatchError> {
+		Ok(())
+	}
+
+	fn is_delivery_failure(_error: &sp_runtime::DispatchError) -> bool {
+		false
+	}
+}
+
+pub struct MockXcmSender;
+impl SendXcm for MockXcmSender {
+	type Ticket = (MultiLocation, Xcm<()>);
+
+	fn validate(
+		destination: &mut Option<MultiLocation>,
+		message: &mut Option<Xcm<()>>,
+	) -> SendResult<Self::Ticket> {
+		let destination = destination.take().ok_or(SendError::MissingArgument)?;
+		let message = message.take().ok_or(SendError::MissingArgument)?;
+		Ok(((destination, message), MultiAssets::new()))
+	}
+
+	fn deliver(ticket: Self::Ticket) -> Result<XcmHash, SendError> {
+		let hash = ticket.1.using_encoded(sp_io::hashing::blake2_256);
+		let mut sent = SentXcm::get();
+		sent.push(ticket);
+		SentXcm::set(sent);
+		Ok(hash)
+	}
 }
 
 pub struct ScheduleAllowList;
@@ -461,6 +535,47 @@ impl EnsureProxy<AccountId> for MockEnsureProxy {
 	}
 }
 
+/// Sibling parachains, as the runtimes see them after converting a `Transact` origin.
+pub struct MockRemoteOrigin;
+impl EnsureOrigin<RuntimeOrigin> for MockRemoteOrigin {
+	type Success = MultiLocation;
+
+	fn try_origin(o: RuntimeOrigin) -> Result<MultiLocation, RuntimeOrigin> {
+		let origin: Result<cumulus_pallet_xcm::Origin, RuntimeOrigin> = o.into();
+		match origin {
+			Ok(cumulus_pallet_xcm::Origin::SiblingParachain(para_id)) =>
+				Ok(MultiLocation::new(1, X1(Parachain(para_id.into())))),
+			Ok(other) => Err(other.into()),
+			Err(o) => Err(o),
+		}
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn try_successful_origin() -> Result<RuntimeOrigin, ()> {
+		Ok(cumulus_pallet_xcm::Origin::SiblingParachain(SIBLING_PARA_ID.into()).into())
+	}
+}
+
+/// Derives the sovereign account of a sibling parachain the way `SiblingParachainConvertsVia`
+/// does.
+pub struct MockRemoteAccountOf;
+impl Convert<MultiLocation, Option<AccountId>> for MockRemoteAccountOf {
+	fn convert(location: MultiLocation) -> Option<AccountId> {
+		match location {
+			MultiLocation { parents: 1, interior: X1(Parachain(para_id)) } =>
+				Some(sibling_account(para_id)),
+			_ => None,
+		}
+	}
+}
+
+pub fn sibling_account(para_id: u32) -> AccountId {
+	let mut account = [0u8; 32];
+	account[..4].copy_from_slice(b"sibl");
+	account[4..8].copy_from_slice(&para_id.to_le_bytes());
+	AccountId32::new(account)
+}
+
 pub struct MockTransferCallCreator;
 impl TransferCallCreator<MultiAddress<AccountId, ()>, Balance, RuntimeCall>
 	for MockTransferCallCreator
@@ -479,13 +594,15 @@ impl TransferCallCreator<MultiAddress<AccountId, ()>, Balance, RuntimeCall>
 
 parameter_types! {
 	pub const MaxTasksPerSlot: u32 = 2;
+	pub static MaxTasksPerAccountPerBlock: u32 = 100;
 	#[derive(Debug)]
 	pub const MaxExecutionTimes: u32 = 3;
 	pub const MaxScheduleSeconds: u64 = 86_400;	// 24 hours in seconds
 	pub const SlotSizeSeconds: u64 = 600;		// 10 minutes in seconds;
-	pub const MaxBlockWeight: u64 = 24_000_000;
+	pub const MaxBlockWeight: Weight = Weight::from_parts(24_000_000, 5 * 1024 * 1024);
 	pub const MaxWeightPercentage: Perbill = Perbill::from_percent(40);
 	pub const UpdateQueueRatio: Perbill = Perbill::from_percent(50);
+	pub const TaskQueuePageSize: u32 = 2;
 	pub const ExecutionWeightFee: Balance = NATIVE_EXECUTION_WEIGHT_FEE;
 
 	// When unit testing dynamic dispatch, we use the real weight value of the extrinsics call
@@ -509,16 +626,26 @@ parameter_types! {
 	pub UniversalLocation: InteriorMultiLocation =
 		X2(GlobalConsensus(RelayNetwork::get()), Parachain(ParachainInfo::parachain_id().into()));
 	pub SelfLocation: MultiLocation = MultiLocation::new(1, X1(Parachain(ParachainInfo::parachain_id().into())));
+	pub const AutomationPoolPalletId: PalletId = PalletId(*b"py/autpl");
+	// No deposit unless a test sets one, so fee assertions stay exact
+	pub static TaskDepositPerItem: Balance = 0;
+	pub static TaskDepositPerByte: Balance = 0;
+	// The weight the mock XCMP transactor reports for its local execution
+	pub static XcmpLocalExecutionWeight: Option<Weight> = None;
+	// The messages sent through the mock XCM sender
+	pub static SentXcm: Vec<(MultiLocation, Xcm<()>)> = vec![];
 }
 
 impl pallet_automation_time::Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type MaxTasksPerSlot = MaxTasksPerSlot;
+	type MaxTasksPerAccountPerBlock = MaxTasksPerAccountPerBlock;
 	type MaxExecutionTimes = MaxExecutionTimes;
 	type MaxScheduleSeconds = MaxScheduleSeconds;
 	type MaxBlockWeight = MaxBlockWeight;
 	type MaxWeightPercentage = MaxWeightPercentage;
 	type UpdateQueueRatio = UpdateQueueRatio;
+	type TaskQueuePageSize = TaskQueuePageSize;
 	type WeightInfo = MockWeight<Test>;
 	type ExecutionWeightFee = ExecutionWeightFee;
 	type MaxWeightPerSlot = MaxWeightPerSlot;
@@ -538,6 +665,12 @@ impl pallet_automation_time::Config for Test {
 	type TransferCallCreator = MockTransferCallCreator;
 	type ReserveProvider = AbsoluteAndRelativeReserveProvider<SelfLocation>;
 	type SelfLocation = SelfLocation;
+	type PoolPalletId = AutomationPoolPalletId;
+	type OnTaskExecuted = ();
+	type StorageDeposit = LinearStorageDeposit<TaskDepositPerItem, TaskDepositPerByte>;
+	type RemoteOrigin = MockRemoteOrigin;
+	type RemoteAccountOf = MockRemoteAccountOf;
+	type XcmSender = MockXcmSender;
 }
 
 // Build genesis storage according to the mock runtime.
@@ -628,9 +761,7 @@ pub fn add_to_task_queue(
 	abort_errors: Vec<Vec<u8>>,
 ) -> TaskIdV2 {
 	let task_id = create_task(owner, task_id, schedule, action, abort_errors);
-	let mut task_queue = AutomationTime::get_task_queue();
-	task_queue.push((AccountId32::new(owner), task_id.clone()));
-	TaskQueueV2::<Test>::put(task_queue);
+	AutomationTime::push_to_task_queue((AccountId32::new(owner), task_id.clone()));
 	task_id
 }
 
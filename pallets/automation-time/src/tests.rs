@@ -16,8 +16,10 @@
 // limitations under the License.
 
 use crate::{
-	mock::*, AccountTasks, Action, ActionOf, AssetPayment, Config, Error, InstructionSequence,
-	LastTimeSlot, MissedTaskV2Of, ScheduleParam, ScheduledTasksOf, TaskOf, TaskQueueV2, WeightInfo,
+	migrations::v4::MigrateToV4, mock::*, AccountTasks, Action, ActionOf, AssetPayment, Config,
+	Error, InstructionSequence, LastTimeSlot, MissedTaskV2Of, Schedule, ScheduleParam,
+	ScheduledTasksOf, TaskCallback, TaskMigrationProgress, TaskOf, TaskQueueBounds, TaskQueuePages,
+	WeightInfo,
 };
 
 use codec::Encode;
@@ -25,7 +27,10 @@ use frame_support::{
 	assert_noop, assert_ok,
 	dispatch::GetDispatchInfo,
 	pallet_prelude::DispatchError,
-	traits::OnInitialize,
+	traits::{
+		Currency, GetStorageVersion, OnInitialize, OnRuntimeUpgrade, ReservableCurrency,
+		StorageVersion,
+	},
 	weights::{constants::WEIGHT_REF_TIME_PER_SECOND, Weight},
 };
 use frame_system::{self, RawOrigin};
@@ -1572,6 +1577,127 @@ fn schedule_time_slot_full_rolls_back() {
 	})
 }
 
+// An account can only schedule MaxTasksPerAccountPerBlock tasks in a block. Other accounts are
+// not affected, and the allowance is restored at the start of the next block.
+#[test]
+fn schedule_over_account_block_limit_fails() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		MaxTasksPerAccountPerBlock::set(2);
+		schedule_task(ALICE, vec![SCHEDULED_TIME], vec![2, 4]);
+		schedule_task(ALICE, vec![SCHEDULED_TIME + SLOT_SIZE_SECONDS], vec![2, 4, 5]);
+		assert_eq!(AutomationTime::get_tasks_scheduled_this_block(AccountId32::new(ALICE)), 2);
+
+		let call: RuntimeCall = frame_system::Call::remark { remark: vec![2] }.into();
+		assert_ok!(fund_account_dynamic_dispatch(&AccountId32::new(ALICE), 1, call.encode()));
+		assert_noop!(
+			AutomationTime::schedule_dynamic_dispatch_task(
+				RuntimeOrigin::signed(AccountId32::new(ALICE)),
+				ScheduleParam::Fixed {
+					execution_times: vec![SCHEDULED_TIME + SLOT_SIZE_SECONDS * 2]
+				},
+				Box::new(call.clone())
+			),
+			Error::<Test>::AccountBlockLimitReached,
+		);
+
+		schedule_task(BOB, vec![SCHEDULED_TIME + SLOT_SIZE_SECONDS * 2], vec![2]);
+
+		System::set_block_number(2);
+		AutomationTime::on_initialize(2);
+		assert_eq!(AutomationTime::get_tasks_scheduled_this_block(AccountId32::new(ALICE)), 0);
+		assert_ok!(AutomationTime::schedule_dynamic_dispatch_task(
+			RuntimeOrigin::signed(AccountId32::new(ALICE)),
+			ScheduleParam::Fixed { execution_times: vec![SCHEDULED_TIME + SLOT_SIZE_SECONDS * 2] },
+			Box::new(call)
+		));
+	})
+}
+
+fn sibling_origin(para_id: u32) -> RuntimeOrigin {
+	cumulus_pallet_xcm::Origin::SiblingParachain(para_id.into()).into()
+}
+
+#[test]
+fn remote_origin_owns_and_cancels_its_tasks() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let sibling = sibling_account(SIBLING_PARA_ID);
+		get_xcmp_funds(sibling.clone());
+
+		assert_ok!(AutomationTime::schedule_xcmp_task(
+			sibling_origin(SIBLING_PARA_ID),
+			ScheduleParam::Fixed { execution_times: vec![SCHEDULED_TIME] },
+			Box::new(MultiLocation::new(1, X1(Parachain(PARA_ID))).into()),
+			Box::new(NATIVE_LOCATION.into()),
+			Box::new(AssetPayment {
+				asset_location: MultiLocation::new(0, Here).into(),
+				amount: 10
+			}),
+			vec![2, 4, 5],
+			Weight::from_parts(100_000, 0),
+			Weight::from_parts(200_000, 0),
+			InstructionSequence::PayThroughSovereignAccount,
+			None,
+		));
+		let task_id = last_task_id();
+		assert!(AutomationTime::get_account_task(sibling.clone(), task_id.clone()).is_some());
+
+		assert_noop!(
+			AutomationTime::cancel_task(sibling_origin(SIBLING_PARA_ID + 1), task_id.clone()),
+			Error::<Test>::TaskDoesNotExist,
+		);
+		assert_ok!(AutomationTime::cancel_task(sibling_origin(SIBLING_PARA_ID), task_id.clone()));
+		assert!(AutomationTime::get_account_task(sibling, task_id).is_none());
+	})
+}
+
+#[test]
+fn remote_origin_pays_schedule_fee_in_its_own_token() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let chain = MultiLocation::new(1, X1(Parachain(PARA_ID)));
+		let sibling = sibling_account(PARA_ID);
+		assert_ok!(Currencies::update_balance(
+			RawOrigin::Root.into(),
+			sibling.clone(),
+			FOREIGN_CURRENCY_ID,
+			XmpFee::get() as i64,
+		));
+
+		assert_ok!(AutomationTime::schedule_xcmp_task(
+			sibling_origin(PARA_ID),
+			ScheduleParam::Fixed { execution_times: vec![SCHEDULED_TIME] },
+			Box::new(chain.into()),
+			Box::new(NATIVE_LOCATION.into()),
+			Box::new(AssetPayment { asset_location: chain.into(), amount: 10 }),
+			vec![2, 4, 5],
+			Weight::from_parts(100_000, 0),
+			Weight::from_parts(200_000, 0),
+			InstructionSequence::PayThroughRemoteDerivativeAccount,
+			None,
+		));
+
+		let task = AutomationTime::get_account_task(sibling.clone(), last_task_id()).unwrap();
+		assert!(matches!(task.action, Action::XCMP { schedule_fee, .. } if schedule_fee == chain));
+		let fee = AutomationTime::calculate_schedule_fee_amount(&task.action, 1).unwrap();
+		assert_eq!(Tokens::accounts(&sibling, FOREIGN_CURRENCY_ID).free, XmpFee::get() - fee);
+		assert_eq!(Balances::free_balance(sibling), 0);
+	})
+}
+
+#[test]
+fn schedule_from_unknown_remote_origin_fails() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let call: RuntimeCall = frame_system::Call::remark { remark: vec![2] }.into();
+		assert_noop!(
+			AutomationTime::schedule_dynamic_dispatch_task(
+				cumulus_pallet_xcm::Origin::Relay.into(),
+				ScheduleParam::Fixed { execution_times: vec![SCHEDULED_TIME] },
+				Box::new(call)
+			),
+			DispatchError::BadOrigin,
+		);
+	})
+}
+
 // verify that task scheduled in different block has the right id
 #[test]
 fn taskid_changed_per_block() {
@@ -2108,6 +2234,62 @@ fn cancel_task_not_found() {
 	})
 }
 
+// verify that the deposit of a task stays reserved while the task is scheduled and that anyone
+// can return it to the owner once the task is gone
+#[test]
+fn reclaim_deposit_works_once_task_is_removed() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		TaskDepositPerItem::set(100);
+		TaskDepositPerByte::set(1);
+		let owner = AccountId32::new(ALICE);
+		_ = <Test as Config>::Currency::deposit_creating(&owner, 10_000);
+
+		let task_id = schedule_task(ALICE, vec![SCHEDULED_TIME], vec![2, 4, 5]);
+		let task = AccountTasks::<Test>::get(owner.clone(), task_id.clone()).unwrap();
+		let deposit = 100 + task.encoded_size() as u128;
+		assert_eq!(AutomationTime::get_task_deposit(owner.clone(), task_id.clone()), Some(deposit));
+		assert_eq!(Balances::reserved_balance(&owner), deposit);
+
+		assert_noop!(
+			AutomationTime::reclaim_deposit(
+				RuntimeOrigin::signed(AccountId32::new(BOB)),
+				owner.clone(),
+				task_id.clone(),
+			),
+			Error::<Test>::TaskStillScheduled,
+		);
+
+		assert_ok!(AutomationTime::cancel_task(
+			RuntimeOrigin::signed(owner.clone()),
+			task_id.clone()
+		));
+		System::reset_events();
+		assert_ok!(AutomationTime::reclaim_deposit(
+			RuntimeOrigin::signed(AccountId32::new(BOB)),
+			owner.clone(),
+			task_id.clone(),
+		));
+		assert_eq!(Balances::reserved_balance(&owner), 0);
+		assert_eq!(
+			events(),
+			[RuntimeEvent::AutomationTime(crate::Event::DepositReclaimed {
+				who: owner.clone(),
+				task_id: task_id.clone(),
+				amount: deposit,
+			})]
+		);
+
+		assert_noop!(
+			AutomationTime::reclaim_deposit(
+				RuntimeOrigin::signed(AccountId32::new(BOB)),
+				owner,
+				task_id,
+			),
+			Error::<Test>::DepositNotFound,
+		);
+	})
+}
+
 // verify only the owner of the task can cancel it
 #[test]
 fn cancel_task_fail_non_owner() {
@@ -2656,14 +2838,112 @@ fn trigger_tasks_completes_all_tasks() {
 	})
 }
 
+#[test]
+fn trigger_tasks_records_the_tasks_run_in_the_block() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let task_id1 = add_task_to_task_queue(
+			ALICE,
+			vec![40],
+			vec![SCHEDULED_TIME],
+			create_dynamic_dispatch_remark_action(vec![2, 4, 5]),
+			vec![],
+		);
+		let task_id2 = add_task_to_task_queue(
+			ALICE,
+			vec![50],
+			vec![SCHEDULED_TIME],
+			create_dynamic_dispatch_remark_action(vec![2, 4]),
+			vec![],
+		);
+		LastTimeSlot::<Test>::put((LAST_BLOCK_TIME, LAST_BLOCK_TIME));
+
+		AutomationTime::trigger_tasks(Weight::from_parts(20_000_000, 0));
+		assert_eq!(AutomationTime::get_executed_tasks(), vec![task_id1, task_id2]);
+
+		AutomationTime::on_initialize(2);
+		assert!(AutomationTime::get_executed_tasks().is_empty());
+	})
+}
+
+#[test]
+fn trigger_tasks_reports_the_outcome_to_the_task_callback() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let owner = AccountId32::new(ALICE);
+		let destination = MultiLocation::new(1, X1(Parachain(SIBLING_PARA_ID)));
+		let task_id = add_task_to_task_queue(
+			ALICE,
+			vec![40],
+			vec![SCHEDULED_TIME],
+			create_dynamic_dispatch_remark_action(vec![2, 4, 5]),
+			vec![],
+		);
+		assert_ok!(AutomationTime::set_task_callback(
+			RuntimeOrigin::signed(owner.clone()),
+			task_id.clone(),
+			Some((Box::new(destination.into()), 7)),
+		));
+		assert_eq!(
+			AutomationTime::get_task_callback(&owner, &task_id),
+			Some(TaskCallback { destination, query_id: 7 })
+		);
+		LastTimeSlot::<Test>::put((LAST_BLOCK_TIME, LAST_BLOCK_TIME));
+
+		AutomationTime::trigger_tasks(Weight::from_parts(20_000_000, 0));
+
+		assert_eq!(
+			SentXcm::get(),
+			vec![(
+				destination,
+				Xcm(vec![
+					QueryResponse {
+						query_id: 7,
+						response: Response::DispatchResult(MaybeErrorCode::Success),
+						max_weight: Weight::zero(),
+						querier: None,
+					},
+					SetTopic(sp_io::hashing::blake2_256(&task_id)),
+				])
+			)]
+		);
+		assert_has_event(RuntimeEvent::AutomationTime(crate::Event::TaskCallbackSent {
+			who: owner.clone(),
+			task_id: task_id.clone(),
+			destination,
+		}));
+		// The task ran its last execution, so its callback is gone with it
+		assert_eq!(AutomationTime::get_task_callback(&owner, &task_id), None);
+	})
+}
+
+#[test]
+fn set_task_callback_only_for_own_tasks() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let task_id = add_task_to_task_queue(
+			ALICE,
+			vec![40],
+			vec![SCHEDULED_TIME],
+			create_dynamic_dispatch_remark_action(vec![2, 4, 5]),
+			vec![],
+		);
+		let destination = MultiLocation::new(1, X1(Parachain(SIBLING_PARA_ID)));
+
+		assert_noop!(
+			AutomationTime::set_task_callback(
+				RuntimeOrigin::signed(AccountId32::new(BOB)),
+				task_id,
+				Some((Box::new(destination.into()), 7)),
+			),
+			Error::<Test>::TaskDoesNotExist,
+		);
+	})
+}
+
 #[test]
 fn trigger_tasks_handles_nonexisting_tasks() {
 	new_test_ext(START_BLOCK_TIME).execute_with(|| {
 		let owner = AccountId32::new(ALICE);
 		let bad_task_id = vec![1, 2, 3];
-		let mut task_queue = AutomationTime::get_task_queue();
-		task_queue.push((owner.clone(), bad_task_id.clone()));
-		TaskQueueV2::<Test>::put(task_queue);
+		AutomationTime::push_to_task_queue((owner.clone(), bad_task_id.clone()));
 		LastTimeSlot::<Test>::put((LAST_BLOCK_TIME, LAST_BLOCK_TIME));
 
 		AutomationTime::trigger_tasks(Weight::from_parts(90_000, 0));
@@ -2679,6 +2959,68 @@ fn trigger_tasks_handles_nonexisting_tasks() {
 	})
 }
 
+#[test]
+fn task_queue_is_split_into_pages() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let owner = AccountId32::new(ALICE);
+		let task_ids: Vec<_> = (0..5u8).map(|i| (owner.clone(), vec![i])).collect();
+		AutomationTime::set_task_queue(task_ids.clone());
+
+		assert_eq!(AutomationTime::get_task_queue_bounds(), (0, 3));
+		assert_eq!(AutomationTime::get_task_queue_page(2).unwrap().len(), 1);
+		assert_eq!(AutomationTime::get_task_queue(), task_ids);
+
+		assert!(AutomationTime::remove_from_task_queue(&vec![1]));
+		assert!(!AutomationTime::remove_from_task_queue(&vec![1]));
+		AutomationTime::push_to_task_queue((owner.clone(), vec![5]));
+		AutomationTime::push_to_task_queue((owner.clone(), vec![6]));
+
+		assert_eq!(AutomationTime::get_task_queue_bounds(), (0, 4));
+		assert_eq!(
+			AutomationTime::get_task_queue()
+				.into_iter()
+				.map(|(_, task_id)| task_id)
+				.collect::<Vec<_>>(),
+			vec![vec![0], vec![2], vec![3], vec![4], vec![5], vec![6]]
+		);
+
+		AutomationTime::clear_task_queue();
+		assert_eq!(AutomationTime::get_task_queue_bounds(), (0, 0));
+		assert_eq!(TaskQueuePages::<Test>::iter().count(), 0);
+	})
+}
+
+#[test]
+fn trigger_tasks_drains_task_queue_a_page_at_a_time() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let owner = AccountId32::new(ALICE);
+		for i in 0..3u8 {
+			AutomationTime::push_to_task_queue((owner.clone(), vec![i]));
+		}
+		LastTimeSlot::<Test>::put((LAST_BLOCK_TIME, LAST_BLOCK_TIME));
+
+		// Enough weight for the first page only.
+		AutomationTime::trigger_tasks(Weight::from_parts(90_000, 0));
+
+		assert_eq!(TaskQueueBounds::<Test>::get(), (1, 2));
+		assert_eq!(TaskQueuePages::<Test>::get(0), None);
+		assert_eq!(AutomationTime::get_task_queue(), vec![(owner.clone(), vec![2])]);
+
+		System::reset_events();
+		AutomationTime::trigger_tasks(Weight::from_parts(90_000, 0));
+
+		assert_eq!(
+			events(),
+			[RuntimeEvent::AutomationTime(crate::Event::TaskNotFound {
+				who: owner,
+				task_id: vec![2]
+			}),]
+		);
+		assert_eq!(TaskQueueBounds::<Test>::get(), (2, 2));
+		assert!(AutomationTime::get_task_queue().is_empty());
+	})
+}
+
 #[test]
 fn trigger_tasks_completes_some_tasks() {
 	new_test_ext(START_BLOCK_TIME).execute_with(|| {
@@ -2877,9 +3219,7 @@ fn missed_tasks_removes_completed_tasks() {
 			vec![],
 		);
 
-		let mut task_queue = AutomationTime::get_task_queue();
-		task_queue.push((owner.clone(), task_id01.clone()));
-		TaskQueueV2::<Test>::put(task_queue);
+		AutomationTime::push_to_task_queue((owner.clone(), task_id01.clone()));
 
 		assert_eq!(AutomationTime::get_missed_queue().len(), 1);
 		assert_eq!(AutomationTime::get_task_queue().len(), 1);
@@ -2987,6 +3327,39 @@ fn trigger_tasks_completes_some_xcmp_tasks() {
 	})
 }
 
+// verify that run_tasks charges an XCMP task the weight its local execution actually used
+#[test]
+fn run_tasks_charges_the_weight_used_by_xcmp_tasks() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let local_execution_weight = Weight::from_parts(30_000, 0);
+		XcmpLocalExecutionWeight::set(Some(local_execution_weight));
+		let owner = AccountId32::new(ALICE);
+		let task_id = add_task_to_task_queue(
+			ALICE,
+			vec![40],
+			vec![SCHEDULED_TIME],
+			create_xcmp_action(XcmpActionParams::default()),
+			vec![],
+		);
+
+		let weight = Weight::from_parts(500_000, 0);
+		let (tasks_left, weight_left) =
+			AutomationTime::run_tasks(vec![(owner.clone(), task_id.clone())], weight);
+
+		assert!(tasks_left.is_empty());
+		assert_eq!(
+			weight_left,
+			weight
+				.saturating_sub(<Test as Config>::WeightInfo::run_xcmp_task())
+				.saturating_sub(local_execution_weight)
+		);
+		assert_has_event(RuntimeEvent::AutomationTime(crate::Event::TaskExecuted {
+			who: owner,
+			task_id,
+		}));
+	})
+}
+
 #[test]
 fn trigger_tasks_completes_auto_compound_delegated_stake_task() {
 	new_test_ext(START_BLOCK_TIME).execute_with(|| {
@@ -3823,3 +4196,273 @@ fn on_init_shutdown() {
 		assert_eq!(AutomationTime::get_missed_queue().len(), 0);
 	})
 }
+
+#[test]
+fn pool_contributions_mint_shares_and_withdrawals_redeem_them() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let alice = AccountId32::new(ALICE);
+		let bob = AccountId32::new(BOB);
+		let pool_id = 0;
+		let pool_account = AutomationTime::pool_account_id(pool_id);
+		_ = <Test as Config>::Currency::deposit_creating(&alice, 10_000);
+		_ = <Test as Config>::Currency::deposit_creating(&bob, 10_000);
+
+		assert_ok!(AutomationTime::create_pool(RuntimeOrigin::signed(alice.clone())));
+		assert_eq!(AutomationTime::get_next_pool_id(), 1);
+
+		assert_ok!(AutomationTime::contribute_to_pool(
+			RuntimeOrigin::signed(alice.clone()),
+			pool_id,
+			1_000
+		));
+		assert_ok!(AutomationTime::contribute_to_pool(
+			RuntimeOrigin::signed(bob.clone()),
+			pool_id,
+			3_000
+		));
+		assert_eq!(AutomationTime::get_pool_shares(pool_id, &alice), 1_000);
+		assert_eq!(AutomationTime::get_pool_shares(pool_id, &bob), 3_000);
+		assert_eq!(Balances::free_balance(&pool_account), 4_000);
+
+		// Spending from the pot reduces the value of every share alike.
+		assert_ok!(Balances::transfer_allow_death(
+			RuntimeOrigin::signed(pool_account.clone()),
+			AccountId32::new(COLLATOR_ACCOUNT),
+			2_000
+		));
+
+		assert_noop!(
+			AutomationTime::withdraw_from_pool(
+				RuntimeOrigin::signed(alice.clone()),
+				pool_id,
+				1_001
+			),
+			Error::<Test>::InsufficientPoolShares,
+		);
+		assert_ok!(AutomationTime::withdraw_from_pool(
+			RuntimeOrigin::signed(bob.clone()),
+			pool_id,
+			3_000
+		));
+		assert_eq!(Balances::free_balance(&bob), 8_500);
+		assert_eq!(AutomationTime::get_pool_shares(pool_id, &bob), 0);
+		assert_eq!(AutomationTime::get_pool(pool_id).unwrap().total_shares, 1_000);
+		assert_eq!(
+			last_event(),
+			RuntimeEvent::AutomationTime(crate::Event::PoolWithdrawn {
+				pool_id,
+				who: bob,
+				amount: 1_500,
+				shares: 3_000,
+			})
+		);
+	})
+}
+
+#[test]
+fn pool_tasks_are_managed_by_the_admin_and_owned_by_the_pool() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let alice = AccountId32::new(ALICE);
+		let bob = AccountId32::new(BOB);
+		let pool_id = 0;
+		let pool_account = AutomationTime::pool_account_id(pool_id);
+		let call: RuntimeCall =
+			frame_system::Call::remark_with_event { remark: vec![2, 4, 5] }.into();
+
+		assert_ok!(AutomationTime::create_pool(RuntimeOrigin::signed(alice.clone())));
+		assert_ok!(fund_account_dynamic_dispatch(&pool_account, 1, call.encode()));
+
+		assert_noop!(
+			AutomationTime::schedule_pool_task(
+				RuntimeOrigin::signed(bob.clone()),
+				pool_id,
+				ScheduleParam::Fixed { execution_times: vec![SCHEDULED_TIME] },
+				Box::new(call.clone()),
+			),
+			Error::<Test>::NotPoolAdmin,
+		);
+
+		assert_ok!(AutomationTime::schedule_pool_task(
+			RuntimeOrigin::signed(alice.clone()),
+			pool_id,
+			ScheduleParam::Fixed { execution_times: vec![SCHEDULED_TIME] },
+			Box::new(call),
+		));
+		let task_id = last_task_id();
+		assert_ne!(AutomationTime::get_account_task(pool_account.clone(), task_id.clone()), None);
+
+		assert_ok!(AutomationTime::set_pool_admin(
+			RuntimeOrigin::signed(alice.clone()),
+			pool_id,
+			bob.clone()
+		));
+		assert_noop!(
+			AutomationTime::cancel_pool_task(
+				RuntimeOrigin::signed(alice),
+				pool_id,
+				task_id.clone()
+			),
+			Error::<Test>::NotPoolAdmin,
+		);
+		assert_ok!(AutomationTime::cancel_pool_task(
+			RuntimeOrigin::signed(bob),
+			pool_id,
+			task_id.clone()
+		));
+		assert_eq!(AutomationTime::get_account_task(pool_account, task_id), None);
+	})
+}
+
+#[test]
+fn task_bounds_migration_removes_tasks_outside_the_bounds() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let owner = AccountId32::new(ALICE);
+		let task_within_bounds = TaskOf::<Test>::create_event_task::<Test>(
+			owner.clone(),
+			vec![1],
+			vec![SCHEDULED_TIME],
+			vec![2, 4],
+			vec![],
+		)
+		.unwrap();
+		AccountTasks::<Test>::insert(owner.clone(), vec![1], task_within_bounds.clone());
+
+		// Stored before MaxExecutionTimes applied to it
+		let execution_times: Vec<u64> =
+			(0..4).map(|i| SCHEDULED_TIME + i * SLOT_SIZE_SECONDS).collect();
+		let task_out_of_bounds = TaskOf::<Test>::new(
+			owner.clone(),
+			vec![2],
+			Schedule::Fixed { execution_times, executions_left: 4 },
+			task_within_bounds.action.clone(),
+			vec![],
+		);
+		AccountTasks::<Test>::insert(owner.clone(), vec![2], task_out_of_bounds);
+
+		// A value that no longer decodes
+		frame_support::storage::unhashed::put_raw(
+			&AccountTasks::<Test>::hashed_key_for(owner.clone(), vec![3]),
+			&[0xff],
+		);
+
+		StorageVersion::new(3).put::<AutomationTime>();
+		MigrateToV4::<Test>::on_runtime_upgrade();
+		assert_eq!(AutomationTime::on_chain_storage_version(), 4);
+		assert_eq!(AutomationTime::get_task_migration(), Some(TaskMigrationProgress::default()));
+
+		AutomationTime::step_task_migration(AutomationTime::get_task_migration().unwrap(), 2);
+		assert_eq!(AutomationTime::get_task_migration().map(|progress| progress.checked), Some(2));
+		AutomationTime::step_task_migration(AutomationTime::get_task_migration().unwrap(), 2);
+		assert_has_event(RuntimeEvent::AutomationTime(crate::Event::TaskMigrationCompleted {
+			checked: 3,
+			removed: 2,
+		}));
+		assert_has_event(RuntimeEvent::AutomationTime(crate::Event::TaskCancelled {
+			who: owner.clone(),
+			task_id: vec![2],
+		}));
+		assert!(AutomationTime::get_task_migration().is_none());
+
+		assert_eq!(
+			AccountTasks::<Test>::iter_prefix(owner.clone()).collect::<Vec<_>>(),
+			vec![(vec![1], task_within_bounds)]
+		);
+
+		// Running the migration again is a no-op
+		MigrateToV4::<Test>::on_runtime_upgrade();
+		assert!(AutomationTime::get_task_migration().is_none());
+	})
+}
+
+#[test]
+fn claim_refund_for_unexecuted_requires_shutdown() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let task_id = schedule_task(ALICE, vec![SCHEDULED_TIME], vec![2, 4, 5]);
+
+		assert_noop!(
+			AutomationTime::claim_refund_for_unexecuted(
+				RuntimeOrigin::signed(AccountId32::new(ALICE)),
+				task_id.clone(),
+			),
+			Error::<Test>::NotShutdown,
+		);
+
+		AutomationTime::shutdown();
+		assert_noop!(
+			AutomationTime::claim_refund_for_unexecuted(
+				RuntimeOrigin::signed(AccountId32::new(BOB)),
+				task_id,
+			),
+			Error::<Test>::TaskDoesNotExist,
+		);
+	})
+}
+
+#[test]
+fn claim_refund_for_unexecuted_refunds_the_executions_left_and_cancels_the_task() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let owner = AccountId32::new(ALICE);
+		let action = create_xcmp_action(XcmpActionParams {
+			execution_fee: AssetPayment {
+				asset_location: MultiLocation::new(1, X1(Parachain(PARA_ID))).into(),
+				amount: 100,
+			},
+			instruction_sequence: InstructionSequence::PayThroughSovereignAccount,
+			..XcmpActionParams::default()
+		});
+		let task_id = add_task_to_task_queue(
+			ALICE,
+			vec![40],
+			vec![SCHEDULED_TIME, SCHEDULED_TIME + SLOT_SIZE_SECONDS],
+			action,
+			vec![],
+		);
+		LastTimeSlot::<Test>::put((SCHEDULED_TIME, SCHEDULED_TIME));
+		AutomationTime::shutdown();
+		System::reset_events();
+
+		assert_ok!(AutomationTime::claim_refund_for_unexecuted(
+			RuntimeOrigin::signed(owner.clone()),
+			task_id.clone(),
+		));
+
+		assert_eq!(AutomationTime::get_account_task(owner.clone(), task_id.clone()), None);
+		assert_eq!(AutomationTime::get_task_queue().len(), 0);
+		assert_has_event(RuntimeEvent::AutomationTime(crate::Event::TaskCancelled {
+			who: owner.clone(),
+			task_id: task_id.clone(),
+		}));
+		assert_last_event(RuntimeEvent::AutomationTime(crate::Event::ExecutionFeeRefunded {
+			who: owner,
+			task_id,
+			amount: 200,
+		}));
+	})
+}
+
+#[test]
+fn claim_refund_for_unexecuted_refunds_nothing_for_fees_paid_on_the_destination() {
+	new_test_ext(START_BLOCK_TIME).execute_with(|| {
+		let owner = AccountId32::new(ALICE);
+		let task_id = add_task_to_task_queue(
+			ALICE,
+			vec![40],
+			vec![SCHEDULED_TIME],
+			create_xcmp_action(XcmpActionParams::default()),
+			vec![],
+		);
+		LastTimeSlot::<Test>::put((SCHEDULED_TIME, SCHEDULED_TIME));
+		AutomationTime::shutdown();
+		System::reset_events();
+
+		assert_ok!(AutomationTime::claim_refund_for_unexecuted(
+			RuntimeOrigin::signed(owner.clone()),
+			task_id.clone(),
+		));
+
+		assert_eq!(
+			events(),
+			[RuntimeEvent::AutomationTime(crate::Event::TaskCancelled { who: owner, task_id })]
+		);
+	})
+}
@@ -19,6 +19,37 @@ pub struct AssetPayment {
 	pub amount: u128,
 }
 
+pub type PoolId = u32;
+
+/// Progress of the multi-block check of `AccountTasks` against the task bounds. The cursor is the
+/// raw storage key to resume from in the next block.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Encode, Decode, TypeInfo)]
+pub struct TaskMigrationProgress {
+	pub cursor: Option<Vec<u8>>,
+	/// The number of tasks checked so far.
+	pub checked: u32,
+	/// The number of tasks removed so far.
+	pub removed: u32,
+}
+
+/// Where the outcome of a task is reported after it runs.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Encode, Decode, TypeInfo)]
+pub struct TaskCallback {
+	/// The location the notification is sent to.
+	pub destination: MultiLocation,
+	/// The query id the notification answers, so the receiver can tell its tasks apart.
+	pub query_id: QueryId,
+}
+
+/// A shared pot that owns and pays for automation tasks on behalf of its contributors.
+#[derive(Debug, Encode, Eq, PartialEq, Decode, TypeInfo, Clone)]
+pub struct AutomationPool<AccountId, Balance> {
+	/// The account allowed to schedule and cancel the pool's tasks.
+	pub admin: AccountId,
+	/// The sum of all contributor shares.
+	pub total_shares: Balance,
+}
+
 /// The enum that stores all action specific data.
 #[derive(Clone, Debug, Eq, PartialEq, Encode, Decode, TypeInfo)]
 pub enum Action<AccountId, Balance> {
@@ -283,6 +314,16 @@ impl<AccountId: Clone, Balance> Task<AccountId, Balance> {
 		Ok(Self::new(owner_id, task_id, schedule, action, abort_errors))
 	}
 
+	/// Whether the task fits the bounds enforced when tasks are scheduled. Tasks stored before a
+	/// bound was introduced may not.
+	pub fn within_bounds<T: Config>(&self) -> bool {
+		match &self.schedule {
+			Schedule::Fixed { execution_times, .. } =>
+				execution_times.len() <= T::MaxExecutionTimes::get() as usize,
+			Schedule::Recurring { .. } => true,
+		}
+	}
+
 	pub fn execution_times(&self) -> Vec<UnixTime> {
 		match &self.schedule {
 			Schedule::Fixed { execution_times, .. } => execution_times.to_vec(),
@@ -70,6 +70,13 @@
 //:append_to_missed_tasks 3_350_768,13581
 //:update_scheduled_task_queue 45_812_000,12375
 //:shift_missed_tasks 6_175_000,3681
+//:create_pool 14_210_000,1489
+//:contribute_to_pool 58_903_000,6196
+//:withdraw_from_pool 60_118_000,6196
+//:set_pool_admin 15_870_000,3551
+//:reclaim_deposit 27_416_000,3593
+//:set_task_callback 17_204_000,3562
+//:claim_refund_for_unexecuted_full 1_463_518_000,406458
 
 #![cfg_attr(rustfmt, rustfmt_skip)]
 #![allow(unused_parens)]
@@ -101,6 +108,13 @@ pub trait WeightInfo {
 	fn append_to_missed_tasks(v: u32, ) -> Weight;
 	fn update_scheduled_task_queue() -> Weight;
 	fn shift_missed_tasks() -> Weight;
+	fn create_pool() -> Weight;
+	fn contribute_to_pool() -> Weight;
+	fn withdraw_from_pool() -> Weight;
+	fn set_pool_admin() -> Weight;
+	fn reclaim_deposit() -> Weight;
+	fn set_task_callback() -> Weight;
+	fn claim_refund_for_unexecuted_full() -> Weight;
 }
 
 /// Weights for pallet_automation_time using the Substrate node and recommended hardware.
@@ -461,6 +475,113 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 		Weight::from_parts(6_175_000, 3681)
 			.saturating_add(T::DbWeight::get().reads(1_u64))
 	}
+	/// Storage: AutomationTime NextPoolId (r:1 w:1)
+	/// Proof Skipped: AutomationTime NextPoolId (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: AutomationTime Pools (r:0 w:1)
+	/// Proof Skipped: AutomationTime Pools (max_values: None, max_size: None, mode: Measured)
+	fn create_pool() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `4`
+		//  Estimated: `1489`
+		// Minimum execution time: 13_854_000 picoseconds.
+		Weight::from_parts(14_210_000, 1489)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: AutomationTime Pools (r:1 w:1)
+	/// Proof Skipped: AutomationTime Pools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: AutomationTime PoolShares (r:1 w:1)
+	/// Proof Skipped: AutomationTime PoolShares (max_values: None, max_size: None, mode: Measured)
+	fn contribute_to_pool() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `412`
+		//  Estimated: `6196`
+		// Minimum execution time: 57_611_000 picoseconds.
+		Weight::from_parts(58_903_000, 6196)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	/// Storage: AutomationTime Pools (r:1 w:1)
+	/// Proof Skipped: AutomationTime Pools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationTime PoolShares (r:1 w:1)
+	/// Proof Skipped: AutomationTime PoolShares (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn withdraw_from_pool() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `520`
+		//  Estimated: `6196`
+		// Minimum execution time: 58_946_000 picoseconds.
+		Weight::from_parts(60_118_000, 6196)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(4_u64))
+	}
+	/// Storage: AutomationTime Pools (r:1 w:1)
+	/// Proof Skipped: AutomationTime Pools (max_values: None, max_size: None, mode: Measured)
+	fn set_pool_admin() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `86`
+		//  Estimated: `3551`
+		// Minimum execution time: 15_312_000 picoseconds.
+		Weight::from_parts(15_870_000, 3551)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: AutomationTime TaskDeposits (r:1 w:1)
+	/// Proof Skipped: AutomationTime TaskDeposits (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationTime AccountTasks (r:1 w:0)
+	/// Proof Skipped: AutomationTime AccountTasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn reclaim_deposit() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `128`
+		//  Estimated: `3593`
+		// Minimum execution time: 26_593_000 picoseconds.
+		Weight::from_parts(27_416_000, 3593)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: AutomationTime AccountTasks (r:1 w:0)
+	/// Proof Skipped: AutomationTime AccountTasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationTime TaskCallbacks (r:0 w:1)
+	/// Proof Skipped: AutomationTime TaskCallbacks (max_values: None, max_size: None, mode: Measured)
+	fn set_task_callback() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `97`
+		//  Estimated: `3562`
+		// Minimum execution time: 16_718_000 picoseconds.
+		Weight::from_parts(17_204_000, 3562)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: AutomationTime Shutdown (r:1 w:0)
+	/// Proof Skipped: AutomationTime Shutdown (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: AutomationTime AccountTasks (r:1 w:1)
+	/// Proof Skipped: AutomationTime AccountTasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: ParachainInfo ParachainId (r:1 w:0)
+	/// Proof: ParachainInfo ParachainId (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: AutomationTime LastTimeSlot (r:1 w:0)
+	/// Proof Skipped: AutomationTime LastTimeSlot (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: AutomationTime ScheduledTasksV3 (r:36 w:36)
+	/// Proof Skipped: AutomationTime ScheduledTasksV3 (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationTime TaskCallbacks (r:0 w:1)
+	/// Proof Skipped: AutomationTime TaskCallbacks (max_values: None, max_size: None, mode: Measured)
+	fn claim_refund_for_unexecuted_full() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `316368`
+		//  Estimated: `406458`
+		// Minimum execution time: 1_431_207_000 picoseconds.
+		Weight::from_parts(1_463_518_000, 406458)
+			.saturating_add(T::DbWeight::get().reads(43_u64))
+			.saturating_add(T::DbWeight::get().writes(40_u64))
+	}
 }
 
 // For backwards compatibility and tests
@@ -820,4 +941,111 @@ impl WeightInfo for () {
 		Weight::from_parts(6_175_000, 3681)
 			.saturating_add(RocksDbWeight::get().reads(1_u64))
 	}
+	/// Storage: AutomationTime NextPoolId (r:1 w:1)
+	/// Proof Skipped: AutomationTime NextPoolId (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: AutomationTime Pools (r:0 w:1)
+	/// Proof Skipped: AutomationTime Pools (max_values: None, max_size: None, mode: Measured)
+	fn create_pool() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `4`
+		//  Estimated: `1489`
+		// Minimum execution time: 13_854_000 picoseconds.
+		Weight::from_parts(14_210_000, 1489)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: AutomationTime Pools (r:1 w:1)
+	/// Proof Skipped: AutomationTime Pools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: AutomationTime PoolShares (r:1 w:1)
+	/// Proof Skipped: AutomationTime PoolShares (max_values: None, max_size: None, mode: Measured)
+	fn contribute_to_pool() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `412`
+		//  Estimated: `6196`
+		// Minimum execution time: 57_611_000 picoseconds.
+		Weight::from_parts(58_903_000, 6196)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	/// Storage: AutomationTime Pools (r:1 w:1)
+	/// Proof Skipped: AutomationTime Pools (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationTime PoolShares (r:1 w:1)
+	/// Proof Skipped: AutomationTime PoolShares (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn withdraw_from_pool() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `520`
+		//  Estimated: `6196`
+		// Minimum execution time: 58_946_000 picoseconds.
+		Weight::from_parts(60_118_000, 6196)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(4_u64))
+	}
+	/// Storage: AutomationTime Pools (r:1 w:1)
+	/// Proof Skipped: AutomationTime Pools (max_values: None, max_size: None, mode: Measured)
+	fn set_pool_admin() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `86`
+		//  Estimated: `3551`
+		// Minimum execution time: 15_312_000 picoseconds.
+		Weight::from_parts(15_870_000, 3551)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: AutomationTime TaskDeposits (r:1 w:1)
+	/// Proof Skipped: AutomationTime TaskDeposits (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationTime AccountTasks (r:1 w:0)
+	/// Proof Skipped: AutomationTime AccountTasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	fn reclaim_deposit() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `128`
+		//  Estimated: `3593`
+		// Minimum execution time: 26_593_000 picoseconds.
+		Weight::from_parts(27_416_000, 3593)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: AutomationTime AccountTasks (r:1 w:0)
+	/// Proof Skipped: AutomationTime AccountTasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationTime TaskCallbacks (r:0 w:1)
+	/// Proof Skipped: AutomationTime TaskCallbacks (max_values: None, max_size: None, mode: Measured)
+	fn set_task_callback() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `97`
+		//  Estimated: `3562`
+		// Minimum execution time: 16_718_000 picoseconds.
+		Weight::from_parts(17_204_000, 3562)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: AutomationTime Shutdown (r:1 w:0)
+	/// Proof Skipped: AutomationTime Shutdown (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: AutomationTime AccountTasks (r:1 w:1)
+	/// Proof Skipped: AutomationTime AccountTasks (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:2 w:2)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: ParachainInfo ParachainId (r:1 w:0)
+	/// Proof: ParachainInfo ParachainId (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: AutomationTime LastTimeSlot (r:1 w:0)
+	/// Proof Skipped: AutomationTime LastTimeSlot (max_values: Some(1), max_size: None, mode: Measured)
+	/// Storage: AutomationTime ScheduledTasksV3 (r:36 w:36)
+	/// Proof Skipped: AutomationTime ScheduledTasksV3 (max_values: None, max_size: None, mode: Measured)
+	/// Storage: AutomationTime TaskCallbacks (r:0 w:1)
+	/// Proof Skipped: AutomationTime TaskCallbacks (max_values: None, max_size: None, mode: Measured)
+	fn claim_refund_for_unexecuted_full() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `316368`
+		//  Estimated: `406458`
+		// Minimum execution time: 1_431_207_000 picoseconds.
+		Weight::from_parts(1_463_518_000, 406458)
+			.saturating_add(RocksDbWeight::get().reads(43_u64))
+			.saturating_add(RocksDbWeight::get().writes(40_u64))
+	}
 }
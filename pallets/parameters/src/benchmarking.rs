@@ -0,0 +1,49 @@
+// This file is part of OAK Blockchain.
+
+// Copyright (C) 2022 OAK Network
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::benchmarks;
+use frame_support::traits::EnsureOrigin;
+use sp_runtime::Perbill;
+
+fn assert_last_event<T: Config>(generic_event: <T as Config>::RuntimeEvent) {
+	frame_system::Pallet::<T>::assert_last_event(generic_event.into());
+}
+
+benchmarks! {
+	set_parameter {
+		let origin = T::UpdateOrigin::try_successful_origin()
+			.map_err(|_| "UpdateOrigin has no successful origin")?;
+		let key = ParameterKey::AutomationTimeMaxWeightPercentage;
+		let value = ParameterValue::Perbill(Perbill::from_percent(10));
+	}: _<T::RuntimeOrigin>(origin, key, value)
+	verify {
+		assert_last_event::<T>(Event::ParameterSet { key, value }.into())
+	}
+
+	reset_parameter {
+		let origin = T::UpdateOrigin::try_successful_origin()
+			.map_err(|_| "UpdateOrigin has no successful origin")?;
+		let key = ParameterKey::AutomationTimeMaxWeightPercentage;
+		Parameters::<T>::insert(key, ParameterValue::Perbill(Perbill::from_percent(10)));
+	}: _<T::RuntimeOrigin>(origin, key)
+	verify {
+		assert_last_event::<T>(Event::ParameterReset { key }.into())
+	}
+}
@@ -0,0 +1,211 @@
+// This file is part of OAK Blockchain.
+
+// Copyright (C) 2022 OAK Network
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Parameters pallet
+//!
+//! Stores automation tuning parameters that governance can change without a runtime upgrade.
+//!
+//! The runtime wires a parameter into another pallet's config with `ParameterOr<T, Key, Fallback>`,
+//! which reads the stored value for `Key` and falls back to `Fallback` when none has been set.
+//! Resetting a parameter removes the stored value so the runtime default applies again.
+//! New values must have the type the key expects and fall within the runtime's `Bounds`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+mod benchmarking;
+pub mod weights;
+pub use weights::WeightInfo;
+
+pub use pallet::*;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+	use sp_runtime::Perbill;
+	use sp_std::marker::PhantomData;
+
+	/// The tunable parameters.
+	#[derive(Clone, Copy, Debug, Encode, Decode, Eq, PartialEq, TypeInfo, MaxEncodedLen)]
+	pub enum ParameterKey {
+		AutomationTimeMaxTasksPerSlot,
+		AutomationTimeMaxWeightPercentage,
+		AutomationTimeMaxScheduleSeconds,
+		AutomationPriceMaxTasksPerSlot,
+		AutomationPriceMaxWeightPercentage,
+		SafeModeFailureRateThreshold,
+		SafeModeMinExecutionsPerBlock,
+		SafeModeTripAfterBlocks,
+		InclusionFeeBurnPercentage,
+		ExecutionFeeBurnPercentage,
+	}
+
+	/// The value of a parameter.
+	#[derive(Clone, Copy, Debug, Encode, Decode, Eq, PartialEq, TypeInfo, MaxEncodedLen)]
+	pub enum ParameterValue {
+		U32(u32),
+		U64(u64),
+		Perbill(Perbill),
+	}
+
+	impl ParameterKey {
+		/// Whether `value` has the type expected by this parameter.
+		pub fn accepts(&self, value: &ParameterValue) -> bool {
+			match self {
+				ParameterKey::AutomationTimeMaxTasksPerSlot |
+				ParameterKey::AutomationPriceMaxTasksPerSlot |
+				ParameterKey::SafeModeMinExecutionsPerBlock |
+				ParameterKey::SafeModeTripAfterBlocks => matches!(value, ParameterValue::U32(_)),
+				ParameterKey::AutomationTimeMaxScheduleSeconds =>
+					matches!(value, ParameterValue::U64(_)),
+				ParameterKey::AutomationTimeMaxWeightPercentage |
+				ParameterKey::AutomationPriceMaxWeightPercentage |
+				ParameterKey::SafeModeFailureRateThreshold |
+				ParameterKey::InclusionFeeBurnPercentage |
+				ParameterKey::ExecutionFeeBurnPercentage => matches!(value, ParameterValue::Perbill(_)),
+			}
+		}
+	}
+
+	/// Limits the values governance may set, on top of the type check in `ParameterKey::accepts`.
+	pub trait ParameterBounds {
+		/// Whether `value` lies within the range the runtime allows for `key`.
+		fn within_bounds(key: ParameterKey, value: ParameterValue) -> bool;
+	}
+
+	impl ParameterBounds for () {
+		fn within_bounds(_key: ParameterKey, _value: ParameterValue) -> bool {
+			true
+		}
+	}
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// Weight information for the extrinsics in this module.
+		type WeightInfo: WeightInfo;
+
+		/// The origin that can change parameters.
+		type UpdateOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// The range each parameter may be set within.
+		type Bounds: ParameterBounds;
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(crate) fn deposit_event)]
+	pub enum Event<T> {
+		/// A parameter was set.
+		ParameterSet { key: ParameterKey, value: ParameterValue },
+		/// A parameter was reset to its runtime default.
+		ParameterReset { key: ParameterKey },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The value does not have the type expected by the parameter.
+		InvalidParameterValue,
+		/// The value is outside the range allowed for the parameter.
+		ParameterOutOfBounds,
+	}
+
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(0);
+
+	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
+	pub struct Pallet<T>(_);
+
+	/// The parameters set by governance.
+	#[pallet::storage]
+	#[pallet::getter(fn parameter)]
+	pub type Parameters<T: Config> = StorageMap<_, Twox64Concat, ParameterKey, ParameterValue>;
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Set a parameter, overriding its runtime default.
+		#[pallet::call_index(0)]
+		#[pallet::weight(<T as Config>::WeightInfo::set_parameter())]
+		pub fn set_parameter(
+			origin: OriginFor<T>,
+			key: ParameterKey,
+			value: ParameterValue,
+		) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+
+			ensure!(key.accepts(&value), Error::<T>::InvalidParameterValue);
+			ensure!(T::Bounds::within_bounds(key, value), Error::<T>::ParameterOutOfBounds);
+
+			Parameters::<T>::insert(key, value);
+			Self::deposit_event(Event::ParameterSet { key, value });
+			Ok(())
+		}
+
+		/// Reset a parameter to its runtime default.
+		#[pallet::call_index(1)]
+		#[pallet::weight(<T as Config>::WeightInfo::reset_parameter())]
+		pub fn reset_parameter(origin: OriginFor<T>, key: ParameterKey) -> DispatchResult {
+			T::UpdateOrigin::ensure_origin(origin)?;
+
+			Parameters::<T>::remove(key);
+			Self::deposit_event(Event::ParameterReset { key });
+			Ok(())
+		}
+	}
+
+	/// Reads the parameter `Key`, falling back to `Fallback` when it is not set.
+	pub struct ParameterOr<T, Key, Fallback>(PhantomData<(T, Key, Fallback)>);
+
+	impl<T: Config, Key: Get<ParameterKey>, Fallback: Get<u32>> Get<u32>
+		for ParameterOr<T, Key, Fallback>
+	{
+		fn get() -> u32 {
+			match Parameters::<T>::get(Key::get()) {
+				Some(ParameterValue::U32(value)) => value,
+				_ => Fallback::get(),
+			}
+		}
+	}
+
+	impl<T: Config, Key: Get<ParameterKey>, Fallback: Get<u64>> Get<u64>
+		for ParameterOr<T, Key, Fallback>
+	{
+		fn get() -> u64 {
+			match Parameters::<T>::get(Key::get()) {
+				Some(ParameterValue::U64(value)) => value,
+				_ => Fallback::get(),
+			}
+		}
+	}
+
+	impl<T: Config, Key: Get<ParameterKey>, Fallback: Get<Perbill>> Get<Perbill>
+		for ParameterOr<T, Key, Fallback>
+	{
+		fn get() -> Perbill {
+			match Parameters::<T>::get(Key::get()) {
+				Some(ParameterValue::Perbill(value)) => value,
+				_ => Fallback::get(),
+			}
+		}
+	}
+}
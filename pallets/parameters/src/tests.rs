@@ -0,0 +1,113 @@
+// This file is part of OAK Blockchain.
+
+// Copyright (C) 2022 OAK Network
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+	mock::{events, new_test_ext, Parameters, RuntimeOrigin, Test},
+	Error, Event, ParameterKey, ParameterOr, ParameterValue,
+};
+use frame_support::{assert_noop, assert_ok, parameter_types, traits::Get};
+use sp_runtime::{AccountId32, DispatchError, Perbill};
+
+parameter_types! {
+	pub const MaxTasksPerSlotKey: ParameterKey = ParameterKey::AutomationTimeMaxTasksPerSlot;
+	pub const MaxWeightPercentageKey: ParameterKey = ParameterKey::AutomationTimeMaxWeightPercentage;
+	pub const DefaultMaxTasksPerSlot: u32 = 256;
+	pub const DefaultMaxWeightPercentage: Perbill = Perbill::from_percent(10);
+}
+
+type MaxTasksPerSlot = ParameterOr<Test, MaxTasksPerSlotKey, DefaultMaxTasksPerSlot>;
+type MaxWeightPercentage = ParameterOr<Test, MaxWeightPercentageKey, DefaultMaxWeightPercentage>;
+
+#[test]
+fn falls_back_to_default_until_set() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(<MaxTasksPerSlot as Get<u32>>::get(), 256);
+
+		assert_ok!(Parameters::set_parameter(
+			RuntimeOrigin::root(),
+			ParameterKey::AutomationTimeMaxTasksPerSlot,
+			ParameterValue::U32(64)
+		));
+		assert_eq!(<MaxTasksPerSlot as Get<u32>>::get(), 64);
+		assert_eq!(<MaxWeightPercentage as Get<Perbill>>::get(), Perbill::from_percent(10));
+
+		assert_ok!(Parameters::reset_parameter(
+			RuntimeOrigin::root(),
+			ParameterKey::AutomationTimeMaxTasksPerSlot
+		));
+		assert_eq!(<MaxTasksPerSlot as Get<u32>>::get(), 256);
+
+		assert_eq!(
+			events(),
+			vec![
+				Event::ParameterSet {
+					key: ParameterKey::AutomationTimeMaxTasksPerSlot,
+					value: ParameterValue::U32(64)
+				},
+				Event::ParameterReset { key: ParameterKey::AutomationTimeMaxTasksPerSlot },
+			]
+		);
+	})
+}
+
+#[test]
+fn rejects_values_of_the_wrong_type() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Parameters::set_parameter(
+				RuntimeOrigin::root(),
+				ParameterKey::AutomationTimeMaxWeightPercentage,
+				ParameterValue::U32(50)
+			),
+			Error::<Test>::InvalidParameterValue
+		);
+	})
+}
+
+#[test]
+fn only_update_origin_can_set_parameters() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Parameters::set_parameter(
+				RuntimeOrigin::signed(AccountId32::new([1u8; 32])),
+				ParameterKey::SafeModeTripAfterBlocks,
+				ParameterValue::U32(5)
+			),
+			DispatchError::BadOrigin
+		);
+	})
+}
+
+#[test]
+fn rejects_values_out_of_bounds() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			Parameters::set_parameter(
+				RuntimeOrigin::root(),
+				ParameterKey::InclusionFeeBurnPercentage,
+				ParameterValue::Perbill(Perbill::from_percent(60))
+			),
+			Error::<Test>::ParameterOutOfBounds
+		);
+
+		assert_ok!(Parameters::set_parameter(
+			RuntimeOrigin::root(),
+			ParameterKey::InclusionFeeBurnPercentage,
+			ParameterValue::Perbill(Perbill::from_percent(50))
+		));
+	})
+}
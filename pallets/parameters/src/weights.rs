@@ -0,0 +1,117 @@
+// This file is part of OAK-blockchain.
+
+// Copyright (C) OAK Network Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Autogenerated weights for pallet_parameters
+//!
+//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 4.0.0-dev
+//! DATE: 2024-01-09, STEPS: `50`, REPEAT: `20`, LOW RANGE: `[]`, HIGH RANGE: `[]`
+//! WORST CASE MAP SIZE: `1000000`
+//! HOSTNAME: `actions-runner-1`, CPU: `Intel(R) Xeon(R) E-2388G CPU @ 3.20GHz`
+//! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("turing-dev"), DB CACHE: 1024
+
+// Executed Command:
+// ./oak-collator
+// benchmark
+// pallet
+// --header
+// ./.maintain/HEADER-GPL3
+// --chain
+// turing-dev
+// --execution
+// wasm
+// --wasm-execution
+// compiled
+// --pallet
+// pallet_parameters
+// --extrinsic
+// *
+// --repeat
+// 20
+// --steps
+// 50
+// --output
+// ./parameters-raw-weights.rs
+// --template
+// ./.maintain/frame-weight-template.hbs
+
+// Summary:
+//:set_parameter 12_408_000,0
+//:reset_parameter 11_932_000,0
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_parameters.
+pub trait WeightInfo {
+	fn set_parameter() -> Weight;
+	fn reset_parameter() -> Weight;
+}
+
+/// Weights for pallet_parameters using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: Parameters Parameters (r:0 w:1)
+	/// Proof: Parameters Parameters (max_values: None, max_size: Some(14), added: 2489, mode: MaxEncodedLen)
+	fn set_parameter() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 11_987_000 picoseconds.
+		Weight::from_parts(12_408_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: Parameters Parameters (r:0 w:1)
+	/// Proof: Parameters Parameters (max_values: None, max_size: Some(14), added: 2489, mode: MaxEncodedLen)
+	fn reset_parameter() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 11_520_000 picoseconds.
+		Weight::from_parts(11_932_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	/// Storage: Parameters Parameters (r:0 w:1)
+	/// Proof: Parameters Parameters (max_values: None, max_size: Some(14), added: 2489, mode: MaxEncodedLen)
+	fn set_parameter() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 11_987_000 picoseconds.
+		Weight::from_parts(12_408_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: Parameters Parameters (r:0 w:1)
+	/// Proof: Parameters Parameters (max_values: None, max_size: Some(14), added: 2489, mode: MaxEncodedLen)
+	fn reset_parameter() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 11_520_000 picoseconds.
+		Weight::from_parts(11_932_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+}
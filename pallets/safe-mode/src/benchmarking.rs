@@ -0,0 +1,45 @@
+// This file is part of OAK Blockchain.
+
+// Copyright (C) 2022 OAK Network
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::benchmarks;
+use frame_support::traits::EnsureOrigin;
+
+fn assert_last_event<T: Config>(generic_event: <T as Config>::RuntimeEvent) {
+	frame_system::Pallet::<T>::assert_last_event(generic_event.into());
+}
+
+benchmarks! {
+	enter {
+		let origin = T::ForceOrigin::try_successful_origin()
+			.map_err(|_| "ForceOrigin has no successful origin")?;
+	}: _<T::RuntimeOrigin>(origin)
+	verify {
+		assert_last_event::<T>(Event::Entered.into())
+	}
+
+	exit {
+		let origin = T::ForceOrigin::try_successful_origin()
+			.map_err(|_| "ForceOrigin has no successful origin")?;
+		Entered::<T>::put(true);
+	}: _<T::RuntimeOrigin>(origin)
+	verify {
+		assert_last_event::<T>(Event::Exited.into())
+	}
+}
@@ -0,0 +1,205 @@
+// This file is part of OAK Blockchain.
+
+// Copyright (C) 2022 OAK Network
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Safe mode pallet
+//!
+//! Unlike the valve, safe mode is entered automatically. The automation pallets report every task
+//! execution through `OnTaskExecuted`. At the end of each block the failure rate is compared against
+//! `FailureRateThreshold`; once `TripAfterBlocks` consecutive blocks exceed it, safe mode is entered.
+//! Blocks with fewer than `MinExecutionsPerBlock` executions do not count towards or reset the streak.
+//!
+//! While in safe mode only the calls in `WhitelistedCalls` are allowed. Safe mode stays on until it is
+//! cleared by `ForceOrigin`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+mod benchmarking;
+pub mod weights;
+pub use weights::WeightInfo;
+
+pub use pallet::*;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::{pallet_prelude::*, traits::Contains};
+	use frame_system::pallet_prelude::*;
+	use sp_runtime::Perbill;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// Weight information for the extrinsics in this module.
+		type WeightInfo: WeightInfo;
+
+		/// The calls that are still allowed while in safe mode.
+		type WhitelistedCalls: Contains<Self::RuntimeCall>;
+
+		/// The origin that can enter and exit safe mode.
+		type ForceOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// A block is anomalous when its task failure rate is above this threshold.
+		#[pallet::constant]
+		type FailureRateThreshold: Get<Perbill>;
+
+		/// The minimum number of task executions for a block to be judged.
+		#[pallet::constant]
+		type MinExecutionsPerBlock: Get<u32>;
+
+		/// The number of consecutive anomalous blocks that trips safe mode.
+		#[pallet::constant]
+		type TripAfterBlocks: Get<u32>;
+	}
+
+	/// Task executions reported during the current block.
+	#[derive(
+		Clone, Copy, Debug, Default, Encode, Decode, Eq, PartialEq, TypeInfo, MaxEncodedLen,
+	)]
+	pub struct ExecutionTally {
+		pub executed: u32,
+		pub failed: u32,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(crate) fn deposit_event)]
+	pub enum Event<T> {
+		/// Safe mode was entered by governance.
+		Entered,
+		/// Safe mode was entered after too many consecutive anomalous blocks.
+		Tripped { anomalous_blocks: u32 },
+		/// Safe mode was cleared. The chain returned to its normal operating state.
+		Exited,
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// Safe mode is already on.
+		AlreadyEntered,
+		/// Safe mode is not on.
+		NotEntered,
+	}
+
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(0);
+
+	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
+	pub struct Pallet<T>(_);
+
+	/// Whether safe mode is on.
+	#[pallet::storage]
+	#[pallet::getter(fn is_entered)]
+	pub type Entered<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	/// The executions reported so far in this block. Cleared at the end of every block.
+	#[pallet::storage]
+	#[pallet::getter(fn block_executions)]
+	pub type BlockExecutions<T: Config> = StorageValue<_, ExecutionTally, ValueQuery>;
+
+	/// The number of consecutive anomalous blocks seen.
+	#[pallet::storage]
+	#[pallet::getter(fn anomalous_blocks)]
+	pub type AnomalousBlocks<T: Config> = StorageValue<_, u32, ValueQuery>;
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(_block: T::BlockNumber) -> Weight {
+			// Reserve the weight used by `on_finalize`.
+			T::DbWeight::get().reads_writes(3, 3)
+		}
+
+		fn on_finalize(_block: T::BlockNumber) {
+			let tally = BlockExecutions::<T>::take();
+
+			if Self::is_entered() || tally.executed < T::MinExecutionsPerBlock::get() {
+				return
+			}
+
+			let failure_rate = Perbill::from_rational(tally.failed, tally.executed);
+			if failure_rate <= T::FailureRateThreshold::get() {
+				AnomalousBlocks::<T>::kill();
+				return
+			}
+
+			let anomalous_blocks = AnomalousBlocks::<T>::mutate(|count| {
+				*count = count.saturating_add(1);
+				*count
+			});
+			if anomalous_blocks >= T::TripAfterBlocks::get() {
+				Entered::<T>::put(true);
+				AnomalousBlocks::<T>::kill();
+				Self::deposit_event(Event::Tripped { anomalous_blocks });
+			}
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Enter safe mode.
+		///
+		/// Only the calls in `WhitelistedCalls` will be allowed until safe mode is exited.
+		#[pallet::call_index(0)]
+		#[pallet::weight(<T as Config>::WeightInfo::enter())]
+		pub fn enter(origin: OriginFor<T>) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+
+			ensure!(!Entered::<T>::get(), Error::<T>::AlreadyEntered);
+
+			Entered::<T>::put(true);
+			Self::deposit_event(Event::Entered);
+			Ok(())
+		}
+
+		/// Exit safe mode.
+		///
+		/// This also resets the count of anomalous blocks.
+		#[pallet::call_index(1)]
+		#[pallet::weight(<T as Config>::WeightInfo::exit())]
+		pub fn exit(origin: OriginFor<T>) -> DispatchResult {
+			T::ForceOrigin::ensure_origin(origin)?;
+
+			ensure!(Entered::<T>::get(), Error::<T>::NotEntered);
+
+			Entered::<T>::put(false);
+			AnomalousBlocks::<T>::kill();
+			Self::deposit_event(Event::Exited);
+			Ok(())
+		}
+	}
+
+	impl<T: Config> primitives::OnTaskExecuted for Pallet<T> {
+		fn on_task_executed(succeeded: bool) {
+			BlockExecutions::<T>::mutate(|tally| {
+				tally.executed = tally.executed.saturating_add(1);
+				if !succeeded {
+					tally.failed = tally.failed.saturating_add(1);
+				}
+			});
+		}
+	}
+
+	impl<T: Config> Contains<T::RuntimeCall> for Pallet<T> {
+		fn contains(call: &T::RuntimeCall) -> bool {
+			!Entered::<T>::get() || T::WhitelistedCalls::contains(call)
+		}
+	}
+}
@@ -0,0 +1,98 @@
+// This file is part of OAK Blockchain.
+
+// Copyright (C) 2022 OAK Network
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+	mock::{events, new_test_ext, RuntimeCall as OuterCall, RuntimeOrigin, SafeMode, Test},
+	Call, Error, Event,
+};
+use frame_support::{assert_noop, assert_ok, dispatch::Dispatchable, traits::Hooks};
+use primitives::OnTaskExecuted;
+use sp_runtime::AccountId32;
+
+fn finalize_block_with(executed: u32, failed: u32) {
+	for i in 0..executed {
+		SafeMode::on_task_executed(i >= failed);
+	}
+	SafeMode::on_finalize(1);
+}
+
+#[test]
+fn trips_after_consecutive_anomalous_blocks() {
+	new_test_ext().execute_with(|| {
+		finalize_block_with(4, 3);
+		finalize_block_with(4, 3);
+		assert_eq!(SafeMode::anomalous_blocks(), 2);
+		assert!(!SafeMode::is_entered());
+
+		finalize_block_with(4, 3);
+		assert!(SafeMode::is_entered());
+		assert_eq!(events(), vec![Event::Tripped { anomalous_blocks: 3 }]);
+
+		let call: OuterCall = frame_system::Call::remark { remark: vec![] }.into();
+		assert_noop!(
+			call.dispatch(RuntimeOrigin::signed(AccountId32::new([1u8; 32]))),
+			frame_system::Error::<Test>::CallFiltered
+		);
+	})
+}
+
+#[test]
+fn healthy_block_resets_the_streak() {
+	new_test_ext().execute_with(|| {
+		finalize_block_with(4, 3);
+		finalize_block_with(4, 3);
+		finalize_block_with(4, 1);
+		assert_eq!(SafeMode::anomalous_blocks(), 0);
+
+		finalize_block_with(4, 3);
+		assert!(!SafeMode::is_entered());
+	})
+}
+
+#[test]
+fn sparse_blocks_are_ignored() {
+	new_test_ext().execute_with(|| {
+		finalize_block_with(4, 4);
+		finalize_block_with(2, 2);
+		assert_eq!(SafeMode::anomalous_blocks(), 1);
+		assert_eq!(SafeMode::block_executions().executed, 0);
+	})
+}
+
+#[test]
+fn governance_can_exit_safe_mode() {
+	new_test_ext().execute_with(|| {
+		let call: OuterCall = Call::enter {}.into();
+		assert_ok!(call.dispatch(RuntimeOrigin::root()));
+
+		let call: OuterCall = Call::enter {}.into();
+		assert_noop!(call.dispatch(RuntimeOrigin::root()), Error::<Test>::AlreadyEntered);
+
+		let call: OuterCall = Call::exit {}.into();
+		assert_noop!(
+			call.dispatch(RuntimeOrigin::signed(AccountId32::new([1u8; 32]))),
+			sp_runtime::DispatchError::BadOrigin
+		);
+
+		let call: OuterCall = Call::exit {}.into();
+		assert_ok!(call.dispatch(RuntimeOrigin::root()));
+		assert_eq!(events(), vec![Event::Entered, Event::Exited]);
+
+		let call: OuterCall = frame_system::Call::remark { remark: vec![] }.into();
+		assert_ok!(call.dispatch(RuntimeOrigin::signed(AccountId32::new([1u8; 32]))));
+	})
+}
@@ -0,0 +1,125 @@
+// This file is part of OAK-blockchain.
+
+// Copyright (C) OAK Network Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Autogenerated weights for pallet_safe_mode
+//!
+//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 4.0.0-dev
+//! DATE: 2024-01-09, STEPS: `50`, REPEAT: `20`, LOW RANGE: `[]`, HIGH RANGE: `[]`
+//! WORST CASE MAP SIZE: `1000000`
+//! HOSTNAME: `actions-runner-1`, CPU: `Intel(R) Xeon(R) E-2388G CPU @ 3.20GHz`
+//! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("turing-dev"), DB CACHE: 1024
+
+// Executed Command:
+// ./oak-collator
+// benchmark
+// pallet
+// --header
+// ./.maintain/HEADER-GPL3
+// --chain
+// turing-dev
+// --execution
+// wasm
+// --wasm-execution
+// compiled
+// --pallet
+// pallet_safe_mode
+// --extrinsic
+// *
+// --repeat
+// 20
+// --steps
+// 50
+// --output
+// ./safe_mode-raw-weights.rs
+// --template
+// ./.maintain/frame-weight-template.hbs
+
+// Summary:
+//:enter 14_872_000,1489
+//:exit 15_963_000,1489
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_safe_mode.
+pub trait WeightInfo {
+	fn enter() -> Weight;
+	fn exit() -> Weight;
+}
+
+/// Weights for pallet_safe_mode using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: SafeMode Entered (r:1 w:1)
+	/// Proof: SafeMode Entered (max_values: Some(1), max_size: Some(1), added: 496, mode: MaxEncodedLen)
+	fn enter() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `4`
+		//  Estimated: `1489`
+		// Minimum execution time: 14_398_000 picoseconds.
+		Weight::from_parts(14_872_000, 1489)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: SafeMode Entered (r:1 w:1)
+	/// Proof: SafeMode Entered (max_values: Some(1), max_size: Some(1), added: 496, mode: MaxEncodedLen)
+	/// Storage: SafeMode AnomalousBlocks (r:0 w:1)
+	/// Proof: SafeMode AnomalousBlocks (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	fn exit() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `4`
+		//  Estimated: `1489`
+		// Minimum execution time: 15_441_000 picoseconds.
+		Weight::from_parts(15_963_000, 1489)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	/// Storage: SafeMode Entered (r:1 w:1)
+	/// Proof: SafeMode Entered (max_values: Some(1), max_size: Some(1), added: 496, mode: MaxEncodedLen)
+	fn enter() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `4`
+		//  Estimated: `1489`
+		// Minimum execution time: 14_398_000 picoseconds.
+		Weight::from_parts(14_872_000, 1489)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: SafeMode Entered (r:1 w:1)
+	/// Proof: SafeMode Entered (max_values: Some(1), max_size: Some(1), added: 496, mode: MaxEncodedLen)
+	/// Storage: SafeMode AnomalousBlocks (r:0 w:1)
+	/// Proof: SafeMode AnomalousBlocks (max_values: Some(1), max_size: Some(4), added: 499, mode: MaxEncodedLen)
+	fn exit() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `4`
+		//  Estimated: `1489`
+		// Minimum execution time: 15_441_000 picoseconds.
+		Weight::from_parts(15_963_000, 1489)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+}
@@ -22,6 +22,8 @@
 //! Open Valve -> Resume normal chain operations. This includes allowing all non-critical pallets to receive transactions but not opening pallet gates.
 //! Open Pallet Gate -> Allow the pallet to start receiving transactions again.
 //! Open Pallet Gates -> Open the pallet gates. To ensure this call is safe it will only open five gates at once and fire an event with how many gates are still closed.
+//!
+//! Batching calls exposed through `Config::BatchedCalls` are only allowed if every inner call is allowed.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
@@ -68,6 +70,9 @@ pub mod pallet {
 
 		/// The filter for who can call this pallet's extrinsics besides sudo.
 		type CallAccessFilter: SortedMembers<<Self as frame_system::Config>::AccountId>;
+
+		/// Unwraps batching calls so that each inner call is checked against the filters.
+		type BatchedCalls: BatchedCalls<Self::RuntimeCall>;
 	}
 
 	#[pallet::event]
@@ -339,6 +344,12 @@ pub mod pallet {
 		<T as frame_system::Config>::RuntimeCall: GetCallMetadata,
 	{
 		fn contains(call: &T::RuntimeCall) -> bool {
+			if let Some(calls) = T::BatchedCalls::batched_calls(call) {
+				if !calls.iter().all(Self::contains) {
+					return false
+				}
+			}
+
 			if ValveClosed::<T>::get() {
 				T::ClosedCallFilter::contains(call)
 			} else {
@@ -48,6 +48,7 @@ construct_runtime!(
 	{
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
 		Valve: pallet_valve::{Pallet, Call, Storage, Event<T>, Config},
+		Utility: pallet_utility::{Pallet, Call, Event},
 	}
 );
 
@@ -82,6 +83,13 @@ impl frame_system::Config for Test {
 	type MaxConsumers = frame_support::traits::ConstU32<16>;
 }
 
+impl pallet_utility::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	type PalletsOrigin = OriginCaller;
+	type WeightInfo = ();
+}
+
 /// During maintenance mode we will not allow any calls.
 pub struct ClosedCallFilter;
 impl Contains<RuntimeCall> for ClosedCallFilter {
@@ -146,6 +154,18 @@ impl Shutdown for MockAutomationPrice {
 	}
 }
 
+pub struct UtilityBatchedCalls;
+impl BatchedCalls<RuntimeCall> for UtilityBatchedCalls {
+	fn batched_calls(call: &RuntimeCall) -> Option<&[RuntimeCall]> {
+		match call {
+			RuntimeCall::Utility(pallet_utility::Call::batch { calls }) |
+			RuntimeCall::Utility(pallet_utility::Call::batch_all { calls }) |
+			RuntimeCall::Utility(pallet_utility::Call::force_batch { calls }) => Some(calls.as_slice()),
+			_ => None,
+		}
+	}
+}
+
 impl Config for Test {
 	type RuntimeEvent = RuntimeEvent;
 	type WeightInfo = ();
@@ -153,6 +173,7 @@ impl Config for Test {
 	type AutomationTime = MockAutomationTime;
 	type AutomationPrice = MockAutomationPrice;
 	type CallAccessFilter = TechCollective;
+	type BatchedCalls = UtilityBatchedCalls;
 }
 
 /// Externality builder for pallet maintenance mode's mock runtime
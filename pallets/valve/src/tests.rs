@@ -112,6 +112,45 @@ fn can_start_with_pallet_gate_closed() {
 		})
 }
 
+#[test]
+fn cannot_batch_call_to_closed_pallet_gate() {
+	ExtBuilder::default()
+		.with_gate_closed(b"System".to_vec())
+		.build()
+		.execute_with(|| {
+			let remark: OuterCall = frame_system::Call::remark { remark: vec![] }.into();
+			let batches: Vec<OuterCall> = vec![
+				pallet_utility::Call::batch { calls: vec![remark.clone()] }.into(),
+				pallet_utility::Call::batch_all { calls: vec![remark.clone()] }.into(),
+				pallet_utility::Call::force_batch { calls: vec![remark.clone()] }.into(),
+				pallet_utility::Call::batch {
+					calls: vec![pallet_utility::Call::batch { calls: vec![remark] }.into()],
+				}
+				.into(),
+			];
+
+			for call in batches {
+				assert_noop!(
+					call.dispatch(RuntimeOrigin::signed(AccountId32::new([1u8; 32]))),
+					frame_system::Error::<Test>::CallFiltered
+				);
+			}
+		})
+}
+
+#[test]
+fn can_batch_calls_to_open_pallet_gates() {
+	ExtBuilder::default()
+		.with_gate_closed(b"Balances".to_vec())
+		.build()
+		.execute_with(|| {
+			let remark: OuterCall = frame_system::Call::remark { remark: vec![] }.into();
+			let call: OuterCall =
+				pallet_utility::Call::batch_all { calls: vec![remark.clone(), remark] }.into();
+			assert_ok!(call.dispatch(RuntimeOrigin::signed(AccountId32::new([1u8; 32]))));
+		})
+}
+
 #[test]
 fn can_open_valve() {
 	ExtBuilder::default().with_valve_closed(true).build().execute_with(|| {
@@ -15,3 +15,15 @@ impl Shutdown for () {
 	fn shutdown() {}
 	fn restart() {}
 }
+
+/// For calls that wrap other calls, e.g. `utility.batch`, so each inner call can be filtered
+pub trait BatchedCalls<Call> {
+	/// The calls wrapped by `call`, or `None` if it does not wrap any
+	fn batched_calls(call: &Call) -> Option<&[Call]>;
+}
+
+impl<Call> BatchedCalls<Call> for () {
+	fn batched_calls(_: &Call) -> Option<&[Call]> {
+		None
+	}
+}
@@ -15,8 +15,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 use super::*;
-use frame_benchmarking::{account, benchmarks};
+use frame_benchmarking::{account, benchmarks, whitelisted_caller};
 use frame_support::traits::Currency;
+use frame_system::RawOrigin;
 use sp_runtime::traits::{SaturatedConversion, Saturating};
 use sp_std::{vec, vec::Vec};
 
@@ -42,4 +43,27 @@ benchmarks! {
 		let pallet_time: u32 = (FIRST_VEST_TIME * 1_000).saturated_into::<u32>();
 		<pallet_timestamp::Pallet<T>>::set_timestamp(pallet_time.into());
 	}: { Vesting::<T>::vest() }
+
+	merge_schedules {
+		let caller: T::AccountId = whitelisted_caller();
+		let amount = T::Currency::minimum_balance().saturating_mul(ED_MULTIPLIER.into());
+		let from = FIRST_VEST_TIME * 2;
+		let into = FIRST_VEST_TIME * 3;
+		VestingSchedule::<T>::insert(from, vec![(caller.clone(), amount)]);
+		VestingSchedule::<T>::insert(into, vec![(caller.clone(), amount)]);
+
+		let pallet_time: u32 = (FIRST_VEST_TIME * 1_000).saturated_into::<u32>();
+		<pallet_timestamp::Pallet<T>>::set_timestamp(pallet_time.into());
+	}: _(RawOrigin::Signed(caller), from, into)
+
+	claim {
+		let caller: T::AccountId = whitelisted_caller();
+		let amount = T::Currency::minimum_balance().saturating_mul(ED_MULTIPLIER.into());
+		VestingSchedule::<T>::insert(FIRST_VEST_TIME, vec![(caller.clone(), amount)]);
+		TotalUnvestedAllocation::<T>::set(amount);
+
+		let pallet_time: u32 = (FIRST_VEST_TIME * 2 * 1_000).saturated_into::<u32>();
+		<pallet_timestamp::Pallet<T>>::set_timestamp(pallet_time.into());
+		let claim_amount = T::Currency::minimum_balance().saturating_mul(2u32.into());
+	}: _(RawOrigin::Signed(caller), FIRST_VEST_TIME, claim_amount)
 }
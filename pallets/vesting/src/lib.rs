@@ -74,12 +74,29 @@ pub mod pallet {
 			amount: BalanceOf<T>,
 			error: DispatchError,
 		},
+		/// An account moved its allocation from one vesting slot into a later one.
+		SchedulesMerged {
+			account: AccountOf<T>,
+			from: UnixTime,
+			into: UnixTime,
+			amount: BalanceOf<T>,
+		},
 	}
 
 	#[pallet::error]
 	pub enum Error<T> {
 		/// Block time not set.
 		BlockTimeNotSet,
+		/// The account has no allocation in the given vesting slot.
+		NoVestingSchedule,
+		/// Schedules can only be merged into a later slot.
+		InvalidMergeTarget,
+		/// The vesting slot has already been reached.
+		ScheduleAlreadyDue,
+		/// The vesting slot has not been reached yet.
+		ScheduleNotDue,
+		/// The claim amount must be non-zero and no more than the allocation in the slot.
+		InvalidClaimAmount,
 	}
 
 	const STORAGE_VERSION: StorageVersion = StorageVersion::new(0);
@@ -107,6 +124,110 @@ pub mod pallet {
 		}
 	}
 
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Merge the caller's allocation in one vesting slot into a later slot.
+		///
+		/// Both slots must still be in the future, so a merge can only delay vesting.
+		/// If the caller already has an allocation in `into` the amounts are summed.
+		///
+		/// # Parameters
+		/// * `from`: The slot to move the allocation out of.
+		/// * `into`: The later slot to move the allocation into.
+		///
+		/// # Errors
+		/// * `InvalidMergeTarget`: `into` is not later than `from`.
+		/// * `ScheduleAlreadyDue`: `from` is not in the future.
+		/// * `NoVestingSchedule`: The caller has no allocation in `from`.
+		#[pallet::call_index(0)]
+		#[pallet::weight(<T as Config>::WeightInfo::merge_schedules())]
+		pub fn merge_schedules(
+			origin: OriginFor<T>,
+			from: UnixTime,
+			into: UnixTime,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(into > from, Error::<T>::InvalidMergeTarget);
+			let current_time = Self::get_current_time_slot()?;
+			ensure!(from > current_time, Error::<T>::ScheduleAlreadyDue);
+
+			let mut from_schedule =
+				Self::get_scheduled_vest(from).ok_or(Error::<T>::NoVestingSchedule)?;
+			let mut amount: BalanceOf<T> = Zero::zero();
+			from_schedule.retain(|(account, vest_amount)| {
+				if *account == who {
+					amount = amount.saturating_add(*vest_amount);
+					false
+				} else {
+					true
+				}
+			});
+			ensure!(!amount.is_zero(), Error::<T>::NoVestingSchedule);
+
+			let mut into_schedule = Self::get_scheduled_vest(into).unwrap_or_default();
+			match into_schedule.iter_mut().find(|(account, _)| *account == who) {
+				Some((_, vest_amount)) => *vest_amount = vest_amount.saturating_add(amount),
+				None => into_schedule.push((who.clone(), amount)),
+			}
+
+			if from_schedule.is_empty() {
+				VestingSchedule::<T>::remove(from);
+			} else {
+				VestingSchedule::<T>::insert(from, from_schedule);
+			}
+			VestingSchedule::<T>::insert(into, into_schedule);
+
+			Self::deposit_event(Event::SchedulesMerged { account: who, from, into, amount });
+			Ok(())
+		}
+
+		/// Claim part of the caller's allocation in a vesting slot that has been reached
+		/// but was not minted, e.g. because no block was produced during that hour.
+		///
+		/// # Parameters
+		/// * `time`: The vesting slot to claim from.
+		/// * `amount`: The amount to mint. Whatever remains can be claimed in a later call.
+		///
+		/// # Errors
+		/// * `ScheduleNotDue`: `time` is still in the future.
+		/// * `NoVestingSchedule`: The caller has no allocation in `time`.
+		/// * `InvalidClaimAmount`: `amount` is zero or greater than the allocation.
+		#[pallet::call_index(1)]
+		#[pallet::weight(<T as Config>::WeightInfo::claim())]
+		pub fn claim(origin: OriginFor<T>, time: UnixTime, amount: BalanceOf<T>) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let current_time = Self::get_current_time_slot()?;
+			ensure!(time <= current_time, Error::<T>::ScheduleNotDue);
+
+			let mut schedule =
+				Self::get_scheduled_vest(time).ok_or(Error::<T>::NoVestingSchedule)?;
+			let index = schedule
+				.iter()
+				.position(|(account, _)| *account == who)
+				.ok_or(Error::<T>::NoVestingSchedule)?;
+			let remaining = schedule[index].1;
+			ensure!(!amount.is_zero() && amount <= remaining, Error::<T>::InvalidClaimAmount);
+
+			if amount == remaining {
+				schedule.remove(index);
+			} else {
+				schedule[index].1 = remaining.saturating_sub(amount);
+			}
+			if schedule.is_empty() {
+				VestingSchedule::<T>::remove(time);
+			} else {
+				VestingSchedule::<T>::insert(time, schedule);
+			}
+
+			<T as Config>::Currency::deposit_creating(&who, amount);
+			let unvested_funds = Self::total_unvested_allocation();
+			TotalUnvestedAllocation::<T>::set(unvested_funds.saturating_sub(amount));
+
+			Self::deposit_event(Event::Vested { account: who, amount });
+			Ok(())
+		}
+	}
+
 	impl<T: Config> Pallet<T> {
 		/// Based on the block time, return the time slot.
 		///
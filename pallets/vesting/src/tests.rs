@@ -15,8 +15,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::{mock::*, Event};
-use frame_support::traits::OnInitialize;
+use crate::{mock::*, Error, Event};
+use frame_support::{assert_noop, assert_ok, traits::OnInitialize};
 use pallet_parachain_staking::AdditionalIssuance;
 
 const FIRST_VEST_TIME: u64 = 1646028000;
@@ -132,6 +132,140 @@ fn additional_issuance() {
 	})
 }
 
+#[test]
+fn merge_schedules_moves_allocation_to_later_slot() {
+	ExtBuilder::default().schedule(get_schedule()).build().execute_with(|| {
+		Timestamp::set_timestamp((FIRST_VEST_TIME - 3_600) * 1_000);
+
+		assert_ok!(Vesting::merge_schedules(
+			RuntimeOrigin::signed(ALICE),
+			FIRST_VEST_TIME,
+			SECOND_VEST_TIME
+		));
+
+		assert_eq!(Vesting::get_scheduled_vest(FIRST_VEST_TIME), Some(vec![(BOB, 100)]));
+		assert_eq!(
+			Vesting::get_scheduled_vest(SECOND_VEST_TIME),
+			Some(vec![(ALICE, 300), (BOB, 200)])
+		);
+		assert_eq!(Vesting::total_unvested_allocation(), 600);
+		assert_eq!(
+			events(),
+			vec![Event::SchedulesMerged {
+				account: ALICE,
+				from: FIRST_VEST_TIME,
+				into: SECOND_VEST_TIME,
+				amount: 100,
+			}]
+		);
+	})
+}
+
+#[test]
+fn merge_schedules_removes_empty_slot() {
+	ExtBuilder::default().schedule(get_schedule()).build().execute_with(|| {
+		Timestamp::set_timestamp((FIRST_VEST_TIME - 3_600) * 1_000);
+		let third_vest_time = SECOND_VEST_TIME + 3_600;
+
+		assert_ok!(Vesting::merge_schedules(
+			RuntimeOrigin::signed(ALICE),
+			FIRST_VEST_TIME,
+			third_vest_time
+		));
+		assert_ok!(Vesting::merge_schedules(
+			RuntimeOrigin::signed(BOB),
+			FIRST_VEST_TIME,
+			third_vest_time
+		));
+
+		assert_eq!(Vesting::get_scheduled_vest(FIRST_VEST_TIME), None);
+		assert_eq!(
+			Vesting::get_scheduled_vest(third_vest_time),
+			Some(vec![(ALICE, 100), (BOB, 100)])
+		);
+	})
+}
+
+#[test]
+fn merge_schedules_fails_for_invalid_slots() {
+	ExtBuilder::default().schedule(get_schedule()).build().execute_with(|| {
+		Timestamp::set_timestamp(FIRST_VEST_TIME * 1_000);
+
+		assert_noop!(
+			Vesting::merge_schedules(
+				RuntimeOrigin::signed(ALICE),
+				SECOND_VEST_TIME,
+				FIRST_VEST_TIME
+			),
+			Error::<Test>::InvalidMergeTarget
+		);
+		assert_noop!(
+			Vesting::merge_schedules(
+				RuntimeOrigin::signed(ALICE),
+				FIRST_VEST_TIME,
+				SECOND_VEST_TIME
+			),
+			Error::<Test>::ScheduleAlreadyDue
+		);
+		assert_noop!(
+			Vesting::merge_schedules(
+				RuntimeOrigin::signed(3),
+				SECOND_VEST_TIME,
+				SECOND_VEST_TIME + 3_600
+			),
+			Error::<Test>::NoVestingSchedule
+		);
+	})
+}
+
+#[test]
+fn claim_missed_slot_partially() {
+	ExtBuilder::default().schedule(get_schedule()).build().execute_with(|| {
+		// The first slot was skipped without a block being produced in it.
+		Timestamp::set_timestamp(SECOND_VEST_TIME * 1_000);
+
+		assert_ok!(Vesting::claim(RuntimeOrigin::signed(ALICE), FIRST_VEST_TIME, 40));
+		assert_eq!(Balances::free_balance(ALICE), 40);
+		assert_eq!(
+			Vesting::get_scheduled_vest(FIRST_VEST_TIME),
+			Some(vec![(ALICE, 60), (BOB, 100)])
+		);
+		assert_eq!(Vesting::total_unvested_allocation(), 560);
+		assert_eq!(events(), vec![Event::Vested { account: ALICE, amount: 40 }]);
+
+		assert_ok!(Vesting::claim(RuntimeOrigin::signed(ALICE), FIRST_VEST_TIME, 60));
+		assert_ok!(Vesting::claim(RuntimeOrigin::signed(BOB), FIRST_VEST_TIME, 100));
+		assert_eq!(Balances::free_balance(ALICE), 100);
+		assert_eq!(Balances::free_balance(BOB), 100);
+		assert_eq!(Vesting::get_scheduled_vest(FIRST_VEST_TIME), None);
+		assert_eq!(Vesting::total_unvested_allocation(), 400);
+	})
+}
+
+#[test]
+fn claim_fails_for_invalid_requests() {
+	ExtBuilder::default().schedule(get_schedule()).build().execute_with(|| {
+		Timestamp::set_timestamp(FIRST_VEST_TIME * 1_000);
+
+		assert_noop!(
+			Vesting::claim(RuntimeOrigin::signed(ALICE), SECOND_VEST_TIME, 100),
+			Error::<Test>::ScheduleNotDue
+		);
+		assert_noop!(
+			Vesting::claim(RuntimeOrigin::signed(3), FIRST_VEST_TIME, 100),
+			Error::<Test>::NoVestingSchedule
+		);
+		assert_noop!(
+			Vesting::claim(RuntimeOrigin::signed(ALICE), FIRST_VEST_TIME, 0),
+			Error::<Test>::InvalidClaimAmount
+		);
+		assert_noop!(
+			Vesting::claim(RuntimeOrigin::signed(ALICE), FIRST_VEST_TIME, 101),
+			Error::<Test>::InvalidClaimAmount
+		);
+	})
+}
+
 fn get_schedule() -> Vec<(u64, Vec<(AccountId, Balance)>)> {
 	let first_vest = vec![(ALICE, 100), (BOB, 100)];
 	let second_vest = vec![(ALICE, 200), (BOB, 200)];
@@ -51,6 +51,8 @@
 
 // Summary:
 //:vest 14_476_016,3735
+//:merge_schedules 21_766_000,7312
+//:claim 39_230_000,4818
 
 #![cfg_attr(rustfmt, rustfmt_skip)]
 #![allow(unused_parens)]
@@ -63,6 +65,8 @@ use core::marker::PhantomData;
 /// Weight functions needed for pallet_vesting.
 pub trait WeightInfo {
 	fn vest(v: u32, ) -> Weight;
+	fn merge_schedules() -> Weight;
+	fn claim() -> Weight;
 }
 
 /// Weights for pallet_vesting using the Substrate node and recommended hardware.
@@ -91,6 +95,36 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(v.into())))
 			.saturating_add(Weight::from_parts(0, 2603).saturating_mul(v.into()))
 	}
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Vesting VestingSchedule (r:2 w:2)
+	/// Proof Skipped: Vesting VestingSchedule (max_values: None, max_size: None, mode: Measured)
+	fn merge_schedules() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1372`
+		//  Estimated: `7312`
+		// Minimum execution time: 21_104_000 picoseconds.
+		Weight::from_parts(21_766_000, 7312)
+			.saturating_add(T::DbWeight::get().reads(3_u64))
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Vesting VestingSchedule (r:1 w:1)
+	/// Proof Skipped: Vesting VestingSchedule (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Vesting TotalUnvestedAllocation (r:1 w:1)
+	/// Proof Skipped: Vesting TotalUnvestedAllocation (max_values: Some(1), max_size: None, mode: Measured)
+	fn claim() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1353`
+		//  Estimated: `4818`
+		// Minimum execution time: 38_412_000 picoseconds.
+		Weight::from_parts(39_230_000, 4818)
+			.saturating_add(T::DbWeight::get().reads(4_u64))
+			.saturating_add(T::DbWeight::get().writes(3_u64))
+	}
 }
 
 // For backwards compatibility and tests
@@ -118,4 +152,34 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().writes((1_u64).saturating_mul(v.into())))
 			.saturating_add(Weight::from_parts(0, 2603).saturating_mul(v.into()))
 	}
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Vesting VestingSchedule (r:2 w:2)
+	/// Proof Skipped: Vesting VestingSchedule (max_values: None, max_size: None, mode: Measured)
+	fn merge_schedules() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1372`
+		//  Estimated: `7312`
+		// Minimum execution time: 21_104_000 picoseconds.
+		Weight::from_parts(21_766_000, 7312)
+			.saturating_add(RocksDbWeight::get().reads(3_u64))
+			.saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: Timestamp Now (r:1 w:0)
+	/// Proof: Timestamp Now (max_values: Some(1), max_size: Some(8), added: 503, mode: MaxEncodedLen)
+	/// Storage: Vesting VestingSchedule (r:1 w:1)
+	/// Proof Skipped: Vesting VestingSchedule (max_values: None, max_size: None, mode: Measured)
+	/// Storage: System Account (r:1 w:1)
+	/// Proof: System Account (max_values: None, max_size: Some(128), added: 2603, mode: MaxEncodedLen)
+	/// Storage: Vesting TotalUnvestedAllocation (r:1 w:1)
+	/// Proof Skipped: Vesting TotalUnvestedAllocation (max_values: Some(1), max_size: None, mode: Measured)
+	fn claim() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `1353`
+		//  Estimated: `4818`
+		// Minimum execution time: 38_412_000 picoseconds.
+		Weight::from_parts(39_230_000, 4818)
+			.saturating_add(RocksDbWeight::get().reads(4_u64))
+			.saturating_add(RocksDbWeight::get().writes(3_u64))
+	}
 }
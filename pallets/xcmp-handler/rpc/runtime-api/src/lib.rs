@@ -20,11 +20,13 @@
 use codec::Codec;
 use sp_runtime::AccountId32;
 use sp_std::vec::Vec;
+use xcm::VersionedMultiLocation;
 
 sp_api::decl_runtime_apis! {
 	pub trait XcmpHandlerApi<Balance> where
 		Balance: Codec,
 	{
 		fn cross_chain_account(account_id: AccountId32) -> Result<AccountId32, Vec<u8>>;
+		fn derive_remote_account(account_id: AccountId32, destination: VersionedMultiLocation) -> Result<AccountId32, Vec<u8>>;
 	}
 }
@@ -0,0 +1,68 @@
+// This file is part of OAK Blockchain.
+
+// Copyright (C) 2022 OAK Network
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::benchmarks;
+use frame_support::traits::EnsureOrigin;
+use sp_std::boxed::Box;
+
+fn assert_last_event<T: Config>(generic_event: <T as Config>::RuntimeEvent) {
+	frame_system::Pallet::<T>::assert_last_event(generic_event.into());
+}
+
+fn corridor() -> (MultiLocation, MultiLocation) {
+	let asset = MultiLocation::new(1, X2(Parachain(1000), GeneralIndex(1984)));
+	let chain = MultiLocation::new(1, X1(Parachain(1000)));
+	(asset, chain)
+}
+
+benchmarks! {
+	open_corridor {
+		let origin = T::CorridorOrigin::try_successful_origin()
+			.map_err(|_| "CorridorOrigin has no successful origin")?;
+		let (asset, chain) = corridor();
+	}: _<T::RuntimeOrigin>(origin, CorridorKind::Reserve, Box::new(asset.into()), Box::new(chain.into()))
+	verify {
+		assert_last_event::<T>(Event::CorridorOpened { kind: CorridorKind::Reserve, asset, chain }.into())
+	}
+
+	close_corridor {
+		let origin = T::CorridorOrigin::try_successful_origin()
+			.map_err(|_| "CorridorOrigin has no successful origin")?;
+		let (asset, chain) = corridor();
+		Corridors::<T>::insert((CorridorKind::Reserve, asset, chain), ());
+	}: _<T::RuntimeOrigin>(origin, CorridorKind::Reserve, Box::new(asset.into()), Box::new(chain.into()))
+	verify {
+		assert_last_event::<T>(Event::CorridorClosed { kind: CorridorKind::Reserve, asset, chain }.into())
+	}
+
+	set_weight_bounds {
+		let origin = T::CorridorOrigin::try_successful_origin()
+			.map_err(|_| "CorridorOrigin has no successful origin")?;
+		let (_, destination) = corridor();
+		let bounds = XcmWeightBounds {
+			instruction_weight: Weight::from_parts(1_000_000, 0),
+			weight_per_byte: Weight::from_parts(1_000, 0),
+			max_call_weight: Weight::from_parts(1_000_000_000, 64 * 1024),
+		};
+	}: _<T::RuntimeOrigin>(origin, Box::new(destination.into()), Some(bounds))
+	verify {
+		assert_last_event::<T>(Event::TransactInfoChanged { destination }.into())
+	}
+}
@@ -23,6 +23,9 @@
 //!
 //! At this moment we only support using our native currency. We are looking into supporting
 //! other chain's native currency and then any currency.
+//!
+//! It also stores the teleport and reserve transfer corridors opened by governance, which the
+//! runtime's XCM configuration consults through [`TeleportCorridors`] and [`ReserveCorridors`].
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
@@ -39,13 +42,23 @@ mod tests;
 
 pub mod migrations;
 
+mod benchmarking;
+pub mod weights;
+pub use weights::WeightInfo;
+
 use cumulus_primitives_core::ParaId;
-use frame_support::pallet_prelude::*;
+use frame_support::{
+	dispatch::{DispatchErrorWithPostInfo, DispatchResultWithPostInfo, WithPostDispatchInfo},
+	pallet_prelude::*,
+	traits::{Contains, ContainsPair},
+};
+use sp_std::{marker::PhantomData, vec::Vec};
 use xcm::latest::prelude::*;
 
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
+	use frame_system::pallet_prelude::*;
 	use orml_traits::{location::Reserve, MultiCurrency};
 	use polkadot_parachain::primitives::Sibling;
 	use sp_runtime::{
@@ -53,6 +66,7 @@ pub mod pallet {
 		TokenError::BelowMinimum,
 	};
 	use sp_std::prelude::*;
+	use xcm::VersionedMultiLocation;
 	use xcm_executor::traits::WeightBounds;
 
 	pub type MultiCurrencyId<T> = <<T as Config>::MultiCurrency as MultiCurrency<
@@ -111,6 +125,12 @@ pub mod pallet {
 		/// Self chain location.
 		#[pallet::constant]
 		type SelfLocation: Get<MultiLocation>;
+
+		/// The origin that can open and close asset transfer corridors.
+		type CorridorOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// Weight information for the extrinsics in this module.
+		type WeightInfo: WeightInfo;
 	}
 
 	const STORAGE_VERSION: StorageVersion = StorageVersion::new(0);
@@ -120,32 +140,46 @@ pub mod pallet {
 	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
+	/// Asset transfer corridors opened by governance, keyed by kind, the asset as seen from this
+	/// chain, and the chain on the other side of the corridor.
+	#[pallet::storage]
+	pub type Corridors<T: Config> = StorageNMap<
+		_,
+		(
+			NMapKey<Twox64Concat, CorridorKind>,
+			NMapKey<Blake2_128Concat, MultiLocation>,
+			NMapKey<Blake2_128Concat, MultiLocation>,
+		),
+		(),
+	>;
+
+	/// Bounds on the weight each destination chain charges for XCM, registered by governance.
+	/// Tasks sending XCM to a registered destination must declare weights within them.
+	#[pallet::storage]
+	pub type DestinationWeightBounds<T: Config> =
+		StorageMap<_, Blake2_128Concat, MultiLocation, XcmWeightBounds>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(crate) fn deposit_event)]
 	pub enum Event<T: Config> {
 		/// XCM sent to target chain.
-		XcmSent {
-			destination: MultiLocation,
-		},
+		XcmSent { destination: MultiLocation },
 		/// XCM transacted in local chain.
 		XcmTransactedLocally,
 		/// XCM fees successfully paid.
-		XcmFeesPaid {
-			source: T::AccountId,
-			dest: T::AccountId,
-		},
+		XcmFeesPaid { source: T::AccountId, dest: T::AccountId },
+		/// XCM fees of a transact that will not be sent were returned.
+		XcmFeesRefunded { source: T::AccountId, dest: T::AccountId },
 		/// XCM fees failed to transfer.
-		XcmFeesFailed {
-			source: T::AccountId,
-			dest: T::AccountId,
-			error: DispatchError,
-		},
-		TransactInfoChanged {
-			destination: MultiLocation,
-		},
-		TransactInfoRemoved {
-			destination: MultiLocation,
-		},
+		XcmFeesFailed { source: T::AccountId, dest: T::AccountId, error: DispatchError },
+		/// The weight bounds of a destination were set.
+		TransactInfoChanged { destination: MultiLocation },
+		/// The weight bounds of a destination were cleared.
+		TransactInfoRemoved { destination: MultiLocation },
+		/// An asset transfer corridor was opened.
+		CorridorOpened { kind: CorridorKind, asset: MultiLocation, chain: MultiLocation },
+		/// An asset transfer corridor was closed.
+		CorridorClosed { kind: CorridorKind, asset: MultiLocation, chain: MultiLocation },
 	}
 
 	#[pallet::error]
@@ -173,10 +207,109 @@ pub mod pallet {
 		InvalidAssetLocation,
 		// The fee payment asset location is not supported.
 		UnsupportedFeePayment,
+		/// The target chain location cannot be converted into an account.
+		CannotDeriveRemoteAccount,
+		/// The corridor is already open.
+		CorridorAlreadyOpen,
+		/// The corridor is not open.
+		CorridorNotOpen,
+		/// The declared call weight is below the cost of decoding the call or above the largest
+		/// call weight of the destination.
+		CallWeightImplausible,
+		/// The declared overall weight does not cover the call and the XCM instructions executed
+		/// on the destination.
+		OverallWeightImplausible,
 	}
 
 	#[pallet::call]
-	impl<T: Config> Pallet<T> {}
+	impl<T: Config> Pallet<T> {
+		/// Open a corridor allowing `asset` to be teleported or reserve transferred between this
+		/// chain and `chain`.
+		///
+		/// # Parameters
+		/// * `kind`: Whether the corridor is for teleports or reserve transfers.
+		/// * `asset`: The location of the asset, as seen from this chain.
+		/// * `chain`: The location of the chain on the other side of the corridor.
+		#[pallet::call_index(0)]
+		#[pallet::weight(<T as Config>::WeightInfo::open_corridor())]
+		pub fn open_corridor(
+			origin: OriginFor<T>,
+			kind: CorridorKind,
+			asset: Box<VersionedMultiLocation>,
+			chain: Box<VersionedMultiLocation>,
+		) -> DispatchResult {
+			T::CorridorOrigin::ensure_origin(origin)?;
+
+			let asset = MultiLocation::try_from(*asset).map_err(|()| Error::<T>::BadVersion)?;
+			let chain = MultiLocation::try_from(*chain).map_err(|()| Error::<T>::BadVersion)?;
+			let key = (kind, asset, chain);
+			ensure!(!Corridors::<T>::contains_key(&key), Error::<T>::CorridorAlreadyOpen);
+
+			Corridors::<T>::insert(&key, ());
+			Self::deposit_event(Event::CorridorOpened { kind, asset, chain });
+			Ok(())
+		}
+
+		/// Close a corridor opened with `open_corridor`.
+		///
+		/// # Parameters
+		/// * `kind`: Whether the corridor is for teleports or reserve transfers.
+		/// * `asset`: The location of the asset, as seen from this chain.
+		/// * `chain`: The location of the chain on the other side of the corridor.
+		#[pallet::call_index(1)]
+		#[pallet::weight(<T as Config>::WeightInfo::close_corridor())]
+		pub fn close_corridor(
+			origin: OriginFor<T>,
+			kind: CorridorKind,
+			asset: Box<VersionedMultiLocation>,
+			chain: Box<VersionedMultiLocation>,
+		) -> DispatchResult {
+			T::CorridorOrigin::ensure_origin(origin)?;
+
+			let asset = MultiLocation::try_from(*asset).map_err(|()| Error::<T>::BadVersion)?;
+			let chain = MultiLocation::try_from(*chain).map_err(|()| Error::<T>::BadVersion)?;
+			let key = (kind, asset, chain);
+			ensure!(Corridors::<T>::contains_key(&key), Error::<T>::CorridorNotOpen);
+
+			Corridors::<T>::remove(&key);
+			Self::deposit_event(Event::CorridorClosed { kind, asset, chain });
+			Ok(())
+		}
+
+		/// Set or clear the weight bounds of a destination chain. Tasks sending XCM there are
+		/// checked against them when they are scheduled.
+		///
+		/// # Parameters
+		/// * `destination`: The location of the destination chain.
+		/// * `bounds`: The weight bounds of the destination, or None to stop checking.
+		#[pallet::call_index(2)]
+		#[pallet::weight(<T as Config>::WeightInfo::set_weight_bounds())]
+		pub fn set_weight_bounds(
+			origin: OriginFor<T>,
+			destination: Box<VersionedMultiLocation>,
+			bounds: Option<XcmWeightBounds>,
+		) -> DispatchResult {
+			T::CorridorOrigin::ensure_origin(origin)?;
+
+			let destination =
+				MultiLocation::try_from(*destination).map_err(|()| Error::<T>::BadVersion)?;
+			match bounds {
+				Some(bounds) => {
+					DestinationWeightBounds::<T>::insert(destination, bounds);
+					Self::deposit_event(Event::TransactInfoChanged { destination });
+				},
+				None => {
+					ensure!(
+						DestinationWeightBounds::<T>::contains_key(destination),
+						Error::<T>::TransactInfoNotFound
+					);
+					DestinationWeightBounds::<T>::remove(destination);
+					Self::deposit_event(Event::TransactInfoRemoved { destination });
+				},
+			}
+			Ok(())
+		}
+	}
 
 	impl<T: Config> Pallet<T> {
 		/// Get the instructions for a transact xcm.
@@ -366,32 +499,33 @@ pub mod pallet {
 		}
 
 		/// Transact XCM instructions on local chain
+		/// Returns the weight used by the execution.
 		///
 		pub fn transact_in_local_chain(
 			internal_instructions: xcm::latest::Xcm<<T as pallet::Config>::RuntimeCall>,
-		) -> Result<(), DispatchError> {
+		) -> Result<Weight, DispatchErrorWithPostInfo> {
 			let local_sovereign_account = T::SelfLocation::get();
 			let weight = T::Weigher::weight(&mut internal_instructions.clone().into())
 				.map_err(|_| Error::<T>::ErrorGettingCallWeight)?;
 			let hash = internal_instructions.using_encoded(sp_io::hashing::blake2_256);
 
 			// Execute instruction on local chain
-			T::XcmExecutor::execute_xcm_in_credit(
+			let outcome = T::XcmExecutor::execute_xcm_in_credit(
 				local_sovereign_account,
 				internal_instructions.into(),
 				hash,
 				weight,
 				weight,
-			)
-			.ensure_complete()
-			.map_err(|error| {
+			);
+			let weight_used = outcome.weight_used();
+			outcome.ensure_complete().map_err(|error| {
 				log::error!("Failed execute in credit with {:?}", error);
-				Error::<T>::XcmExecutionFailed
+				Error::<T>::XcmExecutionFailed.with_weight(weight_used)
 			})?;
 
 			Self::deposit_event(Event::XcmTransactedLocally);
 
-			Ok(())
+			Ok(weight_used)
 		}
 
 		/// Send XCM instructions to parachain.
@@ -425,6 +559,8 @@ pub mod pallet {
 		/// Get the instructions for a transact xcm.
 		/// Execute local transact instructions.
 		/// Send target transact instructions.
+		///
+		/// The actual weight reported back is the weight used by the local execution.
 		pub fn transact_xcm(
 			destination: MultiLocation,
 			asset_location: MultiLocation,
@@ -434,7 +570,7 @@ pub mod pallet {
 			transact_encoded_call_weight: Weight,
 			overall_weight: Weight,
 			flow: InstructionSequence,
-		) -> Result<(), DispatchError> {
+		) -> DispatchResultWithPostInfo {
 			let (local_instructions, target_instructions) = Self::get_instruction_set(
 				destination,
 				asset_location,
@@ -446,10 +582,11 @@ pub mod pallet {
 				flow,
 			)?;
 
-			Self::transact_in_local_chain(local_instructions)?;
-			Self::transact_in_target_chain(destination, target_instructions)?;
+			let local_weight = Self::transact_in_local_chain(local_instructions)?;
+			Self::transact_in_target_chain(destination, target_instructions)
+				.map_err(|error| error.with_weight(local_weight))?;
 
-			Ok(())
+			Ok(Some(local_weight).into())
 		}
 
 		fn do_pay_xcm_fee(
@@ -506,6 +643,85 @@ pub mod pallet {
 
 			Ok(())
 		}
+
+		/// Refund XCMP fees paid with `pay_xcm_fee` for a transact that will not be sent.
+		/// Transfers the fee from the local chain sovereign account back to the payer.
+		pub fn refund_xcm_fee(
+			currency_id: T::CurrencyId,
+			dest: T::AccountId,
+			fee: u128,
+		) -> Result<(), DispatchError> {
+			let local_sovereign_account: T::AccountId =
+				Sibling::from(T::SelfParaId::get()).into_account_truncating();
+			T::MultiCurrency::transfer(
+				currency_id.into(),
+				&local_sovereign_account,
+				&dest,
+				fee.saturated_into(),
+			)?;
+			Self::deposit_event(Event::XcmFeesRefunded { source: local_sovereign_account, dest });
+
+			Ok(())
+		}
+
+		/// Derive the account that `caller` controls on `destination`.
+		///
+		/// This is the account the target chain withdraws from when a task uses
+		/// `PayThroughRemoteDerivativeAccount`, so users can fund it ahead of time.
+		/// `LocationConverter` must match the conversion used by the target chain.
+		pub fn derive_remote_account<AccountId, LocationConverter>(
+			caller: T::AccountId,
+			destination: MultiLocation,
+		) -> Result<AccountId, DispatchError>
+		where
+			LocationConverter: xcm_executor::traits::Convert<MultiLocation, AccountId>,
+		{
+			let remote_location = T::AccountIdToMultiLocation::convert(caller)
+				.reanchored(&destination, T::UniversalLocation::get())
+				.map_err(|_| Error::<T>::CannotReanchor)?;
+
+			LocationConverter::convert(remote_location)
+				.map_err(|_| Error::<T>::CannotDeriveRemoteAccount.into())
+		}
+
+		/// Check the weights declared for a transact to `destination` against the bounds
+		/// registered for it. Destinations without bounds are not checked.
+		///
+		/// The call weight must cover decoding the call and stay within the largest call weight
+		/// of the destination. The overall weight must cover the call and every instruction the
+		/// destination executes for `flow`.
+		pub fn check_transact_weights(
+			destination: MultiLocation,
+			encoded_call_len: usize,
+			encoded_call_weight: Weight,
+			overall_weight: Weight,
+			flow: InstructionSequence,
+		) -> Result<(), DispatchError> {
+			let bounds = match DestinationWeightBounds::<T>::get(destination) {
+				Some(bounds) => bounds,
+				None => return Ok(()),
+			};
+
+			let decode_weight = bounds.weight_per_byte.saturating_mul(encoded_call_len as u64);
+			ensure!(
+				encoded_call_weight.all_gte(decode_weight) &&
+					encoded_call_weight.all_lte(bounds.max_call_weight),
+				Error::<T>::CallWeightImplausible
+			);
+
+			// The number of target instructions built by `get_instruction_set` for each flow
+			let instructions: u64 = match flow {
+				InstructionSequence::PayThroughSovereignAccount => 6,
+				InstructionSequence::PayThroughRemoteDerivativeAccount => 4,
+			};
+			let xcm_weight = bounds
+				.instruction_weight
+				.saturating_mul(instructions)
+				.saturating_add(encoded_call_weight);
+			ensure!(overall_weight.all_gte(xcm_weight), Error::<T>::OverallWeightImplausible);
+
+			Ok(())
+		}
 	}
 }
 
@@ -519,13 +735,32 @@ pub trait XcmpTransactor<AccountId, CurrencyId> {
 		transact_encoded_call_weight: Weight,
 		overall_weight: Weight,
 		flow: InstructionSequence,
-	) -> Result<(), sp_runtime::DispatchError>;
+	) -> DispatchResultWithPostInfo;
 
 	fn pay_xcm_fee(
 		currency_id: CurrencyId,
 		source: AccountId,
 		fee: u128,
 	) -> Result<(), sp_runtime::DispatchError>;
+
+	fn refund_xcm_fee(
+		currency_id: CurrencyId,
+		dest: AccountId,
+		fee: u128,
+	) -> Result<(), sp_runtime::DispatchError>;
+
+	fn check_transact_weights(
+		destination: MultiLocation,
+		encoded_call_len: usize,
+		encoded_call_weight: Weight,
+		overall_weight: Weight,
+		flow: InstructionSequence,
+	) -> Result<(), sp_runtime::DispatchError>;
+
+	/// Whether `transact_xcm` failed with `error` because the message could not be sent to the
+	/// destination, such as when its channel is full or suspended. Sending it again later may
+	/// succeed.
+	fn is_delivery_failure(error: &sp_runtime::DispatchError) -> bool;
 }
 
 impl<T: Config> XcmpTransactor<T::AccountId, T::CurrencyId> for Pallet<T> {
@@ -538,7 +773,7 @@ impl<T: Config> XcmpTransactor<T::AccountId, T::CurrencyId> for Pallet<T> {
 		transact_encoded_call_weight: Weight,
 		overall_weight: Weight,
 		flow: InstructionSequence,
-	) -> Result<(), sp_runtime::DispatchError> {
+	) -> DispatchResultWithPostInfo {
 		Self::transact_xcm(
 			destination,
 			asset_location,
@@ -548,9 +783,7 @@ impl<T: Config> XcmpTransactor<T::AccountId, T::CurrencyId> for Pallet<T> {
 			transact_encoded_call_weight,
 			overall_weight,
 			flow,
-		)?;
-
-		Ok(())
+		)
 	}
 
 	fn pay_xcm_fee(
@@ -562,6 +795,101 @@ impl<T: Config> XcmpTransactor<T::AccountId, T::CurrencyId> for Pallet<T> {
 
 		Ok(())
 	}
+
+	fn refund_xcm_fee(
+		currency_id: T::CurrencyId,
+		dest: T::AccountId,
+		fee: u128,
+	) -> Result<(), sp_runtime::DispatchError> {
+		Self::refund_xcm_fee(currency_id, dest, fee)
+	}
+
+	fn check_transact_weights(
+		destination: MultiLocation,
+		encoded_call_len: usize,
+		encoded_call_weight: Weight,
+		overall_weight: Weight,
+		flow: InstructionSequence,
+	) -> Result<(), sp_runtime::DispatchError> {
+		Self::check_transact_weights(
+			destination,
+			encoded_call_len,
+			encoded_call_weight,
+			overall_weight,
+			flow,
+		)
+	}
+
+	fn is_delivery_failure(error: &sp_runtime::DispatchError) -> bool {
+		*error == Error::<T>::ErrorSendingXcmToTarget.into()
+	}
+}
+
+/// The kind of asset transfer a corridor allows.
+#[derive(Clone, Copy, Debug, Encode, Eq, Decode, PartialEq, TypeInfo, MaxEncodedLen)]
+pub enum CorridorKind {
+	Teleport,
+	Reserve,
+}
+
+/// Bounds on the weight a destination chain charges for XCM, used to reject implausible weights
+/// when a task is scheduled.
+#[derive(Clone, Copy, Debug, Encode, Eq, Decode, PartialEq, TypeInfo, MaxEncodedLen)]
+pub struct XcmWeightBounds {
+	/// The weight of each XCM instruction executed on the destination.
+	pub instruction_weight: Weight,
+	/// The least weight a call takes per byte of its encoding.
+	pub weight_per_byte: Weight,
+	/// The most weight a single call can take on the destination.
+	pub max_call_weight: Weight,
+}
+
+impl<T: Config> Pallet<T> {
+	/// Whether a corridor of `kind` is open for `asset` with `chain`.
+	pub fn is_corridor_open(kind: CorridorKind, asset: &MultiAsset, chain: &MultiLocation) -> bool {
+		match &asset.id {
+			Concrete(location) => Corridors::<T>::contains_key((kind, location, chain)),
+			Abstract(_) => false,
+		}
+	}
+
+	/// Whether every one of `assets` has a corridor of `kind` open with some chain.
+	pub fn all_have_corridor(kind: CorridorKind, assets: &[MultiAsset]) -> bool {
+		!assets.is_empty() &&
+			assets.iter().all(|asset| match &asset.id {
+				Concrete(location) =>
+					Corridors::<T>::iter_key_prefix((kind, location)).next().is_some(),
+				Abstract(_) => false,
+			})
+	}
+}
+
+/// Teleport corridors, usable as both `IsTeleporter` in the XCM executor config and
+/// `XcmTeleportFilter` in pallet-xcm.
+pub struct TeleportCorridors<T>(PhantomData<T>);
+impl<T: Config> ContainsPair<MultiAsset, MultiLocation> for TeleportCorridors<T> {
+	fn contains(asset: &MultiAsset, origin: &MultiLocation) -> bool {
+		Pallet::<T>::is_corridor_open(CorridorKind::Teleport, asset, origin)
+	}
+}
+impl<T: Config> Contains<(MultiLocation, Vec<MultiAsset>)> for TeleportCorridors<T> {
+	fn contains((_, assets): &(MultiLocation, Vec<MultiAsset>)) -> bool {
+		Pallet::<T>::all_have_corridor(CorridorKind::Teleport, assets)
+	}
+}
+
+/// Reserve transfer corridors, usable as both `IsReserve` in the XCM executor config and
+/// `XcmReserveTransferFilter` in pallet-xcm.
+pub struct ReserveCorridors<T>(PhantomData<T>);
+impl<T: Config> ContainsPair<MultiAsset, MultiLocation> for ReserveCorridors<T> {
+	fn contains(asset: &MultiAsset, origin: &MultiLocation) -> bool {
+		Pallet::<T>::is_corridor_open(CorridorKind::Reserve, asset, origin)
+	}
+}
+impl<T: Config> Contains<(MultiLocation, Vec<MultiAsset>)> for ReserveCorridors<T> {
+	fn contains((_, assets): &(MultiLocation, Vec<MultiAsset>)) -> bool {
+		Pallet::<T>::all_have_corridor(CorridorKind::Reserve, assets)
+	}
 }
 
 #[derive(Clone, Copy, Debug, Encode, Eq, Decode, PartialEq, TypeInfo)]
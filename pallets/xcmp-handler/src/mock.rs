@@ -358,6 +358,8 @@ impl pallet_xcmp_handler::Config for Test {
 	type Weigher = FixedWeightBounds<UnitWeightCost, RuntimeCall, MaxInstructions>;
 	type ReserveProvider = AbsoluteAndRelativeReserveProvider<SelfLocation>;
 	type SelfLocation = SelfLocation;
+	type CorridorOrigin = system::EnsureRoot<AccountId>;
+	type WeightInfo = ();
 }
 
 // Build genesis storage according to the mock runtime.
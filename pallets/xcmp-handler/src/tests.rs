@@ -14,12 +14,24 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
-use crate::{mock::*, Error, InstructionSequence};
-use frame_support::{assert_noop, assert_ok};
+use crate::{
+	mock::*, CorridorKind, DestinationWeightBounds, Error, InstructionSequence, ReserveCorridors,
+	TeleportCorridors, XcmWeightBounds,
+};
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{Contains, ContainsPair},
+};
 use frame_system::RawOrigin;
 use polkadot_parachain::primitives::Sibling;
 use sp_runtime::traits::{AccountIdConversion, Convert};
 use xcm::latest::{prelude::*, Weight};
+use xcm_executor::traits::{Convert as XcmConvert, WeightBounds};
+
+type RemoteLocationToAccountId = primitives::HashedDescription<
+	AccountId,
+	primitives::DescribeFamily<primitives::DescribeAllTerminal>,
+>;
 
 //*****************
 //Extrinsics
@@ -27,6 +39,102 @@ use xcm::latest::{prelude::*, Weight};
 
 const PARA_ID: u32 = 1000;
 
+// open_corridor
+#[test]
+fn open_corridor_allows_transfers_of_the_asset() {
+	let asset_location = MultiLocation::new(1, X2(Parachain(PARA_ID), GeneralIndex(1984)));
+	let chain = MultiLocation::new(1, X1(Parachain(PARA_ID)));
+	let asset: MultiAsset = (asset_location, 1_000).into();
+
+	new_test_ext().execute_with(|| {
+		assert!(!<ReserveCorridors<Test> as ContainsPair<_, _>>::contains(&asset, &chain));
+
+		assert_ok!(XcmpHandler::open_corridor(
+			RawOrigin::Root.into(),
+			CorridorKind::Reserve,
+			Box::new(asset_location.into()),
+			Box::new(chain.into()),
+		));
+
+		assert!(<ReserveCorridors<Test> as ContainsPair<_, _>>::contains(&asset, &chain));
+		assert!(<ReserveCorridors<Test> as Contains<_>>::contains(&(chain, vec![asset.clone()])));
+		assert!(!<TeleportCorridors<Test> as ContainsPair<_, _>>::contains(&asset, &chain));
+		assert!(!<TeleportCorridors<Test> as Contains<_>>::contains(&(chain, vec![asset.clone()])));
+		assert_eq!(
+			events(),
+			[RuntimeEvent::XcmpHandler(crate::Event::CorridorOpened {
+				kind: CorridorKind::Reserve,
+				asset: asset_location,
+				chain,
+			})]
+		);
+
+		assert_noop!(
+			XcmpHandler::open_corridor(
+				RawOrigin::Root.into(),
+				CorridorKind::Reserve,
+				Box::new(asset_location.into()),
+				Box::new(chain.into()),
+			),
+			Error::<Test>::CorridorAlreadyOpen
+		);
+	});
+}
+
+#[test]
+fn open_corridor_requires_corridor_origin() {
+	let asset_location = MultiLocation::new(1, X2(Parachain(PARA_ID), GeneralIndex(1984)));
+	let chain = MultiLocation::new(1, X1(Parachain(PARA_ID)));
+
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			XcmpHandler::open_corridor(
+				RuntimeOrigin::signed(ALICE),
+				CorridorKind::Teleport,
+				Box::new(asset_location.into()),
+				Box::new(chain.into()),
+			),
+			sp_runtime::DispatchError::BadOrigin
+		);
+	});
+}
+
+// close_corridor
+#[test]
+fn close_corridor_blocks_transfers_of_the_asset() {
+	let asset_location = MultiLocation::new(1, X2(Parachain(PARA_ID), GeneralIndex(1984)));
+	let chain = MultiLocation::new(1, X1(Parachain(PARA_ID)));
+	let asset: MultiAsset = (asset_location, 1_000).into();
+
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			XcmpHandler::close_corridor(
+				RawOrigin::Root.into(),
+				CorridorKind::Teleport,
+				Box::new(asset_location.into()),
+				Box::new(chain.into()),
+			),
+			Error::<Test>::CorridorNotOpen
+		);
+
+		assert_ok!(XcmpHandler::open_corridor(
+			RawOrigin::Root.into(),
+			CorridorKind::Teleport,
+			Box::new(asset_location.into()),
+			Box::new(chain.into()),
+		));
+		assert_ok!(XcmpHandler::close_corridor(
+			RawOrigin::Root.into(),
+			CorridorKind::Teleport,
+			Box::new(asset_location.into()),
+			Box::new(chain.into()),
+		));
+
+		assert!(!<TeleportCorridors<Test> as ContainsPair<_, _>>::contains(&asset, &chain));
+		assert!(!<TeleportCorridors<Test> as Contains<_>>::contains(&(chain, vec![asset])));
+	});
+}
+
 //*****************
 //Helper  functions
 //*****************
@@ -129,7 +237,10 @@ fn transact_in_local_chain_works() {
 		)
 		.unwrap();
 
-		assert_ok!(XcmpHandler::transact_in_local_chain(local_instructions));
+		let mut instructions = local_instructions.clone();
+		let weight = <Test as crate::Config>::Weigher::weight(&mut instructions).unwrap();
+
+		assert_eq!(XcmpHandler::transact_in_local_chain(local_instructions), Ok(weight));
 		assert_eq!(
 			transact_asset(),
 			vec![
@@ -265,6 +376,51 @@ fn transact_in_target_chain_with_to_reserved_currency_works() {
 	});
 }
 
+#[test]
+fn get_local_currency_instructions_with_asset_hub_asset_works() {
+	new_test_ext().execute_with(|| {
+		let asset_hub = MultiLocation::new(1, X1(Parachain(PARA_ID)));
+		let asset_location =
+			MultiLocation::new(1, X3(Parachain(PARA_ID), PalletInstance(50), GeneralIndex(1984)));
+		let transact_encoded_call: Vec<u8> = vec![0, 1, 2];
+		let transact_encoded_call_weight = Weight::from_parts(100_000_000, 0);
+		let xcm_weight = Weight::from_parts(200_000_000, 0);
+		let xcm_fee = 1_000_000;
+		let descend_location: Junctions =
+			AccountIdToMultiLocation::convert(ALICE).try_into().unwrap();
+
+		let (local_instructions, target_instructions) =
+			XcmpHandler::get_local_currency_instructions(
+				asset_hub,
+				asset_location,
+				descend_location,
+				transact_encoded_call,
+				transact_encoded_call_weight,
+				xcm_weight,
+				xcm_fee,
+			)
+			.unwrap();
+
+		// The fee is burnt here and withdrawn from our sovereign account on Asset Hub.
+		let local_asset = MultiAsset { id: Concrete(asset_location), fun: Fungible(xcm_fee) };
+		assert_eq!(
+			local_instructions.0[..2],
+			[WithdrawAsset(local_asset.clone().into()), BurnAsset(local_asset.into())]
+		);
+		let target_asset = MultiAsset {
+			id: Concrete(MultiLocation::new(0, X2(PalletInstance(50), GeneralIndex(1984)))),
+			fun: Fungible(xcm_fee),
+		};
+		assert_eq!(
+			target_instructions.0[..2],
+			[
+				WithdrawAsset(target_asset.clone().into()),
+				BuyExecution { fees: target_asset, weight_limit: Limited(xcm_weight) },
+			]
+		);
+	});
+}
+
 #[test]
 fn transact_in_target_chain_with_non_reserved_currency_will_throw_unsupported_fee_payment_error() {
 	new_test_ext().execute_with(|| {
@@ -328,6 +484,152 @@ fn pay_xcm_fee_keeps_wallet_alive() {
 	});
 }
 
+#[test]
+fn derive_remote_account_works() {
+	new_test_ext().execute_with(|| {
+		let destination = MultiLocation::new(1, X1(Parachain(PARA_ID)));
+		let remote_location = MultiLocation::new(
+			1,
+			X2(Parachain(LOCAL_PARA_ID), Junction::AccountId32 { network: None, id: ALICE.into() }),
+		);
+		let expected_account = RemoteLocationToAccountId::convert_ref(remote_location).unwrap();
+
+		assert_eq!(
+			XcmpHandler::derive_remote_account::<AccountId, RemoteLocationToAccountId>(
+				ALICE,
+				destination
+			),
+			Ok(expected_account)
+		);
+	});
+}
+
+#[test]
+fn derive_remote_account_with_unreachable_destination_fails() {
+	new_test_ext().execute_with(|| {
+		let destination = MultiLocation::new(3, X1(Parachain(PARA_ID)));
+
+		assert_noop!(
+			XcmpHandler::derive_remote_account::<AccountId, RemoteLocationToAccountId>(
+				ALICE,
+				destination
+			),
+			Error::<Test>::CannotReanchor
+		);
+	});
+}
+
+#[test]
+fn check_transact_weights_rejects_implausible_weights() {
+	let destination = MultiLocation::new(1, X1(Parachain(PARA_ID)));
+	let bounds = XcmWeightBounds {
+		instruction_weight: Weight::from_parts(1_000, 0),
+		weight_per_byte: Weight::from_parts(100, 0),
+		max_call_weight: Weight::from_parts(100_000, 0),
+	};
+	let flow = InstructionSequence::PayThroughRemoteDerivativeAccount;
+
+	new_test_ext().execute_with(|| {
+		// Destinations without bounds are not checked
+		assert_ok!(XcmpHandler::check_transact_weights(
+			destination,
+			10,
+			Weight::zero(),
+			Weight::zero(),
+			flow,
+		));
+
+		assert_ok!(XcmpHandler::set_weight_bounds(
+			RawOrigin::Root.into(),
+			Box::new(destination.into()),
+			Some(bounds),
+		));
+		assert_eq!(
+			events(),
+			[RuntimeEvent::XcmpHandler(crate::Event::TransactInfoChanged { destination })]
+		);
+
+		// 10 bytes cost at least 1_000 to decode, and 4 instructions add 4_000 to the call
+		assert_ok!(XcmpHandler::check_transact_weights(
+			destination,
+			10,
+			Weight::from_parts(1_000, 0),
+			Weight::from_parts(5_000, 0),
+			flow,
+		));
+		assert_noop!(
+			XcmpHandler::check_transact_weights(
+				destination,
+				10,
+				Weight::from_parts(999, 0),
+				Weight::from_parts(5_000, 0),
+				flow,
+			),
+			Error::<Test>::CallWeightImplausible
+		);
+		assert_noop!(
+			XcmpHandler::check_transact_weights(
+				destination,
+				10,
+				Weight::from_parts(100_001, 0),
+				Weight::from_parts(200_000, 0),
+				flow,
+			),
+			Error::<Test>::CallWeightImplausible
+		);
+		assert_noop!(
+			XcmpHandler::check_transact_weights(
+				destination,
+				10,
+				Weight::from_parts(1_000, 0),
+				Weight::from_parts(4_999, 0),
+				flow,
+			),
+			Error::<Test>::OverallWeightImplausible
+		);
+	});
+}
+
+#[test]
+fn set_weight_bounds_clears_registered_bounds() {
+	let destination = MultiLocation::new(1, X1(Parachain(PARA_ID)));
+
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			XcmpHandler::set_weight_bounds(
+				RawOrigin::Root.into(),
+				Box::new(destination.into()),
+				None,
+			),
+			Error::<Test>::TransactInfoNotFound
+		);
+
+		assert_ok!(XcmpHandler::set_weight_bounds(
+			RawOrigin::Root.into(),
+			Box::new(destination.into()),
+			Some(XcmWeightBounds {
+				instruction_weight: Weight::from_parts(1_000, 0),
+				weight_per_byte: Weight::from_parts(100, 0),
+				max_call_weight: Weight::from_parts(100_000, 0),
+			}),
+		));
+		assert_ok!(XcmpHandler::set_weight_bounds(
+			RawOrigin::Root.into(),
+			Box::new(destination.into()),
+			None,
+		));
+
+		assert!(!DestinationWeightBounds::<Test>::contains_key(destination));
+		assert_ok!(XcmpHandler::check_transact_weights(
+			destination,
+			10,
+			Weight::zero(),
+			Weight::zero(),
+			InstructionSequence::PayThroughRemoteDerivativeAccount,
+		));
+	});
+}
+
 fn events() -> Vec<RuntimeEvent> {
 	let evt = System::events().into_iter().map(|evt| evt.event).collect::<Vec<_>>();
 
@@ -0,0 +1,143 @@
+// This file is part of OAK-blockchain.
+
+// Copyright (C) OAK Network Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Autogenerated weights for pallet_xcmp_handler
+//!
+//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 4.0.0-dev
+//! DATE: 2024-01-16, STEPS: `50`, REPEAT: `20`, LOW RANGE: `[]`, HIGH RANGE: `[]`
+//! WORST CASE MAP SIZE: `1000000`
+//! HOSTNAME: `actions-runner-1`, CPU: `Intel(R) Xeon(R) E-2388G CPU @ 3.20GHz`
+//! EXECUTION: Some(Wasm), WASM-EXECUTION: Compiled, CHAIN: Some("turing-dev"), DB CACHE: 1024
+
+// Executed Command:
+// ./oak-collator
+// benchmark
+// pallet
+// --header
+// ./.maintain/HEADER-GPL3
+// --chain
+// turing-dev
+// --execution
+// wasm
+// --wasm-execution
+// compiled
+// --pallet
+// pallet_xcmp_handler
+// --extrinsic
+// *
+// --repeat
+// 20
+// --steps
+// 50
+// --output
+// ./xcmp_handler-raw-weights.rs
+// --template
+// ./.maintain/frame-weight-template.hbs
+
+// Summary:
+//:open_corridor 17_204_000,3571
+//:close_corridor 17_853_000,3571
+//:set_weight_bounds 13_512_000,0
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use core::marker::PhantomData;
+
+/// Weight functions needed for pallet_xcmp_handler.
+pub trait WeightInfo {
+	fn open_corridor() -> Weight;
+	fn close_corridor() -> Weight;
+	fn set_weight_bounds() -> Weight;
+}
+
+/// Weights for pallet_xcmp_handler using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: XcmpHandler Corridors (r:1 w:1)
+	/// Proof Skipped: XcmpHandler Corridors (max_values: None, max_size: None, mode: Measured)
+	fn open_corridor() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `106`
+		//  Estimated: `3571`
+		// Minimum execution time: 16_731_000 picoseconds.
+		Weight::from_parts(17_204_000, 3571)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: XcmpHandler Corridors (r:1 w:1)
+	/// Proof Skipped: XcmpHandler Corridors (max_values: None, max_size: None, mode: Measured)
+	fn close_corridor() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `175`
+		//  Estimated: `3571`
+		// Minimum execution time: 17_382_000 picoseconds.
+		Weight::from_parts(17_853_000, 3571)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: XcmpHandler DestinationWeightBounds (r:0 w:1)
+	/// Proof: XcmpHandler DestinationWeightBounds (max_values: None, max_size: Some(603), added: 3078, mode: MaxEncodedLen)
+	fn set_weight_bounds() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 13_107_000 picoseconds.
+		Weight::from_parts(13_512_000, 0)
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	/// Storage: XcmpHandler Corridors (r:1 w:1)
+	/// Proof Skipped: XcmpHandler Corridors (max_values: None, max_size: None, mode: Measured)
+	fn open_corridor() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `106`
+		//  Estimated: `3571`
+		// Minimum execution time: 16_731_000 picoseconds.
+		Weight::from_parts(17_204_000, 3571)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: XcmpHandler Corridors (r:1 w:1)
+	/// Proof Skipped: XcmpHandler Corridors (max_values: None, max_size: None, mode: Measured)
+	fn close_corridor() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `175`
+		//  Estimated: `3571`
+		// Minimum execution time: 17_382_000 picoseconds.
+		Weight::from_parts(17_853_000, 3571)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: XcmpHandler DestinationWeightBounds (r:0 w:1)
+	/// Proof: XcmpHandler DestinationWeightBounds (max_values: None, max_size: Some(603), added: 3078, mode: MaxEncodedLen)
+	fn set_weight_bounds() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 13_107_000 picoseconds.
+		Weight::from_parts(13_512_000, 0)
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+}
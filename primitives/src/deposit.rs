@@ -0,0 +1,83 @@
+// This file is part of OAK Blockchain.
+
+// Copyright (C) 2022 OAK Network
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Deposits reserved from an account for as long as an item it created stays in storage.
+//!
+//! Pallets compute the deposit from the item's encoded size with [`StorageDeposit`], reserve it
+//! with [`hold_deposit`] and record the amount next to the item. Once the item is removed, the
+//! recorded amount is handed back with [`release_deposit`].
+
+use codec::Encode;
+use frame_support::traits::{Get, ReservableCurrency};
+use sp_runtime::{
+	traits::{Saturating, Zero},
+	DispatchError,
+};
+use sp_std::marker::PhantomData;
+
+/// Computes the deposit for an item kept in storage.
+pub trait StorageDeposit<Balance> {
+	/// The deposit for an item whose encoding is `len` bytes long.
+	fn deposit_for(len: u32) -> Balance;
+}
+
+/// No deposit.
+impl<Balance: Zero> StorageDeposit<Balance> for () {
+	fn deposit_for(_len: u32) -> Balance {
+		Zero::zero()
+	}
+}
+
+/// A fixed amount per item plus an amount per encoded byte.
+pub struct LinearStorageDeposit<PerItem, PerByte>(PhantomData<(PerItem, PerByte)>);
+impl<Balance, PerItem, PerByte> StorageDeposit<Balance> for LinearStorageDeposit<PerItem, PerByte>
+where
+	Balance: Saturating + From<u32>,
+	PerItem: Get<Balance>,
+	PerByte: Get<Balance>,
+{
+	fn deposit_for(len: u32) -> Balance {
+		PerItem::get().saturating_add(PerByte::get().saturating_mul(len.into()))
+	}
+}
+
+/// Reserve the deposit for `item` from `who` and return the amount reserved.
+pub fn hold_deposit<AccountId, Currency, Deposit>(
+	who: &AccountId,
+	item: &impl Encode,
+) -> Result<Currency::Balance, DispatchError>
+where
+	Currency: ReservableCurrency<AccountId>,
+	Deposit: StorageDeposit<Currency::Balance>,
+{
+	let amount = Deposit::deposit_for(item.encoded_size() as u32);
+	if !amount.is_zero() {
+		Currency::reserve(who, amount)?;
+	}
+	Ok(amount)
+}
+
+/// Unreserve a deposit taken with [`hold_deposit`] and return the amount given back to `who`.
+pub fn release_deposit<AccountId, Currency>(
+	who: &AccountId,
+	amount: Currency::Balance,
+) -> Currency::Balance
+where
+	Currency: ReservableCurrency<AccountId>,
+{
+	amount.saturating_sub(Currency::unreserve(who, amount))
+}
@@ -30,6 +30,9 @@ use orml_traits::location::{RelativeReserveProvider, Reserve};
 use xcm::latest::prelude::*;
 
 pub mod assets;
+pub mod deposit;
+
+pub use deposit::{LinearStorageDeposit, StorageDeposit};
 
 pub use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 
@@ -78,6 +81,18 @@ pub trait TransferCallCreator<AccountId, Balance, RuntimeCall> {
 	fn create_transfer_call(dest: AccountId, value: Balance) -> RuntimeCall;
 }
 
+/// Notified each time an automation task has been executed.
+pub trait OnTaskExecuted {
+	fn on_task_executed(succeeded: bool);
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+impl OnTaskExecuted for Tuple {
+	fn on_task_executed(succeeded: bool) {
+		for_tuples!( #( Tuple::on_task_executed(succeeded); )* );
+	}
+}
+
 /// `MultiAsset` reserve location provider. It's based on `RelativeReserveProvider` and in
 /// addition will convert self absolute location to relative location.
 pub struct AbsoluteAndRelativeReserveProvider<AbsoluteLocation>(PhantomData<AbsoluteLocation>);
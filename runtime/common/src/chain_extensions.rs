@@ -0,0 +1,168 @@
+// This file is part of OAK Blockchain.
+
+// Copyright (C) 2022 OAK Network
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Chain extension that lets ink! contracts own and manage automation tasks.
+//!
+//! The contract passes a SCALE encoded `pallet_automation_time::Call` or
+//! `pallet_automation_price::Call`, which is dispatched with the contract account as the signed
+//! origin. Only the scheduling and cancellation calls are accepted.
+//!
+//! Contracts can also read the latest oracle price of an asset pair by passing the encoded
+//! `(chain, exchange, asset1, asset2)` key; the `PriceData` is written to the output buffer.
+//!
+//! Every function returns one of the [`AutomationExtensionStatus`] codes.
+
+use codec::Encode;
+use frame_support::{
+	dispatch::{GetDispatchInfo, PostDispatchInfo},
+	log,
+	traits::Get,
+};
+use pallet_contracts::chain_extension::{
+	ChainExtension, Environment, Ext, InitState, RetVal, SysConfig,
+};
+use sp_runtime::{traits::Dispatchable, DispatchError};
+use sp_std::{marker::PhantomData, vec::Vec};
+
+/// Dispatch a `pallet_automation_time::Call`.
+pub const AUTOMATION_TIME_CALL: u16 = 0x0001;
+/// Dispatch a `pallet_automation_price::Call`.
+pub const AUTOMATION_PRICE_CALL: u16 = 0x0002;
+/// Read the `PriceData` of an asset pair from `pallet_automation_price::PriceRegistry`.
+pub const AUTOMATION_PRICE_READ: u16 = 0x0101;
+
+/// The `(chain, exchange, asset1, asset2)` key of a price read.
+pub type PriceKey = (Vec<u8>, Vec<u8>, Vec<u8>, Vec<u8>);
+
+/// Status codes returned to the contract.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum AutomationExtensionStatus {
+	Success = 0,
+	/// The call is not one a contract is allowed to make.
+	CallNotAllowed = 1,
+	/// The call was dispatched and failed.
+	DispatchFailed = 2,
+	/// No price has been submitted for the asset pair.
+	PriceNotFound = 3,
+}
+
+pub struct AutomationExtension<T>(PhantomData<T>);
+
+impl<T> Default for AutomationExtension<T> {
+	fn default() -> Self {
+		AutomationExtension(PhantomData)
+	}
+}
+
+fn is_allowed_automation_time_call<T: pallet_automation_time::Config>(
+	call: &pallet_automation_time::Call<T>,
+) -> bool {
+	matches!(
+		call,
+		pallet_automation_time::Call::schedule_xcmp_task { .. } |
+			pallet_automation_time::Call::schedule_dynamic_dispatch_task { .. } |
+			pallet_automation_time::Call::cancel_task { .. }
+	)
+}
+
+fn is_allowed_automation_price_call<T: pallet_automation_price::Config>(
+	call: &pallet_automation_price::Call<T>,
+) -> bool {
+	matches!(
+		call,
+		pallet_automation_price::Call::schedule_xcmp_task { .. } |
+			pallet_automation_price::Call::cancel_task { .. }
+	)
+}
+
+impl<T> ChainExtension<T> for AutomationExtension<T>
+where
+	T: pallet_contracts::Config + pallet_automation_time::Config + pallet_automation_price::Config,
+	<T as pallet_contracts::Config>::RuntimeCall:
+		From<pallet_automation_time::Call<T>> + From<pallet_automation_price::Call<T>>,
+{
+	fn call<E: Ext<T = T>>(
+		&mut self,
+		env: Environment<E, InitState>,
+	) -> Result<RetVal, DispatchError> {
+		let mut env = env.buf_in_buf_out();
+		let in_len = env.in_len();
+
+		let call: <T as pallet_contracts::Config>::RuntimeCall = match env.func_id() {
+			AUTOMATION_TIME_CALL => {
+				let call: pallet_automation_time::Call<T> = env.read_as_unbounded(in_len)?;
+				if !is_allowed_automation_time_call(&call) {
+					return Ok(RetVal::Converging(AutomationExtensionStatus::CallNotAllowed as u32))
+				}
+				call.into()
+			},
+			AUTOMATION_PRICE_CALL => {
+				let call: pallet_automation_price::Call<T> = env.read_as_unbounded(in_len)?;
+				if !is_allowed_automation_price_call(&call) {
+					return Ok(RetVal::Converging(AutomationExtensionStatus::CallNotAllowed as u32))
+				}
+				call.into()
+			},
+			AUTOMATION_PRICE_READ => {
+				let (chain, exchange, asset1, asset2): PriceKey = env.read_as_unbounded(in_len)?;
+				env.charge_weight(<T as SysConfig>::DbWeight::get().reads(1u64))?;
+
+				let status = match pallet_automation_price::PriceRegistry::<T>::get((
+					chain,
+					exchange,
+					(asset1, asset2),
+				)) {
+					Some(price) => {
+						env.write(&price.encode(), false, None)?;
+						AutomationExtensionStatus::Success
+					},
+					None => AutomationExtensionStatus::PriceNotFound,
+				};
+				return Ok(RetVal::Converging(status as u32))
+			},
+			func_id => {
+				log::error!(
+					target: "runtime::contracts",
+					"Unknown chain extension function {:?}",
+					func_id
+				);
+				return Err(DispatchError::Other("Unknown chain extension function"))
+			},
+		};
+
+		let dispatch_info = call.get_dispatch_info();
+		let charged = env.charge_weight(dispatch_info.weight)?;
+
+		// The contract owns the task, so it is scheduled from the contract's own account.
+		let origin = <T as SysConfig>::RuntimeOrigin::from(frame_system::RawOrigin::Signed(
+			env.ext().address().clone(),
+		));
+		let result = call.dispatch(origin);
+		let post_info: PostDispatchInfo = match &result {
+			Ok(post_info) => *post_info,
+			Err(e) => e.post_info,
+		};
+		env.adjust_weight(charged, post_info.calc_actual_weight(&dispatch_info));
+
+		let status = match result {
+			Ok(_) => AutomationExtensionStatus::Success,
+			Err(_) => AutomationExtensionStatus::DispatchFailed,
+		};
+		Ok(RetVal::Converging(status as u32))
+	}
+}
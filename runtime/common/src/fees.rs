@@ -14,13 +14,16 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
-use frame_support::traits::{Imbalance, OnUnbalanced};
+use frame_support::traits::{Get, Imbalance, OnUnbalanced};
 use pallet_balances::NegativeImbalance;
+use sp_runtime::Perbill;
 
-pub struct DealWithInclusionFees<R>(sp_std::marker::PhantomData<R>);
-impl<R> OnUnbalanced<NegativeImbalance<R>> for DealWithInclusionFees<R>
+/// Burns the `BurnRatio` share of fees and tips and sends the rest to the treasury.
+pub struct DealWithInclusionFees<R, BurnRatio>(sp_std::marker::PhantomData<(R, BurnRatio)>);
+impl<R, BurnRatio> OnUnbalanced<NegativeImbalance<R>> for DealWithInclusionFees<R, BurnRatio>
 where
 	R: pallet_balances::Config + pallet_treasury::Config,
+	BurnRatio: Get<Perbill>,
 	pallet_treasury::Pallet<R>: OnUnbalanced<NegativeImbalance<R>>,
 {
 	fn on_unbalanceds<B>(mut fees_then_tips: impl Iterator<Item = NegativeImbalance<R>>) {
@@ -28,8 +31,7 @@ where
 			if let Some(tips) = fees_then_tips.next() {
 				tips.merge_into(&mut fees);
 			}
-			// 20% burned, 80% to the treasury
-			let (_, to_treasury) = fees.ration(20, 80);
+			let (_, to_treasury) = split_burn::<R, BurnRatio>(fees);
 			// Balances pallet automatically burns dropped Negative Imbalances by decreasing
 			// total_supply accordingly
 			<pallet_treasury::Pallet<R> as OnUnbalanced<_>>::on_unbalanced(to_treasury);
@@ -37,19 +39,31 @@ where
 	}
 }
 
-pub struct DealWithExecutionFees<R>(sp_std::marker::PhantomData<R>);
-impl<R> OnUnbalanced<NegativeImbalance<R>> for DealWithExecutionFees<R>
+/// Burns the `BurnRatio` share of execution fees and sends the rest to the treasury.
+pub struct DealWithExecutionFees<R, BurnRatio>(sp_std::marker::PhantomData<(R, BurnRatio)>);
+impl<R, BurnRatio> OnUnbalanced<NegativeImbalance<R>> for DealWithExecutionFees<R, BurnRatio>
 where
 	R: pallet_balances::Config + pallet_treasury::Config,
+	BurnRatio: Get<Perbill>,
 	pallet_treasury::Pallet<R>: OnUnbalanced<NegativeImbalance<R>>,
 {
 	fn on_unbalanceds<B>(mut fees: impl Iterator<Item = NegativeImbalance<R>>) {
 		if let Some(fees) = fees.next() {
-			// 20% burned, 80% to the treasury
-			let (_, to_treasury) = fees.ration(20, 80);
+			let (_, to_treasury) = split_burn::<R, BurnRatio>(fees);
 			// Balances pallet automatically burns dropped Negative Imbalances by decreasing
 			// total_supply accordingly
 			<pallet_treasury::Pallet<R> as OnUnbalanced<_>>::on_unbalanced(to_treasury);
 		}
 	}
 }
+
+fn split_burn<R, BurnRatio>(
+	fees: NegativeImbalance<R>,
+) -> (NegativeImbalance<R>, NegativeImbalance<R>)
+where
+	R: pallet_balances::Config,
+	BurnRatio: Get<Perbill>,
+{
+	let burn = BurnRatio::get();
+	fees.ration(burn.deconstruct(), burn.left_from_one().deconstruct())
+}
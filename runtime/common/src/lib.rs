@@ -19,8 +19,11 @@ use frame_support::traits::Get;
 use orml_traits::currency::MutationHooks;
 use sp_std::marker::PhantomData;
 
+pub mod chain_extensions;
 pub mod constants;
 pub mod fees;
+pub mod message_queue;
+pub mod migrations;
 
 pub struct CurrencyHooks<T, DustAccount>(PhantomData<T>, DustAccount);
 impl<T, DustAccount> MutationHooks<T::AccountId, T::CurrencyId, T::Balance>
@@ -0,0 +1,342 @@
+// This file is part of OAK Blockchain.
+
+// Copyright (C) 2022 OAK Network
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Routes inbound DMP and XCMP messages into `pallet-message-queue`.
+//!
+//! Every XCM received from the relay chain or a sibling is enqueued with its
+//! [`AggregateMessageOrigin`] and executed later by the message queue, which services queues
+//! within its configured weight and parks overweight messages until they are executed
+//! manually. XCMP signals and blobs are still handed to the fallback handler, normally the
+//! XCMP queue pallet, which also keeps handling outbound messages.
+
+use codec::{Decode, DecodeLimit, Encode, MaxEncodedLen};
+use cumulus_primitives_core::{
+	relay_chain::BlockNumber as RelayBlockNumber, DmpMessageHandler, ParaId, XcmpMessageFormat,
+	XcmpMessageHandler,
+};
+use frame_support::{
+	log,
+	traits::{EnqueueMessage, Get},
+	weights::Weight,
+	BoundedSlice,
+};
+use scale_info::TypeInfo;
+use sp_std::{marker::PhantomData, vec::Vec};
+use xcm::{latest::prelude::*, VersionedXcm, MAX_XCM_DECODE_DEPTH};
+
+/// Where an enqueued message came from.
+#[derive(
+	Clone, Copy, Debug, Encode, Decode, Eq, PartialEq, Ord, PartialOrd, TypeInfo, MaxEncodedLen,
+)]
+pub enum AggregateMessageOrigin {
+	/// The relay chain, through DMP.
+	Parent,
+	/// A sibling parachain, through XCMP.
+	Sibling(ParaId),
+}
+
+impl From<AggregateMessageOrigin> for MultiLocation {
+	fn from(origin: AggregateMessageOrigin) -> Self {
+		match origin {
+			AggregateMessageOrigin::Parent => MultiLocation::parent(),
+			AggregateMessageOrigin::Sibling(para_id) =>
+				MultiLocation::new(1, X1(Parachain(para_id.into()))),
+		}
+	}
+}
+
+/// Weight of enqueueing one message, covering the book state and page updates.
+fn enqueue_weight<T: frame_system::Config>() -> Weight {
+	T::DbWeight::get().reads_writes(2, 3)
+}
+
+fn enqueue<T: frame_system::Config, Queue: EnqueueMessage<AggregateMessageOrigin>>(
+	message: &[u8],
+	origin: AggregateMessageOrigin,
+) -> Weight {
+	match BoundedSlice::try_from(message) {
+		Ok(message) => Queue::enqueue_message(message, origin),
+		Err(_) => log::error!(
+			target: "runtime::message-queue",
+			"Dropping message from {:?}, it exceeds the maximum message length",
+			origin
+		),
+	}
+	enqueue_weight::<T>()
+}
+
+/// Enqueues every downward message from the relay chain.
+pub struct EnqueueDmp<T, Queue>(PhantomData<(T, Queue)>);
+impl<T, Queue> DmpMessageHandler for EnqueueDmp<T, Queue>
+where
+	T: frame_system::Config,
+	Queue: EnqueueMessage<AggregateMessageOrigin>,
+{
+	fn handle_dmp_messages(
+		iter: impl Iterator<Item = (RelayBlockNumber, Vec<u8>)>,
+		_max_weight: Weight,
+	) -> Weight {
+		iter.fold(Weight::zero(), |weight, (_sent_at, message)| {
+			weight.saturating_add(enqueue::<T, Queue>(&message, AggregateMessageOrigin::Parent))
+		})
+	}
+}
+
+/// Enqueues every XCM sent by a sibling and passes signals and blobs on to `Fallback`.
+pub struct EnqueueXcmp<T, Queue, Fallback>(PhantomData<(T, Queue, Fallback)>);
+impl<T, Queue, Fallback> XcmpMessageHandler for EnqueueXcmp<T, Queue, Fallback>
+where
+	T: frame_system::Config,
+	Queue: EnqueueMessage<AggregateMessageOrigin>,
+	Fallback: XcmpMessageHandler,
+{
+	fn handle_xcmp_messages<'a, I: Iterator<Item = (ParaId, RelayBlockNumber, &'a [u8])>>(
+		iter: I,
+		max_weight: Weight,
+	) -> Weight {
+		let mut weight = Weight::zero();
+		let mut passthrough = Vec::new();
+
+		for (sender, sent_at, data) in iter {
+			let mut remaining = data;
+			match XcmpMessageFormat::decode(&mut remaining) {
+				Ok(XcmpMessageFormat::ConcatenatedVersionedXcm) => {
+					let origin = AggregateMessageOrigin::Sibling(sender);
+					while !remaining.is_empty() {
+						let message_start = remaining;
+						if VersionedXcm::<()>::decode_with_depth_limit(
+							MAX_XCM_DECODE_DEPTH,
+							&mut remaining,
+						)
+						.is_err()
+						{
+							log::error!(
+								target: "runtime::message-queue",
+								"Failed to decode XCM from {:?}, dropping the rest of the page",
+								origin
+							);
+							break
+						}
+						let message = &message_start[..message_start.len() - remaining.len()];
+						weight.saturating_accrue(enqueue::<T, Queue>(message, origin));
+					}
+				},
+				Ok(_) => passthrough.push((sender, sent_at, data)),
+				Err(_) => log::error!(
+					target: "runtime::message-queue",
+					"Unknown XCMP message format from {:?}",
+					sender
+				),
+			}
+		}
+
+		if !passthrough.is_empty() {
+			weight.saturating_accrue(Fallback::handle_xcmp_messages(
+				passthrough.into_iter(),
+				max_weight.saturating_sub(weight),
+			));
+		}
+		weight
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use frame_support::{
+		construct_runtime, parameter_types,
+		traits::{ConstU32, Everything, Footprint},
+		weights::constants::RocksDbWeight,
+	};
+	use sp_runtime::{
+		testing::{Header, H256},
+		traits::{BlakeTwo256, IdentityLookup},
+	};
+	use sp_std::cell::RefCell;
+
+	type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+	type Block = frame_system::mocking::MockBlock<Test>;
+
+	construct_runtime!(
+		pub enum Test where
+			Block = Block,
+			NodeBlock = Block,
+			UncheckedExtrinsic = UncheckedExtrinsic,
+		{
+			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		}
+	);
+
+	parameter_types! {
+		pub const BlockHashCount: u64 = 250;
+	}
+
+	impl frame_system::Config for Test {
+		type BaseCallFilter = Everything;
+		type DbWeight = RocksDbWeight;
+		type RuntimeOrigin = RuntimeOrigin;
+		type Index = u64;
+		type BlockNumber = u64;
+		type RuntimeCall = RuntimeCall;
+		type Hash = H256;
+		type Hashing = BlakeTwo256;
+		type AccountId = u64;
+		type Lookup = IdentityLookup<Self::AccountId>;
+		type Header = Header;
+		type RuntimeEvent = RuntimeEvent;
+		type BlockHashCount = BlockHashCount;
+		type Version = ();
+		type PalletInfo = PalletInfo;
+		type AccountData = ();
+		type OnNewAccount = ();
+		type OnKilledAccount = ();
+		type SystemWeightInfo = ();
+		type BlockWeights = ();
+		type BlockLength = ();
+		type SS58Prefix = ();
+		type OnSetCode = ();
+		type MaxConsumers = ConstU32<16>;
+	}
+
+	thread_local! {
+		static ENQUEUED: RefCell<Vec<(Vec<u8>, AggregateMessageOrigin)>> = RefCell::new(Vec::new());
+		static PASSED_THROUGH: RefCell<Vec<(ParaId, RelayBlockNumber, Vec<u8>)>> =
+			RefCell::new(Vec::new());
+	}
+
+	// Records the enqueued messages, which are at most 64 bytes long
+	pub struct MockQueue;
+	impl EnqueueMessage<AggregateMessageOrigin> for MockQueue {
+		type MaxMessageLen = ConstU32<64>;
+
+		fn enqueue_message(
+			message: BoundedSlice<u8, Self::MaxMessageLen>,
+			origin: AggregateMessageOrigin,
+		) {
+			ENQUEUED.with(|enqueued| enqueued.borrow_mut().push((message.to_vec(), origin)));
+		}
+
+		fn enqueue_messages<'a>(
+			messages: impl Iterator<Item = BoundedSlice<'a, u8, Self::MaxMessageLen>>,
+			origin: AggregateMessageOrigin,
+		) {
+			messages.for_each(|message| Self::enqueue_message(message, origin));
+		}
+
+		fn sweep_queue(_origin: AggregateMessageOrigin) {}
+
+		fn footprint(_origin: AggregateMessageOrigin) -> Footprint {
+			Footprint::default()
+		}
+	}
+
+	// Records the pages handed to the fallback
+	pub struct MockFallback;
+	impl XcmpMessageHandler for MockFallback {
+		fn handle_xcmp_messages<'a, I: Iterator<Item = (ParaId, RelayBlockNumber, &'a [u8])>>(
+			iter: I,
+			_max_weight: Weight,
+		) -> Weight {
+			PASSED_THROUGH.with(|passed_through| {
+				passed_through
+					.borrow_mut()
+					.extend(iter.map(|(sender, sent_at, data)| (sender, sent_at, data.to_vec())))
+			});
+			Weight::zero()
+		}
+	}
+
+	type Handler = EnqueueXcmp<Test, MockQueue, MockFallback>;
+
+	fn xcm(instructions: usize) -> Vec<u8> {
+		VersionedXcm::<()>::from(Xcm::<()>(vec![ClearOrigin; instructions])).encode()
+	}
+
+	fn page(format: XcmpMessageFormat, messages: &[Vec<u8>]) -> Vec<u8> {
+		let mut page = format.encode();
+		messages.iter().for_each(|message| page.extend_from_slice(message));
+		page
+	}
+
+	fn handle(pages: &[(u32, Vec<u8>)]) -> Weight {
+		let pages = pages.iter().map(|(sender, data)| (ParaId::from(*sender), 1, &data[..]));
+		Handler::handle_xcmp_messages(pages, Weight::MAX)
+	}
+
+	fn enqueued() -> Vec<(Vec<u8>, AggregateMessageOrigin)> {
+		ENQUEUED.with(|enqueued| enqueued.take())
+	}
+
+	fn passed_through() -> Vec<(ParaId, RelayBlockNumber, Vec<u8>)> {
+		PASSED_THROUGH.with(|passed_through| passed_through.take())
+	}
+
+	#[test]
+	fn enqueues_each_message_of_a_page() {
+		let messages = vec![xcm(1), xcm(2), xcm(3)];
+		let weight =
+			handle(&[(2000, page(XcmpMessageFormat::ConcatenatedVersionedXcm, &messages))]);
+
+		let origin = AggregateMessageOrigin::Sibling(2000.into());
+		assert_eq!(enqueued(), messages.into_iter().map(|m| (m, origin)).collect::<Vec<_>>());
+		assert_eq!(weight, enqueue_weight::<Test>().saturating_mul(3));
+		assert!(passed_through().is_empty());
+	}
+
+	#[test]
+	fn drops_the_rest_of_a_page_after_a_malformed_message() {
+		let messages = vec![xcm(1), vec![0xff, 0x00], xcm(2)];
+		handle(&[
+			(2000, page(XcmpMessageFormat::ConcatenatedVersionedXcm, &messages)),
+			(2001, page(XcmpMessageFormat::ConcatenatedVersionedXcm, &[xcm(3)])),
+		]);
+
+		// The pages of other senders are still enqueued
+		assert_eq!(
+			enqueued(),
+			vec![
+				(xcm(1), AggregateMessageOrigin::Sibling(2000.into())),
+				(xcm(3), AggregateMessageOrigin::Sibling(2001.into())),
+			]
+		);
+	}
+
+	#[test]
+	fn drops_an_oversized_message_and_enqueues_the_next_ones() {
+		assert!(xcm(100).len() > 64);
+		let messages = vec![xcm(100), xcm(1)];
+		handle(&[(2000, page(XcmpMessageFormat::ConcatenatedVersionedXcm, &messages))]);
+
+		assert_eq!(enqueued(), vec![(xcm(1), AggregateMessageOrigin::Sibling(2000.into()))]);
+	}
+
+	#[test]
+	fn passes_signals_and_blobs_on_to_the_fallback() {
+		let signals = page(XcmpMessageFormat::Signals, &[vec![0]]);
+		let blob = page(XcmpMessageFormat::ConcatenatedEncodedBlob, &[vec![1, 2, 3].encode()]);
+		handle(&[
+			(2000, signals.clone()),
+			(2001, page(XcmpMessageFormat::ConcatenatedVersionedXcm, &[xcm(1)])),
+			(2002, blob.clone()),
+			// An unknown format is dropped
+			(2003, vec![0xff]),
+		]);
+
+		assert_eq!(enqueued(), vec![(xcm(1), AggregateMessageOrigin::Sibling(2001.into()))]);
+		assert_eq!(passed_through(), vec![(2000.into(), 1, signals), (2002.into(), 1, blob)]);
+	}
+}
@@ -0,0 +1,46 @@
+// This file is part of OAK Blockchain.
+
+// Copyright (C) 2022 OAK Network
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use frame_support::{
+	traits::{Get, OnRuntimeUpgrade},
+	weights::Weight,
+};
+use pallet_state_trie_migration::{AutoLimits, MigrationLimits, MigrationProcess};
+use sp_std::marker::PhantomData;
+
+/// Start the automatic migration of the state trie to state version 1, migrating at most
+/// `Limits` of the trie in each block.
+///
+/// Nothing is done once a migration was started, automatically or through signed migrations,
+/// so the limits can still be changed or the migration stopped with `control_auto_migration`.
+pub struct StartStateTrieMigration<T, Limits>(PhantomData<(T, Limits)>);
+impl<T, Limits> OnRuntimeUpgrade for StartStateTrieMigration<T, Limits>
+where
+	T: pallet_state_trie_migration::Config,
+	Limits: Get<MigrationLimits>,
+{
+	fn on_runtime_upgrade() -> Weight {
+		if MigrationProcess::<T>::exists() || AutoLimits::<T>::get().is_some() {
+			return T::DbWeight::get().reads(2)
+		}
+
+		AutoLimits::<T>::put(Some(Limits::get()));
+		log::info!(target: "runtime::migrations", "Started the automatic state trie migration");
+
+		T::DbWeight::get().reads_writes(2, 1)
+	}
+}
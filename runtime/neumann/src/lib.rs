@@ -36,7 +36,7 @@ use sp_runtime::{
 	traits::{
 		AccountIdConversion, AccountIdLookup, BlakeTwo256, Block as BlockT, ConvertInto, Zero,
 	},
-	transaction_validity::{TransactionSource, TransactionValidity},
+	transaction_validity::{TransactionPriority, TransactionSource, TransactionValidity},
 	AccountId32, ApplyExtrinsicResult, MultiAddress, Percent, RuntimeDebug,
 };
 
@@ -53,8 +53,8 @@ use frame_support::{
 	dispatch::DispatchClass,
 	ensure, parameter_types,
 	traits::{
-		ConstU128, ConstU16, ConstU32, ConstU8, Contains, EitherOfDiverse, EnsureOrigin,
-		EnsureOriginWithArg, InstanceFilter, PrivilegeCmp,
+		ConstBool, ConstU128, ConstU16, ConstU32, ConstU8, Contains, EitherOfDiverse, EnsureOrigin,
+		EnsureOriginWithArg, Everything, InsideBoth, InstanceFilter, Nothing, PrivilegeCmp,
 	},
 	weights::{
 		constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight},
@@ -64,7 +64,7 @@ use frame_support::{
 };
 use frame_system::{
 	limits::{BlockLength, BlockWeights},
-	EnsureRoot, EnsureSigned,
+	EnsureRoot, EnsureSigned, EnsureSignedBy, EnsureWithSuccess,
 };
 pub use sp_runtime::{Perbill, Permill, Perquintill};
 
@@ -81,14 +81,18 @@ use polkadot_runtime_common::BlockHashCount;
 
 // XCM configurations.
 pub mod xcm_config;
-use xcm_config::{FeePerSecondProvider, SelfLocationAbsolute, ToTreasury, TokenIdConvert};
+use xcm_config::{
+	FeePerSecondProvider, RemoteLocationToAccountId, SelfLocationAbsolute, ToCollatorAndTreasury,
+	TokenIdConvert, XcmRouter,
+};
 
 pub mod weights;
 
 // Common imports
 use common_runtime::{
+	chain_extensions::AutomationExtension,
 	constants::{
-		currency::{deposit, CENT, DOLLAR, EXISTENTIAL_DEPOSIT, UNIT},
+		currency::{CENT, DOLLAR, EXISTENTIAL_DEPOSIT, UNIT},
 		fees::SlowAdjustingFeeUpdate,
 		time::{DAYS, HOURS, MINUTES, SLOT_DURATION},
 		weight_ratios::{
@@ -97,18 +101,27 @@ use common_runtime::{
 		},
 	},
 	fees::DealWithInclusionFees,
+	message_queue::{EnqueueDmp, EnqueueXcmp},
 	CurrencyHooks,
 };
 use primitives::{
 	AbsoluteAndRelativeReserveProvider, AccountId, Address, Amount, AuraId, Balance, BlockNumber,
-	EnsureProxy, Hash, Header, Index, Signature, TransferCallCreator,
+	EnsureProxy, Hash, Header, Index, LinearStorageDeposit, Signature, TransferCallCreator,
 };
 
 // Custom pallet imports
 pub use pallet_automation_price;
 pub use pallet_automation_time;
+use pallet_parameters::{ParameterKey, ParameterOr, ParameterValue};
+use pallet_state_trie_migration::MigrationLimits;
 use pallet_xcmp_handler::InstructionSequence;
 
+/// Storage deposits on the test network are a hundredth of the production ones, so partners can
+/// integrate without holding production-sized balances.
+pub const fn deposit(items: u32, bytes: u32) -> Balance {
+	common_runtime::constants::currency::deposit(items, bytes) / 100
+}
+
 /// Block type as expected by this runtime.
 pub type Block = generic::Block<Header, UncheckedExtrinsic>;
 
@@ -127,6 +140,7 @@ pub type SignedExtra = (
 	frame_system::CheckNonce<Runtime>,
 	frame_system::CheckWeight<Runtime>,
 	pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+	pallet_automation_price::PrioritizeOracleUpdates<Runtime>,
 );
 
 /// Unchecked extrinsic type as expected by this runtime.
@@ -143,8 +157,25 @@ pub type Executive = frame_executive::Executive<
 	frame_system::ChainContext<Runtime>,
 	Runtime,
 	AllPalletsWithSystem,
+	Migrations,
 >;
 
+// All migrations executed on runtime upgrade as a nested tuple of types implementing
+// `OnRuntimeUpgrade`.
+type Migrations = (
+	common_runtime::migrations::StartStateTrieMigration<Runtime, StateTrieMigrationLimits>,
+	pallet_automation_price::migrations::v2::MigrateToV2<Runtime>,
+	pallet_automation_price::migrations::v3::MigrateToV3<Runtime>,
+	pallet_automation_price::migrations::v4::MigrateToV4<Runtime>,
+	pallet_automation_price::migrations::v5::MigrateToV5<Runtime>,
+	pallet_automation_price::migrations::v6::MigrateToV6<Runtime>,
+	pallet_automation_price::migrations::v7::MigrateToV7<Runtime>,
+	pallet_automation_price::migrations::v8::MigrateToV8<Runtime>,
+	pallet_automation_price::migrations::v9::MigrateToV9<Runtime>,
+	pallet_automation_time::migrations::v3::MigrateToV3<Runtime>,
+	pallet_automation_time::migrations::v4::MigrateToV4<Runtime>,
+);
+
 /// Opaque types. These are used by the CLI to instantiate machinery that don't need to know
 /// the specifics of the runtime. They can then be made to be agnostic over specific formats
 /// of data like extrinsics, allowing for them to continue syncing the network through upgrades
@@ -173,11 +204,11 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
 	spec_name: create_runtime_str!("neumann"),
 	impl_name: create_runtime_str!("neumann"),
 	authoring_version: 1,
-	spec_version: 297,
+	spec_version: 298,
 	impl_version: 0,
 	apis: RUNTIME_API_VERSIONS,
-	transaction_version: 20,
-	state_version: 0,
+	transaction_version: 21,
+	state_version: 1,
 };
 
 // TODO: Update to `0` once asset_registry genesis is available.
@@ -263,7 +294,7 @@ impl frame_system::Config for Runtime {
 	/// The weight of database operations that the runtime can invoke.
 	type DbWeight = RocksDbWeight;
 	/// The basic call filter to use in dispatchable.
-	type BaseCallFilter = Valve;
+	type BaseCallFilter = InsideBoth<InsideBoth<Valve, SafeMode>, InflationBounds>;
 	/// Weight information for the extrinsics of this pallet.
 	type SystemWeightInfo = ();
 	/// Block & extrinsics weights: base values and limits.
@@ -426,6 +457,28 @@ impl pallet_proxy::Config for Runtime {
 	type AnnouncementDepositFactor = AnnouncementDepositFactor;
 }
 
+parameter_types! {
+	// One storage item; key size is 32; value is a bounded friends list plus deposit and threshold.
+	pub const ConfigDepositBase: Balance = deposit(1, 88);
+	// Additional storage of 32 bytes per friend.
+	pub const FriendDepositFactor: Balance = deposit(0, 32);
+	// One storage item; key size is 32 * 2; value is the active recovery and its vouching friends.
+	pub const RecoveryDeposit: Balance = deposit(1, 88);
+}
+
+/// Recovered accounts keep their existing automation tasks: `as_recovered` dispatches with the
+/// lost account's signed origin, which is the owner every task is keyed by.
+impl pallet_recovery::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = pallet_recovery::weights::SubstrateWeight<Runtime>;
+	type RuntimeCall = RuntimeCall;
+	type Currency = Balances;
+	type ConfigDepositBase = ConfigDepositBase;
+	type FriendDepositFactor = FriendDepositFactor;
+	type MaxFriends = ConstU32<9>;
+	type RecoveryDeposit = RecoveryDeposit;
+}
+
 parameter_types! {
 	pub TreasuryAccount: AccountId = TreasuryPalletId::get().into_account_truncating();
 }
@@ -497,12 +550,25 @@ impl orml_asset_registry::Config for Runtime {
 parameter_types! {
 	pub const TransactionByteFee: Balance = 0;
 	pub const WeightToFeeScalar: Balance = 6;
+	/// Share of fees burned until governance sets a different ratio.
+	pub const DefaultFeeBurnPercentage: Perbill = Perbill::from_percent(20);
+	/// The largest share of fees governance may burn.
+	pub const MaxFeeBurnPercentage: Perbill = Perbill::from_percent(50);
 }
 
+/// Share of transaction fees and tips that is burned, the rest goes to the treasury.
+pub type InclusionFeeBurn =
+	ParameterOr<Runtime, InclusionFeeBurnPercentageKey, DefaultFeeBurnPercentage>;
+/// Share of native XCM and automation execution fees that is burned.
+pub type ExecutionFeeBurn =
+	ParameterOr<Runtime, ExecutionFeeBurnPercentageKey, DefaultFeeBurnPercentage>;
+
 impl pallet_transaction_payment::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
-	type OnChargeTransaction =
-		pallet_transaction_payment::CurrencyAdapter<Balances, DealWithInclusionFees<Runtime>>;
+	type OnChargeTransaction = pallet_transaction_payment::CurrencyAdapter<
+		Balances,
+		DealWithInclusionFees<Runtime, InclusionFeeBurn>,
+	>;
 	type WeightToFee = ConstantMultiplier<Balance, WeightToFeeScalar>;
 	type LengthToFee = ConstantMultiplier<Balance, TransactionByteFee>;
 	type FeeMultiplierUpdate = SlowAdjustingFeeUpdate<Self>;
@@ -519,9 +585,9 @@ impl cumulus_pallet_parachain_system::Config for Runtime {
 	type OnSystemEvent = ();
 	type SelfParaId = parachain_info::Pallet<Runtime>;
 	type OutboundXcmpMessageSource = XcmpQueue;
-	type DmpMessageHandler = DmpQueue;
+	type DmpMessageHandler = EnqueueDmp<Runtime, MessageQueue>;
 	type ReservedDmpWeight = ReservedDmpWeight;
-	type XcmpMessageHandler = XcmpQueue;
+	type XcmpMessageHandler = EnqueueXcmp<Runtime, MessageQueue, XcmpQueue>;
 	type ReservedXcmpWeight = ReservedXcmpWeight;
 	type CheckAssociatedRelayNumber = RelayNumberStrictlyIncreases;
 }
@@ -560,7 +626,7 @@ parameter_types! {
 impl pallet_parachain_staking::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type Currency = Balances;
-	type MonetaryGovernanceOrigin = EnsureRoot<AccountId>;
+	type MonetaryGovernanceOrigin = MoreThanHalfCouncil;
 	/// Minimum round length is 2 minutes (10 * 12 second block times)
 	type MinBlocksPerRound = ConstU32<10>;
 	/// Rounds before the collator leaving the candidates request can be executed
@@ -598,6 +664,25 @@ impl pallet_parachain_staking::Config for Runtime {
 	type WeightInfo = pallet_parachain_staking::weights::SubstrateWeight<Runtime>;
 }
 
+parameter_types! {
+	/// The highest annual staking inflation the council may set.
+	pub const MaxAnnualInflation: Perbill = Perbill::from_percent(10);
+}
+
+/// Rejects staking inflation schedules above `MaxAnnualInflation`.
+/// Council motions pass through the call filter, a root referendum is not bound by it.
+pub struct InflationBounds;
+impl Contains<RuntimeCall> for InflationBounds {
+	fn contains(c: &RuntimeCall) -> bool {
+		match c {
+			RuntimeCall::ParachainStaking(pallet_parachain_staking::Call::set_inflation {
+				schedule,
+			}) => schedule.max <= MaxAnnualInflation::get(),
+			_ => true,
+		}
+	}
+}
+
 parameter_types! {
 	pub const CouncilMotionDuration: BlockNumber = 4 * MINUTES;
 	pub MaxProposalWeight: Weight = Perbill::from_percent(50) * RuntimeBlockWeights::get().max_block;
@@ -655,6 +740,11 @@ type MoreThanHalfCouncil = EitherOfDiverse<
 	pallet_collective::EnsureProportionMoreThan<AccountId, CouncilCollective, 1, 2>,
 >;
 
+type MoreThanHalfCouncilOrTechnicalCommittee = EitherOfDiverse<
+	MoreThanHalfCouncil,
+	pallet_collective::EnsureProportionMoreThan<AccountId, TechnicalCollective, 1, 2>,
+>;
+
 impl pallet_membership::Config<pallet_membership::Instance1> for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type AddOrigin = MoreThanHalfCouncil;
@@ -668,6 +758,19 @@ impl pallet_membership::Config<pallet_membership::Instance1> for Runtime {
 	type WeightInfo = pallet_membership::weights::SubstrateWeight<Runtime>;
 }
 
+impl pallet_membership::Config<pallet_membership::Instance2> for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type AddOrigin = MoreThanHalfCouncil;
+	type RemoveOrigin = MoreThanHalfCouncil;
+	type SwapOrigin = MoreThanHalfCouncil;
+	type ResetOrigin = MoreThanHalfCouncil;
+	type PrimeOrigin = MoreThanHalfCouncil;
+	type MembershipInitialized = ();
+	type MembershipChanged = ();
+	type MaxMembers = ConstU32<100>;
+	type WeightInfo = pallet_membership::weights::SubstrateWeight<Runtime>;
+}
+
 impl pallet_sudo::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type RuntimeCall = RuntimeCall;
@@ -692,6 +795,8 @@ parameter_types! {
 	pub CuratorDepositMin: Balance = DOLLAR;
 	pub CuratorDepositMax: Balance = 100 * DOLLAR;
 	pub const BountyValueMinimum: Balance = 5 * UNIT;
+	/// The most a council majority may pay out with `Treasury::spend`, without a proposal bond.
+	pub const MaxSmallSpend: Balance = 1_000 * DOLLAR;
 }
 
 impl pallet_treasury::Config for Runtime {
@@ -716,7 +821,20 @@ impl pallet_treasury::Config for Runtime {
 	type SpendFunds = Bounties;
 	type WeightInfo = pallet_treasury::weights::SubstrateWeight<Runtime>;
 	type MaxApprovals = ConstU32<100>;
-	type SpendOrigin = frame_support::traits::NeverEnsureOrigin<Balance>; // Same as Polkadot
+	type SpendOrigin = EnsureWithSuccess<MoreThanHalfCouncil, AccountId, MaxSmallSpend>;
+}
+
+/// Technical committee members tip oracle operators and infrastructure providers. Anyone can
+/// close a tip once its countdown ends, including an automation task scheduled by the finder.
+impl pallet_tips::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type DataDepositPerByte = DataDepositPerByte;
+	type MaximumReasonLength = ConstU32<16384>;
+	type Tippers = TechnicalMembership;
+	type TipCountdown = TipCountdown;
+	type TipFindersFee = TipFindersFee;
+	type TipReportDepositBase = TipReportDepositBase;
+	type WeightInfo = pallet_tips::weights::SubstrateWeight<Runtime>;
 }
 
 parameter_types! {
@@ -735,7 +853,12 @@ impl pallet_preimage::Config for Runtime {
 }
 
 parameter_types! {
-	pub MaximumSchedulerWeight: Weight = Perbill::from_percent(40) * RuntimeBlockWeights::get().max_block;
+	/// Weight reserved in `on_initialize` for running automation time and automation price tasks.
+	pub AutomationTasksWeight: Weight = (SCHEDULED_TASKS_INITIALIZE_RATIO * MAXIMUM_BLOCK_WEIGHT).saturating_mul(2);
+	/// Governance calls dispatched by the scheduler run in the same `on_initialize` as the
+	/// automation tasks, so their budget leaves room for the automation pallets.
+	pub MaximumSchedulerWeight: Weight = (Perbill::from_percent(40) * RuntimeBlockWeights::get().max_block)
+		.saturating_sub(AutomationTasksWeight::get());
 	pub const NoPreimagePostponement: Option<u32> = Some(10);
 }
 
@@ -848,10 +971,19 @@ impl pallet_democracy::Config for Runtime {
 parameter_types! {
 	pub const MaxScheduleSeconds: u64 = 7 * 24 * 60 * 60;	// 7 days in seconds
 	pub const SlotSizeSeconds: u64 = 600; // 10 minutes in seconds
-	pub const MaxBlockWeight: u64 = MAXIMUM_BLOCK_WEIGHT.ref_time();
+	pub const AutomationPoolPalletId: PalletId = PalletId(*b"py/autpl");
+	pub const MaxBlockWeight: Weight = MAXIMUM_BLOCK_WEIGHT;
 	pub const MaxWeightPercentage: Perbill = SCHEDULED_TASKS_INITIALIZE_RATIO;
 	pub const UpdateQueueRatio: Perbill = Perbill::from_percent(50);
+	pub const TaskQueuePageSize: u32 = 64;
 	pub const ExecutionWeightFee: Balance = 12;
+	/// Share of native automation fees credited to the author of the block collecting them
+	pub const CollatorAutomationFeeShare: Percent = Percent::from_percent(20);
+	/// Reserved from the owner of an automation task until it is removed and the deposit reclaimed
+	pub const TaskDepositPerItem: Balance = 10 * CENT;
+	pub const TaskDepositPerByte: Balance = deposit(0, 1);
+	/// Reserved from an account for each automation price asset pair it registers
+	pub const AutomationPriceAssetDeposit: Balance = 100 * DOLLAR;
 }
 
 pub struct ScheduleAllowList;
@@ -861,6 +993,8 @@ impl Contains<RuntimeCall> for ScheduleAllowList {
 			RuntimeCall::System(_) => true,
 			RuntimeCall::Balances(_) => true,
 			RuntimeCall::ParachainStaking(_) => true,
+			RuntimeCall::XTokens(_) => true,
+			RuntimeCall::Tips(pallet_tips::Call::close_tip { .. }) => true,
 			_ => false,
 		}
 	}
@@ -895,23 +1029,27 @@ impl TransferCallCreator<MultiAddress<AccountId, ()>, Balance, RuntimeCall>
 
 impl pallet_automation_time::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
-	type MaxTasksPerSlot = ConstU32<576>;
+	type MaxTasksPerSlot = ParameterOr<Runtime, AutomationTimeMaxTasksPerSlotKey, ConstU32<576>>;
+	type MaxTasksPerAccountPerBlock = ConstU32<16>;
 	type MaxExecutionTimes = ConstU32<36>;
-	type MaxScheduleSeconds = MaxScheduleSeconds;
+	type MaxScheduleSeconds =
+		ParameterOr<Runtime, AutomationTimeMaxScheduleSecondsKey, MaxScheduleSeconds>;
 	type MaxBlockWeight = MaxBlockWeight;
-	type MaxWeightPercentage = MaxWeightPercentage;
+	type MaxWeightPercentage =
+		ParameterOr<Runtime, AutomationTimeMaxWeightPercentageKey, MaxWeightPercentage>;
 	// Roughly .125% of parachain block weight per hour
 	// ≈ 500_000_000_000 (MaxBlockWeight) * 300 (Blocks/Hour) * .00125
 	type MaxWeightPerSlot = ConstU128<150_000_000_000>;
 	type SlotSizeSeconds = SlotSizeSeconds;
 	type UpdateQueueRatio = UpdateQueueRatio;
+	type TaskQueuePageSize = TaskQueuePageSize;
 	type WeightInfo = pallet_automation_time::weights::SubstrateWeight<Runtime>;
 	type ExecutionWeightFee = ExecutionWeightFee;
 	type Currency = Balances;
 	type MultiCurrency = Currencies;
 	type CurrencyId = TokenId;
 	type XcmpTransactor = XcmpHandler;
-	type FeeHandler = pallet_automation_time::FeeHandler<Runtime, ToTreasury>;
+	type FeeHandler = pallet_automation_time::FeeHandler<Runtime, ToCollatorAndTreasury>;
 	type DelegatorActions = ParachainStaking;
 	type CurrencyIdConvert = TokenIdConvert;
 	type FeeConversionRateProvider = FeePerSecondProvider;
@@ -922,15 +1060,37 @@ impl pallet_automation_time::Config for Runtime {
 	type TransferCallCreator = MigrationTransferCallCreator;
 	type ReserveProvider = AbsoluteAndRelativeReserveProvider<SelfLocationAbsolute>;
 	type SelfLocation = SelfLocationAbsolute;
+	type PoolPalletId = AutomationPoolPalletId;
+	type OnTaskExecuted = SafeMode;
+	type StorageDeposit = LinearStorageDeposit<TaskDepositPerItem, TaskDepositPerByte>;
+	type RemoteOrigin = pallet_xcm::EnsureXcm<Everything>;
+	type RemoteAccountOf = RemoteLocationToAccountId;
+	type XcmSender = XcmRouter;
+}
+
+parameter_types! {
+	/// Keeps oracle price updates ahead of ordinary transactions when blocks are full.
+	pub const OracleUpdatePriority: TransactionPriority = TransactionPriority::max_value() / 2;
+	/// How long the oracle submissions log keeps a submission for.
+	pub const OracleSubmissionRetention: BlockNumber = 7 * DAYS;
+	/// How old a price can get, in seconds, before it stops triggering tasks.
+	pub const MaxPriceStaleness: u64 = 60 * 60;
+	/// Sorted tasks are split into buckets one whole unit of price wide.
+	pub const PriceBucketWidth: u128 = 1_000_000_000_000;
+	pub const AutomationPricePalletId: PalletId = PalletId(*b"py/autpr");
+	/// Keepers that run a queued task earn a tenth of the fee paid when it was scheduled.
+	pub const KeeperRewardShare: Perbill = Perbill::from_percent(10);
 }
 
 impl pallet_automation_price::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
-	type MaxTasksPerSlot = ConstU32<1>;
+	type MaxTasksPerSlot = ParameterOr<Runtime, AutomationPriceMaxTasksPerSlotKey, ConstU32<1>>;
 	type MaxTasksPerAccount = ConstU32<32>;
 	type MaxTasksOverall = ConstU32<16_384>;
+	type MaxTasksPerAssetPair = ConstU32<4_096>;
 	type MaxBlockWeight = MaxBlockWeight;
-	type MaxWeightPercentage = MaxWeightPercentage;
+	type MaxWeightPercentage =
+		ParameterOr<Runtime, AutomationPriceMaxWeightPercentageKey, MaxWeightPercentage>;
 	type WeightInfo = pallet_automation_price::weights::SubstrateWeight<Runtime>;
 	type ExecutionWeightFee = ExecutionWeightFee;
 	type Currency = Balances;
@@ -939,10 +1099,78 @@ impl pallet_automation_price::Config for Runtime {
 	type XcmpTransactor = XcmpHandler;
 	type CurrencyIdConvert = TokenIdConvert;
 	type FeeConversionRateProvider = FeePerSecondProvider;
-	type FeeHandler = pallet_automation_price::FeeHandler<Runtime, ToTreasury>;
+	type FeeHandler = pallet_automation_price::FeeHandler<Runtime, ToCollatorAndTreasury>;
 	type UniversalLocation = UniversalLocation;
 	type SelfParaId = parachain_info::Pallet<Runtime>;
 	type EnsureProxy = AutomationEnsureProxy;
+	type OracleCommittee = OracleMembership;
+	type OnTaskExecuted = SafeMode;
+	type PurgeOrigin = MoreThanHalfCouncilOrTechnicalCommittee;
+	type ShutdownOrigin = MoreThanHalfCouncilOrTechnicalCommittee;
+	type MaxTasksPerPurge = ConstU32<200>;
+	type MaxShiftedTasksPerAssetPair = ConstU32<32>;
+	type PriceBucketWidth = PriceBucketWidth;
+	type MaxXcmpSendAttempts = ConstU32<5>;
+	type XcmpRetryDelay = ConstU32<2>;
+	type MaxXcmpRetries = ConstU32<256>;
+	type StorageDeposit = LinearStorageDeposit<TaskDepositPerItem, TaskDepositPerByte>;
+	type OracleUpdatePriority = OracleUpdatePriority;
+	type WaiveOracleFees = ConstBool<true>;
+	type OracleSubmissionRetention = OracleSubmissionRetention;
+	type ConditionEvaluator = pallet_automation_price::DefaultConditionEvaluator;
+	type MaxExtraConditions = ConstU32<4>;
+	type Randomness = RandomnessCollectiveFlip;
+	type MaxPriceHistory = ConstU32<48>;
+	type RuntimeCall = RuntimeCall;
+	type ScheduleAllowList = ScheduleAllowList;
+	type OracleQuorum = ConstU32<1>;
+	type PriceAggregator = pallet_automation_price::MedianPrice;
+	type MaxPriceStaleness = MaxPriceStaleness;
+	type OracleProviderOrigin = MoreThanHalfCouncil;
+	type AssetAdminOrigin = MoreThanHalfCouncilOrTechnicalCommittee;
+	type AssetDeposit = AutomationPriceAssetDeposit;
+	type AssetDepositSlash = Treasury;
+	type TipDestination = Treasury;
+	type PalletId = AutomationPricePalletId;
+	type KeeperRewardShare = KeeperRewardShare;
+	type MaxTasksPerKeeperCall = ConstU32<64>;
+	type MaxAssets = ConstU32<256>;
+	type OracleSignature = Signature;
+	type OracleSigner = sp_runtime::MultiSigner;
+	type UnsignedSubmissionInterval = ConstU32<10>;
+	type PriceDecimals = ConstU8<12>;
+}
+
+impl pallet_insecure_randomness_collective_flip::Config for Runtime {}
+
+parameter_types! {
+	pub const DepositPerItem: Balance = deposit(1, 0);
+	pub const DepositPerByte: Balance = deposit(0, 1);
+	pub const DefaultDepositLimit: Balance = deposit(1024, 1024 * 1024);
+	pub Schedule: pallet_contracts::Schedule<Runtime> = Default::default();
+}
+
+impl pallet_contracts::Config for Runtime {
+	type Time = Timestamp;
+	type Randomness = RandomnessCollectiveFlip;
+	type Currency = Balances;
+	type RuntimeEvent = RuntimeEvent;
+	type RuntimeCall = RuntimeCall;
+	/// Contracts reach the runtime only through the chain extension.
+	type CallFilter = Nothing;
+	type DepositPerItem = DepositPerItem;
+	type DepositPerByte = DepositPerByte;
+	type DefaultDepositLimit = DefaultDepositLimit;
+	type CallStack = [pallet_contracts::Frame<Self>; 5];
+	type WeightPrice = pallet_transaction_payment::Pallet<Self>;
+	type WeightInfo = pallet_contracts::weights::SubstrateWeight<Self>;
+	type ChainExtension = AutomationExtension<Self>;
+	type Schedule = Schedule;
+	type AddressGenerator = pallet_contracts::DefaultAddressGenerator;
+	type MaxCodeLen = ConstU32<{ 123 * 1024 }>;
+	type MaxStorageKeyLen = ConstU32<128>;
+	type UnsafeUnstableInterface = ConstBool<false>;
+	type MaxDebugBufferLen = ConstU32<{ 2 * 1024 * 1024 }>;
 }
 
 pub struct ClosedCallFilter;
@@ -954,13 +1182,29 @@ impl Contains<RuntimeCall> for ClosedCallFilter {
 			RuntimeCall::Balances(_) => false,
 			RuntimeCall::Bounties(_) => false,
 			RuntimeCall::ParachainStaking(_) => false,
+			RuntimeCall::Tips(_) => false,
 			RuntimeCall::Treasury(_) => false,
+			RuntimeCall::Vesting(_) => false,
+			RuntimeCall::AutomationPrice(pallet_automation_price::Call::purge_tasks { .. }) => true,
 			RuntimeCall::AutomationPrice(_) => false,
+			RuntimeCall::Contracts(_) => false,
 			_ => true,
 		}
 	}
 }
 
+pub struct UtilityBatchedCalls;
+impl pallet_valve::BatchedCalls<RuntimeCall> for UtilityBatchedCalls {
+	fn batched_calls(call: &RuntimeCall) -> Option<&[RuntimeCall]> {
+		match call {
+			RuntimeCall::Utility(pallet_utility::Call::batch { calls }) |
+			RuntimeCall::Utility(pallet_utility::Call::batch_all { calls }) |
+			RuntimeCall::Utility(pallet_utility::Call::force_batch { calls }) => Some(calls.as_slice()),
+			_ => None,
+		}
+	}
+}
+
 impl pallet_valve::Config for Runtime {
 	type RuntimeEvent = RuntimeEvent;
 	type WeightInfo = pallet_valve::weights::SubstrateWeight<Runtime>;
@@ -968,6 +1212,81 @@ impl pallet_valve::Config for Runtime {
 	type AutomationTime = AutomationTime;
 	type AutomationPrice = AutomationPrice;
 	type CallAccessFilter = TechnicalMembership;
+	type BatchedCalls = UtilityBatchedCalls;
+}
+
+/// Calls that remain available while the chain is in safe mode.
+pub struct SafeModeWhitelistedCalls;
+impl Contains<RuntimeCall> for SafeModeWhitelistedCalls {
+	fn contains(c: &RuntimeCall) -> bool {
+		match c {
+			RuntimeCall::System(_) => true,
+			RuntimeCall::Timestamp(_) => true,
+			RuntimeCall::ParachainSystem(_) => true,
+			RuntimeCall::Sudo(_) => true,
+			RuntimeCall::Council(_) => true,
+			RuntimeCall::TechnicalCommittee(_) => true,
+			RuntimeCall::TechnicalMembership(_) => true,
+			RuntimeCall::Democracy(_) => true,
+			RuntimeCall::Preimage(_) => true,
+			RuntimeCall::Scheduler(_) => true,
+			RuntimeCall::Valve(_) => true,
+			RuntimeCall::SafeMode(_) => true,
+			RuntimeCall::Parameters(_) => true,
+			RuntimeCall::AutomationPrice(pallet_automation_price::Call::purge_tasks { .. }) => true,
+			_ => false,
+		}
+	}
+}
+
+parameter_types! {
+	pub const SafeModeFailureRateThreshold: Perbill = Perbill::from_percent(50);
+}
+
+impl pallet_safe_mode::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = pallet_safe_mode::weights::SubstrateWeight<Runtime>;
+	type WhitelistedCalls = SafeModeWhitelistedCalls;
+	type ForceOrigin = MoreThanHalfCouncil;
+	type FailureRateThreshold =
+		ParameterOr<Runtime, SafeModeFailureRateThresholdKey, SafeModeFailureRateThreshold>;
+	type MinExecutionsPerBlock =
+		ParameterOr<Runtime, SafeModeMinExecutionsPerBlockKey, ConstU32<10>>;
+	type TripAfterBlocks = ParameterOr<Runtime, SafeModeTripAfterBlocksKey, ConstU32<10>>;
+}
+
+parameter_types! {
+	pub const AutomationTimeMaxTasksPerSlotKey: ParameterKey = ParameterKey::AutomationTimeMaxTasksPerSlot;
+	pub const AutomationTimeMaxWeightPercentageKey: ParameterKey = ParameterKey::AutomationTimeMaxWeightPercentage;
+	pub const AutomationTimeMaxScheduleSecondsKey: ParameterKey = ParameterKey::AutomationTimeMaxScheduleSeconds;
+	pub const AutomationPriceMaxTasksPerSlotKey: ParameterKey = ParameterKey::AutomationPriceMaxTasksPerSlot;
+	pub const AutomationPriceMaxWeightPercentageKey: ParameterKey = ParameterKey::AutomationPriceMaxWeightPercentage;
+	pub const SafeModeFailureRateThresholdKey: ParameterKey = ParameterKey::SafeModeFailureRateThreshold;
+	pub const SafeModeMinExecutionsPerBlockKey: ParameterKey = ParameterKey::SafeModeMinExecutionsPerBlock;
+	pub const SafeModeTripAfterBlocksKey: ParameterKey = ParameterKey::SafeModeTripAfterBlocks;
+	pub const InclusionFeeBurnPercentageKey: ParameterKey = ParameterKey::InclusionFeeBurnPercentage;
+	pub const ExecutionFeeBurnPercentageKey: ParameterKey = ParameterKey::ExecutionFeeBurnPercentage;
+}
+
+/// Keeps the fee burn ratios within `MaxFeeBurnPercentage`.
+pub struct RuntimeParameterBounds;
+impl pallet_parameters::ParameterBounds for RuntimeParameterBounds {
+	fn within_bounds(key: ParameterKey, value: ParameterValue) -> bool {
+		match (key, value) {
+			(
+				ParameterKey::InclusionFeeBurnPercentage | ParameterKey::ExecutionFeeBurnPercentage,
+				ParameterValue::Perbill(burn),
+			) => burn <= MaxFeeBurnPercentage::get(),
+			_ => true,
+		}
+	}
+}
+
+impl pallet_parameters::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = pallet_parameters::weights::SubstrateWeight<Runtime>;
+	type UpdateOrigin = MoreThanHalfCouncil;
+	type Bounds = RuntimeParameterBounds;
 }
 
 impl pallet_vesting::Config for Runtime {
@@ -983,6 +1302,27 @@ impl pallet_utility::Config for Runtime {
 	type WeightInfo = pallet_utility::weights::SubstrateWeight<Runtime>;
 }
 
+parameter_types! {
+	pub const MigrationSignedDepositPerItem: Balance = CENT;
+	pub const MigrationSignedDepositBase: Balance = 20 * DOLLAR;
+	pub const MigrationMaxKeyLen: u32 = 512;
+	// Migrate at most 160 keys or 200 KiB of the trie in each block
+	pub const StateTrieMigrationLimits: MigrationLimits =
+		MigrationLimits { size: 200 * 1024, item: 160 };
+}
+
+impl pallet_state_trie_migration::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type ControlOrigin = EnsureRoot<AccountId>;
+	type Currency = Balances;
+	type MaxKeyLen = MigrationMaxKeyLen;
+	type SignedDepositPerItem = MigrationSignedDepositPerItem;
+	type SignedDepositBase = MigrationSignedDepositBase;
+	/// Only technical committee members may submit signed migrations.
+	type SignedFilter = EnsureSignedBy<TechnicalMembership, AccountId>;
+	type WeightInfo = pallet_state_trie_migration::weights::SubstrateWeight<Runtime>;
+}
+
 // Create the runtime by composing the FRAME pallets that were previously configured.
 construct_runtime!(
 	pub enum Runtime where
@@ -1017,6 +1357,9 @@ construct_runtime!(
 		Identity: pallet_identity::{Pallet, Call, Storage, Event<T>} = 31,
 		Proxy: pallet_proxy::{Pallet, Call, Storage, Event<T>} = 32,
 		Utility: pallet_utility::{Pallet, Call, Event} = 33,
+		StateTrieMigration: pallet_state_trie_migration::{Pallet, Call, Storage, Event<T>} = 34,
+		RandomnessCollectiveFlip: pallet_insecure_randomness_collective_flip::{Pallet, Storage} = 35,
+		Recovery: pallet_recovery::{Pallet, Call, Storage, Event<T>} = 36,
 
 		// XCM helpers.
 		XcmpQueue: cumulus_pallet_xcmp_queue::{Pallet, Call, Storage, Event<T>} = 40,
@@ -1025,9 +1368,11 @@ construct_runtime!(
 		DmpQueue: cumulus_pallet_dmp_queue::{Pallet, Call, Storage, Event<T>} = 43,
 		XTokens: orml_xtokens::{Pallet, Storage, Call, Event<T>} = 44,
 		UnknownTokens: orml_unknown_tokens::{Pallet, Storage, Event} = 45,
+		MessageQueue: pallet_message_queue::{Pallet, Call, Storage, Event<T>} = 46,
 
 		// Support pallets.
 		Sudo: pallet_sudo::{Pallet, Call, Storage, Event<T>, Config<T>} = 50,
+		Tips: pallet_tips::{Pallet, Call, Storage, Event<T>} = 49,
 		Treasury: pallet_treasury::{Pallet, Call, Storage, Config, Event<T>} = 51,
 		Council: pallet_collective::<Instance1>::{Pallet, Call, Storage, Event<T>, Origin<T>, Config<T>} = 52,
 		TechnicalCommittee: pallet_collective::<Instance2>::{Pallet, Call, Storage, Event<T>, Origin<T>, Config<T>} = 53,
@@ -1039,13 +1384,66 @@ construct_runtime!(
 
 		//custom pallets
 		AutomationTime: pallet_automation_time::{Pallet, Call, Storage, Event<T>} = 60,
-		Vesting: pallet_vesting::{Pallet, Storage, Config<T>, Event<T>} = 61,
-		XcmpHandler: pallet_xcmp_handler::{Pallet, Call, Event<T>} = 62,
-
-		AutomationPrice: pallet_automation_price::{Pallet, Call, Storage, Event<T>} = 200,
+		Vesting: pallet_vesting::{Pallet, Call, Storage, Config<T>, Event<T>} = 61,
+		XcmpHandler: pallet_xcmp_handler::{Pallet, Call, Storage, Event<T>} = 62,
+		SafeMode: pallet_safe_mode::{Pallet, Call, Storage, Event<T>} = 63,
+		Parameters: pallet_parameters::{Pallet, Call, Storage, Event<T>} = 64,
+		Contracts: pallet_contracts::{Pallet, Call, Storage, Event<T>} = 65,
+
+		AutomationPrice: pallet_automation_price::{Pallet, Call, Storage, Event<T>, Config<T>, ValidateUnsigned} = 200,
+		OracleMembership: pallet_membership::<Instance2>::{Pallet, Call, Storage, Event<T>, Config<T>} = 201,
 	}
 );
 
+// Contract debug output is only returned by dry runs through the runtime API.
+const CONTRACTS_DEBUG_OUTPUT: bool = true;
+
+// A price of an asset pair given out by the runtime API
+fn automation_price_data(
+	price: pallet_automation_price::PriceData,
+) -> pallet_automation_price_rpc_runtime_api::PriceData {
+	pallet_automation_price_rpc_runtime_api::PriceData {
+		round: price.round,
+		updated_at: price.updated_at,
+		value: price.value,
+	}
+}
+
+// The details of a price task given out by the runtime API
+fn automation_price_task_details(
+	task: pallet_automation_price::Task<Runtime>,
+) -> pallet_automation_price_rpc_runtime_api::TaskDetails<AccountId, Hash> {
+	use pallet_automation_price::{Action, TaskStatus};
+	use pallet_automation_price_rpc_runtime_api::{
+		TaskActionKind, TaskDetails, TaskStatus as Status,
+	};
+
+	let status = match AutomationPrice::get_task_status(&task) {
+		TaskStatus::Scheduled => Status::Scheduled,
+		TaskStatus::Queued => Status::Queued,
+		TaskStatus::CoolingDown => Status::CoolingDown,
+		TaskStatus::RetryingXcmSend => Status::RetryingXcmSend,
+		TaskStatus::Paused => Status::Paused,
+		TaskStatus::Expired => Status::Expired,
+	};
+	let action = match task.action {
+		Action::XCMP { .. } => TaskActionKind::Xcmp,
+		Action::DynamicDispatch { .. } => TaskActionKind::DynamicDispatch,
+	};
+	TaskDetails {
+		owner_id: task.owner_id,
+		task_id: Hash::from(task.task_id),
+		chain: task.chain,
+		exchange: task.exchange,
+		asset_pair: task.asset_pair,
+		trigger_function: task.trigger_function,
+		trigger_params: task.trigger_params,
+		action,
+		expired_at: task.expired_at,
+		status,
+	}
+}
+
 impl_runtime_apis! {
 	impl sp_consensus_aura::AuraApi<Block, AuraId> for Runtime {
 		fn slot_duration() -> sp_consensus_aura::SlotDuration {
@@ -1176,6 +1574,17 @@ impl_runtime_apis! {
 			Account32Hash::<RelayNetwork, sp_runtime::AccountId32>::convert_ref(multiloc)
 				.map_err(|_| "unable to convert account".into())
 		}
+
+		fn derive_remote_account(account_id: AccountId32, destination: xcm::VersionedMultiLocation) -> Result<AccountId32, Vec<u8>> {
+			use primitives::{DescribeAllTerminal, DescribeFamily, HashedDescription};
+
+			let destination = MultiLocation::try_from(destination).map_err(|()| "Unable to convert VersionedMultiLocation".as_bytes())?;
+			XcmpHandler::derive_remote_account::<
+				AccountId32,
+				HashedDescription<AccountId32, DescribeFamily<DescribeAllTerminal>>,
+			>(account_id, destination)
+			.map_err(|_| "unable to derive remote account".into())
+		}
 	}
 
 	impl pallet_automation_time_rpc_runtime_api::AutomationTimeApi<Block, AccountId, Hash, Balance> for Runtime {
@@ -1195,6 +1604,9 @@ impl_runtime_apis! {
 				},
 				RuntimeCall::AutomationTime(pallet_automation_time::Call::schedule_dynamic_dispatch_task{
 					call, schedule, ..
+				}) |
+				RuntimeCall::AutomationTime(pallet_automation_time::Call::schedule_pool_task{
+					call, schedule, ..
 				}) => {
 					let action = Action::DynamicDispatch { encoded_call: call.encode() };
 					Ok((action, schedule.number_of_executions()))
@@ -1252,6 +1664,14 @@ impl_runtime_apis! {
 			use pallet_automation_price::Action;
 
 			let action = match uxt.function {
+				RuntimeCall::AutomationPrice(pallet_automation_price::Call::schedule_xcmp_task{
+					destination, schedule_fee, execution_fee, encoded_call, encoded_call_weight, overall_weight, ..
+				}) => {
+					let destination = MultiLocation::try_from(*destination).map_err(|()| "Unable to convert VersionedMultiLocation".as_bytes())?;
+					let schedule_fee = MultiLocation::try_from(*schedule_fee).map_err(|()| "Unable to convert VersionedMultiLocation".as_bytes())?;
+					let action = Action::XCMP { destination, schedule_fee, execution_fee: *execution_fee, encoded_call, encoded_call_weight, overall_weight, schedule_as: None, instruction_sequence: InstructionSequence::PayThroughSovereignAccount };
+					Ok(action)
+				},
 				RuntimeCall::AutomationPrice(pallet_automation_price::Call::schedule_xcmp_task_through_proxy{
 					chain,
 					exchange,
@@ -1274,14 +1694,128 @@ impl_runtime_apis! {
 			let fee_handler = <Self as pallet_automation_price::Config>::FeeHandler::new(&nobody, &action)
 				.map_err(|_| "Unable to parse fee".as_bytes())?;
 
+			// The XCM fee is sent to the destination every time the task runs
+			let xcm_fee = match &action {
+				Action::XCMP { execution_fee, .. } => execution_fee.amount,
+				_ => 0,
+			};
+
 			Ok(AutomationPriceFeeDetails {
 				schedule_fee: fee_handler.schedule_fee_amount,
-				execution_fee: fee_handler.execution_fee_amount
+				execution_fee: fee_handler.execution_fee_amount,
+				xcm_fee,
+			})
+		}
+
+		fn get_asset_metadata(
+			chain: Vec<u8>,
+			exchange: Vec<u8>,
+			asset1: Vec<u8>,
+			asset2: Vec<u8>,
+		) -> Option<pallet_automation_price_rpc_runtime_api::AssetMetadata> {
+			AutomationPrice::get_asset_metadata(chain, exchange, asset1, asset2).map(|metadata| {
+				pallet_automation_price_rpc_runtime_api::AssetMetadata {
+					symbol: metadata.symbol,
+					decimal: metadata.decimal,
+				}
 			})
 		}
+
+		fn get_price(
+			chain: Vec<u8>,
+			exchange: Vec<u8>,
+			asset1: Vec<u8>,
+			asset2: Vec<u8>,
+		) -> Option<pallet_automation_price_rpc_runtime_api::PriceData> {
+			AutomationPrice::get_asset_price_data((chain, exchange, (asset1, asset2)))
+				.map(automation_price_data)
+		}
+
+		fn get_price_history(
+			chain: Vec<u8>,
+			exchange: Vec<u8>,
+			asset1: Vec<u8>,
+			asset2: Vec<u8>,
+		) -> Vec<pallet_automation_price_rpc_runtime_api::PriceData> {
+			AutomationPrice::get_price_history((chain, exchange, (asset1, asset2)))
+				.into_iter()
+				.map(automation_price_data)
+				.collect()
+		}
+
+		fn get_task(task_id: Hash) -> Option<pallet_automation_price_rpc_runtime_api::TaskDetails<AccountId, Hash>> {
+			AutomationPrice::find_task(&task_id.to_fixed_bytes()).map(automation_price_task_details)
+		}
+
+		fn get_account_tasks(account_id: AccountId) -> Vec<pallet_automation_price_rpc_runtime_api::TaskDetails<AccountId, Hash>> {
+			pallet_automation_price::Tasks::<Runtime>::iter_prefix_values(account_id)
+				.map(automation_price_task_details)
+				.collect()
+		}
 	}
 
 
+	impl pallet_contracts::ContractsApi<Block, AccountId, Balance, BlockNumber, Hash> for Runtime {
+		fn call(
+			origin: AccountId,
+			dest: AccountId,
+			value: Balance,
+			gas_limit: Option<Weight>,
+			storage_deposit_limit: Option<Balance>,
+			input_data: Vec<u8>,
+		) -> pallet_contracts_primitives::ContractExecResult<Balance> {
+			let gas_limit = gas_limit.unwrap_or(RuntimeBlockWeights::get().max_block);
+			Contracts::bare_call(
+				origin,
+				dest,
+				value,
+				gas_limit,
+				storage_deposit_limit,
+				input_data,
+				CONTRACTS_DEBUG_OUTPUT,
+				pallet_contracts::Determinism::Enforced,
+			)
+		}
+
+		fn instantiate(
+			origin: AccountId,
+			value: Balance,
+			gas_limit: Option<Weight>,
+			storage_deposit_limit: Option<Balance>,
+			code: pallet_contracts_primitives::Code<Hash>,
+			data: Vec<u8>,
+			salt: Vec<u8>,
+		) -> pallet_contracts_primitives::ContractInstantiateResult<AccountId, Balance> {
+			let gas_limit = gas_limit.unwrap_or(RuntimeBlockWeights::get().max_block);
+			Contracts::bare_instantiate(
+				origin,
+				value,
+				gas_limit,
+				storage_deposit_limit,
+				code,
+				data,
+				salt,
+				CONTRACTS_DEBUG_OUTPUT,
+			)
+		}
+
+		fn upload_code(
+			origin: AccountId,
+			code: Vec<u8>,
+			storage_deposit_limit: Option<Balance>,
+			determinism: pallet_contracts::Determinism,
+		) -> pallet_contracts_primitives::CodeUploadResult<Hash, Balance> {
+			Contracts::bare_upload_code(origin, code, storage_deposit_limit, determinism)
+		}
+
+		fn get_storage(
+			address: AccountId,
+			key: Vec<u8>,
+		) -> pallet_contracts_primitives::GetStorageResult {
+			Contracts::get_storage(address, key)
+		}
+	}
+
 	impl cumulus_primitives_core::CollectCollationInfo<Block> for Runtime {
 		fn collect_collation_info(header: &<Block as BlockT>::Header) -> cumulus_primitives_core::CollationInfo {
 			ParachainSystem::collect_collation_info(header)
@@ -1308,7 +1842,10 @@ impl_runtime_apis! {
 			list_benchmark!(list, extra, pallet_automation_time, AutomationTime::<Runtime>);
 			list_benchmark!(list, extra, pallet_automation_price, AutomationPrice::<Runtime>);
 			list_benchmark!(list, extra, pallet_valve, Valve::<Runtime>);
+			list_benchmark!(list, extra, pallet_safe_mode, SafeMode::<Runtime>);
+			list_benchmark!(list, extra, pallet_parameters, Parameters::<Runtime>);
 			list_benchmark!(list, extra, pallet_vesting, Vesting::<Runtime>);
+			list_benchmark!(list, extra, pallet_xcmp_handler, XcmpHandler::<Runtime>);
 			list_benchmark!(list, extra, pallet_parachain_staking, ParachainStaking::<Runtime>);
 
 			let storage_info = AllPalletsWithSystem::storage_info();
@@ -1349,7 +1886,10 @@ impl_runtime_apis! {
 			add_benchmark!(params, batches, pallet_automation_price, AutomationPrice::<Runtime>);
 
 			add_benchmark!(params, batches, pallet_valve, Valve::<Runtime>);
+			add_benchmark!(params, batches, pallet_safe_mode, SafeMode::<Runtime>);
+			add_benchmark!(params, batches, pallet_parameters, Parameters::<Runtime>);
 			add_benchmark!(params, batches, pallet_vesting, Vesting::<Runtime>);
+			add_benchmark!(params, batches, pallet_xcmp_handler, XcmpHandler::<Runtime>);
 			add_benchmark!(params, batches, pallet_parachain_staking, ParachainStaking::<Runtime>);
 
 			if batches.is_empty() { return Err("Benchmark not found for this pallet.".into()) }
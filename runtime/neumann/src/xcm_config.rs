@@ -1,18 +1,21 @@
 use super::{
-	AccountId, AllPalletsWithSystem, Balance, Balances, Currencies, ParachainInfo, ParachainSystem,
-	PolkadotXcm, Runtime, RuntimeCall, RuntimeEvent, RuntimeOrigin, TokenId, TreasuryAccount,
-	UniversalLocation, UnknownTokens, XcmpQueue, MAXIMUM_BLOCK_WEIGHT, NATIVE_TOKEN_ID,
+	AccountId, AllPalletsWithSystem, Authorship, Balance, Balances, CollatorAutomationFeeShare,
+	Currencies, ExecutionFeeBurn, MessageQueue, MoreThanHalfCouncil, ParachainInfo,
+	ParachainSystem, PolkadotXcm, Runtime, RuntimeBlockWeights, RuntimeCall, RuntimeEvent,
+	RuntimeOrigin, TokenId, TreasuryAccount, UniversalLocation, UnknownTokens, XcmpQueue,
+	MAXIMUM_BLOCK_WEIGHT, NATIVE_TOKEN_ID,
 };
 
 use frame_support::{
 	match_types, parameter_types,
-	traits::{ConstU32, Everything, Nothing},
+	traits::{ConstU32, Everything, Get, Nothing},
 };
 use frame_system::EnsureRoot;
-use sp_runtime::{traits::Convert, Percent};
+use sp_runtime::{traits::Convert, Perbill};
 
 // Polkadot Imports
 use pallet_xcm::XcmPassthrough;
+use pallet_xcmp_handler::{ReserveCorridors, TeleportCorridors};
 use polkadot_parachain::primitives::Sibling;
 
 // XCM Imports
@@ -20,9 +23,9 @@ use xcm::latest::{prelude::*, Weight};
 use xcm_builder::{
 	AccountId32Aliases, AllowKnownQueryResponses, AllowSubscriptionsFrom,
 	AllowTopLevelPaidExecutionFrom, AllowUnpaidExecutionFrom, EnsureXcmOrigin, FixedWeightBounds,
-	ParentIsPreset, RelayChainAsNative, SiblingParachainAsNative, SiblingParachainConvertsVia,
-	SignedAccountId32AsNative, SignedToAccountId32, SovereignSignedViaLocation, TakeRevenue,
-	TakeWeightCredit, WithComputedOrigin,
+	ParentIsPreset, ProcessXcmMessage, RelayChainAsNative, SiblingParachainAsNative,
+	SiblingParachainConvertsVia, SignedAccountId32AsNative, SignedToAccountId32,
+	SovereignSignedViaLocation, TakeRevenue, TakeWeightCredit, WithComputedOrigin,
 };
 use xcm_executor::{Config, XcmExecutor};
 
@@ -36,6 +39,7 @@ use orml_xcm_support::{
 	DepositToAlternative, IsNativeConcrete, MultiCurrencyAdapter, MultiNativeAsset,
 };
 
+use common_runtime::message_queue::AggregateMessageOrigin;
 use primitives::{
 	AbsoluteAndRelativeReserveProvider, DescribeAllTerminal, DescribeFamily, HashedDescription,
 };
@@ -128,9 +132,9 @@ impl TakeRevenue for ToTreasury {
 		{
 			if let Some(currency_id) = TokenIdConvert::convert(id) {
 				if currency_id == NATIVE_TOKEN_ID {
-					// Deposit to native treasury account
-					// 20% burned, 80% to the treasury
-					let to_treasury = Percent::from_percent(80).mul_floor(amount);
+					// Deposit to native treasury account, the governance-set share is burned
+					let burn = <ExecutionFeeBurn as Get<Perbill>>::get();
+					let to_treasury = burn.left_from_one().mul_floor(amount);
 					// Due to the way XCM works the amount has already been taken off the total allocation balance.
 					// Thus whatever we deposit here gets added back to the total allocation, and the rest is burned.
 					let _ = Currencies::deposit(currency_id, &TreasuryAccount::get(), to_treasury);
@@ -145,6 +149,33 @@ impl TakeRevenue for ToTreasury {
 	}
 }
 
+/// Revenue handler for automation fees.
+/// A share of native token fees is credited to the author of the current block,
+/// the remainder is handled like any other revenue.
+pub struct ToCollatorAndTreasury;
+impl TakeRevenue for ToCollatorAndTreasury {
+	fn take_revenue(revenue: MultiAsset) {
+		if let MultiAsset { id: AssetId::Concrete(id), fun: Fungibility::Fungible(amount) } =
+			&revenue
+		{
+			if let (Some(NATIVE_TOKEN_ID), Some(author)) =
+				(TokenIdConvert::convert(*id), Authorship::author())
+			{
+				let to_author = CollatorAutomationFeeShare::get().mul_floor(*amount);
+				// Fall back to the treasury if the author's account cannot receive the share.
+				if Currencies::deposit(NATIVE_TOKEN_ID, &author, to_author).is_ok() {
+					ToTreasury::take_revenue(MultiAsset {
+						id: AssetId::Concrete(*id),
+						fun: Fungibility::Fungible(amount.saturating_sub(to_author)),
+					});
+					return
+				}
+			}
+		}
+		ToTreasury::take_revenue(revenue)
+	}
+}
+
 type AssetRegistryOf<T> = orml_asset_registry::Pallet<T>;
 
 pub struct FeePerSecondProvider;
@@ -171,9 +202,9 @@ impl Config for XcmConfig {
 	// How to withdraw and deposit an asset.
 	type AssetTransactor = LocalAssetTransactor;
 	type OriginConverter = XcmOriginToTransactDispatchOrigin;
-	type IsReserve = MultiNativeAsset<AbsoluteReserveProvider>;
+	type IsReserve = (MultiNativeAsset<AbsoluteReserveProvider>, ReserveCorridors<Runtime>);
 	// Teleporting is disabled.
-	type IsTeleporter = ();
+	type IsTeleporter = TeleportCorridors<Runtime>;
 	type UniversalLocation = UniversalLocation;
 	type Barrier = Barrier;
 	type Weigher = FixedWeightBounds<UnitWeightCost, RuntimeCall, MaxInstructions>;
@@ -221,7 +252,7 @@ impl pallet_xcm::Config for Runtime {
 	type ExecuteXcmOrigin = EnsureXcmOrigin<RuntimeOrigin, LocalOriginToLocation>;
 	type XcmExecuteFilter = Nothing;
 	type XcmExecutor = XcmExecutor<XcmConfig>;
-	type XcmTeleportFilter = Nothing;
+	type XcmTeleportFilter = TeleportCorridors<Runtime>;
 	type XcmReserveTransferFilter = Everything;
 	type Weigher = FixedWeightBounds<UnitWeightCost, RuntimeCall, MaxInstructions>;
 	type UniversalLocation = UniversalLocation;
@@ -265,16 +296,51 @@ impl cumulus_pallet_dmp_queue::Config for Runtime {
 	type ExecuteOverweightOrigin = EnsureRoot<AccountId>;
 }
 
+parameter_types! {
+	pub MessageQueueServiceWeight: Weight =
+		Perbill::from_percent(25) * RuntimeBlockWeights::get().max_block;
+}
+
+/// Inbound DMP and XCMP messages are executed from here, see `common_runtime::message_queue`.
+/// The DMP queue pallet only drains what it had queued before the switch.
+impl pallet_message_queue::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type WeightInfo = pallet_message_queue::weights::SubstrateWeight<Runtime>;
+	type MessageProcessor =
+		ProcessXcmMessage<AggregateMessageOrigin, XcmExecutor<XcmConfig>, RuntimeCall>;
+	type Size = u32;
+	type QueueChangeHandler = ();
+	type HeapSize = ConstU32<{ 64 * 1024 }>;
+	type MaxStale = ConstU32<8>;
+	type ServiceWeight = MessageQueueServiceWeight;
+}
+
 parameter_types! {
 	pub SelfLocation: MultiLocation = Here.into_location();
 	pub SelfLocationAbsolute: MultiLocation = MultiLocation::new(1, X1(Parachain(ParachainInfo::parachain_id().into())));
 	pub const BaseXcmWeight: Weight = Weight::from_parts(100_000_000, 0);
-	pub const MaxAssetsForTransfer: usize = 1;
+	// The transferred asset plus a separate fee asset, as sent by `transfer_multiasset_with_fee`
+	// and `transfer_multicurrencies`.
+	pub const MaxAssetsForTransfer: usize = 2;
+}
+
+/// Asset Hub is the reserve of its own assets and holds the native token transferred there.
+pub const ASSET_HUB_PARA_ID: u32 = 1000;
+
+parameter_types! {
+	pub AssetHubLocation: MultiLocation = MultiLocation::new(1, X1(Parachain(ASSET_HUB_PARA_ID)));
+	// Paid, in the transferred asset, for the reserve hop when an Asset Hub asset is sent
+	// on to a third chain.
+	pub const AssetHubMinXcmFee: u128 = 4_000_000_000;
 }
 
 parameter_type_with_key! {
-	pub ParachainMinFee: |_location: MultiLocation| -> Option<u128> {
-		None
+	pub ParachainMinFee: |location: MultiLocation| -> Option<u128> {
+		if *location == AssetHubLocation::get() {
+			Some(AssetHubMinXcmFee::get())
+		} else {
+			None
+		}
 	};
 }
 
@@ -318,6 +384,8 @@ impl pallet_xcmp_handler::Config for Runtime {
 	type Weigher = FixedWeightBounds<UnitWeightCost, RuntimeCall, MaxInstructions>;
 	type ReserveProvider = AbsoluteAndRelativeReserveProvider<SelfLocationAbsolute>;
 	type SelfLocation = SelfLocationAbsolute;
+	type CorridorOrigin = MoreThanHalfCouncil;
+	type WeightInfo = pallet_xcmp_handler::weights::SubstrateWeight<Runtime>;
 }
 
 pub struct TokenIdConvert;
@@ -355,3 +423,15 @@ impl Convert<AccountId, MultiLocation> for AccountIdToMultiLocation {
 		X1(AccountId32 { network: None, id: account.into() }).into()
 	}
 }
+
+/// Derives the local account of a remote location the same way the XCM executor does, so a
+/// chain owns the same tasks whether it acts as a remote origin or as its sovereign account.
+pub struct RemoteLocationToAccountId;
+impl Convert<MultiLocation, Option<AccountId>> for RemoteLocationToAccountId {
+	fn convert(location: MultiLocation) -> Option<AccountId> {
+		<LocationToAccountId as xcm_executor::traits::Convert<MultiLocation, AccountId>>::convert(
+			location,
+		)
+		.ok()
+	}
+}
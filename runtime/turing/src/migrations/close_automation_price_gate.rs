@@ -0,0 +1,37 @@
+use core::marker::PhantomData;
+
+use frame_support::{traits::OnRuntimeUpgrade, weights::Weight};
+#[cfg(feature = "try-runtime")]
+use sp_std::vec::Vec;
+
+const AUTOMATION_PRICE_PALLET_NAME: &[u8] = b"AutomationPrice";
+
+/// Close the AutomationPrice pallet gate when the pallet is first enabled on a live network.
+///
+/// Governance opens it through `valve.open_pallet_gate` once assets and oracles are configured.
+/// Remove this from the runtime's `Migrations` after the upgrade that enables the pallet,
+/// otherwise later upgrades would close the gate again.
+pub struct CloseAutomationPriceGate<T>(PhantomData<T>);
+impl<T: pallet_valve::Config> OnRuntimeUpgrade for CloseAutomationPriceGate<T> {
+	fn on_runtime_upgrade() -> Weight {
+		let pallet_name = AUTOMATION_PRICE_PALLET_NAME.to_vec();
+		if pallet_valve::ClosedPallets::<T>::contains_key(&pallet_name) {
+			return T::DbWeight::get().reads(1)
+		}
+
+		pallet_valve::ClosedPallets::<T>::insert(pallet_name, ());
+		pallet_valve::ClosedPalletCount::<T>::mutate(|count| *count = count.saturating_add(1));
+		log::info!(target: "runtime::migrations", "Closed the AutomationPrice pallet gate");
+
+		T::DbWeight::get().reads_writes(2, 2)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade(_: Vec<u8>) -> Result<(), &'static str> {
+		frame_support::ensure!(
+			pallet_valve::ClosedPallets::<T>::contains_key(AUTOMATION_PRICE_PALLET_NAME.to_vec()),
+			"AutomationPrice pallet gate should be closed"
+		);
+		Ok(())
+	}
+}
@@ -1 +1,2 @@
 // Runtime Migrations
+pub mod close_automation_price_gate;